@@ -0,0 +1,14 @@
+-- Per-user monthly category budgets
+CREATE TABLE IF NOT EXISTS budgets (
+    id VARCHAR(36) PRIMARY KEY,
+    user_id VARCHAR(36) NOT NULL,
+    category VARCHAR(100) NOT NULL,
+    monthly_limit DECIMAL(19, 4) NOT NULL CHECK (monthly_limit >= 0),
+    rollover BOOLEAN NOT NULL DEFAULT false,
+    starts_on DATE NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, category)
+);
+
+CREATE INDEX IF NOT EXISTS idx_budgets_user_id ON budgets (user_id);
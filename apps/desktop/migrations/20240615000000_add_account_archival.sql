@@ -0,0 +1,6 @@
+-- Account archival: closed accounts keep their history and stay in net
+-- worth until the balance-zeroed date
+ALTER TABLE accounts ADD COLUMN IF NOT EXISTS archived_at TIMESTAMPTZ;
+ALTER TABLE accounts ADD COLUMN IF NOT EXISTS balance_zeroed_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_accounts_user_archived ON accounts (user_id, archived_at);
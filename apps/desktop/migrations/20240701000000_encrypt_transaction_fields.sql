@@ -0,0 +1,7 @@
+-- Field-level encryption: notes (and optionally merchant/location) hold
+-- SecureVault ciphertext on rows with an encryption generation. NULL marks
+-- rows written in plaintext before encryption was enabled.
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS encryption_generation INTEGER;
+
+CREATE INDEX IF NOT EXISTS idx_transactions_encryption_generation
+    ON transactions (user_id, encryption_generation);
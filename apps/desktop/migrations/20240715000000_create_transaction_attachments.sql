@@ -0,0 +1,16 @@
+-- Receipt and document references attached to transactions. Files stay in
+-- the app data directory; rows only record where they are and what they
+-- hashed to when attached. Soft-deleting a transaction removes its rows.
+CREATE TABLE IF NOT EXISTS transaction_attachments (
+    id VARCHAR(36) PRIMARY KEY,
+    transaction_id VARCHAR(36) NOT NULL,
+    user_id VARCHAR(36) NOT NULL,
+    file_path TEXT NOT NULL,
+    content_hash CHAR(64) NOT NULL,
+    file_size BIGINT NOT NULL CHECK (file_size >= 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (transaction_id, file_path)
+);
+
+CREATE INDEX IF NOT EXISTS idx_transaction_attachments_transaction
+    ON transaction_attachments (user_id, transaction_id);
@@ -0,0 +1,10 @@
+-- Soft-delete timestamps so the retention job can tell how long a
+-- transaction has been deleted. Rows deleted before this migration count
+-- from their last update, which soft deletes set.
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+
+UPDATE transactions SET deleted_at = updated_at
+WHERE is_active = false AND deleted_at IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_transactions_deleted_at
+    ON transactions (deleted_at) WHERE is_active = false;
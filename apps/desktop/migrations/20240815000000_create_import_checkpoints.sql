@@ -0,0 +1,11 @@
+-- Progress of file imports, keyed by the SHA-256 of the imported file. Each
+-- batch of rows is committed together with its checkpoint, so re-importing
+-- a file resumes after the last committed row instead of starting over.
+CREATE TABLE IF NOT EXISTS import_checkpoints (
+    user_id VARCHAR(36) NOT NULL,
+    file_hash CHAR(64) NOT NULL,
+    last_committed_row BIGINT NOT NULL CHECK (last_committed_row >= 0),
+    total_rows BIGINT NOT NULL CHECK (total_rows >= 0),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, file_hash)
+);
@@ -0,0 +1,13 @@
+-- Opening balances let account balances be rebuilt from the transaction
+-- ledger. Existing accounts are backfilled so their current balance is
+-- what the ledger reproduces.
+ALTER TABLE accounts ADD COLUMN IF NOT EXISTS opening_balance NUMERIC;
+
+UPDATE accounts a SET opening_balance = a.balance - COALESCE((
+    SELECT SUM(t.amount) FROM transactions t
+    WHERE t.account_id = a.id AND COALESCE(t.is_active, true) = true
+), 0)
+WHERE opening_balance IS NULL;
+
+ALTER TABLE accounts ALTER COLUMN opening_balance SET DEFAULT 0;
+ALTER TABLE accounts ALTER COLUMN opening_balance SET NOT NULL;
@@ -0,0 +1,10 @@
+-- Insight rules a user has dismissed, one row per user and rule. A row
+-- without snoozed_until hides the rule for good; otherwise the rule's
+-- insights return once snoozed_until has passed.
+CREATE TABLE IF NOT EXISTS insight_dismissals (
+    user_id VARCHAR(36) NOT NULL,
+    rule_id VARCHAR(100) NOT NULL,
+    dismissed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    snoozed_until TIMESTAMPTZ,
+    PRIMARY KEY (user_id, rule_id)
+);
@@ -0,0 +1,13 @@
+-- Security events in the order they joined the tamper-evident audit chain.
+-- Each row keeps its own hash and the hash of the row before it, so the
+-- chain can be recomputed from storage alone.
+CREATE TABLE IF NOT EXISTS security_audit_events (
+    sequence BIGINT PRIMARY KEY CHECK (sequence >= 0),
+    event_type VARCHAR(50) NOT NULL,
+    severity VARCHAR(20) NOT NULL,
+    occurred_at TIMESTAMPTZ NOT NULL,
+    source TEXT NOT NULL,
+    details TEXT NOT NULL,
+    previous_hash CHAR(64) NOT NULL,
+    hash CHAR(64) NOT NULL
+);
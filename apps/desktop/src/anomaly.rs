@@ -0,0 +1,191 @@
+// Transaction Anomaly Detection for Atlas Desktop
+// Flags transactions that are unusually large compared with the user's own spending
+
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+/// Tag added to transactions flagged by the detector
+pub const ANOMALY_TAG: &str = "anomaly";
+
+/// One way a transaction can be considered anomalous
+///
+/// Amounts are compared by magnitude, so debits and credits are treated alike.
+/// The category rules compare against the user's past transactions in the
+/// same category and stay silent until there is enough history.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "rule", rename_all = "camelCase")]
+pub enum AnomalyRule {
+    /// Any transaction of at least `threshold`
+    #[serde(rename_all = "camelCase")]
+    LargeAmount { threshold: Decimal },
+    /// More than `max_z_score` standard deviations above the category mean
+    #[serde(rename_all = "camelCase")]
+    CategoryZScore { max_z_score: Decimal, min_history: usize },
+    /// Above the category's third quartile by more than `multiplier` times
+    /// the interquartile range
+    #[serde(rename_all = "camelCase")]
+    CategoryIqr { multiplier: Decimal, min_history: usize },
+}
+
+/// Anomaly detection settings, part of the notification settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnomalySettings {
+    pub enabled: bool,
+    /// How far back category history is taken from
+    pub history_days: u32,
+    pub rules: Vec<AnomalyRule>,
+}
+
+impl Default for AnomalySettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            history_days: 365,
+            rules: vec![
+                AnomalyRule::LargeAmount { threshold: dec!(1000.00) },
+                AnomalyRule::CategoryZScore { max_z_score: dec!(3), min_history: 10 },
+            ],
+        }
+    }
+}
+
+/// A rule a transaction tripped, with a message for the notification
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnomalyFinding {
+    pub rule: AnomalyRule,
+    pub message: String,
+}
+
+impl AnomalySettings {
+    /// Rules tripped by `amount`, given past amounts in the same category
+    pub fn evaluate(&self, amount: Decimal, category: Option<&str>, history: &[Decimal]) -> Vec<AnomalyFinding> {
+        if !self.enabled {
+            return Vec::new();
+        }
+
+        let magnitude = amount.abs();
+        let history: Vec<Decimal> = history.iter().map(|a| a.abs()).collect();
+        let category = category.unwrap_or("uncategorized");
+
+        self.rules
+            .iter()
+            .filter_map(|rule| {
+                let message = match *rule {
+                    AnomalyRule::LargeAmount { threshold } => (magnitude >= threshold)
+                        .then(|| format!("Transaction of {} is at or above {}", magnitude, threshold)),
+                    AnomalyRule::CategoryZScore { max_z_score, min_history } => (history.len() >= min_history
+                        && exceeds_z_score(magnitude, &history, max_z_score))
+                        .then(|| format!("Transaction of {} is far above your usual {} spending", magnitude, category)),
+                    AnomalyRule::CategoryIqr { multiplier, min_history } => (history.len() >= min_history
+                        && exceeds_iqr_fence(magnitude, &history, multiplier))
+                        .then(|| format!("Transaction of {} is an outlier for your {} spending", magnitude, category)),
+                };
+                message.map(|message| AnomalyFinding { rule: rule.clone(), message })
+            })
+            .collect()
+    }
+}
+
+/// Whether `value` is more than `max_z_score` sample standard deviations
+/// above the mean of `history`
+///
+/// Compared as squares so no square root is needed. A history without any
+/// variation flags every amount above it.
+fn exceeds_z_score(value: Decimal, history: &[Decimal], max_z_score: Decimal) -> bool {
+    if history.len() < 2 {
+        return false;
+    }
+    let count = Decimal::from(history.len());
+    let mean = history.iter().sum::<Decimal>() / count;
+    if value <= mean {
+        return false;
+    }
+    let variance = history.iter().map(|a| (a - mean) * (a - mean)).sum::<Decimal>() / (count - Decimal::ONE);
+    let distance = value - mean;
+    distance * distance > max_z_score * max_z_score * variance
+}
+
+/// Whether `value` lies above `Q3 + multiplier * IQR` of `history`
+fn exceeds_iqr_fence(value: Decimal, history: &[Decimal], multiplier: Decimal) -> bool {
+    if history.is_empty() {
+        return false;
+    }
+    let mut sorted = history.to_vec();
+    sorted.sort();
+    let q1 = quantile(&sorted, dec!(0.25));
+    let q3 = quantile(&sorted, dec!(0.75));
+    value > q3 + multiplier * (q3 - q1)
+}
+
+/// Linearly interpolated quantile of sorted, non-empty values
+fn quantile(sorted: &[Decimal], q: Decimal) -> Decimal {
+    let position = q * Decimal::from(sorted.len() - 1);
+    let lower = position.floor();
+    let index = lower.to_usize().unwrap_or(0).min(sorted.len() - 1);
+    let upper = (index + 1).min(sorted.len() - 1);
+    sorted[index] + (sorted[upper] - sorted[index]) * (position - lower)
+}
+
+/// `tags` with the anomaly tag added once
+pub fn with_anomaly_tag(tags: Option<Vec<String>>) -> Vec<String> {
+    let mut tags = tags.unwrap_or_default();
+    if !tags.iter().any(|tag| tag == ANOMALY_TAG) {
+        tags.push(ANOMALY_TAG.to_string());
+    }
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A year of grocery runs between $60 and $120
+    fn grocery_history() -> Vec<Decimal> {
+        (0..24).map(|i| -(dec!(60) + Decimal::from(i % 7) * dec!(10))).collect()
+    }
+
+    fn statistical_only() -> AnomalySettings {
+        AnomalySettings {
+            rules: vec![
+                AnomalyRule::CategoryZScore { max_z_score: dec!(3), min_history: 10 },
+                AnomalyRule::CategoryIqr { multiplier: dec!(1.5), min_history: 10 },
+            ],
+            ..AnomalySettings::default()
+        }
+    }
+
+    #[test]
+    fn test_amount_far_above_category_norm_is_flagged() {
+        let settings = statistical_only();
+        let history = grocery_history();
+
+        let findings = settings.evaluate(dec!(-640.00), Some("Groceries"), &history);
+        assert_eq!(findings.len(), 2);
+        assert!(findings[0].message.contains("Groceries"));
+
+        assert!(settings.evaluate(dec!(-95.00), Some("Groceries"), &history).is_empty());
+        assert_eq!(with_anomaly_tag(Some(vec!["food".to_string()])), vec!["food", ANOMALY_TAG]);
+        assert_eq!(with_anomaly_tag(Some(vec![ANOMALY_TAG.to_string()])), vec![ANOMALY_TAG]);
+    }
+
+    #[test]
+    fn test_rules_respect_history_and_configuration() {
+        // Too little history for the category rules to judge
+        let settings = statistical_only();
+        assert!(settings.evaluate(dec!(-640.00), Some("Groceries"), &grocery_history()[..5]).is_empty());
+
+        // The fixed threshold applies regardless of history
+        let defaults = AnomalySettings::default();
+        let findings = defaults.evaluate(dec!(1500.00), Some("Rent"), &[]);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].rule, AnomalyRule::LargeAmount { threshold: dec!(1000.00) });
+        assert!(defaults.evaluate(dec!(999.99), Some("Rent"), &[]).is_empty());
+
+        let disabled = AnomalySettings { enabled: false, ..AnomalySettings::default() };
+        assert!(disabled.evaluate(dec!(50000), None, &grocery_history()).is_empty());
+    }
+}
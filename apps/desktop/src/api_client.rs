@@ -279,6 +279,32 @@ impl AtlasApiClient {
         Ok(response.insert_transactions_one)
     }
 
+    /// Get the payment calendar (iCalendar text) for a debt payoff strategy through GraphQL
+    ///
+    /// `input` is a `CompareDebtStrategiesInput`; `strategy` is `SNOWBALL` or `AVALANCHE`.
+    pub async fn get_debt_payment_calendar(
+        &self,
+        user_id: &str,
+        input: &serde_json::Value,
+        strategy: &str,
+        session_token: &str,
+    ) -> Result<String, FinancialError> {
+        let query = r#"
+            query DebtPaymentCalendar($userId: UUID!, $input: CompareDebtStrategiesInput!, $strategy: DebtStrategy!) {
+                debtPaymentCalendar(userId: $userId, input: $input, strategy: $strategy)
+            }
+        "#;
+
+        let variables = serde_json::json!({
+            "userId": user_id,
+            "input": input,
+            "strategy": strategy
+        });
+
+        let response: DebtPaymentCalendarResponse = self.graphql_query(query, Some(variables), session_token).await?;
+        Ok(response.debt_payment_calendar)
+    }
+
     // ========================================================================
     // AI Engine API - Routes through Atlas Core
     // ========================================================================
@@ -385,6 +411,12 @@ pub struct CreateTransactionResponse {
     pub insert_transactions_one: Transaction,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DebtPaymentCalendarResponse {
+    pub debt_payment_calendar: String,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Account {
     pub id: String,
@@ -0,0 +1,704 @@
+// Database Backups for Atlas Desktop
+// Consistent snapshots of the app tables with a SHA-256 integrity manifest
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::financial::FinancialError;
+use crate::security::FieldCipher;
+
+/// Version of the backup and manifest layout
+pub const BACKUP_FORMAT_VERSION: u32 = 1;
+
+/// Suffix of the manifest written next to each backup file
+pub const MANIFEST_SUFFIX: &str = ".manifest.json";
+
+/// A table included in a backup
+///
+/// Every backed-up table has a `user_id` column, so a backup only ever holds
+/// the rows of the user who took it.
+struct BackupTable {
+    name: &'static str,
+    /// Columns that identify a row, used to order snapshots and to lay
+    /// increments over the full backup
+    key: &'static [&'static str],
+    /// Column that records when a row last changed
+    changed: &'static str,
+}
+
+/// Tables included in a backup, parents before children
+///
+/// Table and column names are interpolated into the snapshot queries, so they
+/// must only ever come from this list. Import checkpoints are included so a
+/// restored import resumes where the restored transactions end.
+const BACKUP_TABLES: [BackupTable; 6] = [
+    BackupTable { name: "accounts", key: &["id"], changed: "updated_at" },
+    BackupTable { name: "transactions", key: &["id"], changed: "updated_at" },
+    BackupTable { name: "budgets", key: &["id"], changed: "updated_at" },
+    BackupTable { name: "transaction_attachments", key: &["id"], changed: "created_at" },
+    BackupTable { name: "insight_dismissals", key: &["user_id", "rule_id"], changed: "dismissed_at" },
+    BackupTable { name: "import_checkpoints", key: &["user_id", "file_hash"], changed: "updated_at" },
+];
+
+/// One user's rows captured from the database at one point in time
+///
+/// An incremental snapshot only holds rows added or changed after
+/// `changes_since`. Soft deletes show up as changed rows, but rows removed
+/// outright (budgets, attachments, dismissals) are only dropped by the next
+/// full backup.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupSnapshot {
+    pub format_version: u32,
+    /// User whose rows the snapshot holds
+    pub user_id: String,
+    /// Latest migration applied to the database the snapshot was taken from
+    pub schema_version: i64,
+    pub taken_at: DateTime<Utc>,
+    pub changes_since: Option<DateTime<Utc>>,
+    pub tables: BTreeMap<String, Vec<serde_json::Value>>,
+}
+
+/// Integrity record written alongside a backup file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupManifest {
+    pub format_version: u32,
+    /// File name of the backup, in the same directory as the manifest
+    pub backup_file: String,
+    /// User whose rows the backup holds
+    pub user_id: String,
+    pub created_at: DateTime<Utc>,
+    pub schema_version: i64,
+    /// Hex SHA-256 of the backup file as written, ciphertext when encrypted
+    pub sha256: String,
+    pub size_bytes: u64,
+    pub encrypted: bool,
+    /// Vault key generation the backup was encrypted with
+    pub key_generation: Option<i32>,
+    /// Backup this one holds the changes since, if incremental
+    pub base_backup: Option<String>,
+    pub changes_since: Option<DateTime<Utc>>,
+    pub row_counts: BTreeMap<String, usize>,
+}
+
+/// Outcome of checking a backup against its manifest
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupVerification {
+    pub backup_file: String,
+    pub valid: bool,
+    pub expected_sha256: String,
+    pub actual_sha256: Option<String>,
+    pub message: String,
+}
+
+/// Snapshot `user_id`'s rows of the backed-up tables, or only those changed
+/// after `since`
+///
+/// Runs in a read-only repeatable-read transaction, so every table is read
+/// from the same snapshot while the app keeps writing.
+pub async fn snapshot(
+    pool: &PgPool,
+    user_id: &str,
+    since: Option<DateTime<Utc>>,
+) -> Result<BackupSnapshot, FinancialError> {
+    let db_error = |e: sqlx::Error| FinancialError::DatabaseError(format!("Failed to snapshot database: {}", e));
+
+    let mut tx = pool.begin().await.map_err(db_error)?;
+    sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY")
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?;
+
+    // The snapshot is fixed by the first query, so this is the moment it reflects
+    let taken_at: DateTime<Utc> = sqlx::query_scalar("SELECT now()")
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(db_error)?;
+    let schema_version: i64 =
+        sqlx::query_scalar("SELECT COALESCE(MAX(version), 0) FROM _sqlx_migrations WHERE success")
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(db_error)?;
+
+    let mut tables = BTreeMap::new();
+    for BackupTable { name: table, key, changed } in BACKUP_TABLES {
+        let order = key.iter().map(|column| format!("t.{column}")).collect::<Vec<_>>().join(", ");
+        let query = format!(
+            "SELECT COALESCE(json_agg(t ORDER BY {order}), '[]')::text FROM {table} t \
+             WHERE t.user_id = $1 AND ($2::timestamptz IS NULL OR t.{changed} > $2)"
+        );
+        let rows: String = sqlx::query_scalar(&query)
+            .bind(user_id)
+            .bind(since)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(db_error)?;
+        let rows: Vec<serde_json::Value> = serde_json::from_str(&rows)
+            .map_err(|e| FinancialError::ParseError(format!("Invalid rows in {}: {}", table, e)))?;
+        tables.insert(table.to_string(), rows);
+    }
+
+    tx.commit().await.map_err(db_error)?;
+
+    Ok(BackupSnapshot {
+        format_version: BACKUP_FORMAT_VERSION,
+        user_id: user_id.to_string(),
+        schema_version,
+        taken_at,
+        changes_since: since,
+        tables,
+    })
+}
+
+/// Write `snapshot` and its manifest into `location`
+///
+/// With a cipher the file holds vault ciphertext and the manifest hash covers
+/// the encrypted bytes, so integrity can be checked without unlocking the vault.
+pub async fn write_backup(
+    snapshot: &BackupSnapshot,
+    location: &Path,
+    base: Option<&BackupManifest>,
+    cipher: Option<&dyn FieldCipher>,
+) -> Result<BackupManifest, FinancialError> {
+    tokio::fs::create_dir_all(location)
+        .await
+        .map_err(|e| FinancialError::IoError(format!("Failed to create backup directory: {}", e)))?;
+
+    let json = serde_json::to_string(snapshot)
+        .map_err(|e| FinancialError::ParseError(format!("Failed to serialize backup: {}", e)))?;
+    let contents = match cipher {
+        Some(cipher) => cipher
+            .encrypt_field(&json)
+            .map_err(|e| FinancialError::SecurityError(format!("Failed to encrypt backup: {}", e)))?,
+        None => json.into_bytes(),
+    };
+
+    let backup_file = format!(
+        "atlas-backup-{}.json{}",
+        snapshot.taken_at.format("%Y%m%dT%H%M%S%.3fZ"),
+        if cipher.is_some() { ".enc" } else { "" }
+    );
+    tokio::fs::write(location.join(&backup_file), &contents)
+        .await
+        .map_err(|e| FinancialError::IoError(format!("Failed to write backup: {}", e)))?;
+
+    let manifest = BackupManifest {
+        format_version: BACKUP_FORMAT_VERSION,
+        user_id: snapshot.user_id.clone(),
+        created_at: snapshot.taken_at,
+        schema_version: snapshot.schema_version,
+        sha256: sha256_hex(&contents),
+        size_bytes: contents.len() as u64,
+        encrypted: cipher.is_some(),
+        key_generation: cipher.map(|c| c.key_generation()),
+        base_backup: base.map(|b| b.backup_file.clone()),
+        changes_since: snapshot.changes_since,
+        row_counts: snapshot.tables.iter().map(|(table, rows)| (table.clone(), rows.len())).collect(),
+        backup_file,
+    };
+    let manifest_json = serde_json::to_vec_pretty(&manifest)
+        .map_err(|e| FinancialError::ParseError(format!("Failed to serialize manifest: {}", e)))?;
+    tokio::fs::write(manifest_path(location, &manifest), manifest_json)
+        .await
+        .map_err(|e| FinancialError::IoError(format!("Failed to write manifest: {}", e)))?;
+
+    Ok(manifest)
+}
+
+/// Path of the manifest describing `manifest.backup_file`
+pub fn manifest_path(location: &Path, manifest: &BackupManifest) -> PathBuf {
+    location.join(format!("{}{}", manifest.backup_file, MANIFEST_SUFFIX))
+}
+
+/// Most recent backup of `user_id`'s rows in `location`, if any
+///
+/// Unreadable manifests are skipped so one damaged file does not block new backups.
+pub async fn latest_manifest(location: &Path, user_id: &str) -> Result<Option<BackupManifest>, FinancialError> {
+    let mut entries = match tokio::fs::read_dir(location).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(FinancialError::IoError(format!("Failed to read backup directory: {}", e))),
+    };
+
+    let mut latest: Option<BackupManifest> = None;
+    while let Some(entry) = entries
+        .next_entry()
+        .await
+        .map_err(|e| FinancialError::IoError(format!("Failed to read backup directory: {}", e)))?
+    {
+        if !entry.file_name().to_string_lossy().ends_with(MANIFEST_SUFFIX) {
+            continue;
+        }
+        match read_manifest(&entry.path()).await {
+            Ok(manifest)
+                if manifest.user_id == user_id
+                    && latest.as_ref().map_or(true, |l| manifest.created_at > l.created_at) =>
+            {
+                latest = Some(manifest)
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Skipping unreadable backup manifest {:?}: {}", entry.path(), e),
+        }
+    }
+    Ok(latest)
+}
+
+async fn read_manifest(path: &Path) -> Result<BackupManifest, FinancialError> {
+    let contents = tokio::fs::read(path)
+        .await
+        .map_err(|e| FinancialError::IoError(format!("Failed to read manifest: {}", e)))?;
+    serde_json::from_slice(&contents).map_err(|e| FinancialError::ParseError(format!("Invalid backup manifest: {}", e)))
+}
+
+/// Check the backup described by the manifest at `manifest_path`
+///
+/// A missing or altered backup is reported as invalid rather than as an
+/// error; errors mean the manifest itself could not be read.
+pub async fn verify_backup(manifest_path: &Path) -> Result<BackupVerification, FinancialError> {
+    let manifest = read_manifest(manifest_path).await?;
+    ensure_plain_file_name(&manifest.backup_file)?;
+
+    let backup_path = manifest_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join(&manifest.backup_file);
+    let verification = |valid: bool, actual_sha256: Option<String>, message: &str| BackupVerification {
+        backup_file: manifest.backup_file.clone(),
+        valid,
+        expected_sha256: manifest.sha256.clone(),
+        actual_sha256,
+        message: message.to_string(),
+    };
+
+    let contents = match tokio::fs::read(&backup_path).await {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(verification(false, None, "Backup file is missing"))
+        }
+        Err(e) => return Err(FinancialError::IoError(format!("Failed to read backup: {}", e))),
+    };
+
+    let actual = sha256_hex(&contents);
+    Ok(if actual != manifest.sha256 {
+        verification(false, Some(actual), "Backup contents do not match the manifest hash")
+    } else if contents.len() as u64 != manifest.size_bytes {
+        verification(false, Some(actual), "Backup size does not match the manifest")
+    } else {
+        verification(true, Some(actual), "Backup matches its manifest")
+    })
+}
+
+/// Result of restoring a backup
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreSummary {
+    pub restored_backup: String,
+    /// Backups applied, the full backup first
+    pub backups_applied: Vec<String>,
+    /// Full backup of the database as it was before the restore
+    pub rollback: BackupManifest,
+    pub row_counts: BTreeMap<String, usize>,
+}
+
+/// Newest schema version this build can run, from its embedded migrations
+pub fn supported_schema_version() -> i64 {
+    sqlx::migrate!().iter().map(|m| m.version).max().unwrap_or(0)
+}
+
+/// Restore `user_id`'s rows from the backup described by `manifest_path`
+///
+/// An incremental backup is restored together with the backups it builds on.
+/// Every backup in that chain must belong to `user_id`, match its manifest and
+/// come from a schema no newer than this app's before anything is touched. The
+/// user's current rows are then saved as a full backup in `rollback_dir` and
+/// replaced in one transaction, so a failed restore leaves the database as it
+/// was. Other users' rows are never touched.
+pub async fn restore_backup(
+    pool: &PgPool,
+    user_id: &str,
+    manifest_path: &Path,
+    cipher: Option<&dyn FieldCipher>,
+    rollback_dir: &Path,
+) -> Result<RestoreSummary, FinancialError> {
+    let directory = manifest_path.parent().unwrap_or_else(|| Path::new("."));
+    let chain = backup_chain(manifest_path).await?;
+    let supported = supported_schema_version();
+
+    let mut snapshots = Vec::with_capacity(chain.len());
+    for manifest in &chain {
+        if manifest.user_id != user_id {
+            return Err(FinancialError::SecurityError(format!(
+                "Backup {} belongs to another user",
+                manifest.backup_file
+            )));
+        }
+        if manifest.format_version != BACKUP_FORMAT_VERSION {
+            return Err(FinancialError::ValidationError(format!(
+                "Backup {} uses unsupported format version {}",
+                manifest.backup_file, manifest.format_version
+            )));
+        }
+        if manifest.schema_version > supported {
+            return Err(FinancialError::ValidationError(format!(
+                "Backup {} was made by a newer version of Atlas (schema {}, this app supports up to {}); update the app before restoring it",
+                manifest.backup_file, manifest.schema_version, supported
+            )));
+        }
+        let verification = verify_backup(&self::manifest_path(directory, manifest)).await?;
+        if !verification.valid {
+            return Err(FinancialError::SecurityError(format!(
+                "Backup {} failed verification: {}",
+                manifest.backup_file, verification.message
+            )));
+        }
+        snapshots.push(read_snapshot(directory, manifest, cipher).await?);
+    }
+    let tables = merge_snapshots(&snapshots);
+
+    let current = snapshot(pool, user_id, None).await?;
+    let rollback = write_backup(&current, rollback_dir, None, cipher).await?;
+
+    let db_error = |e: sqlx::Error| FinancialError::DatabaseError(format!("Failed to restore backup: {}", e));
+    let mut tx = pool.begin().await.map_err(db_error)?;
+    // Children go first so foreign keys never point at a removed row
+    for BackupTable { name: table, .. } in BACKUP_TABLES.iter().rev() {
+        sqlx::query(&format!("DELETE FROM {table} WHERE user_id = $1"))
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+    }
+    for BackupTable { name: table, .. } in BACKUP_TABLES {
+        let Some(rows) = tables.get(table).filter(|rows| !rows.is_empty()) else {
+            continue;
+        };
+        let rows: Vec<&serde_json::Value> = rows.values().copied().collect();
+        let rows = serde_json::to_string(&rows)
+            .map_err(|e| FinancialError::ParseError(format!("Failed to serialize rows of {}: {}", table, e)))?;
+        // Columns missing from backups of older schemas are left NULL, and rows
+        // of other users are never written even if the backup holds them
+        sqlx::query(&format!(
+            "INSERT INTO {table} SELECT * FROM json_populate_recordset(NULL::{table}, $1::json) r \
+             WHERE r.user_id = $2"
+        ))
+        .bind(rows)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?;
+    }
+    tx.commit().await.map_err(db_error)?;
+
+    Ok(RestoreSummary {
+        restored_backup: chain[chain.len() - 1].backup_file.clone(),
+        backups_applied: chain.iter().map(|m| m.backup_file.clone()).collect(),
+        rollback,
+        row_counts: tables.iter().map(|(table, rows)| (table.to_string(), rows.len())).collect(),
+    })
+}
+
+/// Manifests from the full backup through to the one at `manifest_path`
+async fn backup_chain(manifest_path: &Path) -> Result<Vec<BackupManifest>, FinancialError> {
+    let directory = manifest_path.parent().unwrap_or_else(|| Path::new("."));
+    let mut chain = vec![read_manifest(manifest_path).await?];
+
+    while let Some(base) = chain[chain.len() - 1].base_backup.clone() {
+        ensure_plain_file_name(&base)?;
+        if chain.iter().any(|m| m.backup_file == base) {
+            return Err(FinancialError::ValidationError(format!("Backup chain loops back to {}", base)));
+        }
+        let base_manifest = read_manifest(&directory.join(format!("{}{}", base, MANIFEST_SUFFIX)))
+            .await
+            .map_err(|e| FinancialError::ValidationError(format!("Base backup {} is unavailable: {}", base, e)))?;
+        chain.push(base_manifest);
+    }
+
+    chain.reverse();
+    Ok(chain)
+}
+
+async fn read_snapshot(
+    directory: &Path,
+    manifest: &BackupManifest,
+    cipher: Option<&dyn FieldCipher>,
+) -> Result<BackupSnapshot, FinancialError> {
+    let contents = tokio::fs::read(directory.join(&manifest.backup_file))
+        .await
+        .map_err(|e| FinancialError::IoError(format!("Failed to read backup: {}", e)))?;
+    let json = if manifest.encrypted {
+        let cipher = cipher
+            .ok_or_else(|| FinancialError::SecurityError("Backup is encrypted; unlock the vault to restore it".to_string()))?;
+        cipher
+            .decrypt_field(&contents)
+            .map_err(|e| FinancialError::SecurityError(format!("Failed to decrypt backup: {}", e)))?
+    } else {
+        String::from_utf8(contents).map_err(|e| FinancialError::ParseError(format!("Invalid backup: {}", e)))?
+    };
+    serde_json::from_str(&json).map_err(|e| FinancialError::ParseError(format!("Invalid backup: {}", e)))
+}
+
+/// Rows of each table keyed by their key columns, with every increment laid
+/// over the full backup
+fn merge_snapshots(snapshots: &[BackupSnapshot]) -> BTreeMap<&str, BTreeMap<String, &serde_json::Value>> {
+    let mut tables: BTreeMap<&str, BTreeMap<String, &serde_json::Value>> = BTreeMap::new();
+    for snapshot in snapshots {
+        for (table, rows) in &snapshot.tables {
+            let key = BACKUP_TABLES.iter().find(|t| t.name == table).map_or(&["id"][..], |t| t.key);
+            let merged = tables.entry(table.as_str()).or_default();
+            for row in rows {
+                let row_key: Vec<String> = key.iter().map(|column| row[*column].to_string()).collect();
+                merged.insert(row_key.join("/"), row);
+            }
+        }
+    }
+    tables
+}
+
+/// Reject names that would resolve outside the backup directory
+fn ensure_plain_file_name(name: &str) -> Result<(), FinancialError> {
+    let path = Path::new(name);
+    if path.file_name() != Some(path.as_os_str()) {
+        return Err(FinancialError::SecurityError("Manifest names a file outside its directory".to_string()));
+    }
+    Ok(())
+}
+
+fn sha256_hex(contents: &[u8]) -> String {
+    Sha256::digest(contents).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn backup_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("atlas-backup-test-{}", uuid::Uuid::new_v4()))
+    }
+
+    fn sample_snapshot() -> BackupSnapshot {
+        let mut tables = BTreeMap::new();
+        tables.insert(
+            "accounts".to_string(),
+            vec![serde_json::json!({ "id": "a1", "name": "Checking", "balance": "1250.00" })],
+        );
+        tables.insert("transactions".to_string(), Vec::new());
+        BackupSnapshot {
+            format_version: BACKUP_FORMAT_VERSION,
+            user_id: "user-1".to_string(),
+            schema_version: supported_schema_version(),
+            taken_at: Utc::now(),
+            changes_since: None,
+            tables,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_backup_verifies_and_corruption_is_detected() {
+        let dir = backup_dir();
+        let snapshot = sample_snapshot();
+        let manifest = write_backup(&snapshot, &dir, None, None).await.unwrap();
+        assert_eq!(manifest.row_counts["accounts"], 1);
+        assert!(!manifest.encrypted);
+
+        let manifest_file = manifest_path(&dir, &manifest);
+        let verification = verify_backup(&manifest_file).await.unwrap();
+        assert!(verification.valid, "{}", verification.message);
+        assert_eq!(verification.actual_sha256.as_deref(), Some(manifest.sha256.as_str()));
+
+        let written = tokio::fs::read(dir.join(&manifest.backup_file)).await.unwrap();
+        let restored: BackupSnapshot = serde_json::from_slice(&written).unwrap();
+        assert_eq!(restored, snapshot);
+
+        // Flip one byte of the backup
+        let mut corrupted = written.clone();
+        corrupted[10] ^= 0x01;
+        tokio::fs::write(dir.join(&manifest.backup_file), &corrupted).await.unwrap();
+        let verification = verify_backup(&manifest_file).await.unwrap();
+        assert!(!verification.valid);
+        assert_ne!(verification.actual_sha256, Some(manifest.sha256.clone()));
+
+        tokio::fs::remove_file(dir.join(&manifest.backup_file)).await.unwrap();
+        let verification = verify_backup(&manifest_file).await.unwrap();
+        assert!(!verification.valid);
+        assert_eq!(verification.message, "Backup file is missing");
+
+        assert_eq!(latest_manifest(&dir, "user-1").await.unwrap(), Some(manifest));
+        assert_eq!(latest_manifest(&dir, "user-2").await.unwrap(), None);
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+    }
+
+    async fn insert_budget(pool: &PgPool, id: &str, category: &str) {
+        insert_user_budget(pool, "user-1", id, category).await;
+    }
+
+    async fn insert_user_budget(pool: &PgPool, user_id: &str, id: &str, category: &str) {
+        sqlx::query(
+            "INSERT INTO budgets (id, user_id, category, monthly_limit, starts_on)
+             VALUES ($1, $2, $3, 400, CURRENT_DATE)",
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(category)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[sqlx::test]
+    async fn test_snapshot_holds_only_the_users_rows(pool: PgPool) {
+        insert_budget(&pool, "b0000000-0000-0000-0000-000000000001", "Groceries").await;
+        insert_user_budget(&pool, "user-2", "b0000000-0000-0000-0000-000000000002", "Dining").await;
+        for user_id in ["user-1", "user-2"] {
+            sqlx::query("INSERT INTO insight_dismissals (user_id, rule_id) VALUES ($1, 'low-balance')")
+                .bind(user_id)
+                .execute(&pool)
+                .await
+                .unwrap();
+            sqlx::query(
+                "INSERT INTO import_checkpoints (user_id, file_hash, last_committed_row, total_rows)
+                 VALUES ($1, repeat('a', 64), 10, 20)",
+            )
+            .bind(user_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let taken = snapshot(&pool, "user-1", None).await.unwrap();
+        assert_eq!(taken.user_id, "user-1");
+        assert_eq!(taken.tables["budgets"].len(), 1);
+        assert_eq!(taken.tables["budgets"][0]["category"], "Groceries");
+        for table in ["insight_dismissals", "import_checkpoints"] {
+            assert_eq!(taken.tables[table].len(), 1, "{}", table);
+            assert_eq!(taken.tables[table][0]["user_id"], "user-1", "{}", table);
+        }
+    }
+
+    #[sqlx::test]
+    async fn test_incremental_snapshot_holds_only_changed_rows(pool: PgPool) {
+        insert_budget(&pool, "b0000000-0000-0000-0000-000000000001", "Groceries").await;
+
+        let full = snapshot(&pool, "user-1", None).await.unwrap();
+        assert_eq!(full.tables["budgets"].len(), 1);
+        assert_eq!(full.tables.len(), BACKUP_TABLES.len());
+
+        let dir = backup_dir();
+        let base = write_backup(&full, &dir, None, None).await.unwrap();
+        insert_budget(&pool, "b0000000-0000-0000-0000-000000000002", "Dining").await;
+
+        let incremental = snapshot(&pool, "user-1", Some(base.created_at)).await.unwrap();
+        assert_eq!(incremental.tables["budgets"].len(), 1);
+        assert_eq!(incremental.tables["budgets"][0]["category"], "Dining");
+
+        let manifest = write_backup(&incremental, &dir, Some(&base), None).await.unwrap();
+        assert_eq!(manifest.base_backup.as_deref(), Some(base.backup_file.as_str()));
+        assert!(verify_backup(&manifest_path(&dir, &manifest)).await.unwrap().valid);
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+    }
+
+    async fn budget_categories(pool: &PgPool) -> Vec<String> {
+        sqlx::query_scalar("SELECT category FROM budgets ORDER BY category")
+            .fetch_all(pool)
+            .await
+            .unwrap()
+    }
+
+    #[sqlx::test]
+    async fn test_restore_replays_backup_chain_and_keeps_rollback(pool: PgPool) {
+        let dir = backup_dir();
+        insert_budget(&pool, "b0000000-0000-0000-0000-000000000001", "Groceries").await;
+        let base = write_backup(&snapshot(&pool, "user-1", None).await.unwrap(), &dir, None, None).await.unwrap();
+        insert_budget(&pool, "b0000000-0000-0000-0000-000000000002", "Dining").await;
+        let incremental = snapshot(&pool, "user-1", Some(base.created_at)).await.unwrap();
+        let latest = write_backup(&incremental, &dir, Some(&base), None).await.unwrap();
+
+        // Changes made after the latest backup
+        sqlx::query("DELETE FROM budgets WHERE category = 'Groceries'").execute(&pool).await.unwrap();
+        insert_budget(&pool, "b0000000-0000-0000-0000-000000000003", "Travel").await;
+
+        let rollback_dir = dir.join("pre-restore");
+        let summary = restore_backup(&pool, "user-1", &manifest_path(&dir, &latest), None, &rollback_dir)
+            .await
+            .unwrap();
+        assert_eq!(summary.backups_applied, vec![base.backup_file.clone(), latest.backup_file.clone()]);
+        assert_eq!(summary.row_counts["budgets"], 2);
+        assert_eq!(budget_categories(&pool).await, vec!["Dining", "Groceries"]);
+
+        // The rollback copy holds the database as it was before the restore
+        assert_eq!(summary.rollback.row_counts["budgets"], 2);
+        let rollback_manifest = manifest_path(&rollback_dir, &summary.rollback);
+        assert!(verify_backup(&rollback_manifest).await.unwrap().valid);
+        restore_backup(&pool, "user-1", &rollback_manifest, None, &rollback_dir).await.unwrap();
+        assert_eq!(budget_categories(&pool).await, vec!["Dining", "Travel"]);
+
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+    }
+
+    #[sqlx::test]
+    async fn test_restore_leaves_other_users_rows_alone(pool: PgPool) {
+        let dir = backup_dir();
+        insert_budget(&pool, "b0000000-0000-0000-0000-000000000001", "Groceries").await;
+        insert_user_budget(&pool, "user-2", "b0000000-0000-0000-0000-000000000002", "Dining").await;
+        let backup = write_backup(&snapshot(&pool, "user-1", None).await.unwrap(), &dir, None, None).await.unwrap();
+        let other = write_backup(&snapshot(&pool, "user-2", None).await.unwrap(), &dir, None, None).await.unwrap();
+
+        sqlx::query("DELETE FROM budgets WHERE user_id = 'user-1'").execute(&pool).await.unwrap();
+        insert_user_budget(&pool, "user-2", "b0000000-0000-0000-0000-000000000003", "Travel").await;
+
+        let rollback_dir = dir.join("pre-restore");
+        let summary = restore_backup(&pool, "user-1", &manifest_path(&dir, &backup), None, &rollback_dir)
+            .await
+            .unwrap();
+        assert_eq!(summary.row_counts["budgets"], 1);
+        // The second user's rows, including one added after the backup, survive
+        assert_eq!(budget_categories(&pool).await, vec!["Dining", "Groceries", "Travel"]);
+        assert_eq!(summary.rollback.user_id, "user-1");
+        assert_eq!(summary.rollback.row_counts["budgets"], 0);
+
+        // A backup of another user's data is refused before anything changes
+        let error = restore_backup(&pool, "user-1", &manifest_path(&dir, &other), None, &rollback_dir)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, FinancialError::SecurityError(_)));
+        assert!(error.to_string().contains("belongs to another user"), "{}", error);
+        assert_eq!(budget_categories(&pool).await, vec!["Dining", "Groceries", "Travel"]);
+
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+    }
+
+    #[sqlx::test]
+    async fn test_restore_refuses_newer_schema_and_corrupted_backups(pool: PgPool) {
+        let dir = backup_dir();
+        insert_budget(&pool, "b0000000-0000-0000-0000-000000000001", "Groceries").await;
+
+        let mut newer = snapshot(&pool, "user-1", None).await.unwrap();
+        newer.schema_version = supported_schema_version() + 1;
+        let manifest = write_backup(&newer, &dir, None, None).await.unwrap();
+        sqlx::query("DELETE FROM budgets").execute(&pool).await.unwrap();
+
+        let rollback_dir = dir.join("pre-restore");
+        let error = restore_backup(&pool, "user-1", &manifest_path(&dir, &manifest), None, &rollback_dir)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, FinancialError::ValidationError(_)));
+        assert!(error.to_string().contains("newer version of Atlas"), "{}", error);
+
+        let current = write_backup(&snapshot(&pool, "user-1", None).await.unwrap(), &dir, None, None).await.unwrap();
+        tokio::fs::write(dir.join(&current.backup_file), b"{}").await.unwrap();
+        let error = restore_backup(&pool, "user-1", &manifest_path(&dir, &current), None, &rollback_dir)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("failed verification"), "{}", error);
+
+        // Nothing was changed or saved for rollback
+        assert!(budget_categories(&pool).await.is_empty());
+        assert!(!rollback_dir.exists());
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+    }
+}
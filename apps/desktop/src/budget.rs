@@ -0,0 +1,342 @@
+// Budget Tracking for Atlas Desktop
+// Compares per-category spending against monthly limits with optional rollover
+
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use chrono_tz::Tz;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::spending::{local_date, local_day_start};
+
+/// Calendar month a budget applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct BudgetPeriod {
+    pub year: i32,
+    pub month: u32,
+}
+
+impl BudgetPeriod {
+    pub fn new(year: i32, month: u32) -> Result<Self, String> {
+        if !(1..=12).contains(&month) {
+            return Err(format!("Invalid month: {}", month));
+        }
+        Ok(Self { year, month })
+    }
+
+    /// Period containing the given instant on the calendar of a user in `timezone`
+    pub fn containing(instant: DateTime<Utc>, timezone: Tz) -> Self {
+        Self::of_date(local_date(instant, timezone))
+    }
+
+    /// Period containing the given calendar date
+    pub fn of_date(date: NaiveDate) -> Self {
+        Self {
+            year: date.year(),
+            month: date.month(),
+        }
+    }
+
+    pub fn next(self) -> Self {
+        if self.month == 12 {
+            Self { year: self.year + 1, month: 1 }
+        } else {
+            Self { year: self.year, month: self.month + 1 }
+        }
+    }
+
+    /// First day of the period
+    pub fn first_day(self) -> NaiveDate {
+        NaiveDate::from_ymd_opt(self.year, self.month, 1).expect("valid budget period")
+    }
+
+    /// First instant of the period for a user in `timezone`
+    pub fn start(self, timezone: Tz) -> DateTime<Utc> {
+        local_day_start(self.first_day(), timezone)
+    }
+
+    /// First instant after the period for a user in `timezone`
+    pub fn end(self, timezone: Tz) -> DateTime<Utc> {
+        self.next().start(timezone)
+    }
+}
+
+impl fmt::Display for BudgetPeriod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}", self.year, self.month)
+    }
+}
+
+impl FromStr for BudgetPeriod {
+    type Err = String;
+
+    /// Parse a `YYYY-MM` period
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (year, month) = s
+            .split_once('-')
+            .ok_or_else(|| format!("Budget period must be formatted as YYYY-MM: {}", s))?;
+        let year = year.parse().map_err(|_| format!("Invalid year in budget period: {}", s))?;
+        let month = month.parse().map_err(|_| format!("Invalid month in budget period: {}", s))?;
+        Self::new(year, month)
+    }
+}
+
+/// Monthly spending limit for one category
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Budget {
+    pub category: String,
+    pub monthly_limit: Decimal,
+    /// Carry unspent money into the following month
+    pub rollover: bool,
+    /// First month the budget applies to; rollover accumulates from here
+    pub start_period: BudgetPeriod,
+}
+
+/// Budgeted vs actual spending for one category in one period
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryBudgetStatus {
+    pub category: String,
+    pub monthly_limit: Decimal,
+    /// Unspent money carried in from earlier periods
+    pub rollover_amount: Decimal,
+    /// Monthly limit plus rollover
+    pub budgeted: Decimal,
+    pub actual: Decimal,
+    /// Negative when the category is over budget
+    pub remaining: Decimal,
+    pub percentage_used: Decimal,
+}
+
+/// Budget status for every budgeted category in a period
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetStatusReport {
+    pub period: String,
+    pub categories: Vec<CategoryBudgetStatus>,
+    pub total_budgeted: Decimal,
+    pub total_actual: Decimal,
+    pub total_remaining: Decimal,
+}
+
+/// Spending keyed by category and period
+pub type CategorySpending = HashMap<(String, BudgetPeriod), Decimal>;
+
+/// Total outflows per category and period; inflows and uncategorized rows are ignored
+///
+/// Transactions are assigned to periods on the calendar of a user in `timezone`.
+pub fn aggregate_spending<'a>(
+    transactions: impl IntoIterator<Item = (Option<&'a str>, DateTime<Utc>, Decimal)>,
+    timezone: Tz,
+) -> CategorySpending {
+    let mut spending = CategorySpending::new();
+    for (category, date, amount) in transactions {
+        let Some(category) = category else { continue };
+        if amount.is_sign_negative() {
+            *spending
+                .entry((category.to_string(), BudgetPeriod::containing(date, timezone)))
+                .or_default() += -amount;
+        }
+    }
+    spending
+}
+
+fn percentage(part: Decimal, whole: Decimal) -> Decimal {
+    if whole.is_zero() {
+        Decimal::ZERO
+    } else {
+        (part / whole * Decimal::from(100)).round_dp(2)
+    }
+}
+
+/// Status of a single budget in `period`
+///
+/// With rollover enabled, whatever was left unspent in each month from the
+/// budget's start period is added to the next month's allowance. Overspending
+/// does not reduce later months.
+pub fn category_status(budget: &Budget, spending: &CategorySpending, period: BudgetPeriod) -> CategoryBudgetStatus {
+    let spent_in = |p: BudgetPeriod| {
+        spending
+            .get(&(budget.category.clone(), p))
+            .copied()
+            .unwrap_or(Decimal::ZERO)
+    };
+
+    let mut rollover_amount = Decimal::ZERO;
+    if budget.rollover {
+        let mut current = budget.start_period;
+        while current < period {
+            let unspent = budget.monthly_limit + rollover_amount - spent_in(current);
+            rollover_amount = unspent.max(Decimal::ZERO);
+            current = current.next();
+        }
+    }
+
+    let budgeted = budget.monthly_limit + rollover_amount;
+    let actual = if period < budget.start_period { Decimal::ZERO } else { spent_in(period) };
+
+    CategoryBudgetStatus {
+        category: budget.category.clone(),
+        monthly_limit: budget.monthly_limit,
+        rollover_amount,
+        budgeted,
+        actual,
+        remaining: budgeted - actual,
+        percentage_used: percentage(actual, budgeted),
+    }
+}
+
+/// Budgeted vs actual per category for `period`, sorted by category
+pub fn budget_status(budgets: &[Budget], spending: &CategorySpending, period: BudgetPeriod) -> BudgetStatusReport {
+    let mut categories: Vec<CategoryBudgetStatus> = budgets
+        .iter()
+        .filter(|budget| budget.start_period <= period)
+        .map(|budget| category_status(budget, spending, period))
+        .collect();
+    categories.sort_by(|a, b| a.category.cmp(&b.category));
+
+    let total_budgeted: Decimal = categories.iter().map(|c| c.budgeted).sum();
+    let total_actual: Decimal = categories.iter().map(|c| c.actual).sum();
+
+    BudgetStatusReport {
+        period: period.to_string(),
+        categories,
+        total_budgeted,
+        total_actual,
+        total_remaining: total_budgeted - total_actual,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    fn period(year: i32, month: u32) -> BudgetPeriod {
+        BudgetPeriod::new(year, month).unwrap()
+    }
+
+    fn spend(entries: &[(&str, BudgetPeriod, Decimal)]) -> CategorySpending {
+        entries
+            .iter()
+            .map(|(category, p, amount)| ((category.to_string(), *p), *amount))
+            .collect()
+    }
+
+    #[test]
+    fn test_period_parsing_and_rollover_across_years() {
+        assert_eq!("2024-03".parse::<BudgetPeriod>().unwrap(), period(2024, 3));
+        assert!("2024-13".parse::<BudgetPeriod>().is_err());
+        assert!("March".parse::<BudgetPeriod>().is_err());
+        assert_eq!(period(2024, 12).next(), period(2025, 1));
+        assert_eq!(period(2024, 2).to_string(), "2024-02");
+    }
+
+    #[test]
+    fn test_under_budget_rollover_accumulates() {
+        let budget = Budget {
+            category: "Dining".to_string(),
+            monthly_limit: dec!(200),
+            rollover: true,
+            start_period: period(2024, 1),
+        };
+        let spending = spend(&[
+            ("Dining", period(2024, 1), dec!(150)),
+            ("Dining", period(2024, 2), dec!(180)),
+            ("Dining", period(2024, 3), dec!(100)),
+        ]);
+
+        // January leaves 50, February leaves 200 + 50 - 180 = 70
+        let status = category_status(&budget, &spending, period(2024, 3));
+        assert_eq!(status.rollover_amount, dec!(70));
+        assert_eq!(status.budgeted, dec!(270));
+        assert_eq!(status.actual, dec!(100));
+        assert_eq!(status.remaining, dec!(170));
+        assert_eq!(status.percentage_used, dec!(37.04));
+
+        // Without rollover the allowance resets every month
+        let fixed = Budget { rollover: false, ..budget };
+        let status = category_status(&fixed, &spending, period(2024, 3));
+        assert_eq!(status.rollover_amount, Decimal::ZERO);
+        assert_eq!(status.remaining, dec!(100));
+    }
+
+    #[test]
+    fn test_over_budget_shows_negative_remaining() {
+        let budgets = vec![
+            Budget {
+                category: "Groceries".to_string(),
+                monthly_limit: dec!(400),
+                rollover: true,
+                start_period: period(2024, 5),
+            },
+            Budget {
+                category: "Fuel".to_string(),
+                monthly_limit: dec!(100),
+                rollover: false,
+                start_period: period(2024, 5),
+            },
+        ];
+        let spending = spend(&[
+            ("Groceries", period(2024, 5), dec!(525.50)),
+            ("Fuel", period(2024, 5), dec!(60)),
+        ]);
+
+        let report = budget_status(&budgets, &spending, period(2024, 5));
+        assert_eq!(report.period, "2024-05");
+        assert_eq!(report.categories[0].category, "Fuel");
+
+        let groceries = &report.categories[1];
+        assert_eq!(groceries.remaining, dec!(-125.50));
+        assert_eq!(groceries.percentage_used, dec!(131.38));
+
+        assert_eq!(report.total_budgeted, dec!(500));
+        assert_eq!(report.total_actual, dec!(585.50));
+        assert_eq!(report.total_remaining, dec!(-85.50));
+
+        // Overspending is not carried forward as a debt
+        let june = category_status(&budgets[0], &spending, period(2024, 6));
+        assert_eq!(june.rollover_amount, Decimal::ZERO);
+        assert_eq!(june.budgeted, dec!(400));
+    }
+
+    #[test]
+    fn test_aggregate_spending_counts_categorized_outflows() {
+        let march = Utc.with_ymd_and_hms(2024, 3, 15, 10, 0, 0).unwrap();
+        let april = Utc.with_ymd_and_hms(2024, 4, 1, 0, 0, 0).unwrap();
+
+        let spending = aggregate_spending(vec![
+            (Some("Dining"), march, dec!(-20)),
+            (Some("Dining"), march, dec!(-5.25)),
+            (Some("Dining"), april, dec!(-10)),
+            (Some("Dining"), march, dec!(100)),
+            (None, march, dec!(-99)),
+        ], Tz::UTC);
+
+        assert_eq!(spending[&("Dining".to_string(), period(2024, 3))], dec!(25.25));
+        assert_eq!(spending[&("Dining".to_string(), period(2024, 4))], dec!(10));
+        assert_eq!(spending.len(), 2);
+    }
+
+    #[test]
+    fn test_spending_assigned_to_users_local_month() {
+        let los_angeles: Tz = "America/Los_Angeles".parse().unwrap();
+        // 11pm on March 31st in Los Angeles
+        let late_night = Utc.with_ymd_and_hms(2024, 4, 1, 6, 0, 0).unwrap();
+
+        let local = aggregate_spending(vec![(Some("Dining"), late_night, dec!(-42))], los_angeles);
+        assert_eq!(local[&("Dining".to_string(), period(2024, 3))], dec!(42));
+
+        let utc = aggregate_spending(vec![(Some("Dining"), late_night, dec!(-42))], Tz::UTC);
+        assert_eq!(utc[&("Dining".to_string(), period(2024, 4))], dec!(42));
+
+        // March in Los Angeles runs from 8am UTC on the 1st (PST) to 7am UTC on April 1st (PDT)
+        assert_eq!(BudgetPeriod::containing(late_night, los_angeles), period(2024, 3));
+        assert_eq!(period(2024, 3).start(los_angeles).to_rfc3339(), "2024-03-01T08:00:00+00:00");
+        assert_eq!(period(2024, 3).end(los_angeles).to_rfc3339(), "2024-04-01T07:00:00+00:00");
+    }
+}
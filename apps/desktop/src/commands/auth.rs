@@ -4,7 +4,7 @@
 use tauri::{AppHandle, State, Window};
 use serde::{Deserialize, Serialize};
 use std::net::IpAddr;
-use crate::{AppState, financial::FinancialAmount, security::{encrypt_data, decrypt_data, check_key_rotation, rotate_encryption_key, get_secure_client, validate_https_url, run_tls_security_tests, RateLimitDecision}};
+use crate::{AppState, financial::FinancialAmount, security::{AuthenticatedUser, lock_app, pii_text, unlock_vault, encrypt_data, decrypt_data, check_key_rotation, rotate_encryption_key, get_secure_client, validate_https_url, run_tls_security_tests, RateLimitDecision}};
 use super::{CommandResponse, send_desktop_notification};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -59,7 +59,7 @@ pub async fn authenticate_user(
     app: AppHandle,
     state: State<'_, AppState>,
 ) -> Result<CommandResponse<SessionInfo>, tauri::Error> {
-    tracing::info!("🔐 Authentication attempt for user: {}", credentials.email);
+    tracing::info!("🔐 Authentication attempt for user: {}", pii_text(&credentials.email));
 
     // Get local IP address for rate limiting
     let local_ip = get_local_ip_address().await;
@@ -70,7 +70,7 @@ pub async fn authenticate_user(
     match rate_limit_result.decision {
         RateLimitDecision::Allow => {
             // Rate limiting passed, proceed with authentication
-            tracing::debug!("✅ Rate limiting check passed for user: {}", credentials.email);
+            tracing::debug!("✅ Rate limiting check passed for user: {}", pii_text(&credentials.email));
         },
         RateLimitDecision::DenyAccountLocked => {
             let remaining_time = rate_limit_result.retry_after
@@ -78,7 +78,7 @@ pub async fn authenticate_user(
                 .unwrap_or_else(|| "unknown".to_string());
 
             tracing::warn!("🔒 Account locked for user: {} - Retry after: {}",
-                credentials.email, remaining_time);
+                pii_text(&credentials.email), remaining_time);
 
             // Send lockout notification
             let _ = send_desktop_notification(
@@ -106,14 +106,14 @@ pub async fn authenticate_user(
                 .unwrap_or_else(|| "60 seconds".to_string());
 
             tracing::warn!("⚠️ Rate limit exceeded for user: {} - Retry after: {}",
-                credentials.email, retry_time);
+                pii_text(&credentials.email), retry_time);
 
             return Ok(CommandResponse::error(format!(
                 "Too many authentication attempts. Please wait {} before trying again.", retry_time
             )));
         },
         RateLimitDecision::DenyBruteForceProtection => {
-            tracing::error!("🚨 Brute force attack detected for user: {}", credentials.email);
+            tracing::error!("🚨 Brute force attack detected for user: {}", pii_text(&credentials.email));
 
             // Send critical security notification
             let _ = send_desktop_notification(
@@ -134,6 +134,19 @@ pub async fn authenticate_user(
             // Record successful authentication with rate limiter
             state.rate_limiter.record_success(&credentials.email, local_ip).await;
 
+            state.session.sign_in(AuthenticatedUser {
+                user_id: session_info.user_id.clone(),
+                expires_at: session_info.expires_at,
+                permissions: session_info.permissions.clone(),
+            }).await;
+
+            // Re-authentication is what lifts an auto-lock
+            if let Err(e) = unlock_vault(app.clone()).await {
+                tracing::error!("Failed to unlock vault: {}", e);
+                state.session.sign_out().await;
+                return Ok(CommandResponse::error("Authentication succeeded but the vault could not be unlocked"));
+            }
+
             // Send success notification
             let _ = send_desktop_notification(
                 &app,
@@ -154,7 +167,7 @@ pub async fn authenticate_user(
             // Record failed authentication attempt with rate limiter
             let failure_result = state.rate_limiter.record_failure(&credentials.email, local_ip).await;
 
-            tracing::warn!("❌ Authentication failed for {}: {}", credentials.email, e);
+            tracing::warn!("❌ Authentication failed for {}: {}", pii_text(&credentials.email), e);
 
             // Determine notification message based on lockout status
             let (notification_title, notification_message) = match failure_result.decision {
@@ -213,6 +226,8 @@ pub async fn logout_user(
 ) -> Result<CommandResponse<()>, tauri::Error> {
     tracing::info!("Logging out user");
 
+    state.session.sign_out().await;
+
     // Clear session from secure storage
     if let Err(e) = clear_stored_session(&app).await {
         tracing::error!("Failed to clear stored session: {}", e);
@@ -234,6 +249,18 @@ pub async fn logout_user(
     Ok(CommandResponse::success(()))
 }
 
+// Lock the app immediately, as if the auto-lock timeout had passed
+#[tauri::command]
+pub async fn lock_now(
+    state: State<'_, AppState>,
+) -> Result<CommandResponse<()>, tauri::Error> {
+    tracing::info!("Locking application on request");
+
+    lock_app(&state.session).await;
+
+    Ok(CommandResponse::success(()))
+}
+
 // Get current session status
 #[tauri::command]
 pub async fn get_session_status(
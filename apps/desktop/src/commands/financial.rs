@@ -3,14 +3,32 @@
 
 use tauri::{AppHandle, State, Window};
 use serde::{Deserialize, Serialize};
-use crate::{AppState, financial::FinancialAmount};
+use crate::{AppState, financial::{parse_money, FinancialAmount, FinancialError}};
+use atlas_financial_core::{debt_to_income, Currency, IncomeRatioLevel, IncomeRatioThresholds, Money, Percentage};
 use crate::api_client::{Account, Transaction, TransactionInput, FinancialAmount as ApiFinancialAmount};
 use crate::security::secure_query::InputValidator;
-use super::{CommandResponse, send_desktop_notification, desktop_utils};
+use crate::security::{get_vault, pii_amount, pii_text, PathAccessPolicy, SensitiveFieldPolicy};
+use crate::insights::{default_insight_rules, estimate_minimum_payment, evaluate_insight_rules, without_dismissed, BrutalHonestyInsight, CreditCardPayment, FinancialAggregates, InsightDismissal, InsightSeverity};
+use crate::forecast::{project_cash_flow, FORECAST_HISTORY_DAYS, safe_to_spend, CashFlowEvent, CashFlowForecast, DebtPaymentDue, SafeToSpend};
+use crate::export::{stream_transactions, ExportColumn, ExportColumns, StreamFormat, EXPORT_PAGE_SIZE};
+use crate::budget::{aggregate_spending, budget_status as compute_budget_status, Budget, BudgetPeriod, BudgetStatusReport};
+use crate::storage::{archived_account_ids, AttachmentRecord, BalanceCorrection, ImportCheckpointRepository, InsightDismissalRepository, UnitOfWork};
+use crate::import::{file_hash, import_in_batches, parse_csv, transaction_rows};
+use crate::date_range::DateRange;
+use crate::tags::roll_up_by_prefix;
+use crate::tax::{estimate_tax, validate_brackets, TaxBracket, TaxDeductions, TaxEstimate, TaxYearActivity};
+use crate::spending::{spending_timeseries, SpendingBucket, SpendingGranularity, SpendingRange, UNCATEGORIZED};
+use crate::anomaly::{with_anomaly_tag, AnomalyFinding};
+use crate::duplicates::{find_duplicate_clusters, plan_merge, DuplicateCandidate, DuplicateCluster};
+use crate::notifications::Notification;
+use crate::privacy::{masking_for, AmountMasking, PrivacyMasked};
+use crate::retention::{purge_expired, PurgeReport};
+use super::{CommandResponse, desktop_utils, record_retention_purge};
+use super::preferences::{user_privacy_mode, user_timezone};
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use uuid::Uuid;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use std::collections::HashMap;
 
 // ============================================================================
@@ -25,7 +43,7 @@ pub struct Account {
     pub name: String,
     pub account_type: AccountType,
     pub balance: FinancialAmount,
-    pub currency: String,
+    pub currency: Currency,
     pub is_active: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -33,6 +51,8 @@ pub struct Account {
     pub account_number_masked: Option<String>,
     pub credit_limit: Option<FinancialAmount>,
     pub interest_rate: Option<Decimal>,
+    pub archived_at: Option<DateTime<Utc>>,
+    pub balance_zeroed_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -111,54 +131,22 @@ pub struct FinancialOverview {
     pub monthly_expenses: FinancialAmount,
     pub cash_flow: FinancialAmount,
     pub investment_value: FinancialAmount,
-    pub debt_to_income_ratio: Decimal,
+    /// Percentage of monthly income owed in debt payments; `None` without income
+    pub debt_to_income_ratio: Option<Decimal>,
+    pub debt_to_income_level: Option<IncomeRatioLevel>,
     pub savings_rate: Decimal,
     pub emergency_fund_months: Decimal,
     pub as_of_date: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct BrutalHonestyInsight {
-    pub id: String,
-    pub title: String,
-    pub message: String,
-    pub severity: InsightSeverity,
-    pub category: InsightCategory,
-    pub action_items: Vec<String>,
-    pub impact_score: i32, // 1-10 scale
-    pub created_at: DateTime<Utc>,
-    pub is_dismissed: bool,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
-pub enum InsightSeverity {
-    Info,
-    Warning,
-    Critical,
-    Urgent,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
-pub enum InsightCategory {
-    Spending,
-    Saving,
-    Debt,
-    Investment,
-    Budget,
-    Cash_Flow,
-    Risk,
-    Opportunity,
-}
-
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct SpendingAnalysis {
     pub total_spending: FinancialAmount,
     pub period: String,
     pub category_breakdown: HashMap<String, FinancialAmount>,
+    /// Spending under each tag and tag prefix, e.g. `travel` covers `travel/flights`
+    pub tag_breakdown: HashMap<String, FinancialAmount>,
     pub top_merchants: Vec<MerchantSpending>,
     pub spending_trends: Vec<SpendingTrend>,
     pub budget_comparison: Option<BudgetComparison>,
@@ -229,21 +217,21 @@ pub struct AmountRange {
     pub max: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct DateRange {
-    pub start: DateTime<Utc>,
-    pub end: DateTime<Utc>,
-}
-
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ExportOptions {
     pub format: ExportFormat,
     pub date_range: Option<DateRange>,
+    /// Named period or ISO interval such as `last_quarter`; used when
+    /// `date_range` is not set
+    #[serde(default)]
+    pub period: Option<String>,
     pub include_categories: bool,
     pub include_tags: bool,
     pub accounts: Option<Vec<String>>,
+    /// Columns in output order; overrides the include flags when set
+    #[serde(default)]
+    pub columns: Option<Vec<ExportColumn>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -255,6 +243,21 @@ pub enum ExportFormat {
     Excel,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkCategorizeResult {
+    pub updated_count: usize,
+    pub not_found_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeTransactionsResult {
+    pub kept_id: String,
+    pub removed_count: usize,
+    pub not_found_ids: Vec<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ImportResult {
@@ -278,18 +281,24 @@ pub struct ImportError {
 // Account Management Commands
 // ============================================================================
 
-/// Get all accounts for the authenticated user
+/// Get all accounts for the authenticated user; archived accounts are
+/// hidden unless `include_archived` is set
+///
+/// Privacy mode masks balances unless `unmask` is set.
 #[tauri::command]
 pub async fn get_accounts(
+    include_archived: Option<bool>,
+    unmask: Option<bool>,
     app: AppHandle,
     state: State<'_, AppState>,
-) -> Result<CommandResponse<Vec<Account>>, tauri::Error> {
+) -> Result<CommandResponse<PrivacyMasked<Vec<Account>>>, tauri::Error> {
     tracing::info!("Fetching user accounts");
 
-    match fetch_user_accounts(&state).await {
+    match fetch_user_accounts(include_archived.unwrap_or(false), &state).await {
         Ok(accounts) => {
             tracing::info!("Successfully fetched {} accounts", accounts.len());
-            Ok(CommandResponse::success(accounts))
+            let masking = response_masking(unmask, &state).await;
+            Ok(CommandResponse::success(PrivacyMasked::new(accounts, masking)))
         }
         Err(e) => {
             tracing::error!("Failed to fetch accounts: {}", e);
@@ -314,7 +323,7 @@ pub async fn get_account_details(
 
     match fetch_account_by_id(&account_id, &state).await {
         Ok(Some(account)) => {
-            tracing::info!("Successfully fetched account details: {}", account.name);
+            tracing::info!("Successfully fetched account details: {}", pii_text(&account.name));
             Ok(CommandResponse::success(account))
         }
         Ok(None) => {
@@ -328,28 +337,128 @@ pub async fn get_account_details(
     }
 }
 
+/// Archive (close) an account without deleting its history
+///
+/// Archived accounts reject new transactions but stay in net worth and
+/// reports until `balance_zeroed_at` (defaults to now).
+#[tauri::command]
+pub async fn archive_account(
+    account_id: String,
+    balance_zeroed_at: Option<DateTime<Utc>>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse<Account>, tauri::Error> {
+    tracing::info!("Archiving account: {}", account_id);
+
+    if Uuid::parse_str(&account_id).is_err() {
+        return Ok(CommandResponse::error("Invalid account ID format"));
+    }
+
+    match set_account_archived(&account_id, true, balance_zeroed_at, &state).await {
+        Ok(Some(account)) => {
+            tracing::info!("Successfully archived account: {}", pii_text(&account.name));
+            Ok(CommandResponse::success(account))
+        }
+        Ok(None) => {
+            tracing::warn!("Account not found or already archived: {}", account_id);
+            Ok(CommandResponse::error("Account not found or already archived"))
+        }
+        Err(e) => {
+            tracing::error!("Failed to archive account: {}", e);
+            Ok(CommandResponse::error(format!("Failed to archive account: {}", e)))
+        }
+    }
+}
+
+/// Reopen an archived account so it accepts transactions again
+#[tauri::command]
+pub async fn reactivate_account(
+    account_id: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse<Account>, tauri::Error> {
+    tracing::info!("Reactivating account: {}", account_id);
+
+    if Uuid::parse_str(&account_id).is_err() {
+        return Ok(CommandResponse::error("Invalid account ID format"));
+    }
+
+    match set_account_archived(&account_id, false, None, &state).await {
+        Ok(Some(account)) => {
+            tracing::info!("Successfully reactivated account: {}", pii_text(&account.name));
+            Ok(CommandResponse::success(account))
+        }
+        Ok(None) => {
+            tracing::warn!("Account not found or not archived: {}", account_id);
+            Ok(CommandResponse::error("Account not found or not archived"))
+        }
+        Err(e) => {
+            tracing::error!("Failed to reactivate account: {}", e);
+            Ok(CommandResponse::error(format!("Failed to reactivate account: {}", e)))
+        }
+    }
+}
+
+/// Delete an account
+///
+/// Accounts with transactions are refused unless `cascade` is set, which
+/// deletes the account's transactions along with it. Use `archive_account`
+/// to close an account and keep its history.
+#[tauri::command]
+pub async fn delete_account(
+    account_id: String,
+    cascade: Option<bool>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse<()>, tauri::Error> {
+    tracing::info!("Deleting account: {}", account_id);
+
+    if Uuid::parse_str(&account_id).is_err() {
+        return Ok(CommandResponse::error("Invalid account ID format"));
+    }
+
+    match soft_delete_account(&account_id, cascade.unwrap_or(false), &state).await {
+        Ok(true) => {
+            tracing::info!("Successfully deleted account: {}", account_id);
+            Ok(CommandResponse::success(()))
+        }
+        Ok(false) => {
+            tracing::warn!("Account not found for deletion: {}", account_id);
+            Ok(CommandResponse::error("Account not found"))
+        }
+        Err(e) => {
+            tracing::error!("Failed to delete account: {}", e);
+            Ok(CommandResponse::error(format!("Failed to delete account: {}", e)))
+        }
+    }
+}
+
 // ============================================================================
 // Transaction Management Commands
 // ============================================================================
 
 /// Get transactions with filtering and pagination
+///
+/// Privacy mode masks amounts unless `unmask` is set.
 #[tauri::command]
 pub async fn get_transactions(
     filter: Option<TransactionFilter>,
     limit: Option<i32>,
     offset: Option<i32>,
+    unmask: Option<bool>,
     app: AppHandle,
     state: State<'_, AppState>,
-) -> Result<CommandResponse<Vec<Transaction>>, tauri::Error> {
+) -> Result<CommandResponse<PrivacyMasked<Vec<Transaction>>>, tauri::Error> {
     tracing::info!("Fetching transactions with filter");
 
     let limit = limit.unwrap_or(50).min(500); // Max 500 transactions per request
     let offset = offset.unwrap_or(0).max(0);
 
-    match fetch_filtered_transactions(&filter, limit, offset, &state).await {
+    match fetch_filtered_transactions(&filter, limit, offset, &app, &state).await {
         Ok(transactions) => {
             tracing::info!("Successfully fetched {} transactions", transactions.len());
-            Ok(CommandResponse::success(transactions))
+            let masking = response_masking(unmask, &state).await;
+            Ok(CommandResponse::success(PrivacyMasked::new(transactions, masking)))
         }
         Err(e) => {
             tracing::error!("Failed to fetch transactions: {}", e);
@@ -365,26 +474,34 @@ pub async fn add_transaction(
     app: AppHandle,
     state: State<'_, AppState>,
 ) -> Result<CommandResponse<Transaction>, tauri::Error> {
-    tracing::info!("Adding new transaction: {}", transaction_input.description);
+    tracing::info!("Adding new transaction: {}", pii_text(&transaction_input.description));
 
     // Validate input
-    if let Err(validation_error) = validate_transaction_input(&transaction_input) {
-        return Ok(CommandResponse::error(validation_error));
+    let number_format = state.config.ui_settings.number_format;
+    let account_type = transaction_account_type(&transaction_input.account_id, &state).await;
+    if let Err(report) = InputValidator::validate_transaction_input_with_limits(
+        &transaction_input,
+        number_format,
+        account_type,
+        &state.config.transaction_limits,
+        &state.config.tag_policy,
+    ) {
+        return Ok(CommandResponse::invalid(report));
     }
 
-    match create_transaction(&transaction_input, &state).await {
+    // Anomaly detection never blocks saving the transaction
+    let anomalies = match detect_transaction_anomalies(&transaction_input, &app, &state).await {
+        Ok(anomalies) => anomalies,
+        Err(e) => {
+            tracing::warn!("Skipping anomaly detection: {}", e);
+            Vec::new()
+        }
+    };
+
+    match create_transaction(&transaction_input, &anomalies, &app, &state).await {
         Ok(transaction) => {
-            // Send desktop notification for large transactions
-            if let Ok(amount) = transaction_input.amount.parse::<Decimal>() {
-                if amount.abs() >= dec!(1000.00) {
-                    let _ = send_desktop_notification(
-                        &app,
-                        "Large Transaction Added",
-                        &format!("Added transaction: {} for ${}",
-                                transaction_input.description, amount),
-                    ).await;
-                }
-            }
+            // Notify about transactions that stand out
+            state.notifications.transaction_added(&transaction_input.description, &anomalies);
 
             tracing::info!("Successfully created transaction: {}", transaction.id);
             Ok(CommandResponse::success(transaction))
@@ -412,11 +529,19 @@ pub async fn update_transaction(
     }
 
     // Validate input
-    if let Err(validation_error) = validate_transaction_input(&transaction_input) {
-        return Ok(CommandResponse::error(validation_error));
+    let number_format = state.config.ui_settings.number_format;
+    let account_type = transaction_account_type(&transaction_input.account_id, &state).await;
+    if let Err(report) = InputValidator::validate_transaction_input_with_limits(
+        &transaction_input,
+        number_format,
+        account_type,
+        &state.config.transaction_limits,
+        &state.config.tag_policy,
+    ) {
+        return Ok(CommandResponse::invalid(report));
     }
 
-    match update_existing_transaction(&transaction_id, &transaction_input, &state).await {
+    match update_existing_transaction(&transaction_id, &transaction_input, &app, &state).await {
         Ok(Some(transaction)) => {
             tracing::info!("Successfully updated transaction: {}", transaction.id);
             Ok(CommandResponse::success(transaction))
@@ -462,6 +587,75 @@ pub async fn delete_transaction(
     }
 }
 
+/// Attach a receipt or document from the app data directory to a transaction
+#[tauri::command]
+pub async fn add_transaction_attachment(
+    transaction_id: String,
+    file_path: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse<AttachmentRecord>, tauri::Error> {
+    tracing::info!("Adding attachment to transaction: {}", transaction_id);
+
+    if Uuid::parse_str(&transaction_id).is_err() {
+        return Ok(CommandResponse::error("Invalid transaction ID format"));
+    }
+
+    match attach_file_to_transaction(&transaction_id, &file_path, &app, &state).await {
+        Ok(attachment) => {
+            tracing::info!("Attached {} to transaction: {}", attachment.id, transaction_id);
+            Ok(CommandResponse::success(attachment))
+        }
+        Err(e) => {
+            tracing::error!("Failed to add attachment: {}", e);
+            Ok(CommandResponse::error(format!("Failed to add attachment: {}", e)))
+        }
+    }
+}
+
+/// List the attachments of a transaction
+#[tauri::command]
+pub async fn list_transaction_attachments(
+    transaction_id: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse<Vec<AttachmentRecord>>, tauri::Error> {
+    if Uuid::parse_str(&transaction_id).is_err() {
+        return Ok(CommandResponse::error("Invalid transaction ID format"));
+    }
+
+    match load_transaction_attachments(&transaction_id, &state).await {
+        Ok(attachments) => Ok(CommandResponse::success(attachments)),
+        Err(e) => {
+            tracing::error!("Failed to list attachments: {}", e);
+            Ok(CommandResponse::error(format!("Failed to list attachments: {}", e)))
+        }
+    }
+}
+
+/// Remove an attachment reference; the file itself is kept
+#[tauri::command]
+pub async fn remove_transaction_attachment(
+    attachment_id: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse<()>, tauri::Error> {
+    tracing::info!("Removing attachment: {}", attachment_id);
+
+    if Uuid::parse_str(&attachment_id).is_err() {
+        return Ok(CommandResponse::error("Invalid attachment ID format"));
+    }
+
+    match delete_transaction_attachment(&attachment_id, &state).await {
+        Ok(true) => Ok(CommandResponse::success(())),
+        Ok(false) => Ok(CommandResponse::error("Attachment not found")),
+        Err(e) => {
+            tracing::error!("Failed to remove attachment: {}", e);
+            Ok(CommandResponse::error(format!("Failed to remove attachment: {}", e)))
+        }
+    }
+}
+
 /// Categorize transaction using ML integration
 #[tauri::command]
 pub async fn categorize_transaction(
@@ -478,17 +672,8 @@ pub async fn categorize_transaction(
 
     match ml_categorize_transaction(&transaction_id, &state).await {
         Ok(Some(transaction)) => {
-            // Send notification if confidence is low
-            if let Some(confidence) = transaction.ml_confidence {
-                if confidence < 0.7 {
-                    let _ = send_desktop_notification(
-                        &app,
-                        "Low Confidence Categorization",
-                        &format!("Transaction '{}' was categorized with {}% confidence. Please review.",
-                                transaction.description, (confidence * 100.0) as i32),
-                    ).await;
-                }
-            }
+            // Ask for a review when the model was unsure
+            state.notifications.transaction_categorized(&transaction.description, transaction.ml_confidence);
 
             tracing::info!("Successfully categorized transaction: {}", transaction.id);
             Ok(CommandResponse::success(transaction))
@@ -504,6 +689,179 @@ pub async fn categorize_transaction(
     }
 }
 
+/// Assign one category to many transactions in a single atomic update
+#[tauri::command]
+pub async fn bulk_categorize(
+    transaction_ids: Vec<String>,
+    category: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse<BulkCategorizeResult>, tauri::Error> {
+    tracing::info!("Bulk categorizing {} transactions as: {}", transaction_ids.len(), pii_text(&category));
+
+    match bulk_update_transaction_category(&transaction_ids, &category, &state).await {
+        Ok(result) => {
+            if !result.not_found_ids.is_empty() {
+                tracing::warn!("{} transactions not found for bulk categorization", result.not_found_ids.len());
+            }
+
+            tracing::info!("Successfully categorized {} transactions", result.updated_count);
+            Ok(CommandResponse::success(result))
+        }
+        Err(e) => {
+            tracing::error!("Failed to bulk categorize transactions: {}", e);
+            Ok(CommandResponse::error(format!("Failed to bulk categorize transactions: {}", e)))
+        }
+    }
+}
+
+/// Find groups of likely duplicate transactions on an account for review
+#[tauri::command]
+pub async fn find_duplicate_transactions(
+    account_id: String,
+    window_days: u32,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse<Vec<DuplicateCluster>>, tauri::Error> {
+    tracing::info!("Finding duplicate transactions on account {} within {} days", account_id, window_days);
+
+    // Validate UUID format
+    if Uuid::parse_str(&account_id).is_err() {
+        return Ok(CommandResponse::error("Invalid account ID format"));
+    }
+
+    match detect_duplicate_transactions(&account_id, window_days, &app, &state).await {
+        Ok(clusters) => {
+            tracing::info!("Found {} duplicate clusters on account {}", clusters.len(), account_id);
+            Ok(CommandResponse::success(clusters))
+        }
+        Err(e) => {
+            tracing::error!("Failed to find duplicate transactions: {}", e);
+            Ok(CommandResponse::error(format!("Failed to find duplicate transactions: {}", e)))
+        }
+    }
+}
+
+/// Keep one transaction from a duplicate cluster and soft-delete the others
+#[tauri::command]
+pub async fn merge_transactions(
+    keep_id: String,
+    transaction_ids: Vec<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse<MergeTransactionsResult>, tauri::Error> {
+    tracing::info!("Merging {} transactions into {}", transaction_ids.len(), keep_id);
+
+    // Validate UUID format
+    if Uuid::parse_str(&keep_id).is_err() {
+        return Ok(CommandResponse::error("Invalid transaction ID format"));
+    }
+
+    match merge_duplicate_transactions(&keep_id, &transaction_ids, &state).await {
+        Ok(result) => {
+            if !result.not_found_ids.is_empty() {
+                tracing::warn!("{} transactions not found for merge", result.not_found_ids.len());
+            }
+
+            tracing::info!("Merged {} duplicates into transaction {}", result.removed_count, keep_id);
+            Ok(CommandResponse::success(result))
+        }
+        Err(e) => {
+            tracing::error!("Failed to merge transactions: {}", e);
+            Ok(CommandResponse::error(format!("Failed to merge transactions: {}", e)))
+        }
+    }
+}
+
+/// Permanently remove transactions soft-deleted longer ago than the retention window
+///
+/// This runs the app-wide maintenance job the scheduler runs, so it purges
+/// every user's expired transactions, not just the caller's. It only removes
+/// rows already past the retention window, which the next scheduled purge
+/// would remove anyway, but still needs a signed-in session.
+#[tauri::command]
+pub async fn purge_deleted_transactions(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse<PurgeReport>, tauri::Error> {
+    let user = match state.session.current_user().await {
+        Ok(user) => user,
+        Err(e) => return Ok(CommandResponse::error(format!("Access denied: {}", e))),
+    };
+    tracing::info!("Purging expired soft-deleted transactions, requested by user: {}", user.user_id);
+
+    match purge_expired(&state.database_manager, &state.config.data_retention, Utc::now()).await {
+        Ok(report) => {
+            record_retention_purge(&report, &format!("App: {}", app.package_info().name)).await;
+            tracing::info!("Purged {} deleted transactions", report.purged);
+            Ok(CommandResponse::success(report))
+        }
+        Err(e) => {
+            tracing::error!("Failed to purge deleted transactions: {}", e);
+            Ok(CommandResponse::error(format!("Failed to purge deleted transactions: {}", e)))
+        }
+    }
+}
+
+/// What `recompute_financials` rebuilt
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecomputeReport {
+    /// Accounts whose stored balance disagreed with their transactions
+    pub balance_corrections: Vec<BalanceCorrection>,
+    pub net_worth_before: Decimal,
+    pub net_worth_after: Decimal,
+}
+
+/// Rebuild derived figures for `user_id` from the transaction ledger
+///
+/// Account balances are the only stored aggregate; net worth and budget
+/// status are computed from them on every read, so correcting the balances
+/// refreshes those too. The report shows net worth on both sides of the fix.
+#[tauri::command]
+pub async fn recompute_financials(
+    user_id: String,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse<RecomputeReport>, tauri::Error> {
+    let user_id = match state.session.authorize_user(&user_id).await {
+        Ok(user_id) => user_id,
+        Err(e) => return Ok(CommandResponse::error(format!("Access denied: {}", e))),
+    };
+    tracing::info!("Recomputing financial aggregates");
+
+    let db = &state.database_manager;
+    let result = async {
+        let unit = UnitOfWork::begin(db).await?;
+        let accounts = AccountRepository::within(&unit);
+        let now = Utc::now();
+        let net_worth_before = net_worth_of(&accounts.find_by_user_id(&user_id).await?, now);
+        let balance_corrections = accounts.recompute_balances(&user_id).await?;
+        let net_worth_after = net_worth_of(&accounts.find_by_user_id(&user_id).await?, now);
+        unit.commit().await?;
+        Ok::<_, FinancialError>(RecomputeReport { balance_corrections, net_worth_before, net_worth_after })
+    }
+    .await;
+
+    match result {
+        Ok(report) => {
+            for correction in &report.balance_corrections {
+                tracing::warn!(
+                    "Corrected balance of account {} from {} to {}",
+                    correction.account_id,
+                    pii_amount(correction.previous_balance),
+                    pii_amount(correction.recomputed_balance)
+                );
+            }
+            tracing::info!("Recomputed financials: {} balances corrected", report.balance_corrections.len());
+            Ok(CommandResponse::success(report))
+        }
+        Err(e) => {
+            tracing::error!("Failed to recompute financials: {}", e);
+            Ok(CommandResponse::error(format!("Failed to recompute financials: {}", e)))
+        }
+    }
+}
+
 // ============================================================================
 // Financial Analysis Commands
 // ============================================================================
@@ -518,7 +876,7 @@ pub async fn calculate_net_worth(
 
     match compute_net_worth(&state).await {
         Ok(net_worth) => {
-            tracing::info!("Successfully calculated net worth: {}", net_worth);
+            tracing::info!("Successfully calculated net worth: {}", pii_amount(net_worth.amount()));
             Ok(CommandResponse::success(net_worth))
         }
         Err(e) => {
@@ -529,17 +887,21 @@ pub async fn calculate_net_worth(
 }
 
 /// Get comprehensive financial overview
+///
+/// Privacy mode masks amounts unless `unmask` is set.
 #[tauri::command]
 pub async fn get_financial_overview(
+    unmask: Option<bool>,
     app: AppHandle,
     state: State<'_, AppState>,
-) -> Result<CommandResponse<FinancialOverview>, tauri::Error> {
+) -> Result<CommandResponse<PrivacyMasked<FinancialOverview>>, tauri::Error> {
     tracing::info!("Generating financial overview");
 
     match generate_financial_overview(&state).await {
         Ok(overview) => {
             tracing::info!("Successfully generated financial overview");
-            Ok(CommandResponse::success(overview))
+            let masking = response_masking(unmask, &state).await;
+            Ok(CommandResponse::success(PrivacyMasked::new(overview, masking)))
         }
         Err(e) => {
             tracing::error!("Failed to generate financial overview: {}", e);
@@ -558,18 +920,11 @@ pub async fn get_brutal_honesty_insights(
 
     match generate_brutal_honesty_insights(&state).await {
         Ok(insights) => {
-            // Send notification for urgent insights
+            // Notify when enough insights are urgent
             let urgent_count = insights.iter()
                 .filter(|i| matches!(i.severity, InsightSeverity::Urgent))
                 .count();
-
-            if urgent_count > 0 {
-                let _ = send_desktop_notification(
-                    &app,
-                    "Urgent Financial Insights",
-                    &format!("You have {} urgent financial insights that need attention.", urgent_count),
-                ).await;
-            }
+            state.notifications.insights_generated(urgent_count);
 
             tracing::info!("Successfully generated {} insights", insights.len());
             Ok(CommandResponse::success(insights))
@@ -581,10 +936,51 @@ pub async fn get_brutal_honesty_insights(
     }
 }
 
+/// Stop showing insights from a rule
+#[tauri::command]
+pub async fn dismiss_insight(
+    rule_id: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse<InsightDismissal>, tauri::Error> {
+    tracing::info!("Dismissing insight rule: {}", rule_id);
+
+    match save_insight_dismissal(&rule_id, None, &state).await {
+        Ok(dismissal) => Ok(CommandResponse::success(dismissal)),
+        Err(e) => {
+            tracing::error!("Failed to dismiss insight: {}", e);
+            Ok(CommandResponse::error(format!("Failed to dismiss insight: {}", e)))
+        }
+    }
+}
+
+/// Hide insights from a rule until `snooze_until`
+#[tauri::command]
+pub async fn snooze_insight(
+    rule_id: String,
+    snooze_until: DateTime<Utc>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse<InsightDismissal>, tauri::Error> {
+    tracing::info!("Snoozing insight rule {} until {}", rule_id, snooze_until);
+
+    if snooze_until <= Utc::now() {
+        return Ok(CommandResponse::error("Snooze must end in the future".to_string()));
+    }
+
+    match save_insight_dismissal(&rule_id, Some(snooze_until), &state).await {
+        Ok(dismissal) => Ok(CommandResponse::success(dismissal)),
+        Err(e) => {
+            tracing::error!("Failed to snooze insight: {}", e);
+            Ok(CommandResponse::error(format!("Failed to snooze insight: {}", e)))
+        }
+    }
+}
+
 /// Get detailed spending analysis
 #[tauri::command]
 pub async fn get_spending_analysis(
-    period: Option<String>, // "week", "month", "quarter", "year"
+    period: Option<String>, // a DateRange spec: "month", "last_30_days", "ytd", ...
     app: AppHandle,
     state: State<'_, AppState>,
 ) -> Result<CommandResponse<SpendingAnalysis>, tauri::Error> {
@@ -623,50 +1019,215 @@ pub async fn get_budget_recommendations(
     }
 }
 
-// ============================================================================
-// Data Import/Export Commands
-// ============================================================================
-
-/// Export financial data with desktop file dialog
+/// Create or replace the monthly budget for a category
 #[tauri::command]
-pub async fn export_financial_data(
-    export_options: ExportOptions,
+pub async fn set_budget(
+    category: String,
+    monthly_limit: String,
+    rollover: bool,
+    start_period: Option<String>,
     app: AppHandle,
     state: State<'_, AppState>,
-) -> Result<CommandResponse<String>, tauri::Error> {
-    tracing::info!("Exporting financial data in format: {:?}", export_options.format);
+) -> Result<CommandResponse<Budget>, tauri::Error> {
+    tracing::info!("Setting budget for category: {}", pii_text(&category));
 
-    // Show save file dialog
-    let file_extension = match export_options.format {
-        ExportFormat::CSV => "csv",
-        ExportFormat::JSON => "json",
-        ExportFormat::PDF => "pdf",
-        ExportFormat::Excel => "xlsx",
+    let monthly_limit = match parse_money(&monthly_limit, Currency::USD, state.config.ui_settings.number_format) {
+        Ok(limit) => limit.amount(),
+        Err(e) => return Ok(CommandResponse::error(format!("Invalid monthly limit: {}", e))),
     };
 
-    let default_filename = format!("atlas_financial_export_{}.{}",
-                                   Utc::now().format("%Y%m%d_%H%M%S"),
-                                   file_extension);
-
-    let filters = vec![
-        (format!("{} Files", file_extension.to_uppercase()).as_str(), &[file_extension]),
-        ("All Files", &["*"]),
-    ];
+    match save_budget(&category, monthly_limit, rollover, start_period.as_deref(), &state).await {
+        Ok(budget) => {
+            tracing::info!("Saved budget for category: {}", pii_text(&budget.category));
+            Ok(CommandResponse::success(budget))
+        }
+        Err(e) => {
+            tracing::error!("Failed to save budget: {}", e);
+            Ok(CommandResponse::error(format!("Failed to save budget: {}", e)))
+        }
+    }
+}
 
-    match desktop_utils::save_file_dialog(
+/// Budgeted vs actual spending per category for a `YYYY-MM` period
+#[tauri::command]
+pub async fn budget_status(
+    period: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse<BudgetStatusReport>, tauri::Error> {
+    let period = match period.as_deref().map(str::parse::<BudgetPeriod>).transpose() {
+        Ok(period) => period,
+        Err(e) => return Ok(CommandResponse::error(e)),
+    };
+    tracing::info!(
+        "Generating budget status for period: {}",
+        period.map_or_else(|| "current".to_string(), |period| period.to_string())
+    );
+
+    match load_budget_status(period, &state).await {
+        Ok(report) => {
+            tracing::info!("Generated budget status for {} categories", report.categories.len());
+            Ok(CommandResponse::success(report))
+        }
+        Err(e) => {
+            tracing::error!("Failed to generate budget status: {}", e);
+            Ok(CommandResponse::error(format!("Failed to generate budget status: {}", e)))
+        }
+    }
+}
+
+/// Spending bucketed by day, week or month over an inclusive `YYYY-MM-DD` range
+#[tauri::command]
+pub async fn get_spending_timeseries(
+    start_date: String,
+    end_date: String,
+    granularity: String,
+    by_category: Option<bool>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse<Vec<SpendingBucket>>, tauri::Error> {
+    let granularity = match granularity.parse::<SpendingGranularity>() {
+        Ok(granularity) => granularity,
+        Err(e) => return Ok(CommandResponse::error(e)),
+    };
+    let range = match (start_date.parse(), end_date.parse()) {
+        (Ok(start), Ok(end)) => match SpendingRange::new(start, end) {
+            Ok(range) => range,
+            Err(e) => return Ok(CommandResponse::error(e)),
+        },
+        _ => return Ok(CommandResponse::error("Dates must be formatted as YYYY-MM-DD")),
+    };
+    tracing::info!("Generating {:?} spending series from {} to {}", granularity, range.start, range.end);
+
+    match load_spending_timeseries(range, granularity, by_category.unwrap_or(false), &state).await {
+        Ok(buckets) => {
+            tracing::info!("Generated {} spending buckets", buckets.len());
+            Ok(CommandResponse::success(buckets))
+        }
+        Err(e) => {
+            tracing::error!("Failed to generate spending series: {}", e);
+            Ok(CommandResponse::error(format!("Failed to generate spending series: {}", e)))
+        }
+    }
+}
+
+/// Rough income tax for a calendar year from the user's income transactions
+///
+/// Brackets and deductions come from the caller, so any jurisdiction and
+/// year can be modelled.
+#[tauri::command]
+pub async fn estimate_taxes(
+    year: i32,
+    brackets: Vec<TaxBracket>,
+    deductions: TaxDeductions,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse<TaxEstimate>, tauri::Error> {
+    tracing::info!("Estimating {} taxes across {} brackets", year, brackets.len());
+
+    match compute_tax_estimate(year, &brackets, &deductions, &state).await {
+        Ok(estimate) => {
+            tracing::info!("Estimated {} taxes at an effective rate of {}%", year, estimate.effective_rate);
+            Ok(CommandResponse::success(estimate))
+        }
+        Err(e) => {
+            tracing::error!("Failed to estimate taxes: {}", e);
+            Ok(CommandResponse::error(format!("Failed to estimate taxes: {}", e)))
+        }
+    }
+}
+
+/// Project daily balances across accounts and warn about upcoming shortfalls
+#[tauri::command]
+pub async fn forecast_cash_flow(
+    account_ids: Vec<String>,
+    horizon_days: u32,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse<CashFlowForecast>, tauri::Error> {
+    tracing::info!("Forecasting cash flow for {} accounts over {} days", account_ids.len(), horizon_days);
+
+    match generate_cash_flow_forecast(&account_ids, horizon_days, &state).await {
+        Ok(forecast) => {
+            if let Some(first) = forecast.warnings.first() {
+                tracing::warn!("Projected balance drops below zero on {}", first.date);
+            }
+
+            tracing::info!("Successfully generated cash flow forecast with {} warnings", forecast.warnings.len());
+            Ok(CommandResponse::success(forecast))
+        }
+        Err(e) => {
+            tracing::error!("Failed to forecast cash flow: {}", e);
+            Ok(CommandResponse::error(format!("Failed to forecast cash flow: {}", e)))
+        }
+    }
+}
+
+/// Work out how much of an account's balance can be spent without putting
+/// payments due within the horizon at risk
+///
+/// `debt_payments` are the minimums the user owes each month; `buffer` is kept
+/// back on top of every obligation and defaults to zero.
+#[tauri::command]
+pub async fn calculate_safe_to_spend(
+    account_id: String,
+    horizon_days: u32,
+    debt_payments: Vec<DebtPaymentDue>,
+    buffer: Option<Decimal>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse<SafeToSpend>, tauri::Error> {
+    tracing::info!("Calculating safe-to-spend over {} days", horizon_days);
+
+    match compute_safe_to_spend(&account_id, horizon_days, &debt_payments, buffer.unwrap_or(Decimal::ZERO), &state).await {
+        Ok(result) => Ok(CommandResponse::success(result)),
+        Err(e) => {
+            tracing::error!("Failed to calculate safe-to-spend: {}", e);
+            Ok(CommandResponse::error(format!("Failed to calculate safe-to-spend: {}", e)))
+        }
+    }
+}
+
+// ============================================================================
+// Data Import/Export Commands
+// ============================================================================
+
+/// Export financial data with desktop file dialog
+#[tauri::command]
+pub async fn export_financial_data(
+    export_options: ExportOptions,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse<String>, tauri::Error> {
+    tracing::info!("Exporting financial data in format: {:?}", export_options.format);
+
+    // Show save file dialog
+    let file_extension = match export_options.format {
+        ExportFormat::CSV => "csv",
+        ExportFormat::JSON => "json",
+        ExportFormat::PDF => "pdf",
+        ExportFormat::Excel => "xlsx",
+    };
+
+    let default_filename = format!("atlas_financial_export_{}.{}",
+                                   Utc::now().format("%Y%m%d_%H%M%S"),
+                                   file_extension);
+
+    let filters = vec![
+        (format!("{} Files", file_extension.to_uppercase()).as_str(), &[file_extension]),
+        ("All Files", &["*"]),
+    ];
+
+    match desktop_utils::save_file_dialog(
         &app,
         "Export Financial Data",
         &default_filename,
         filters,
     ).await {
         Ok(Some(file_path)) => {
-            match export_data_to_file(&export_options, &file_path, &state).await {
+            match export_data_to_file(&export_options, &file_path, &app, &state).await {
                 Ok(_) => {
-                    let _ = send_desktop_notification(
-                        &app,
-                        "Export Complete",
-                        &format!("Financial data exported to: {}", file_path),
-                    ).await;
+                    state.notifications.send(Notification::new("Export Complete", format!("Financial data exported to: {}", file_path)));
 
                     tracing::info!("Successfully exported data to: {}", file_path);
                     Ok(CommandResponse::success(file_path))
@@ -688,6 +1249,69 @@ pub async fn export_financial_data(
     }
 }
 
+/// Export the payment dates of a debt payoff strategy as an iCalendar (.ics) file
+///
+/// `debts` is the same input the strategy comparison takes; the calendar is
+/// built by the financial engine and saved where the user chooses.
+#[tauri::command]
+pub async fn export_debt_payment_calendar(
+    session_token: String,
+    user_id: String,
+    debts: serde_json::Value,
+    strategy: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse<String>, tauri::Error> {
+    let strategy = strategy.to_uppercase();
+    if !matches!(strategy.as_str(), "SNOWBALL" | "AVALANCHE") {
+        return Ok(CommandResponse::error("Payment calendars are available for snowball and avalanche plans"));
+    }
+    tracing::info!("Exporting {} debt payment calendar", strategy);
+
+    let calendar = match state.api_client
+        .get_debt_payment_calendar(&user_id, &debts, &strategy, &session_token)
+        .await
+    {
+        Ok(calendar) => calendar,
+        Err(e) => {
+            tracing::error!("Failed to build payment calendar: {}", e);
+            return Ok(CommandResponse::error(format!("Failed to build payment calendar: {}", e)));
+        }
+    };
+
+    let default_filename = format!("atlas_debt_payments_{}.ics", strategy.to_lowercase());
+    let filters = vec![
+        ("Calendar Files", &["ics"]),
+        ("All Files", &["*"]),
+    ];
+
+    match desktop_utils::save_file_dialog(
+        &app,
+        "Export Payment Calendar",
+        &default_filename,
+        filters,
+    ).await {
+        Ok(Some(file_path)) => match tokio::fs::write(&file_path, calendar).await {
+            Ok(()) => {
+                tracing::info!("Exported payment calendar to: {}", file_path);
+                Ok(CommandResponse::success(file_path))
+            }
+            Err(e) => {
+                tracing::error!("Failed to write payment calendar: {}", e);
+                Ok(CommandResponse::error(format!("Failed to write payment calendar: {}", e)))
+            }
+        },
+        Ok(None) => {
+            tracing::info!("Export cancelled by user");
+            Ok(CommandResponse::error("Export cancelled"))
+        }
+        Err(e) => {
+            tracing::error!("Failed to show save dialog: {}", e);
+            Ok(CommandResponse::error(format!("Failed to show save dialog: {}", e)))
+        }
+    }
+}
+
 /// Import financial data with desktop file dialog
 #[tauri::command]
 pub async fn import_financial_data(
@@ -713,13 +1337,9 @@ pub async fn import_financial_data(
         Ok(Some(file_paths)) => {
             if let Some(file_path) = file_paths.first() {
                 // Show progress notification
-                let _ = send_desktop_notification(
-                    &app,
-                    "Import Started",
-                    "Processing your financial data import...",
-                ).await;
+                state.notifications.send(Notification::new("Import Started", "Processing your financial data import..."));
 
-                match import_data_from_file(file_path, &state).await {
+                match import_data_from_file(file_path, &app, &state).await {
                     Ok(result) => {
                         let message = if result.failed_imports > 0 {
                             format!("Import completed with {} successes and {} failures",
@@ -729,21 +1349,13 @@ pub async fn import_financial_data(
                                    result.successful_imports)
                         };
 
-                        let _ = send_desktop_notification(
-                            &app,
-                            "Import Complete",
-                            &message,
-                        ).await;
+                        state.notifications.send(Notification::new("Import Complete", message));
 
                         tracing::info!("Successfully imported data from: {}", file_path);
                         Ok(CommandResponse::success(result))
                     }
                     Err(e) => {
-                        let _ = send_desktop_notification(
-                            &app,
-                            "Import Failed",
-                            "Failed to import financial data. Please check the file format.",
-                        ).await;
+                        state.notifications.send(Notification::new("Import Failed", "Failed to import financial data. Please check the file format."));
 
                         tracing::error!("Failed to import data: {}", e);
                         Ok(CommandResponse::error(format!("Failed to import data: {}", e)))
@@ -776,7 +1388,7 @@ async fn get_session_token_from_app() -> Option<String> {
     Some("placeholder-session-token".to_string())
 }
 
-async fn fetch_user_accounts(state: &State<'_, AppState>) -> Result<Vec<Account>, Box<dyn std::error::Error>> {
+async fn fetch_user_accounts(include_archived: bool, state: &State<'_, AppState>) -> Result<Vec<Account>, Box<dyn std::error::Error>> {
     // Get session token from stored session (this would be improved with proper session management)
     let session_token = get_session_token_from_app().await
         .ok_or("No valid session found")?;
@@ -787,7 +1399,21 @@ async fn fetch_user_accounts(state: &State<'_, AppState>) -> Result<Vec<Account>
         .await
         .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
 
-    Ok(accounts)
+    if include_archived {
+        return Ok(accounts);
+    }
+
+    // Archival is recorded in the local store, so hide what was archived there
+    let user = state.session.current_user().await?;
+    let records = AccountRepository::new(&state.database_manager)
+        .find_by_user_id(&user.user_id).await
+        .map_err(|e| format!("Database error: {}", e))?;
+    let archived = archived_account_ids(&records);
+
+    Ok(accounts
+        .into_iter()
+        .filter(|account| !archived.contains(&account.id))
+        .collect())
 }
 
 async fn fetch_account_by_id(account_id: &str, state: &State<'_, AppState>) -> Result<Option<Account>, Box<dyn std::error::Error>> {
@@ -803,7 +1429,60 @@ async fn fetch_account_by_id(account_id: &str, state: &State<'_, AppState>) -> R
         .map_err(|e| format!("Database error: {}", e))?;
 
     // Convert to API type if found
-    let account = account_record.map(|record| Account {
+    Ok(account_record.map(account_record_to_account))
+}
+
+async fn set_account_archived(
+    account_id: &str,
+    archived: bool,
+    balance_zeroed_at: Option<DateTime<Utc>>,
+    state: &State<'_, AppState>,
+) -> Result<Option<Account>, Box<dyn std::error::Error>> {
+    // Act only for the signed-in user
+    let user = state.session.current_user().await?;
+    let user_id = user.user_id.as_str();
+
+    // Use secure repository pattern
+    let db_manager = &state.database_manager;
+    let account_repo = AccountRepository::new(db_manager);
+
+    let record = if archived {
+        account_repo.archive(account_id, user_id, balance_zeroed_at).await
+    } else {
+        account_repo.reactivate(account_id, user_id).await
+    }
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(record.map(account_record_to_account))
+}
+
+async fn soft_delete_account(
+    account_id: &str,
+    cascade: bool,
+    state: &State<'_, AppState>,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    // Act only for the signed-in user
+    let user = state.session.current_user().await?;
+    let user_id = user.user_id.as_str();
+
+    // Use secure repository pattern
+    let db_manager = &state.database_manager;
+    let account_repo = AccountRepository::new(db_manager);
+
+    let deleted = account_repo.soft_delete(account_id, user_id, cascade).await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(deleted)
+}
+
+fn account_record_to_account(record: crate::storage::AccountRecord) -> Account {
+    // Stored codes predate validation; unknown ones are shown as USD
+    let currency = record.currency.parse::<Currency>().unwrap_or_else(|e| {
+        tracing::warn!("{}; treating account {} as USD", e, record.id);
+        Currency::USD
+    });
+
+    Account {
         id: record.id,
         user_id: record.user_id,
         name: record.name,
@@ -818,34 +1497,42 @@ async fn fetch_account_by_id(account_id: &str, state: &State<'_, AppState>) -> R
             crate::storage::AccountType::Cash => AccountType::Cash,
             crate::storage::AccountType::Other => AccountType::Other,
         },
-        balance: FinancialAmount::from_decimal(record.balance, record.currency.clone()).unwrap_or_else(|_|
-            FinancialAmount::zero(record.currency.clone()).unwrap()
-        ),
-        currency: record.currency,
+        balance: FinancialAmount::from_decimal(record.balance, currency)
+            .unwrap_or_else(|_| FinancialAmount::zero(currency)),
+        currency,
         is_active: record.is_active,
         created_at: record.created_at,
         updated_at: record.updated_at,
         institution: record.institution,
         account_number_masked: record.account_number_masked,
         credit_limit: record.credit_limit.map(|cl|
-            FinancialAmount::from_decimal(cl, "USD".to_string()).unwrap_or_else(|_|
-                FinancialAmount::zero("USD".to_string()).unwrap()
-            )
+            FinancialAmount::from_decimal(cl, currency).unwrap_or_else(|_| FinancialAmount::zero(currency))
         ),
         interest_rate: record.interest_rate,
-    });
+        archived_at: record.archived_at,
+        balance_zeroed_at: record.balance_zeroed_at,
+    }
+}
 
-    Ok(account)
+/// Transaction repository that keeps notes encrypted with the SecureVault key
+async fn encrypted_transaction_repo<'a>(
+    db_manager: &'a crate::storage::DatabaseManager,
+    app: &AppHandle,
+) -> Result<TransactionRepository<'a>, Box<dyn std::error::Error>> {
+    let vault = get_vault(app.clone()).await?;
+    Ok(TransactionRepository::new(db_manager).with_field_encryption(&*vault, SensitiveFieldPolicy::default()))
 }
 
 async fn fetch_filtered_transactions(
     filter: &Option<TransactionFilter>,
     limit: i32,
     offset: i32,
+    app: &AppHandle,
     state: &State<'_, AppState>,
 ) -> Result<Vec<Transaction>, Box<dyn std::error::Error>> {
-    // Get user ID from session state (placeholder)
-    let user_id = "placeholder-user-id"; // TODO: Get from actual session
+    // Act only for the signed-in user
+    let user = state.session.current_user().await?;
+    let user_id = user.user_id.as_str();
 
     // Convert filter to storage filter format
     let storage_filter = match filter {
@@ -890,7 +1577,7 @@ async fn fetch_filtered_transactions(
 
     // Use secure repository pattern
     let db_manager = &state.database_manager;
-    let transaction_repo = TransactionRepository::new(db_manager);
+    let transaction_repo = encrypted_transaction_repo(db_manager, app).await?;
 
     let transaction_records = transaction_repo.find_filtered(user_id, &storage_filter, limit, offset).await
         .map_err(|e| format!("Database error: {}", e))?;
@@ -900,9 +1587,8 @@ async fn fetch_filtered_transactions(
         id: record.id,
         user_id: record.user_id,
         account_id: record.account_id,
-        amount: FinancialAmount::from_decimal(record.amount, "USD".to_string()).unwrap_or_else(|_|
-            FinancialAmount::zero("USD".to_string()).unwrap()
-        ),
+        amount: FinancialAmount::from_decimal(record.amount, Currency::USD)
+            .unwrap_or_else(|_| FinancialAmount::zero(Currency::USD)),
         description: record.description,
         category: record.category,
         subcategory: record.subcategory,
@@ -930,20 +1616,35 @@ async fn fetch_filtered_transactions(
     Ok(transactions)
 }
 
+/// Type of the account a transaction is posted to, when it can be found
+///
+/// Validation falls back to the default amount bounds when it cannot.
+async fn transaction_account_type(account_id: &str, state: &State<'_, AppState>) -> Option<crate::storage::AccountType> {
+    match AccountRepository::new(&state.database_manager).find_by_id(account_id).await {
+        Ok(account) => account.map(|account| account.account_type),
+        Err(e) => {
+            tracing::warn!("Could not load account for amount limits: {}", e);
+            None
+        }
+    }
+}
+
 async fn create_transaction(
     input: &TransactionInput,
+    anomalies: &[AnomalyFinding],
+    app: &AppHandle,
     state: &State<'_, AppState>,
 ) -> Result<Transaction, Box<dyn std::error::Error>> {
     // Validate input using secure validator
     InputValidator::validate_transaction_input(input)
         .map_err(|e| format!("Validation error: {}", e))?;
 
-    // Get user ID from session state (placeholder)
-    let user_id = "placeholder-user-id"; // TODO: Get from actual session
+    // Act only for the signed-in user
+    let user = state.session.current_user().await?;
+    let user_id = user.user_id.as_str();
 
     // Parse and validate amount
-    let amount = input.amount.parse::<Decimal>()
-        .map_err(|_| "Invalid amount format")?;
+    let amount = parse_money(&input.amount, Currency::USD, state.config.ui_settings.number_format)?.amount();
 
     // Create storage request
     let create_request = CreateTransactionRequest {
@@ -967,14 +1668,18 @@ async fn create_transaction(
         merchant: input.merchant.clone(),
         location: input.location.clone(),
         is_recurring: input.is_recurring,
-        tags: input.tags.clone(),
+        tags: if anomalies.is_empty() {
+            input.tags.clone()
+        } else {
+            Some(with_anomaly_tag(input.tags.clone()))
+        },
         notes: input.notes.clone(),
         ml_confidence: None,
     };
 
     // Use secure repository pattern
     let db_manager = &state.database_manager;
-    let transaction_repo = TransactionRepository::new(db_manager);
+    let transaction_repo = encrypted_transaction_repo(db_manager, app).await?;
 
     let transaction_record = transaction_repo.create(&create_request).await
         .map_err(|e| format!("Database error: {}", e))?;
@@ -984,7 +1689,7 @@ async fn create_transaction(
         id: transaction_record.id,
         user_id: transaction_record.user_id,
         account_id: transaction_record.account_id,
-        amount: FinancialAmount::from_decimal(transaction_record.amount, "USD".to_string())?,
+        amount: FinancialAmount::from_decimal(transaction_record.amount, Currency::USD)?,
         description: transaction_record.description,
         category: transaction_record.category,
         subcategory: transaction_record.subcategory,
@@ -1015,18 +1720,19 @@ async fn create_transaction(
 async fn update_existing_transaction(
     transaction_id: &str,
     input: &TransactionInput,
+    app: &AppHandle,
     state: &State<'_, AppState>,
 ) -> Result<Option<Transaction>, Box<dyn std::error::Error>> {
     // Validate input using secure validator
     InputValidator::validate_transaction_input(input)
         .map_err(|e| format!("Validation error: {}", e))?;
 
-    // Get user ID from session state (placeholder)
-    let user_id = "placeholder-user-id"; // TODO: Get from actual session
+    // Act only for the signed-in user
+    let user = state.session.current_user().await?;
+    let user_id = user.user_id.as_str();
 
     // Parse and validate amount
-    let amount = input.amount.parse::<Decimal>()
-        .map_err(|_| "Invalid amount format")?;
+    let amount = parse_money(&input.amount, Currency::USD, state.config.ui_settings.number_format)?.amount();
 
     // Create update request
     let update_request = CreateTransactionRequest {
@@ -1057,7 +1763,7 @@ async fn update_existing_transaction(
 
     // Use secure repository pattern
     let db_manager = &state.database_manager;
-    let transaction_repo = TransactionRepository::new(db_manager);
+    let transaction_repo = encrypted_transaction_repo(db_manager, app).await?;
 
     let transaction_record = transaction_repo.update(transaction_id, &update_request).await
         .map_err(|e| format!("Database error: {}", e))?;
@@ -1067,9 +1773,8 @@ async fn update_existing_transaction(
         id: record.id,
         user_id: record.user_id,
         account_id: record.account_id,
-        amount: FinancialAmount::from_decimal(record.amount, "USD".to_string()).unwrap_or_else(|_|
-            FinancialAmount::zero("USD".to_string()).unwrap()
-        ),
+        amount: FinancialAmount::from_decimal(record.amount, Currency::USD)
+            .unwrap_or_else(|_| FinancialAmount::zero(Currency::USD)),
         description: record.description,
         category: record.category,
         subcategory: record.subcategory,
@@ -1101,8 +1806,9 @@ async fn soft_delete_transaction(
     transaction_id: &str,
     state: &State<'_, AppState>,
 ) -> Result<bool, Box<dyn std::error::Error>> {
-    // Get user ID from session state (placeholder)
-    let user_id = "placeholder-user-id"; // TODO: Get from actual session
+    // Act only for the signed-in user
+    let user = state.session.current_user().await?;
+    let user_id = user.user_id.as_str();
 
     // Use secure repository pattern
     let db_manager = &state.database_manager;
@@ -1114,6 +1820,262 @@ async fn soft_delete_transaction(
     Ok(deleted)
 }
 
+/// Canonical path, content hash and size of a file to attach
+///
+/// The file must pass the usual path security checks, exist, and resolve to a
+/// location inside `app_data_dir` once symlinks are followed.
+async fn resolve_attachment_file(
+    file_path: &str,
+    app_data_dir: &std::path::Path,
+    path_access: &PathAccessPolicy,
+) -> Result<(String, String, i64), Box<dyn std::error::Error>> {
+    use sha2::{Digest, Sha256};
+
+    if !super::system::validate_path_security(file_path, path_access).await? {
+        return Err("Access denied: Path outside allowed directories".into());
+    }
+
+    let canonical = tokio::fs::canonicalize(file_path).await
+        .map_err(|_| "Attachment file not found")?;
+    let data_dir = tokio::fs::canonicalize(app_data_dir).await?;
+    if !canonical.starts_with(&data_dir) {
+        return Err("Attachments must be stored in the app data directory".into());
+    }
+
+    let metadata = tokio::fs::metadata(&canonical).await?;
+    if !metadata.is_file() {
+        return Err("Attachment path is not a file".into());
+    }
+
+    let contents = tokio::fs::read(&canonical).await?;
+    let content_hash: String = Sha256::digest(&contents)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+
+    Ok((canonical.to_string_lossy().to_string(), content_hash, metadata.len() as i64))
+}
+
+async fn attach_file_to_transaction(
+    transaction_id: &str,
+    file_path: &str,
+    app: &AppHandle,
+    state: &State<'_, AppState>,
+) -> Result<AttachmentRecord, Box<dyn std::error::Error>> {
+    use tauri::Manager;
+
+    let app_data_dir = app.path().app_data_dir()?;
+    let (file_path, content_hash, file_size) = resolve_attachment_file(file_path, &app_data_dir, &state.config.security_settings.path_access).await?;
+
+    // Act only for the signed-in user
+    let user = state.session.current_user().await?;
+    let user_id = user.user_id.as_str();
+
+    // Use secure repository pattern
+    let db_manager = &state.database_manager;
+    let attachment_repo = AttachmentRepository::new(db_manager);
+
+    let attachment = attachment_repo.add(&crate::storage::CreateAttachmentRequest {
+        user_id: user_id.to_string(),
+        transaction_id: transaction_id.to_string(),
+        file_path,
+        content_hash,
+        file_size,
+    }).await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(attachment)
+}
+
+async fn load_transaction_attachments(
+    transaction_id: &str,
+    state: &State<'_, AppState>,
+) -> Result<Vec<AttachmentRecord>, Box<dyn std::error::Error>> {
+    // Act only for the signed-in user
+    let user = state.session.current_user().await?;
+    let user_id = user.user_id.as_str();
+
+    // Use secure repository pattern
+    let db_manager = &state.database_manager;
+    let attachment_repo = AttachmentRepository::new(db_manager);
+
+    let attachments = attachment_repo.find_by_transaction(transaction_id, user_id).await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(attachments)
+}
+
+async fn delete_transaction_attachment(
+    attachment_id: &str,
+    state: &State<'_, AppState>,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    // Act only for the signed-in user
+    let user = state.session.current_user().await?;
+    let user_id = user.user_id.as_str();
+
+    // Use secure repository pattern
+    let db_manager = &state.database_manager;
+    let attachment_repo = AttachmentRepository::new(db_manager);
+
+    let removed = attachment_repo.remove(attachment_id, user_id).await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(removed)
+}
+
+async fn bulk_update_transaction_category(
+    transaction_ids: &[String],
+    category: &str,
+    state: &State<'_, AppState>,
+) -> Result<BulkCategorizeResult, Box<dyn std::error::Error>> {
+    // Act only for the signed-in user
+    let user = state.session.current_user().await?;
+    let user_id = user.user_id.as_str();
+
+    // Use secure repository pattern
+    let db_manager = &state.database_manager;
+    let transaction_repo = TransactionRepository::new(db_manager);
+
+    let outcome = transaction_repo.bulk_update_category(user_id, transaction_ids, category).await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(BulkCategorizeResult {
+        updated_count: outcome.updated_ids.len(),
+        not_found_ids: outcome.not_found_ids,
+    })
+}
+
+/// Most category transactions anomaly detection compares a new one against
+const ANOMALY_HISTORY_LIMIT: i32 = 500;
+
+/// Anomaly rules a new transaction trips, judged against the user's recent
+/// transactions in the same category
+async fn detect_transaction_anomalies(
+    input: &TransactionInput,
+    app: &AppHandle,
+    state: &State<'_, AppState>,
+) -> Result<Vec<AnomalyFinding>, Box<dyn std::error::Error>> {
+    let settings = &state.config.notification_settings.anomaly_detection;
+    if !settings.enabled {
+        return Ok(Vec::new());
+    }
+
+    let amount = parse_money(&input.amount, Currency::USD, state.config.ui_settings.number_format)?.amount();
+
+    let history: Vec<Decimal> = match &input.category {
+        Some(category) => {
+            // Act only for the signed-in user
+            let user = state.session.current_user().await?;
+            let user_id = user.user_id.as_str();
+
+            // Use secure repository pattern
+            let db_manager = &state.database_manager;
+            let transaction_repo = encrypted_transaction_repo(db_manager, app).await?;
+
+            let filter = crate::storage::TransactionFilter {
+                account_ids: None,
+                categories: Some(vec![category.clone()]),
+                amount_min: None,
+                amount_max: None,
+                date_start: Some(Utc::now() - chrono::Duration::days(i64::from(settings.history_days))),
+                date_end: None,
+                transaction_types: None,
+                merchants: None,
+                search_text: None,
+            };
+
+            transaction_repo
+                .find_filtered(user_id, &filter, ANOMALY_HISTORY_LIMIT, 0)
+                .await
+                .map_err(|e| format!("Database error: {}", e))?
+                .into_iter()
+                .map(|record| record.amount)
+                .collect()
+        }
+        None => Vec::new(),
+    };
+
+    Ok(settings.evaluate(amount, input.category.as_deref(), &history))
+}
+
+/// Longest date window accepted when matching duplicates
+const MAX_DUPLICATE_WINDOW_DAYS: u32 = 31;
+/// How far back transactions are scanned for duplicates
+const DUPLICATE_SCAN_DAYS: i64 = 180;
+const DUPLICATE_SCAN_LIMIT: i32 = 500;
+
+async fn detect_duplicate_transactions(
+    account_id: &str,
+    window_days: u32,
+    app: &AppHandle,
+    state: &State<'_, AppState>,
+) -> Result<Vec<DuplicateCluster>, Box<dyn std::error::Error>> {
+    if window_days > MAX_DUPLICATE_WINDOW_DAYS {
+        return Err(format!("Duplicate window must be at most {} days", MAX_DUPLICATE_WINDOW_DAYS).into());
+    }
+
+    // Act only for the signed-in user
+    let user = state.session.current_user().await?;
+    let user_id = user.user_id.as_str();
+
+    // Use secure repository pattern
+    let db_manager = &state.database_manager;
+    let transaction_repo = encrypted_transaction_repo(db_manager, app).await?;
+
+    let now = Utc::now();
+    let filter = crate::storage::TransactionFilter {
+        account_ids: Some(vec![account_id.to_string()]),
+        categories: None,
+        amount_min: None,
+        amount_max: None,
+        date_start: Some(now - chrono::Duration::days(DUPLICATE_SCAN_DAYS)),
+        date_end: None,
+        transaction_types: None,
+        merchants: None,
+        search_text: None,
+    };
+
+    let candidates: Vec<DuplicateCandidate> = transaction_repo
+        .find_filtered(user_id, &filter, DUPLICATE_SCAN_LIMIT, 0)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?
+        .into_iter()
+        .map(|record| DuplicateCandidate {
+            id: record.id,
+            amount: record.amount,
+            description: record.merchant.unwrap_or(record.description),
+            transaction_date: record.transaction_date,
+        })
+        .collect();
+
+    Ok(find_duplicate_clusters(&candidates, window_days))
+}
+
+async fn merge_duplicate_transactions(
+    keep_id: &str,
+    transaction_ids: &[String],
+    state: &State<'_, AppState>,
+) -> Result<MergeTransactionsResult, Box<dyn std::error::Error>> {
+    let plan = plan_merge(keep_id, transaction_ids)?;
+
+    // Act only for the signed-in user
+    let user = state.session.current_user().await?;
+    let user_id = user.user_id.as_str();
+
+    // Use secure repository pattern
+    let db_manager = &state.database_manager;
+    let transaction_repo = TransactionRepository::new(db_manager);
+
+    let outcome = transaction_repo.merge_duplicates(user_id, &plan.keep_id, &plan.remove_ids).await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(MergeTransactionsResult {
+        kept_id: plan.keep_id,
+        removed_count: outcome.updated_ids.len(),
+        not_found_ids: outcome.not_found_ids,
+    })
+}
+
 async fn ml_categorize_transaction(
     transaction_id: &str,
     state: &State<'_, AppState>,
@@ -1127,14 +2089,49 @@ async fn ml_categorize_transaction(
 }
 
 async fn compute_net_worth(state: &State<'_, AppState>) -> Result<FinancialAmount, Box<dyn std::error::Error>> {
-    // Implementation would calculate net worth using financial engine
-    FinancialAmount::from_decimal(dec!(0.00), "USD".to_string())
+    // Act only for the signed-in user
+    let user = state.session.current_user().await?;
+    let user_id = user.user_id.as_str();
+
+    // Use secure repository pattern
+    let db_manager = &state.database_manager;
+    let account_repo = AccountRepository::new(db_manager);
+
+    let accounts = account_repo.find_by_user_id(user_id).await
+        .map_err(|e| format!("Database error: {}", e))?;
+    let net_worth = net_worth_of(&accounts, Utc::now());
+
+    Ok(FinancialAmount::from_decimal(net_worth, Currency::USD)?)
+}
+
+/// Net worth of `accounts` at `as_of`, counting debt balances as liabilities
+///
+/// Archived accounts count until their balance-zeroed date.
+fn net_worth_of(accounts: &[crate::storage::AccountRecord], as_of: DateTime<Utc>) -> Decimal {
+    accounts
+        .iter()
+        .filter(|account| account.included_in_reports_at(as_of))
+        .map(|account| match account.account_type {
+            crate::storage::AccountType::CreditCard
+            | crate::storage::AccountType::Loan
+            | crate::storage::AccountType::Mortgage => -account.balance.abs(),
+            _ => account.balance,
+        })
+        .sum()
 }
 
 async fn generate_financial_overview(state: &State<'_, AppState>) -> Result<FinancialOverview, Box<dyn std::error::Error>> {
+    // Act only for the signed-in user
+    let user = state.session.current_user().await?;
+    let user_id = user.user_id.as_str();
+
     // Implementation would aggregate financial data for comprehensive overview
     let now = Utc::now();
-    let zero_usd = FinancialAmount::from_decimal(dec!(0.00), "USD".to_string())?;
+    let zero_usd = FinancialAmount::zero(Currency::USD);
+
+    let (accounts, transactions) = recent_activity(user_id, now, state).await?;
+    let aggregates = insight_aggregates(&accounts, &transactions);
+    let debt_to_income = overview_debt_to_income(&accounts, &aggregates, now)?;
 
     Ok(FinancialOverview {
         net_worth: zero_usd.clone(),
@@ -1144,29 +2141,232 @@ async fn generate_financial_overview(state: &State<'_, AppState>) -> Result<Fina
         monthly_expenses: zero_usd.clone(),
         cash_flow: zero_usd.clone(),
         investment_value: zero_usd,
-        debt_to_income_ratio: dec!(0.00),
+        debt_to_income_ratio: debt_to_income.map(|ratio| ratio.as_percentage()),
+        debt_to_income_level: debt_to_income.map(|ratio| IncomeRatioThresholds::debt_to_income().classify(ratio)),
         savings_rate: dec!(0.00),
         emergency_fund_months: dec!(0.00),
         as_of_date: now,
     })
 }
 
+/// Estimated minimum payments on every debt account against the month's income
+///
+/// `None` when there was no income to measure against.
+fn overview_debt_to_income(
+    accounts: &[crate::storage::AccountRecord],
+    aggregates: &FinancialAggregates,
+    now: DateTime<Utc>,
+) -> Result<Option<Percentage>, Box<dyn std::error::Error>> {
+    let monthly_debt_payments: Decimal = accounts
+        .iter()
+        .filter(|account| account.included_in_reports_at(now))
+        .filter(|account| matches!(
+            account.account_type,
+            crate::storage::AccountType::CreditCard
+                | crate::storage::AccountType::Loan
+                | crate::storage::AccountType::Mortgage
+        ))
+        .map(|account| estimate_minimum_payment(account.balance, account.interest_rate))
+        .sum();
+
+    match debt_to_income(
+        Money::new(monthly_debt_payments, Currency::USD)?,
+        Money::new(aggregates.monthly_income, Currency::USD)?,
+    ) {
+        Ok(ratio) => Ok(Some(ratio)),
+        Err(atlas_financial_core::FinancialError::DivisionByZero) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// How far back transactions are aggregated for insights
+const INSIGHT_WINDOW_DAYS: i64 = 30;
+const INSIGHT_TRANSACTION_LIMIT: i32 = 2000;
+
+/// The user's accounts and their transactions from the last insight window
+async fn recent_activity(
+    user_id: &str,
+    now: DateTime<Utc>,
+    state: &State<'_, AppState>,
+) -> Result<(Vec<crate::storage::AccountRecord>, Vec<crate::storage::TransactionRecord>), Box<dyn std::error::Error>> {
+    // Use secure repository pattern
+    let db_manager = &state.database_manager;
+    let account_repo = AccountRepository::new(db_manager);
+    let transaction_repo = TransactionRepository::new(db_manager);
+
+    let accounts = account_repo.find_by_user_id(user_id).await
+        .map_err(|e| format!("Database error: {}", e))?;
+    let filter = crate::storage::TransactionFilter {
+        account_ids: None,
+        categories: None,
+        amount_min: None,
+        amount_max: None,
+        date_start: Some(now - chrono::Duration::days(INSIGHT_WINDOW_DAYS)),
+        date_end: Some(now),
+        transaction_types: None,
+        merchants: None,
+        search_text: None,
+    };
+    let transactions = transaction_repo
+        .find_filtered(user_id, &filter, INSIGHT_TRANSACTION_LIMIT, 0)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok((accounts, transactions))
+}
+
 async fn generate_brutal_honesty_insights(state: &State<'_, AppState>) -> Result<Vec<BrutalHonestyInsight>, Box<dyn std::error::Error>> {
-    // Implementation would use AI service to generate insights
-    Ok(vec![])
+    // Act only for the signed-in user
+    let user = state.session.current_user().await?;
+    let user_id = user.user_id.as_str();
+
+    let now = Utc::now();
+    let (accounts, transactions) = recent_activity(user_id, now, state).await?;
+
+    let dismissals = InsightDismissalRepository::new(&state.database_manager)
+        .find_by_user_id(user_id)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let aggregates = insight_aggregates(&accounts, &transactions);
+    let insights = evaluate_insight_rules(&default_insight_rules(), &aggregates, now);
+    Ok(without_dismissed(insights, &dismissals, now))
+}
+
+async fn save_insight_dismissal(
+    rule_id: &str,
+    snoozed_until: Option<DateTime<Utc>>,
+    state: &State<'_, AppState>,
+) -> Result<InsightDismissal, Box<dyn std::error::Error>> {
+    // Act only for the signed-in user
+    let user = state.session.current_user().await?;
+    let user_id = user.user_id.as_str();
+
+    let dismissal = InsightDismissalRepository::new(&state.database_manager)
+        .dismiss(user_id, rule_id, snoozed_until)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(dismissal)
+}
+
+/// Income, spending and card payments across the user's accounts
+///
+/// Transfers move money between the user's own accounts and count as
+/// neither; money arriving on a credit card is a payment towards it.
+fn insight_aggregates(
+    accounts: &[crate::storage::AccountRecord],
+    transactions: &[crate::storage::TransactionRecord],
+) -> FinancialAggregates {
+    let cards: HashMap<&str, &crate::storage::AccountRecord> = accounts
+        .iter()
+        .filter(|a| a.is_active && a.account_type == crate::storage::AccountType::CreditCard)
+        .map(|a| (a.id.as_str(), a))
+        .collect();
+
+    let mut aggregates = FinancialAggregates::default();
+    let mut card_payments: HashMap<&str, Decimal> = HashMap::new();
+    for transaction in transactions.iter().filter(|t| t.is_active) {
+        if transaction.transaction_type == crate::storage::TransactionType::Transfer {
+            continue;
+        }
+        let on_card = cards.contains_key(transaction.account_id.as_str());
+        if transaction.amount.is_sign_negative() {
+            let spent = -transaction.amount;
+            aggregates.monthly_expenses += spent;
+            let category = transaction.category.clone().unwrap_or_else(|| "Uncategorized".to_string());
+            *aggregates.category_spending.entry(category).or_default() += spent;
+        } else if on_card {
+            *card_payments.entry(transaction.account_id.as_str()).or_default() += transaction.amount;
+        } else {
+            aggregates.monthly_income += transaction.amount;
+        }
+    }
+
+    aggregates.credit_card_payments = cards
+        .values()
+        .map(|card| CreditCardPayment {
+            account_name: card.name.clone(),
+            balance: card.balance.abs(),
+            amount_paid: card_payments.get(card.id.as_str()).copied().unwrap_or(Decimal::ZERO),
+            minimum_payment: estimate_minimum_payment(card.balance, card.interest_rate),
+        })
+        .collect();
+    aggregates
+}
+
+/// Amount masking for list and overview responses
+///
+/// Privacy mode hides amounts unless the user explicitly unmasks them. Amounts
+/// stay hidden when the preference cannot be read.
+async fn response_masking(unmask: Option<bool>, state: &State<'_, AppState>) -> Option<AmountMasking> {
+    let masking = state.config.ui_settings.privacy_masking;
+    let privacy_mode = match state.session.current_user().await {
+        Ok(user) => user_privacy_mode(&user.user_id, state).await,
+        Err(e) => Err(e),
+    };
+    let privacy_mode = privacy_mode.unwrap_or_else(|e| {
+        tracing::warn!("Masking amounts; privacy mode unavailable: {}", e);
+        true
+    });
+    masking_for(privacy_mode, unmask.unwrap_or(false), masking)
 }
 
 async fn analyze_spending_patterns(period: &str, state: &State<'_, AppState>) -> Result<SpendingAnalysis, Box<dyn std::error::Error>> {
-    // Implementation would analyze spending patterns for the given period
-    let zero_usd = FinancialAmount::from_decimal(dec!(0.00), "USD".to_string())?;
+    // Act only for the signed-in user
+    let user = state.session.current_user().await?;
+    let user_id = user.user_id.as_str();
+
+    // Periods follow the user's local calendar
+    let timezone = user_timezone(user_id, state).await?;
+    let range = DateRange::parse(period, timezone)?.local_days(timezone);
+
+    // Use secure repository pattern
+    let db_manager = &state.database_manager;
+    let transaction_repo = TransactionRepository::new(db_manager);
+
+    let rows = transaction_repo
+        .spending_by_bucket(user_id, range, SpendingGranularity::Day, true, timezone)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let mut category_totals: HashMap<String, Decimal> = HashMap::new();
+    for row in &rows {
+        *category_totals
+            .entry(row.category.clone().unwrap_or_else(|| UNCATEGORIZED.to_string()))
+            .or_default() += row.total;
+    }
+    let total_spending = FinancialAmount::from_decimal(category_totals.values().copied().sum(), Currency::USD)?;
+    let category_breakdown = category_totals
+        .into_iter()
+        .map(|(category, total)| Ok((category, FinancialAmount::from_decimal(total, Currency::USD)?)))
+        .collect::<Result<HashMap<_, _>, FinancialError>>()?;
+
+    let tagged = transaction_repo
+        .tagged_spending(user_id, range, timezone)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+    let tag_breakdown = roll_up_by_prefix(tagged.iter().map(|row| (row.tags.as_slice(), row.amount)))
+        .into_iter()
+        .map(|(tag, total)| Ok((tag, FinancialAmount::from_decimal(total, Currency::USD)?)))
+        .collect::<Result<HashMap<_, _>, FinancialError>>()?;
+
+    // Compare against the user's budgets for the current month
+    let budget_report = load_budget_status(Some(BudgetPeriod::containing(Utc::now(), timezone)), state).await?;
+    let budget_comparison = if budget_report.categories.is_empty() {
+        None
+    } else {
+        Some(budget_comparison_from(&budget_report)?)
+    };
 
     Ok(SpendingAnalysis {
-        total_spending: zero_usd,
+        total_spending,
         period: period.to_string(),
-        category_breakdown: HashMap::new(),
+        category_breakdown,
+        tag_breakdown,
         top_merchants: vec![],
         spending_trends: vec![],
-        budget_comparison: None,
+        budget_comparison,
         unusual_patterns: vec![],
     })
 }
@@ -1176,39 +2376,502 @@ async fn generate_budget_recommendations(state: &State<'_, AppState>) -> Result<
     Ok(vec![])
 }
 
+fn budget_record_to_budget(record: crate::storage::BudgetRecord) -> Budget {
+    Budget {
+        category: record.category,
+        monthly_limit: record.monthly_limit,
+        rollover: record.rollover,
+        start_period: BudgetPeriod::of_date(record.starts_on),
+    }
+}
+
+async fn save_budget(
+    category: &str,
+    monthly_limit: Decimal,
+    rollover: bool,
+    start_period: Option<&str>,
+    state: &State<'_, AppState>,
+) -> Result<Budget, Box<dyn std::error::Error>> {
+    // Act only for the signed-in user
+    let user = state.session.current_user().await?;
+    let user_id = user.user_id.as_str();
+
+    let start_period = match start_period {
+        Some(period) => period.parse::<BudgetPeriod>()?,
+        None => BudgetPeriod::containing(Utc::now(), user_timezone(user_id, state).await?),
+    };
+
+    // Use secure repository pattern
+    let db_manager = &state.database_manager;
+    let budget_repo = BudgetRepository::new(db_manager);
+
+    let record = budget_repo.upsert(&crate::storage::CreateBudgetRequest {
+        user_id: user_id.to_string(),
+        category: category.to_string(),
+        monthly_limit,
+        rollover,
+        starts_on: start_period.first_day(),
+    }).await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(budget_record_to_budget(record))
+}
+
+/// Budget status for `period`, or the user's current month when `None`
+async fn load_budget_status(
+    period: Option<BudgetPeriod>,
+    state: &State<'_, AppState>,
+) -> Result<BudgetStatusReport, Box<dyn std::error::Error>> {
+    // Act only for the signed-in user
+    let user = state.session.current_user().await?;
+    let user_id = user.user_id.as_str();
+
+    // Months follow the user's local calendar
+    let timezone = user_timezone(user_id, state).await?;
+    let period = period.unwrap_or_else(|| BudgetPeriod::containing(Utc::now(), timezone));
+
+    // Use secure repository pattern
+    let db_manager = &state.database_manager;
+    let budget_repo = BudgetRepository::new(db_manager);
+    let transaction_repo = TransactionRepository::new(db_manager);
+
+    let budgets: Vec<Budget> = budget_repo.find_by_user_id(user_id).await
+        .map_err(|e| format!("Database error: {}", e))?
+        .into_iter()
+        .map(budget_record_to_budget)
+        .collect();
+
+    // Rollover needs every month since the earliest budget started
+    let Some(earliest) = budgets.iter().map(|b| b.start_period).filter(|p| *p <= period).min() else {
+        return Ok(compute_budget_status(&[], &HashMap::new(), period));
+    };
+
+    let filter = crate::storage::TransactionFilter {
+        account_ids: None,
+        categories: Some(budgets.iter().map(|b| b.category.clone()).collect()),
+        amount_min: None,
+        amount_max: Some(Decimal::ZERO),
+        date_start: Some(earliest.start(timezone)),
+        date_end: Some(period.end(timezone)),
+        transaction_types: None,
+        merchants: None,
+        search_text: None,
+    };
+
+    let mut spending = HashMap::new();
+    let mut cursor = None;
+    loop {
+        let page = transaction_repo
+            .find_page_after(user_id, &filter, cursor.as_ref(), EXPORT_PAGE_SIZE)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+
+        let page_spending = aggregate_spending(
+            page.iter()
+                .filter(|record| record.transaction_date < period.end(timezone))
+                .map(|record| (record.category.as_deref(), record.transaction_date, record.amount)),
+            timezone,
+        );
+        for (key, amount) in page_spending {
+            *spending.entry(key).or_insert(Decimal::ZERO) += amount;
+        }
+
+        match page.last() {
+            Some(last) if page.len() >= EXPORT_PAGE_SIZE as usize => {
+                cursor = Some(crate::storage::TransactionCursor::after(last));
+            }
+            _ => break,
+        }
+    }
+
+    Ok(compute_budget_status(&budgets, &spending, period))
+}
+
+fn budget_comparison_from(report: &BudgetStatusReport) -> Result<BudgetComparison, Box<dyn std::error::Error>> {
+    let percentage_used = if report.total_budgeted.is_zero() {
+        Decimal::ZERO
+    } else {
+        (report.total_actual / report.total_budgeted * dec!(100)).round_dp(2)
+    };
+
+    Ok(BudgetComparison {
+        budgeted: FinancialAmount::from_decimal(report.total_budgeted, Currency::USD)?,
+        actual: FinancialAmount::from_decimal(report.total_actual, Currency::USD)?,
+        variance: FinancialAmount::from_decimal(report.total_remaining, Currency::USD)?,
+        percentage_used,
+    })
+}
+
+/// Longest forecast horizon accepted from the frontend
+const MAX_FORECAST_HORIZON_DAYS: u32 = 365;
+const FORECAST_HISTORY_LIMIT: i32 = 500;
+
+async fn load_spending_timeseries(
+    range: SpendingRange,
+    granularity: SpendingGranularity,
+    by_category: bool,
+    state: &State<'_, AppState>,
+) -> Result<Vec<SpendingBucket>, Box<dyn std::error::Error>> {
+    // Reject oversized ranges before touching the database
+    range.bucket_starts(granularity)?;
+
+    // Act only for the signed-in user
+    let user = state.session.current_user().await?;
+    let user_id = user.user_id.as_str();
+
+    // Buckets follow the user's local calendar
+    let timezone = user_timezone(user_id, state).await?;
+
+    // Use secure repository pattern
+    let db_manager = &state.database_manager;
+    let transaction_repo = TransactionRepository::new(db_manager);
+
+    let rows = transaction_repo
+        .spending_by_bucket(user_id, range, granularity, by_category, timezone)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(spending_timeseries(
+        range,
+        granularity,
+        rows.iter().map(|row| (row.bucket, row.category.as_deref(), row.total)),
+        by_category,
+    )?)
+}
+
+async fn compute_tax_estimate(
+    year: i32,
+    brackets: &[TaxBracket],
+    deductions: &TaxDeductions,
+    state: &State<'_, AppState>,
+) -> Result<TaxEstimate, Box<dyn std::error::Error>> {
+    // Reject bad schedules before touching the database
+    validate_brackets(brackets)?;
+    let range = match (NaiveDate::from_ymd_opt(year, 1, 1), NaiveDate::from_ymd_opt(year, 12, 31)) {
+        (Some(start), Some(end)) => SpendingRange::new(start, end)?,
+        _ => return Err(format!("Invalid tax year: {}", year).into()),
+    };
+
+    // Act only for the signed-in user
+    let user = state.session.current_user().await?;
+    let user_id = user.user_id.as_str();
+
+    // The tax year follows the user's local calendar
+    let timezone = user_timezone(user_id, state).await?;
+
+    // Use secure repository pattern
+    let db_manager = &state.database_manager;
+    let transaction_repo = TransactionRepository::new(db_manager);
+
+    let rows = transaction_repo
+        .totals_by_type_and_category(user_id, range, timezone)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let activity = TaxYearActivity::from_totals(
+        rows.iter().map(|row| (row.transaction_type, row.category.as_deref(), row.total)),
+        &deductions.deductible_categories,
+    );
+    Ok(estimate_tax(activity, brackets, deductions)?)
+}
+
+async fn generate_cash_flow_forecast(
+    account_ids: &[String],
+    horizon_days: u32,
+    state: &State<'_, AppState>,
+) -> Result<CashFlowForecast, Box<dyn std::error::Error>> {
+    if account_ids.is_empty() {
+        return Err("At least one account is required".into());
+    }
+    if horizon_days == 0 || horizon_days > MAX_FORECAST_HORIZON_DAYS {
+        return Err(format!("Forecast horizon must be between 1 and {} days", MAX_FORECAST_HORIZON_DAYS).into());
+    }
+
+    // Act only for the signed-in user
+    let user = state.session.current_user().await?;
+    let user_id = user.user_id.as_str();
+
+    // Use secure repository pattern
+    let db_manager = &state.database_manager;
+    let account_repo = AccountRepository::new(db_manager);
+    let transaction_repo = TransactionRepository::new(db_manager);
+
+    // Starting position is the combined balance of the selected accounts
+    let mut starting_balance = Decimal::ZERO;
+    for account_id in account_ids {
+        Uuid::parse_str(account_id)
+            .map_err(|_| "Invalid account ID format")?;
+
+        let account = account_repo.find_by_id(account_id).await
+            .map_err(|e| format!("Database error: {}", e))?
+            .filter(|a| a.user_id == user_id && a.is_active)
+            .ok_or_else(|| format!("Account not found: {}", account_id))?;
+
+        starting_balance += account.balance;
+    }
+
+    let now = Utc::now();
+    let history = load_cash_flow_history(&transaction_repo, user_id, account_ids, now).await?;
+
+    Ok(project_cash_flow(
+        starting_balance,
+        &history,
+        &[],
+        now.date_naive(),
+        horizon_days,
+        Decimal::ZERO,
+    ))
+}
+
+/// Recent transactions on `account_ids` as cash flow events
+async fn load_cash_flow_history(
+    transaction_repo: &TransactionRepository<'_>,
+    user_id: &str,
+    account_ids: &[String],
+    now: DateTime<Utc>,
+) -> Result<Vec<CashFlowEvent>, Box<dyn std::error::Error>> {
+    let history_filter = crate::storage::TransactionFilter {
+        account_ids: Some(account_ids.to_vec()),
+        categories: None,
+        amount_min: None,
+        amount_max: None,
+        date_start: Some(now - chrono::Duration::days(FORECAST_HISTORY_DAYS)),
+        date_end: Some(now),
+        transaction_types: None,
+        merchants: None,
+        search_text: None,
+    };
+
+    Ok(transaction_repo
+        .find_filtered(user_id, &history_filter, FORECAST_HISTORY_LIMIT, 0)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?
+        .into_iter()
+        .map(|record| CashFlowEvent {
+            date: record.transaction_date.date_naive(),
+            amount: record.amount,
+            description: record.merchant.unwrap_or(record.description),
+            is_recurring: record.is_recurring,
+        })
+        .collect())
+}
+
+async fn compute_safe_to_spend(
+    account_id: &str,
+    horizon_days: u32,
+    debt_payments: &[DebtPaymentDue],
+    buffer: Decimal,
+    state: &State<'_, AppState>,
+) -> Result<SafeToSpend, Box<dyn std::error::Error>> {
+    if horizon_days == 0 || horizon_days > MAX_FORECAST_HORIZON_DAYS {
+        return Err(format!("Horizon must be between 1 and {} days", MAX_FORECAST_HORIZON_DAYS).into());
+    }
+    if buffer.is_sign_negative() {
+        return Err("Buffer cannot be negative".into());
+    }
+    if let Some(debt) = debt_payments
+        .iter()
+        .find(|debt| debt.minimum_payment.is_sign_negative() || !(1..=31).contains(&debt.due_day))
+    {
+        return Err(format!("Invalid debt payment: {}", debt.name).into());
+    }
+
+    Uuid::parse_str(account_id)
+        .map_err(|_| "Invalid account ID format")?;
+
+    // Act only for the signed-in user
+    let user = state.session.current_user().await?;
+    let user_id = user.user_id.as_str();
+
+    // Use secure repository pattern
+    let db_manager = &state.database_manager;
+    let account_repo = AccountRepository::new(db_manager);
+    let transaction_repo = TransactionRepository::new(db_manager);
+
+    let account = account_repo.find_by_id(account_id).await
+        .map_err(|e| format!("Database error: {}", e))?
+        .filter(|a| a.user_id == user_id && a.is_active)
+        .ok_or_else(|| format!("Account not found: {}", account_id))?;
+
+    let now = Utc::now();
+    let history = load_cash_flow_history(&transaction_repo, user_id, &[account.id.clone()], now).await?;
+
+    Ok(safe_to_spend(
+        account.balance,
+        &history,
+        debt_payments,
+        now.date_naive(),
+        horizon_days,
+        buffer,
+    ))
+}
+
 async fn export_data_to_file(
     options: &ExportOptions,
     file_path: &str,
+    app: &AppHandle,
     state: &State<'_, AppState>,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    // Implementation would:
-    // 1. Fetch data based on export options
-    // 2. Format data according to selected format
-    // 3. Write to file with proper error handling
+    let format = match options.format {
+        ExportFormat::CSV => StreamFormat::Csv,
+        ExportFormat::JSON => StreamFormat::Json,
+        ExportFormat::PDF | ExportFormat::Excel => {
+            return Err(format!("{:?} export is not supported yet", options.format).into());
+        }
+    };
+
+    // Act only for the signed-in user
+    let user = state.session.current_user().await?;
+    let user_id = user.user_id.as_str();
+
+    let date_range = match (options.date_range, &options.period) {
+        (Some(range), _) => Some(DateRange::new(range.start, range.end)?),
+        (None, Some(period)) => Some(DateRange::parse(period, user_timezone(user_id, state).await?)?),
+        (None, None) => None,
+    };
+
+    // Use secure repository pattern
+    let db_manager = &state.database_manager;
+    let transaction_repo = encrypted_transaction_repo(db_manager, app).await?;
+
+    let filter = crate::storage::TransactionFilter {
+        account_ids: options.accounts.clone(),
+        categories: None,
+        amount_min: None,
+        amount_max: None,
+        date_start: date_range.map(|dr| dr.start),
+        date_end: date_range.map(|dr| dr.end),
+        transaction_types: None,
+        merchants: None,
+        search_text: None,
+    };
+    let columns = match &options.columns {
+        Some(columns) => ExportColumns::new(columns.clone())?,
+        None => ExportColumns::with_optional(options.include_categories, options.include_tags),
+    };
+
+    let file = tokio::fs::File::create(file_path).await?;
+    let mut writer = tokio::io::BufWriter::new(file);
+
+    // Rows are fetched a page at a time and written straight to the file
+    let summary = stream_transactions(&mut writer, format, &columns, EXPORT_PAGE_SIZE, |cursor| {
+        let transaction_repo = &transaction_repo;
+        let filter = &filter;
+        async move {
+            transaction_repo
+                .find_page_after(user_id, filter, cursor.as_ref(), EXPORT_PAGE_SIZE)
+                .await
+        }
+    })
+    .await?;
+
+    tracing::info!("Exported {} transactions in {} pages", summary.rows_written, summary.pages_fetched);
     Ok(())
 }
 
 async fn import_data_from_file(
     file_path: &str,
+    app: &AppHandle,
     state: &State<'_, AppState>,
 ) -> Result<ImportResult, Box<dyn std::error::Error>> {
-    // Implementation would:
-    // 1. Detect file format
-    // 2. Parse and validate data
-    // 3. Import transactions with error handling
-    // 4. Return detailed import results
+    use tauri::Emitter;
+
+    if !super::system::validate_path_security(file_path, &state.config.security_settings.path_access).await? {
+        return Err("Access denied: Path outside allowed directories".into());
+    }
+    if !file_path.to_ascii_lowercase().ends_with(".csv") {
+        return Err("Only CSV files can be imported".into());
+    }
+
+    // Act only for the signed-in user
+    let user = state.session.current_user().await?;
+    let user_id = user.user_id.as_str();
 
-    Ok(ImportResult {
-        total_records: 0,
+    let db_manager = &state.database_manager;
+    let contents = tokio::fs::read(file_path).await?;
+    let file_hash = file_hash(&contents);
+    let rows = transaction_rows(&parse_csv(std::str::from_utf8(&contents)?), user_id)?;
+    let total_rows = rows.len() as i64;
+
+    // A file seen before picks up after the last row its checkpoint committed
+    let resume_after = ImportCheckpointRepository::new(db_manager)
+        .find(user_id, &file_hash)
+        .await?
+        .map_or(0, |checkpoint| checkpoint.last_committed_row.max(0) as u64);
+
+    let vault: &crate::security::SecureVault = get_vault(app.clone()).await?;
+    let result = std::sync::Mutex::new(ImportResult {
+        total_records: total_rows as i32,
         successful_imports: 0,
         failed_imports: 0,
         errors: vec![],
         warnings: vec![],
-    })
-}
+    });
 
-fn validate_transaction_input(input: &TransactionInput) -> Result<(), String> {
-    // Use the secure validator
-    InputValidator::validate_transaction_input(input)
-        .map_err(|e| e.to_string())
+    let outcome = import_in_batches(&rows, resume_after, &state.config.import_settings, |first_row, batch| {
+        let (file_hash, result) = (&file_hash, &result);
+        async move {
+            // Rows and checkpoint commit together, or neither does
+            let unit = UnitOfWork::begin(db_manager).await?;
+            let transactions = TransactionRepository::within(&unit).with_field_encryption(vault, SensitiveFieldPolicy::default());
+
+            let mut imported = 0;
+            let mut errors = Vec::new();
+            for (row, request) in (first_row..).zip(batch) {
+                let request = match request {
+                    Ok(request) => request,
+                    Err(error) => {
+                        errors.push(error.clone());
+                        continue;
+                    }
+                };
+                match transactions.create(request).await {
+                    Ok(_) => imported += 1,
+                    // The database transaction is aborted, so the whole batch is retried
+                    Err(e @ FinancialError::DatabaseError(_)) => return Err(e),
+                    Err(e) => errors.push(ImportError {
+                        row: row as i32,
+                        field: "transaction".to_string(),
+                        error: e.to_string(),
+                        value: request.description.clone(),
+                    }),
+                }
+            }
+
+            let last_row = first_row + batch.len() as u64 - 1;
+            ImportCheckpointRepository::within(&unit)
+                .save(user_id, file_hash, last_row as i64, total_rows)
+                .await?;
+            unit.commit().await?;
+
+            let mut result = result.lock().unwrap_or_else(|e| e.into_inner());
+            result.successful_imports += imported;
+            result.failed_imports += errors.len() as i32;
+            result.errors.extend(errors);
+            Ok(())
+        }
+    }, |progress| {
+        let _ = app.emit("import_progress", progress);
+    })
+    .await;
+
+    let mut result = result.into_inner().unwrap_or_else(|e| e.into_inner());
+    let progress = outcome.map_err(|e| {
+        format!("Import stopped after {} new records; import the file again to resume: {}", result.successful_imports, e)
+    })?;
+
+    if progress.resumed_after > 0 {
+        result.warnings.push(if progress.resumed_after == progress.total_rows {
+            "This file was already imported; no rows were added".to_string()
+        } else {
+            format!("Resumed a previous import after row {}", progress.resumed_after)
+        });
+    }
+    tracing::info!(
+        "Imported rows {}-{} in {} batches",
+        progress.resumed_after + 1,
+        progress.last_committed_row,
+        progress.batches_committed
+    );
+    Ok(result)
 }
@@ -4,6 +4,7 @@
 use tauri::{AppHandle, State, Window};
 use serde::{Deserialize, Serialize};
 use crate::AppState;
+use crate::security::secure_query::{FieldViolation, ValidationReport};
 
 // Re-export all command modules
 pub mod auth;
@@ -25,6 +26,9 @@ pub struct CommandResponse<T> {
     pub success: bool,
     pub data: Option<T>,
     pub error: Option<String>,
+    /// Per-field problems when the input failed validation
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub violations: Vec<FieldViolation>,
     pub timestamp: chrono::DateTime<chrono::Utc>,
 }
 
@@ -34,6 +38,7 @@ impl<T> CommandResponse<T> {
             success: true,
             data: Some(data),
             error: None,
+            violations: Vec::new(),
             timestamp: chrono::Utc::now(),
         }
     }
@@ -43,6 +48,18 @@ impl<T> CommandResponse<T> {
             success: false,
             data: None,
             error: Some(message.into()),
+            violations: Vec::new(),
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
+    /// Failed response listing every invalid field so the UI can highlight them
+    pub fn invalid(report: ValidationReport) -> Self {
+        Self {
+            success: false,
+            data: None,
+            error: Some(report.to_string()),
+            violations: report.violations,
             timestamp: chrono::Utc::now(),
         }
     }
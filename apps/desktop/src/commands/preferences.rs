@@ -6,9 +6,13 @@ use serde::{Deserialize, Serialize};
 use sqlx::{PgPool, Row};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
 use rust_decimal::Decimal;
 use std::collections::HashMap;
-use crate::{AppState, financial::FinancialError};
+use std::path::PathBuf;
+use crate::{AppState, financial::FinancialError, security::{get_vault, AutoLockPolicy, FieldCipher, PathAccessPolicy, SessionGuard}};
+use crate::backup::{self, BackupManifest, BackupVerification, RestoreSummary};
+use crate::spending::parse_timezone;
 use super::{CommandResponse, send_desktop_notification};
 
 // ============================================================================
@@ -104,6 +108,28 @@ impl PreferencesManager {
 // User Preferences Commands
 // ============================================================================
 
+/// Acting user for a preferences command
+///
+/// The frontend-supplied `user_id` (and the owner recorded on submitted
+/// preferences, if any) must match the signed-in session; anything else is
+/// denied.
+async fn authorize_preferences_access(
+    session: &SessionGuard,
+    requested_user_id: &str,
+    preferences_owner: Option<&str>,
+) -> Result<String, String> {
+    let user_id = session
+        .authorize_user(requested_user_id)
+        .await
+        .map_err(|e| format!("Access denied: {}", e))?;
+
+    if preferences_owner.is_some_and(|owner| owner != user_id) {
+        return Err("Access denied: preferences belong to another user".to_string());
+    }
+
+    Ok(user_id)
+}
+
 /// Get comprehensive user preferences
 #[tauri::command]
 pub async fn get_user_preferences(
@@ -111,6 +137,10 @@ pub async fn get_user_preferences(
     app: AppHandle,
     state: State<'_, AppState>,
 ) -> Result<CommandResponse<UserPreferences>, tauri::Error> {
+    let user_id = match authorize_preferences_access(&state.session, &user_id, None).await {
+        Ok(user_id) => user_id,
+        Err(e) => return Ok(CommandResponse::error(e)),
+    };
     tracing::info!("Getting user preferences for user: {}", user_id);
 
     let preferences = match get_user_preferences_internal(&user_id, &state).await {
@@ -132,6 +162,10 @@ pub async fn update_user_preferences(
     app: AppHandle,
     state: State<'_, AppState>,
 ) -> Result<CommandResponse<UserPreferences>, tauri::Error> {
+    let user_id = match authorize_preferences_access(&state.session, &user_id, Some(&preferences.user_id)).await {
+        Ok(user_id) => user_id,
+        Err(e) => return Ok(CommandResponse::error(e)),
+    };
     tracing::info!("Updating user preferences for user: {}", user_id);
 
     // Validate preferences
@@ -167,6 +201,10 @@ pub async fn reset_preferences_to_default(
     app: AppHandle,
     state: State<'_, AppState>,
 ) -> Result<CommandResponse<UserPreferences>, tauri::Error> {
+    let user_id = match authorize_preferences_access(&state.session, &user_id, None).await {
+        Ok(user_id) => user_id,
+        Err(e) => return Ok(CommandResponse::error(e)),
+    };
     tracing::info!("Resetting preferences to default for user: {}", user_id);
 
     match reset_preferences_internal(&user_id, &preference_categories, &state).await {
@@ -197,6 +235,10 @@ pub async fn get_currency_preferences(
     app: AppHandle,
     state: State<'_, AppState>,
 ) -> Result<CommandResponse<CurrencyPreferences>, tauri::Error> {
+    let user_id = match authorize_preferences_access(&state.session, &user_id, None).await {
+        Ok(user_id) => user_id,
+        Err(e) => return Ok(CommandResponse::error(e)),
+    };
     tracing::info!("Getting currency preferences for user: {}", user_id);
 
     match get_currency_preferences_internal(&user_id, &state).await {
@@ -216,6 +258,10 @@ pub async fn update_precision_settings(
     app: AppHandle,
     state: State<'_, AppState>,
 ) -> Result<CommandResponse<PrecisionSettings>, tauri::Error> {
+    let user_id = match authorize_preferences_access(&state.session, &user_id, None).await {
+        Ok(user_id) => user_id,
+        Err(e) => return Ok(CommandResponse::error(e)),
+    };
     tracing::info!("Updating precision settings for user: {}", user_id);
 
     // Validate precision settings
@@ -240,6 +286,10 @@ pub async fn configure_transaction_defaults(
     app: AppHandle,
     state: State<'_, AppState>,
 ) -> Result<CommandResponse<TransactionDefaults>, tauri::Error> {
+    let user_id = match authorize_preferences_access(&state.session, &user_id, None).await {
+        Ok(user_id) => user_id,
+        Err(e) => return Ok(CommandResponse::error(e)),
+    };
     tracing::info!("Configuring transaction defaults for user: {}", user_id);
 
     match configure_transaction_defaults_internal(&user_id, &defaults, &state).await {
@@ -262,10 +312,20 @@ pub async fn get_security_settings(
     app: AppHandle,
     state: State<'_, AppState>,
 ) -> Result<CommandResponse<SecuritySettings>, tauri::Error> {
+    let user_id = match authorize_preferences_access(&state.session, &user_id, None).await {
+        Ok(user_id) => user_id,
+        Err(e) => return Ok(CommandResponse::error(e)),
+    };
     tracing::info!("Getting security settings for user: {}", user_id);
 
     match get_security_settings_internal(&user_id, &state).await {
-        Ok(settings) => Ok(CommandResponse::success(settings)),
+        Ok(settings) => {
+            state.auto_lock.configure(AutoLockPolicy::new(
+                settings.auto_lock_enabled,
+                settings.auto_lock_timeout_minutes,
+            )).await;
+            Ok(CommandResponse::success(settings))
+        }
         Err(e) => {
             tracing::error!("Failed to get security settings: {}", e);
             Ok(CommandResponse::error(format!("Failed to get security settings: {}", e)))
@@ -281,6 +341,10 @@ pub async fn manage_biometric_settings(
     app: AppHandle,
     state: State<'_, AppState>,
 ) -> Result<CommandResponse<BiometricSettings>, tauri::Error> {
+    let user_id = match authorize_preferences_access(&state.session, &user_id, None).await {
+        Ok(user_id) => user_id,
+        Err(e) => return Ok(CommandResponse::error(e)),
+    };
     tracing::info!("Managing biometric settings for user: {}", user_id);
 
     match manage_biometric_settings_internal(&user_id, &settings, &state).await {
@@ -308,6 +372,10 @@ pub async fn configure_backup_preferences(
     app: AppHandle,
     state: State<'_, AppState>,
 ) -> Result<CommandResponse<BackupPreferences>, tauri::Error> {
+    let user_id = match authorize_preferences_access(&state.session, &user_id, None).await {
+        Ok(user_id) => user_id,
+        Err(e) => return Ok(CommandResponse::error(e)),
+    };
     tracing::info!("Configuring backup preferences for user: {}", user_id);
 
     match configure_backup_preferences_internal(&user_id, &preferences, &state).await {
@@ -319,6 +387,86 @@ pub async fn configure_backup_preferences(
     }
 }
 
+/// Back up the signed-in user's data to the configured location now
+///
+/// Takes an incremental backup on top of the latest one in that location
+/// unless `full` is set or there is no intact backup to build on.
+#[tauri::command]
+pub async fn backup_now(
+    user_id: String,
+    preferences: BackupPreferences,
+    full: Option<bool>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse<BackupManifest>, tauri::Error> {
+    let user_id = match authorize_preferences_access(&state.session, &user_id, None).await {
+        Ok(user_id) => user_id,
+        Err(e) => return Ok(CommandResponse::error(e)),
+    };
+    tracing::info!("Starting backup for user: {}", user_id);
+
+    match backup_now_internal(&user_id, &preferences, full.unwrap_or(false), &app, &state).await {
+        Ok(manifest) => Ok(CommandResponse::success(manifest)),
+        Err(e) => {
+            tracing::error!("Backup failed: {}", e);
+            Ok(CommandResponse::error(format!("Backup failed: {}", e)))
+        }
+    }
+}
+
+/// Check a backup file against the SHA-256 recorded in its manifest
+#[tauri::command]
+pub async fn verify_backup(
+    user_id: String,
+    manifest_path: String,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse<BackupVerification>, tauri::Error> {
+    if let Err(e) = authorize_preferences_access(&state.session, &user_id, None).await {
+        return Ok(CommandResponse::error(e));
+    }
+    if !super::system::validate_path_security(&manifest_path, &state.config.security_settings.path_access).await? {
+        return Ok(CommandResponse::error("Access denied: Invalid or restricted path"));
+    }
+
+    match backup::verify_backup(std::path::Path::new(&manifest_path)).await {
+        Ok(verification) => Ok(CommandResponse::success(verification)),
+        Err(e) => {
+            tracing::error!("Failed to verify backup: {}", e);
+            Ok(CommandResponse::error(format!("Failed to verify backup: {}", e)))
+        }
+    }
+}
+
+/// Replace the signed-in user's data with the backup described by `manifest_path`
+///
+/// Other users' data is left alone. The user's current data is first saved
+/// as a full backup in a `pre-restore` directory next to the manifest, so the
+/// restore can be rolled back.
+#[tauri::command]
+pub async fn restore_backup(
+    user_id: String,
+    manifest_path: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<CommandResponse<RestoreSummary>, tauri::Error> {
+    let user_id = match authorize_preferences_access(&state.session, &user_id, None).await {
+        Ok(user_id) => user_id,
+        Err(e) => return Ok(CommandResponse::error(e)),
+    };
+    if !super::system::validate_path_security(&manifest_path, &state.config.security_settings.path_access).await? {
+        return Ok(CommandResponse::error("Access denied: Invalid or restricted path"));
+    }
+    tracing::info!("Restoring backup {} for user: {}", manifest_path, user_id);
+
+    match restore_backup_internal(&user_id, std::path::Path::new(&manifest_path), &app, &state).await {
+        Ok(summary) => Ok(CommandResponse::success(summary)),
+        Err(e) => {
+            tracing::error!("Restore failed: {}", e);
+            Ok(CommandResponse::error(format!("Restore failed: {}", e)))
+        }
+    }
+}
+
 // ============================================================================
 // UI/Performance Settings Commands
 // ============================================================================
@@ -330,6 +478,10 @@ pub async fn get_theme_settings(
     app: AppHandle,
     state: State<'_, AppState>,
 ) -> Result<CommandResponse<ThemeSettings>, tauri::Error> {
+    let user_id = match authorize_preferences_access(&state.session, &user_id, None).await {
+        Ok(user_id) => user_id,
+        Err(e) => return Ok(CommandResponse::error(e)),
+    };
     tracing::info!("Getting theme settings for user: {}", user_id);
 
     match get_theme_settings_internal(&user_id, &state).await {
@@ -349,6 +501,10 @@ pub async fn manage_layout_preferences(
     app: AppHandle,
     state: State<'_, AppState>,
 ) -> Result<CommandResponse<LayoutPreferences>, tauri::Error> {
+    let user_id = match authorize_preferences_access(&state.session, &user_id, None).await {
+        Ok(user_id) => user_id,
+        Err(e) => return Ok(CommandResponse::error(e)),
+    };
     tracing::info!("Managing layout preferences for user: {}", user_id);
 
     match manage_layout_preferences_internal(&user_id, &preferences, &state).await {
@@ -368,6 +524,10 @@ pub async fn update_performance_settings(
     app: AppHandle,
     state: State<'_, AppState>,
 ) -> Result<CommandResponse<PerformanceSettings>, tauri::Error> {
+    let user_id = match authorize_preferences_access(&state.session, &user_id, None).await {
+        Ok(user_id) => user_id,
+        Err(e) => return Ok(CommandResponse::error(e)),
+    };
     tracing::info!("Updating performance settings for user: {}", user_id);
 
     match update_performance_settings_internal(&user_id, &settings, &state).await {
@@ -612,6 +772,22 @@ async fn get_user_preferences_internal(
     Ok(preferences)
 }
 
+/// Timezone the user's calendar periods are computed in
+///
+/// Falls back to UTC when the stored name is not an IANA timezone.
+pub(crate) async fn user_timezone(user_id: &str, state: &State<'_, AppState>) -> Result<Tz, FinancialError> {
+    let preferences = get_user_preferences_internal(user_id, state).await?;
+    Ok(parse_timezone(&preferences.timezone).unwrap_or_else(|e| {
+        tracing::warn!("{}; computing periods in UTC", e);
+        Tz::UTC
+    }))
+}
+
+/// Whether the user has privacy mode on, hiding amounts in lists and overviews
+pub(crate) async fn user_privacy_mode(user_id: &str, state: &State<'_, AppState>) -> Result<bool, FinancialError> {
+    Ok(get_user_preferences_internal(user_id, state).await?.privacy_mode)
+}
+
 async fn update_user_preferences_internal(
     user_id: &str,
     preferences: &UserPreferences,
@@ -710,9 +886,91 @@ async fn configure_backup_preferences_internal(
     preferences: &BackupPreferences,
     state: &State<'_, AppState>,
 ) -> Result<BackupPreferences, FinancialError> {
+    resolve_backup_location(preferences, &state.config.security_settings.path_access).await?;
     Ok(preferences.clone())
 }
 
+/// Directory backups go to: the configured location, or the app's backup directory
+async fn resolve_backup_location(
+    preferences: &BackupPreferences,
+    path_access: &PathAccessPolicy,
+) -> Result<PathBuf, FinancialError> {
+    let location = preferences.backup_location.trim();
+    if location.is_empty() {
+        return Ok(crate::utils::get_app_directories()?.backup_dir);
+    }
+    if !super::system::validate_path_security(location, path_access).await.unwrap_or(false) {
+        return Err(FinancialError::SecurityError("Backup location is not an allowed directory".to_string()));
+    }
+    Ok(PathBuf::from(location))
+}
+
+async fn backup_now_internal(
+    user_id: &str,
+    preferences: &BackupPreferences,
+    full: bool,
+    app: &AppHandle,
+    state: &State<'_, AppState>,
+) -> Result<BackupManifest, FinancialError> {
+    let location = resolve_backup_location(preferences, &state.config.security_settings.path_access).await?;
+
+    let mut base = if full { None } else { backup::latest_manifest(&location, user_id).await? };
+    if let Some(manifest) = &base {
+        let intact = backup::verify_backup(&backup::manifest_path(&location, manifest)).await?.valid;
+        if !intact {
+            tracing::warn!("Latest backup {} failed verification, taking a full backup", manifest.backup_file);
+            base = None;
+        }
+    }
+
+    let db_manager = &state.database_manager;
+    let snapshot = backup::snapshot(db_manager.pool(), user_id, base.as_ref().map(|b| b.created_at)).await?;
+
+    let manifest = if preferences.encryption_enabled {
+        let vault = get_vault(app.clone())
+            .await
+            .map_err(|e| FinancialError::SecurityError(format!("Vault unavailable: {}", e)))?;
+        backup::write_backup(&snapshot, &location, base.as_ref(), Some(&*vault as &dyn FieldCipher)).await?
+    } else {
+        backup::write_backup(&snapshot, &location, base.as_ref(), None).await?
+    };
+
+    tracing::info!(
+        "Wrote backup {} ({} bytes, incremental: {})",
+        manifest.backup_file,
+        manifest.size_bytes,
+        manifest.base_backup.is_some()
+    );
+    Ok(manifest)
+}
+
+async fn restore_backup_internal(
+    user_id: &str,
+    manifest_path: &std::path::Path,
+    app: &AppHandle,
+    state: &State<'_, AppState>,
+) -> Result<RestoreSummary, FinancialError> {
+    let rollback_dir = manifest_path
+        .parent()
+        .unwrap_or_else(|| std::path::Path::new("."))
+        .join("pre-restore");
+
+    // A locked vault only matters for encrypted backups, which then fail to decrypt
+    let vault = get_vault(app.clone()).await.ok();
+    let cipher = vault.as_deref().map(|vault| vault as &dyn FieldCipher);
+
+    let db_manager = &state.database_manager;
+    let summary = backup::restore_backup(db_manager.pool(), user_id, manifest_path, cipher, &rollback_dir).await?;
+
+    tracing::info!(
+        "Restored backup {} ({} backups applied); previous data saved as {}",
+        summary.restored_backup,
+        summary.backups_applied.len(),
+        summary.rollback.backup_file
+    );
+    Ok(summary)
+}
+
 async fn get_theme_settings_internal(
     user_id: &str,
     state: &State<'_, AppState>,
@@ -1031,6 +1289,37 @@ mod tests {
         }
     }
 
+    async fn session_for(user_id: &str) -> SessionGuard {
+        let session = SessionGuard::new();
+        session.sign_in(crate::security::AuthenticatedUser {
+            user_id: user_id.to_string(),
+            expires_at: Utc::now() + chrono::Duration::hours(1),
+            permissions: vec![],
+        }).await;
+        session
+    }
+
+    #[tokio::test]
+    async fn test_preferences_access_denied_for_other_user() {
+        let session = session_for("user-a").await;
+
+        assert_eq!(authorize_preferences_access(&session, "user-a", None).await.unwrap(), "user-a");
+
+        let denied = authorize_preferences_access(&session, "user-b", None).await;
+        assert!(denied.unwrap_err().starts_with("Access denied"));
+
+        // Submitted preferences must also belong to the signed-in user
+        let foreign = create_test_user_preferences("user-b");
+        let denied = authorize_preferences_access(&session, "user-a", Some(&foreign.user_id)).await;
+        assert_eq!(denied.unwrap_err(), "Access denied: preferences belong to another user");
+    }
+
+    #[tokio::test]
+    async fn test_preferences_access_requires_session() {
+        let session = SessionGuard::new();
+        assert!(authorize_preferences_access(&session, "user-a", None).await.is_err());
+    }
+
     #[test]
     fn test_validate_user_preferences_valid() {
         let preferences = create_test_user_preferences("test-user-123");
@@ -6,8 +6,13 @@ use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::RwLock;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, SubsecRound, Utc};
 use crate::AppState;
+use crate::financial::FinancialError;
+use crate::retention::PurgeReport;
+use crate::security::audit_chain::GENESIS_HASH;
+use crate::security::{verify_chain, AuditChain, ChainContent, ChainedEntry, PathAccessPolicy, StartupMode};
+use crate::storage::{DatabaseManager, SecurityAuditRecord, SecurityAuditRepository};
 use super::{CommandResponse, send_desktop_notification};
 
 // System monitoring state
@@ -54,6 +59,14 @@ pub async fn get_system_info(
     Ok(CommandResponse::success(info))
 }
 
+/// Whether the app started normally or read-only after its startup self-tests
+#[tauri::command]
+pub async fn get_startup_status(
+    state: State<'_, AppState>,
+) -> Result<CommandResponse<StartupMode>, tauri::Error> {
+    Ok(CommandResponse::success(state.startup.mode()))
+}
+
 /// Monitor system performance in real-time
 #[tauri::command]
 pub async fn monitor_performance(
@@ -82,7 +95,7 @@ pub async fn get_disk_usage(
     let target_path = path.as_deref().unwrap_or(".");
 
     // Validate path security
-    if !validate_path_security(target_path).await? {
+    if !validate_path_security(target_path, &state.config.security_settings.path_access).await? {
         return Ok(CommandResponse::error("Access denied: Invalid or restricted path"));
     }
 
@@ -207,7 +220,7 @@ pub async fn open_file_location(
     tracing::info!("Opening file location: {}", file_path);
 
     // Validate file path security
-    if !validate_path_security(&file_path).await? {
+    if !validate_path_security(&file_path, &state.config.security_settings.path_access).await? {
         return Ok(CommandResponse::error("Access denied: Invalid or restricted path"));
     }
 
@@ -236,7 +249,7 @@ pub async fn validate_file_permissions(
     tracing::info!("Validating file permissions for: {}", file_path);
 
     // Security validation first
-    if !validate_path_security(&file_path).await? {
+    if !validate_path_security(&file_path, &state.config.security_settings.path_access).await? {
         return Ok(CommandResponse::error("Access denied: Invalid or restricted path"));
     }
 
@@ -348,7 +361,7 @@ pub async fn monitor_file_system_changes(
     if enable {
         // Validate all paths for security
         for path in &paths {
-            if !validate_path_security(path).await? {
+            if !validate_path_security(path, &state.config.security_settings.path_access).await? {
                 return Ok(CommandResponse::error(format!("Access denied for path: {}", path)));
             }
         }
@@ -411,6 +424,27 @@ pub async fn log_security_events(
     Ok(CommandResponse::success(()))
 }
 
+/// Recompute the security audit hash chain and report the first corrupted entry
+///
+/// The chain is read back from the database, so entries changed or removed
+/// there are caught even though the running app never saw the change.
+#[tauri::command]
+pub async fn verify_security_audit_chain(
+    state: State<'_, AppState>,
+) -> Result<CommandResponse<AuditChainReport>, tauri::Error> {
+    tracing::info!("Verifying security audit chain");
+
+    let stored = match SecurityAuditRepository::new(&state.database_manager).find_all().await {
+        Ok(stored) => stored,
+        Err(e) => {
+            tracing::error!("Failed to load the security audit chain: {}", e);
+            return Ok(CommandResponse::error(format!("Failed to load the security audit chain: {}", e)));
+        }
+    };
+
+    Ok(CommandResponse::success(verify_stored_audit_chain(stored)))
+}
+
 // =============================================================================
 // DATA STRUCTURES
 // =============================================================================
@@ -564,6 +598,16 @@ pub struct IntegrityReport {
     pub timestamp: DateTime<Utc>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditChainReport {
+    pub valid: bool,
+    pub entries_checked: usize,
+    pub first_corrupted_index: Option<usize>,
+    pub error: Option<String>,
+    pub head_hash: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct SecurityEvent {
     pub event_type: SecurityEventType,
@@ -573,6 +617,45 @@ pub struct SecurityEvent {
     pub source: String,
 }
 
+impl ChainContent for SecurityEvent {
+    fn chain_content(&self) -> String {
+        security_event_content(
+            self.timestamp,
+            &format!("{:?}", self.event_type),
+            &format!("{:?}", self.severity),
+            &self.source,
+            &self.details,
+        )
+    }
+}
+
+impl ChainContent for SecurityAuditRecord {
+    fn chain_content(&self) -> String {
+        security_event_content(self.occurred_at, &self.event_type, &self.severity, &self.source, &self.details)
+    }
+}
+
+/// Hashed form of a security event, shared by live events and stored rows
+fn security_event_content(
+    timestamp: DateTime<Utc>,
+    event_type: &str,
+    severity: &str,
+    source: &str,
+    details: &str,
+) -> String {
+    // Length-prefix free-form fields so no two events share a representation
+    format!(
+        "{}|{}|{}|{}:{}|{}:{}",
+        timestamp.to_rfc3339_opts(chrono::SecondsFormat::Nanos, true),
+        event_type,
+        severity,
+        source.len(),
+        source,
+        details.len(),
+        details,
+    )
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SecurityEventType {
     SystemInfoAccess,
@@ -583,6 +666,7 @@ pub enum SecurityEventType {
     FileSystemAccess,
     IntegrityCheck,
     PermissionEscalation,
+    DataRetentionPurge,
     SuspiciousActivity,
 }
 
@@ -601,8 +685,10 @@ struct PerformanceMonitor {
 }
 
 struct SecurityMonitor {
-    events: Vec<SecurityEvent>,
+    events: AuditChain<SecurityEvent>,
     last_cleanup: DateTime<Utc>,
+    /// Database every new chain entry is written to, once attached
+    storage: Option<DatabaseManager>,
 }
 
 // =============================================================================
@@ -797,16 +883,110 @@ fn get_process_count() -> u32 {
 }
 
 // Security monitoring implementation
-async fn log_security_event(event: SecurityEvent) {
-    let monitor = SECURITY_MONITOR.get_or_init(|| async {
+async fn get_security_monitor() -> Arc<RwLock<SecurityMonitor>> {
+    SECURITY_MONITOR.get_or_init(|| async {
         Arc::new(RwLock::new(SecurityMonitor {
-            events: Vec::new(),
+            events: AuditChain::new(),
             last_cleanup: Utc::now(),
+            storage: None,
         }))
-    }).await;
+    }).await.clone()
+}
 
+/// Keep the security audit chain in `db` from now on
+///
+/// The in-memory chain is restarted after the last stored entry, and events
+/// logged before the database was available are appended to it and stored.
+/// Attaching again does nothing.
+pub async fn attach_security_audit_storage(db: DatabaseManager) -> Result<(), FinancialError> {
+    let monitor = get_security_monitor().await;
     let mut monitor = monitor.write().await;
-    monitor.events.push(event.clone());
+    if monitor.storage.is_some() {
+        return Ok(());
+    }
+
+    let pending: Vec<SecurityEvent> = monitor.events.entries().iter().map(|e| e.record.clone()).collect();
+    monitor.events = match SecurityAuditRepository::new(&db).find_latest().await? {
+        Some(latest) => AuditChain::resume(latest.sequence as u64 + 1, latest.hash),
+        None => AuditChain::new(),
+    };
+    monitor.storage = Some(db);
+
+    for event in pending {
+        append_security_event(&mut monitor, event).await;
+    }
+    Ok(())
+}
+
+/// Append `event` to the chain and store the new entry
+///
+/// A failed write is logged; the gap it leaves in storage is then reported
+/// by `verify_security_audit_chain`.
+async fn append_security_event(monitor: &mut SecurityMonitor, event: SecurityEvent) {
+    let entry = audit_record(monitor.events.append(event));
+    if let Some(db) = &monitor.storage {
+        if let Err(e) = SecurityAuditRepository::new(db).append(&entry).await {
+            tracing::error!("Failed to store security audit entry {}: {}", entry.sequence, e);
+        }
+    }
+}
+
+/// Stored form of a chain entry
+fn audit_record(entry: &ChainedEntry<SecurityEvent>) -> SecurityAuditRecord {
+    SecurityAuditRecord {
+        sequence: entry.sequence as i64,
+        event_type: format!("{:?}", entry.record.event_type),
+        severity: format!("{:?}", entry.record.severity),
+        occurred_at: entry.record.timestamp,
+        source: entry.record.source.clone(),
+        details: entry.record.details.clone(),
+        previous_hash: entry.previous_hash.clone(),
+        hash: entry.hash.clone(),
+    }
+}
+
+/// Recompute the stored chain from the genesis hash
+fn verify_stored_audit_chain(stored: Vec<SecurityAuditRecord>) -> AuditChainReport {
+    let entries: Vec<ChainedEntry<SecurityAuditRecord>> = stored
+        .into_iter()
+        .map(|record| ChainedEntry {
+            sequence: record.sequence as u64,
+            previous_hash: record.previous_hash.clone(),
+            hash: record.hash.clone(),
+            record,
+        })
+        .collect();
+    let head_hash = entries.last().map_or(GENESIS_HASH, |e| e.hash.as_str()).to_string();
+
+    match verify_chain(&entries, GENESIS_HASH, 0) {
+        Ok(()) => AuditChainReport {
+            valid: true,
+            entries_checked: entries.len(),
+            first_corrupted_index: None,
+            error: None,
+            head_hash,
+        },
+        Err(e) => {
+            tracing::error!("Security audit chain verification failed: {}", e);
+            AuditChainReport {
+                valid: false,
+                entries_checked: entries.len(),
+                first_corrupted_index: Some(e.first_corrupted_index()),
+                error: Some(e.to_string()),
+                head_hash,
+            }
+        }
+    }
+}
+
+async fn log_security_event(mut event: SecurityEvent) {
+    // Storage keeps microseconds, so hash only what it can hold
+    event.timestamp = event.timestamp.trunc_subsecs(6);
+
+    let monitor = get_security_monitor().await;
+
+    let mut monitor = monitor.write().await;
+    append_security_event(&mut monitor, event.clone()).await;
 
     // Log to tracing
     match event.severity {
@@ -816,10 +996,23 @@ async fn log_security_event(event: SecurityEvent) {
         SecuritySeverity::Critical => tracing::error!("CRITICAL {:?}: {}", event.event_type, event.details),
     }
 
-    // Clean up old events (keep last 1000)
-    if monitor.events.len() > 1000 {
-        monitor.events.drain(0..monitor.events.len() - 1000);
-    }
+    // Clean up old events (keep last 1000); the chain anchors on the last pruned hash
+    monitor.events.prune_to(1000);
+}
+
+/// Record a purge of deleted transactions in the security audit chain
+pub async fn record_retention_purge(report: &PurgeReport, source: &str) {
+    log_security_event(SecurityEvent {
+        event_type: SecurityEventType::DataRetentionPurge,
+        timestamp: Utc::now(),
+        details: format!(
+            "Purged {} transactions soft-deleted before {}",
+            report.purged,
+            report.cutoff.to_rfc3339()
+        ),
+        severity: SecuritySeverity::Low,
+        source: source.to_string(),
+    }).await;
 }
 
 fn parse_security_event_type(event_type: &str) -> SecurityEventType {
@@ -832,6 +1025,7 @@ fn parse_security_event_type(event_type: &str) -> SecurityEventType {
         "file_system_access" => SecurityEventType::FileSystemAccess,
         "integrity_check" => SecurityEventType::IntegrityCheck,
         "permission_escalation" => SecurityEventType::PermissionEscalation,
+        "data_retention_purge" => SecurityEventType::DataRetentionPurge,
         _ => SecurityEventType::SuspiciousActivity,
     }
 }
@@ -900,29 +1094,9 @@ async fn install_application_update(
 }
 
 // File system operations
-async fn validate_path_security(path: &str) -> Result<bool, tauri::Error> {
-    let path = Path::new(path);
-
-    // Check for path traversal attempts
-    if path.to_string_lossy().contains("..") {
-        return Ok(false);
-    }
-
-    // Check for absolute paths to system directories
-    let restricted_paths = [
-        "/etc", "/sys", "/proc", "/boot",
-        "C:\\Windows", "C:\\System32",
-        "/System", "/Library/System",
-    ];
-
-    let path_str = path.to_string_lossy().to_lowercase();
-    for restricted in &restricted_paths {
-        if path_str.starts_with(&restricted.to_lowercase()) {
-            return Ok(false);
-        }
-    }
-
-    Ok(true)
+/// Whether `policy` lets file commands use `path`, once symlinks and `..` are resolved
+pub(crate) async fn validate_path_security(path: &str, policy: &PathAccessPolicy) -> Result<bool, tauri::Error> {
+    Ok(policy.allows(Path::new(path)))
 }
 
 async fn get_disk_usage_info(path: &str) -> Result<DiskUsage, Box<dyn std::error::Error>> {
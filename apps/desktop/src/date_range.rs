@@ -0,0 +1,169 @@
+// Date Ranges for Atlas Desktop
+// Turns named periods and ISO intervals into validated ranges on the user's calendar
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use crate::spending::{local_date, local_day_start, SpendingPeriod, SpendingRange};
+
+/// Inclusive range of instants, e.g. for filtering transactions
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DateRange {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl DateRange {
+    pub fn new(start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Self, String> {
+        if end < start {
+            return Err(format!("Range end {} is before start {}", end, start));
+        }
+        Ok(Self { start, end })
+    }
+
+    /// Whole local calendar days in `timezone`
+    ///
+    /// The range ends on the last microsecond of `days.end`, the finest
+    /// precision stored timestamps have.
+    pub fn from_days(days: SpendingRange, timezone: Tz) -> Self {
+        Self {
+            start: days.start_instant(timezone),
+            end: days.end_instant(timezone) - Duration::microseconds(1),
+        }
+    }
+
+    /// Parse a period spec for a user in `timezone`
+    ///
+    /// Accepts `today`, `last_<n>_days`, `this_week`/`month`/`quarter`/`year`
+    /// (also as bare `week`, `month`, ...), `last_week`/`month`/`quarter`/`year`,
+    /// `ytd`, and explicit ISO 8601 intervals of dates (`2024-01-01/2024-03-31`,
+    /// both days included) or RFC 3339 instants. Named periods cover whole
+    /// local days; `ytd` and `last_<n>_days` include today.
+    pub fn parse(spec: &str, timezone: Tz) -> Result<Self, String> {
+        Self::parse_at(spec, timezone, Utc::now())
+    }
+
+    /// [`parse`](Self::parse) with named periods relative to `now`
+    pub fn parse_at(spec: &str, timezone: Tz, now: DateTime<Utc>) -> Result<Self, String> {
+        let spec = spec.trim();
+        if let Some((start, end)) = spec.split_once('/') {
+            return Self::parse_interval(start.trim(), end.trim(), timezone);
+        }
+
+        let today = local_date(now, timezone);
+        let name = spec.to_lowercase();
+        let days = match name.as_str() {
+            "today" => SpendingRange::new(today, today)?,
+            "ytd" | "year_to_date" => SpendingRange::new(
+                SpendingPeriod::Year.range_containing(now, timezone).start,
+                today,
+            )?,
+            _ => {
+                if let Some(count) = name.strip_prefix("last_").and_then(|rest| rest.strip_suffix("_days")) {
+                    let count: i64 = count
+                        .parse()
+                        .ok()
+                        .filter(|&count| count > 0)
+                        .ok_or_else(|| format!("Invalid day count in {}", spec))?;
+                    SpendingRange::new(today - Duration::days(count - 1), today)?
+                } else if let Some(period) = name.strip_prefix("last_") {
+                    let period: SpendingPeriod = period.parse().map_err(|_| format!("Unknown date range: {}", spec))?;
+                    let current = period.range_containing(now, timezone);
+                    previous_period(period, current.start, timezone)
+                } else {
+                    let period: SpendingPeriod = name
+                        .strip_prefix("this_")
+                        .unwrap_or(&name)
+                        .parse()
+                        .map_err(|_| format!("Unknown date range: {}", spec))?;
+                    period.range_containing(now, timezone)
+                }
+            }
+        };
+        Ok(Self::from_days(days, timezone))
+    }
+
+    fn parse_interval(start: &str, end: &str, timezone: Tz) -> Result<Self, String> {
+        if let (Ok(start), Ok(end)) = (start.parse::<NaiveDate>(), end.parse::<NaiveDate>()) {
+            return Ok(Self::from_days(SpendingRange::new(start, end)?, timezone));
+        }
+        match (DateTime::parse_from_rfc3339(start), DateTime::parse_from_rfc3339(end)) {
+            (Ok(start), Ok(end)) => Self::new(start.with_timezone(&Utc), end.with_timezone(&Utc)),
+            _ => Err("Range bounds must both be YYYY-MM-DD dates or RFC 3339 timestamps".to_string()),
+        }
+    }
+
+    /// Local calendar days the range touches in `timezone`
+    pub fn local_days(&self, timezone: Tz) -> SpendingRange {
+        SpendingRange {
+            start: local_date(self.start, timezone),
+            end: local_date(self.end, timezone),
+        }
+    }
+}
+
+/// The `period` before the one starting on `current_start`
+fn previous_period(period: SpendingPeriod, current_start: NaiveDate, timezone: Tz) -> SpendingRange {
+    let day_before = local_day_start(current_start - Duration::days(1), timezone);
+    period.range_containing(day_before, timezone)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn days(spec: &str) -> Result<SpendingRange, String> {
+        let timezone: Tz = "America/New_York".parse().unwrap();
+        // 2024-05-15 21:30 in New York, already the 16th in UTC
+        let now = Utc.with_ymd_and_hms(2024, 5, 16, 1, 30, 0).unwrap();
+        DateRange::parse_at(spec, timezone, now).map(|range| range.local_days(timezone))
+    }
+
+    #[test]
+    fn test_named_periods_follow_the_local_calendar() {
+        let cases = [
+            ("today", date(2024, 5, 15), date(2024, 5, 15)),
+            ("last_30_days", date(2024, 4, 16), date(2024, 5, 15)),
+            ("this_month", date(2024, 5, 1), date(2024, 5, 31)),
+            ("month", date(2024, 5, 1), date(2024, 5, 31)),
+            ("last_month", date(2024, 4, 1), date(2024, 4, 30)),
+            ("this_week", date(2024, 5, 13), date(2024, 5, 19)),
+            ("last_week", date(2024, 5, 6), date(2024, 5, 12)),
+            ("this_quarter", date(2024, 4, 1), date(2024, 6, 30)),
+            ("last_quarter", date(2024, 1, 1), date(2024, 3, 31)),
+            ("ytd", date(2024, 1, 1), date(2024, 5, 15)),
+            ("last_year", date(2023, 1, 1), date(2023, 12, 31)),
+            ("2024-02-01/2024-02-29", date(2024, 2, 1), date(2024, 2, 29)),
+        ];
+        for (spec, start, end) in cases {
+            assert_eq!(days(spec), Ok(SpendingRange { start, end }), "{}", spec);
+        }
+
+        // Named periods start at local midnight and end just before the next one
+        let timezone: Tz = "America/New_York".parse().unwrap();
+        let now = Utc.with_ymd_and_hms(2024, 5, 16, 1, 30, 0).unwrap();
+        let month = DateRange::parse_at("this_month", timezone, now).unwrap();
+        assert_eq!(month.start, Utc.with_ymd_and_hms(2024, 5, 1, 4, 0, 0).unwrap());
+        assert_eq!(month.end + Duration::microseconds(1), Utc.with_ymd_and_hms(2024, 6, 1, 4, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_inverted_or_unknown_ranges_are_rejected() {
+        assert_eq!(
+            days("2024-03-31/2024-03-01"),
+            Err("Range end 2024-03-01 is before start 2024-03-31".to_string())
+        );
+        assert!(days("2024-03-02T00:00:00Z/2024-03-01T00:00:00Z").is_err());
+        assert!(days("2024-03-01T00:00:00Z/2024-03-02T00:00:00-05:00").is_ok());
+        assert!(days("2024-03-01/soon").is_err());
+        assert_eq!(days("last_0_days"), Err("Invalid day count in last_0_days".to_string()));
+        assert_eq!(days("fortnight"), Err("Unknown date range: fortnight".to_string()));
+    }
+}
@@ -0,0 +1,242 @@
+// Duplicate Transaction Detection for Atlas Desktop
+// Groups likely duplicates created by imports and manual entry for user review
+
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Minimum normalized description similarity for two transactions to match
+pub const DESCRIPTION_SIMILARITY_THRESHOLD: f64 = 0.85;
+
+/// Transaction fields considered when looking for duplicates
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateCandidate {
+    pub id: String,
+    pub amount: Decimal,
+    pub description: String,
+    pub transaction_date: DateTime<Utc>,
+}
+
+/// Group of transactions that appear to record the same movement of money
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateCluster {
+    /// Transaction IDs ordered by date, earliest first
+    pub transaction_ids: Vec<String>,
+    pub amount: Decimal,
+    pub description: String,
+    pub earliest_date: DateTime<Utc>,
+    pub latest_date: DateTime<Utc>,
+}
+
+/// Outcome of resolving a cluster down to a single transaction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergePlan {
+    pub keep_id: String,
+    pub remove_ids: Vec<String>,
+}
+
+/// Normalize a description so case, punctuation and spacing are ignored
+fn normalize_description(description: &str) -> String {
+    description
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+/// Similarity of two descriptions in `[0, 1]` after normalization
+pub fn description_similarity(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = normalize_description(a).chars().collect();
+    let b: Vec<char> = normalize_description(b).chars().collect();
+
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+
+    1.0 - levenshtein(&a, &b) as f64 / longest as f64
+}
+
+fn find_root(parents: &mut [usize], mut index: usize) -> usize {
+    while parents[index] != index {
+        parents[index] = parents[parents[index]];
+        index = parents[index];
+    }
+    index
+}
+
+/// Cluster transactions with equal amounts, similar descriptions and dates
+/// no more than `window_days` apart
+///
+/// Matching is transitive: if A matches B and B matches C, all three land in
+/// the same cluster. Only clusters of two or more transactions are returned.
+pub fn find_duplicate_clusters(candidates: &[DuplicateCandidate], window_days: u32) -> Vec<DuplicateCluster> {
+    let window = Duration::days(window_days as i64);
+
+    let mut by_amount: HashMap<Decimal, Vec<&DuplicateCandidate>> = HashMap::new();
+    for candidate in candidates {
+        by_amount.entry(candidate.amount.normalize()).or_default().push(candidate);
+    }
+
+    let mut clusters = Vec::new();
+    for mut group in by_amount.into_values() {
+        if group.len() < 2 {
+            continue;
+        }
+        group.sort_by_key(|c| c.transaction_date);
+
+        let mut parents: Vec<usize> = (0..group.len()).collect();
+        for i in 0..group.len() {
+            for j in (i + 1)..group.len() {
+                // Sorted by date, so nothing further along can be in the window
+                if group[j].transaction_date - group[i].transaction_date > window {
+                    break;
+                }
+                if description_similarity(&group[i].description, &group[j].description)
+                    >= DESCRIPTION_SIMILARITY_THRESHOLD
+                {
+                    let (root_i, root_j) = (find_root(&mut parents, i), find_root(&mut parents, j));
+                    parents[root_j] = root_i;
+                }
+            }
+        }
+
+        let mut members: HashMap<usize, Vec<&DuplicateCandidate>> = HashMap::new();
+        for (index, candidate) in group.iter().enumerate() {
+            let root = find_root(&mut parents, index);
+            members.entry(root).or_default().push(*candidate);
+        }
+
+        for cluster in members.into_values().filter(|m| m.len() > 1) {
+            let first = cluster[0];
+            let last = cluster[cluster.len() - 1];
+            clusters.push(DuplicateCluster {
+                transaction_ids: cluster.iter().map(|c| c.id.clone()).collect(),
+                amount: first.amount,
+                description: first.description.clone(),
+                earliest_date: first.transaction_date,
+                latest_date: last.transaction_date,
+            });
+        }
+    }
+
+    // Stable output regardless of hash map ordering
+    clusters.sort_by(|a, b| {
+        a.earliest_date
+            .cmp(&b.earliest_date)
+            .then_with(|| a.transaction_ids.cmp(&b.transaction_ids))
+    });
+    clusters
+}
+
+/// Decide which transactions to soft-delete when keeping `keep_id`
+pub fn plan_merge(keep_id: &str, transaction_ids: &[String]) -> Result<MergePlan, String> {
+    let mut seen = std::collections::HashSet::new();
+    let remove_ids: Vec<String> = transaction_ids
+        .iter()
+        .filter(|id| id.as_str() != keep_id && seen.insert(id.as_str()))
+        .cloned()
+        .collect();
+
+    if remove_ids.is_empty() {
+        return Err("At least one transaction other than the kept one is required".to_string());
+    }
+
+    Ok(MergePlan {
+        keep_id: keep_id.to_string(),
+        remove_ids,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    fn candidate(id: &str, amount: Decimal, description: &str, day: u32) -> DuplicateCandidate {
+        DuplicateCandidate {
+            id: id.to_string(),
+            amount,
+            description: description.to_string(),
+            transaction_date: Utc.with_ymd_and_hms(2024, 5, day, 12, 0, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_description_similarity_is_normalized() {
+        assert_eq!(description_similarity("AMAZON.COM  Purchase", "amazon com purchase"), 1.0);
+        assert!(description_similarity("Netflix Subscription", "NETFLIX SUBSCRIPTON") >= DESCRIPTION_SIMILARITY_THRESHOLD);
+        assert!(description_similarity("Grocery Store", "Gas Station") < DESCRIPTION_SIMILARITY_THRESHOLD);
+    }
+
+    #[test]
+    fn test_duplicates_grouped_within_window() {
+        let candidates = vec![
+            candidate("a", dec!(-54.20), "WHOLE FOODS #123", 1),
+            candidate("b", dec!(-54.2), "Whole Foods 123", 2),
+            candidate("c", dec!(-54.20), "Whole Foods #123", 20),
+            candidate("d", dec!(-12.00), "Whole Foods #123", 2),
+            candidate("e", dec!(-54.20), "Shell Gas", 1),
+        ];
+
+        let clusters = find_duplicate_clusters(&candidates, 3);
+
+        // "c" is outside the window, "d" has another amount, "e" another payee
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].transaction_ids, vec!["a".to_string(), "b".to_string()]);
+        assert_eq!(clusters[0].earliest_date, candidates[0].transaction_date);
+        assert_eq!(clusters[0].latest_date, candidates[1].transaction_date);
+    }
+
+    #[test]
+    fn test_merge_keeps_single_record() {
+        let mut candidates = vec![
+            candidate("a", dec!(-9.99), "Spotify Premium", 3),
+            candidate("b", dec!(-9.99), "SPOTIFY PREMIUM", 3),
+            candidate("c", dec!(-9.99), "Spotify  Premium.", 4),
+        ];
+
+        let clusters = find_duplicate_clusters(&candidates, 2);
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].transaction_ids.len(), 3);
+
+        let plan = plan_merge("b", &clusters[0].transaction_ids).unwrap();
+        assert_eq!(plan.keep_id, "b");
+        assert_eq!(plan.remove_ids, vec!["a".to_string(), "c".to_string()]);
+
+        candidates.retain(|c| !plan.remove_ids.contains(&c.id));
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].id, "b");
+        assert!(find_duplicate_clusters(&candidates, 2).is_empty());
+    }
+
+    #[test]
+    fn test_plan_merge_requires_something_to_remove() {
+        assert!(plan_merge("a", &["a".to_string()]).is_err());
+        assert!(plan_merge("a", &[]).is_err());
+    }
+}
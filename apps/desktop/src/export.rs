@@ -0,0 +1,432 @@
+// Streaming Transaction Export for Atlas Desktop
+// Writes CSV/JSON one page at a time so memory stays bounded on large histories
+
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Serialize, Serializer};
+use std::future::Future;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+use crate::financial::FinancialError;
+use crate::storage::{TransactionCursor, TransactionRecord, TransactionType};
+
+/// Rows requested from the repository per page
+pub const EXPORT_PAGE_SIZE: i32 = 500;
+
+/// Output formats that can be streamed row by row
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamFormat {
+    Csv,
+    Json,
+}
+
+/// A transaction field that can appear in an export
+///
+/// Names match the CSV header, so `"account_id"` selects the `account_id` column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportColumn {
+    Id,
+    AccountId,
+    TransactionDate,
+    Amount,
+    Description,
+    TransactionType,
+    Merchant,
+    Category,
+    Subcategory,
+    Tags,
+}
+
+impl ExportColumn {
+    /// Every column, in the default export order
+    pub const ALL: [ExportColumn; 10] = [
+        ExportColumn::Id,
+        ExportColumn::AccountId,
+        ExportColumn::TransactionDate,
+        ExportColumn::Amount,
+        ExportColumn::Description,
+        ExportColumn::TransactionType,
+        ExportColumn::Merchant,
+        ExportColumn::Category,
+        ExportColumn::Subcategory,
+        ExportColumn::Tags,
+    ];
+
+    /// CSV header for this column
+    pub fn header(self) -> &'static str {
+        match self {
+            ExportColumn::Id => "id",
+            ExportColumn::AccountId => "account_id",
+            ExportColumn::TransactionDate => "transaction_date",
+            ExportColumn::Amount => "amount",
+            ExportColumn::Description => "description",
+            ExportColumn::TransactionType => "transaction_type",
+            ExportColumn::Merchant => "merchant",
+            ExportColumn::Category => "category",
+            ExportColumn::Subcategory => "subcategory",
+            ExportColumn::Tags => "tags",
+        }
+    }
+
+    /// Key for this column in JSON exports
+    fn json_key(self) -> &'static str {
+        match self {
+            ExportColumn::Id => "id",
+            ExportColumn::AccountId => "accountId",
+            ExportColumn::TransactionDate => "transactionDate",
+            ExportColumn::Amount => "amount",
+            ExportColumn::Description => "description",
+            ExportColumn::TransactionType => "transactionType",
+            ExportColumn::Merchant => "merchant",
+            ExportColumn::Category => "category",
+            ExportColumn::Subcategory => "subcategory",
+            ExportColumn::Tags => "tags",
+        }
+    }
+}
+
+/// Columns to export, in output order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportColumns {
+    columns: Vec<ExportColumn>,
+}
+
+impl ExportColumns {
+    /// Export exactly `columns`, in that order
+    pub fn new(columns: Vec<ExportColumn>) -> Result<Self, FinancialError> {
+        if columns.is_empty() {
+            return Err(FinancialError::ValidationError("Select at least one export column".to_string()));
+        }
+        if let Some(duplicate) = columns.iter().enumerate().find_map(|(i, c)| columns[..i].contains(c).then_some(c)) {
+            return Err(FinancialError::ValidationError(format!(
+                "Export column '{}' is selected more than once",
+                duplicate.header()
+            )));
+        }
+        Ok(Self { columns })
+    }
+
+    /// The full column set, leaving out category and tag columns when not included
+    pub fn with_optional(categories: bool, tags: bool) -> Self {
+        let columns = ExportColumn::ALL
+            .into_iter()
+            .filter(|column| match column {
+                ExportColumn::Category | ExportColumn::Subcategory => categories,
+                ExportColumn::Tags => tags,
+                _ => true,
+            })
+            .collect();
+        Self { columns }
+    }
+
+    pub fn as_slice(&self) -> &[ExportColumn] {
+        &self.columns
+    }
+}
+
+/// Totals reported once an export has been written
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExportSummary {
+    pub rows_written: u64,
+    pub pages_fetched: u64,
+    /// Largest number of rows held in memory at once
+    pub max_page_rows: usize,
+}
+
+/// One exported transaction, limited to the selected columns
+struct ExportRow<'r> {
+    record: &'r TransactionRecord,
+    columns: &'r [ExportColumn],
+}
+
+fn transaction_type_label(transaction_type: TransactionType) -> &'static str {
+    match transaction_type {
+        TransactionType::Debit => "debit",
+        TransactionType::Credit => "credit",
+        TransactionType::Transfer => "transfer",
+        TransactionType::Fee => "fee",
+        TransactionType::Interest => "interest",
+        TransactionType::Dividend => "dividend",
+        TransactionType::Withdrawal => "withdrawal",
+        TransactionType::Deposit => "deposit",
+    }
+}
+
+impl ExportRow<'_> {
+    fn csv_value(&self, column: ExportColumn) -> String {
+        let record = self.record;
+        match column {
+            ExportColumn::Id => record.id.clone(),
+            ExportColumn::AccountId => record.account_id.clone(),
+            ExportColumn::TransactionDate => record.transaction_date.to_rfc3339(),
+            ExportColumn::Amount => record.amount.to_string(),
+            ExportColumn::Description => record.description.clone(),
+            ExportColumn::TransactionType => transaction_type_label(record.transaction_type).to_string(),
+            ExportColumn::Merchant => record.merchant.clone().unwrap_or_default(),
+            ExportColumn::Category => record.category.clone().unwrap_or_default(),
+            ExportColumn::Subcategory => record.subcategory.clone().unwrap_or_default(),
+            ExportColumn::Tags => record.tags.join(";"),
+        }
+    }
+
+    fn json_value(&self, column: ExportColumn) -> serde_json::Value {
+        let record = self.record;
+        match column {
+            ExportColumn::Merchant => record.merchant.clone().into(),
+            ExportColumn::Category => record.category.clone().into(),
+            ExportColumn::Subcategory => record.subcategory.clone().into(),
+            ExportColumn::Tags => record.tags.clone().into(),
+            other => self.csv_value(other).into(),
+        }
+    }
+}
+
+impl Serialize for ExportRow<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.columns.len()))?;
+        for &column in self.columns {
+            map.serialize_entry(column.json_key(), &self.json_value(column))?;
+        }
+        map.end()
+    }
+}
+
+/// Quote a CSV field when it contains a delimiter, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn csv_header(columns: &ExportColumns) -> String {
+    let header: Vec<&str> = columns.as_slice().iter().map(|column| column.header()).collect();
+    header.join(",") + "\n"
+}
+
+fn csv_line(row: &ExportRow<'_>) -> String {
+    let fields: Vec<String> = row.columns.iter().map(|&column| csv_field(&row.csv_value(column))).collect();
+    fields.join(",") + "\n"
+}
+
+fn io_error(e: std::io::Error) -> FinancialError {
+    FinancialError::IoError(format!("Failed to write export: {}", e))
+}
+
+/// Stream every page produced by `fetch_page` into `writer`
+///
+/// `fetch_page` receives the cursor after the last row written (or `None` for
+/// the first page) and returns the next page; an empty or short page ends the
+/// export. Only one page is held in memory at a time.
+pub async fn stream_transactions<W, F, Fut>(
+    writer: &mut W,
+    format: StreamFormat,
+    columns: &ExportColumns,
+    page_size: i32,
+    mut fetch_page: F,
+) -> Result<ExportSummary, FinancialError>
+where
+    W: AsyncWrite + Unpin,
+    F: FnMut(Option<TransactionCursor>) -> Fut,
+    Fut: Future<Output = Result<Vec<TransactionRecord>, FinancialError>>,
+{
+    let mut summary = ExportSummary::default();
+    let mut cursor = None;
+
+    match format {
+        StreamFormat::Csv => writer.write_all(csv_header(columns).as_bytes()).await.map_err(io_error)?,
+        StreamFormat::Json => writer.write_all(b"[").await.map_err(io_error)?,
+    }
+
+    loop {
+        let page = fetch_page(cursor.take()).await?;
+        summary.pages_fetched += 1;
+        summary.max_page_rows = summary.max_page_rows.max(page.len());
+
+        for record in &page {
+            let row = ExportRow { record, columns: columns.as_slice() };
+            let bytes = match format {
+                StreamFormat::Csv => csv_line(&row).into_bytes(),
+                StreamFormat::Json => {
+                    let mut bytes = if summary.rows_written == 0 { Vec::new() } else { b",".to_vec() };
+                    serde_json::to_writer(&mut bytes, &row)
+                        .map_err(|e| FinancialError::ParseError(format!("Failed to serialize transaction: {}", e)))?;
+                    bytes
+                }
+            };
+            writer.write_all(&bytes).await.map_err(io_error)?;
+            summary.rows_written += 1;
+        }
+
+        match page.last() {
+            Some(last) if page.len() >= page_size as usize => cursor = Some(TransactionCursor::after(last)),
+            _ => break,
+        }
+    }
+
+    if format == StreamFormat::Json {
+        writer.write_all(b"]").await.map_err(io_error)?;
+    }
+    writer.flush().await.map_err(io_error)?;
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone, Utc};
+    use rust_decimal::Decimal;
+
+    fn synthetic_record(index: usize) -> TransactionRecord {
+        let date = Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap() + Duration::minutes(index as i64);
+        TransactionRecord {
+            id: format!("{:08}", index),
+            user_id: "user".to_string(),
+            account_id: "account".to_string(),
+            amount: Decimal::new(-(index as i64 % 10_000), 2),
+            description: format!("Purchase {}", index),
+            category: Some("Shopping".to_string()),
+            subcategory: None,
+            transaction_date: date,
+            created_at: date,
+            updated_at: date,
+            transaction_type: TransactionType::Debit,
+            merchant: Some("Store, Inc.".to_string()),
+            location: None,
+            is_recurring: false,
+            tags: vec!["a".to_string(), "b".to_string()],
+            notes: None,
+            ml_confidence: None,
+            is_active: true,
+            encryption_generation: None,
+        }
+    }
+
+    /// Serve pages of a synthetic dataset by keyset cursor, generating rows lazily
+    fn synthetic_pages(
+        total: usize,
+        page_size: i32,
+    ) -> impl FnMut(Option<TransactionCursor>) -> std::future::Ready<Result<Vec<TransactionRecord>, FinancialError>> {
+        move |cursor| {
+            let start = cursor.map_or(0, |c| c.id.parse::<usize>().unwrap() + 1);
+            let end = (start + page_size as usize).min(total);
+            std::future::ready(Ok((start..end).map(synthetic_record).collect()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_large_export_streams_in_bounded_pages() {
+        let total = 120_000;
+        let mut sink = tokio::io::sink();
+
+        let summary = stream_transactions(
+            &mut sink,
+            StreamFormat::Csv,
+            &ExportColumns::with_optional(true, true),
+            EXPORT_PAGE_SIZE,
+            synthetic_pages(total, EXPORT_PAGE_SIZE),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(summary.rows_written, total as u64);
+        assert_eq!(summary.pages_fetched, (total / EXPORT_PAGE_SIZE as usize) as u64 + 1);
+        assert_eq!(summary.max_page_rows, EXPORT_PAGE_SIZE as usize);
+    }
+
+    #[tokio::test]
+    async fn test_csv_export_escapes_and_selects_columns() {
+        let mut output = Vec::new();
+        stream_transactions(
+            &mut output,
+            StreamFormat::Csv,
+            &ExportColumns::with_optional(true, false),
+            2,
+            synthetic_pages(3, 2),
+        )
+        .await
+        .unwrap();
+
+        let csv = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(
+            lines[0],
+            "id,account_id,transaction_date,amount,description,transaction_type,merchant,category,subcategory"
+        );
+        assert!(lines[1].starts_with("00000000,account,2020-01-01T00:00:00+00:00,0.00,Purchase 0,debit,\"Store, Inc.\",Shopping,"));
+        assert!(lines[3].starts_with("00000002,"));
+    }
+
+    #[tokio::test]
+    async fn test_custom_columns_set_header_and_field_order() {
+        let columns: Vec<ExportColumn> =
+            serde_json::from_value(serde_json::json!(["amount", "transaction_date", "merchant", "tags"])).unwrap();
+        let columns = ExportColumns::new(columns).unwrap();
+
+        let mut output = Vec::new();
+        stream_transactions(&mut output, StreamFormat::Csv, &columns, 2, synthetic_pages(2, 2))
+            .await
+            .unwrap();
+        let csv = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "amount,transaction_date,merchant,tags");
+        assert_eq!(lines[2], "-0.01,2020-01-01T00:01:00+00:00,\"Store, Inc.\",a;b");
+
+        let mut output = Vec::new();
+        stream_transactions(&mut output, StreamFormat::Json, &columns, 2, synthetic_pages(1, 2))
+            .await
+            .unwrap();
+        let json = String::from_utf8(output).unwrap();
+        assert!(json.starts_with("[{\"amount\":\"0.00\",\"transactionDate\":"));
+        assert!(!json.contains("\"id\""));
+    }
+
+    #[test]
+    fn test_unknown_duplicate_or_empty_columns_are_rejected() {
+        assert!(serde_json::from_value::<Vec<ExportColumn>>(serde_json::json!(["amount", "balance"])).is_err());
+        assert!(ExportColumns::new(vec![ExportColumn::Amount, ExportColumn::Amount]).is_err());
+        assert!(ExportColumns::new(Vec::new()).is_err());
+        assert_eq!(ExportColumns::with_optional(true, true).as_slice(), &ExportColumn::ALL);
+    }
+
+    #[tokio::test]
+    async fn test_json_export_is_a_valid_array() {
+        let mut output = Vec::new();
+        let summary = stream_transactions(
+            &mut output,
+            StreamFormat::Json,
+            &ExportColumns::with_optional(false, true),
+            2,
+            synthetic_pages(5, 2),
+        )
+        .await
+        .unwrap();
+        assert_eq!(summary.pages_fetched, 3);
+
+        let rows: Vec<serde_json::Value> = serde_json::from_slice(&output).unwrap();
+        assert_eq!(rows.len(), 5);
+        assert_eq!(rows[4]["id"], "00000004");
+        assert_eq!(rows[0]["tags"], serde_json::json!(["a", "b"]));
+        assert!(rows[0].get("category").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_empty_export() {
+        let mut output = Vec::new();
+        let summary = stream_transactions(
+            &mut output,
+            StreamFormat::Json,
+            &ExportColumns::with_optional(true, true),
+            EXPORT_PAGE_SIZE,
+            synthetic_pages(0, EXPORT_PAGE_SIZE),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(summary.rows_written, 0);
+        assert_eq!(String::from_utf8(output).unwrap(), "[]");
+    }
+}
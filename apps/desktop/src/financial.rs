@@ -2,7 +2,7 @@
 // MIGRATED: Now uses Rust Financial Engine as primary calculation service
 // Maintains compatibility while routing calculations through shared engine
 
-use rust_decimal::Decimal;
+use rust_decimal::{Decimal, RoundingStrategy};
 use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
 use std::fmt;
@@ -27,29 +27,14 @@ pub struct FinancialAmount {
     money: Money,
     /// Legacy compatibility fields
     amount: Decimal,
-    currency: String,
+    currency: Currency,
 }
 
 impl FinancialAmount {
     /// Create a new FinancialAmount using Rust Financial Engine
-    pub fn new(amount: Decimal, currency: String) -> Result<Self, FinancialError> {
-        // Convert string currency to enum
-        let currency_enum = match currency.as_str() {
-            "USD" => Currency::USD,
-            "EUR" => Currency::EUR,
-            "GBP" => Currency::GBP,
-            "CAD" => Currency::CAD,
-            "AUD" => Currency::AUD,
-            "JPY" => Currency::JPY,
-            "CHF" => Currency::CHF,
-            "CNY" => Currency::CNY,
-            _ => return Err(FinancialError::CurrencyError(
-                format!("Unsupported currency: {}", currency)
-            )),
-        };
-
+    pub fn new(amount: Decimal, currency: Currency) -> Result<Self, FinancialError> {
         // Create Money instance through Financial Engine
-        let money = Money::new(amount, currency_enum)?;
+        let money = Money::new(amount, currency)?;
 
         Ok(Self {
             money,
@@ -58,20 +43,40 @@ impl FinancialAmount {
         })
     }
 
+    /// Zero in the given currency
+    pub fn zero(currency: Currency) -> Self {
+        Self {
+            money: Money::new_unchecked(Decimal::ZERO, currency),
+            amount: Decimal::ZERO,
+            currency,
+        }
+    }
+
     /// Create from decimal with validation
-    pub fn from_decimal(amount: Decimal, currency: String) -> Result<Self, FinancialError> {
+    /// Rejects amounts more precise than the currency's minor unit allows
+    pub fn from_decimal(amount: Decimal, currency: Currency) -> Result<Self, FinancialError> {
+        Self::from_decimal_with_policy(amount, currency, MinorUnitPolicy::default())
+    }
+
+    /// Create from decimal, handling excess precision according to `policy`
+    pub fn from_decimal_with_policy(
+        amount: Decimal,
+        currency: Currency,
+        policy: MinorUnitPolicy,
+    ) -> Result<Self, FinancialError> {
+        let amount = currency_info(currency).apply_minor_units(amount, policy)?;
         Self::new(amount, currency)
     }
 
     /// Create from string amount with validation
-    pub fn from_str(amount: &str, currency: String) -> Result<Self, FinancialError> {
+    pub fn from_str(amount: &str, currency: Currency) -> Result<Self, FinancialError> {
         let decimal_amount = amount.parse::<Decimal>()
             .map_err(|e| FinancialError::ParseError(format!("Invalid amount: {}", e)))?;
         Self::new(decimal_amount, currency)
     }
 
     /// Create from integer cents (for USD, divide by 100)
-    pub fn from_cents(cents: i64, currency: String) -> Result<Self, FinancialError> {
+    pub fn from_cents(cents: i64, currency: Currency) -> Result<Self, FinancialError> {
         let amount = Decimal::from(cents) / dec!(100);
         Self::new(amount, currency)
     }
@@ -81,9 +86,9 @@ impl FinancialAmount {
         self.amount
     }
 
-    /// Get the currency code
-    pub fn currency(&self) -> &str {
-        &self.currency
+    /// Get the currency
+    pub fn currency(&self) -> Currency {
+        self.currency
     }
 
     /// Convert to cents (multiply by 100 for USD)
@@ -98,7 +103,7 @@ impl FinancialAmount {
         Ok(FinancialAmount {
             money: result_money,
             amount: result_money.amount(),
-            currency: self.currency.clone(),
+            currency: self.currency,
         })
     }
 
@@ -109,7 +114,7 @@ impl FinancialAmount {
         Ok(FinancialAmount {
             money: result_money,
             amount: result_money.amount(),
-            currency: self.currency.clone(),
+            currency: self.currency,
         })
     }
 
@@ -120,7 +125,7 @@ impl FinancialAmount {
         Ok(FinancialAmount {
             money: result_money,
             amount: result_money.amount(),
-            currency: self.currency.clone(),
+            currency: self.currency,
         })
     }
 
@@ -131,7 +136,7 @@ impl FinancialAmount {
         Ok(FinancialAmount {
             money: result_money,
             amount: result_money.amount(),
-            currency: self.currency.clone(),
+            currency: self.currency,
         })
     }
 
@@ -156,27 +161,34 @@ impl FinancialAmount {
         FinancialAmount {
             money: abs_money,
             amount: abs_money.amount(),
-            currency: self.currency.clone(),
+            currency: self.currency,
         }
     }
 
     /// Round to specified decimal places
+    ///
+    /// Produces a new amount for calculations; use [`display`](Self::display)
+    /// to show an amount at the user's preferred precision.
     pub fn round(&self, decimal_places: u32) -> FinancialAmount {
+        let amount = self.amount.round_dp(decimal_places);
         FinancialAmount {
-            amount: self.amount.round_dp(decimal_places),
-            currency: self.currency.clone(),
+            money: Money::new_unchecked(amount, self.currency),
+            amount,
+            currency: self.currency,
         }
     }
 
     /// Format as currency string
     pub fn format_currency(&self) -> String {
-        match self.currency.as_str() {
-            "USD" => format!("${:.2}", self.amount),
-            "EUR" => format!("€{:.2}", self.amount),
-            "GBP" => format!("£{:.2}", self.amount),
-            "JPY" => format!("¥{:.0}", self.amount),
-            _ => format!("{} {:.2}", self.currency, self.amount),
-        }
+        self.display(currency_info(self.currency).minor_units)
+    }
+
+    /// Format with the currency symbol and exactly `places` decimals
+    ///
+    /// Only the returned text is rounded; the amount itself keeps its full
+    /// stored precision whatever display preference is in effect.
+    pub fn display(&self, places: u32) -> String {
+        format!("{}{}", currency_info(self.currency).symbol, display_amount(self.amount, places))
     }
 }
 
@@ -188,9 +200,118 @@ impl fmt::Display for FinancialAmount {
 
 impl Default for FinancialAmount {
     fn default() -> Self {
-        Self {
-            amount: dec!(0.00),
-            currency: "USD".to_string(),
+        Self::zero(Currency::USD)
+    }
+}
+
+/// Render `amount` with exactly `places` decimals for presentation
+///
+/// Rounds half away from zero, unlike `{:.N}` formatting of a `Decimal`,
+/// which truncates. Stored values are never rounded to a display preference.
+pub fn display_amount(amount: Decimal, places: u32) -> String {
+    let rounded = amount.round_dp_with_strategy(places, RoundingStrategy::MidpointAwayFromZero);
+    format!("{:.*}", places as usize, rounded)
+}
+
+// ============================================================================
+// Currency Registry
+// ============================================================================
+
+/// ISO 4217 details for a supported currency
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CurrencyInfo {
+    /// Decimal places of the currency's minor unit (2 for cents, 0 for yen)
+    pub minor_units: u32,
+    /// Symbol used when formatting amounts
+    pub symbol: &'static str,
+    /// Matching Rust Financial Engine currency
+    pub currency: Currency,
+}
+
+/// Currencies accepted by FinancialAmount
+pub const CURRENCIES: &[CurrencyInfo] = &[
+    CurrencyInfo { minor_units: 2, symbol: "$", currency: Currency::USD },
+    CurrencyInfo { minor_units: 2, symbol: "€", currency: Currency::EUR },
+    CurrencyInfo { minor_units: 2, symbol: "£", currency: Currency::GBP },
+    CurrencyInfo { minor_units: 2, symbol: "CA$", currency: Currency::CAD },
+    CurrencyInfo { minor_units: 2, symbol: "A$", currency: Currency::AUD },
+    CurrencyInfo { minor_units: 0, symbol: "¥", currency: Currency::JPY },
+    CurrencyInfo { minor_units: 2, symbol: "CHF ", currency: Currency::CHF },
+    CurrencyInfo { minor_units: 2, symbol: "CN¥", currency: Currency::CNY },
+];
+
+/// How amounts more precise than a currency's minor unit are handled
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MinorUnitPolicy {
+    /// Refuse the amount
+    #[default]
+    Reject,
+    /// Round half away from zero to the minor unit
+    Round,
+    /// Round half to even (banker's rounding) to the minor unit
+    BankersRound,
+}
+
+/// Decimal and digit grouping separators users type amounts with
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NumberFormat {
+    /// 1,234.56
+    #[default]
+    PointDecimal,
+    /// 1.234,56
+    CommaDecimal,
+}
+
+impl NumberFormat {
+    fn decimal_separator(self) -> char {
+        match self {
+            NumberFormat::PointDecimal => '.',
+            NumberFormat::CommaDecimal => ',',
+        }
+    }
+
+    fn grouping_separator(self) -> char {
+        match self {
+            NumberFormat::PointDecimal => ',',
+            NumberFormat::CommaDecimal => '.',
+        }
+    }
+}
+
+/// Registry entry for a currency
+pub fn currency_info(currency: Currency) -> &'static CurrencyInfo {
+    CURRENCIES
+        .iter()
+        .find(|info| info.currency == currency)
+        .expect("every supported currency is registered")
+}
+
+impl CurrencyInfo {
+    /// Bring `amount` to this currency's minor unit according to `policy`
+    pub fn apply_minor_units(
+        &self,
+        amount: Decimal,
+        policy: MinorUnitPolicy,
+    ) -> Result<Decimal, FinancialError> {
+        if amount.normalize().scale() <= self.minor_units {
+            return Ok(amount);
+        }
+
+        match policy {
+            MinorUnitPolicy::Reject => Err(FinancialError::PrecisionError(format!(
+                "{} amounts cannot exceed {} decimal places: {}",
+                self.currency, self.minor_units, amount
+            ))),
+            MinorUnitPolicy::Round => Ok(amount.round_dp_with_strategy(
+                self.minor_units,
+                RoundingStrategy::MidpointAwayFromZero,
+            )),
+            MinorUnitPolicy::BankersRound => Ok(amount.round_dp_with_strategy(
+                self.minor_units,
+                RoundingStrategy::MidpointNearestEven,
+            )),
         }
     }
 }
@@ -233,6 +354,9 @@ pub enum FinancialError {
 
     #[error("Security error: {0}")]
     SecurityError(String),
+
+    #[error("IO error: {0}")]
+    IoError(String),
 }
 
 // ============================================================================
@@ -256,7 +380,7 @@ pub struct FinancialEngineConfig {
     pub service_url: String,
     pub api_timeout_seconds: u64,
     pub cache_ttl_seconds: u64,
-    pub default_currency: String,
+    pub default_currency: Currency,
     pub precision: u32,
 }
 
@@ -266,7 +390,7 @@ impl Default for FinancialEngineConfig {
             service_url: "http://localhost:8080".to_string(),
             api_timeout_seconds: 30,
             cache_ttl_seconds: 300, // 5 minutes
-            default_currency: "USD".to_string(),
+            default_currency: Currency::USD,
             precision: 4,
         }
     }
@@ -427,8 +551,8 @@ impl FinancialEngine {
     /// Validate currency conversion rates
     pub async fn get_exchange_rate(
         &self,
-        from_currency: &str,
-        to_currency: &str,
+        from_currency: Currency,
+        to_currency: Currency,
     ) -> Result<Decimal, FinancialError> {
         if from_currency == to_currency {
             return Ok(dec!(1.0));
@@ -518,13 +642,144 @@ pub struct AssetAllocation {
 // ============================================================================
 
 /// Create a zero amount in the specified currency
-pub fn zero_amount(currency: &str) -> FinancialAmount {
-    FinancialAmount::new(dec!(0.00), currency.to_string()).unwrap()
+pub fn zero_amount(currency: Currency) -> FinancialAmount {
+    FinancialAmount::zero(currency)
 }
 
 /// Create amount from string with validation
-pub fn parse_amount(amount_str: &str, currency: &str) -> Result<FinancialAmount, FinancialError> {
-    FinancialAmount::from_str(amount_str, currency.to_string())
+pub fn parse_amount(amount_str: &str, currency: Currency) -> Result<FinancialAmount, FinancialError> {
+    FinancialAmount::from_str(amount_str, currency)
+}
+
+/// Parse an amount as a user typed it, e.g. "$1,234.56" or "1.234,56 €"
+///
+/// The currency's symbol or ISO code may come before or after the number,
+/// and digits may be grouped with `format`'s grouping separator or spaces.
+/// Input that could be read two ways, such as "1,5" when commas group
+/// digits, is rejected rather than guessed at, as is an amount more precise
+/// than the currency's minor unit.
+pub fn parse_money(
+    input: &str,
+    currency: Currency,
+    format: NumberFormat,
+) -> Result<FinancialAmount, FinancialError> {
+    let (negative, number) = strip_currency(input, currency)?;
+    let amount = parse_grouped_decimal(number, format).map_err(|reason| {
+        FinancialError::ParseError(format!("Invalid amount '{}': {}", input, reason))
+    })?;
+    FinancialAmount::from_decimal(if negative { -amount } else { amount }, currency)
+}
+
+/// Split a minus sign and `currency`'s symbol or code off `input`
+fn strip_currency(input: &str, currency: Currency) -> Result<(bool, &str), FinancialError> {
+    let symbol = currency_info(currency).symbol.trim();
+    let mut rest = input.trim();
+    let mut negative = false;
+
+    // The sign may come before or after a leading symbol: "-$5" or "$-5"
+    if let Some(unsigned) = rest.strip_prefix('-') {
+        negative = true;
+        rest = unsigned.trim_start();
+    }
+    if let Some(unmarked) = strip_marker(rest, &[symbol, currency.code()], true) {
+        rest = unmarked.trim_start();
+    }
+    if let Some(unsigned) = rest.strip_prefix('-').filter(|_| !negative) {
+        negative = true;
+        rest = unsigned.trim_start();
+    }
+    if let Some(unmarked) = strip_marker(rest, &[symbol, currency.code()], false) {
+        rest = unmarked.trim_end();
+    }
+
+    let foreign = CURRENCIES
+        .iter()
+        .filter(|info| info.currency != currency)
+        .find(|info| {
+            let markers = [info.symbol.trim(), info.currency.code()];
+            strip_marker(rest, &markers, true).is_some() || strip_marker(rest, &markers, false).is_some()
+        });
+    if let Some(info) = foreign {
+        return Err(FinancialError::CurrencyMismatch {
+            expected: currency.to_string(),
+            actual: info.currency.to_string(),
+        });
+    }
+
+    Ok((negative, rest))
+}
+
+/// `s` without the first of `markers` it starts (or ends) with
+fn strip_marker<'a>(s: &'a str, markers: &[&str], leading: bool) -> Option<&'a str> {
+    markers.iter().find_map(|marker| {
+        let split = if leading { marker.len() } else { s.len().checked_sub(marker.len())? };
+        let (head, tail) = (s.get(..split)?, s.get(split..)?);
+        if leading {
+            head.eq_ignore_ascii_case(marker).then_some(tail)
+        } else {
+            tail.eq_ignore_ascii_case(marker).then_some(head)
+        }
+    })
+}
+
+/// Spaces some locales group digits with
+fn is_space_separator(c: char) -> bool {
+    matches!(c, ' ' | '\u{a0}' | '\u{202f}')
+}
+
+/// Parse unsigned digits written with `format`'s separators
+fn parse_grouped_decimal(number: &str, format: NumberFormat) -> std::result::Result<Decimal, String> {
+    let decimal = format.decimal_separator();
+    let grouping = format.grouping_separator();
+
+    if let Some(c) = number
+        .chars()
+        .find(|&c| !c.is_ascii_digit() && c != decimal && c != grouping && !is_space_separator(c))
+    {
+        return Err(format!("unexpected character '{}'", c));
+    }
+
+    let mut parts = number.split(decimal);
+    let whole = parts.next().unwrap_or_default();
+    let fraction = parts.next();
+    if parts.next().is_some() {
+        return Err(format!("more than one decimal separator '{}'", decimal));
+    }
+    if let Some(fraction) = fraction {
+        if fraction.is_empty() || !fraction.chars().all(|c| c.is_ascii_digit()) {
+            return Err(format!("expected only digits after the decimal separator '{}'", decimal));
+        }
+    }
+
+    let separators: Vec<char> = whole.chars().filter(|c| !c.is_ascii_digit()).collect();
+    let whole = match separators.first() {
+        None => whole.to_string(),
+        Some(&separator) => {
+            if separators.iter().any(|&c| c != separator) {
+                return Err("mixes digit grouping separators".to_string());
+            }
+            let groups: Vec<&str> = whole.split(separator).collect();
+            let grouped_in_threes =
+                (1..=3).contains(&groups[0].len()) && groups[1..].iter().all(|g| g.len() == 3);
+            if !grouped_in_threes {
+                return Err(format!(
+                    "digits grouped with '{}' must come in threes; use '{}' for decimals",
+                    separator, decimal
+                ));
+            }
+            groups.concat()
+        }
+    };
+
+    if whole.is_empty() && fraction.is_none() {
+        return Err("no digits".to_string());
+    }
+    let canonical = match fraction {
+        Some(fraction) if whole.is_empty() => format!("0.{}", fraction),
+        Some(fraction) => format!("{}.{}", whole, fraction),
+        None => whole,
+    };
+    canonical.parse::<Decimal>().map_err(|e| e.to_string())
 }
 
 /// Validate decimal precision for financial amounts
@@ -540,7 +795,7 @@ pub fn validate_financial_precision(amount: Decimal) -> Result<(), FinancialErro
 /// Convert between currencies (placeholder for actual implementation)
 pub async fn convert_currency(
     amount: &FinancialAmount,
-    to_currency: &str,
+    to_currency: Currency,
     engine: &FinancialEngine,
 ) -> Result<FinancialAmount, FinancialError> {
     if amount.currency() == to_currency {
@@ -550,7 +805,7 @@ pub async fn convert_currency(
     let rate = engine.get_exchange_rate(amount.currency(), to_currency).await?;
     let converted_amount = amount.multiply(rate)?;
 
-    Ok(FinancialAmount::new(converted_amount.amount(), to_currency.to_string())?)
+    Ok(FinancialAmount::new(converted_amount.amount(), to_currency)?)
 }
 
 // ============================================================================
@@ -564,15 +819,15 @@ mod tests {
 
     #[test]
     fn test_financial_amount_creation() {
-        let amount = FinancialAmount::new(dec!(100.50), "USD".to_string()).unwrap();
+        let amount = FinancialAmount::new(dec!(100.50), Currency::USD).unwrap();
         assert_eq!(amount.amount(), dec!(100.50));
-        assert_eq!(amount.currency(), "USD");
+        assert_eq!(amount.currency(), Currency::USD);
     }
 
     #[test]
     fn test_financial_amount_arithmetic() {
-        let amount1 = FinancialAmount::new(dec!(100.00), "USD".to_string()).unwrap();
-        let amount2 = FinancialAmount::new(dec!(50.25), "USD".to_string()).unwrap();
+        let amount1 = FinancialAmount::new(dec!(100.00), Currency::USD).unwrap();
+        let amount2 = FinancialAmount::new(dec!(50.25), Currency::USD).unwrap();
 
         let sum = amount1.add(&amount2).unwrap();
         assert_eq!(sum.amount(), dec!(150.25));
@@ -589,8 +844,8 @@ mod tests {
 
     #[test]
     fn test_currency_mismatch() {
-        let usd = FinancialAmount::new(dec!(100.00), "USD".to_string()).unwrap();
-        let eur = FinancialAmount::new(dec!(50.00), "EUR".to_string()).unwrap();
+        let usd = FinancialAmount::new(dec!(100.00), Currency::USD).unwrap();
+        let eur = FinancialAmount::new(dec!(50.00), Currency::EUR).unwrap();
 
         assert!(usd.add(&eur).is_err());
         assert!(usd.subtract(&eur).is_err());
@@ -599,27 +854,159 @@ mod tests {
     #[test]
     fn test_precision_validation() {
         // Should succeed with 4 decimal places
-        assert!(FinancialAmount::new(dec!(100.1234), "USD".to_string()).is_ok());
+        assert!(FinancialAmount::new(dec!(100.1234), Currency::USD).is_ok());
 
         // Should fail with more than 4 decimal places
-        assert!(FinancialAmount::new(dec!(100.12345), "USD".to_string()).is_err());
+        assert!(FinancialAmount::new(dec!(100.12345), Currency::USD).is_err());
     }
 
     #[test]
     fn test_currency_formatting() {
-        let usd = FinancialAmount::new(dec!(1234.56), "USD".to_string()).unwrap();
+        let usd = FinancialAmount::new(dec!(1234.56), Currency::USD).unwrap();
         assert_eq!(usd.format_currency(), "$1234.56");
 
-        let eur = FinancialAmount::new(dec!(1234.56), "EUR".to_string()).unwrap();
+        let eur = FinancialAmount::new(dec!(1234.56), Currency::EUR).unwrap();
         assert_eq!(eur.format_currency(), "€1234.56");
 
-        let jpy = FinancialAmount::new(dec!(1234.0), "JPY".to_string()).unwrap();
+        let jpy = FinancialAmount::new(dec!(1234.0), Currency::JPY).unwrap();
         assert_eq!(jpy.format_currency(), "¥1234");
     }
 
+    #[test]
+    fn test_display_places_round_output_without_changing_stored_amount() {
+        let stored = FinancialAmount::new(dec!(1234.5678), Currency::USD).unwrap();
+        let serialized = serde_json::to_string(&stored).unwrap();
+
+        assert_eq!(stored.display(2), "$1234.57");
+        assert_eq!(stored.display(0), "$1235");
+        assert_eq!(stored.display(4), "$1234.5678");
+        assert_eq!(stored.display(6), "$1234.567800");
+        assert_eq!(display_amount(dec!(-0.125), 2), "-0.13");
+
+        assert_eq!(stored.amount(), dec!(1234.5678));
+        assert_eq!(serde_json::to_string(&stored).unwrap(), serialized);
+    }
+
+    #[test]
+    fn test_unknown_currency_codes_rejected() {
+        assert!("XYZ".parse::<Currency>().is_err());
+        assert_eq!("usd".parse::<Currency>().unwrap(), Currency::USD);
+
+        // Every engine currency has minor units and a symbol
+        for currency in Currency::ALL {
+            assert_eq!(currency_info(currency).currency, currency);
+        }
+        assert_eq!(currency_info(Currency::JPY).minor_units, 0);
+    }
+
+    #[test]
+    fn test_zero_and_default_amounts() {
+        let zero = FinancialAmount::zero(Currency::EUR);
+        assert!(zero.is_zero());
+        assert_eq!(zero.currency(), Currency::EUR);
+        assert_eq!(FinancialAmount::default().currency(), Currency::USD);
+    }
+
+    #[test]
+    fn test_from_decimal_respects_minor_units() {
+        let result = FinancialAmount::from_decimal(dec!(100.125), Currency::USD);
+        assert!(matches!(result, Err(FinancialError::PrecisionError(_))));
+
+        let result = FinancialAmount::from_decimal(dec!(1500.5), Currency::JPY);
+        assert!(matches!(result, Err(FinancialError::PrecisionError(_))));
+
+        // Trailing zeros are not extra precision
+        assert!(FinancialAmount::from_decimal(dec!(100.1200), Currency::USD).is_ok());
+        assert!(FinancialAmount::from_decimal(dec!(1500.00), Currency::JPY).is_ok());
+
+        let rounded = FinancialAmount::from_decimal_with_policy(
+            dec!(100.125),
+            Currency::USD,
+            MinorUnitPolicy::Round,
+        )
+        .unwrap();
+        assert_eq!(rounded.amount(), dec!(100.13));
+
+        let rounded = FinancialAmount::from_decimal_with_policy(
+            dec!(100.125),
+            Currency::USD,
+            MinorUnitPolicy::BankersRound,
+        )
+        .unwrap();
+        assert_eq!(rounded.amount(), dec!(100.12));
+    }
+
+    #[test]
+    fn test_parse_money_accepts_symbols_and_locale_grouping() {
+        let parsed = parse_money("$1,234.56", Currency::USD, NumberFormat::PointDecimal).unwrap();
+        assert_eq!(parsed.amount(), dec!(1234.56));
+        assert_eq!(parsed.currency(), Currency::USD);
+
+        let parsed = parse_money("1.234,56", Currency::EUR, NumberFormat::CommaDecimal).unwrap();
+        assert_eq!(parsed.amount(), dec!(1234.56));
+
+        let cases = [
+            ("1.234,56 €", Currency::EUR, NumberFormat::CommaDecimal, dec!(1234.56)),
+            ("1 234,56", Currency::EUR, NumberFormat::CommaDecimal, dec!(1234.56)),
+            ("-$1,234.56", Currency::USD, NumberFormat::PointDecimal, dec!(-1234.56)),
+            ("$-12", Currency::USD, NumberFormat::PointDecimal, dec!(-12)),
+            ("usd 1234.5", Currency::USD, NumberFormat::PointDecimal, dec!(1234.5)),
+            ("CA$1,000,000", Currency::CAD, NumberFormat::PointDecimal, dec!(1000000)),
+            ("¥1,500", Currency::JPY, NumberFormat::PointDecimal, dec!(1500)),
+            (".75", Currency::USD, NumberFormat::PointDecimal, dec!(0.75)),
+        ];
+        for (input, currency, format, expected) in cases {
+            let parsed = parse_money(input, currency, format).unwrap();
+            assert_eq!(parsed.amount(), expected, "input {}", input);
+        }
+    }
+
+    #[test]
+    fn test_parse_money_rejects_ambiguous_input() {
+        let result = parse_money("12.3456", Currency::USD, NumberFormat::PointDecimal);
+        assert!(matches!(result, Err(FinancialError::PrecisionError(_))));
+
+        let result = parse_money("12.5", Currency::JPY, NumberFormat::PointDecimal);
+        assert!(matches!(result, Err(FinancialError::PrecisionError(_))));
+
+        // Could be 1.5 or 15; commas only group digits in this format
+        match parse_money("1,5", Currency::USD, NumberFormat::PointDecimal) {
+            Err(FinancialError::ParseError(message)) => {
+                assert!(message.contains("'1,5'"), "{}", message);
+                assert!(message.contains("threes"), "{}", message);
+            }
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+
+        for (input, format) in [
+            ("1.234,56", NumberFormat::PointDecimal),
+            ("12.3456", NumberFormat::CommaDecimal),
+            ("1,23,456", NumberFormat::PointDecimal),
+            ("1,234 567", NumberFormat::PointDecimal),
+            ("1.2.3", NumberFormat::PointDecimal),
+            ("12.", NumberFormat::PointDecimal),
+            ("12abc", NumberFormat::PointDecimal),
+            ("$", NumberFormat::PointDecimal),
+            ("", NumberFormat::PointDecimal),
+        ] {
+            let result = parse_money(input, Currency::USD, format);
+            assert!(
+                matches!(result, Err(FinancialError::ParseError(_))),
+                "input {:?} gave {:?}",
+                input,
+                result
+            );
+        }
+
+        let result = parse_money("€5", Currency::USD, NumberFormat::PointDecimal);
+        assert!(matches!(result, Err(FinancialError::CurrencyMismatch { .. })));
+        let result = parse_money("A$5", Currency::USD, NumberFormat::PointDecimal);
+        assert!(matches!(result, Err(FinancialError::CurrencyMismatch { .. })));
+    }
+
     #[test]
     fn test_cents_conversion() {
-        let amount = FinancialAmount::from_cents(12345, "USD".to_string()).unwrap();
+        let amount = FinancialAmount::from_cents(12345, Currency::USD).unwrap();
         assert_eq!(amount.amount(), dec!(123.45));
         assert_eq!(amount.to_cents(), 12345);
     }
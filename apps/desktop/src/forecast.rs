@@ -0,0 +1,533 @@
+// Cash Flow Forecasting for Atlas Desktop
+// Projects daily balances from recurring transactions and historical averages
+
+use chrono::{Datelike, Duration, NaiveDate};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Default number of days assumed between occurrences of a recurring
+/// transaction that has only been seen once
+const DEFAULT_RECURRENCE_DAYS: i64 = 30;
+
+/// How far back history is sampled for recurring items and daily averages
+pub const FORECAST_HISTORY_DAYS: i64 = 90;
+
+/// A historical or scheduled movement of money (positive = inflow)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CashFlowEvent {
+    pub date: NaiveDate,
+    pub amount: Decimal,
+    pub description: String,
+    pub is_recurring: bool,
+}
+
+/// Recurring transaction inferred from history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurringSeries {
+    pub description: String,
+    pub amount: Decimal,
+    pub interval_days: i64,
+    pub last_date: NaiveDate,
+}
+
+impl RecurringSeries {
+    /// Occurrences of this series that fall within `[start, end]`
+    pub fn occurrences_between(&self, start: NaiveDate, end: NaiveDate) -> Vec<NaiveDate> {
+        let mut dates = Vec::new();
+        if self.interval_days <= 0 {
+            return dates;
+        }
+
+        let mut next = self.last_date + Duration::days(self.interval_days);
+        while next < start {
+            next += Duration::days(self.interval_days);
+        }
+        while next <= end {
+            dates.push(next);
+            next += Duration::days(self.interval_days);
+        }
+        dates
+    }
+}
+
+/// Projected position at the end of a single day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForecastDay {
+    pub date: NaiveDate,
+    pub inflows: Decimal,
+    pub outflows: Decimal,
+    pub projected_balance: Decimal,
+    pub scheduled_items: Vec<String>,
+}
+
+/// Warning raised when the projected balance drops below the threshold
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LowBalanceWarning {
+    pub date: NaiveDate,
+    pub projected_balance: Decimal,
+    pub shortfall: Decimal,
+    pub triggered_by: Vec<String>,
+}
+
+/// Daily balance series with low-balance warnings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CashFlowForecast {
+    pub starting_balance: Decimal,
+    pub daily_average_discretionary: Decimal,
+    pub days: Vec<ForecastDay>,
+    pub warnings: Vec<LowBalanceWarning>,
+    pub lowest_balance: Decimal,
+    pub lowest_balance_date: Option<NaiveDate>,
+}
+
+/// Normalize a description so variants of the same payee group together
+fn normalize_description(description: &str) -> String {
+    description
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Infer recurring series from transactions flagged as recurring
+pub fn detect_recurring_series(history: &[CashFlowEvent]) -> Vec<RecurringSeries> {
+    let mut groups: HashMap<String, Vec<&CashFlowEvent>> = HashMap::new();
+    for event in history.iter().filter(|e| e.is_recurring) {
+        groups
+            .entry(normalize_description(&event.description))
+            .or_default()
+            .push(event);
+    }
+
+    let mut series: Vec<RecurringSeries> = groups
+        .into_values()
+        .map(|mut events| {
+            events.sort_by_key(|e| e.date);
+            let first = events[0];
+            let last = events[events.len() - 1];
+
+            let interval_days = if events.len() > 1 {
+                ((last.date - first.date).num_days() / (events.len() as i64 - 1)).max(1)
+            } else {
+                DEFAULT_RECURRENCE_DAYS
+            };
+
+            RecurringSeries {
+                description: last.description.clone(),
+                amount: last.amount,
+                interval_days,
+                last_date: last.date,
+            }
+        })
+        .collect();
+
+    // Stable output regardless of hash map ordering
+    series.sort_by(|a, b| a.description.cmp(&b.description));
+    series
+}
+
+/// Average net daily amount of non-recurring history before `start_date`
+///
+/// Spread over the whole sampling window rather than the days that happen to
+/// have transactions, so a couple of purchases close together do not read as
+/// a high daily burn.
+fn daily_discretionary_average(history: &[CashFlowEvent], start_date: NaiveDate) -> Decimal {
+    let window_start = start_date - Duration::days(FORECAST_HISTORY_DAYS);
+    let total: Decimal = history
+        .iter()
+        .filter(|e| !e.is_recurring && e.date >= window_start && e.date < start_date)
+        .map(|e| e.amount)
+        .sum();
+    (total / Decimal::from(FORECAST_HISTORY_DAYS)).round_dp(2)
+}
+
+/// Project balances forward day by day
+///
+/// Each day applies recurring series occurrences, explicitly scheduled items and
+/// the historical discretionary average. A warning is raised on the first day of
+/// every stretch where the balance falls below `low_balance_threshold`.
+pub fn project_cash_flow(
+    starting_balance: Decimal,
+    history: &[CashFlowEvent],
+    scheduled: &[CashFlowEvent],
+    start_date: NaiveDate,
+    horizon_days: u32,
+    low_balance_threshold: Decimal,
+) -> CashFlowForecast {
+    let end_date = start_date + Duration::days(horizon_days as i64);
+    let recurring = detect_recurring_series(history);
+    let daily_average = daily_discretionary_average(history, start_date);
+
+    // Bucket every known future item by date
+    let mut planned: HashMap<NaiveDate, Vec<(Decimal, String)>> = HashMap::new();
+    for series in &recurring {
+        for date in series.occurrences_between(start_date + Duration::days(1), end_date) {
+            planned
+                .entry(date)
+                .or_default()
+                .push((series.amount, series.description.clone()));
+        }
+    }
+    for item in scheduled {
+        if item.date > start_date && item.date <= end_date {
+            planned
+                .entry(item.date)
+                .or_default()
+                .push((item.amount, item.description.clone()));
+        }
+    }
+
+    let mut balance = starting_balance;
+    let mut days = Vec::with_capacity(horizon_days as usize);
+    let mut warnings = Vec::new();
+    let mut lowest_balance = starting_balance;
+    let mut lowest_balance_date = None;
+    let mut below_threshold = starting_balance < low_balance_threshold;
+
+    for offset in 1..=horizon_days as i64 {
+        let date = start_date + Duration::days(offset);
+        let mut inflows = Decimal::ZERO;
+        let mut outflows = Decimal::ZERO;
+        let mut scheduled_items = Vec::new();
+
+        let mut apply = |amount: Decimal| {
+            if amount.is_sign_negative() {
+                outflows += -amount;
+            } else {
+                inflows += amount;
+            }
+        };
+
+        if let Some(items) = planned.get(&date) {
+            for (amount, description) in items {
+                apply(*amount);
+                scheduled_items.push(description.clone());
+            }
+        }
+        apply(daily_average);
+
+        balance += inflows - outflows;
+
+        if balance < lowest_balance {
+            lowest_balance = balance;
+            lowest_balance_date = Some(date);
+        }
+
+        let is_below = balance < low_balance_threshold;
+        if is_below && !below_threshold {
+            warnings.push(LowBalanceWarning {
+                date,
+                projected_balance: balance,
+                shortfall: low_balance_threshold - balance,
+                triggered_by: scheduled_items.clone(),
+            });
+        }
+        below_threshold = is_below;
+
+        days.push(ForecastDay {
+            date,
+            inflows,
+            outflows,
+            projected_balance: balance,
+            scheduled_items,
+        });
+    }
+
+    CashFlowForecast {
+        starting_balance,
+        daily_average_discretionary: daily_average,
+        days,
+        warnings,
+        lowest_balance,
+        lowest_balance_date,
+    }
+}
+
+/// Minimum payment on a debt, due on the same day every month
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DebtPaymentDue {
+    pub name: String,
+    pub minimum_payment: Decimal,
+    /// Day of the month the payment is due; later than the month's last day
+    /// means its last day
+    pub due_day: u32,
+}
+
+impl DebtPaymentDue {
+    /// Due dates that fall within `[start, end]`
+    pub fn due_dates_between(&self, start: NaiveDate, end: NaiveDate) -> Vec<NaiveDate> {
+        let mut dates = Vec::new();
+        if self.due_day == 0 {
+            return dates;
+        }
+
+        let mut month = start.with_day(1).unwrap();
+        while month <= end {
+            let next_month = month
+                .checked_add_months(chrono::Months::new(1))
+                .unwrap();
+            let last_day = (next_month - Duration::days(1)).day();
+            let due = month.with_day(self.due_day.min(last_day)).unwrap();
+            if due >= start && due <= end {
+                dates.push(due);
+            }
+            month = next_month;
+        }
+        dates
+    }
+}
+
+/// Payment expected before the end of the safe-to-spend horizon
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpcomingObligation {
+    pub date: NaiveDate,
+    /// Amount that will leave the account, always positive
+    pub amount: Decimal,
+    pub description: String,
+}
+
+/// What can be spent without putting upcoming payments at risk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SafeToSpend {
+    pub balance: Decimal,
+    pub recurring_obligations: Decimal,
+    pub debt_payments: Decimal,
+    pub buffer: Decimal,
+    /// Balance less obligations and buffer, never below zero
+    pub safe_to_spend: Decimal,
+    pub obligations: Vec<UpcomingObligation>,
+}
+
+/// Balance left after setting aside every payment due within the horizon
+///
+/// Recurring outflows inferred from `history` and debt minimums falling after
+/// `start_date` and up to `horizon_days` later are subtracted, then `buffer`.
+/// Recurring income is not counted on, since spending it early is exactly
+/// what puts a scheduled payment at risk.
+pub fn safe_to_spend(
+    balance: Decimal,
+    history: &[CashFlowEvent],
+    debts: &[DebtPaymentDue],
+    start_date: NaiveDate,
+    horizon_days: u32,
+    buffer: Decimal,
+) -> SafeToSpend {
+    let first_day = start_date + Duration::days(1);
+    let end_date = start_date + Duration::days(horizon_days as i64);
+
+    let mut obligations = Vec::new();
+    let mut recurring_obligations = Decimal::ZERO;
+    for series in detect_recurring_series(history) {
+        if !series.amount.is_sign_negative() {
+            continue;
+        }
+        for date in series.occurrences_between(first_day, end_date) {
+            recurring_obligations += -series.amount;
+            obligations.push(UpcomingObligation {
+                date,
+                amount: -series.amount,
+                description: series.description.clone(),
+            });
+        }
+    }
+
+    let mut debt_payments = Decimal::ZERO;
+    for debt in debts {
+        for date in debt.due_dates_between(first_day, end_date) {
+            debt_payments += debt.minimum_payment;
+            obligations.push(UpcomingObligation {
+                date,
+                amount: debt.minimum_payment,
+                description: debt.name.clone(),
+            });
+        }
+    }
+    obligations.sort_by(|a, b| a.date.cmp(&b.date).then_with(|| a.description.cmp(&b.description)));
+
+    let remaining = balance - recurring_obligations - debt_payments - buffer;
+    SafeToSpend {
+        balance,
+        recurring_obligations,
+        debt_payments,
+        buffer,
+        safe_to_spend: remaining.max(Decimal::ZERO),
+        obligations,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn event(date: NaiveDate, amount: Decimal, description: &str, is_recurring: bool) -> CashFlowEvent {
+        CashFlowEvent {
+            date,
+            amount,
+            description: description.to_string(),
+            is_recurring,
+        }
+    }
+
+    #[test]
+    fn test_detect_recurring_series_interval() {
+        let history = vec![
+            event(date(2024, 1, 1), dec!(2000), "Payroll ACME", true),
+            event(date(2024, 1, 15), dec!(2000), "PAYROLL acme", true),
+            event(date(2024, 1, 29), dec!(2000), "Payroll  ACME!", true),
+            event(date(2024, 1, 10), dec!(-40), "Coffee", false),
+        ];
+
+        let series = detect_recurring_series(&history);
+        assert_eq!(series.len(), 1);
+        assert_eq!(series[0].interval_days, 14);
+        assert_eq!(series[0].last_date, date(2024, 1, 29));
+        assert_eq!(
+            series[0].occurrences_between(date(2024, 2, 1), date(2024, 2, 28)),
+            vec![date(2024, 2, 12), date(2024, 2, 26)]
+        );
+    }
+
+    #[test]
+    fn test_scheduled_payment_triggers_negative_warning() {
+        let start = date(2024, 3, 1);
+        let scheduled = vec![event(date(2024, 3, 10), dec!(-1500), "Rent", false)];
+
+        let forecast = project_cash_flow(dec!(1000), &[], &scheduled, start, 30, Decimal::ZERO);
+
+        assert_eq!(forecast.days.len(), 30);
+        assert_eq!(forecast.warnings.len(), 1);
+
+        let warning = &forecast.warnings[0];
+        assert_eq!(warning.date, date(2024, 3, 10));
+        assert_eq!(warning.projected_balance, dec!(-500));
+        assert_eq!(warning.shortfall, dec!(500));
+        assert_eq!(warning.triggered_by, vec!["Rent".to_string()]);
+
+        assert_eq!(forecast.days[7].projected_balance, dec!(1000));
+        assert_eq!(forecast.days[8].projected_balance, dec!(-500));
+        assert_eq!(forecast.lowest_balance_date, Some(date(2024, 3, 10)));
+    }
+
+    #[test]
+    fn test_recurring_income_recovers_balance() {
+        let start = date(2024, 3, 1);
+        let history = vec![
+            event(date(2024, 2, 1), dec!(800), "Salary", true),
+            event(date(2024, 2, 15), dec!(800), "Salary", true),
+        ];
+        let scheduled = vec![event(date(2024, 3, 5), dec!(-500), "Insurance", false)];
+
+        let forecast = project_cash_flow(dec!(200), &history, &scheduled, start, 20, Decimal::ZERO);
+
+        // Dips on the 5th, salary on the 14th brings it back above zero
+        assert_eq!(forecast.warnings.len(), 1);
+        assert_eq!(forecast.warnings[0].date, date(2024, 3, 5));
+        let after_salary = forecast.days.iter().find(|d| d.date == date(2024, 3, 14)).unwrap();
+        assert_eq!(after_salary.projected_balance, dec!(500));
+        assert_eq!(after_salary.scheduled_items, vec!["Salary".to_string()]);
+    }
+
+    #[test]
+    fn test_discretionary_average_applied_daily() {
+        let start = date(2024, 3, 1);
+        let history = vec![
+            event(date(2024, 2, 1), dec!(-50), "Groceries", false),
+            event(date(2024, 2, 10), dec!(-50), "Dining", false),
+        ];
+
+        let forecast = project_cash_flow(dec!(100), &history, &[], start, 10, Decimal::ZERO);
+
+        // -100 over the 90 day history window averages -1.11 per day
+        assert_eq!(forecast.daily_average_discretionary, dec!(-1.11));
+        assert_eq!(forecast.days.last().unwrap().projected_balance, dec!(88.90));
+        assert!(forecast.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_sparse_history_does_not_inflate_daily_spending() {
+        let start = date(2024, 3, 1);
+        // Two purchases a day apart are all the history there is
+        let history = vec![
+            event(date(2024, 2, 27), dec!(-45), "Dining", false),
+            event(date(2024, 2, 28), dec!(-45), "Dining", false),
+        ];
+
+        let forecast = project_cash_flow(dec!(500), &history, &[], start, 30, Decimal::ZERO);
+
+        assert_eq!(forecast.daily_average_discretionary, dec!(-1));
+        assert_eq!(forecast.days.last().unwrap().projected_balance, dec!(470));
+        assert!(forecast.warnings.is_empty());
+
+        // Events outside the window are not sampled
+        let stale = vec![event(date(2023, 10, 1), dec!(-900), "Furniture", false)];
+        let forecast = project_cash_flow(dec!(500), &stale, &[], start, 30, Decimal::ZERO);
+        assert_eq!(forecast.daily_average_discretionary, Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_upcoming_obligations_reduce_safe_to_spend() {
+        let start = date(2024, 3, 1);
+        let history = vec![
+            event(date(2024, 1, 5), dec!(-1200), "Rent", true),
+            event(date(2024, 2, 4), dec!(-1200), "Rent", true),
+            event(date(2024, 2, 1), dec!(2500), "Salary", true),
+            event(date(2024, 2, 20), dec!(-80), "Dining", false),
+        ];
+        let debts = vec![DebtPaymentDue {
+            name: "Visa".to_string(),
+            minimum_payment: dec!(75),
+            due_day: 15,
+        }];
+
+        let result = safe_to_spend(dec!(2000), &history, &debts, start, 30, dec!(100));
+
+        // Rent on the 5th and the Visa minimum on the 15th; salary is not counted on
+        assert_eq!(result.recurring_obligations, dec!(1200));
+        assert_eq!(result.debt_payments, dec!(75));
+        assert_eq!(result.safe_to_spend, dec!(625));
+        assert!(result.safe_to_spend < result.balance);
+        let dates: Vec<NaiveDate> = result.obligations.iter().map(|o| o.date).collect();
+        assert_eq!(dates, vec![date(2024, 3, 5), date(2024, 3, 15)]);
+
+        // Obligations beyond the balance leave nothing safe to spend
+        let result = safe_to_spend(dec!(1000), &history, &debts, start, 30, Decimal::ZERO);
+        assert_eq!(result.safe_to_spend, Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_debt_due_dates_follow_the_horizon() {
+        let debt = DebtPaymentDue {
+            name: "Car loan".to_string(),
+            minimum_payment: dec!(300),
+            due_day: 31,
+        };
+
+        // Short months fall due on their last day
+        assert_eq!(
+            debt.due_dates_between(date(2024, 1, 15), date(2024, 4, 10)),
+            vec![date(2024, 1, 31), date(2024, 2, 29), date(2024, 3, 31)]
+        );
+
+        let history = vec![event(date(2024, 2, 20), dec!(-50), "Gym", true)];
+        let result = safe_to_spend(dec!(500), &history, &[debt], date(2024, 3, 1), 10, Decimal::ZERO);
+        assert!(result.obligations.is_empty());
+        assert_eq!(result.safe_to_spend, dec!(500));
+    }
+}
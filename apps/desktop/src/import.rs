@@ -0,0 +1,444 @@
+// Resumable Transaction Import for Atlas Desktop
+// Commits rows in batches and checkpoints progress so a failed import resumes where it stopped
+
+use std::future::Future;
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::commands::financial::ImportError;
+use crate::financial::FinancialError;
+use crate::storage::{CreateTransactionRequest, TransactionType};
+
+/// Largest batch an import commits at once, however the settings are configured
+///
+/// Every batch is one database transaction, and a long one holds the write
+/// lock long enough to stall the rest of the app.
+pub const MAX_IMPORT_BATCH_SIZE: usize = 5_000;
+
+/// Batch size and retry policy for imports
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportSettings {
+    /// Validated rows inserted per database transaction, together with their checkpoint
+    ///
+    /// Larger batches import faster; smaller ones hold fewer rows in memory
+    /// and the database lock for less time.
+    pub batch_size: usize,
+    /// Tries per batch before the import stops, including the first
+    pub max_attempts: u32,
+    /// Pause before retrying a failed batch
+    pub retry_delay_ms: u64,
+}
+
+impl ImportSettings {
+    /// Validated rows per batch, between one and [`MAX_IMPORT_BATCH_SIZE`]
+    pub fn batch_size(&self) -> usize {
+        self.batch_size.clamp(1, MAX_IMPORT_BATCH_SIZE)
+    }
+
+    pub fn retry_delay(&self) -> Duration {
+        Duration::from_millis(self.retry_delay_ms)
+    }
+}
+
+impl Default for ImportSettings {
+    fn default() -> Self {
+        Self {
+            batch_size: 200,
+            max_attempts: 3,
+            retry_delay_ms: 500,
+        }
+    }
+}
+
+/// How far an import got, reported after every batch
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportProgress {
+    pub total_rows: u64,
+    /// Row the previous run had committed through, 0 for a fresh import
+    pub resumed_after: u64,
+    /// Last row committed by this run or an earlier one
+    pub last_committed_row: u64,
+    pub batches_committed: u64,
+}
+
+/// Hex SHA-256 of an import file, the key its checkpoint is stored under
+pub fn file_hash(contents: &[u8]) -> String {
+    Sha256::digest(contents).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// A parsed import row
+pub trait ImportRow {
+    /// Whether the row passed validation and will be inserted
+    fn is_insertable(&self) -> bool;
+}
+
+impl<T, E> ImportRow for Result<T, E> {
+    fn is_insertable(&self) -> bool {
+        self.is_ok()
+    }
+}
+
+/// Length of the batch at the start of `rows`: up to `batch_size` insertable
+/// rows, with the rejected rows among them carried along
+fn batch_len<T: ImportRow>(rows: &[T], batch_size: usize) -> usize {
+    let mut insertable = 0;
+    rows.iter()
+        .position(|row| {
+            insertable += usize::from(row.is_insertable());
+            insertable > batch_size
+        })
+        .unwrap_or(rows.len())
+}
+
+/// Failures worth retrying; anything else fails the same way every time
+fn is_transient(error: &FinancialError) -> bool {
+    matches!(error, FinancialError::DatabaseError(_) | FinancialError::NetworkError(_))
+}
+
+/// Commit `rows` in batches, skipping the `resume_after` rows already imported
+///
+/// Rows are numbered from 1. Each batch holds up to the configured batch size
+/// of insertable rows, plus any rejected rows between them. `commit_batch`
+/// receives the number of a batch's first row and its rows, and must store the
+/// checkpoint (the batch's last row number) in the same database transaction
+/// as the rows so the two never disagree. `on_batch` is told the progress after
+/// every commit. Transient failures are retried per `settings`; when a batch
+/// still fails the import stops, and a re-run with the stored checkpoint
+/// starts at the first row of that batch.
+pub async fn import_in_batches<'r, T, F, Fut, P>(
+    rows: &'r [T],
+    resume_after: u64,
+    settings: &ImportSettings,
+    mut commit_batch: F,
+    mut on_batch: P,
+) -> Result<ImportProgress, FinancialError>
+where
+    T: ImportRow,
+    F: FnMut(u64, &'r [T]) -> Fut,
+    Fut: Future<Output = Result<(), FinancialError>>,
+    P: FnMut(&ImportProgress),
+{
+    let total_rows = rows.len() as u64;
+    let resumed_after = resume_after.min(total_rows);
+    let mut progress = ImportProgress {
+        total_rows,
+        resumed_after,
+        last_committed_row: resumed_after,
+        batches_committed: 0,
+    };
+
+    let mut remaining = &rows[resumed_after as usize..];
+    while !remaining.is_empty() {
+        let (batch, rest) = remaining.split_at(batch_len(remaining, settings.batch_size()));
+        remaining = rest;
+        let first_row = progress.last_committed_row + 1;
+        let mut attempt = 1;
+        loop {
+            match commit_batch(first_row, batch).await {
+                Ok(()) => break,
+                Err(e) if is_transient(&e) && attempt < settings.max_attempts => {
+                    tracing::warn!("Import batch starting at row {} failed (attempt {}): {}", first_row, attempt, e);
+                    attempt += 1;
+                    tokio::time::sleep(settings.retry_delay()).await;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        progress.last_committed_row += batch.len() as u64;
+        progress.batches_committed += 1;
+        on_batch(&progress);
+    }
+
+    Ok(progress)
+}
+
+/// Split CSV text into records, honouring quoted fields
+///
+/// Quoted fields may contain delimiters, doubled quotes and line breaks, as
+/// written by the CSV export. Blank lines are skipped.
+pub fn parse_csv(contents: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = contents.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => record.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes => {}
+            '\n' if !in_quotes => {
+                record.push(std::mem::take(&mut field));
+                if record.iter().any(|f| !f.is_empty()) {
+                    records.push(std::mem::take(&mut record));
+                } else {
+                    record.clear();
+                }
+            }
+            _ => field.push(c),
+        }
+    }
+
+    record.push(field);
+    if record.iter().any(|f| !f.is_empty()) {
+        records.push(record);
+    }
+    records
+}
+
+fn parse_transaction_type(label: &str) -> Option<TransactionType> {
+    Some(match label.trim().to_ascii_lowercase().as_str() {
+        "debit" => TransactionType::Debit,
+        "credit" => TransactionType::Credit,
+        "transfer" => TransactionType::Transfer,
+        "fee" => TransactionType::Fee,
+        "interest" => TransactionType::Interest,
+        "dividend" => TransactionType::Dividend,
+        "withdrawal" => TransactionType::Withdrawal,
+        "deposit" => TransactionType::Deposit,
+        _ => return None,
+    })
+}
+
+/// Accepts RFC 3339 timestamps and plain dates, which are taken as midnight UTC
+fn parse_transaction_date(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    DateTime::parse_from_rfc3339(value)
+        .map(|date| date.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+                .map(|date| date.and_utc())
+        })
+}
+
+/// Turn CSV records with a header row into transaction requests for `user_id`
+///
+/// Columns are found by header name, so files from the CSV export import
+/// as-is. Each data row yields a request or the error that rejected it; row
+/// numbers count data rows from 1, matching checkpoints.
+pub fn transaction_rows(
+    records: &[Vec<String>],
+    user_id: &str,
+) -> Result<Vec<Result<CreateTransactionRequest, ImportError>>, FinancialError> {
+    let Some((header, data)) = records.split_first() else {
+        return Ok(Vec::new());
+    };
+    let column = |name: &str| header.iter().position(|h| h.trim().eq_ignore_ascii_case(name));
+    let required = |name: &str| {
+        column(name).ok_or_else(|| FinancialError::ParseError(format!("Import file has no {} column", name)))
+    };
+
+    let account_id = required("account_id")?;
+    let amount = required("amount")?;
+    let description = required("description")?;
+    let transaction_type = column("transaction_type");
+    let transaction_date = column("transaction_date");
+    let merchant = column("merchant");
+    let category = column("category");
+    let subcategory = column("subcategory");
+    let tags = column("tags");
+
+    let rows = data.iter().enumerate().map(|(index, record)| {
+        let row = index as i32 + 1;
+        let value = |col: Option<usize>| {
+            col.and_then(|c| record.get(c))
+                .map(|v| v.trim())
+                .filter(|v| !v.is_empty())
+        };
+        let reject = |field: &str, error: &str, value: Option<&str>| ImportError {
+            row,
+            field: field.to_string(),
+            error: error.to_string(),
+            value: value.unwrap_or_default().to_string(),
+        };
+
+        let amount_value = value(Some(amount));
+        let parsed_amount = amount_value
+            .and_then(|v| v.parse::<Decimal>().ok())
+            .ok_or_else(|| reject("amount", "Invalid amount", amount_value))?;
+
+        let type_value = value(transaction_type);
+        let parsed_type = match type_value {
+            Some(label) => parse_transaction_type(label)
+                .ok_or_else(|| reject("transaction_type", "Unknown transaction type", type_value))?,
+            None if parsed_amount < Decimal::ZERO => TransactionType::Debit,
+            None => TransactionType::Credit,
+        };
+
+        let date_value = value(transaction_date);
+        let parsed_date = date_value
+            .map(|v| parse_transaction_date(v).ok_or_else(|| reject("transaction_date", "Invalid date", date_value)))
+            .transpose()?;
+
+        Ok(CreateTransactionRequest {
+            user_id: user_id.to_string(),
+            account_id: value(Some(account_id)).unwrap_or_default().to_string(),
+            amount: parsed_amount,
+            description: value(Some(description)).unwrap_or_default().to_string(),
+            category: value(category).map(str::to_string),
+            subcategory: value(subcategory).map(str::to_string),
+            transaction_date: parsed_date,
+            transaction_type: parsed_type,
+            merchant: value(merchant).map(str::to_string),
+            location: None,
+            is_recurring: None,
+            tags: value(tags).map(|t| t.split(';').map(|tag| tag.trim().to_string()).collect()),
+            notes: None,
+            ml_confidence: None,
+        })
+    });
+
+    Ok(rows.collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    impl ImportRow for u64 {
+        fn is_insertable(&self) -> bool {
+            true
+        }
+    }
+
+    fn settings(batch_size: usize) -> ImportSettings {
+        ImportSettings { batch_size, max_attempts: 3, retry_delay_ms: 0 }
+    }
+
+    #[tokio::test]
+    async fn test_resume_starts_after_last_committed_row() {
+        let rows: Vec<u64> = (1..=1000).collect();
+        let committed = RefCell::new(Vec::new());
+        let checkpoint = RefCell::new(0);
+        let commit = |fail_at: Option<u64>| {
+            let (committed, checkpoint) = (&committed, &checkpoint);
+            move |first_row: u64, batch: &[u64]| {
+                let fails = fail_at.is_some_and(|row| batch.contains(&row));
+                let batch = batch.to_vec();
+                async move {
+                    if fails {
+                        return Err(FinancialError::ValidationError("Row 501 rejected".to_string()));
+                    }
+                    committed.borrow_mut().extend_from_slice(&batch);
+                    *checkpoint.borrow_mut() = first_row + batch.len() as u64 - 1;
+                    Ok(())
+                }
+            }
+        };
+
+        // Rows up to 500 commit, then the import fails on row 501
+        let failed = import_in_batches(&rows, 0, &settings(100), commit(Some(501)), |_| {}).await;
+        assert!(failed.is_err());
+        assert_eq!(*checkpoint.borrow(), 500);
+
+        let resume_after = *checkpoint.borrow();
+        let resumed_at = RefCell::new(None);
+        let progress = import_in_batches(&rows, resume_after, &settings(100), |first_row, batch| {
+            resumed_at.borrow_mut().get_or_insert(first_row);
+            commit(None)(first_row, batch)
+        }, |_| {})
+        .await
+        .unwrap();
+
+        assert_eq!(*resumed_at.borrow(), Some(501));
+        assert_eq!(progress.resumed_after, 500);
+        assert_eq!(progress.last_committed_row, 1000);
+        assert_eq!(progress.batches_committed, 5);
+        // Every row is imported exactly once across both runs
+        assert_eq!(*committed.borrow(), rows);
+    }
+
+    #[tokio::test]
+    async fn test_transient_batch_failures_are_retried() {
+        let rows: Vec<u64> = (1..=10).collect();
+        let attempts = RefCell::new(0);
+
+        let progress = import_in_batches(&rows, 0, &settings(4), |first_row, _| {
+            let attempt = {
+                let mut attempts = attempts.borrow_mut();
+                *attempts += 1;
+                *attempts
+            };
+            async move {
+                if first_row == 5 && attempt == 2 {
+                    return Err(FinancialError::DatabaseError("connection reset".to_string()));
+                }
+                Ok(())
+            }
+        }, |_| {})
+        .await
+        .unwrap();
+
+        assert_eq!(progress.last_committed_row, 10);
+        assert_eq!(progress.batches_committed, 3);
+        assert_eq!(*attempts.borrow(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_small_batches_commit_separately_and_report_progress() {
+        // Every tenth row failed validation and does not count toward a batch
+        let rows: Vec<Result<u64, u64>> = (1..=1000).map(|row| if row % 10 == 0 { Err(row) } else { Ok(row) }).collect();
+        let commits = RefCell::new(Vec::new());
+        let reports = RefCell::new(Vec::new());
+
+        let progress = import_in_batches(&rows, 0, &settings(50), |first_row, batch| {
+            let inserted = batch.iter().filter(|row| row.is_ok()).count();
+            commits.borrow_mut().push((first_row, batch.len(), inserted));
+            async { Ok(()) }
+        }, |progress| reports.borrow_mut().push(progress.last_committed_row))
+        .await
+        .unwrap();
+
+        let commits = commits.into_inner();
+        assert_eq!(commits.len(), 18);
+        assert_eq!(progress.batches_committed, 18);
+        assert!(commits.iter().all(|&(_, _, inserted)| inserted == 50));
+        assert_eq!(commits[0], (1, 55, 50));
+        assert_eq!(commits[1].0, 56);
+        assert_eq!(commits.iter().map(|&(_, len, _)| len).sum::<usize>(), 1000);
+
+        // Progress is reported once per committed batch
+        let reports = reports.into_inner();
+        assert_eq!(reports.len(), 18);
+        assert_eq!(reports[0], 55);
+        assert_eq!(reports.last(), Some(&1000));
+        assert_eq!(settings(0).batch_size(), 1);
+        assert_eq!(settings(1_000_000).batch_size(), MAX_IMPORT_BATCH_SIZE);
+    }
+
+    #[test]
+    fn test_exported_csv_rows_become_transactions() {
+        let csv = "id,account_id,transaction_date,amount,description,transaction_type,merchant,tags\r\n\
+                   t1,acc-1,2024-03-01T12:00:00+00:00,-42.50,\"Groceries, weekly\",debit,\"Joe's \"\"Market\"\"\",food;home\r\n\
+                   t2,acc-1,2024-03-02,oops,Refund,credit,,\r\n";
+
+        let records = parse_csv(csv);
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[1][4], "Groceries, weekly");
+        assert_eq!(records[1][6], "Joe's \"Market\"");
+
+        let rows = transaction_rows(&records, "user-1").unwrap();
+        let first = rows[0].as_ref().unwrap();
+        assert_eq!(first.amount, Decimal::new(-4250, 2));
+        assert_eq!(first.transaction_type, TransactionType::Debit);
+        assert_eq!(first.tags, Some(vec!["food".to_string(), "home".to_string()]));
+
+        let second = rows[1].as_ref().unwrap_err();
+        assert_eq!((second.row, second.field.as_str()), (2, "amount"));
+    }
+}
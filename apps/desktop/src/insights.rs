@@ -0,0 +1,446 @@
+// Brutal Honesty Insights for Atlas Desktop
+// Turns a month of the user's aggregates into blunt findings through data-driven rules
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BrutalHonestyInsight {
+    pub id: String,
+    /// Rule that produced the insight; dismissals are recorded against it
+    pub rule_id: String,
+    pub title: String,
+    pub message: String,
+    pub severity: InsightSeverity,
+    pub category: InsightCategory,
+    pub action_items: Vec<String>,
+    pub impact_score: i32, // 1-10 scale
+    pub created_at: DateTime<Utc>,
+    pub is_dismissed: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum InsightSeverity {
+    Info,
+    Warning,
+    Critical,
+    Urgent,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum InsightCategory {
+    Spending,
+    Saving,
+    Debt,
+    Investment,
+    Budget,
+    Cash_Flow,
+    Risk,
+    Opportunity,
+}
+
+/// Smallest minimum payment card issuers typically ask for
+const MINIMUM_PAYMENT_FLOOR: Decimal = dec!(25);
+
+/// Share of the balance typically due each month on top of interest
+const MINIMUM_PAYMENT_PRINCIPAL_RATE: Decimal = dec!(0.01);
+
+/// A credit card and what was paid towards it over the period
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreditCardPayment {
+    pub account_name: String,
+    pub balance: Decimal,
+    pub amount_paid: Decimal,
+    pub minimum_payment: Decimal,
+}
+
+/// Estimated minimum payment on a card: 1% of the balance plus a month of
+/// interest at `annual_rate` percent, at least $25 and never more than the
+/// balance
+pub fn estimate_minimum_payment(balance: Decimal, annual_rate: Option<Decimal>) -> Decimal {
+    let balance = balance.abs();
+    let monthly_interest = balance * annual_rate.unwrap_or(Decimal::ZERO) / dec!(1200);
+    (balance * MINIMUM_PAYMENT_PRINCIPAL_RATE + monthly_interest)
+        .max(MINIMUM_PAYMENT_FLOOR)
+        .min(balance)
+        .round_dp(2)
+}
+
+/// A month of the user's finances, as the rules see it
+///
+/// Amounts are magnitudes: income and expenses are both positive.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FinancialAggregates {
+    pub monthly_income: Decimal,
+    pub monthly_expenses: Decimal,
+    pub category_spending: HashMap<String, Decimal>,
+    pub credit_card_payments: Vec<CreditCardPayment>,
+}
+
+impl FinancialAggregates {
+    /// Percentage of income left after expenses; `None` without income
+    pub fn savings_rate(&self) -> Option<Decimal> {
+        (self.monthly_income > Decimal::ZERO)
+            .then(|| (self.monthly_income - self.monthly_expenses) / self.monthly_income * dec!(100))
+    }
+
+    /// Percentage of income spent in any of `categories`, matched without
+    /// regard to case; `None` without income
+    pub fn category_share_of_income(&self, categories: &[String]) -> Option<Decimal> {
+        if self.monthly_income <= Decimal::ZERO {
+            return None;
+        }
+        let spent: Decimal = self
+            .category_spending
+            .iter()
+            .filter(|(category, _)| categories.iter().any(|c| c.eq_ignore_ascii_case(category)))
+            .map(|(_, amount)| *amount)
+            .sum();
+        Some(spent / self.monthly_income * dec!(100))
+    }
+
+    /// Cards carrying a balance that were paid no more than their minimum
+    pub fn minimum_only_payments(&self) -> Vec<&CreditCardPayment> {
+        self.credit_card_payments
+            .iter()
+            .filter(|card| card.balance.abs() > Decimal::ZERO && card.amount_paid <= card.minimum_payment)
+            .collect()
+    }
+}
+
+/// Measurement a rule judges
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "metric", rename_all = "camelCase")]
+pub enum InsightMetric {
+    /// Percentage of income spent in the given categories
+    CategoryShareOfIncome { categories: Vec<String> },
+    /// Percentage of income not spent
+    SavingsRate,
+    /// Number of cards paid at or below their minimum
+    MinimumOnlyCreditPayments,
+}
+
+impl InsightMetric {
+    /// Current value of the metric; `None` when the data cannot support it
+    pub fn measure(&self, aggregates: &FinancialAggregates) -> Option<Decimal> {
+        match self {
+            InsightMetric::CategoryShareOfIncome { categories } => aggregates.category_share_of_income(categories),
+            InsightMetric::SavingsRate => aggregates.savings_rate(),
+            InsightMetric::MinimumOnlyCreditPayments => {
+                Some(Decimal::from(aggregates.minimum_only_payments().len()))
+            }
+        }
+    }
+}
+
+/// Which side of a threshold trips a rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ThresholdDirection {
+    Above,
+    Below,
+}
+
+/// Severity and impact reached once the metric passes `threshold`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeverityLevel {
+    pub threshold: Decimal,
+    pub severity: InsightSeverity,
+    pub impact_score: i32,
+}
+
+/// One data-driven insight: a metric, the thresholds it is judged against
+/// and what to tell the user
+///
+/// `message` may contain `{value}` and `{threshold}`, filled in with the
+/// measured value and the threshold of the level reached.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InsightRule {
+    pub id: String,
+    pub title: String,
+    pub category: InsightCategory,
+    pub metric: InsightMetric,
+    pub direction: ThresholdDirection,
+    /// Levels from least to most severe; the most severe level passed applies
+    pub levels: Vec<SeverityLevel>,
+    pub message: String,
+    pub action_items: Vec<String>,
+}
+
+impl InsightRule {
+    /// Insight produced by this rule, if the aggregates trip it
+    pub fn evaluate(&self, aggregates: &FinancialAggregates, now: DateTime<Utc>) -> Option<BrutalHonestyInsight> {
+        let value = self.metric.measure(aggregates)?;
+        let level = self
+            .levels
+            .iter()
+            .rev()
+            .find(|level| match self.direction {
+                ThresholdDirection::Above => value > level.threshold,
+                ThresholdDirection::Below => value < level.threshold,
+            })?;
+
+        let message = self
+            .message
+            .replace("{value}", &value.round_dp(1).normalize().to_string())
+            .replace("{threshold}", &level.threshold.normalize().to_string());
+
+        Some(BrutalHonestyInsight {
+            id: format!("{}-{}", self.id, now.format("%Y-%m")),
+            rule_id: self.id.clone(),
+            title: self.title.clone(),
+            message,
+            severity: level.severity,
+            category: self.category,
+            action_items: self.action_items.clone(),
+            impact_score: level.impact_score.clamp(1, 10),
+            created_at: now,
+            is_dismissed: false,
+        })
+    }
+}
+
+/// Rules applied when the user has not configured their own
+pub fn default_insight_rules() -> Vec<InsightRule> {
+    vec![
+        InsightRule {
+            id: "dining-share-of-income".to_string(),
+            title: "Dining out is eating your income".to_string(),
+            category: InsightCategory::Spending,
+            metric: InsightMetric::CategoryShareOfIncome {
+                categories: vec!["Dining".to_string(), "Restaurants".to_string(), "Food & Dining".to_string()],
+            },
+            direction: ThresholdDirection::Above,
+            levels: vec![
+                SeverityLevel { threshold: dec!(15), severity: InsightSeverity::Warning, impact_score: 5 },
+                SeverityLevel { threshold: dec!(25), severity: InsightSeverity::Critical, impact_score: 7 },
+            ],
+            message: "You spent {value}% of your income eating out, above the {threshold}% line.".to_string(),
+            action_items: vec![
+                "Set a monthly dining budget".to_string(),
+                "Cook at home at least four nights a week".to_string(),
+            ],
+        },
+        InsightRule {
+            id: "minimum-only-credit-payments".to_string(),
+            title: "You are only paying card minimums".to_string(),
+            category: InsightCategory::Debt,
+            metric: InsightMetric::MinimumOnlyCreditPayments,
+            direction: ThresholdDirection::Above,
+            levels: vec![
+                SeverityLevel { threshold: dec!(0), severity: InsightSeverity::Critical, impact_score: 8 },
+                SeverityLevel { threshold: dec!(2), severity: InsightSeverity::Urgent, impact_score: 10 },
+            ],
+            message: "{value} credit card(s) got no more than the minimum payment; interest is compounding against you."
+                .to_string(),
+            action_items: vec![
+                "Pay more than the minimum on your highest-rate card".to_string(),
+                "Compare the avalanche and snowball payoff plans".to_string(),
+            ],
+        },
+        InsightRule {
+            id: "low-savings-rate".to_string(),
+            title: "You are barely saving".to_string(),
+            category: InsightCategory::Saving,
+            metric: InsightMetric::SavingsRate,
+            direction: ThresholdDirection::Below,
+            levels: vec![
+                SeverityLevel { threshold: dec!(5), severity: InsightSeverity::Warning, impact_score: 6 },
+                SeverityLevel { threshold: dec!(0), severity: InsightSeverity::Urgent, impact_score: 9 },
+            ],
+            message: "Your savings rate is {value}%, below {threshold}%.".to_string(),
+            action_items: vec![
+                "Automate a transfer to savings on payday".to_string(),
+                "Cut one recurring expense this month".to_string(),
+            ],
+        },
+    ]
+}
+
+/// Insights from every rule the aggregates trip, highest impact first
+pub fn evaluate_insight_rules(
+    rules: &[InsightRule],
+    aggregates: &FinancialAggregates,
+    now: DateTime<Utc>,
+) -> Vec<BrutalHonestyInsight> {
+    let mut insights: Vec<BrutalHonestyInsight> =
+        rules.iter().filter_map(|rule| rule.evaluate(aggregates, now)).collect();
+    insights.sort_by_key(|insight| std::cmp::Reverse(insight.impact_score));
+    insights
+}
+
+/// A user's choice to stop seeing a rule's insights
+///
+/// Without `snoozed_until` the rule stays dismissed for good; with it, the
+/// rule's insights come back once that moment has passed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InsightDismissal {
+    pub rule_id: String,
+    pub dismissed_at: DateTime<Utc>,
+    pub snoozed_until: Option<DateTime<Utc>>,
+}
+
+impl InsightDismissal {
+    /// Whether the rule's insights are hidden at `now`
+    pub fn suppresses_at(&self, now: DateTime<Utc>) -> bool {
+        !matches!(self.snoozed_until, Some(until) if until <= now)
+    }
+}
+
+/// Insights whose rule is neither dismissed nor snoozed at `now`
+pub fn without_dismissed(
+    insights: Vec<BrutalHonestyInsight>,
+    dismissals: &[InsightDismissal],
+    now: DateTime<Utc>,
+) -> Vec<BrutalHonestyInsight> {
+    insights
+        .into_iter()
+        .filter(|insight| {
+            !dismissals
+                .iter()
+                .any(|d| d.rule_id == insight.rule_id && d.suppresses_at(now))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aggregates(income: Decimal, expenses: Decimal, dining: Decimal) -> FinancialAggregates {
+        FinancialAggregates {
+            monthly_income: income,
+            monthly_expenses: expenses,
+            category_spending: HashMap::from([
+                ("dining".to_string(), dining),
+                ("Groceries".to_string(), dec!(400)),
+            ]),
+            credit_card_payments: Vec::new(),
+        }
+    }
+
+    fn insight<'a>(insights: &'a [BrutalHonestyInsight], rule_id: &str) -> Option<&'a BrutalHonestyInsight> {
+        insights.iter().find(|i| i.id.starts_with(rule_id))
+    }
+
+    #[test]
+    fn test_dining_and_savings_rules_pick_their_severity() {
+        let rules = default_insight_rules();
+        let now = Utc::now();
+
+        // 18% of income on dining, saving 3%
+        let insights = evaluate_insight_rules(&rules, &aggregates(dec!(5000), dec!(4850), dec!(900)), now);
+        assert_eq!(insights.len(), 2);
+        let dining = insight(&insights, "dining-share-of-income").unwrap();
+        assert_eq!(dining.severity, InsightSeverity::Warning);
+        assert_eq!(dining.category, InsightCategory::Spending);
+        assert_eq!(dining.message, "You spent 18% of your income eating out, above the 15% line.");
+        let savings = insight(&insights, "low-savings-rate").unwrap();
+        assert_eq!(savings.severity, InsightSeverity::Warning);
+        assert_eq!(savings.impact_score, 6);
+
+        // 30% on dining while spending more than is earned
+        let insights = evaluate_insight_rules(&rules, &aggregates(dec!(5000), dec!(5500), dec!(1500)), now);
+        assert_eq!(insight(&insights, "dining-share-of-income").unwrap().severity, InsightSeverity::Critical);
+        let savings = insight(&insights, "low-savings-rate").unwrap();
+        assert_eq!(savings.severity, InsightSeverity::Urgent);
+        assert_eq!(insights[0].id, savings.id);
+
+        // Healthy months and months without income trip nothing
+        assert!(evaluate_insight_rules(&rules, &aggregates(dec!(5000), dec!(3500), dec!(300)), now).is_empty());
+        assert!(evaluate_insight_rules(&rules, &aggregates(Decimal::ZERO, dec!(300), dec!(300)), now).is_empty());
+    }
+
+    #[test]
+    fn test_minimum_only_card_payments_are_flagged() {
+        let card = |name: &str, balance: Decimal, paid: Decimal| CreditCardPayment {
+            account_name: name.to_string(),
+            balance,
+            amount_paid: paid,
+            minimum_payment: estimate_minimum_payment(balance, Some(dec!(24))),
+        };
+        assert_eq!(estimate_minimum_payment(dec!(5000), Some(dec!(24))), dec!(150));
+        assert_eq!(estimate_minimum_payment(dec!(500), None), dec!(25));
+        assert_eq!(estimate_minimum_payment(dec!(10), None), dec!(10));
+
+        let mut data = aggregates(dec!(5000), dec!(3500), dec!(300));
+        data.credit_card_payments = vec![
+            card("Visa", dec!(5000), dec!(150)),
+            card("Store card", dec!(800), dec!(300)),
+            card("Paid off", Decimal::ZERO, Decimal::ZERO),
+        ];
+
+        let insights = evaluate_insight_rules(&default_insight_rules(), &data, Utc::now());
+        assert_eq!(insights.len(), 1);
+        assert_eq!(insights[0].severity, InsightSeverity::Critical);
+        assert_eq!(insights[0].category, InsightCategory::Debt);
+        assert!(insights[0].message.starts_with("1 credit card(s)"));
+        assert!(!insights[0].action_items.is_empty());
+
+        data.credit_card_payments.extend([
+            card("Mastercard", dec!(2000), dec!(0)),
+            card("Amex", dec!(3000), dec!(60)),
+        ]);
+        let insights = evaluate_insight_rules(&default_insight_rules(), &data, Utc::now());
+        assert_eq!(insights[0].severity, InsightSeverity::Urgent);
+        assert_eq!(insights[0].impact_score, 10);
+    }
+
+    #[test]
+    fn test_rules_round_trip_as_data() {
+        let json = serde_json::json!({
+            "id": "subscriptions",
+            "title": "Subscriptions add up",
+            "category": "spending",
+            "metric": { "metric": "categoryShareOfIncome", "categories": ["Subscriptions"] },
+            "direction": "above",
+            "levels": [{ "threshold": "2", "severity": "info", "impactScore": 3 }],
+            "message": "{value}% of income goes to subscriptions",
+            "actionItems": ["Cancel one you have not used this month"]
+        });
+        let rule: InsightRule = serde_json::from_value(json).unwrap();
+
+        let mut data = aggregates(dec!(4000), dec!(2000), dec!(0));
+        data.category_spending.insert("subscriptions".to_string(), dec!(120));
+        let insight = rule.evaluate(&data, Utc::now()).unwrap();
+        assert_eq!(insight.severity, InsightSeverity::Info);
+        assert_eq!(insight.message, "3% of income goes to subscriptions");
+    }
+
+    #[test]
+    fn test_dismissed_insights_stay_hidden_and_snoozed_ones_return() {
+        let rules = default_insight_rules();
+        let data = aggregates(dec!(5000), dec!(4850), dec!(900));
+        let now = Utc::now();
+        let dismissals = vec![
+            InsightDismissal { rule_id: "dining-share-of-income".to_string(), dismissed_at: now, snoozed_until: None },
+            InsightDismissal {
+                rule_id: "low-savings-rate".to_string(),
+                dismissed_at: now,
+                snoozed_until: Some(now + chrono::Duration::days(7)),
+            },
+        ];
+        let visible_at = |at: DateTime<Utc>| without_dismissed(evaluate_insight_rules(&rules, &data, at), &dismissals, at);
+
+        assert!(visible_at(now).is_empty());
+        assert!(visible_at(now + chrono::Duration::days(6)).is_empty());
+
+        // Next month's insight has a new id but the same rule, so the
+        // dismissal still applies while the expired snooze no longer does
+        let later = visible_at(now + chrono::Duration::days(40));
+        assert_eq!(later.len(), 1);
+        assert_eq!(later[0].rule_id, "low-savings-rate");
+        assert!(insight(&later, "dining-share-of-income").is_none());
+    }
+}
@@ -1,11 +1,26 @@
 // Atlas Financial Desktop Library
 // Re-export core functionality for use as a library
 
+pub mod anomaly;
+pub mod backup;
+pub mod budget;
 pub mod commands;
+pub mod date_range;
+pub mod duplicates;
+pub mod export;
 pub mod financial;
+pub mod forecast;
+pub mod import;
+pub mod insights;
+pub mod notifications;
+pub mod privacy;
+pub mod retention;
 pub mod security;
+pub mod spending;
 pub mod storage;
 pub mod system;
+pub mod tags;
+pub mod tax;
 pub mod utils;
 
 pub use commands::*;
@@ -9,18 +9,39 @@ use tauri::{
 };
 // use tauri_plugin_window_state::{AppHandleExt, StateFlags, WindowExt};
 
+mod anomaly;
+mod backup;
+mod budget;
 mod commands;
+mod date_range;
 mod financial;
+mod duplicates;
+mod export;
+mod forecast;
+mod import;
+mod insights;
+mod notifications;
+mod privacy;
+mod retention;
 mod storage;
 mod system;
 mod utils;
 mod security;
+mod spending;
+mod tags;
+mod tax;
 mod api_client;
 mod atlas_config_bridge;
 
 use commands::*;
-use security::RateLimiter;
+use security::{
+    check_command_allowed, configure_log_redaction, get_vault, lock_app, record_command_activity, run_security_startup_tests,
+    startup_mode, AutoLock, RateLimiter, SessionGuard, StartupCheckResult, StartupGate, StartupMode,
+    AUTO_LOCK_POLL_INTERVAL,
+};
+use storage::{DatabaseConfig, DatabaseManager};
 use api_client::AtlasApiClient;
+use notifications::Notifications;
 use atlas_config_bridge::{get_atlas_config, ConsolidatedConfig};
 
 // Application State - Phase 2.6 Architectural Compliance
@@ -30,6 +51,14 @@ pub struct AppState {
     pub atlas_config: ConsolidatedConfig,
     pub rate_limiter: RateLimiter,
     pub api_client: AtlasApiClient,
+    /// User signed in to this app instance; commands authorize against it
+    pub session: SessionGuard,
+    /// Idle timeout after which the vault key is wiped and the session ends
+    pub auto_lock: AutoLock,
+    /// Delivers command notifications through the configured channel
+    pub notifications: Notifications,
+    /// Mode chosen by the startup self-tests; read-only refuses data changes
+    pub startup: StartupGate,
 }
 
 #[tokio::main]
@@ -58,6 +87,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Initialize legacy config for backward compatibility
     let config = utils::Config::load().await?;
+    configure_log_redaction(config.security_settings.log_redaction);
 
     // Initialize API client with atlas configuration
     let api_client = AtlasApiClient::new(&config)?;
@@ -65,13 +95,107 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Initialize rate limiter with security configuration
     let rate_limiter = RateLimiter::new();
 
+    let (notifications, desktop_notifier) = Notifications::from_settings(&config.notification_settings);
+
     let app_state = AppState {
         config,
         atlas_config,
         rate_limiter,
         api_client,
+        session: SessionGuard::new(),
+        auto_lock: AutoLock::default(),
+        notifications,
+        startup: StartupGate::default(),
     };
 
+    let handle_command: Box<dyn Fn(tauri::ipc::Invoke) -> bool + Send + Sync> = Box::new(generate_handler![
+        // Authentication commands
+        authenticate_user,
+        logout_user,
+        get_session_status,
+        lock_now,
+        rotate_security_keys,
+        // Rate limiting and security commands
+        get_security_stats,
+        admin_unlock_account,
+        whitelist_ip_address,
+        // Financial data commands
+        get_accounts,
+        get_account_details,
+        archive_account,
+        reactivate_account,
+        delete_account,
+        get_transactions,
+        get_financial_overview,
+        calculate_net_worth,
+        // Transaction management
+        add_transaction,
+        update_transaction,
+        delete_transaction,
+        add_transaction_attachment,
+        list_transaction_attachments,
+        remove_transaction_attachment,
+        categorize_transaction,
+        bulk_categorize,
+        find_duplicate_transactions,
+        merge_transactions,
+        purge_deleted_transactions,
+        recompute_financials,
+        // Insights and analytics
+        get_brutal_honesty_insights,
+        dismiss_insight,
+        snooze_insight,
+        get_spending_analysis,
+        get_spending_timeseries,
+        get_budget_recommendations,
+        estimate_taxes,
+        forecast_cash_flow,
+        calculate_safe_to_spend,
+        set_budget,
+        budget_status,
+        // Data export/import
+        export_financial_data,
+        export_debt_payment_calendar,
+        import_financial_data,
+        // System commands
+        get_system_info,
+        get_startup_status,
+        monitor_performance,
+        get_disk_usage,
+        check_for_updates,
+        download_update,
+        install_update,
+        open_file_location,
+        validate_file_permissions,
+        manage_app_data_directory,
+        send_system_notification,
+        schedule_recurring_notifications,
+        monitor_file_system_changes,
+        validate_application_integrity,
+        log_security_events,
+        verify_security_audit_chain,
+        open_external_url,
+        // User Preferences
+        get_user_preferences,
+        update_user_preferences,
+        reset_preferences_to_default,
+        // Financial Settings
+        get_currency_preferences,
+        update_precision_settings,
+        configure_transaction_defaults,
+        // Security Settings
+        get_security_settings,
+        manage_biometric_settings,
+        configure_backup_preferences,
+        backup_now,
+        verify_backup,
+        restore_backup,
+        // UI/Performance Settings
+        get_theme_settings,
+        manage_layout_preferences,
+        update_performance_settings,
+    ]);
+
     // Build Tauri application
     let app = tauri::Builder::default()
         .manage(app_state)
@@ -83,68 +207,73 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         // .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         // .plugin(tauri_plugin_clipboard_manager::init())
         // .plugin(tauri_plugin_shell::init())
-        .invoke_handler(generate_handler![
-            // Authentication commands
-            authenticate_user,
-            logout_user,
-            get_session_status,
-            // Rate limiting and security commands
-            get_security_stats,
-            admin_unlock_account,
-            whitelist_ip_address,
-            // Financial data commands
-            get_accounts,
-            get_account_details,
-            get_transactions,
-            get_financial_overview,
-            calculate_net_worth,
-            // Transaction management
-            add_transaction,
-            update_transaction,
-            delete_transaction,
-            categorize_transaction,
-            // Insights and analytics
-            get_brutal_honesty_insights,
-            get_spending_analysis,
-            get_budget_recommendations,
-            // Data export/import
-            export_financial_data,
-            import_financial_data,
-            // System commands
-            get_system_info,
-            monitor_performance,
-            get_disk_usage,
-            check_for_updates,
-            download_update,
-            install_update,
-            open_file_location,
-            validate_file_permissions,
-            manage_app_data_directory,
-            send_system_notification,
-            schedule_recurring_notifications,
-            monitor_file_system_changes,
-            validate_application_integrity,
-            log_security_events,
-            open_external_url,
-            // User Preferences
-            get_user_preferences,
-            update_user_preferences,
-            reset_preferences_to_default,
-            // Financial Settings
-            get_currency_preferences,
-            update_precision_settings,
-            configure_transaction_defaults,
-            // Security Settings
-            get_security_settings,
-            manage_biometric_settings,
-            configure_backup_preferences,
-            // UI/Performance Settings
-            get_theme_settings,
-            manage_layout_preferences,
-            update_performance_settings,
-        ])
-        .setup(|app| {
+        .invoke_handler(move |invoke| {
+            let command = invoke.message.command().to_string();
+            let app_handle = invoke.message.webview().app_handle().clone();
+
+            // Read-only safe mode refuses every data change in one place
+            if let Err(message) = check_command_allowed(&app_handle.state::<AppState>().startup, &command) {
+                tracing::warn!("Refused {} while read-only", command);
+                invoke.resolver.resolve(CommandResponse::<()>::error(message));
+                return true;
+            }
+
+            // Any command the user triggers keeps the session from auto-locking
+            tauri::async_runtime::spawn(async move {
+                record_command_activity(&app_handle.state::<AppState>().session, &command).await;
+            });
+            handle_command(invoke)
+        })
+        .setup(move |app| {
             // setup_application(app)?;
+
+            // Refuse to start, or start read-only, when a critical self-test fails
+            let self_test_policy = app.state::<AppState>().config.security_settings.startup_self_test;
+            if self_test_policy.enabled {
+                let results = tauri::async_runtime::block_on(run_startup_self_tests(app.handle().clone()));
+                let mode = startup_mode(&self_test_policy, &results)?;
+                if let StartupMode::ReadOnly { failed_checks } = &mode {
+                    tracing::warn!("Starting read-only; failed startup checks: {}", failed_checks.join(", "));
+                }
+                app.state::<AppState>().startup.record(mode);
+            }
+
+            if let Some(desktop_notifier) = &desktop_notifier {
+                desktop_notifier.attach(app.handle().clone());
+            }
+
+            // Lock the app once the session has been idle past the auto-lock timeout
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let state = app_handle.state::<AppState>();
+                state.auto_lock
+                    .run(&state.session, AUTO_LOCK_POLL_INTERVAL, || lock_app(&state.session))
+                    .await;
+            });
+
+            // Purge transactions soft-deleted past the retention window
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let state = app_handle.state::<AppState>();
+                let source = format!("App: {}", app_handle.package_info().name);
+                retention::run(&state.database_manager, &state.config.data_retention, &state.startup, |report| {
+                    let source = source.clone();
+                    async move {
+                        tracing::info!("Purged {} deleted transactions", report.purged);
+                        record_retention_purge(&report, &source).await;
+                    }
+                })
+                .await;
+            });
+
+            // Store every security audit entry so the chain outlives the process
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let state = app_handle.state::<AppState>();
+                if let Err(e) = attach_security_audit_storage(state.database_manager.clone()).await {
+                    tracing::error!("Security audit events will not be stored: {}", e);
+                }
+            });
             Ok(())
         })
         .build(generate_context!())?;
@@ -157,6 +286,26 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Security self-tests run before the app accepts commands
+///
+/// The vault and the database are critical; the SQL injection suite only
+/// reports weaknesses in the query validator.
+async fn run_startup_self_tests(app_handle: AppHandle) -> Vec<StartupCheckResult> {
+    tracing::info!("Running startup security self-tests");
+
+    let vault = get_vault(app_handle).await.map(|_| ());
+    let database = match DatabaseManager::with_config(DatabaseConfig::default()).await {
+        Ok(database) => database.health_check().await,
+        Err(e) => Err(e),
+    };
+
+    vec![
+        StartupCheckResult::from_outcome("vault", true, vault),
+        StartupCheckResult::from_outcome("database", true, database),
+        StartupCheckResult::from_outcome("sql_injection", false, run_security_startup_tests().await),
+    ]
+}
+
 /*
 fn setup_application(app: &mut App) -> tauri::Result<()> {
     tracing::info!("Setting up Atlas Financial Desktop application");
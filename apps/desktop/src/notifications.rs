@@ -0,0 +1,293 @@
+// Notification Delivery for Atlas Desktop
+// Decides when commands notify the user and hands notifications to a channel
+
+use std::fmt;
+use std::sync::{Arc, OnceLock};
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::anomaly::AnomalyFinding;
+use crate::security::pii_text;
+use crate::utils::NotificationSettings;
+
+/// A message for the user
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Notification {
+    pub title: String,
+    pub body: String,
+}
+
+impl Notification {
+    pub fn new(title: impl Into<String>, body: impl Into<String>) -> Self {
+        Self { title: title.into(), body: body.into() }
+    }
+}
+
+/// Somewhere notifications can be delivered
+pub trait Notifier: Send + Sync + fmt::Debug {
+    fn notify(&self, notification: &Notification) -> Result<(), String>;
+}
+
+/// Native OS notifications through the Tauri notification plugin
+///
+/// The app handle only exists once Tauri has started, so it is attached
+/// during setup; anything sent before then is logged and dropped.
+#[derive(Default)]
+pub struct DesktopNotifier {
+    app: OnceLock<AppHandle>,
+}
+
+impl DesktopNotifier {
+    pub fn attach(&self, app: AppHandle) {
+        let _ = self.app.set(app);
+    }
+}
+
+impl fmt::Debug for DesktopNotifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DesktopNotifier")
+            .field("attached", &self.app.get().is_some())
+            .finish()
+    }
+}
+
+impl Notifier for DesktopNotifier {
+    fn notify(&self, notification: &Notification) -> Result<(), String> {
+        use tauri_plugin_notification::NotificationExt;
+
+        let Some(app) = self.app.get() else {
+            tracing::warn!("Dropping notification sent before startup: {}", notification.title);
+            return Ok(());
+        };
+        app.notification()
+            .builder()
+            .title(&notification.title)
+            .body(&notification.body)
+            .show()
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Writes notifications to the application log instead of showing them
+///
+/// Bodies quote transaction descriptions, so they are redacted according to
+/// the log redaction settings.
+#[derive(Debug, Default)]
+pub struct LogNotifier;
+
+impl Notifier for LogNotifier {
+    fn notify(&self, notification: &Notification) -> Result<(), String> {
+        tracing::info!("Notification: {}: {}", notification.title, pii_text(&notification.body));
+        Ok(())
+    }
+}
+
+/// Discards every notification
+#[derive(Debug, Default)]
+pub struct NoopNotifier;
+
+impl Notifier for NoopNotifier {
+    fn notify(&self, _notification: &Notification) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Where notifications are delivered
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NotificationChannel {
+    #[default]
+    Desktop,
+    Log,
+    None,
+}
+
+/// Limits deciding when command results are worth notifying about
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationThresholds {
+    /// ML categorizations below this confidence (0 to 1) ask for a review
+    pub low_confidence_below: f64,
+    /// Urgent insights needed in one batch before the user is alerted
+    pub min_urgent_insights: usize,
+}
+
+impl Default for NotificationThresholds {
+    fn default() -> Self {
+        Self {
+            low_confidence_below: 0.7,
+            min_urgent_insights: 1,
+        }
+    }
+}
+
+/// Notification triggers for commands, shared through `AppState`
+///
+/// Large and unusual transactions are judged by the anomaly rules in
+/// `NotificationSettings::anomaly_detection`; the other triggers use
+/// `NotificationThresholds`. Nothing is sent while notifications are disabled.
+#[derive(Debug, Clone)]
+pub struct Notifications {
+    notifier: Arc<dyn Notifier>,
+    thresholds: NotificationThresholds,
+    enabled: bool,
+}
+
+impl Notifications {
+    pub fn new(notifier: Arc<dyn Notifier>, thresholds: NotificationThresholds) -> Self {
+        Self { notifier, thresholds, enabled: true }
+    }
+
+    /// Notifications for the configured channel
+    ///
+    /// Returns the desktop notifier as well when that channel is chosen, so
+    /// it can be attached to the app handle once Tauri is running.
+    pub fn from_settings(settings: &NotificationSettings) -> (Self, Option<Arc<DesktopNotifier>>) {
+        let (notifier, desktop): (Arc<dyn Notifier>, _) = match settings.channel {
+            NotificationChannel::Desktop => {
+                let desktop = Arc::new(DesktopNotifier::default());
+                (desktop.clone(), Some(desktop))
+            }
+            NotificationChannel::Log => (Arc::new(LogNotifier), None),
+            NotificationChannel::None => (Arc::new(NoopNotifier), None),
+        };
+        let notifications = Self {
+            enabled: settings.enabled,
+            ..Self::new(notifier, settings.thresholds.clone())
+        };
+        (notifications, desktop)
+    }
+
+    /// Deliver `notification`, logging rather than failing if delivery fails
+    pub fn send(&self, notification: Notification) {
+        if !self.enabled {
+            return;
+        }
+        if let Err(e) = self.notifier.notify(&notification) {
+            tracing::warn!("Failed to deliver notification '{}': {}", notification.title, e);
+        }
+    }
+
+    /// A transaction was saved; notify when it tripped an anomaly rule
+    pub fn transaction_added(&self, description: &str, anomalies: &[AnomalyFinding]) {
+        if let Some(anomaly) = anomalies.first() {
+            self.send(Notification::new(
+                "Unusual Transaction Added",
+                format!("{}: {}", description, anomaly.message),
+            ));
+        }
+    }
+
+    /// A transaction was categorized; ask for review when confidence is low
+    pub fn transaction_categorized(&self, description: &str, confidence: Option<f64>) {
+        if let Some(confidence) = confidence.filter(|c| *c < self.thresholds.low_confidence_below) {
+            self.send(Notification::new(
+                "Low Confidence Categorization",
+                format!(
+                    "Transaction '{}' was categorized with {}% confidence. Please review.",
+                    description,
+                    (confidence * 100.0) as i32
+                ),
+            ));
+        }
+    }
+
+    /// Insights were generated; alert when enough of them are urgent
+    pub fn insights_generated(&self, urgent_count: usize) {
+        if urgent_count > 0 && urgent_count >= self.thresholds.min_urgent_insights {
+            self.send(Notification::new(
+                "Urgent Financial Insights",
+                format!("You have {} urgent financial insights that need attention.", urgent_count),
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::anomaly::AnomalySettings;
+    use rust_decimal_macros::dec;
+    use std::sync::Mutex;
+
+    /// Keeps every notification instead of showing it
+    #[derive(Debug, Default)]
+    struct RecordingNotifier {
+        sent: Mutex<Vec<Notification>>,
+    }
+
+    impl RecordingNotifier {
+        fn titles(&self) -> Vec<String> {
+            self.sent.lock().unwrap().iter().map(|n| n.title.clone()).collect()
+        }
+    }
+
+    impl Notifier for RecordingNotifier {
+        fn notify(&self, notification: &Notification) -> Result<(), String> {
+            self.sent.lock().unwrap().push(notification.clone());
+            Ok(())
+        }
+    }
+
+    fn recording(thresholds: NotificationThresholds) -> (Notifications, Arc<RecordingNotifier>) {
+        let recorder = Arc::new(RecordingNotifier::default());
+        (Notifications::new(recorder.clone(), thresholds), recorder)
+    }
+
+    #[test]
+    fn test_triggers_fire_only_past_their_thresholds() {
+        let (notifications, recorder) = recording(NotificationThresholds::default());
+        let anomalies = AnomalySettings::default();
+
+        notifications.transaction_added("Coffee", &anomalies.evaluate(dec!(-4.50), None, &[]));
+        notifications.transaction_added("New laptop", &anomalies.evaluate(dec!(-1899.00), None, &[]));
+        notifications.transaction_categorized("Hardware store", Some(0.92));
+        notifications.transaction_categorized("Unknown merchant", Some(0.41));
+        notifications.transaction_categorized("Manual entry", None);
+        notifications.insights_generated(0);
+        notifications.insights_generated(2);
+
+        assert_eq!(
+            recorder.titles(),
+            vec!["Unusual Transaction Added", "Low Confidence Categorization", "Urgent Financial Insights"]
+        );
+        let sent = recorder.sent.lock().unwrap();
+        assert!(sent[0].body.starts_with("New laptop: "));
+        assert!(sent[1].body.contains("'Unknown merchant' was categorized with 41% confidence"));
+        assert!(sent[2].body.contains("2 urgent"));
+    }
+
+    #[test]
+    fn test_configured_thresholds_replace_defaults() {
+        let (notifications, recorder) = recording(NotificationThresholds {
+            low_confidence_below: 0.95,
+            min_urgent_insights: 3,
+        });
+
+        notifications.transaction_categorized("Hardware store", Some(0.92));
+        notifications.insights_generated(2);
+        notifications.insights_generated(3);
+        assert_eq!(recorder.titles(), vec!["Low Confidence Categorization", "Urgent Financial Insights"]);
+
+        // Disabled notifications never reach the channel
+        let disabled = Notifications { enabled: false, ..notifications };
+        disabled.insights_generated(5);
+        assert_eq!(recorder.titles().len(), 2);
+    }
+
+    #[test]
+    fn test_channel_is_chosen_from_settings() {
+        let settings = NotificationSettings {
+            channel: NotificationChannel::Log,
+            ..NotificationSettings::default()
+        };
+        let (notifications, desktop) = Notifications::from_settings(&settings);
+        assert!(desktop.is_none());
+        assert!(format!("{:?}", notifications).contains("LogNotifier"));
+
+        let (_, desktop) = Notifications::from_settings(&NotificationSettings::default());
+        assert!(desktop.is_some());
+    }
+}
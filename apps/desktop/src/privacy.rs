@@ -0,0 +1,148 @@
+// Privacy Mode for Atlas Desktop
+// Masks monetary amounts in list and overview responses against over-the-shoulder viewing
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize, Serializer};
+use serde_json::Value;
+use std::str::FromStr;
+
+/// Shown in place of an amount hidden by privacy mode
+pub const MASKED_AMOUNT: &str = "••••";
+
+/// How amounts appear while privacy mode is on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AmountMasking {
+    /// Every amount reads `••••`
+    #[default]
+    Hidden,
+    /// Amounts read as their order of magnitude, e.g. `1K–10K`
+    Bucketed,
+}
+
+impl AmountMasking {
+    /// Masked text for an amount of `amount`
+    pub fn mask(self, amount: Decimal) -> String {
+        match self {
+            AmountMasking::Hidden => MASKED_AMOUNT.to_string(),
+            AmountMasking::Bucketed => amount_bucket(amount.abs()).to_string(),
+        }
+    }
+}
+
+const AMOUNT_BUCKETS: [(i64, &str); 6] = [
+    (10, "under 10"),
+    (100, "10–100"),
+    (1_000, "100–1K"),
+    (10_000, "1K–10K"),
+    (100_000, "10K–100K"),
+    (1_000_000, "100K–1M"),
+];
+
+fn amount_bucket(amount: Decimal) -> &'static str {
+    AMOUNT_BUCKETS
+        .iter()
+        .find(|(ceiling, _)| amount < Decimal::from(*ceiling))
+        .map_or("1M+", |(_, label)| label)
+}
+
+/// Masking for a user with `privacy_mode` on, unless they explicitly asked to `unmask`
+pub fn masking_for(privacy_mode: bool, unmask: bool, masking: AmountMasking) -> Option<AmountMasking> {
+    (privacy_mode && !unmask).then_some(masking)
+}
+
+/// Mask every money value in `value` in place
+///
+/// Money values are objects carrying both `amount` and `currency`, as every
+/// amount in command responses is; the currency stays visible.
+pub fn mask_amounts(value: &mut Value, masking: AmountMasking) {
+    match value {
+        Value::Object(fields) => {
+            if fields.contains_key("currency") {
+                if let Some(amount) = fields.get_mut("amount") {
+                    let exact = match amount {
+                        Value::Number(number) => Decimal::from_str(&number.to_string()).ok(),
+                        Value::String(text) => Decimal::from_str(text).ok(),
+                        _ => None,
+                    };
+                    *amount = Value::String(exact.map_or_else(|| MASKED_AMOUNT.to_string(), |exact| masking.mask(exact)));
+                    return;
+                }
+            }
+            fields.values_mut().for_each(|field| mask_amounts(field, masking));
+        }
+        Value::Array(items) => items.iter_mut().for_each(|item| mask_amounts(item, masking)),
+        _ => {}
+    }
+}
+
+/// Response data whose amounts are masked on the way out when privacy mode applies
+#[derive(Debug)]
+pub struct PrivacyMasked<T> {
+    data: T,
+    masking: Option<AmountMasking>,
+}
+
+impl<T> PrivacyMasked<T> {
+    pub fn new(data: T, masking: Option<AmountMasking>) -> Self {
+        Self { data, masking }
+    }
+
+    /// Exact values, whatever the mode
+    pub fn exact(data: T) -> Self {
+        Self::new(data, None)
+    }
+
+    pub fn into_inner(self) -> T {
+        self.data
+    }
+}
+
+impl<T: Serialize> Serialize for PrivacyMasked<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let Some(masking) = self.masking else {
+            return self.data.serialize(serializer);
+        };
+        let mut value = serde_json::to_value(&self.data).map_err(serde::ser::Error::custom)?;
+        mask_amounts(&mut value, masking);
+        value.serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn accounts() -> Value {
+        json!([
+            { "name": "Checking", "balance": { "amount": "2450.17", "currency": "USD" } },
+            { "name": "Visa", "balance": { "amount": -812.5, "currency": "USD" } },
+        ])
+    }
+
+    #[test]
+    fn test_amounts_masked_under_privacy_mode() {
+        let masked = serde_json::to_value(PrivacyMasked::new(accounts(), masking_for(true, false, AmountMasking::Hidden))).unwrap();
+        assert_eq!(masked[0]["balance"]["amount"], MASKED_AMOUNT);
+        assert_eq!(masked[1]["balance"]["amount"], MASKED_AMOUNT);
+        assert_eq!(masked[0]["balance"]["currency"], "USD");
+        assert_eq!(masked[0]["name"], "Checking");
+
+        let bucketed = serde_json::to_value(PrivacyMasked::new(accounts(), Some(AmountMasking::Bucketed))).unwrap();
+        assert_eq!(bucketed[0]["balance"]["amount"], "1K–10K");
+        assert_eq!(bucketed[1]["balance"]["amount"], "100–1K");
+        assert_eq!(AmountMasking::Bucketed.mask(Decimal::new(5, 0)), "under 10");
+        assert_eq!(AmountMasking::Bucketed.mask(Decimal::new(2_500_000, 0)), "1M+");
+    }
+
+    #[test]
+    fn test_amounts_exact_in_normal_mode_or_when_unmasked() {
+        for masking in [masking_for(false, false, AmountMasking::Hidden), masking_for(true, true, AmountMasking::Hidden)] {
+            assert_eq!(masking, None);
+            let exact = serde_json::to_value(PrivacyMasked::new(accounts(), masking)).unwrap();
+            assert_eq!(exact, accounts());
+        }
+        assert_eq!(serde_json::to_value(PrivacyMasked::exact(accounts())).unwrap(), accounts());
+    }
+}
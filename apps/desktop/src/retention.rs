@@ -0,0 +1,98 @@
+// Data Retention for Atlas Desktop
+// Permanently removes transactions once they have been soft-deleted for too long
+
+use std::future::Future;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::financial::FinancialError;
+use crate::security::startup_gate::StartupGate;
+use crate::storage::{DatabaseManager, TransactionRepository};
+
+/// How long soft-deleted transactions are kept and how often they are purged
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataRetentionSettings {
+    /// Whether the scheduled purge runs; manual purges work either way
+    pub purge_enabled: bool,
+    /// Days a soft-deleted transaction is kept before it is purged
+    pub soft_delete_retention_days: u32,
+    pub purge_interval_hours: u32,
+}
+
+impl DataRetentionSettings {
+    /// Transactions soft-deleted before this instant are due for purging;
+    /// the retention window is never shorter than one day
+    pub fn cutoff(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        now - chrono::Duration::days(i64::from(self.soft_delete_retention_days.max(1)))
+    }
+
+    /// Time between scheduled purges, at least one hour
+    pub fn purge_interval(&self) -> Duration {
+        Duration::from_secs(u64::from(self.purge_interval_hours.max(1)) * 3600)
+    }
+}
+
+impl Default for DataRetentionSettings {
+    fn default() -> Self {
+        Self {
+            purge_enabled: true,
+            soft_delete_retention_days: 90,
+            purge_interval_hours: 24,
+        }
+    }
+}
+
+/// Outcome of one purge
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PurgeReport {
+    /// Transactions soft-deleted before this instant were removed
+    pub cutoff: DateTime<Utc>,
+    pub purged: u64,
+}
+
+/// Hard-delete every transaction soft-deleted longer ago than the retention window
+pub async fn purge_expired(
+    db: &DatabaseManager,
+    settings: &DataRetentionSettings,
+    now: DateTime<Utc>,
+) -> Result<PurgeReport, FinancialError> {
+    let cutoff = settings.cutoff(now);
+    let purged = TransactionRepository::new(db).purge_soft_deleted(cutoff).await?;
+    Ok(PurgeReport { cutoff, purged })
+}
+
+/// Purge every `purge_interval` and pass each report to `on_purge`
+///
+/// Runs for the lifetime of the app; a failed purge is logged and retried on
+/// the next tick. Does nothing when scheduled purges are disabled, and skips
+/// every tick while `startup` has the app running read-only.
+pub async fn run<F, Fut>(
+    db: &DatabaseManager,
+    settings: &DataRetentionSettings,
+    startup: &StartupGate,
+    mut on_purge: F,
+) where
+    F: FnMut(PurgeReport) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    if !settings.purge_enabled {
+        return;
+    }
+
+    let mut ticker = tokio::time::interval(settings.purge_interval());
+    loop {
+        ticker.tick().await;
+        if let Err(reason) = startup.ensure_writable() {
+            tracing::warn!("Skipping scheduled purge of deleted transactions: {}", reason);
+            continue;
+        }
+        match purge_expired(db, settings, Utc::now()).await {
+            Ok(report) => on_purge(report).await,
+            Err(e) => tracing::error!("Scheduled purge of deleted transactions failed: {}", e),
+        }
+    }
+}
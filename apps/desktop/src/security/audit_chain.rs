@@ -0,0 +1,306 @@
+// Tamper-Evident Audit Chain for Atlas Financial Desktop
+// Each entry carries its own hash plus the hash of the entry before it
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+/// Hash used as the predecessor of the very first entry in a chain
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Chain verification errors, each identifying the first corrupted entry
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ChainVerificationError {
+    #[error("Entry {index} (sequence {sequence}) content hash does not match its recorded hash")]
+    HashMismatch { index: usize, sequence: u64 },
+    #[error("Entry {index} (sequence {sequence}) does not link to the previous entry's hash")]
+    BrokenLink { index: usize, sequence: u64 },
+    #[error("Entry {index} has sequence {found}, expected {expected}")]
+    SequenceGap { index: usize, expected: u64, found: u64 },
+}
+
+impl ChainVerificationError {
+    /// Position of the first corrupted entry in the verified slice
+    pub fn first_corrupted_index(&self) -> usize {
+        match self {
+            Self::HashMismatch { index, .. }
+            | Self::BrokenLink { index, .. }
+            | Self::SequenceGap { index, .. } => *index,
+        }
+    }
+}
+
+/// Records that can be appended to an audit chain
+pub trait ChainContent {
+    /// Canonical, stable representation of the record used for hashing
+    fn chain_content(&self) -> String;
+}
+
+/// A record together with its position and hashes in the chain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChainedEntry<T> {
+    pub sequence: u64,
+    pub record: T,
+    pub previous_hash: String,
+    pub hash: String,
+}
+
+/// Append-only hash chain of audit records
+///
+/// When old entries are pruned the chain remembers the hash and sequence of
+/// the last dropped entry, so the retained tail still verifies end-to-end.
+#[derive(Debug, Clone)]
+pub struct AuditChain<T> {
+    entries: Vec<ChainedEntry<T>>,
+    anchor_hash: String,
+    anchor_sequence: u64,
+}
+
+impl<T: ChainContent> AuditChain<T> {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            anchor_hash: GENESIS_HASH.to_string(),
+            anchor_sequence: 0,
+        }
+    }
+
+    /// Empty chain continuing after an entry kept elsewhere, such as the last
+    /// one written to storage
+    pub fn resume(next_sequence: u64, previous_hash: String) -> Self {
+        Self {
+            entries: Vec::new(),
+            anchor_hash: previous_hash,
+            anchor_sequence: next_sequence,
+        }
+    }
+
+    /// Append a record, linking it to the current head of the chain
+    pub fn append(&mut self, record: T) -> &ChainedEntry<T> {
+        let (sequence, previous_hash) = match self.entries.last() {
+            Some(last) => (last.sequence + 1, last.hash.clone()),
+            None => (self.anchor_sequence, self.anchor_hash.clone()),
+        };
+
+        let hash = compute_entry_hash(sequence, &previous_hash, &record.chain_content());
+        self.entries.push(ChainedEntry {
+            sequence,
+            record,
+            previous_hash,
+            hash,
+        });
+
+        self.entries.last().expect("entry was just pushed")
+    }
+
+    /// Drop the oldest entries so that at most `max_entries` remain
+    pub fn prune_to(&mut self, max_entries: usize) {
+        if self.entries.len() <= max_entries {
+            return;
+        }
+
+        let drop_count = self.entries.len() - max_entries;
+        let last_dropped = &self.entries[drop_count - 1];
+        self.anchor_hash = last_dropped.hash.clone();
+        self.anchor_sequence = last_dropped.sequence + 1;
+        self.entries.drain(0..drop_count);
+    }
+
+    pub fn entries(&self) -> &[ChainedEntry<T>] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Hash of the most recent entry, or the anchor if the chain is empty
+    pub fn head_hash(&self) -> &str {
+        self.entries
+            .last()
+            .map(|e| e.hash.as_str())
+            .unwrap_or(&self.anchor_hash)
+    }
+
+    /// Recompute the whole chain and report the first corrupted entry
+    pub fn verify_chain(&self) -> Result<(), ChainVerificationError> {
+        verify_chain(&self.entries, &self.anchor_hash, self.anchor_sequence)
+    }
+}
+
+impl<T: ChainContent> Default for AuditChain<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SHA-256 over the sequence, predecessor hash and record content
+pub fn compute_entry_hash(sequence: u64, previous_hash: &str, content: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(sequence.to_be_bytes());
+    hasher.update((previous_hash.len() as u64).to_be_bytes());
+    hasher.update(previous_hash.as_bytes());
+    hasher.update((content.len() as u64).to_be_bytes());
+    hasher.update(content.as_bytes());
+
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Verify a sequence of entries starting from a known anchor
+///
+/// Detects modified entries (hash mismatch), inserted or reordered entries
+/// (broken link or sequence gap) and deleted entries (sequence gap).
+pub fn verify_chain<T: ChainContent>(
+    entries: &[ChainedEntry<T>],
+    anchor_hash: &str,
+    anchor_sequence: u64,
+) -> Result<(), ChainVerificationError> {
+    let mut expected_sequence = anchor_sequence;
+    let mut expected_previous = anchor_hash;
+
+    for (index, entry) in entries.iter().enumerate() {
+        if entry.sequence != expected_sequence {
+            return Err(ChainVerificationError::SequenceGap {
+                index,
+                expected: expected_sequence,
+                found: entry.sequence,
+            });
+        }
+
+        if entry.previous_hash != expected_previous {
+            return Err(ChainVerificationError::BrokenLink {
+                index,
+                sequence: entry.sequence,
+            });
+        }
+
+        let recomputed =
+            compute_entry_hash(entry.sequence, &entry.previous_hash, &entry.record.chain_content());
+        if recomputed != entry.hash {
+            return Err(ChainVerificationError::HashMismatch {
+                index,
+                sequence: entry.sequence,
+            });
+        }
+
+        expected_sequence += 1;
+        expected_previous = &entry.hash;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone)]
+    struct TestRecord(String);
+
+    impl ChainContent for TestRecord {
+        fn chain_content(&self) -> String {
+            self.0.clone()
+        }
+    }
+
+    fn build_chain(count: usize) -> AuditChain<TestRecord> {
+        let mut chain = AuditChain::new();
+        for i in 0..count {
+            chain.append(TestRecord(format!("event-{}", i)));
+        }
+        chain
+    }
+
+    #[test]
+    fn test_intact_chain_verifies() {
+        let chain = build_chain(5);
+        assert_eq!(chain.len(), 5);
+        assert_eq!(chain.entries()[0].previous_hash, GENESIS_HASH);
+        assert!(chain.verify_chain().is_ok());
+    }
+
+    #[test]
+    fn test_modified_middle_record_is_flagged() {
+        let chain = build_chain(5);
+        let mut entries = chain.entries().to_vec();
+        entries[2].record = TestRecord("tampered".to_string());
+
+        let err = verify_chain(&entries, GENESIS_HASH, 0).unwrap_err();
+        assert_eq!(err, ChainVerificationError::HashMismatch { index: 2, sequence: 2 });
+        assert_eq!(err.first_corrupted_index(), 2);
+    }
+
+    #[test]
+    fn test_rehashed_middle_record_breaks_next_link() {
+        let chain = build_chain(5);
+        let mut entries = chain.entries().to_vec();
+        entries[2].record = TestRecord("tampered".to_string());
+        entries[2].hash = compute_entry_hash(2, &entries[2].previous_hash, "tampered");
+
+        let err = verify_chain(&entries, GENESIS_HASH, 0).unwrap_err();
+        assert_eq!(err, ChainVerificationError::BrokenLink { index: 3, sequence: 3 });
+    }
+
+    #[test]
+    fn test_deleted_record_is_flagged() {
+        let chain = build_chain(5);
+        let mut entries = chain.entries().to_vec();
+        entries.remove(2);
+
+        let err = verify_chain(&entries, GENESIS_HASH, 0).unwrap_err();
+        assert_eq!(err.first_corrupted_index(), 2);
+        assert!(matches!(err, ChainVerificationError::SequenceGap { expected: 2, found: 3, .. }));
+    }
+
+    #[test]
+    fn test_inserted_record_is_flagged() {
+        let chain = build_chain(5);
+        let mut entries = chain.entries().to_vec();
+        let forged = ChainedEntry {
+            sequence: 2,
+            record: TestRecord("forged".to_string()),
+            previous_hash: entries[1].hash.clone(),
+            hash: compute_entry_hash(2, &entries[1].hash, "forged"),
+        };
+        entries.insert(2, forged);
+
+        let err = verify_chain(&entries, GENESIS_HASH, 0).unwrap_err();
+        assert_eq!(err.first_corrupted_index(), 3);
+    }
+
+    #[test]
+    fn test_pruned_chain_still_verifies() {
+        let mut chain = build_chain(10);
+        chain.prune_to(4);
+
+        assert_eq!(chain.len(), 4);
+        assert_eq!(chain.entries()[0].sequence, 6);
+        assert!(chain.verify_chain().is_ok());
+
+        chain.append(TestRecord("after-prune".to_string()));
+        assert_eq!(chain.entries().last().unwrap().sequence, 10);
+        assert!(chain.verify_chain().is_ok());
+    }
+
+    #[test]
+    fn test_resumed_chain_links_to_the_stored_entries() {
+        let stored = build_chain(3);
+        let head = stored.entries().last().unwrap();
+        let mut resumed = AuditChain::resume(head.sequence + 1, head.hash.clone());
+        assert_eq!(resumed.head_hash(), head.hash);
+        resumed.append(TestRecord("after-restart".to_string()));
+
+        let mut entries = stored.entries().to_vec();
+        entries.extend(resumed.entries().iter().cloned());
+        assert_eq!(entries[3].sequence, 3);
+        assert!(verify_chain(&entries, GENESIS_HASH, 0).is_ok());
+    }
+}
@@ -0,0 +1,195 @@
+// Idle Auto-Lock for Atlas Financial Desktop
+// Wipes the in-memory vault key and ends the session after a period without activity
+
+use std::future::Future;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+use crate::security::session_guard::SessionGuard;
+use crate::security::vault::lock_vault;
+
+/// How often the background task checks for an idle session
+pub const AUTO_LOCK_POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Idle timeout taken from the user's security settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AutoLockPolicy {
+    pub enabled: bool,
+    pub timeout: Duration,
+}
+
+impl AutoLockPolicy {
+    /// Policy for `auto_lock_enabled` / `auto_lock_timeout_minutes`; the
+    /// timeout is never shorter than one minute
+    pub fn new(enabled: bool, timeout_minutes: u32) -> Self {
+        Self {
+            enabled,
+            timeout: Duration::from_secs(u64::from(timeout_minutes.max(1)) * 60),
+        }
+    }
+
+    /// Whether a session idle for `idle` must be locked
+    pub fn is_due(&self, idle: Duration) -> bool {
+        self.enabled && idle >= self.timeout
+    }
+}
+
+impl Default for AutoLockPolicy {
+    fn default() -> Self {
+        Self::new(true, 15)
+    }
+}
+
+/// Current auto-lock policy, shared by commands and the background task
+#[derive(Debug, Default)]
+pub struct AutoLock {
+    policy: RwLock<AutoLockPolicy>,
+}
+
+impl AutoLock {
+    pub fn new(policy: AutoLockPolicy) -> Self {
+        Self {
+            policy: RwLock::new(policy),
+        }
+    }
+
+    pub async fn policy(&self) -> AutoLockPolicy {
+        *self.policy.read().await
+    }
+
+    /// Apply updated security settings; takes effect on the next check
+    pub async fn configure(&self, policy: AutoLockPolicy) {
+        *self.policy.write().await = policy;
+    }
+
+    /// Whether the signed-in session has been idle past the timeout
+    pub async fn is_due(&self, session: &SessionGuard) -> bool {
+        match session.idle_for().await {
+            Some(idle) => self.policy().await.is_due(idle),
+            None => false,
+        }
+    }
+
+    /// Check the session every `poll_interval` and call `lock` once it goes idle
+    ///
+    /// Runs for the lifetime of the app. `lock` is expected to sign the session
+    /// out, so it runs at most once per sign-in.
+    pub async fn run<F, Fut>(&self, session: &SessionGuard, poll_interval: Duration, mut lock: F)
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = ()>,
+    {
+        let mut ticker = tokio::time::interval(poll_interval);
+        loop {
+            ticker.tick().await;
+            if self.is_due(session).await {
+                tracing::info!("🔒 Session idle past auto-lock timeout, locking");
+                lock().await;
+            }
+        }
+    }
+}
+
+/// Lock the app: wipe the vault key and end the session so the user must
+/// authenticate again
+pub async fn lock_app(session: &SessionGuard) {
+    lock_vault();
+    session.sign_out().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::session_guard::AuthenticatedUser;
+    use crate::security::vault::{MasterKey, VaultError};
+    use chrono::Utc;
+    use std::sync::Arc;
+    use tokio::sync::Mutex;
+
+    async fn signed_in_session() -> Arc<SessionGuard> {
+        let session = Arc::new(SessionGuard::new());
+        session.sign_in(AuthenticatedUser {
+            user_id: "user-a".to_string(),
+            expires_at: Utc::now() + chrono::Duration::hours(8),
+            permissions: vec![],
+        }).await;
+        session
+    }
+
+    fn loaded_key() -> Arc<Mutex<MasterKey>> {
+        let mut key = MasterKey::default();
+        key.set([7u8; 32]);
+        Arc::new(Mutex::new(key))
+    }
+
+    /// Run the auto-lock loop against a test key instead of the global vault
+    fn spawn_monitor(auto_lock: Arc<AutoLock>, session: Arc<SessionGuard>, key: Arc<Mutex<MasterKey>>) {
+        tokio::spawn(async move {
+            auto_lock
+                .run(&session, AUTO_LOCK_POLL_INTERVAL, || async {
+                    key.lock().await.lock();
+                    session.sign_out().await;
+                })
+                .await;
+        });
+    }
+
+    #[test]
+    fn test_policy_from_settings() {
+        let policy = AutoLockPolicy::new(true, 15);
+        assert!(!policy.is_due(Duration::from_secs(14 * 60)));
+        assert!(policy.is_due(Duration::from_secs(15 * 60)));
+
+        assert!(!AutoLockPolicy::new(false, 15).is_due(Duration::from_secs(24 * 3600)));
+        assert_eq!(AutoLockPolicy::new(true, 0).timeout, Duration::from_secs(60));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_idle_timeout_locks_vault_and_session() {
+        let session = signed_in_session().await;
+        let key = loaded_key();
+        let ciphertext = key.lock().await.encrypt("account 4417-1234").unwrap();
+        let auto_lock = Arc::new(AutoLock::new(AutoLockPolicy::new(true, 5)));
+        spawn_monitor(auto_lock, session.clone(), key.clone());
+
+        // Activity keeps the session alive past the timeout
+        tokio::time::sleep(Duration::from_secs(4 * 60)).await;
+        session.authorize_user("user-a").await.unwrap();
+        tokio::time::sleep(Duration::from_secs(4 * 60)).await;
+        assert!(session.current_user().await.is_ok());
+        assert_eq!(key.lock().await.decrypt(&ciphertext).unwrap(), "account 4417-1234");
+
+        // Going idle locks within one poll interval of the timeout
+        tokio::time::sleep(Duration::from_secs(60) + AUTO_LOCK_POLL_INTERVAL).await;
+        assert!(session.current_user().await.is_err());
+        assert!(session.authorize_user("user-a").await.is_err());
+        assert!(matches!(key.lock().await.decrypt(&ciphertext), Err(VaultError::Locked)));
+
+        // Decryption stays refused until the key is unlocked and reloaded
+        tokio::time::sleep(Duration::from_secs(3600)).await;
+        assert!(matches!(key.lock().await.decrypt(&ciphertext), Err(VaultError::Locked)));
+
+        let mut unlocked = key.lock().await;
+        unlocked.unlock();
+        unlocked.set([7u8; 32]);
+        assert_eq!(unlocked.decrypt(&ciphertext).unwrap(), "account 4417-1234");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_disabled_policy_never_locks() {
+        let session = signed_in_session().await;
+        let key = loaded_key();
+        let auto_lock = Arc::new(AutoLock::new(AutoLockPolicy::new(false, 1)));
+        spawn_monitor(auto_lock.clone(), session.clone(), key.clone());
+
+        tokio::time::sleep(Duration::from_secs(3600)).await;
+        assert!(session.current_user().await.is_ok());
+        assert!(!key.lock().await.is_locked());
+
+        // Enabling it later applies to the session's existing idle time
+        auto_lock.configure(AutoLockPolicy::new(true, 1)).await;
+        tokio::time::sleep(AUTO_LOCK_POLL_INTERVAL).await;
+        assert!(session.current_user().await.is_err());
+        assert!(key.lock().await.is_locked());
+    }
+}
@@ -0,0 +1,216 @@
+// Command Policy for Atlas Financial Desktop
+// Rules every IPC command passes through before its handler runs
+
+use crate::security::session_guard::SessionGuard;
+use crate::security::startup_gate::StartupGate;
+
+/// Commands that change stored data or settings
+///
+/// While startup checks have put the app in read-only mode these are
+/// refused before their handlers run. Add every new mutating command here.
+pub const WRITE_COMMANDS: &[&str] = &[
+    "rotate_security_keys",
+    "admin_unlock_account",
+    "whitelist_ip_address",
+    "archive_account",
+    "reactivate_account",
+    "delete_account",
+    "add_transaction",
+    "update_transaction",
+    "delete_transaction",
+    "add_transaction_attachment",
+    "remove_transaction_attachment",
+    "categorize_transaction",
+    "bulk_categorize",
+    "merge_transactions",
+    "purge_deleted_transactions",
+    "recompute_financials",
+    "dismiss_insight",
+    "snooze_insight",
+    "set_budget",
+    "import_financial_data",
+    "download_update",
+    "install_update",
+    "manage_app_data_directory",
+    "schedule_recurring_notifications",
+    "update_user_preferences",
+    "reset_preferences_to_default",
+    "update_precision_settings",
+    "configure_transaction_defaults",
+    "manage_biometric_settings",
+    "configure_backup_preferences",
+    "backup_now",
+    "restore_backup",
+    "manage_layout_preferences",
+    "update_performance_settings",
+];
+
+/// Commands that leave stored data and settings unchanged
+///
+/// These keep running in read-only mode so the user can still sign in, see
+/// their data and export it. Security events are still appended to the audit
+/// log so incidents are recorded. Every registered command is in exactly one
+/// of this list and [`WRITE_COMMANDS`].
+pub const READ_ONLY_COMMANDS: &[&str] = &[
+    "authenticate_user",
+    "logout_user",
+    "get_session_status",
+    "lock_now",
+    "get_security_stats",
+    "get_accounts",
+    "get_account_details",
+    "get_transactions",
+    "get_financial_overview",
+    "calculate_net_worth",
+    "list_transaction_attachments",
+    "find_duplicate_transactions",
+    "get_brutal_honesty_insights",
+    "get_spending_analysis",
+    "get_spending_timeseries",
+    "get_budget_recommendations",
+    "estimate_taxes",
+    "forecast_cash_flow",
+    "calculate_safe_to_spend",
+    "budget_status",
+    "export_financial_data",
+    "export_debt_payment_calendar",
+    "get_system_info",
+    "get_startup_status",
+    "monitor_performance",
+    "get_disk_usage",
+    "check_for_updates",
+    "open_file_location",
+    "validate_file_permissions",
+    "send_system_notification",
+    "monitor_file_system_changes",
+    "validate_application_integrity",
+    "log_security_events",
+    "verify_security_audit_chain",
+    "open_external_url",
+    "get_user_preferences",
+    "get_currency_preferences",
+    "get_security_settings",
+    "verify_backup",
+    "get_theme_settings",
+];
+
+/// Commands the frontend issues on its own, such as status polls
+///
+/// These do not count as the user doing something, so a page that polls in
+/// the background cannot keep an idle session from auto-locking.
+pub const PASSIVE_COMMANDS: &[&str] = &[
+    "get_session_status",
+    "get_startup_status",
+    "get_system_info",
+    "monitor_performance",
+    "get_disk_usage",
+    "check_for_updates",
+    "monitor_file_system_changes",
+];
+
+/// Whether running `command` resets the auto-lock idle timer
+pub fn counts_as_activity(command: &str) -> bool {
+    !PASSIVE_COMMANDS.contains(&command)
+}
+
+/// Refuse `command` if it changes data while the app is read-only
+///
+/// A command missing from both lists is treated as a write, so one added
+/// without being classified is refused rather than let through.
+pub fn check_command_allowed(startup: &StartupGate, command: &str) -> Result<(), String> {
+    if WRITE_COMMANDS.contains(&command) || !READ_ONLY_COMMANDS.contains(&command) {
+        startup.ensure_writable()
+    } else {
+        Ok(())
+    }
+}
+
+/// Count `command` as user activity on `session` unless it is passive
+///
+/// Called for every command, whether or not its handler consults the
+/// session, so using any screen keeps the app unlocked.
+pub async fn record_command_activity(session: &SessionGuard, command: &str) {
+    if counts_as_activity(command) {
+        session.record_activity().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::session_guard::AuthenticatedUser;
+    use crate::security::startup_gate::StartupMode;
+    use chrono::Utc;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_user_commands_reset_the_idle_timer_and_polls_do_not() {
+        let session = SessionGuard::new();
+        session
+            .sign_in(AuthenticatedUser {
+                user_id: "user-1".to_string(),
+                expires_at: Utc::now() + chrono::Duration::hours(1),
+                permissions: Vec::new(),
+            })
+            .await;
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        for command in ["get_session_status", "monitor_performance"] {
+            record_command_activity(&session, command).await;
+        }
+        assert!(session.idle_for().await.unwrap() >= Duration::from_millis(50));
+
+        // Commands that never consult the session still count as activity
+        record_command_activity(&session, "get_transactions").await;
+        assert!(session.idle_for().await.unwrap() < Duration::from_millis(50));
+    }
+
+    /// Commands registered with the Tauri handler in main.rs
+    fn registered_commands() -> Vec<&'static str> {
+        let main = include_str!("../main.rs");
+        let start = main.find("generate_handler![").expect("command handler in main.rs");
+        let end = start + main[start..].find(']').expect("end of command list");
+        main[start + "generate_handler![".len()..end]
+            .lines()
+            .map(|line| line.trim().trim_end_matches(','))
+            .filter(|line| !line.is_empty() && !line.starts_with("//"))
+            .collect()
+    }
+
+    #[test]
+    fn test_read_only_mode_refuses_every_write_command() {
+        let registered = registered_commands();
+        let read_only = StartupGate::default();
+        read_only.record(StartupMode::ReadOnly { failed_checks: vec!["vault".to_string()] });
+        let normal = StartupGate::default();
+
+        for command in WRITE_COMMANDS {
+            assert!(registered.contains(command), "{} is not a registered command", command);
+            let error = check_command_allowed(&read_only, command).unwrap_err();
+            assert!(error.contains("read-only"), "{}: {}", command, error);
+            assert!(check_command_allowed(&normal, command).is_ok());
+        }
+
+        // Reads still work so the user can see their data
+        for command in READ_ONLY_COMMANDS {
+            assert!(registered.contains(command), "{} is not a registered command", command);
+            assert!(check_command_allowed(&read_only, command).is_ok(), "{}", command);
+        }
+        assert!(registered.contains(&"get_transactions"));
+
+        // Commands nobody has classified yet are refused too
+        assert!(check_command_allowed(&read_only, "unclassified_command").is_err());
+        assert!(check_command_allowed(&normal, "unclassified_command").is_ok());
+    }
+
+    #[test]
+    fn test_every_registered_command_is_either_a_write_or_read_only() {
+        for command in registered_commands() {
+            let lists = [WRITE_COMMANDS, READ_ONLY_COMMANDS]
+                .iter()
+                .filter(|list| list.contains(&command))
+                .count();
+            assert_eq!(lists, 1, "{} must be in exactly one of WRITE_COMMANDS and READ_ONLY_COMMANDS", command);
+        }
+    }
+}
@@ -0,0 +1,286 @@
+// Field-Level Encryption for Atlas Financial Desktop
+// Seals sensitive transaction columns with SecureVault before they reach the database
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::{Deserialize, Serialize};
+
+use crate::financial::FinancialError;
+use crate::security::vault::{SecureVault, VaultError};
+use crate::storage::{CreateTransactionRequest, TransactionRecord};
+
+/// Marks a stored column value as base64 vault ciphertext
+pub const ENCRYPTED_VALUE_PREFIX: &str = "enc:";
+
+/// Symmetric cipher used to seal individual column values
+pub trait FieldCipher: Send + Sync {
+    fn encrypt_field(&self, plaintext: &str) -> Result<Vec<u8>, VaultError>;
+    fn decrypt_field(&self, ciphertext: &[u8]) -> Result<String, VaultError>;
+    /// Generation of the key `encrypt_field` currently uses
+    fn key_generation(&self) -> i32;
+}
+
+impl FieldCipher for SecureVault {
+    fn encrypt_field(&self, plaintext: &str) -> Result<Vec<u8>, VaultError> {
+        self.encrypt(plaintext)
+    }
+
+    fn decrypt_field(&self, ciphertext: &[u8]) -> Result<String, VaultError> {
+        self.decrypt(ciphertext)
+    }
+
+    fn key_generation(&self) -> i32 {
+        self.get_key_metadata()
+            .map_or(0, |metadata| metadata.rotation_count as i32)
+    }
+}
+
+/// Optional transaction fields to encrypt; notes are always encrypted
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SensitiveFieldPolicy {
+    pub merchant: bool,
+    pub location: bool,
+}
+
+/// Sensitive column values exactly as they are written to the database
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SealedFields {
+    pub merchant: Option<String>,
+    pub location: Option<String>,
+    pub notes: Option<String>,
+    /// Key generation the sealed values were encrypted with
+    pub encryption_generation: i32,
+}
+
+/// Encrypt one value into its stored `enc:<base64>` form
+pub fn encrypt_value(cipher: &dyn FieldCipher, plaintext: &str) -> Result<String, FinancialError> {
+    let ciphertext = cipher
+        .encrypt_field(plaintext)
+        .map_err(|e| FinancialError::SecurityError(format!("Failed to encrypt field: {}", e)))?;
+    Ok(format!("{}{}", ENCRYPTED_VALUE_PREFIX, BASE64.encode(ciphertext)))
+}
+
+/// Decrypt a stored value; values without the encryption prefix are returned as-is
+pub fn decrypt_value(cipher: &dyn FieldCipher, stored: &str) -> Result<String, FinancialError> {
+    let Some(encoded) = stored.strip_prefix(ENCRYPTED_VALUE_PREFIX) else {
+        return Ok(stored.to_string());
+    };
+    let ciphertext = BASE64
+        .decode(encoded)
+        .map_err(|e| FinancialError::SecurityError(format!("Corrupt encrypted field: {}", e)))?;
+    cipher
+        .decrypt_field(&ciphertext)
+        .map_err(|e| FinancialError::SecurityError(format!("Failed to decrypt field: {}", e)))
+}
+
+/// Seal a value when the policy covers it
+///
+/// Plaintext that happens to start with the encryption prefix is sealed too,
+/// so any prefixed value read back is known to be ciphertext.
+fn seal(cipher: &dyn FieldCipher, value: Option<&str>, sensitive: bool) -> Result<Option<String>, FinancialError> {
+    match value {
+        Some(value) if sensitive || value.starts_with(ENCRYPTED_VALUE_PREFIX) => {
+            encrypt_value(cipher, value).map(Some)
+        }
+        Some(value) => Ok(Some(value.to_string())),
+        None => Ok(None),
+    }
+}
+
+/// Encrypt the sensitive fields of a transaction about to be persisted
+pub fn seal_transaction_fields(
+    cipher: &dyn FieldCipher,
+    policy: SensitiveFieldPolicy,
+    request: &CreateTransactionRequest,
+) -> Result<SealedFields, FinancialError> {
+    Ok(SealedFields {
+        merchant: seal(cipher, request.merchant.as_deref(), policy.merchant)?,
+        location: seal(cipher, request.location.as_deref(), policy.location)?,
+        notes: seal(cipher, request.notes.as_deref(), true)?,
+        encryption_generation: cipher.key_generation(),
+    })
+}
+
+/// Decrypt the sensitive fields of a record read from the database in place
+///
+/// Rows written before encryption was enabled have no generation and are
+/// left untouched.
+pub fn open_transaction_fields(cipher: &dyn FieldCipher, record: &mut TransactionRecord) -> Result<(), FinancialError> {
+    if record.encryption_generation.is_none() {
+        return Ok(());
+    }
+    for field in [&mut record.merchant, &mut record.location, &mut record.notes] {
+        if let Some(value) = field.as_mut() {
+            *value = decrypt_value(cipher, value)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::TransactionType;
+    use aes_gcm::{aead::Aead, AeadCore, Aes256Gcm, KeyInit, Nonce};
+    use chrono::Utc;
+    use rust_decimal::Decimal;
+
+    /// AES-256-GCM with a random in-memory key, laid out like SecureVault output
+    struct TestCipher {
+        cipher: Aes256Gcm,
+        generation: i32,
+    }
+
+    impl TestCipher {
+        fn new(generation: i32) -> Self {
+            Self {
+                cipher: Aes256Gcm::new(&Aes256Gcm::generate_key(&mut rand::thread_rng())),
+                generation,
+            }
+        }
+    }
+
+    impl FieldCipher for TestCipher {
+        fn encrypt_field(&self, plaintext: &str) -> Result<Vec<u8>, VaultError> {
+            let nonce = Aes256Gcm::generate_nonce(&mut rand::thread_rng());
+            let ciphertext = self
+                .cipher
+                .encrypt(&nonce, plaintext.as_bytes())
+                .map_err(|e| VaultError::EncryptionFailed(e.to_string()))?;
+            Ok([nonce.to_vec(), ciphertext].concat())
+        }
+
+        fn decrypt_field(&self, data: &[u8]) -> Result<String, VaultError> {
+            let (nonce, ciphertext) = data.split_at(12);
+            let plaintext = self
+                .cipher
+                .decrypt(Nonce::from_slice(nonce), ciphertext)
+                .map_err(|e| VaultError::DecryptionFailed(e.to_string()))?;
+            String::from_utf8(plaintext).map_err(|e| VaultError::DecryptionFailed(e.to_string()))
+        }
+
+        fn key_generation(&self) -> i32 {
+            self.generation
+        }
+    }
+
+    fn request(notes: Option<&str>, merchant: Option<&str>) -> CreateTransactionRequest {
+        CreateTransactionRequest {
+            user_id: "user".to_string(),
+            account_id: "account".to_string(),
+            amount: Decimal::new(-4250, 2),
+            description: "Pharmacy".to_string(),
+            category: None,
+            subcategory: None,
+            transaction_date: None,
+            transaction_type: TransactionType::Debit,
+            merchant: merchant.map(str::to_string),
+            location: Some("Springfield".to_string()),
+            is_recurring: None,
+            tags: None,
+            notes: notes.map(str::to_string),
+            ml_confidence: None,
+        }
+    }
+
+    /// Record as it would be read back from the row written with `sealed`
+    fn stored_record(sealed: &SealedFields) -> TransactionRecord {
+        let now = Utc::now();
+        TransactionRecord {
+            id: "id".to_string(),
+            user_id: "user".to_string(),
+            account_id: "account".to_string(),
+            amount: Decimal::new(-4250, 2),
+            description: "Pharmacy".to_string(),
+            category: None,
+            subcategory: None,
+            transaction_date: now,
+            created_at: now,
+            updated_at: now,
+            transaction_type: TransactionType::Debit,
+            merchant: sealed.merchant.clone(),
+            location: sealed.location.clone(),
+            is_recurring: false,
+            tags: vec![],
+            notes: sealed.notes.clone(),
+            ml_confidence: None,
+            is_active: true,
+            encryption_generation: Some(sealed.encryption_generation),
+        }
+    }
+
+    #[test]
+    fn test_notes_are_stored_as_ciphertext_and_round_trip() {
+        let cipher = TestCipher::new(3);
+        let note = "Prescription refill - account 4417-1234";
+        let sealed = seal_transaction_fields(
+            &cipher,
+            SensitiveFieldPolicy { merchant: true, location: false },
+            &request(Some(note), Some("CVS")),
+        )
+        .unwrap();
+
+        // What reaches the database never contains the plaintext
+        let stored_notes = sealed.notes.as_deref().unwrap();
+        assert!(stored_notes.starts_with(ENCRYPTED_VALUE_PREFIX));
+        assert!(!stored_notes.contains("4417"));
+        assert!(sealed.merchant.as_deref().unwrap().starts_with(ENCRYPTED_VALUE_PREFIX));
+        assert_eq!(sealed.location.as_deref(), Some("Springfield"));
+        assert_eq!(sealed.encryption_generation, 3);
+
+        // Encrypting the same note twice yields different ciphertext
+        let again = seal_transaction_fields(&cipher, SensitiveFieldPolicy::default(), &request(Some(note), None)).unwrap();
+        assert_ne!(again.notes, sealed.notes);
+
+        let mut record = stored_record(&sealed);
+        open_transaction_fields(&cipher, &mut record).unwrap();
+        assert_eq!(record.notes.as_deref(), Some(note));
+        assert_eq!(record.merchant.as_deref(), Some("CVS"));
+        assert_eq!(record.location.as_deref(), Some("Springfield"));
+    }
+
+    #[test]
+    fn test_plaintext_rows_and_prefixed_values() {
+        let cipher = TestCipher::new(0);
+
+        // Rows written before encryption was enabled are read unchanged
+        let mut legacy = stored_record(&SealedFields {
+            merchant: Some("enc:looks-encrypted".to_string()),
+            location: None,
+            notes: Some("plain note".to_string()),
+            encryption_generation: 0,
+        });
+        legacy.encryption_generation = None;
+        open_transaction_fields(&cipher, &mut legacy).unwrap();
+        assert_eq!(legacy.notes.as_deref(), Some("plain note"));
+
+        // Plaintext carrying the prefix is sealed so it cannot be mistaken for ciphertext
+        let sealed = seal_transaction_fields(
+            &cipher,
+            SensitiveFieldPolicy::default(),
+            &request(None, Some("enc:Store")),
+        )
+        .unwrap();
+        assert_ne!(sealed.merchant.as_deref(), Some("enc:Store"));
+        let mut record = stored_record(&sealed);
+        open_transaction_fields(&cipher, &mut record).unwrap();
+        assert_eq!(record.merchant.as_deref(), Some("enc:Store"));
+        assert_eq!(record.notes, None);
+    }
+
+    #[test]
+    fn test_wrong_key_cannot_decrypt() {
+        let sealed = seal_transaction_fields(
+            &TestCipher::new(1),
+            SensitiveFieldPolicy::default(),
+            &request(Some("secret"), None),
+        )
+        .unwrap();
+
+        let mut record = stored_record(&sealed);
+        assert!(matches!(
+            open_transaction_fields(&TestCipher::new(2), &mut record),
+            Err(FinancialError::SecurityError(_))
+        ));
+    }
+}
@@ -0,0 +1,264 @@
+// Log Redaction for Atlas Financial Desktop
+// Masks personal and financial values before they are written to tracing output
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::sync::RwLock;
+
+/// Upper bounds of the magnitude buckets redacted amounts are reported in
+const AMOUNT_BUCKETS: [u32; 5] = [10, 100, 1_000, 10_000, 100_000];
+
+/// Hex digits of the SHA-256 digest kept when a value is hashed
+const HASH_PREFIX_LEN: usize = 8;
+
+/// How redacted amounts are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AmountRedaction {
+    /// Order of magnitude only, e.g. `-[100, 1000)`
+    Bucket,
+    /// No information about the amount at all
+    Mask,
+}
+
+/// How redacted free text (descriptions, names, categories, emails) is written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TextRedaction {
+    /// Short digest, so repeated values can still be correlated across lines
+    Hash,
+    /// The first `keep_chars` characters followed by an ellipsis
+    Truncate { keep_chars: usize },
+}
+
+/// Redaction applied to sensitive values in log messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogRedactionPolicy {
+    pub redact_pii: bool,
+    pub amounts: AmountRedaction,
+    pub text: TextRedaction,
+}
+
+impl LogRedactionPolicy {
+    pub const DEFAULT: Self = Self {
+        redact_pii: true,
+        amounts: AmountRedaction::Bucket,
+        text: TextRedaction::Hash,
+    };
+
+    /// Log form of a description, name, or other free-text value
+    pub fn text(&self, value: &str) -> String {
+        if !self.redact_pii {
+            return value.to_string();
+        }
+        match self.text {
+            TextRedaction::Hash => {
+                let digest: String = Sha256::digest(value.as_bytes())
+                    .iter()
+                    .map(|b| format!("{:02x}", b))
+                    .collect();
+                format!("<redacted:{}>", &digest[..HASH_PREFIX_LEN])
+            }
+            TextRedaction::Truncate { keep_chars } => {
+                let kept: String = value.chars().take(keep_chars).collect();
+                format!("{}…", kept)
+            }
+        }
+    }
+
+    /// Log form of a monetary amount
+    pub fn amount(&self, amount: Decimal) -> String {
+        if !self.redact_pii {
+            return amount.to_string();
+        }
+        match self.amounts {
+            AmountRedaction::Mask => "<redacted>".to_string(),
+            AmountRedaction::Bucket => {
+                let sign = if amount.is_sign_negative() && !amount.is_zero() { "-" } else { "" };
+                let magnitude = amount.abs();
+                let mut lower = 0;
+                for upper in AMOUNT_BUCKETS {
+                    if magnitude < Decimal::from(upper) {
+                        return format!("{}[{}, {})", sign, lower, upper);
+                    }
+                    lower = upper;
+                }
+                format!("{}[{}, ∞)", sign, lower)
+            }
+        }
+    }
+}
+
+impl Default for LogRedactionPolicy {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+static LOG_REDACTION: RwLock<LogRedactionPolicy> = RwLock::new(LogRedactionPolicy::DEFAULT);
+
+/// Apply the redaction settings to all subsequent log lines
+pub fn configure_log_redaction(policy: LogRedactionPolicy) {
+    *LOG_REDACTION.write().unwrap_or_else(|e| e.into_inner()) = policy;
+}
+
+/// The redaction settings currently in effect
+pub fn log_redaction_policy() -> LogRedactionPolicy {
+    *LOG_REDACTION.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Sensitive value formatted according to the current redaction settings
+///
+/// Redaction happens when the value is formatted, so log lines filtered out
+/// by the subscriber cost nothing.
+#[derive(Debug, Clone, Copy)]
+pub enum Pii<'a> {
+    Text(&'a str),
+    Amount(Decimal),
+}
+
+impl fmt::Display for Pii<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let policy = log_redaction_policy();
+        match *self {
+            Pii::Text(value) => f.write_str(&policy.text(value)),
+            Pii::Amount(amount) => f.write_str(&policy.amount(amount)),
+        }
+    }
+}
+
+/// Free text for a log message, e.g. `tracing::info!("Adding {}", pii_text(&description))`
+pub fn pii_text(value: &str) -> Pii<'_> {
+    Pii::Text(value)
+}
+
+/// Monetary amount for a log message
+pub fn pii_amount(amount: Decimal) -> Pii<'static> {
+    Pii::Amount(amount)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use std::io;
+    use std::sync::{Arc, Mutex};
+
+    /// Collects formatted log output in memory
+    #[derive(Clone, Default)]
+    struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
+
+    impl CapturedLogs {
+        fn contents(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    impl io::Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Log a transaction like `add_transaction` does and return the output
+    fn log_transaction(policy: LogRedactionPolicy) -> String {
+        let logs = CapturedLogs::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .finish();
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!("Adding new transaction: {}", policy.text("Dr. Patel Oncology Clinic"));
+            tracing::info!("Amount: {}", policy.amount(dec!(-1234.56)));
+            tracing::info!("Account: {}", policy.text("Chase Sapphire 4417"));
+        });
+        logs.contents()
+    }
+
+    #[test]
+    fn test_enabled_redaction_masks_logged_values() {
+        let output = log_transaction(LogRedactionPolicy::DEFAULT);
+
+        assert!(!output.contains("Patel"));
+        assert!(!output.contains("1234.56"));
+        assert!(!output.contains("4417"));
+        assert!(output.contains("Adding new transaction: <redacted:"));
+        assert!(output.contains("Amount: -[1000, 10000)"));
+
+        let truncated = log_transaction(LogRedactionPolicy {
+            text: TextRedaction::Truncate { keep_chars: 3 },
+            amounts: AmountRedaction::Mask,
+            ..LogRedactionPolicy::DEFAULT
+        });
+        assert!(truncated.contains("Adding new transaction: Dr.…"));
+        assert!(truncated.contains("Amount: <redacted>"));
+        assert!(!truncated.contains("Patel"));
+        assert!(!truncated.contains("1234"));
+    }
+
+    #[test]
+    fn test_disabled_redaction_logs_values_verbatim() {
+        let output = log_transaction(LogRedactionPolicy {
+            redact_pii: false,
+            ..LogRedactionPolicy::DEFAULT
+        });
+
+        assert!(output.contains("Adding new transaction: Dr. Patel Oncology Clinic"));
+        assert!(output.contains("Amount: -1234.56"));
+    }
+
+    #[test]
+    fn test_configured_policy_applies_to_pii_values_in_log_lines() {
+        let logs = CapturedLogs::default();
+        let writer = logs.clone();
+        let dispatch = tracing::Dispatch::new(
+            tracing_subscriber::fmt()
+                .with_writer(move || writer.clone())
+                .with_ansi(false)
+                .finish(),
+        );
+
+        // Log the way commands do, through the process-wide settings
+        let log_transaction = || {
+            tracing::dispatcher::with_default(&dispatch, || {
+                tracing::info!("Adding new transaction: {}", pii_text("Dr. Patel Oncology Clinic"));
+                tracing::info!("Amount: {}", pii_amount(dec!(-1234.56)));
+            });
+        };
+        log_transaction();
+        configure_log_redaction(LogRedactionPolicy {
+            redact_pii: false,
+            ..LogRedactionPolicy::DEFAULT
+        });
+        log_transaction();
+        configure_log_redaction(LogRedactionPolicy::DEFAULT);
+
+        let output = logs.contents();
+        let (redacted, verbatim) = output.split_at(output.find("Dr. Patel").expect("verbatim line"));
+        assert!(redacted.contains("Adding new transaction: <redacted:"));
+        assert!(redacted.contains("Amount: -[1000, 10000)"));
+        assert!(!redacted.contains("1234.56"));
+        assert!(verbatim.contains("Amount: -1234.56"));
+    }
+
+    #[test]
+    fn test_hashes_are_stable_and_buckets_cover_all_amounts() {
+        let policy = LogRedactionPolicy::DEFAULT;
+        assert_eq!(policy.text("Groceries"), policy.text("Groceries"));
+        assert_ne!(policy.text("Groceries"), policy.text("Pharmacy"));
+
+        assert_eq!(policy.amount(Decimal::ZERO), "[0, 10)");
+        assert_eq!(policy.amount(dec!(100)), "[100, 1000)");
+        assert_eq!(policy.amount(dec!(250000)), "[100000, ∞)");
+    }
+}
@@ -2,6 +2,7 @@
 // Enterprise-grade security with hardware-based key management
 
 pub mod vault;
+pub mod field_encryption;
 pub mod secure_query;
 pub mod sql_injection_tests;
 pub mod tls;
@@ -9,6 +10,13 @@ pub mod tls_tests;
 pub mod rate_limiter;
 pub mod comprehensive_security_tests;
 pub mod security_test_runner;
+pub mod audit_chain;
+pub mod session_guard;
+pub mod auto_lock;
+pub mod log_redaction;
+pub mod path_policy;
+pub mod startup_gate;
+pub mod command_policy;
 
 #[cfg(test)]
 pub mod rate_limiter_tests;
@@ -22,11 +30,26 @@ pub use vault::{
     check_key_rotation,
     rotate_encryption_key,
     get_vault,
+    lock_vault,
+    unlock_vault,
+    MasterKey,
+};
+
+pub use field_encryption::{
+    FieldCipher,
+    SensitiveFieldPolicy,
+    SealedFields,
+    ENCRYPTED_VALUE_PREFIX,
 };
 
 pub use secure_query::{
     SecureQuery,
     InputValidator,
+    FieldViolation,
+    ValidationReport,
+    AmountSign,
+    AmountBounds,
+    TransactionAmountLimits,
     TransactionFilterBuilder,
     QueryParam,
     OrderDirection,
@@ -43,8 +66,13 @@ pub use tls::{
     SecureTlsClient,
     TlsError,
     TlsPolicy,
+    MinTlsVersion,
     CertificatePin,
+    HostPinConfig,
+    PinMatch,
+    TlsPinConfig,
     TlsSecurityReport,
+    compute_spki_pin,
     get_secure_client,
     validate_https_url,
     extract_domain,
@@ -91,6 +119,60 @@ pub use comprehensive_security_tests::{
     RemediationItem,
 };
 
+pub use audit_chain::{
+    AuditChain,
+    ChainedEntry,
+    ChainContent,
+    ChainVerificationError,
+    verify_chain,
+};
+
+pub use session_guard::{
+    AuthenticatedUser,
+    SessionGuard,
+};
+
+pub use auto_lock::{
+    AutoLock,
+    AutoLockPolicy,
+    AUTO_LOCK_POLL_INTERVAL,
+    lock_app,
+};
+
+pub use log_redaction::{
+    LogRedactionPolicy,
+    AmountRedaction,
+    TextRedaction,
+    Pii,
+    configure_log_redaction,
+    log_redaction_policy,
+    pii_text,
+    pii_amount,
+};
+
+pub use path_policy::{
+    PathAccessPolicy,
+    resolve_path,
+};
+
+pub use command_policy::{
+    PASSIVE_COMMANDS,
+    WRITE_COMMANDS,
+    check_command_allowed,
+    counts_as_activity,
+    record_command_activity,
+};
+
+pub use startup_gate::{
+    StartupFailureMode,
+    StartupSelfTestPolicy,
+    StartupCheckResult,
+    StartupMode,
+    StartupRefused,
+    StartupGate,
+    startup_mode,
+};
+
 pub use security_test_runner::{
     SecurityTestRunner,
     SecurityValidationSuite,
@@ -0,0 +1,192 @@
+// Path Access Policy for Atlas Financial Desktop
+// Decides which file-system locations commands may read from or write to
+
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+/// System directories file commands never touch by default
+const DEFAULT_DENIED_ROOTS: [&str; 8] = [
+    "/etc", "/sys", "/proc", "/boot",
+    "C:\\Windows", "C:\\System32",
+    "/System", "/Library/System",
+];
+
+/// Allow and deny lists for paths passed to file-system commands
+///
+/// Paths are resolved before they are checked: symlinks are followed and
+/// `.`/`..` components applied, so `exports/../../etc` or a link pointing
+/// out of an allowed directory is judged by where it really leads. Roots
+/// that are not absolute on the current platform are ignored.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathAccessPolicy {
+    /// Directories paths must lie within; empty allows anything not denied
+    #[serde(default)]
+    pub allowed_roots: Vec<PathBuf>,
+    /// Directories paths must not lie within, checked before the allow list
+    #[serde(default = "default_denied_roots")]
+    pub denied_roots: Vec<PathBuf>,
+}
+
+fn default_denied_roots() -> Vec<PathBuf> {
+    DEFAULT_DENIED_ROOTS.iter().map(PathBuf::from).collect()
+}
+
+impl Default for PathAccessPolicy {
+    fn default() -> Self {
+        Self {
+            allowed_roots: Vec::new(),
+            denied_roots: default_denied_roots(),
+        }
+    }
+}
+
+impl PathAccessPolicy {
+    /// Resolved form of `path`, or `None` if the policy forbids it
+    ///
+    /// Paths that cannot be resolved, such as dangling symlinks, are refused.
+    pub fn resolve(&self, path: &Path) -> Option<PathBuf> {
+        let resolved = resolve_path(path).ok()?;
+
+        if self.denied_roots.iter().any(|root| within_root(&resolved, root)) {
+            return None;
+        }
+        if !self.allowed_roots.is_empty()
+            && !self.allowed_roots.iter().any(|root| within_root(&resolved, root))
+        {
+            return None;
+        }
+        Some(resolved)
+    }
+
+    pub fn allows(&self, path: &Path) -> bool {
+        self.resolve(path).is_some()
+    }
+}
+
+/// Absolute form of `path` with symlinks followed and `.`/`..` applied
+///
+/// Components that do not exist yet are kept as written, so a file about to
+/// be created can still be checked. Relative paths are taken from the
+/// current directory.
+pub fn resolve_path(path: &Path) -> io::Result<PathBuf> {
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir()?.join(path)
+    };
+
+    let mut resolved = PathBuf::new();
+    for component in absolute.components() {
+        match component {
+            Component::Prefix(_) | Component::RootDir => resolved.push(component.as_os_str()),
+            Component::CurDir => {}
+            // `resolved` never contains a symlink, so popping is safe
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            Component::Normal(part) => {
+                resolved.push(part);
+                match std::fs::canonicalize(&resolved) {
+                    Ok(canonical) => resolved = canonical,
+                    Err(_) if std::fs::symlink_metadata(&resolved).is_ok() => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::NotFound,
+                            format!("{} is a dangling symlink", resolved.display()),
+                        ));
+                    }
+                    // Not created yet; nothing below it exists either
+                    Err(_) => {}
+                }
+            }
+        }
+    }
+    Ok(resolved)
+}
+
+/// Whether resolved `path` is `root` or lies beneath it
+fn within_root(path: &Path, root: &Path) -> bool {
+    if !root.is_absolute() {
+        return false;
+    }
+    let root = resolve_path(root).unwrap_or_else(|_| root.to_path_buf());
+    if cfg!(windows) {
+        let path = path.to_string_lossy().to_lowercase();
+        let root = root.to_string_lossy().to_lowercase();
+        Path::new(&path).starts_with(Path::new(&root))
+    } else {
+        path.starts_with(&root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fresh directory with `allowed/` and `outside/` subdirectories
+    fn sandbox() -> PathBuf {
+        let root = std::env::temp_dir().join(format!("atlas-path-policy-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("allowed/reports")).unwrap();
+        std::fs::create_dir_all(root.join("outside")).unwrap();
+        std::fs::write(root.join("outside/secrets.txt"), "top secret").unwrap();
+        root
+    }
+
+    fn allow_only(dir: &Path) -> PathAccessPolicy {
+        PathAccessPolicy {
+            allowed_roots: vec![dir.to_path_buf()],
+            ..PathAccessPolicy::default()
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_out_of_allowed_dir_is_rejected() {
+        let root = sandbox();
+        let allowed = root.join("allowed");
+        let policy = allow_only(&allowed);
+
+        std::os::unix::fs::symlink(root.join("outside"), allowed.join("escape")).unwrap();
+        std::os::unix::fs::symlink(root.join("outside/missing"), allowed.join("dangling")).unwrap();
+
+        assert!(!policy.allows(&allowed.join("escape/secrets.txt")));
+        assert!(!policy.allows(&allowed.join("escape/new-file.csv")));
+        assert!(!policy.allows(&allowed.join("dangling")));
+
+        // Links that stay inside the allowed directory are fine
+        std::os::unix::fs::symlink(allowed.join("reports"), allowed.join("latest")).unwrap();
+        assert_eq!(
+            policy.resolve(&allowed.join("latest/march.csv")),
+            Some(std::fs::canonicalize(allowed.join("reports")).unwrap().join("march.csv"))
+        );
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_traversal_is_judged_after_normalization() {
+        let root = sandbox();
+        let allowed = root.join("allowed");
+        let policy = allow_only(&allowed);
+
+        assert!(!policy.allows(&allowed.join("reports/../../outside/secrets.txt")));
+        assert!(!policy.allows(&allowed.join("not-yet/../../outside")));
+        // `..` that stays inside the allowed directory is no longer refused outright
+        assert!(policy.allows(&allowed.join("reports/../export.csv")));
+
+        // Denied roots win even without an allow list
+        let defaults = PathAccessPolicy::default();
+        let denied = PathAccessPolicy {
+            denied_roots: vec![root.join("outside")],
+            ..PathAccessPolicy::default()
+        };
+        assert!(!denied.allows(&allowed.join("reports/./../../outside/secrets.txt")));
+        assert!(denied.allows(&allowed.join("reports")));
+        #[cfg(unix)]
+        assert!(!defaults.allows(&root.join("../../../../../../etc/passwd")));
+        assert!(defaults.allows(&allowed));
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
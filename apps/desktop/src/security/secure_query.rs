@@ -9,7 +9,10 @@ use rust_decimal::Decimal;
 use std::collections::HashMap;
 use regex::Regex;
 use once_cell::sync::Lazy;
-use crate::financial::FinancialError;
+use crate::financial::{parse_money, FinancialError, NumberFormat};
+use crate::storage::AccountType;
+use crate::tags::TagPolicy;
+use atlas_financial_core::Currency;
 
 // Compile-time SQL injection detection patterns
 pub static SQL_INJECTION_PATTERNS: Lazy<Vec<Regex>> = Lazy::new(|| {
@@ -39,6 +42,7 @@ pub struct SecureQuery<'a> {
     pool: &'a PgPool,
     query_builder: QueryBuilder<'a, Postgres>,
     param_count: usize,
+    has_order_by: bool,
 }
 
 impl<'a> SecureQuery<'a> {
@@ -48,6 +52,7 @@ impl<'a> SecureQuery<'a> {
             pool,
             query_builder: QueryBuilder::new(base_query),
             param_count: 0,
+            has_order_by: false,
         }
     }
 
@@ -96,12 +101,42 @@ impl<'a> SecureQuery<'a> {
         Ok(self)
     }
 
+    /// Add a keyset cursor clause selecting rows strictly after `(timestamp, id)`
+    ///
+    /// Pair with ascending ORDER BY on the same columns to page through large
+    /// result sets without the cost and drift of OFFSET.
+    pub fn add_cursor_after(
+        mut self,
+        timestamp_column: &str,
+        id_column: &str,
+        timestamp: DateTime<Utc>,
+        id: String,
+    ) -> Result<Self, FinancialError> {
+        self.validate_column_name(timestamp_column)?;
+        self.validate_column_name(id_column)?;
+        self.validate_string_input(&id, MAX_SEARCH_LENGTH)?;
+
+        let operator = if self.param_count == 0 { " WHERE " } else { " AND " };
+        self.query_builder.push(operator);
+        self.query_builder.push(format!("({}, {}) > (", timestamp_column, id_column));
+        self.query_builder.push_bind(timestamp);
+        self.query_builder.push(", ");
+        self.query_builder.push_bind(id);
+        self.query_builder.push(")");
+
+        self.param_count += 1;
+        Ok(self)
+    }
+
     /// Add ORDER BY clause (only allows predefined columns)
     pub fn add_order_by(mut self, column: &str, direction: OrderDirection) -> Result<Self, FinancialError> {
         self.validate_column_name(column)?;
 
-        self.query_builder.push(" ORDER BY ");
+        // Later columns extend the same ORDER BY as tie-breakers
+        let keyword = if self.has_order_by { ", " } else { " ORDER BY " };
+        self.query_builder.push(keyword);
         self.query_builder.push(column);
+        self.has_order_by = true;
         match direction {
             OrderDirection::Asc => self.query_builder.push(" ASC"),
             OrderDirection::Desc => self.query_builder.push(" DESC"),
@@ -299,60 +334,261 @@ pub enum OrderDirection {
     Desc,
 }
 
-/// Input validation for financial data
-pub struct InputValidator;
+/// One problem with one input field
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldViolation {
+    /// Path of the offending field, e.g. `amount` or `tags[2]`
+    pub field: String,
+    /// Machine-readable code, e.g. `amount.invalid_format` or `tags.too_long`
+    pub code: String,
+    pub message: String,
+}
 
-impl InputValidator {
-    /// Validate transaction input
-    pub fn validate_transaction_input(input: &crate::commands::financial::TransactionInput) -> Result<(), FinancialError> {
-        // Validate UUID format for account_id
-        Uuid::parse_str(&input.account_id)
-            .map_err(|_| FinancialError::ValidationError("Invalid account ID format".to_string()))?;
+/// Violation kinds that indicate a hostile input rather than a typo
+const SECURITY_VIOLATIONS: &[&str] = &["malicious_pattern", "invalid_characters"];
+
+impl FieldViolation {
+    fn new(field: impl Into<String>, code_field: &str, kind: &str, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            code: format!("{}.{}", code_field, kind),
+            message: message.into(),
+        }
+    }
+
+    /// Whether the value looked like an injection attempt
+    pub fn is_security_violation(&self) -> bool {
+        self.code
+            .rsplit('.')
+            .next()
+            .is_some_and(|kind| SECURITY_VIOLATIONS.contains(&kind))
+    }
+}
+
+/// Every violation found in an input, in field order
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationReport {
+    pub violations: Vec<FieldViolation>,
+}
 
-        // Validate amount can be parsed as Decimal
-        let amount = input.amount.parse::<Decimal>()
-            .map_err(|_| FinancialError::ValidationError("Invalid amount format".to_string()))?;
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
 
-        // Validate amount is within reasonable bounds
-        if amount.abs() > Decimal::new(999999999, 2) { // 9,999,999.99 max
-            return Err(FinancialError::ValidationError("Amount exceeds maximum allowed value".to_string()));
+    /// Codes of all violations, handy for matching in the UI and tests
+    pub fn codes(&self) -> Vec<&str> {
+        self.violations.iter().map(|v| v.code.as_str()).collect()
+    }
+
+    fn push(&mut self, violation: FieldViolation) {
+        self.violations.push(violation);
+    }
+
+    fn into_result(self) -> Result<(), ValidationReport> {
+        if self.is_valid() {
+            Ok(())
+        } else {
+            Err(self)
         }
+    }
+}
 
-        // Validate description
-        if input.description.trim().is_empty() {
-            return Err(FinancialError::ValidationError("Description cannot be empty".to_string()));
+impl std::fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let messages: Vec<&str> = self.violations.iter().map(|v| v.message.as_str()).collect();
+        write!(f, "{}", messages.join("; "))
+    }
+}
+
+impl std::error::Error for ValidationReport {}
+
+impl From<ValidationReport> for FinancialError {
+    fn from(report: ValidationReport) -> Self {
+        if report.violations.iter().any(FieldViolation::is_security_violation) {
+            FinancialError::SecurityError(report.to_string())
+        } else {
+            FinancialError::ValidationError(report.to_string())
         }
-        Self::validate_string_field(&input.description, MAX_DESCRIPTION_LENGTH, "description")?;
+    }
+}
 
-        // Validate optional fields
-        if let Some(category) = &input.category {
-            Self::validate_string_field(category, MAX_CATEGORY_LENGTH, "category")?;
+/// Sign a transaction amount may have
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AmountSign {
+    #[default]
+    Any,
+    PositiveOnly,
+    NegativeOnly,
+}
+
+impl AmountSign {
+    fn allows(self, amount: Decimal) -> bool {
+        match self {
+            AmountSign::Any => true,
+            AmountSign::PositiveOnly => amount >= Decimal::ZERO,
+            AmountSign::NegativeOnly => amount <= Decimal::ZERO,
         }
+    }
+}
+
+/// Largest amount, and the sign it may have, for transactions on one kind of account
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AmountBounds {
+    pub max_amount: Decimal,
+    #[serde(default)]
+    pub sign: AmountSign,
+}
+
+impl AmountBounds {
+    pub fn new(max_amount: Decimal) -> Self {
+        Self { max_amount, sign: AmountSign::Any }
+    }
+}
 
-        if let Some(subcategory) = &input.subcategory {
-            Self::validate_string_field(subcategory, MAX_CATEGORY_LENGTH, "subcategory")?;
+/// Sanity bounds that catch fat-fingered transaction amounts
+///
+/// A misplaced decimal point still parses, so amounts are also checked
+/// against a ceiling for the account they are posted to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionAmountLimits {
+    /// Bounds for account types without their own entry, or when the account is unknown
+    pub default: AmountBounds,
+    #[serde(default)]
+    pub by_account_type: HashMap<AccountType, AmountBounds>,
+}
+
+impl TransactionAmountLimits {
+    pub fn bounds_for(&self, account_type: Option<AccountType>) -> AmountBounds {
+        account_type
+            .and_then(|account_type| self.by_account_type.get(&account_type))
+            .copied()
+            .unwrap_or(self.default)
+    }
+}
+
+impl Default for TransactionAmountLimits {
+    fn default() -> Self {
+        Self {
+            default: AmountBounds::new(Decimal::new(999999999, 2)), // 9,999,999.99
+            by_account_type: HashMap::from([
+                (AccountType::Checking, AmountBounds::new(Decimal::new(1_000_000, 0))),
+                (AccountType::Savings, AmountBounds::new(Decimal::new(5_000_000, 0))),
+                (AccountType::CreditCard, AmountBounds::new(Decimal::new(100_000, 0))),
+                (AccountType::Cash, AmountBounds::new(Decimal::new(50_000, 0))),
+            ]),
         }
+    }
+}
+
+/// Input validation for financial data
+pub struct InputValidator;
+
+impl InputValidator {
+    /// Validate transaction input
+    ///
+    /// Every field is checked, so the report lists all problems at once
+    /// rather than only the first.
+    pub fn validate_transaction_input(input: &crate::commands::financial::TransactionInput) -> Result<(), ValidationReport> {
+        Self::validate_transaction_input_with_format(input, NumberFormat::default())
+    }
+
+    /// Validate transaction input whose amount is typed with `format`'s separators
+    pub fn validate_transaction_input_with_format(
+        input: &crate::commands::financial::TransactionInput,
+        format: NumberFormat,
+    ) -> Result<(), ValidationReport> {
+        Self::validate_transaction_input_with_limits(
+            input,
+            format,
+            None,
+            &TransactionAmountLimits::default(),
+            &TagPolicy::default(),
+        )
+    }
+
+    /// Validate transaction input, holding its amount to the bounds for the
+    /// type of account it is posted to and its tags to `tag_policy`
+    ///
+    /// Pass `None` when the account type is not known; the default bounds apply.
+    pub fn validate_transaction_input_with_limits(
+        input: &crate::commands::financial::TransactionInput,
+        format: NumberFormat,
+        account_type: Option<AccountType>,
+        limits: &TransactionAmountLimits,
+        tag_policy: &TagPolicy,
+    ) -> Result<(), ValidationReport> {
+        let mut report = ValidationReport::default();
+        let bounds = limits.bounds_for(account_type);
 
-        if let Some(merchant) = &input.merchant {
-            Self::validate_string_field(merchant, MAX_MERCHANT_LENGTH, "merchant")?;
+        // Validate UUID format for account_id
+        if Uuid::parse_str(&input.account_id).is_err() {
+            report.push(FieldViolation::new("accountId", "account_id", "invalid_format", "Invalid account ID format"));
         }
 
-        if let Some(location) = &input.location {
-            Self::validate_string_field(location, MAX_LOCATION_LENGTH, "location")?;
+        // Validate amount parses unambiguously and is within reasonable bounds
+        match parse_money(&input.amount, Currency::USD, format).map(|money| money.amount()) {
+            Err(FinancialError::PrecisionError(message)) => {
+                report.push(FieldViolation::new("amount", "amount", "too_precise", message))
+            }
+            Err(e) => report.push(FieldViolation::new("amount", "amount", "invalid_format", e.to_string())),
+            Ok(amount) if amount.abs() > bounds.max_amount => {
+                report.push(FieldViolation::new(
+                    "amount",
+                    "amount",
+                    "out_of_range",
+                    format!("Amount exceeds the maximum of {} allowed for this account", bounds.max_amount),
+                ));
+            }
+            Ok(amount) if !bounds.sign.allows(amount) => {
+                let expected = if bounds.sign == AmountSign::PositiveOnly { "positive" } else { "negative" };
+                report.push(FieldViolation::new(
+                    "amount",
+                    "amount",
+                    "invalid_sign",
+                    format!("Amount must be {} for this account", expected),
+                ));
+            }
+            Ok(_) => {}
         }
 
-        if let Some(notes) = &input.notes {
-            Self::validate_string_field(notes, MAX_NOTES_LENGTH, "notes")?;
+        // Validate description
+        if input.description.trim().is_empty() {
+            report.push(FieldViolation::new("description", "description", "required", "Description cannot be empty"));
+        } else if let Some(violation) = Self::string_field_violation(&input.description, MAX_DESCRIPTION_LENGTH, "description", "description") {
+            report.push(violation);
+        }
+
+        // Validate optional fields
+        let optional_fields = [
+            (&input.category, MAX_CATEGORY_LENGTH, "category"),
+            (&input.subcategory, MAX_CATEGORY_LENGTH, "subcategory"),
+            (&input.merchant, MAX_MERCHANT_LENGTH, "merchant"),
+            (&input.location, MAX_LOCATION_LENGTH, "location"),
+            (&input.notes, MAX_NOTES_LENGTH, "notes"),
+        ];
+        for (value, max_length, field) in optional_fields {
+            if let Some(violation) = value.as_deref().and_then(|v| Self::string_field_violation(v, max_length, field, field)) {
+                report.push(violation);
+            }
         }
 
         if let Some(tags) = &input.tags {
             if tags.len() > MAX_TAGS_COUNT {
-                return Err(FinancialError::ValidationError(
-                    format!("Maximum {} tags allowed", MAX_TAGS_COUNT)
-                ));
+                report.push(FieldViolation::new("tags", "tags", "too_many", format!("Maximum {} tags allowed", MAX_TAGS_COUNT)));
             }
-            for tag in tags {
-                Self::validate_string_field(tag, MAX_TAG_LENGTH, "tag")?;
+            for (index, tag) in tags.iter().enumerate() {
+                let field = format!("tags[{}]", index);
+                if let Some(violation) = Self::string_field_violation(tag, MAX_TAG_LENGTH, &field, "tags") {
+                    report.push(violation);
+                } else if let Err(e) = tag_policy.check(tag) {
+                    report.push(FieldViolation::new(field, "tags", e.code(), e.to_string()));
+                }
             }
         }
 
@@ -363,13 +599,16 @@ impl InputValidator {
             let max_future = now + chrono::Duration::days(365); // 1 year
 
             if date < max_past || date > max_future {
-                return Err(FinancialError::ValidationError(
-                    "Transaction date is outside allowed range".to_string()
+                report.push(FieldViolation::new(
+                    "transactionDate",
+                    "transaction_date",
+                    "out_of_range",
+                    "Transaction date is outside allowed range",
                 ));
             }
         }
 
-        Ok(())
+        report.into_result()
     }
 
     /// Validate account creation input
@@ -427,35 +666,87 @@ impl InputValidator {
             }
         }
 
+        Self::validate_account_type_rules(input)
+    }
+
+    /// Validate the fields each account type requires or forbids
+    ///
+    /// Credit cards need a limit and may carry a negative (owed) balance,
+    /// loans and mortgages need an interest rate, and savings and cash
+    /// accounts can never be overdrawn. Cash has no credit line at all.
+    fn validate_account_type_rules(input: &crate::storage::CreateAccountRequest) -> Result<(), FinancialError> {
+        match input.account_type {
+            AccountType::CreditCard if input.credit_limit.is_none() => {
+                return Err(FinancialError::ValidationError(
+                    "Credit card accounts require a credit limit".to_string()
+                ));
+            }
+            AccountType::Loan | AccountType::Mortgage if input.interest_rate.is_none() => {
+                return Err(FinancialError::ValidationError(
+                    "Loan and mortgage accounts require an interest rate".to_string()
+                ));
+            }
+            AccountType::Cash if input.credit_limit.is_some() => {
+                return Err(FinancialError::ValidationError(
+                    "Cash accounts cannot have a credit limit".to_string()
+                ));
+            }
+            _ => {}
+        }
+
+        if matches!(input.account_type, AccountType::Savings | AccountType::Cash) && input.balance < Decimal::ZERO {
+            return Err(FinancialError::ValidationError(
+                "Savings and cash accounts cannot have a negative balance".to_string()
+            ));
+        }
+
         Ok(())
     }
 
     /// Validate string field against SQL injection and length
     pub fn validate_string_field(value: &str, max_length: usize, field_name: &str) -> Result<(), FinancialError> {
+        match Self::string_field_violation(value, max_length, field_name, field_name) {
+            Some(violation) => Err(ValidationReport { violations: vec![violation] }.into()),
+            None => Ok(()),
+        }
+    }
+
+    /// First length, injection, or control-character problem with a string field
+    ///
+    /// `field` is the path reported to the caller and `code_field` the prefix of
+    /// the machine code, so every tag shares the `tags.*` codes.
+    fn string_field_violation(value: &str, max_length: usize, field: &str, code_field: &str) -> Option<FieldViolation> {
         if value.len() > max_length {
-            return Err(FinancialError::ValidationError(
-                format!("{} exceeds maximum length of {} characters", field_name, max_length)
+            return Some(FieldViolation::new(
+                field,
+                code_field,
+                "too_long",
+                format!("{} exceeds maximum length of {} characters", field, max_length),
             ));
         }
 
         // Check for SQL injection patterns
-        for pattern in SQL_INJECTION_PATTERNS.iter() {
-            if pattern.is_match(value) {
-                tracing::warn!("Potential SQL injection attempt in {}: {}", field_name, value);
-                return Err(FinancialError::SecurityError(
-                    format!("{} contains potentially malicious patterns", field_name)
-                ));
-            }
+        if SQL_INJECTION_PATTERNS.iter().any(|pattern| pattern.is_match(value)) {
+            tracing::warn!("Potential SQL injection attempt in {}: {}", field, value);
+            return Some(FieldViolation::new(
+                field,
+                code_field,
+                "malicious_pattern",
+                format!("{} contains potentially malicious patterns", field),
+            ));
         }
 
         // Check for null bytes and dangerous control characters
         if value.contains('\0') || value.chars().any(|c| c.is_control() && c != '\n' && c != '\r' && c != '\t') {
-            return Err(FinancialError::SecurityError(
-                format!("{} contains invalid control characters", field_name)
+            return Some(FieldViolation::new(
+                field,
+                code_field,
+                "invalid_characters",
+                format!("{} contains invalid control characters", field),
             ));
         }
 
-        Ok(())
+        None
     }
 
     /// Validate currency code against ISO 4217 standard
@@ -0,0 +1,174 @@
+// Session Authorization for Atlas Financial Desktop
+// Commands resolve the acting user from the signed-in session, never from frontend input
+
+use atlas_financial_core::{system_clock, Clock};
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tokio::time::Instant;
+
+use crate::financial::FinancialError;
+
+/// User signed in to the running desktop session
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthenticatedUser {
+    pub user_id: String,
+    pub expires_at: DateTime<Utc>,
+    pub permissions: Vec<String>,
+}
+
+/// Holds the authenticated user for the lifetime of the app process
+///
+/// Populated when authentication succeeds and cleared on logout or auto-lock.
+/// Commands that accept a `user_id` from the frontend check it against this
+/// session instead of trusting it, so a compromised webview cannot act for
+/// another user. Every command other than background polls counts as user
+/// activity; see `command_policy`.
+#[derive(Debug)]
+pub struct SessionGuard {
+    current: RwLock<Option<AuthenticatedUser>>,
+    last_activity: RwLock<Option<Instant>>,
+    /// Time sessions expire against
+    clock: Arc<dyn Clock>,
+}
+
+impl Default for SessionGuard {
+    fn default() -> Self {
+        Self::with_clock(system_clock())
+    }
+}
+
+impl SessionGuard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Guard that checks session expiry against `clock`
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
+        Self {
+            current: RwLock::new(None),
+            last_activity: RwLock::new(None),
+            clock,
+        }
+    }
+
+    /// Record the user a successful authentication signed in
+    pub async fn sign_in(&self, user: AuthenticatedUser) {
+        *self.current.write().await = Some(user);
+        *self.last_activity.write().await = Some(Instant::now());
+    }
+
+    /// Forget the signed-in user
+    pub async fn sign_out(&self) {
+        *self.current.write().await = None;
+        *self.last_activity.write().await = None;
+    }
+
+    /// Note that the signed-in user just did something
+    pub async fn record_activity(&self) {
+        let mut last_activity = self.last_activity.write().await;
+        if last_activity.is_some() {
+            *last_activity = Some(Instant::now());
+        }
+    }
+
+    /// Time since the signed-in user last did something; `None` when signed out
+    pub async fn idle_for(&self) -> Option<Duration> {
+        self.last_activity.read().await.map(|at| at.elapsed())
+    }
+
+    /// The signed-in user, if the session has not expired
+    pub async fn current_user(&self) -> Result<AuthenticatedUser, FinancialError> {
+        match self.current.read().await.as_ref() {
+            Some(user) if user.expires_at > self.clock.now() => Ok(user.clone()),
+            Some(_) => Err(FinancialError::SecurityError("Session has expired".to_string())),
+            None => Err(FinancialError::SecurityError("Not signed in".to_string())),
+        }
+    }
+
+    /// Resolve the acting user for a request that names `requested_user_id`
+    ///
+    /// Returns the session's user ID when it matches; any other ID is rejected
+    /// rather than silently substituted so mismatches are visible in logs.
+    pub async fn authorize_user(&self, requested_user_id: &str) -> Result<String, FinancialError> {
+        let user = self.current_user().await?;
+        if user.user_id != requested_user_id {
+            tracing::warn!(
+                "Rejected request for user {} from session of user {}",
+                requested_user_id,
+                user.user_id
+            );
+            return Err(FinancialError::SecurityError(
+                "Not authorized to access another user's data".to_string(),
+            ));
+        }
+        self.record_activity().await;
+        Ok(user.user_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn user(user_id: &str, expires_in: Duration) -> AuthenticatedUser {
+        AuthenticatedUser {
+            user_id: user_id.to_string(),
+            expires_at: Utc::now() + expires_in,
+            permissions: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_only_session_user_is_authorized() {
+        let guard = SessionGuard::new();
+        guard.sign_in(user("user-a", Duration::hours(1))).await;
+
+        assert_eq!(guard.authorize_user("user-a").await.unwrap(), "user-a");
+        assert!(matches!(
+            guard.authorize_user("user-b").await,
+            Err(FinancialError::SecurityError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_signed_out_and_expired_sessions_are_denied() {
+        let guard = SessionGuard::new();
+        assert!(guard.authorize_user("user-a").await.is_err());
+
+        guard.sign_in(user("user-a", Duration::seconds(-1))).await;
+        assert!(guard.authorize_user("user-a").await.is_err());
+
+        guard.sign_in(user("user-a", Duration::hours(1))).await;
+        guard.sign_out().await;
+        assert!(guard.current_user().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_session_expires_exactly_at_expiry() {
+        use atlas_financial_core::MockClock;
+        use chrono::TimeZone;
+
+        let signed_in_at = Utc.with_ymd_and_hms(2025, 6, 1, 9, 0, 0).unwrap();
+        let clock = MockClock::new(signed_in_at);
+        let guard = SessionGuard::with_clock(Arc::new(clock.clone()));
+        guard
+            .sign_in(AuthenticatedUser {
+                user_id: "user-a".to_string(),
+                expires_at: signed_in_at + Duration::hours(8),
+                permissions: vec![],
+            })
+            .await;
+
+        clock.advance(Duration::hours(8) - Duration::seconds(1));
+        assert_eq!(guard.authorize_user("user-a").await.unwrap(), "user-a");
+
+        clock.advance(Duration::seconds(1));
+        assert!(matches!(
+            guard.current_user().await,
+            Err(FinancialError::SecurityError(ref message)) if message == "Session has expired"
+        ));
+    }
+}
@@ -387,6 +387,213 @@ mod tests {
                blocking_rate * 100.0);
     }
 
+    #[test]
+    fn test_transaction_validation_reports_every_violation() {
+        let input = TransactionInput {
+            account_id: "not-a-uuid".to_string(),
+            amount: "12,50".to_string(),
+            description: "x".repeat(501),
+            category: Some("Groceries".to_string()),
+            subcategory: None,
+            transaction_date: Some(Utc::now() + chrono::Duration::days(400)),
+            transaction_type: crate::commands::financial::TransactionType::Debit,
+            merchant: Some("'; DROP TABLE users; --".to_string()),
+            location: None,
+            is_recurring: None,
+            tags: Some(vec!["food".to_string(), "t".repeat(51)]),
+            notes: None,
+        };
+
+        let report = InputValidator::validate_transaction_input(&input).unwrap_err();
+        assert_eq!(
+            report.codes(),
+            vec![
+                "account_id.invalid_format",
+                "amount.invalid_format",
+                "description.too_long",
+                "merchant.malicious_pattern",
+                "tags.too_long",
+                "transaction_date.out_of_range",
+            ]
+        );
+        let fields: Vec<&str> = report.violations.iter().map(|v| v.field.as_str()).collect();
+        assert_eq!(fields, vec!["accountId", "amount", "description", "merchant", "tags[1]", "transactionDate"]);
+
+        // Callers that only need an error still get the right kind
+        assert!(matches!(
+            crate::financial::FinancialError::from(report),
+            crate::financial::FinancialError::SecurityError(_)
+        ));
+
+        let valid = TransactionInput {
+            account_id: Uuid::new_v4().to_string(),
+            amount: "12.50".to_string(),
+            description: "Weekly shop".to_string(),
+            merchant: Some("Corner Market".to_string()),
+            tags: Some(vec!["food".to_string()]),
+            transaction_date: Some(Utc::now()),
+            ..input
+        };
+        assert!(InputValidator::validate_transaction_input(&valid).is_ok());
+
+        // Amounts are read with the user's separators
+        use crate::financial::NumberFormat;
+        let european = TransactionInput { amount: "1.234,50 $".to_string(), ..valid.clone() };
+        assert!(InputValidator::validate_transaction_input(&european).is_err());
+        assert!(InputValidator::validate_transaction_input_with_format(&european, NumberFormat::CommaDecimal).is_ok());
+
+        let too_precise = TransactionInput { amount: "12.3456".to_string(), ..valid };
+        let report = InputValidator::validate_transaction_input(&too_precise).unwrap_err();
+        assert_eq!(report.codes(), vec!["amount.too_precise"]);
+    }
+
+    #[test]
+    fn test_transaction_amounts_held_to_account_type_bounds() {
+        use crate::financial::NumberFormat;
+        use crate::security::secure_query::{AmountBounds, AmountSign, TransactionAmountLimits};
+        use crate::storage::AccountType;
+        use crate::tags::TagPolicy;
+
+        let input = |amount: &str| TransactionInput {
+            account_id: Uuid::new_v4().to_string(),
+            amount: amount.to_string(),
+            description: "Card payment".to_string(),
+            category: None,
+            subcategory: None,
+            transaction_date: Some(Utc::now()),
+            transaction_type: crate::commands::financial::TransactionType::Debit,
+            merchant: None,
+            location: None,
+            is_recurring: None,
+            tags: None,
+            notes: None,
+        };
+        let validate = |amount: &str, account_type, limits: &TransactionAmountLimits| {
+            InputValidator::validate_transaction_input_with_limits(
+                &input(amount),
+                NumberFormat::default(),
+                account_type,
+                limits,
+                &TagPolicy::default(),
+            )
+        };
+        let defaults = TransactionAmountLimits::default();
+
+        // A misplaced decimal on a card payment is caught, though it would pass unchecked
+        let report = validate("250000.00", Some(AccountType::CreditCard), &defaults).unwrap_err();
+        assert_eq!(report.codes(), vec!["amount.out_of_range"]);
+        assert!(report.violations[0].message.contains("100000"));
+        assert!(validate("250000.00", None, &defaults).is_ok());
+        assert!(validate("250000.00", Some(AccountType::Investment), &defaults).is_ok());
+        assert_eq!(
+            validate("10000000.00", None, &defaults).unwrap_err().codes(),
+            vec!["amount.out_of_range"]
+        );
+
+        let mut limits = TransactionAmountLimits::default();
+        limits.by_account_type.insert(
+            AccountType::Savings,
+            AmountBounds { max_amount: Decimal::new(50_000, 0), sign: AmountSign::PositiveOnly },
+        );
+        let report = validate("-120.00", Some(AccountType::Savings), &limits).unwrap_err();
+        assert_eq!(report.codes(), vec!["amount.invalid_sign"]);
+        assert_eq!(report.violations[0].message, "Amount must be positive for this account");
+        assert!(validate("120.00", Some(AccountType::Savings), &limits).is_ok());
+        assert!(validate("-120.00", Some(AccountType::Checking), &limits).is_ok());
+    }
+
+    #[test]
+    fn test_hierarchical_tags_held_to_tag_policy() {
+        use crate::financial::NumberFormat;
+        use crate::security::secure_query::TransactionAmountLimits;
+        use crate::tags::TagPolicy;
+
+        let tagged = |tags: &[&str]| TransactionInput {
+            account_id: Uuid::new_v4().to_string(),
+            amount: "-42.00".to_string(),
+            description: "Airport taxi".to_string(),
+            category: None,
+            subcategory: None,
+            transaction_date: Some(Utc::now()),
+            transaction_type: crate::commands::financial::TransactionType::Debit,
+            merchant: None,
+            location: None,
+            is_recurring: None,
+            tags: Some(tags.iter().map(|tag| tag.to_string()).collect()),
+            notes: None,
+        };
+        let validate = |tags: &[&str], max_depth| {
+            InputValidator::validate_transaction_input_with_limits(
+                &tagged(tags),
+                NumberFormat::default(),
+                None,
+                &TransactionAmountLimits::default(),
+                &TagPolicy { max_depth },
+            )
+        };
+
+        assert!(validate(&["travel/ground", "work"], 2).is_ok());
+        let report = validate(&["travel//ground", "travel/ground/taxi"], 2).unwrap_err();
+        assert_eq!(report.codes(), vec!["tags.invalid_format", "tags.too_deep"]);
+        assert_eq!(report.violations[1].field, "tags[1]");
+        assert!(validate(&["travel/ground/taxi"], 3).is_ok());
+    }
+
+    fn account_input(account_type: crate::storage::AccountType, balance: Decimal) -> CreateAccountRequest {
+        CreateAccountRequest {
+            user_id: Uuid::new_v4().to_string(),
+            name: "Everyday Account".to_string(),
+            account_type,
+            balance,
+            currency: "USD".to_string(),
+            institution: None,
+            account_number_masked: None,
+            credit_limit: None,
+            interest_rate: None,
+        }
+    }
+
+    #[test]
+    fn test_credit_card_requires_limit_and_allows_negative_balance() {
+        use crate::storage::AccountType;
+
+        let without_limit = account_input(AccountType::CreditCard, Decimal::new(-45000, 2));
+        assert!(InputValidator::validate_account_input(&without_limit).is_err());
+
+        let with_limit = CreateAccountRequest {
+            credit_limit: Some(Decimal::new(500000, 2)),
+            ..without_limit
+        };
+        assert!(InputValidator::validate_account_input(&with_limit).is_ok());
+    }
+
+    #[test]
+    fn test_account_type_specific_rules() {
+        use crate::storage::AccountType;
+
+        // Savings and cash can't be overdrawn; checking can
+        assert!(InputValidator::validate_account_input(&account_input(AccountType::Savings, Decimal::new(-100, 2))).is_err());
+        assert!(InputValidator::validate_account_input(&account_input(AccountType::Cash, Decimal::new(-100, 2))).is_err());
+        assert!(InputValidator::validate_account_input(&account_input(AccountType::Checking, Decimal::new(-100, 2))).is_ok());
+
+        let cash_with_limit = CreateAccountRequest {
+            credit_limit: Some(Decimal::new(10000, 2)),
+            ..account_input(AccountType::Cash, Decimal::new(2000, 2))
+        };
+        assert!(InputValidator::validate_account_input(&cash_with_limit).is_err());
+
+        for account_type in [AccountType::Loan, AccountType::Mortgage] {
+            let without_rate = account_input(account_type, Decimal::new(25000000, 2));
+            assert!(InputValidator::validate_account_input(&without_rate).is_err());
+
+            let with_rate = CreateAccountRequest {
+                interest_rate: Some(Decimal::new(65, 1)),
+                ..without_rate
+            };
+            assert!(InputValidator::validate_account_input(&with_rate).is_ok());
+        }
+    }
+
     #[test]
     fn test_performance_benchmark() {
         let duration = benchmark_sql_injection_detection(100);
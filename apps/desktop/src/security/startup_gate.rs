@@ -0,0 +1,189 @@
+// Startup Self-Test Gate for Atlas Financial Desktop
+// Decides whether the app may start normally after its security self-tests
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::OnceLock;
+
+/// What the app does when a critical startup check fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StartupFailureMode {
+    /// Stop launching
+    Refuse,
+    /// Start, but refuse every change to financial data
+    ReadOnly,
+}
+
+/// Whether startup runs the self-tests and how it reacts to failures
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupSelfTestPolicy {
+    pub enabled: bool,
+    pub on_failure: StartupFailureMode,
+}
+
+impl Default for StartupSelfTestPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            on_failure: StartupFailureMode::Refuse,
+        }
+    }
+}
+
+/// Outcome of one startup check
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupCheckResult {
+    pub name: String,
+    /// Critical checks decide the startup mode; others are only logged
+    pub critical: bool,
+    pub error: Option<String>,
+}
+
+impl StartupCheckResult {
+    pub fn from_outcome<E: fmt::Display>(name: &str, critical: bool, outcome: Result<(), E>) -> Self {
+        Self {
+            name: name.to_string(),
+            critical,
+            error: outcome.err().map(|e| e.to_string()),
+        }
+    }
+
+    pub fn passed(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// How the app is running after the startup gate
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "camelCase")]
+pub enum StartupMode {
+    Normal,
+    /// Critical checks failed; data can be viewed but not changed
+    #[serde(rename_all = "camelCase")]
+    ReadOnly { failed_checks: Vec<String> },
+}
+
+/// Critical startup checks failed and the policy refuses to start
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Startup self-tests failed: {}", .failed_checks.join(", "))]
+pub struct StartupRefused {
+    pub failed_checks: Vec<String>,
+}
+
+/// Startup mode the policy allows for these check results
+///
+/// Failed non-critical checks never change the mode. When the policy is
+/// disabled the results are ignored, since the checks were not meant to run.
+pub fn startup_mode(
+    policy: &StartupSelfTestPolicy,
+    results: &[StartupCheckResult],
+) -> Result<StartupMode, StartupRefused> {
+    if !policy.enabled {
+        return Ok(StartupMode::Normal);
+    }
+
+    for result in results.iter().filter(|r| !r.passed()) {
+        let error = result.error.as_deref().unwrap_or_default();
+        if result.critical {
+            tracing::error!("Critical startup check '{}' failed: {}", result.name, error);
+        } else {
+            tracing::warn!("Startup check '{}' failed: {}", result.name, error);
+        }
+    }
+
+    let failed_checks: Vec<String> = results
+        .iter()
+        .filter(|r| r.critical && !r.passed())
+        .map(|r| r.name.clone())
+        .collect();
+    if failed_checks.is_empty() {
+        return Ok(StartupMode::Normal);
+    }
+
+    match policy.on_failure {
+        StartupFailureMode::Refuse => Err(StartupRefused { failed_checks }),
+        StartupFailureMode::ReadOnly => Ok(StartupMode::ReadOnly { failed_checks }),
+    }
+}
+
+/// Startup mode shared with commands once the gate has run
+#[derive(Debug, Default)]
+pub struct StartupGate {
+    mode: OnceLock<StartupMode>,
+}
+
+impl StartupGate {
+    /// Record the mode decided at startup; later calls are ignored
+    pub fn record(&self, mode: StartupMode) {
+        if self.mode.set(mode).is_err() {
+            tracing::warn!("Startup mode was already recorded");
+        }
+    }
+
+    /// Current mode; `Normal` until the gate has run
+    pub fn mode(&self) -> StartupMode {
+        self.mode.get().cloned().unwrap_or(StartupMode::Normal)
+    }
+
+    /// Refuse changes while the app is running read-only
+    pub fn ensure_writable(&self) -> Result<(), String> {
+        match self.mode.get() {
+            Some(StartupMode::ReadOnly { failed_checks }) => Err(format!(
+                "Atlas is running read-only because startup checks failed: {}",
+                failed_checks.join(", ")
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn results(vault: Result<(), &str>) -> Vec<StartupCheckResult> {
+        vec![
+            StartupCheckResult::from_outcome("vault", true, vault),
+            StartupCheckResult::from_outcome("database", true, Ok::<(), &str>(())),
+            StartupCheckResult::from_outcome("sql_injection", false, Err("detection rate 80%")),
+        ]
+    }
+
+    fn policy(on_failure: StartupFailureMode) -> StartupSelfTestPolicy {
+        StartupSelfTestPolicy { enabled: true, on_failure }
+    }
+
+    #[test]
+    fn test_failing_vault_check_enters_the_configured_safe_mode() {
+        let failing = results(Err("Key derivation failed"));
+
+        let refused = startup_mode(&policy(StartupFailureMode::Refuse), &failing).unwrap_err();
+        assert_eq!(refused.failed_checks, vec!["vault".to_string()]);
+        assert_eq!(refused.to_string(), "Startup self-tests failed: vault");
+
+        let mode = startup_mode(&policy(StartupFailureMode::ReadOnly), &failing).unwrap();
+        assert_eq!(mode, StartupMode::ReadOnly { failed_checks: vec!["vault".to_string()] });
+
+        let gate = StartupGate::default();
+        assert!(gate.ensure_writable().is_ok());
+        gate.record(mode);
+        let error = gate.ensure_writable().unwrap_err();
+        assert!(error.contains("read-only") && error.contains("vault"));
+
+        // A disabled gate does not act on results
+        let disabled = StartupSelfTestPolicy { enabled: false, ..policy(StartupFailureMode::Refuse) };
+        assert_eq!(startup_mode(&disabled, &failing).unwrap(), StartupMode::Normal);
+    }
+
+    #[test]
+    fn test_non_critical_failures_start_normally() {
+        let mode = startup_mode(&policy(StartupFailureMode::Refuse), &results(Ok(()))).unwrap();
+        assert_eq!(mode, StartupMode::Normal);
+
+        let json = serde_json::to_value(StartupMode::ReadOnly { failed_checks: vec!["database".to_string()] }).unwrap();
+        assert_eq!(json, serde_json::json!({ "mode": "readOnly", "failedChecks": ["database"] }));
+    }
+}
@@ -2,14 +2,33 @@
 // Certificate pinning, HTTPS enforcement, and secure communication
 
 use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
-use reqwest::{Client, Certificate, ClientBuilder};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use parking_lot::RwLock;
+use reqwest::{Client, ClientBuilder};
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::client::WebPkiServerVerifier;
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{DigitallySignedStruct, RootCertStore, SignatureScheme, SupportedProtocolVersion};
 use serde::{Deserialize, Serialize};
 use sha2::{Sha256, Digest};
-use tokio::sync::RwLock;
 use tracing::{error, info, warn};
 
+/// Environment variable pointing at the TOML pin configuration file
+pub const PIN_CONFIG_ENV: &str = "SECURITY_TLS_PIN_CONFIG";
+/// Environment variable controlling whether every host needs a backup pin
+pub const REQUIRE_BACKUP_PIN_ENV: &str = "SECURITY_TLS_REQUIRE_BACKUP_PIN";
+/// Environment variable holding the minimum TLS version, `1.2` or `1.3`
+pub const MIN_TLS_VERSION_ENV: &str = "SECURITY_TLS_MIN_VERSION";
+
+type SharedPins = Arc<RwLock<HashMap<String, CertificatePin>>>;
+
+/// Pins for one host. Values are base64 SHA-256 hashes of the
+/// certificate's SubjectPublicKeyInfo, as used by HPKP.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CertificatePin {
     pub domain: String,
@@ -20,9 +39,163 @@ pub struct CertificatePin {
     pub last_verified: Option<u64>,
 }
 
+/// Which pin set a presented certificate matched
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PinMatch {
+    Primary,
+    Backup,
+}
+
+/// Pin configuration for a single host as written in the config file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostPinConfig {
+    pub host: String,
+    pub pins: Vec<String>,
+    #[serde(default)]
+    pub backup_pins: Vec<String>,
+    /// Unix timestamp after which the pins should be rotated
+    pub expires_at: Option<u64>,
+}
+
+/// Certificate pin configuration loaded at startup
+///
+/// ```toml
+/// require_backup_pin = true
+///
+/// [[hosts]]
+/// host = "api.atlas-financial.com"
+/// pins = ["<base64 sha256 spki>", "<next rotation pin>"]
+/// backup_pins = ["<offline backup key pin>"]
+/// expires_at = 1798761600
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TlsPinConfig {
+    #[serde(default = "default_require_backup_pin")]
+    pub require_backup_pin: bool,
+    #[serde(default)]
+    pub hosts: Vec<HostPinConfig>,
+}
+
+fn default_require_backup_pin() -> bool {
+    true
+}
+
+impl Default for TlsPinConfig {
+    fn default() -> Self {
+        Self {
+            require_backup_pin: default_require_backup_pin(),
+            hosts: Vec::new(),
+        }
+    }
+}
+
+impl TlsPinConfig {
+    /// Parse pin configuration from TOML
+    pub fn from_toml_str(contents: &str) -> Result<Self, TlsError> {
+        toml::from_str(contents).map_err(|e| TlsError::PinConfig(e.to_string()))
+    }
+
+    /// Load pin configuration from a TOML file
+    pub fn from_file(path: &Path) -> Result<Self, TlsError> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| TlsError::PinConfig(format!("{}: {}", path.display(), e)))?;
+        Self::from_toml_str(&contents)
+    }
+
+    /// Load pin configuration from the file named by `SECURITY_TLS_PIN_CONFIG`
+    ///
+    /// Without the variable no hosts are pinned and connections rely on
+    /// standard WebPKI validation only.
+    pub fn from_env() -> Result<Self, TlsError> {
+        let mut config = match std::env::var(PIN_CONFIG_ENV) {
+            Ok(path) if !path.trim().is_empty() => Self::from_file(Path::new(path.trim()))?,
+            _ => {
+                warn!("⚠️ {} not set, no certificate pins configured", PIN_CONFIG_ENV);
+                Self::default()
+            }
+        };
+
+        if let Ok(value) = std::env::var(REQUIRE_BACKUP_PIN_ENV) {
+            config.require_backup_pin = value.to_lowercase() == "true";
+        }
+
+        Ok(config)
+    }
+
+    /// Validate every host and convert to pins keyed by host name
+    pub fn into_pins(self) -> Result<HashMap<String, CertificatePin>, TlsError> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let mut pins = HashMap::new();
+
+        for host in self.hosts {
+            let domain = host.host.trim().to_lowercase();
+            let pin = CertificatePin {
+                domain: domain.clone(),
+                sha256_pins: host.pins,
+                backup_pins: host.backup_pins,
+                issued_at: now,
+                expires_at: host.expires_at.unwrap_or(u64::MAX),
+                last_verified: None,
+            };
+
+            validate_pin(&pin, self.require_backup_pin)?;
+            pins.insert(domain, pin);
+        }
+
+        Ok(pins)
+    }
+}
+
+/// Oldest TLS version a connection may negotiate
+///
+/// rustls never offers TLS 1.0 or 1.1, so servers limited to those versions
+/// fail the handshake under either setting.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MinTlsVersion {
+    #[default]
+    #[serde(rename = "1.2")]
+    Tls12,
+    #[serde(rename = "1.3")]
+    Tls13,
+}
+
+impl MinTlsVersion {
+    /// Protocol versions the client offers, newest first
+    pub fn protocol_versions(self) -> &'static [&'static SupportedProtocolVersion] {
+        static TLS12_AND_LATER: [&SupportedProtocolVersion; 2] = [&rustls::version::TLS13, &rustls::version::TLS12];
+        static TLS13_ONLY: [&SupportedProtocolVersion; 1] = [&rustls::version::TLS13];
+
+        match self {
+            MinTlsVersion::Tls12 => &TLS12_AND_LATER,
+            MinTlsVersion::Tls13 => &TLS13_ONLY,
+        }
+    }
+}
+
+impl fmt::Display for MinTlsVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MinTlsVersion::Tls12 => write!(f, "1.2"),
+            MinTlsVersion::Tls13 => write!(f, "1.3"),
+        }
+    }
+}
+
+impl FromStr for MinTlsVersion {
+    type Err = TlsError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim() {
+            "1.2" => Ok(MinTlsVersion::Tls12),
+            "1.3" => Ok(MinTlsVersion::Tls13),
+            other => Err(TlsError::UnsupportedTlsVersion(other.to_string())),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlsPolicy {
-    pub min_tls_version: String,
+    pub min_tls_version: MinTlsVersion,
     pub cipher_suites: Vec<String>,
     pub require_sni: bool,
     pub require_ocsp_stapling: bool,
@@ -33,14 +206,28 @@ pub struct TlsPolicy {
 #[derive(Debug)]
 pub struct SecureTlsClient {
     client: Client,
-    pins: Arc<RwLock<HashMap<String, CertificatePin>>>,
+    pins: SharedPins,
     policy: TlsPolicy,
+    require_backup_pin: bool,
+}
+
+impl TlsPolicy {
+    /// Default policy with the minimum version from `SECURITY_TLS_MIN_VERSION`
+    pub fn from_env() -> Result<Self, TlsError> {
+        let mut policy = Self::default();
+        if let Ok(value) = std::env::var(MIN_TLS_VERSION_ENV) {
+            if !value.trim().is_empty() {
+                policy.min_tls_version = value.parse()?;
+            }
+        }
+        Ok(policy)
+    }
 }
 
 impl Default for TlsPolicy {
     fn default() -> Self {
         Self {
-            min_tls_version: "1.3".to_string(),
+            min_tls_version: MinTlsVersion::default(),
             cipher_suites: vec![
                 "TLS_AES_256_GCM_SHA384".to_string(),
                 "TLS_CHACHA20_POLY1305_SHA256".to_string(),
@@ -54,112 +241,150 @@ impl Default for TlsPolicy {
     }
 }
 
+/// Server certificate verifier that enforces configured pins
+///
+/// Standard WebPKI chain validation runs first. For pinned hosts at least one
+/// certificate in the presented chain must match a primary or backup pin,
+/// otherwise the handshake is aborted. Hosts without pins are not affected.
+#[derive(Debug)]
+pub struct PinnedCertVerifier {
+    inner: Arc<WebPkiServerVerifier>,
+    pins: SharedPins,
+}
+
+impl PinnedCertVerifier {
+    fn new(roots: RootCertStore, pins: SharedPins) -> Result<Self, TlsError> {
+        let inner = WebPkiServerVerifier::builder_with_provider(
+            Arc::new(roots),
+            Arc::new(rustls::crypto::ring::default_provider()),
+        )
+        .build()
+        .map_err(|e| TlsError::CertificateError(e.to_string()))?;
+
+        Ok(Self { inner, pins })
+    }
+}
+
+impl ServerCertVerifier for PinnedCertVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let verified = self.inner.verify_server_cert(end_entity, intermediates, server_name, ocsp_response, now)?;
+
+        let host = server_name.to_str().to_lowercase();
+        let chain: Vec<&[u8]> = std::iter::once(end_entity.as_ref())
+            .chain(intermediates.iter().map(|c| c.as_ref()))
+            .collect();
+
+        let mut pins = self.pins.write();
+        let Some(pin) = pins.get_mut(&host) else {
+            return Ok(verified);
+        };
+
+        match check_chain_pins(pin, &chain) {
+            Ok(_) => {
+                pin.last_verified = Some(SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs());
+                Ok(verified)
+            }
+            Err(e) => {
+                error!("❌ {}", e);
+                Err(rustls::Error::General(e.to_string()))
+            }
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.inner.supported_verify_schemes()
+    }
+}
+
 impl SecureTlsClient {
-    /// Create new secure TLS client with certificate pinning
+    /// Create new secure TLS client with pins and policy loaded from configuration
     pub async fn new() -> Result<Self, TlsError> {
-        let policy = TlsPolicy::default();
-        let pins = Arc::new(RwLock::new(HashMap::new()));
+        Self::with_policy(TlsPinConfig::from_env()?, TlsPolicy::from_env()?)
+    }
 
-        // Initialize with SuperTokens pinned certificates
-        let mut initial_pins = HashMap::new();
+    /// Create secure TLS client enforcing the given pin configuration
+    pub fn with_pin_config(config: TlsPinConfig) -> Result<Self, TlsError> {
+        Self::with_policy(config, TlsPolicy::default())
+    }
 
-        // SuperTokens production certificate pins (example - replace with actual)
-        let supertokens_pin = CertificatePin {
-            domain: "api.atlas-financial.com".to_string(),
-            sha256_pins: vec![
-                "C5:B1:AB:4E:4C:B1:CD:DE:67:05:58:B3:1A:5E:38:32:E4:83:99:2E:01:03:F6:4A:25:4C:66:5E:7C:B6:AE:50".to_string(),
-            ],
-            backup_pins: vec![
-                "E7:2D:C5:2C:C0:E1:B0:73:06:74:B8:7C:15:DC:B6:5B:2B:1A:F5:F7:A3:5B:84:F6:A6:2F:1E:17:B3:F8:CA:58".to_string(),
-            ],
-            issued_at: SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs(),
-            expires_at: SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() + (365 * 24 * 3600), // 1 year
-            last_verified: None,
-        };
+    /// Create secure TLS client enforcing the given pins and TLS policy
+    pub fn with_policy(config: TlsPinConfig, policy: TlsPolicy) -> Result<Self, TlsError> {
+        let require_backup_pin = config.require_backup_pin;
+        let pins: SharedPins = Arc::new(RwLock::new(config.into_pins()?));
 
-        initial_pins.insert("api.atlas-financial.com".to_string(), supertokens_pin);
-        *pins.write().await = initial_pins;
+        let roots = RootCertStore {
+            roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+        };
+        let verifier = PinnedCertVerifier::new(roots, pins.clone())?;
+        let tls_config = tls_client_config(&policy, Arc::new(verifier))?;
 
         // Build secure HTTP client
         let client = ClientBuilder::new()
-            .use_rustls_tls()
-            .min_tls_version(reqwest::tls::Version::TLS_1_3)
+            .use_preconfigured_tls(tls_config)
             .https_only(true)
             .timeout(Duration::from_secs(30))
             .connection_verbose(true)
             .build()
             .map_err(TlsError::ClientBuild)?;
 
-        info!("🔒 Secure TLS client initialized with certificate pinning");
+        info!(
+            "🔒 Secure TLS client initialized with {} pinned hosts, TLS {}+",
+            pins.read().len(),
+            policy.min_tls_version
+        );
 
         Ok(Self {
             client,
             pins,
             policy,
+            require_backup_pin,
         })
     }
 
     /// Add certificate pin for domain
     pub async fn add_certificate_pin(&self, pin: CertificatePin) -> Result<(), TlsError> {
-        let mut pins = self.pins.write().await;
+        validate_pin(&pin, self.require_backup_pin)?;
 
-        // Validate pin format
-        for pin_value in &pin.sha256_pins {
-            if !Self::is_valid_pin_format(pin_value) {
-                return Err(TlsError::InvalidPin(pin_value.clone()));
-            }
-        }
-
-        for backup_pin in &pin.backup_pins {
-            if !Self::is_valid_pin_format(backup_pin) {
-                return Err(TlsError::InvalidPin(backup_pin.clone()));
-            }
-        }
-
-        pins.insert(pin.domain.clone(), pin);
-        info!("📍 Certificate pin added for domain: {}", pins.len());
+        let mut pins = self.pins.write();
+        pins.insert(pin.domain.to_lowercase(), pin);
+        info!("📍 Certificate pin added, {} domains pinned", pins.len());
 
         Ok(())
     }
 
-    /// Verify certificate against pinned values
-    pub async fn verify_certificate_pin(&self, domain: &str, cert_der: &[u8]) -> Result<bool, TlsError> {
-        let pins = self.pins.read().await;
+    /// Verify a DER certificate against the pins configured for a domain
+    pub async fn verify_certificate_pin(&self, domain: &str, cert_der: &[u8]) -> Result<PinMatch, TlsError> {
+        let pins = self.pins.read();
 
-        let pin = pins.get(domain)
+        let pin = pins.get(&domain.to_lowercase())
             .ok_or_else(|| TlsError::NoPinForDomain(domain.to_string()))?;
 
-        // Calculate SHA-256 hash of certificate
-        let mut hasher = Sha256::new();
-        hasher.update(cert_der);
-        let cert_hash = hasher.finalize();
-        let cert_pin = format!("{:X}", cert_hash).chars()
-            .enumerate()
-            .fold(String::new(), |mut acc, (i, c)| {
-                if i > 0 && i % 2 == 0 {
-                    acc.push(':');
-                }
-                acc.push(c);
-                acc
-            });
-
-        // Check against primary pins
-        if pin.sha256_pins.contains(&cert_pin) {
-            info!("✅ Certificate pin verified for {}", domain);
-            return Ok(true);
-        }
-
-        // Check against backup pins
-        if pin.backup_pins.contains(&cert_pin) {
-            warn!("⚠️ Using backup certificate pin for {}", domain);
-            return Ok(true);
-        }
-
-        error!("❌ Certificate pin verification failed for {}", domain);
-        error!("Expected one of: {:?}", pin.sha256_pins);
-        error!("Got: {}", cert_pin);
-
-        Err(TlsError::PinVerificationFailed(domain.to_string()))
+        check_chain_pins(pin, &[cert_der])
     }
 
     /// Make secure HTTPS request with certificate pinning
@@ -239,7 +464,7 @@ impl SecureTlsClient {
 
     /// Check if certificate pins need renewal
     pub async fn check_pin_expiration(&self) -> Vec<String> {
-        let pins = self.pins.read().await;
+        let pins = self.pins.read();
         let current_time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
         let warning_threshold = 30 * 24 * 3600; // 30 days
 
@@ -256,7 +481,11 @@ impl SecureTlsClient {
 
     /// Update certificate pin for domain
     pub async fn update_certificate_pin(&self, domain: &str, new_pin: CertificatePin) -> Result<(), TlsError> {
-        let mut pins = self.pins.write().await;
+        validate_pin(&new_pin, self.require_backup_pin)?;
+
+        let domain = domain.to_lowercase();
+        let domain = domain.as_str();
+        let mut pins = self.pins.write();
 
         if !pins.contains_key(domain) {
             return Err(TlsError::NoPinForDomain(domain.to_string()));
@@ -270,7 +499,7 @@ impl SecureTlsClient {
 
     /// Generate security report for TLS configuration
     pub async fn generate_security_report(&self) -> TlsSecurityReport {
-        let pins = self.pins.read().await;
+        let pins = self.pins.read();
         let current_time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
 
         let total_pins = pins.len();
@@ -300,7 +529,7 @@ impl SecureTlsClient {
             total_pins,
             expired_pins,
             expiring_soon,
-            tls_version: self.policy.min_tls_version.clone(),
+            tls_version: self.policy.min_tls_version.to_string(),
             hsts_enabled: true,
             certificate_transparency: self.policy.certificate_transparency,
             recommendations,
@@ -308,21 +537,9 @@ impl SecureTlsClient {
         }
     }
 
-    /// Validate pin format (SHA-256 fingerprint)
+    /// Validate pin format (base64 SHA-256 SPKI hash)
     fn is_valid_pin_format(pin: &str) -> bool {
-        // Expected format: XX:XX:XX:... (64 hex characters with colons)
-        if pin.len() != 95 { // 64 chars + 31 colons
-            return false;
-        }
-
-        let parts: Vec<&str> = pin.split(':').collect();
-        if parts.len() != 32 {
-            return false;
-        }
-
-        parts.iter().all(|part| {
-            part.len() == 2 && part.chars().all(|c| c.is_ascii_hexdigit())
-        })
+        BASE64.decode(pin).map(|bytes| bytes.len() == 32).unwrap_or(false)
     }
 
     /// Calculate security score based on pin status
@@ -365,8 +582,14 @@ pub enum TlsError {
     #[error("No certificate pin configured for domain: {0}")]
     NoPinForDomain(String),
 
-    #[error("Certificate pin verification failed for domain: {0}")]
-    PinVerificationFailed(String),
+    #[error("Certificate pin mismatch for domain {domain}: presented {presented:?}")]
+    PinMismatch { domain: String, presented: Vec<String> },
+
+    #[error("Host {0} must configure a backup pin distinct from its primary pins")]
+    MissingBackupPin(String),
+
+    #[error("Invalid certificate pin configuration: {0}")]
+    PinConfig(String),
 
     #[error("Invalid certificate pin format: {0}")]
     InvalidPin(String),
@@ -376,6 +599,80 @@ pub enum TlsError {
 
     #[error("Certificate parsing error: {0}")]
     CertificateError(String),
+
+    #[error("Unsupported minimum TLS version: {0} (expected 1.2 or 1.3)")]
+    UnsupportedTlsVersion(String),
+}
+
+/// rustls configuration offering only the protocol versions `policy` allows
+///
+/// Connections to servers that cannot negotiate one of them fail during the
+/// handshake rather than falling back to an older version.
+fn tls_client_config(policy: &TlsPolicy, verifier: Arc<dyn ServerCertVerifier>) -> Result<rustls::ClientConfig, TlsError> {
+    let mut tls_config = rustls::ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+        .with_protocol_versions(policy.min_tls_version.protocol_versions())
+        .map_err(|e| TlsError::CertificateError(e.to_string()))?
+        .dangerous()
+        .with_custom_certificate_verifier(verifier)
+        .with_no_client_auth();
+    tls_config.alpn_protocols = vec![b"http/1.1".to_vec()];
+    Ok(tls_config)
+}
+
+/// Base64 SHA-256 hash of a DER certificate's SubjectPublicKeyInfo
+pub fn compute_spki_pin(cert_der: &[u8]) -> Result<String, TlsError> {
+    let (_, cert) = x509_parser::parse_x509_certificate(cert_der)
+        .map_err(|e| TlsError::CertificateError(e.to_string()))?;
+
+    let digest = Sha256::digest(cert.tbs_certificate.subject_pki.raw);
+    Ok(BASE64.encode(digest))
+}
+
+/// Check that a pin is well formed and, if required, has a usable backup
+fn validate_pin(pin: &CertificatePin, require_backup_pin: bool) -> Result<(), TlsError> {
+    if pin.domain.trim().is_empty() {
+        return Err(TlsError::PinConfig("pinned host name is empty".to_string()));
+    }
+
+    if pin.sha256_pins.is_empty() {
+        return Err(TlsError::PinConfig(format!("no pins configured for {}", pin.domain)));
+    }
+
+    for value in pin.sha256_pins.iter().chain(pin.backup_pins.iter()) {
+        if !SecureTlsClient::is_valid_pin_format(value) {
+            return Err(TlsError::InvalidPin(value.clone()));
+        }
+    }
+
+    // A backup only helps if it survives loss of the primary key
+    if require_backup_pin && !pin.backup_pins.iter().any(|b| !pin.sha256_pins.contains(b)) {
+        return Err(TlsError::MissingBackupPin(pin.domain.clone()));
+    }
+
+    Ok(())
+}
+
+/// Match any certificate in a chain against a host's primary and backup pins
+fn check_chain_pins(pin: &CertificatePin, chain: &[&[u8]]) -> Result<PinMatch, TlsError> {
+    let mut presented = Vec::with_capacity(chain.len());
+    for cert_der in chain {
+        presented.push(compute_spki_pin(cert_der)?);
+    }
+
+    if presented.iter().any(|p| pin.sha256_pins.contains(p)) {
+        info!("✅ Certificate pin verified for {}", pin.domain);
+        return Ok(PinMatch::Primary);
+    }
+
+    if presented.iter().any(|p| pin.backup_pins.contains(p)) {
+        warn!("⚠️ Using backup certificate pin for {}", pin.domain);
+        return Ok(PinMatch::Backup);
+    }
+
+    Err(TlsError::PinMismatch {
+        domain: pin.domain.clone(),
+        presented,
+    })
 }
 
 /// Create global secure TLS client instance
@@ -408,16 +705,195 @@ pub fn extract_domain(url: &str) -> Result<String, TlsError> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rustls::pki_types::PrivateKeyDer;
+    use rustls::ProtocolVersion;
+
+    const HOST: &str = "api.atlas-financial.com";
+    const UNRELATED_PIN: &str = "47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU=";
+
+    /// Issue a CA and a leaf certificate for `host` signed by it
+    fn issue_chain(host: &str) -> (CertificateDer<'static>, CertificateDer<'static>) {
+        let (ca, leaf, _) = issue_chain_with_key(host);
+        (ca, leaf)
+    }
+
+    /// Like `issue_chain`, also returning the leaf's private key
+    fn issue_chain_with_key(host: &str) -> (CertificateDer<'static>, CertificateDer<'static>, PrivateKeyDer<'static>) {
+        let mut ca_params = rcgen::CertificateParams::new(Vec::new()).unwrap();
+        ca_params.is_ca = rcgen::IsCa::Ca(rcgen::BasicConstraints::Unconstrained);
+        let ca_key = rcgen::KeyPair::generate().unwrap();
+        let ca = ca_params.self_signed(&ca_key).unwrap();
+
+        let leaf_key = rcgen::KeyPair::generate().unwrap();
+        let leaf = rcgen::CertificateParams::new(vec![host.to_string()])
+            .unwrap()
+            .signed_by(&leaf_key, &ca, &ca_key)
+            .unwrap();
+
+        let leaf_key = PrivateKeyDer::try_from(leaf_key.serialize_der()).unwrap();
+        (ca.der().clone(), leaf.der().clone(), leaf_key)
+    }
+
+    fn host_pin(pins: Vec<String>, backup_pins: Vec<String>) -> CertificatePin {
+        CertificatePin {
+            domain: HOST.to_string(),
+            sha256_pins: pins,
+            backup_pins,
+            issued_at: 0,
+            expires_at: u64::MAX,
+            last_verified: None,
+        }
+    }
+
+    fn verifier_for(ca: &CertificateDer<'static>, pin: CertificatePin) -> PinnedCertVerifier {
+        let mut roots = RootCertStore::empty();
+        roots.add(ca.clone()).unwrap();
+
+        let pins = HashMap::from([(pin.domain.clone(), pin)]);
+        PinnedCertVerifier::new(roots, Arc::new(RwLock::new(pins))).unwrap()
+    }
+
+    fn verify(verifier: &PinnedCertVerifier, leaf: &CertificateDer<'static>) -> Result<ServerCertVerified, rustls::Error> {
+        let server_name = ServerName::try_from(HOST).unwrap();
+        verifier.verify_server_cert(leaf, &[], &server_name, &[], UnixTime::now())
+    }
 
     #[test]
     fn test_pin_format_validation() {
-        let valid_pin = "C5:B1:AB:4E:4C:B1:CD:DE:67:05:58:B3:1A:5E:38:32:E4:83:99:2E:01:03:F6:4A:25:4C:66:5E:7C:B6:AE:50";
-        assert!(SecureTlsClient::is_valid_pin_format(valid_pin));
+        assert!(SecureTlsClient::is_valid_pin_format(UNRELATED_PIN));
+
+        let hex_fingerprint = "C5:B1:AB:4E:4C:B1:CD:DE:67:05:58:B3:1A:5E:38:32:E4:83:99:2E:01:03:F6:4A:25:4C:66:5E:7C:B6:AE:50";
+        assert!(!SecureTlsClient::is_valid_pin_format(hex_fingerprint));
 
         let invalid_pin = "invalid-pin-format";
         assert!(!SecureTlsClient::is_valid_pin_format(invalid_pin));
     }
 
+    #[test]
+    fn test_pin_config_requires_backup_pin() {
+        let without_backup = format!(
+            "[[hosts]]\nhost = \"{}\"\npins = [\"{}\"]\n",
+            HOST, UNRELATED_PIN
+        );
+
+        let config = TlsPinConfig::from_toml_str(&without_backup).unwrap();
+        assert!(config.require_backup_pin);
+        assert!(matches!(config.into_pins(), Err(TlsError::MissingBackupPin(host)) if host == HOST));
+
+        let relaxed = format!("require_backup_pin = false\n{}", without_backup);
+        let pins = TlsPinConfig::from_toml_str(&relaxed).unwrap().into_pins().unwrap();
+        assert_eq!(pins[HOST].sha256_pins, vec![UNRELATED_PIN.to_string()]);
+    }
+
+    #[test]
+    fn test_matching_pin_is_accepted() {
+        let (ca, leaf) = issue_chain(HOST);
+        let leaf_pin = compute_spki_pin(&leaf).unwrap();
+        let verifier = verifier_for(&ca, host_pin(vec![leaf_pin], vec![UNRELATED_PIN.to_string()]));
+
+        assert!(verify(&verifier, &leaf).is_ok());
+        assert!(verifier.pins.read()[HOST].last_verified.is_some());
+    }
+
+    #[test]
+    fn test_mismatched_pin_is_rejected() {
+        let (ca, leaf) = issue_chain(HOST);
+        let pin = host_pin(vec![UNRELATED_PIN.to_string()], vec![]);
+
+        let err = check_chain_pins(&pin, &[leaf.as_ref()]).unwrap_err();
+        assert!(matches!(err, TlsError::PinMismatch { ref domain, .. } if domain == HOST));
+
+        // The handshake fails closed even though the chain itself is trusted
+        let verifier = verifier_for(&ca, pin);
+        assert!(verify(&verifier, &leaf).is_err());
+    }
+
+    #[test]
+    fn test_backup_and_rotated_pins_are_accepted() {
+        let (_, leaf) = issue_chain(HOST);
+        let leaf_pin = compute_spki_pin(&leaf).unwrap();
+
+        let rotated = host_pin(vec![UNRELATED_PIN.to_string(), leaf_pin.clone()], vec![]);
+        assert_eq!(check_chain_pins(&rotated, &[leaf.as_ref()]).unwrap(), PinMatch::Primary);
+
+        let backup = host_pin(vec![UNRELATED_PIN.to_string()], vec![leaf_pin]);
+        assert_eq!(check_chain_pins(&backup, &[leaf.as_ref()]).unwrap(), PinMatch::Backup);
+    }
+
+    #[test]
+    fn test_unpinned_host_uses_standard_validation() {
+        let (ca, leaf) = issue_chain(HOST);
+        let mut pin = host_pin(vec![UNRELATED_PIN.to_string()], vec![]);
+        pin.domain = "other.atlas-financial.com".to_string();
+
+        assert!(verify(&verifier_for(&ca, pin), &leaf).is_ok());
+    }
+
+    fn client_config(ca: &CertificateDer<'static>, min_tls_version: MinTlsVersion) -> rustls::ClientConfig {
+        let mut roots = RootCertStore::empty();
+        roots.add(ca.clone()).unwrap();
+        let verifier = PinnedCertVerifier::new(roots, Arc::new(RwLock::new(HashMap::new()))).unwrap();
+        let policy = TlsPolicy { min_tls_version, ..TlsPolicy::default() };
+        tls_client_config(&policy, Arc::new(verifier)).unwrap()
+    }
+
+    /// Run a handshake in memory against a server offering only `versions`
+    fn handshake(
+        client_config: rustls::ClientConfig,
+        versions: &[&'static SupportedProtocolVersion],
+        leaf: CertificateDer<'static>,
+        key: PrivateKeyDer<'static>,
+    ) -> Result<ProtocolVersion, rustls::Error> {
+        let server_config = rustls::ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+            .with_protocol_versions(versions)
+            .unwrap()
+            .with_no_client_auth()
+            .with_single_cert(vec![leaf], key)
+            .unwrap();
+
+        let mut client = rustls::ClientConnection::new(Arc::new(client_config), ServerName::try_from(HOST).unwrap())?;
+        let mut server = rustls::ServerConnection::new(Arc::new(server_config))?;
+        while client.is_handshaking() || server.is_handshaking() {
+            let mut records = Vec::new();
+            client.write_tls(&mut records).unwrap();
+            server.read_tls(&mut records.as_slice()).unwrap();
+            server.process_new_packets()?;
+
+            records.clear();
+            server.write_tls(&mut records).unwrap();
+            client.read_tls(&mut records.as_slice()).unwrap();
+            client.process_new_packets()?;
+        }
+        Ok(client.protocol_version().unwrap())
+    }
+
+    #[test]
+    fn test_min_tls_version_limits_offered_protocols() {
+        let offered = |min: MinTlsVersion| -> Vec<ProtocolVersion> {
+            min.protocol_versions().iter().map(|v| v.version).collect()
+        };
+
+        assert_eq!(TlsPolicy::default().min_tls_version, MinTlsVersion::Tls12);
+        assert_eq!(offered(MinTlsVersion::Tls12), vec![ProtocolVersion::TLSv1_3, ProtocolVersion::TLSv1_2]);
+        assert_eq!(offered(MinTlsVersion::Tls13), vec![ProtocolVersion::TLSv1_3]);
+
+        assert_eq!("1.3".parse::<MinTlsVersion>().unwrap(), MinTlsVersion::Tls13);
+        for legacy in ["1.0", "1.1"] {
+            assert!(matches!(legacy.parse::<MinTlsVersion>(), Err(TlsError::UnsupportedTlsVersion(_))));
+        }
+    }
+
+    #[test]
+    fn test_tls13_only_client_refuses_tls12_server() {
+        let (ca, leaf, key) = issue_chain_with_key(HOST);
+        let tls12_only = &[&rustls::version::TLS12];
+
+        let negotiated = handshake(client_config(&ca, MinTlsVersion::Tls12), tls12_only, leaf.clone(), key.clone_key());
+        assert_eq!(negotiated.unwrap(), ProtocolVersion::TLSv1_2);
+
+        assert!(handshake(client_config(&ca, MinTlsVersion::Tls13), tls12_only, leaf, key).is_err());
+    }
+
     #[test]
     fn test_https_validation() {
         assert!(validate_https_url("https://api.atlas-financial.com").is_ok());
@@ -517,7 +517,7 @@ fn assess_compliance_status(cert_tests: &[CertificateTest], https_tests: &[Https
         ssl_labs_grade,
         hsts_compliant: https_passed,
         certificate_transparency: cert_passed,
-        tls_version_compliant: true, // TLS 1.3 enforced
+        tls_version_compliant: true, // Minimum TLS version enforced at the handshake
     }
 }
 
@@ -9,6 +9,7 @@ use thiserror::Error;
 use pbkdf2::pbkdf2_hmac;
 use sha2::Sha256;
 use chrono::{DateTime, Utc, Duration};
+use zeroize::{Zeroize, Zeroizing};
 
 /// SecureVault Error Types
 #[derive(Error, Debug)]
@@ -25,6 +26,8 @@ pub enum VaultError {
     KeychainFailed(String),
     #[error("Key rotation failed: {0}")]
     KeyRotationFailed(String),
+    #[error("Vault is locked; sign in again to unlock it")]
+    Locked,
     #[error("Audit logging failed: {0}")]
     AuditFailed(String),
     #[error("IO error: {0}")]
@@ -64,10 +67,176 @@ struct EncryptedKeyData {
     pub version: u8,
 }
 
+/// Decrypted master key held in memory while the app is unlocked
+///
+/// Keys replaced by rotation are kept as retired keys so data sealed under
+/// an older generation still opens. Locking wipes every key and refuses all
+/// encryption and decryption, including the legacy key fallback, until the
+/// vault is unlocked again.
+#[derive(Default)]
+pub struct MasterKey {
+    key: Option<Zeroizing<[u8; 32]>>,
+    retired: Vec<Zeroizing<[u8; 32]>>,
+    locked: bool,
+}
+
+impl MasterKey {
+    /// Cache a derived or loaded key
+    pub fn set(&mut self, key: [u8; 32]) {
+        self.key = Some(Zeroizing::new(key));
+    }
+
+    /// Keep a key from an earlier generation for decryption only
+    pub fn add_retired(&mut self, key: [u8; 32]) {
+        self.retired.push(Zeroizing::new(key));
+    }
+
+    /// Move the current key to the retired keys ahead of a rotation
+    pub fn retire_current(&mut self) {
+        if let Some(key) = self.key.take() {
+            self.retired.insert(0, key);
+        }
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        self.key.is_some()
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+
+    /// Zeroize every key and refuse further use until `unlock`
+    pub fn lock(&mut self) {
+        if let Some(mut key) = self.key.take() {
+            key.zeroize();
+        }
+        for mut key in self.retired.drain(..) {
+            key.zeroize();
+        }
+        self.locked = true;
+    }
+
+    /// Allow the key to be loaded again; the caller must re-load it
+    pub fn unlock(&mut self) {
+        self.locked = false;
+    }
+
+    /// Encrypt data, prefixing the ciphertext with its nonce
+    pub fn encrypt(&self, data: &str) -> Result<Vec<u8>, VaultError> {
+        if self.locked {
+            return Err(VaultError::Locked);
+        }
+        let key = self.key.as_ref().ok_or_else(|| {
+            VaultError::EncryptionFailed("Vault not initialized".to_string())
+        })?;
+
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key.as_slice()));
+        let nonce = Aes256Gcm::generate_nonce(&mut rand::thread_rng());
+
+        let ciphertext = cipher.encrypt(&nonce, data.as_bytes())
+            .map_err(|e| VaultError::EncryptionFailed(e.to_string()))?;
+
+        // Prepend nonce to ciphertext
+        let mut result = nonce.to_vec();
+        result.extend_from_slice(&ciphertext);
+
+        Ok(result)
+    }
+
+    /// Decrypt data using the current key, then retired keys newest first
+    /// (with backward compatibility)
+    pub fn decrypt(&self, data: &[u8]) -> Result<String, VaultError> {
+        if self.locked {
+            return Err(VaultError::Locked);
+        }
+        if data.len() < 12 {
+            return Err(VaultError::DecryptionFailed("Invalid encrypted data".to_string()));
+        }
+
+        // Try current key first
+        if let Some(key) = &self.key {
+            if let Ok(result) = decrypt_with_key(data, key) {
+                return Ok(result);
+            }
+        }
+
+        // Data sealed before a rotation
+        for key in &self.retired {
+            if let Ok(result) = decrypt_with_key(data, key) {
+                return Ok(result);
+            }
+        }
+
+        // Try backward compatibility with hardcoded key (48-hour grace period)
+        let legacy_key = b"atlas_financial_desktop_key_32b!";
+        if let Ok(result) = decrypt_with_key(data, legacy_key) {
+            tracing::warn!("⚠️  Used legacy key for decryption - consider key migration");
+            return Ok(result);
+        }
+
+        Err(VaultError::DecryptionFailed("Unable to decrypt with any available key".to_string()))
+    }
+}
+
+/// Decrypt with specific key
+fn decrypt_with_key(data: &[u8], key: &[u8; 32]) -> Result<String, VaultError> {
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+
+    // Extract nonce and ciphertext
+    let (nonce, ciphertext) = data.split_at(12);
+    let nonce = Nonce::from_slice(nonce);
+
+    let plaintext = cipher.decrypt(nonce, ciphertext)
+        .map_err(|e| VaultError::DecryptionFailed(e.to_string()))?;
+
+    String::from_utf8(plaintext)
+        .map_err(|e| VaultError::DecryptionFailed(e.to_string()))
+}
+
+/// App data file listing retired key ids, newest first
+const RETIRED_KEY_IDS_FILE: &str = "retired_key_ids";
+
+/// Read a stored key from the OS keychain
+fn read_key_from_keychain(key_id: &str) -> Result<[u8; 32], VaultError> {
+    use keyring::Entry;
+
+    let key_entry = Entry::new("Atlas-Financial-Desktop", key_id)
+        .map_err(|e| VaultError::KeychainFailed(e.to_string()))?;
+
+    let key_b64 = key_entry.get_password()
+        .map_err(|e| VaultError::KeychainFailed(e.to_string()))?;
+
+    let key_bytes = Zeroizing::new(base64::decode(&key_b64)
+        .map_err(|e| VaultError::KeychainFailed(e.to_string()))?);
+
+    if key_bytes.len() != 32 {
+        return Err(VaultError::KeychainFailed("Invalid key length".to_string()));
+    }
+
+    let mut key = [0u8; 32];
+    key.copy_from_slice(&key_bytes);
+    Ok(key)
+}
+
+/// Ids of keys replaced by rotation; missing file means none
+async fn read_retired_key_ids(app_data_dir: &std::path::Path) -> Result<Vec<String>, VaultError> {
+    match tokio::fs::read_to_string(app_data_dir.join(RETIRED_KEY_IDS_FILE)).await {
+        Ok(contents) => Ok(contents
+            .lines()
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .map(str::to_string)
+            .collect()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
 /// SecureVault implementation with enterprise features
 pub struct SecureVault {
     app_handle: tauri::AppHandle,
-    master_key: Option<[u8; 32]>,
+    master_key: MasterKey,
     current_metadata: Option<KeyMetadata>,
 }
 
@@ -76,7 +245,7 @@ impl SecureVault {
     pub fn new(app_handle: tauri::AppHandle) -> Self {
         Self {
             app_handle,
-            master_key: None,
+            master_key: MasterKey::default(),
             current_metadata: None,
         }
     }
@@ -92,7 +261,7 @@ impl SecureVault {
         }
 
         // Generate new key with hardware fingerprinting
-        self.generate_new_key().await?;
+        self.generate_new_key(0).await?;
 
         // Audit log initialization
         self.audit_log("vault_initialized", "SecureVault initialized with new key").await?;
@@ -193,7 +362,11 @@ impl SecureVault {
     }
 
     /// Generate new encryption key with metadata
-    async fn generate_new_key(&mut self) -> Result<(), VaultError> {
+    ///
+    /// `rotation_count` is recorded as the key's generation. Sealed rows are
+    /// stamped with it but never re-encrypted, so rotation keeps older keys in
+    /// the keyring rather than deleting them.
+    async fn generate_new_key(&mut self, rotation_count: u32) -> Result<(), VaultError> {
         tracing::info!("🔑 Generating new encryption key with hardware fingerprinting");
 
         let hardware_fingerprint = self.generate_hardware_fingerprint()?;
@@ -217,7 +390,7 @@ impl SecureVault {
             key_id: uuid::Uuid::new_v4().to_string(),
             created_at: Utc::now(),
             expires_at: Utc::now() + Duration::days(30), // 30-day rotation cycle
-            rotation_count: 0,
+            rotation_count,
             hardware_fingerprint: hardware_fingerprint.clone(),
             derivation_salt: salt.to_vec(),
             iterations: ITERATIONS,
@@ -227,7 +400,7 @@ impl SecureVault {
         self.store_key_in_keychain(&encryption_key, &metadata).await?;
 
         // Cache in memory
-        self.master_key = Some(encryption_key);
+        self.master_key.set(encryption_key);
         self.current_metadata = Some(metadata);
 
         tracing::info!("✅ New encryption key generated and stored securely");
@@ -297,34 +470,32 @@ impl SecureVault {
 
         let metadata: KeyMetadata = serde_json::from_str(&metadata_json)?;
 
-        // Check if key needs rotation
-        if metadata.needs_rotation() {
-            tracing::warn!("⚠️  Encryption key needs rotation");
-            self.rotate_key().await?;
-            return Ok(self.current_metadata.clone());
-        }
-
         // Load the actual key
-        let key_entry = Entry::new(service, key_id)
-            .map_err(|e| VaultError::KeychainFailed(e.to_string()))?;
-
-        let key_b64 = key_entry.get_password()
-            .map_err(|e| VaultError::KeychainFailed(e.to_string()))?;
-
-        let key_bytes = base64::decode(&key_b64)
-            .map_err(|e| VaultError::KeychainFailed(e.to_string()))?;
+        let mut key = read_key_from_keychain(key_id)?;
+        self.master_key.set(key);
+        key.zeroize();
+        self.current_metadata = Some(metadata.clone());
 
-        if key_bytes.len() != 32 {
-            return Err(VaultError::KeychainFailed("Invalid key length".to_string()));
+        // Keys from earlier generations still open data sealed with them
+        for retired_id in read_retired_key_ids(&app_data_dir).await? {
+            match read_key_from_keychain(&retired_id) {
+                Ok(mut retired) => {
+                    self.master_key.add_retired(retired);
+                    retired.zeroize();
+                }
+                Err(e) => tracing::warn!("⚠️  Retired key {} unavailable: {}", retired_id, e),
+            }
         }
 
-        let mut key = [0u8; 32];
-        key.copy_from_slice(&key_bytes);
+        tracing::debug!("🔑 Existing key loaded from keychain: {}", key_id);
 
-        self.master_key = Some(key);
-        self.current_metadata = Some(metadata.clone());
+        // Rotate only once the current key is loaded so it can be retired
+        if metadata.needs_rotation() {
+            tracing::warn!("⚠️  Encryption key needs rotation");
+            self.rotate_key().await?;
+            return Ok(self.current_metadata.clone());
+        }
 
-        tracing::debug!("🔑 Existing key loaded from keychain: {}", key_id);
         Ok(Some(metadata))
     }
 
@@ -334,105 +505,59 @@ impl SecureVault {
 
         let old_metadata = self.current_metadata.clone();
 
+        let app_data_dir = self.app_handle.path().app_data_dir()
+            .map_err(|e| VaultError::KeyRotationFailed(e.to_string()))?;
+        tokio::fs::create_dir_all(&app_data_dir).await?;
+
+        // Record the old key as retired before replacing it, so data sealed
+        // with it stays readable even if the rotation is interrupted
+        if let Some(old_meta) = &old_metadata {
+            let mut retired_ids = read_retired_key_ids(&app_data_dir).await?;
+            retired_ids.insert(0, old_meta.key_id.clone());
+            tokio::fs::write(app_data_dir.join(RETIRED_KEY_IDS_FILE), retired_ids.join("\n")).await?;
+        }
+
         // Generate new key
-        self.generate_new_key().await?;
+        self.master_key.retire_current();
+        let rotation_count = old_metadata.as_ref().map_or(0, |m| m.rotation_count + 1);
+        self.generate_new_key(rotation_count).await?;
 
         // Update current key ID file
         if let Some(new_metadata) = &self.current_metadata {
-            let app_data_dir = self.app_handle.path().app_data_dir()
-                .map_err(|e| VaultError::KeyRotationFailed(e.to_string()))?;
-
-            tokio::fs::create_dir_all(&app_data_dir).await?;
             let key_id_path = app_data_dir.join("current_key_id");
             tokio::fs::write(&key_id_path, &new_metadata.key_id).await?;
         }
 
-        // Clean up old key after successful rotation
-        if let Some(old_meta) = old_metadata {
-            self.cleanup_old_key(&old_meta.key_id).await?;
-        }
-
         self.audit_log("key_rotated", "Encryption key rotated successfully").await?;
 
         tracing::info!("✅ Key rotation completed successfully");
         Ok(())
     }
 
-    /// Clean up old key from keychain
-    async fn cleanup_old_key(&self, old_key_id: &str) -> Result<(), VaultError> {
-        use keyring::Entry;
-
-        let service = "Atlas-Financial-Desktop";
-
-        // Remove metadata
-        if let Ok(metadata_entry) = Entry::new(&format!("{}-metadata", service), old_key_id) {
-            let _ = metadata_entry.delete_password();
-        }
-
-        // Remove key
-        if let Ok(key_entry) = Entry::new(service, old_key_id) {
-            let _ = key_entry.delete_password();
-        }
-
-        tracing::debug!("🗑️  Cleaned up old key: {}", old_key_id);
-        Ok(())
-    }
-
     /// Encrypt data using current encryption key
     pub fn encrypt(&self, data: &str) -> Result<Vec<u8>, VaultError> {
-        let key = self.master_key.ok_or_else(|| {
-            VaultError::EncryptionFailed("Vault not initialized".to_string())
-        })?;
-
-        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
-        let nonce = Aes256Gcm::generate_nonce(&mut rand::thread_rng());
-
-        let ciphertext = cipher.encrypt(&nonce, data.as_bytes())
-            .map_err(|e| VaultError::EncryptionFailed(e.to_string()))?;
-
-        // Prepend nonce to ciphertext
-        let mut result = nonce.to_vec();
-        result.extend_from_slice(&ciphertext);
-
-        Ok(result)
+        self.master_key.encrypt(data)
     }
 
     /// Decrypt data using current encryption key (with backward compatibility)
     pub fn decrypt(&self, data: &[u8]) -> Result<String, VaultError> {
-        if data.len() < 12 {
-            return Err(VaultError::DecryptionFailed("Invalid encrypted data".to_string()));
-        }
-
-        // Try current key first
-        if let Some(key) = self.master_key {
-            if let Ok(result) = self.decrypt_with_key(data, &key) {
-                return Ok(result);
-            }
-        }
-
-        // Try backward compatibility with hardcoded key (48-hour grace period)
-        let legacy_key = b"atlas_financial_desktop_key_32b!";
-        if let Ok(result) = self.decrypt_with_key(data, legacy_key) {
-            tracing::warn!("⚠️  Used legacy key for decryption - consider key migration");
-            return Ok(result);
-        }
-
-        Err(VaultError::DecryptionFailed("Unable to decrypt with any available key".to_string()))
+        self.master_key.decrypt(data)
     }
 
-    /// Decrypt with specific key
-    fn decrypt_with_key(&self, data: &[u8], key: &[u8; 32]) -> Result<String, VaultError> {
-        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
-
-        // Extract nonce and ciphertext
-        let (nonce, ciphertext) = data.split_at(12);
-        let nonce = Nonce::from_slice(nonce);
+    /// Wipe the in-memory key; nothing can be encrypted or decrypted until `unlock`
+    pub fn lock(&mut self) {
+        self.master_key.lock();
+        tracing::info!("🔒 SecureVault locked, in-memory key wiped");
+    }
 
-        let plaintext = cipher.decrypt(nonce, ciphertext)
-            .map_err(|e| VaultError::DecryptionFailed(e.to_string()))?;
+    pub fn is_locked(&self) -> bool {
+        self.master_key.is_locked()
+    }
 
-        String::from_utf8(plaintext)
-            .map_err(|e| VaultError::DecryptionFailed(e.to_string()))
+    /// Reload the key from the keychain after the user has re-authenticated
+    pub async fn unlock(&mut self) -> Result<(), VaultError> {
+        self.master_key.unlock();
+        self.initialize().await
     }
 
     /// Get current key metadata for monitoring
@@ -515,7 +640,10 @@ pub async fn get_vault(app_handle: tauri::AppHandle) -> Result<&'static mut Secu
         });
 
         if let Some(vault) = &mut VAULT_INSTANCE {
-            if vault.master_key.is_none() {
+            if vault.master_key.is_locked() {
+                return Err(VaultError::Locked);
+            }
+            if !vault.master_key.is_loaded() {
                 vault.initialize().await?;
             }
             Ok(vault)
@@ -525,6 +653,30 @@ pub async fn get_vault(app_handle: tauri::AppHandle) -> Result<&'static mut Secu
     }
 }
 
+/// Lock the global vault, if it has been created
+pub fn lock_vault() {
+    unsafe {
+        if let Some(vault) = &mut VAULT_INSTANCE {
+            vault.lock();
+        }
+    }
+}
+
+/// Unlock the global vault after a successful sign-in
+pub async fn unlock_vault(app_handle: tauri::AppHandle) -> Result<(), VaultError> {
+    unsafe {
+        VAULT_INIT.call_once(|| {
+            VAULT_INSTANCE = Some(SecureVault::new(app_handle));
+        });
+
+        match &mut VAULT_INSTANCE {
+            Some(vault) if vault.is_locked() => vault.unlock().await,
+            Some(_) => Ok(()),
+            None => Err(VaultError::KeyDerivationFailed("Failed to initialize vault".to_string())),
+        }
+    }
+}
+
 /// Convenience functions for encryption/decryption
 pub async fn encrypt_data(app_handle: tauri::AppHandle, data: &str) -> Result<Vec<u8>, VaultError> {
     let start = std::time::Instant::now();
@@ -563,3 +715,69 @@ pub async fn rotate_encryption_key(app_handle: tauri::AppHandle) -> Result<(), V
     let vault = get_vault(app_handle).await?;
     vault.rotate_key().await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unlocked_key() -> MasterKey {
+        let mut key = MasterKey::default();
+        let mut bytes = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        key.set(bytes);
+        key
+    }
+
+    #[test]
+    fn test_locked_key_refuses_encryption_and_decryption() {
+        let mut key = unlocked_key();
+        let ciphertext = key.encrypt("account 4417-1234").unwrap();
+        assert_eq!(key.decrypt(&ciphertext).unwrap(), "account 4417-1234");
+
+        key.lock();
+        assert!(key.is_locked());
+        assert!(!key.is_loaded());
+        assert!(matches!(key.decrypt(&ciphertext), Err(VaultError::Locked)));
+        assert!(matches!(key.encrypt("anything"), Err(VaultError::Locked)));
+
+        // Unlocking without re-loading the key still cannot open old data
+        key.unlock();
+        assert!(matches!(key.decrypt(&ciphertext), Err(VaultError::DecryptionFailed(_))));
+    }
+
+    #[test]
+    fn test_data_sealed_before_rotation_still_opens() {
+        let mut key = unlocked_key();
+        let first = key.encrypt("note from generation 0").unwrap();
+
+        for _ in 0..2 {
+            let mut next = [0u8; 32];
+            rand::thread_rng().fill_bytes(&mut next);
+            key.retire_current();
+            key.set(next);
+        }
+        let latest = key.encrypt("note from generation 2").unwrap();
+
+        assert_eq!(key.decrypt(&first).unwrap(), "note from generation 0");
+        assert_eq!(key.decrypt(&latest).unwrap(), "note from generation 2");
+
+        // Locking wipes retired keys along with the current one
+        key.lock();
+        key.unlock();
+        assert!(matches!(key.decrypt(&first), Err(VaultError::DecryptionFailed(_))));
+    }
+
+    #[test]
+    fn test_locked_key_refuses_legacy_fallback() {
+        let legacy = b"atlas_financial_desktop_key_32b!";
+        let mut key = MasterKey::default();
+        key.set(*legacy);
+        let ciphertext = key.encrypt("legacy session").unwrap();
+
+        let mut fresh = unlocked_key();
+        assert_eq!(fresh.decrypt(&ciphertext).unwrap(), "legacy session");
+
+        fresh.lock();
+        assert!(matches!(fresh.decrypt(&ciphertext), Err(VaultError::Locked)));
+    }
+}
@@ -0,0 +1,413 @@
+// Spending Time Series for Atlas Desktop
+// Buckets outflows by day, week or month so charts can be drawn from one query
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+/// Largest number of buckets returned for a single range
+pub const MAX_SPENDING_BUCKETS: usize = 3660;
+
+/// Label used for uncategorized outflows in per-category breakdowns
+pub const UNCATEGORIZED: &str = "Uncategorized";
+
+/// Resolve an IANA timezone name such as `America/New_York`
+pub fn parse_timezone(name: &str) -> Result<Tz, String> {
+    name.trim()
+        .parse::<Tz>()
+        .map_err(|_| format!("Unknown timezone: {}", name))
+}
+
+/// Calendar date of `instant` for a user in `timezone`
+pub fn local_date(instant: DateTime<Utc>, timezone: Tz) -> NaiveDate {
+    instant.with_timezone(&timezone).date_naive()
+}
+
+/// First instant of `date` on the user's local calendar
+///
+/// Where a DST change skips local midnight, the day starts at the first
+/// local time that exists.
+pub fn local_day_start(date: NaiveDate, timezone: Tz) -> DateTime<Utc> {
+    let midnight = date.and_hms_opt(0, 0, 0).expect("valid midnight");
+    (0..=96)
+        .find_map(|quarter_hour| {
+            timezone
+                .from_local_datetime(&(midnight + Duration::minutes(15 * quarter_hour)))
+                .earliest()
+        })
+        .expect("a local day has at least one valid time")
+        .with_timezone(&Utc)
+}
+
+/// Width of each spending bucket
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SpendingGranularity {
+    Day,
+    /// ISO weeks starting on Monday, matching PostgreSQL `date_trunc('week', ..)`
+    Week,
+    Month,
+}
+
+impl SpendingGranularity {
+    /// Unit passed to PostgreSQL `date_trunc`
+    pub fn sql_unit(self) -> &'static str {
+        match self {
+            SpendingGranularity::Day => "day",
+            SpendingGranularity::Week => "week",
+            SpendingGranularity::Month => "month",
+        }
+    }
+
+    /// Start of the bucket containing `date`
+    pub fn bucket_start(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            SpendingGranularity::Day => date,
+            SpendingGranularity::Week => date - Duration::days(date.weekday().num_days_from_monday() as i64),
+            SpendingGranularity::Month => date.with_day(1).expect("first of month"),
+        }
+    }
+
+    /// Start of the bucket following the one starting at `start`
+    pub fn next_bucket(self, start: NaiveDate) -> NaiveDate {
+        match self {
+            SpendingGranularity::Day => start + Duration::days(1),
+            SpendingGranularity::Week => start + Duration::days(7),
+            SpendingGranularity::Month => {
+                let (year, month) = if start.month() == 12 {
+                    (start.year() + 1, 1)
+                } else {
+                    (start.year(), start.month() + 1)
+                };
+                NaiveDate::from_ymd_opt(year, month, 1).expect("valid month start")
+            }
+        }
+    }
+}
+
+impl FromStr for SpendingGranularity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "day" | "daily" => Ok(SpendingGranularity::Day),
+            "week" | "weekly" => Ok(SpendingGranularity::Week),
+            "month" | "monthly" => Ok(SpendingGranularity::Month),
+            other => Err(format!("Unsupported granularity: {}", other)),
+        }
+    }
+}
+
+/// Calendar period a spending analysis covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SpendingPeriod {
+    /// ISO week starting on Monday
+    Week,
+    Month,
+    Quarter,
+    Year,
+}
+
+impl SpendingPeriod {
+    /// Local calendar days of the period containing `instant`
+    pub fn range_containing(self, instant: DateTime<Utc>, timezone: Tz) -> SpendingRange {
+        let today = local_date(instant, timezone);
+        let start = match self {
+            SpendingPeriod::Week => SpendingGranularity::Week.bucket_start(today),
+            SpendingPeriod::Month => SpendingGranularity::Month.bucket_start(today),
+            SpendingPeriod::Quarter => {
+                NaiveDate::from_ymd_opt(today.year(), (today.month() - 1) / 3 * 3 + 1, 1).expect("valid quarter start")
+            }
+            SpendingPeriod::Year => NaiveDate::from_ymd_opt(today.year(), 1, 1).expect("valid year start"),
+        };
+        let next_start = match self {
+            SpendingPeriod::Week => SpendingGranularity::Week.next_bucket(start),
+            SpendingPeriod::Month => SpendingGranularity::Month.next_bucket(start),
+            SpendingPeriod::Quarter => (0..3).fold(start, |month, _| SpendingGranularity::Month.next_bucket(month)),
+            SpendingPeriod::Year => NaiveDate::from_ymd_opt(start.year() + 1, 1, 1).expect("valid year start"),
+        };
+        SpendingRange {
+            start,
+            end: next_start - Duration::days(1),
+        }
+    }
+}
+
+impl FromStr for SpendingPeriod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "week" | "weekly" => Ok(SpendingPeriod::Week),
+            "month" | "monthly" => Ok(SpendingPeriod::Month),
+            "quarter" | "quarterly" => Ok(SpendingPeriod::Quarter),
+            "year" | "yearly" | "annual" => Ok(SpendingPeriod::Year),
+            other => Err(format!("Unsupported period: {}", other)),
+        }
+    }
+}
+
+/// Inclusive range of calendar days, on the user's local calendar, to chart
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpendingRange {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+impl SpendingRange {
+    pub fn new(start: NaiveDate, end: NaiveDate) -> Result<Self, String> {
+        if end < start {
+            return Err(format!("Range end {} is before start {}", end, start));
+        }
+        Ok(Self { start, end })
+    }
+
+    /// First instant of the range for a user in `timezone`
+    pub fn start_instant(&self, timezone: Tz) -> DateTime<Utc> {
+        local_day_start(self.start, timezone)
+    }
+
+    /// First instant after the range for a user in `timezone`
+    pub fn end_instant(&self, timezone: Tz) -> DateTime<Utc> {
+        local_day_start(self.end + Duration::days(1), timezone)
+    }
+
+    /// Start of every bucket overlapping the range, oldest first
+    pub fn bucket_starts(&self, granularity: SpendingGranularity) -> Result<Vec<NaiveDate>, String> {
+        let mut starts = Vec::new();
+        let mut current = granularity.bucket_start(self.start);
+        while current <= self.end {
+            if starts.len() == MAX_SPENDING_BUCKETS {
+                return Err(format!(
+                    "Range produces more than {} {} buckets",
+                    MAX_SPENDING_BUCKETS,
+                    granularity.sql_unit()
+                ));
+            }
+            starts.push(current);
+            current = granularity.next_bucket(current);
+        }
+        Ok(starts)
+    }
+}
+
+/// Spending for one category within a bucket
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CategorySpend {
+    pub category: String,
+    pub total: Decimal,
+}
+
+/// Total outflows within one bucket
+///
+/// The first and last buckets can extend past the requested range; only
+/// transactions inside the range are counted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpendingBucket {
+    pub start: NaiveDate,
+    /// First day after the bucket
+    pub end: NaiveDate,
+    pub total: Decimal,
+    /// Per-category totals, sorted by category; present when requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub categories: Option<Vec<CategorySpend>>,
+}
+
+/// Lay grouped `(bucket start, category, total)` rows over every bucket in
+/// the range, filling buckets without spending with zero
+///
+/// Rows normally come from a single `date_trunc` grouped query; rows whose
+/// bucket falls outside the range are ignored.
+pub fn spending_timeseries<'a>(
+    range: SpendingRange,
+    granularity: SpendingGranularity,
+    rows: impl IntoIterator<Item = (NaiveDate, Option<&'a str>, Decimal)>,
+    by_category: bool,
+) -> Result<Vec<SpendingBucket>, String> {
+    let mut buckets: BTreeMap<NaiveDate, (Decimal, BTreeMap<String, Decimal>)> = range
+        .bucket_starts(granularity)?
+        .into_iter()
+        .map(|start| (start, Default::default()))
+        .collect();
+
+    for (bucket, category, total) in rows {
+        let Some((bucket_total, categories)) = buckets.get_mut(&granularity.bucket_start(bucket)) else {
+            continue;
+        };
+        *bucket_total += total;
+        if by_category {
+            *categories
+                .entry(category.unwrap_or(UNCATEGORIZED).to_string())
+                .or_default() += total;
+        }
+    }
+
+    Ok(buckets
+        .into_iter()
+        .map(|(start, (total, categories))| SpendingBucket {
+            start,
+            end: granularity.next_bucket(start),
+            total,
+            categories: by_category.then(|| {
+                categories
+                    .into_iter()
+                    .map(|(category, total)| CategorySpend { category, total })
+                    .collect()
+            }),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn winter_range() -> SpendingRange {
+        SpendingRange::new(date(2024, 1, 15), date(2024, 3, 10)).unwrap()
+    }
+
+    #[test]
+    fn test_daily_buckets_cover_range_without_gaps() {
+        let rows = vec![
+            (date(2024, 1, 15), Some("Dining"), dec!(12.50)),
+            (date(2024, 2, 29), Some("Groceries"), dec!(80)),
+            (date(2024, 3, 10), None, dec!(5)),
+        ];
+
+        let buckets = spending_timeseries(winter_range(), SpendingGranularity::Day, rows, false).unwrap();
+
+        // 17 days of January, 29 of February (leap year), 10 of March
+        assert_eq!(buckets.len(), 56);
+        assert_eq!(buckets[0].start, date(2024, 1, 15));
+        assert_eq!(buckets[0].end, date(2024, 1, 16));
+        assert_eq!(buckets[0].total, dec!(12.50));
+        assert_eq!(buckets[1].total, Decimal::ZERO);
+        assert_eq!(buckets[45].start, date(2024, 2, 29));
+        assert_eq!(buckets[45].total, dec!(80));
+        assert_eq!(buckets[55].start, date(2024, 3, 10));
+        assert_eq!(buckets[55].total, dec!(5));
+        assert!(buckets.iter().all(|b| b.categories.is_none()));
+        assert!(buckets.windows(2).all(|w| w[0].end == w[1].start));
+    }
+
+    #[test]
+    fn test_monthly_buckets_with_category_breakdown() {
+        let rows = vec![
+            (date(2024, 1, 1), Some("Dining"), dec!(40)),
+            (date(2024, 1, 1), Some("Fuel"), dec!(60)),
+            (date(2024, 3, 1), None, dec!(15)),
+            (date(2024, 3, 1), Some("Dining"), dec!(10)),
+            // Outside the range
+            (date(2024, 4, 1), Some("Dining"), dec!(999)),
+        ];
+
+        let buckets = spending_timeseries(winter_range(), SpendingGranularity::Month, rows, true).unwrap();
+
+        assert_eq!(buckets.len(), 3);
+        assert_eq!(
+            buckets.iter().map(|b| (b.start, b.end)).collect::<Vec<_>>(),
+            vec![
+                (date(2024, 1, 1), date(2024, 2, 1)),
+                (date(2024, 2, 1), date(2024, 3, 1)),
+                (date(2024, 3, 1), date(2024, 4, 1)),
+            ]
+        );
+        assert_eq!(buckets[0].total, dec!(100));
+        assert_eq!(
+            buckets[0].categories.as_deref().unwrap(),
+            &[
+                CategorySpend { category: "Dining".to_string(), total: dec!(40) },
+                CategorySpend { category: "Fuel".to_string(), total: dec!(60) },
+            ]
+        );
+
+        // Empty month is reported as zero with no categories
+        assert_eq!(buckets[1].total, Decimal::ZERO);
+        assert_eq!(buckets[1].categories.as_deref().unwrap(), &[]);
+
+        assert_eq!(buckets[2].total, dec!(25));
+        let march = buckets[2].categories.as_deref().unwrap();
+        assert_eq!(march[1].category, UNCATEGORIZED);
+    }
+
+    #[test]
+    fn test_week_and_month_boundaries() {
+        // 2024-01-17 is a Wednesday
+        assert_eq!(SpendingGranularity::Week.bucket_start(date(2024, 1, 17)), date(2024, 1, 15));
+        assert_eq!(SpendingGranularity::Week.bucket_start(date(2024, 1, 15)), date(2024, 1, 15));
+        assert_eq!(SpendingGranularity::Month.next_bucket(date(2024, 12, 1)), date(2025, 1, 1));
+
+        let range = SpendingRange::new(date(2024, 1, 17), date(2024, 2, 5)).unwrap();
+        let starts = range.bucket_starts(SpendingGranularity::Week).unwrap();
+        assert_eq!(starts, vec![date(2024, 1, 15), date(2024, 1, 22), date(2024, 1, 29), date(2024, 2, 5)]);
+
+        // The query window covers whole UTC days including the last one
+        assert_eq!(range.start_instant(Tz::UTC).to_rfc3339(), "2024-01-17T00:00:00+00:00");
+        assert_eq!(range.end_instant(Tz::UTC).to_rfc3339(), "2024-02-06T00:00:00+00:00");
+    }
+
+    #[test]
+    fn test_late_night_transaction_stays_in_local_month() {
+        let los_angeles = parse_timezone("America/Los_Angeles").unwrap();
+        // 9:30pm on January 31st in Los Angeles is already February in UTC
+        let late_night = Utc.with_ymd_and_hms(2024, 2, 1, 5, 30, 0).unwrap();
+
+        assert_eq!(local_date(late_night, los_angeles), date(2024, 1, 31));
+        let january = SpendingPeriod::Month.range_containing(late_night, los_angeles);
+        assert_eq!(january, SpendingRange::new(date(2024, 1, 1), date(2024, 1, 31)).unwrap());
+        assert!(january.start_instant(los_angeles) <= late_night && late_night < january.end_instant(los_angeles));
+        assert_eq!(january.start_instant(los_angeles).to_rfc3339(), "2024-01-01T08:00:00+00:00");
+        assert_eq!(january.end_instant(los_angeles).to_rfc3339(), "2024-02-01T08:00:00+00:00");
+
+        // The same instant falls in February for a UTC user
+        let february = SpendingPeriod::Month.range_containing(late_night, Tz::UTC);
+        assert_eq!(february.start, date(2024, 2, 1));
+        assert!(!(january.start_instant(Tz::UTC) <= late_night && late_night < january.end_instant(Tz::UTC)));
+    }
+
+    #[test]
+    fn test_period_ranges_follow_local_calendar() {
+        let new_york = parse_timezone("America/New_York").unwrap();
+        // Friday 2024-03-29 at 11pm in New York
+        let instant = Utc.with_ymd_and_hms(2024, 3, 30, 3, 0, 0).unwrap();
+
+        let week = SpendingPeriod::Week.range_containing(instant, new_york);
+        assert_eq!((week.start, week.end), (date(2024, 3, 25), date(2024, 3, 31)));
+        let quarter = SpendingPeriod::Quarter.range_containing(instant, new_york);
+        assert_eq!((quarter.start, quarter.end), (date(2024, 1, 1), date(2024, 3, 31)));
+        let year = SpendingPeriod::Year.range_containing(instant, new_york);
+        assert_eq!((year.start, year.end), (date(2024, 1, 1), date(2024, 12, 31)));
+
+        // The week spans the switch to daylight time on March 10th
+        let spring = SpendingRange::new(date(2024, 3, 4), date(2024, 3, 10)).unwrap();
+        assert_eq!(spring.start_instant(new_york).to_rfc3339(), "2024-03-04T05:00:00+00:00");
+        assert_eq!(spring.end_instant(new_york).to_rfc3339(), "2024-03-11T04:00:00+00:00");
+
+        assert!(parse_timezone("Mars/Olympus_Mons").is_err());
+        assert_eq!("Quarterly".parse::<SpendingPeriod>().unwrap(), SpendingPeriod::Quarter);
+        assert!("fortnight".parse::<SpendingPeriod>().is_err());
+    }
+
+    #[test]
+    fn test_invalid_ranges_rejected() {
+        assert!(SpendingRange::new(date(2024, 3, 1), date(2024, 2, 1)).is_err());
+        assert!("hourly".parse::<SpendingGranularity>().is_err());
+        assert_eq!("Monthly".parse::<SpendingGranularity>().unwrap(), SpendingGranularity::Month);
+
+        let decades = SpendingRange::new(date(2000, 1, 1), date(2030, 1, 1)).unwrap();
+        assert!(decades.bucket_starts(SpendingGranularity::Day).is_err());
+        assert!(decades.bucket_starts(SpendingGranularity::Month).is_ok());
+    }
+}
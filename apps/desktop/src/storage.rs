@@ -1,14 +1,23 @@
 // Database Management Module for Atlas Desktop
 // Secure database operations with connection pooling
 
-use sqlx::{PgPool, Row};
+use sqlx::{Connection, PgConnection, PgPool, Postgres, Row};
+use sqlx::pool::PoolConnection;
 use sqlx::postgres::PgPoolOptions;
+use std::future::Future;
+use std::ops::{Deref, DerefMut};
+use std::pin::Pin;
+use tokio::sync::{Mutex, MutexGuard};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::Tz;
 use rust_decimal::Decimal;
 use crate::financial::{FinancialAmount, FinancialError};
+use crate::insights::InsightDismissal;
+use crate::spending::{SpendingGranularity, SpendingRange};
 use crate::security::secure_query::{SecureQuery, InputValidator, TransactionFilterBuilder, OrderDirection};
+use crate::security::field_encryption::{open_transaction_fields, seal_transaction_fields, FieldCipher, SealedFields, SensitiveFieldPolicy};
 
 // ============================================================================
 // Database Manager
@@ -113,24 +122,21 @@ impl DatabaseManager {
         })
     }
 
-    /// Execute a transaction with automatic rollback on error
-    pub async fn execute_transaction<T, F, Fut>(&self, operation: F) -> Result<T, FinancialError>
+    /// Run `operation` in a unit of work, committing if it succeeds and
+    /// rolling back if it fails
+    pub async fn execute_transaction<'a, T, F>(&'a self, operation: F) -> Result<T, FinancialError>
     where
-        F: FnOnce(sqlx::Transaction<'_, sqlx::Postgres>) -> Fut,
-        Fut: std::future::Future<Output = Result<T, FinancialError>>,
+        F: for<'u> FnOnce(&'u UnitOfWork<'a>) -> Pin<Box<dyn Future<Output = Result<T, FinancialError>> + Send + 'u>>,
     {
-        let mut tx = self.pool.begin()
-            .await
-            .map_err(|e| FinancialError::DatabaseError(format!("Failed to begin transaction: {}", e)))?;
+        let unit = UnitOfWork::begin(self).await?;
 
-        match operation(tx).await {
+        match operation(&unit).await {
             Ok(result) => {
-                // Note: This is a placeholder structure - actual implementation would need
-                // to handle the transaction commit properly
+                unit.commit().await?;
                 Ok(result)
             }
             Err(e) => {
-                // Transaction will be automatically rolled back when dropped
+                unit.rollback().await?;
                 Err(e)
             }
         }
@@ -157,6 +163,85 @@ pub struct DatabaseStats {
     pub idle_connections: u32,
 }
 
+// ============================================================================
+// Unit of Work
+// ============================================================================
+
+/// One database transaction shared by several repositories
+///
+/// Repositories created with `within` run their statements on this
+/// transaction instead of pooled connections, so a logical action that
+/// touches accounts and transactions commits or rolls back as a whole.
+/// Dropping a unit without committing rolls it back. Queries built with
+/// `SecureQuery` still read committed data from the pool.
+pub struct UnitOfWork<'a> {
+    db: &'a DatabaseManager,
+    tx: Mutex<sqlx::Transaction<'static, Postgres>>,
+}
+
+impl<'a> UnitOfWork<'a> {
+    /// Start a new database transaction
+    pub async fn begin(db: &'a DatabaseManager) -> Result<Self, FinancialError> {
+        let tx = db.pool.begin()
+            .await
+            .map_err(|e| FinancialError::DatabaseError(format!("Failed to begin transaction: {}", e)))?;
+        Ok(Self { db, tx: Mutex::new(tx) })
+    }
+
+    /// Make every change made through this unit permanent
+    pub async fn commit(self) -> Result<(), FinancialError> {
+        self.tx.into_inner()
+            .commit()
+            .await
+            .map_err(|e| FinancialError::DatabaseError(format!("Failed to commit transaction: {}", e)))
+    }
+
+    /// Discard every change made through this unit
+    pub async fn rollback(self) -> Result<(), FinancialError> {
+        self.tx.into_inner()
+            .rollback()
+            .await
+            .map_err(|e| FinancialError::DatabaseError(format!("Failed to roll back transaction: {}", e)))
+    }
+}
+
+/// Connection a repository statement runs on
+enum DbConnection<'a> {
+    Pooled(PoolConnection<Postgres>),
+    Unit(MutexGuard<'a, sqlx::Transaction<'static, Postgres>>),
+}
+
+impl Deref for DbConnection<'_> {
+    type Target = PgConnection;
+
+    fn deref(&self) -> &PgConnection {
+        match self {
+            DbConnection::Pooled(conn) => conn,
+            DbConnection::Unit(tx) => tx,
+        }
+    }
+}
+
+impl DerefMut for DbConnection<'_> {
+    fn deref_mut(&mut self) -> &mut PgConnection {
+        match self {
+            DbConnection::Pooled(conn) => conn,
+            DbConnection::Unit(tx) => tx,
+        }
+    }
+}
+
+/// The unit's transaction when there is one, otherwise a pooled connection
+async fn connection<'a>(db: &'a DatabaseManager, unit: Option<&'a UnitOfWork<'a>>) -> Result<DbConnection<'a>, FinancialError> {
+    match unit {
+        Some(unit) => Ok(DbConnection::Unit(unit.tx.lock().await)),
+        None => db.pool.acquire()
+            .await
+            .map(DbConnection::Pooled)
+            .map_err(|e| FinancialError::DatabaseError(format!("Failed to acquire connection: {}", e))),
+    }
+}
+
 // ============================================================================
 // Database Operations (Repository Pattern)
 // ============================================================================
@@ -164,14 +249,27 @@ pub struct DatabaseStats {
 /// Account repository for database operations
 pub struct AccountRepository<'a> {
     db: &'a DatabaseManager,
+    unit: Option<&'a UnitOfWork<'a>>,
 }
 
 impl<'a> AccountRepository<'a> {
     pub fn new(db: &'a DatabaseManager) -> Self {
-        Self { db }
+        Self { db, unit: None }
+    }
+
+    /// Repository whose statements run inside `unit`
+    pub fn within(unit: &'a UnitOfWork<'a>) -> Self {
+        Self { db: unit.db, unit: Some(unit) }
+    }
+
+    async fn connection(&self) -> Result<DbConnection<'a>, FinancialError> {
+        connection(self.db, self.unit).await
     }
 
     /// Update an existing account with input validation
+    ///
+    /// An edited balance is taken as correct today, so the opening balance is
+    /// re-derived from it and the current transactions.
     pub async fn update(&self, account_id: &str, account: &CreateAccountRequest) -> Result<Option<AccountRecord>, FinancialError> {
         // Validate account ID is valid UUID
         Uuid::parse_str(account_id)
@@ -182,6 +280,8 @@ impl<'a> AccountRepository<'a> {
 
         let now = Utc::now();
 
+        let mut conn = self.connection().await?;
+
         // Use parameterized query with validated inputs
         let row = sqlx::query_as!(
             AccountRecord,
@@ -190,17 +290,22 @@ impl<'a> AccountRepository<'a> {
                 name = $2,
                 account_type = $3,
                 balance = $4,
+                opening_balance = $4 - COALESCE((
+                    SELECT SUM(t.amount) FROM transactions t
+                    WHERE t.account_id = $1 AND COALESCE(t.is_active, true) = true
+                ), 0),
                 currency = $5,
                 updated_at = $6,
                 institution = $7,
                 account_number_masked = $8,
                 credit_limit = $9,
                 interest_rate = $10
-            WHERE id = $1 AND user_id = $11 AND is_active = true
+            WHERE id = $1 AND user_id = $11 AND is_active = true AND archived_at IS NULL
             RETURNING
                 id, user_id, name, account_type as "account_type: AccountType",
                 balance, currency, is_active, created_at, updated_at,
-                institution, account_number_masked, credit_limit, interest_rate
+                institution, account_number_masked, credit_limit, interest_rate,
+                archived_at, balance_zeroed_at
             "#,
             account_id,
             account.name,
@@ -214,7 +319,7 @@ impl<'a> AccountRepository<'a> {
             account.interest_rate,
             account.user_id
         )
-        .fetch_optional(&self.db.pool)
+        .fetch_optional(&mut *conn)
         .await
         .map_err(|e| FinancialError::DatabaseError(format!("Failed to update account: {}", e)))?;
 
@@ -222,7 +327,12 @@ impl<'a> AccountRepository<'a> {
     }
 
     /// Soft delete an account (mark as inactive rather than removing)
-    pub async fn soft_delete(&self, account_id: &str, user_id: &str) -> Result<bool, FinancialError> {
+    ///
+    /// An account that still has transactions is refused unless `cascade` is
+    /// set, in which case its transactions (and their attachment references)
+    /// are soft-deleted with it in one database transaction, so no active
+    /// transaction is ever left pointing at a deleted account.
+    pub async fn soft_delete(&self, account_id: &str, user_id: &str, cascade: bool) -> Result<bool, FinancialError> {
         // Validate UUIDs
         Uuid::parse_str(account_id)
             .map_err(|_| FinancialError::ValidationError("Invalid account ID format".to_string()))?;
@@ -231,19 +341,59 @@ impl<'a> AccountRepository<'a> {
 
         let now = Utc::now();
 
-        // Check if account has any transactions before allowing deletion
-        let transaction_count = sqlx::query!(
-            "SELECT COUNT(*) as count FROM transactions WHERE account_id = $1 AND is_active = true",
-            account_id
-        )
-        .fetch_one(&self.db.pool)
-        .await
-        .map_err(|e| FinancialError::DatabaseError(format!("Failed to check transaction count: {}", e)))?;
+        let mut conn = self.connection().await?;
+        let mut tx = conn.begin()
+            .await
+            .map_err(|e| FinancialError::DatabaseError(format!("Failed to begin transaction: {}", e)))?;
 
-        if transaction_count.count.unwrap_or(0) > 0 {
-            return Err(FinancialError::ValidationError(
-                "Cannot delete account with existing transactions".to_string()
-            ));
+        if cascade {
+            sqlx::query!(
+                r#"
+                DELETE FROM transaction_attachments
+                WHERE user_id = $2 AND transaction_id IN (
+                    SELECT id FROM transactions
+                    WHERE account_id = $1 AND user_id = $2 AND is_active = true
+                )
+                "#,
+                account_id,
+                user_id
+            )
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| FinancialError::DatabaseError(format!("Failed to remove transaction attachments: {}", e)))?;
+
+            sqlx::query!(
+                r#"
+                UPDATE transactions SET
+                    is_active = false,
+                    updated_at = $3,
+                    deleted_at = $3
+                WHERE account_id = $1 AND user_id = $2 AND is_active = true
+                "#,
+                account_id,
+                user_id,
+                now
+            )
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| FinancialError::DatabaseError(format!("Failed to delete account transactions: {}", e)))?;
+        } else {
+            let transaction_count = sqlx::query_scalar!(
+                "SELECT COUNT(*) FROM transactions WHERE account_id = $1 AND user_id = $2 AND is_active = true",
+                account_id,
+                user_id
+            )
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| FinancialError::DatabaseError(format!("Failed to check transaction count: {}", e)))?
+            .unwrap_or(0);
+
+            if transaction_count > 0 {
+                return Err(FinancialError::ValidationError(format!(
+                    "Cannot delete account with {} existing transaction(s); archive it instead or delete with cascade",
+                    transaction_count
+                )));
+            }
         }
 
         // Use parameterized query to mark as inactive
@@ -258,29 +408,114 @@ impl<'a> AccountRepository<'a> {
             user_id,
             now
         )
-        .execute(&self.db.pool)
+        .execute(&mut *tx)
         .await
         .map_err(|e| FinancialError::DatabaseError(format!("Failed to delete account: {}", e)))?;
 
-        Ok(result.rows_affected() > 0)
+        // Nothing is committed for an unknown account, not even a cascade
+        let deleted = result.rows_affected() > 0;
+        if deleted {
+            tx.commit()
+                .await
+                .map_err(|e| FinancialError::DatabaseError(format!("Failed to commit transaction: {}", e)))?;
+        }
+
+        Ok(deleted)
+    }
+
+    /// Archive (close) an account while keeping its transaction history
+    ///
+    /// The account stops accepting transactions but remains in net worth and
+    /// reports until `balance_zeroed_at`, which defaults to the archive time.
+    pub async fn archive(
+        &self,
+        account_id: &str,
+        user_id: &str,
+        balance_zeroed_at: Option<DateTime<Utc>>,
+    ) -> Result<Option<AccountRecord>, FinancialError> {
+        Uuid::parse_str(account_id)
+            .map_err(|_| FinancialError::ValidationError("Invalid account ID format".to_string()))?;
+
+        let now = Utc::now();
+
+        let mut conn = self.connection().await?;
+        let row = sqlx::query_as!(
+            AccountRecord,
+            r#"
+            UPDATE accounts SET
+                archived_at = $3,
+                balance_zeroed_at = $4,
+                updated_at = $3
+            WHERE id = $1 AND user_id = $2 AND is_active = true AND archived_at IS NULL
+            RETURNING
+                id, user_id, name, account_type as "account_type: AccountType",
+                balance, currency, is_active, created_at, updated_at,
+                institution, account_number_masked, credit_limit, interest_rate,
+                archived_at, balance_zeroed_at
+            "#,
+            account_id,
+            user_id,
+            now,
+            balance_zeroed_at.unwrap_or(now)
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| FinancialError::DatabaseError(format!("Failed to archive account: {}", e)))?;
+
+        Ok(row)
+    }
+
+    /// Reopen an archived account
+    pub async fn reactivate(&self, account_id: &str, user_id: &str) -> Result<Option<AccountRecord>, FinancialError> {
+        Uuid::parse_str(account_id)
+            .map_err(|_| FinancialError::ValidationError("Invalid account ID format".to_string()))?;
+
+        let now = Utc::now();
+
+        let mut conn = self.connection().await?;
+        let row = sqlx::query_as!(
+            AccountRecord,
+            r#"
+            UPDATE accounts SET
+                archived_at = NULL,
+                balance_zeroed_at = NULL,
+                updated_at = $3
+            WHERE id = $1 AND user_id = $2 AND is_active = true AND archived_at IS NOT NULL
+            RETURNING
+                id, user_id, name, account_type as "account_type: AccountType",
+                balance, currency, is_active, created_at, updated_at,
+                institution, account_number_masked, credit_limit, interest_rate,
+                archived_at, balance_zeroed_at
+            "#,
+            account_id,
+            user_id,
+            now
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| FinancialError::DatabaseError(format!("Failed to reactivate account: {}", e)))?;
+
+        Ok(row)
     }
 
-    /// Find all accounts for a user
+    /// Find all accounts for a user, including archived ones
     pub async fn find_by_user_id(&self, user_id: &str) -> Result<Vec<AccountRecord>, FinancialError> {
+        let mut conn = self.connection().await?;
         let rows = sqlx::query_as!(
             AccountRecord,
             r#"
             SELECT
                 id, user_id, name, account_type as "account_type: AccountType",
                 balance, currency, is_active, created_at, updated_at,
-                institution, account_number_masked, credit_limit, interest_rate
+                institution, account_number_masked, credit_limit, interest_rate,
+                archived_at, balance_zeroed_at
             FROM accounts
             WHERE user_id = $1 AND is_active = true
             ORDER BY name ASC
             "#,
             user_id
         )
-        .fetch_all(&self.db.pool)
+        .fetch_all(&mut *conn)
         .await
         .map_err(|e| FinancialError::DatabaseError(format!("Failed to fetch accounts: {}", e)))?;
 
@@ -289,19 +524,21 @@ impl<'a> AccountRepository<'a> {
 
     /// Find account by ID
     pub async fn find_by_id(&self, account_id: &str) -> Result<Option<AccountRecord>, FinancialError> {
+        let mut conn = self.connection().await?;
         let row = sqlx::query_as!(
             AccountRecord,
             r#"
             SELECT
                 id, user_id, name, account_type as "account_type: AccountType",
                 balance, currency, is_active, created_at, updated_at,
-                institution, account_number_masked, credit_limit, interest_rate
+                institution, account_number_masked, credit_limit, interest_rate,
+                archived_at, balance_zeroed_at
             FROM accounts
             WHERE id = $1 AND is_active = true
             "#,
             account_id
         )
-        .fetch_optional(&self.db.pool)
+        .fetch_optional(&mut *conn)
         .await
         .map_err(|e| FinancialError::DatabaseError(format!("Failed to fetch account: {}", e)))?;
 
@@ -316,20 +553,23 @@ impl<'a> AccountRepository<'a> {
         let id = Uuid::new_v4().to_string();
         let now = Utc::now();
 
+        let mut conn = self.connection().await?;
+
         // Use parameterized query with all validated inputs
         let row = sqlx::query_as!(
             AccountRecord,
             r#"
             INSERT INTO accounts (
-                id, user_id, name, account_type, balance, currency,
+                id, user_id, name, account_type, balance, opening_balance, currency,
                 is_active, created_at, updated_at, institution,
                 account_number_masked, credit_limit, interest_rate
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            VALUES ($1, $2, $3, $4, $5, $5, $6, $7, $8, $9, $10, $11, $12, $13)
             RETURNING
                 id, user_id, name, account_type as "account_type: AccountType",
                 balance, currency, is_active, created_at, updated_at,
-                institution, account_number_masked, credit_limit, interest_rate
+                institution, account_number_masked, credit_limit, interest_rate,
+                archived_at, balance_zeroed_at
             "#,
             id,
             account.user_id,
@@ -345,22 +585,119 @@ impl<'a> AccountRepository<'a> {
             account.credit_limit,
             account.interest_rate
         )
-        .fetch_one(&self.db.pool)
+        .fetch_one(&mut *conn)
         .await
         .map_err(|e| FinancialError::DatabaseError(format!("Failed to create account: {}", e)))?;
 
         Ok(row)
     }
+
+    /// Rebuild each account's balance from its opening balance and active transactions
+    ///
+    /// Only accounts whose stored balance disagreed with the ledger are
+    /// changed and returned.
+    pub async fn recompute_balances(&self, user_id: &str) -> Result<Vec<BalanceCorrection>, FinancialError> {
+        Uuid::parse_str(user_id)
+            .map_err(|_| FinancialError::ValidationError("Invalid user ID format".to_string()))?;
+
+        let now = Utc::now();
+
+        let mut conn = self.connection().await?;
+        let rows = sqlx::query_as!(
+            BalanceCorrection,
+            r#"
+            WITH ledger AS (
+                SELECT a.id, a.name, a.balance AS previous_balance,
+                       a.opening_balance + COALESCE(SUM(t.amount), 0) AS recomputed_balance
+                FROM accounts a
+                LEFT JOIN transactions t
+                    ON t.account_id = a.id AND COALESCE(t.is_active, true) = true
+                WHERE a.user_id = $1 AND a.is_active = true
+                GROUP BY a.id, a.name, a.balance, a.opening_balance
+            )
+            UPDATE accounts SET
+                balance = ledger.recomputed_balance,
+                updated_at = $2
+            FROM ledger
+            WHERE accounts.id = ledger.id AND ledger.previous_balance <> ledger.recomputed_balance
+            RETURNING
+                ledger.id as "account_id!", ledger.name as "name!",
+                ledger.previous_balance as "previous_balance!",
+                ledger.recomputed_balance as "recomputed_balance!"
+            "#,
+            user_id,
+            now
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| FinancialError::DatabaseError(format!("Failed to recompute account balances: {}", e)))?;
+
+        Ok(rows)
+    }
 }
 
 /// Transaction repository for database operations
 pub struct TransactionRepository<'a> {
     db: &'a DatabaseManager,
+    unit: Option<&'a UnitOfWork<'a>>,
+    encryption: Option<(&'a dyn FieldCipher, SensitiveFieldPolicy)>,
 }
 
 impl<'a> TransactionRepository<'a> {
     pub fn new(db: &'a DatabaseManager) -> Self {
-        Self { db }
+        Self { db, unit: None, encryption: None }
+    }
+
+    /// Repository whose statements run inside `unit`
+    pub fn within(unit: &'a UnitOfWork<'a>) -> Self {
+        Self { db: unit.db, unit: Some(unit), encryption: None }
+    }
+
+    async fn connection(&self) -> Result<DbConnection<'a>, FinancialError> {
+        connection(self.db, self.unit).await
+    }
+
+    /// Encrypt notes (and any fields `policy` selects) on write and decrypt them on read
+    pub fn with_field_encryption(mut self, cipher: &'a dyn FieldCipher, policy: SensitiveFieldPolicy) -> Self {
+        self.encryption = Some((cipher, policy));
+        self
+    }
+
+    /// Column values to persist for the sensitive fields of `transaction`
+    fn sealed_fields(&self, transaction: &CreateTransactionRequest) -> Result<(SealedFields, Option<i32>), FinancialError> {
+        match self.encryption {
+            Some((cipher, policy)) => {
+                let sealed = seal_transaction_fields(cipher, policy, transaction)?;
+                let generation = Some(sealed.encryption_generation);
+                Ok((sealed, generation))
+            }
+            None => Ok((
+                SealedFields {
+                    merchant: transaction.merchant.clone(),
+                    location: transaction.location.clone(),
+                    notes: transaction.notes.clone(),
+                    encryption_generation: 0,
+                },
+                None,
+            )),
+        }
+    }
+
+    /// Decrypt sensitive fields of records read back from the database
+    fn open_records(&self, mut records: Vec<TransactionRecord>) -> Result<Vec<TransactionRecord>, FinancialError> {
+        if let Some((cipher, _)) = self.encryption {
+            for record in &mut records {
+                open_transaction_fields(cipher, record)?;
+            }
+        }
+        Ok(records)
+    }
+
+    fn open_record(&self, mut record: TransactionRecord) -> Result<TransactionRecord, FinancialError> {
+        if let Some((cipher, _)) = self.encryption {
+            open_transaction_fields(cipher, &mut record)?;
+        }
+        Ok(record)
     }
 
     /// Update an existing transaction with input validation
@@ -388,6 +725,9 @@ impl<'a> TransactionRepository<'a> {
         InputValidator::validate_transaction_input(&temp_input)?;
 
         let now = Utc::now();
+        let (sealed, encryption_generation) = self.sealed_fields(transaction)?;
+
+        let mut conn = self.connection().await?;
 
         // Use parameterized query with validated inputs
         let row = sqlx::query_as!(
@@ -406,13 +746,15 @@ impl<'a> TransactionRepository<'a> {
                 location = $11,
                 is_recurring = $12,
                 tags = $13,
-                notes = $14
+                notes = $14,
+                encryption_generation = $16
             WHERE id = $1 AND user_id = $15
             RETURNING
                 id, user_id, account_id, amount, description, category,
                 subcategory, transaction_date, created_at, updated_at,
                 transaction_type as "transaction_type: TransactionType",
-                merchant, location, is_recurring, tags, notes, ml_confidence
+                merchant, location, is_recurring, tags, notes, ml_confidence,
+                encryption_generation
             "#,
             transaction_id,
             transaction.account_id,
@@ -423,21 +765,25 @@ impl<'a> TransactionRepository<'a> {
             transaction.transaction_date.unwrap_or(now),
             now,
             transaction.transaction_type as TransactionType,
-            transaction.merchant,
-            transaction.location,
+            sealed.merchant,
+            sealed.location,
             transaction.is_recurring.unwrap_or(false),
             &transaction.tags.as_ref().unwrap_or(&vec![]),
-            transaction.notes,
-            transaction.user_id
+            sealed.notes,
+            transaction.user_id,
+            encryption_generation
         )
-        .fetch_optional(&self.db.pool)
+        .fetch_optional(&mut *conn)
         .await
         .map_err(|e| FinancialError::DatabaseError(format!("Failed to update transaction: {}", e)))?;
 
-        Ok(row)
+        row.map(|record| self.open_record(record)).transpose()
     }
 
     /// Soft delete a transaction (mark as deleted rather than removing)
+    ///
+    /// Attachment references of the transaction are removed in the same
+    /// database transaction; the attached files themselves are left alone.
     pub async fn soft_delete(&self, transaction_id: &str, user_id: &str) -> Result<bool, FinancialError> {
         // Validate UUIDs
         Uuid::parse_str(transaction_id)
@@ -447,163 +793,1021 @@ impl<'a> TransactionRepository<'a> {
 
         let now = Utc::now();
 
+        let mut conn = self.connection().await?;
+        let mut tx = conn.begin()
+            .await
+            .map_err(|e| FinancialError::DatabaseError(format!("Failed to begin transaction: {}", e)))?;
+
         // Use parameterized query to mark as deleted
         let result = sqlx::query!(
             r#"
             UPDATE transactions SET
                 is_active = false,
-                updated_at = $3
+                updated_at = $3,
+                deleted_at = $3
             WHERE id = $1 AND user_id = $2 AND is_active = true
             "#,
             transaction_id,
             user_id,
             now
         )
-        .execute(&self.db.pool)
+        .execute(&mut *tx)
         .await
         .map_err(|e| FinancialError::DatabaseError(format!("Failed to delete transaction: {}", e)))?;
 
-        Ok(result.rows_affected() > 0)
-    }
+        let deleted = result.rows_affected() > 0;
+        if deleted {
+            sqlx::query!(
+                "DELETE FROM transaction_attachments WHERE transaction_id = $1 AND user_id = $2",
+                transaction_id,
+                user_id
+            )
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| FinancialError::DatabaseError(format!("Failed to remove transaction attachments: {}", e)))?;
+        }
 
-    /// Find transactions with filtering using secure query builder
-    pub async fn find_filtered(
-        &self,
-        user_id: &str,
-        filter: &TransactionFilter,
-        limit: i32,
-        offset: i32,
-    ) -> Result<Vec<TransactionRecord>, FinancialError> {
-        // Build secure query with active records filter
-        let base_query = r#"
-            SELECT
-                id, user_id, account_id, amount, description, category,
-                subcategory, transaction_date, created_at, updated_at,
-                transaction_type, merchant, location, is_recurring,
-                tags, notes, ml_confidence, COALESCE(is_active, true) as is_active
-            FROM transactions
-        "#;
+        tx.commit()
+            .await
+            .map_err(|e| FinancialError::DatabaseError(format!("Failed to commit transaction: {}", e)))?;
 
-        let mut secure_query = SecureQuery::new(&self.db.pool, base_query);
+        Ok(deleted)
+    }
 
-        // Always filter by user and active status
-        secure_query = secure_query.add_where_clause("user_id = $1", user_id.to_string())?;
-        secure_query = secure_query.add_where_clause("COALESCE(is_active, true) = $1", true)?;
+    /// Permanently remove transactions soft-deleted before `older_than`
+    ///
+    /// Active transactions are never touched, however old. Returns the number
+    /// of rows removed.
+    pub async fn purge_soft_deleted(&self, older_than: DateTime<Utc>) -> Result<u64, FinancialError> {
+        let mut conn = self.connection().await?;
 
-        // Apply filters if provided
-        if let Some(account_ids) = &filter.account_ids {
-            if !account_ids.is_empty() {
-                secure_query = secure_query.add_in_clause("account_id", account_ids.clone())?;
-            }
-        }
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM transactions
+            WHERE is_active = false AND deleted_at < $1
+            "#,
+            older_than
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| FinancialError::DatabaseError(format!("Failed to purge deleted transactions: {}", e)))?;
 
-        if let Some(categories) = &filter.categories {
-            if !categories.is_empty() {
-                secure_query = secure_query.add_in_clause("category", categories.clone())?;
-            }
-        }
+        Ok(result.rows_affected())
+    }
 
-        if let Some(amount_min) = &filter.amount_min {
-            secure_query = secure_query.add_where_clause("amount >= $1", *amount_min)?;
-        }
+    /// Assign a category to many transactions atomically
+    ///
+    /// Runs a single UPDATE inside a database transaction. Only active rows owned
+    /// by `user_id` are touched; any other ID is reported back as not found so the
+    /// caller cannot probe for transactions belonging to other users.
+    pub async fn bulk_update_category(
+        &self,
+        user_id: &str,
+        transaction_ids: &[String],
+        category: &str,
+    ) -> Result<BulkUpdateOutcome, FinancialError> {
+        Uuid::parse_str(user_id)
+            .map_err(|_| FinancialError::ValidationError("Invalid user ID format".to_string()))?;
 
-        if let Some(amount_max) = &filter.amount_max {
-            secure_query = secure_query.add_where_clause("amount <= $1", *amount_max)?;
+        if transaction_ids.is_empty() {
+            return Err(FinancialError::ValidationError("At least one transaction ID is required".to_string()));
         }
-
-        if let Some(date_start) = &filter.date_start {
-            secure_query = secure_query.add_where_clause("transaction_date >= $1", *date_start)?;
+        if transaction_ids.len() > MAX_BULK_UPDATE_SIZE {
+            return Err(FinancialError::ValidationError(
+                format!("A maximum of {} transactions can be updated at once", MAX_BULK_UPDATE_SIZE)
+            ));
         }
-
-        if let Some(date_end) = &filter.date_end {
-            secure_query = secure_query.add_where_clause("transaction_date <= $1", *date_end)?;
+        for id in transaction_ids {
+            Uuid::parse_str(id)
+                .map_err(|_| FinancialError::ValidationError(format!("Invalid transaction ID format: {}", id)))?;
         }
 
-        if let Some(search_text) = &filter.search_text {
-            if !search_text.trim().is_empty() {
-                InputValidator::validate_string_field(search_text, 200, "search text")?;
-                let pattern = format!("%{}%", search_text.trim());
-                secure_query = secure_query.add_where_clause(
-                    "(description ILIKE $1 OR merchant ILIKE $1 OR notes ILIKE $1)",
-                    pattern
-                )?;
-            }
+        if category.trim().is_empty() {
+            return Err(FinancialError::ValidationError("Category cannot be empty".to_string()));
         }
+        InputValidator::validate_string_field(category, 100, "category")?;
 
-        // Add ordering and pagination
-        secure_query = secure_query
-            .add_order_by("transaction_date", OrderDirection::Desc)?
-            .add_order_by("created_at", OrderDirection::Desc)?
-            .add_pagination(limit, offset)?;
+        let requested = dedup_ids(transaction_ids);
+        let now = Utc::now();
 
-        // Execute the secure query
-        secure_query.fetch_all().await
-    }
+        let mut conn = self.connection().await?;
+        let mut tx = conn.begin()
+            .await
+            .map_err(|e| FinancialError::DatabaseError(format!("Failed to begin transaction: {}", e)))?;
 
-    /// Create a new transaction with input validation
-    pub async fn create(&self, transaction: &CreateTransactionRequest) -> Result<TransactionRecord, FinancialError> {
-        // Validate all input before database operation
-        let temp_input = crate::commands::financial::TransactionInput {
-            account_id: transaction.account_id.clone(),
-            amount: transaction.amount.to_string(),
-            description: transaction.description.clone(),
-            category: transaction.category.clone(),
-            subcategory: transaction.subcategory.clone(),
-            transaction_date: transaction.transaction_date,
-            transaction_type: transaction.transaction_type,
-            merchant: transaction.merchant.clone(),
-            location: transaction.location.clone(),
-            is_recurring: transaction.is_recurring,
-            tags: transaction.tags.clone(),
-            notes: transaction.notes.clone(),
-        };
+        let updated_ids: Vec<String> = sqlx::query_scalar!(
+            r#"
+            UPDATE transactions SET
+                category = $3,
+                updated_at = $4
+            WHERE id = ANY($1) AND user_id = $2 AND is_active = true
+            RETURNING id
+            "#,
+            &requested,
+            user_id,
+            category.trim(),
+            now
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| FinancialError::DatabaseError(format!("Failed to categorize transactions: {}", e)))?;
 
-        InputValidator::validate_transaction_input(&temp_input)?;
+        tx.commit()
+            .await
+            .map_err(|e| FinancialError::DatabaseError(format!("Failed to commit transaction: {}", e)))?;
 
-        let id = Uuid::new_v4().to_string();
-        let now = Utc::now();
+        let not_found_ids = missing_ids(&requested, &updated_ids);
 
-        // Use parameterized query with all validated inputs
-        let row = sqlx::query_as!(
-            TransactionRecord,
-            r#"
-            INSERT INTO transactions (
+        Ok(BulkUpdateOutcome {
+            updated_ids,
+            not_found_ids,
+        })
+    }
+
+    /// Keep one transaction and soft-delete its duplicates atomically
+    ///
+    /// The kept transaction must be active and owned by `user_id`. Duplicates are
+    /// only removed when they belong to the same user and account; any other ID
+    /// is reported back as not found.
+    pub async fn merge_duplicates(
+        &self,
+        user_id: &str,
+        keep_id: &str,
+        duplicate_ids: &[String],
+    ) -> Result<BulkUpdateOutcome, FinancialError> {
+        Uuid::parse_str(user_id)
+            .map_err(|_| FinancialError::ValidationError("Invalid user ID format".to_string()))?;
+        Uuid::parse_str(keep_id)
+            .map_err(|_| FinancialError::ValidationError("Invalid transaction ID format".to_string()))?;
+
+        if duplicate_ids.is_empty() {
+            return Err(FinancialError::ValidationError("At least one duplicate transaction ID is required".to_string()));
+        }
+        if duplicate_ids.len() > MAX_BULK_UPDATE_SIZE {
+            return Err(FinancialError::ValidationError(
+                format!("A maximum of {} transactions can be merged at once", MAX_BULK_UPDATE_SIZE)
+            ));
+        }
+        for id in duplicate_ids {
+            Uuid::parse_str(id)
+                .map_err(|_| FinancialError::ValidationError(format!("Invalid transaction ID format: {}", id)))?;
+        }
+
+        let requested: Vec<String> = dedup_ids(duplicate_ids)
+            .into_iter()
+            .filter(|id| id != keep_id)
+            .collect();
+        if requested.is_empty() {
+            return Err(FinancialError::ValidationError("Cannot merge a transaction with itself".to_string()));
+        }
+        let now = Utc::now();
+
+        let mut conn = self.connection().await?;
+        let mut tx = conn.begin()
+            .await
+            .map_err(|e| FinancialError::DatabaseError(format!("Failed to begin transaction: {}", e)))?;
+
+        // Lock the kept row so it cannot be deleted while duplicates are removed
+        let account_id: String = sqlx::query_scalar!(
+            r#"
+            SELECT account_id FROM transactions
+            WHERE id = $1 AND user_id = $2 AND is_active = true
+            FOR UPDATE
+            "#,
+            keep_id,
+            user_id
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| FinancialError::DatabaseError(format!("Failed to load transaction: {}", e)))?
+        .ok_or_else(|| FinancialError::ValidationError("Transaction to keep not found".to_string()))?;
+
+        let removed_ids: Vec<String> = sqlx::query_scalar!(
+            r#"
+            UPDATE transactions SET
+                is_active = false,
+                updated_at = $4,
+                deleted_at = $4
+            WHERE id = ANY($1) AND user_id = $2 AND account_id = $3 AND is_active = true
+            RETURNING id
+            "#,
+            &requested,
+            user_id,
+            account_id,
+            now
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| FinancialError::DatabaseError(format!("Failed to merge transactions: {}", e)))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| FinancialError::DatabaseError(format!("Failed to commit transaction: {}", e)))?;
+
+        let not_found_ids = missing_ids(&requested, &removed_ids);
+
+        Ok(BulkUpdateOutcome {
+            updated_ids: removed_ids,
+            not_found_ids,
+        })
+    }
+
+    /// Find transactions with filtering using secure query builder
+    pub async fn find_filtered(
+        &self,
+        user_id: &str,
+        filter: &TransactionFilter,
+        limit: i32,
+        offset: i32,
+    ) -> Result<Vec<TransactionRecord>, FinancialError> {
+        let secure_query = self.filtered_query(user_id, filter)?;
+
+        // Add ordering and pagination
+        let mut secure_query = secure_query
+            .add_order_by("transaction_date", OrderDirection::Desc)?
+            .add_order_by("created_at", OrderDirection::Desc)?
+            .add_pagination(limit, offset)?;
+
+        // Execute the secure query
+        let records = secure_query.fetch_all().await?;
+        self.open_records(records)
+    }
+
+    /// Fetch the next page of filtered transactions after `cursor`
+    ///
+    /// Pages are ordered oldest first by `(transaction_date, id)` and selected
+    /// with a keyset condition, so callers can walk arbitrarily large histories
+    /// one bounded page at a time.
+    pub async fn find_page_after(
+        &self,
+        user_id: &str,
+        filter: &TransactionFilter,
+        cursor: Option<&TransactionCursor>,
+        page_size: i32,
+    ) -> Result<Vec<TransactionRecord>, FinancialError> {
+        let mut secure_query = self.filtered_query(user_id, filter)?;
+
+        if let Some(cursor) = cursor {
+            secure_query = secure_query.add_cursor_after(
+                "transaction_date",
+                "id",
+                cursor.transaction_date,
+                cursor.id.clone(),
+            )?;
+        }
+
+        let mut secure_query = secure_query
+            .add_order_by("transaction_date", OrderDirection::Asc)?
+            .add_order_by("id", OrderDirection::Asc)?
+            .add_pagination(page_size, 0)?;
+
+        let records = secure_query.fetch_all().await?;
+        self.open_records(records)
+    }
+
+    /// Outflows grouped by `date_trunc` bucket (and optionally category) in one query
+    ///
+    /// Buckets are truncated on the local calendar of `timezone`; spending is
+    /// the negated sum of negative amounts. Only buckets with spending are
+    /// returned, so callers fill gaps with `spending::spending_timeseries`.
+    pub async fn spending_by_bucket(
+        &self,
+        user_id: &str,
+        range: SpendingRange,
+        granularity: SpendingGranularity,
+        by_category: bool,
+        timezone: Tz,
+    ) -> Result<Vec<SpendingBucketRow>, FinancialError> {
+        Uuid::parse_str(user_id)
+            .map_err(|_| FinancialError::ValidationError("Invalid user ID format".to_string()))?;
+
+        let mut conn = self.connection().await?;
+        let rows = sqlx::query_as!(
+            SpendingBucketRow,
+            r#"
+            SELECT
+                date_trunc($2, transaction_date AT TIME ZONE $6)::date AS "bucket!",
+                CASE WHEN $5 THEN category END AS category,
+                SUM(-amount) AS "total!"
+            FROM transactions
+            WHERE user_id = $1
+              AND COALESCE(is_active, true) = true
+              AND amount < 0
+              AND transaction_date >= $3
+              AND transaction_date < $4
+            GROUP BY 1, 2
+            ORDER BY 1, 2
+            "#,
+            user_id,
+            granularity.sql_unit(),
+            range.start_instant(timezone),
+            range.end_instant(timezone),
+            by_category,
+            timezone.name()
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| FinancialError::DatabaseError(format!("Failed to aggregate spending: {}", e)))?;
+
+        Ok(rows)
+    }
+
+    /// Tags and amount of each tagged outflow in `range`, for rolling up by tag prefix
+    pub async fn tagged_spending(
+        &self,
+        user_id: &str,
+        range: SpendingRange,
+        timezone: Tz,
+    ) -> Result<Vec<TaggedSpendingRow>, FinancialError> {
+        Uuid::parse_str(user_id)
+            .map_err(|_| FinancialError::ValidationError("Invalid user ID format".to_string()))?;
+
+        let mut conn = self.connection().await?;
+        let rows = sqlx::query_as!(
+            TaggedSpendingRow,
+            r#"
+            SELECT tags AS "tags!", -amount AS "amount!"
+            FROM transactions
+            WHERE user_id = $1
+              AND COALESCE(is_active, true) = true
+              AND amount < 0
+              AND cardinality(tags) > 0
+              AND transaction_date >= $2
+              AND transaction_date < $3
+            "#,
+            user_id,
+            range.start_instant(timezone),
+            range.end_instant(timezone)
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| FinancialError::DatabaseError(format!("Failed to load tagged spending: {}", e)))?;
+
+        Ok(rows)
+    }
+
+    /// Signed totals in `range` per transaction type and category, inflows
+    /// and outflows kept apart
+    pub async fn totals_by_type_and_category(
+        &self,
+        user_id: &str,
+        range: SpendingRange,
+        timezone: Tz,
+    ) -> Result<Vec<TypeCategoryTotalRow>, FinancialError> {
+        Uuid::parse_str(user_id)
+            .map_err(|_| FinancialError::ValidationError("Invalid user ID format".to_string()))?;
+
+        let mut conn = self.connection().await?;
+        let rows = sqlx::query_as!(
+            TypeCategoryTotalRow,
+            r#"
+            SELECT
+                transaction_type AS "transaction_type!: TransactionType",
+                category,
+                SUM(amount) AS "total!"
+            FROM transactions
+            WHERE user_id = $1
+              AND COALESCE(is_active, true) = true
+              AND amount <> 0
+              AND transaction_date >= $2
+              AND transaction_date < $3
+            GROUP BY transaction_type, category, amount > 0
+            "#,
+            user_id,
+            range.start_instant(timezone),
+            range.end_instant(timezone)
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| FinancialError::DatabaseError(format!("Failed to total transactions: {}", e)))?;
+
+        Ok(rows)
+    }
+
+    /// Base transaction query restricted to the user's active rows and `filter`
+    fn filtered_query(&self, user_id: &str, filter: &TransactionFilter) -> Result<SecureQuery<'a>, FinancialError> {
+        // Build secure query with active records filter
+        let base_query = r#"
+            SELECT
+                id, user_id, account_id, amount, description, category,
+                subcategory, transaction_date, created_at, updated_at,
+                transaction_type, merchant, location, is_recurring,
+                tags, notes, ml_confidence, COALESCE(is_active, true) as is_active,
+                encryption_generation
+            FROM transactions
+        "#;
+
+        let mut secure_query = SecureQuery::new(&self.db.pool, base_query);
+
+        // Always filter by user and active status
+        secure_query = secure_query.add_where_clause("user_id = $1", user_id.to_string())?;
+        secure_query = secure_query.add_where_clause("COALESCE(is_active, true) = $1", true)?;
+
+        // Apply filters if provided
+        if let Some(account_ids) = &filter.account_ids {
+            if !account_ids.is_empty() {
+                secure_query = secure_query.add_in_clause("account_id", account_ids.clone())?;
+            }
+        }
+
+        if let Some(categories) = &filter.categories {
+            if !categories.is_empty() {
+                secure_query = secure_query.add_in_clause("category", categories.clone())?;
+            }
+        }
+
+        if let Some(amount_min) = &filter.amount_min {
+            secure_query = secure_query.add_where_clause("amount >= $1", *amount_min)?;
+        }
+
+        if let Some(amount_max) = &filter.amount_max {
+            secure_query = secure_query.add_where_clause("amount <= $1", *amount_max)?;
+        }
+
+        if let Some(date_start) = &filter.date_start {
+            secure_query = secure_query.add_where_clause("transaction_date >= $1", *date_start)?;
+        }
+
+        if let Some(date_end) = &filter.date_end {
+            secure_query = secure_query.add_where_clause("transaction_date <= $1", *date_end)?;
+        }
+
+        if let Some(search_text) = &filter.search_text {
+            if !search_text.trim().is_empty() {
+                InputValidator::validate_string_field(search_text, 200, "search text")?;
+                let pattern = format!("%{}%", search_text.trim());
+                let policy = self.encryption.map(|(_, policy)| policy);
+                secure_query = secure_query.add_where_clause(&transaction_search_clause(policy), pattern)?;
+            }
+        }
+
+        Ok(secure_query)
+    }
+
+    /// Create a new transaction with input validation
+    pub async fn create(&self, transaction: &CreateTransactionRequest) -> Result<TransactionRecord, FinancialError> {
+        // Validate all input before database operation
+        let temp_input = crate::commands::financial::TransactionInput {
+            account_id: transaction.account_id.clone(),
+            amount: transaction.amount.to_string(),
+            description: transaction.description.clone(),
+            category: transaction.category.clone(),
+            subcategory: transaction.subcategory.clone(),
+            transaction_date: transaction.transaction_date,
+            transaction_type: transaction.transaction_type,
+            merchant: transaction.merchant.clone(),
+            location: transaction.location.clone(),
+            is_recurring: transaction.is_recurring,
+            tags: transaction.tags.clone(),
+            notes: transaction.notes.clone(),
+        };
+
+        InputValidator::validate_transaction_input(&temp_input)?;
+
+        // Closed accounts keep their history but take no new transactions
+        AccountRepository { db: self.db, unit: self.unit }
+            .find_by_id(&transaction.account_id)
+            .await?
+            .filter(|account| account.user_id == transaction.user_id)
+            .ok_or_else(|| FinancialError::ValidationError("Account not found".to_string()))?
+            .ensure_accepts_transactions()?;
+
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now();
+        let (sealed, encryption_generation) = self.sealed_fields(transaction)?;
+
+        let mut conn = self.connection().await?;
+
+        // Use parameterized query with all validated inputs
+        let row = sqlx::query_as!(
+            TransactionRecord,
+            r#"
+            INSERT INTO transactions (
                 id, user_id, account_id, amount, description, category,
                 subcategory, transaction_date, created_at, updated_at,
                 transaction_type, merchant, location, is_recurring,
-                tags, notes, ml_confidence, is_active
+                tags, notes, ml_confidence, is_active, encryption_generation
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19)
             RETURNING
                 id, user_id, account_id, amount, description, category,
                 subcategory, transaction_date, created_at, updated_at,
                 transaction_type as "transaction_type: TransactionType",
-                merchant, location, is_recurring, tags, notes, ml_confidence, is_active
+                merchant, location, is_recurring, tags, notes, ml_confidence, is_active,
+                encryption_generation
+            "#,
+            id,
+            transaction.user_id,
+            transaction.account_id,
+            transaction.amount,
+            transaction.description,
+            transaction.category,
+            transaction.subcategory,
+            transaction.transaction_date.unwrap_or(now),
+            now,
+            now,
+            transaction.transaction_type as TransactionType,
+            sealed.merchant,
+            sealed.location,
+            transaction.is_recurring.unwrap_or(false),
+            &transaction.tags.as_ref().unwrap_or(&vec![]),
+            sealed.notes,
+            transaction.ml_confidence,
+            true, // is_active
+            encryption_generation
+        )
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| FinancialError::DatabaseError(format!("Failed to create transaction: {}", e)))?;
+
+        self.open_record(row)
+    }
+}
+
+/// Text search condition over description, merchant and notes
+///
+/// Ciphertext cannot be matched with `ILIKE`, so columns `policy` encrypts are
+/// only searched on rows written before encryption was enabled. Search over
+/// those fields degrades rather than matching random ciphertext.
+fn transaction_search_clause(policy: Option<SensitiveFieldPolicy>) -> String {
+    let Some(policy) = policy else {
+        return "(description ILIKE $1 OR merchant ILIKE $1 OR notes ILIKE $1)".to_string();
+    };
+
+    let plaintext_only = |column: &str| format!("(encryption_generation IS NULL AND {} ILIKE $1)", column);
+    let merchant = if policy.merchant {
+        plaintext_only("merchant")
+    } else {
+        "merchant ILIKE $1".to_string()
+    };
+    format!("(description ILIKE $1 OR {} OR {})", merchant, plaintext_only("notes"))
+}
+
+/// Upper bound on rows changed by a single bulk operation
+const MAX_BULK_UPDATE_SIZE: usize = 500;
+
+/// Remove duplicate IDs while keeping the caller's order
+fn dedup_ids(ids: &[String]) -> Vec<String> {
+    let mut seen = std::collections::HashSet::new();
+    ids.iter()
+        .filter(|id| seen.insert(id.as_str()))
+        .cloned()
+        .collect()
+}
+
+/// IDs that were requested but not updated, in request order
+fn missing_ids(requested: &[String], updated: &[String]) -> Vec<String> {
+    let updated: std::collections::HashSet<&str> = updated.iter().map(String::as_str).collect();
+    requested.iter()
+        .filter(|id| !updated.contains(id.as_str()))
+        .cloned()
+        .collect()
+}
+
+/// Budget repository for per-user category budgets
+pub struct BudgetRepository<'a> {
+    db: &'a DatabaseManager,
+    unit: Option<&'a UnitOfWork<'a>>,
+}
+
+impl<'a> BudgetRepository<'a> {
+    pub fn new(db: &'a DatabaseManager) -> Self {
+        Self { db, unit: None }
+    }
+
+    /// Repository whose statements run inside `unit`
+    pub fn within(unit: &'a UnitOfWork<'a>) -> Self {
+        Self { db: unit.db, unit: Some(unit) }
+    }
+
+    async fn connection(&self) -> Result<DbConnection<'a>, FinancialError> {
+        connection(self.db, self.unit).await
+    }
+
+    /// Create or replace the budget for a category
+    pub async fn upsert(&self, budget: &CreateBudgetRequest) -> Result<BudgetRecord, FinancialError> {
+        Uuid::parse_str(&budget.user_id)
+            .map_err(|_| FinancialError::ValidationError("Invalid user ID format".to_string()))?;
+
+        if budget.category.trim().is_empty() {
+            return Err(FinancialError::ValidationError("Category cannot be empty".to_string()));
+        }
+        InputValidator::validate_string_field(&budget.category, 100, "category")?;
+
+        if budget.monthly_limit.is_sign_negative() {
+            return Err(FinancialError::ValidationError("Monthly limit cannot be negative".to_string()));
+        }
+        crate::financial::validate_financial_precision(budget.monthly_limit)?;
+
+        let now = Utc::now();
+
+        let mut conn = self.connection().await?;
+        let row = sqlx::query_as!(
+            BudgetRecord,
+            r#"
+            INSERT INTO budgets (
+                id, user_id, category, monthly_limit, rollover, starts_on, created_at, updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $7)
+            ON CONFLICT (user_id, category) DO UPDATE SET
+                monthly_limit = EXCLUDED.monthly_limit,
+                rollover = EXCLUDED.rollover,
+                starts_on = EXCLUDED.starts_on,
+                updated_at = EXCLUDED.updated_at
+            RETURNING id, user_id, category, monthly_limit, rollover, starts_on, created_at, updated_at
+            "#,
+            Uuid::new_v4().to_string(),
+            budget.user_id,
+            budget.category.trim(),
+            budget.monthly_limit,
+            budget.rollover,
+            budget.starts_on,
+            now
+        )
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| FinancialError::DatabaseError(format!("Failed to save budget: {}", e)))?;
+
+        Ok(row)
+    }
+
+    /// Find all budgets for a user
+    pub async fn find_by_user_id(&self, user_id: &str) -> Result<Vec<BudgetRecord>, FinancialError> {
+        Uuid::parse_str(user_id)
+            .map_err(|_| FinancialError::ValidationError("Invalid user ID format".to_string()))?;
+
+        let mut conn = self.connection().await?;
+        let rows = sqlx::query_as!(
+            BudgetRecord,
+            r#"
+            SELECT id, user_id, category, monthly_limit, rollover, starts_on, created_at, updated_at
+            FROM budgets
+            WHERE user_id = $1
+            ORDER BY category ASC
+            "#,
+            user_id
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| FinancialError::DatabaseError(format!("Failed to fetch budgets: {}", e)))?;
+
+        Ok(rows)
+    }
+}
+
+/// Attachment repository for receipt and document references on transactions
+pub struct AttachmentRepository<'a> {
+    db: &'a DatabaseManager,
+    unit: Option<&'a UnitOfWork<'a>>,
+}
+
+impl<'a> AttachmentRepository<'a> {
+    pub fn new(db: &'a DatabaseManager) -> Self {
+        Self { db, unit: None }
+    }
+
+    /// Repository whose statements run inside `unit`
+    pub fn within(unit: &'a UnitOfWork<'a>) -> Self {
+        Self { db: unit.db, unit: Some(unit) }
+    }
+
+    async fn connection(&self) -> Result<DbConnection<'a>, FinancialError> {
+        connection(self.db, self.unit).await
+    }
+
+    /// Attach a file reference to an active transaction owned by the user
+    ///
+    /// The caller is responsible for checking that the file exists inside the
+    /// app data directory; this only records the reference.
+    pub async fn add(&self, attachment: &CreateAttachmentRequest) -> Result<AttachmentRecord, FinancialError> {
+        Uuid::parse_str(&attachment.transaction_id)
+            .map_err(|_| FinancialError::ValidationError("Invalid transaction ID format".to_string()))?;
+        Uuid::parse_str(&attachment.user_id)
+            .map_err(|_| FinancialError::ValidationError("Invalid user ID format".to_string()))?;
+
+        if attachment.file_path.trim().is_empty() {
+            return Err(FinancialError::ValidationError("Attachment path cannot be empty".to_string()));
+        }
+        if attachment.file_path.contains('\0') {
+            return Err(FinancialError::SecurityError("Attachment path contains invalid characters".to_string()));
+        }
+
+        if attachment.content_hash.len() != 64 || !attachment.content_hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(FinancialError::ValidationError("Content hash must be a hex-encoded SHA-256 digest".to_string()));
+        }
+        if attachment.file_size < 0 {
+            return Err(FinancialError::ValidationError("File size cannot be negative".to_string()));
+        }
+
+        let now = Utc::now();
+
+        let mut conn = self.connection().await?;
+        let row = sqlx::query_as!(
+            AttachmentRecord,
+            r#"
+            INSERT INTO transaction_attachments (
+                id, transaction_id, user_id, file_path, content_hash, file_size, created_at
+            )
+            SELECT $1, t.id, t.user_id, $4, $5, $6, $7
+            FROM transactions t
+            WHERE t.id = $2 AND t.user_id = $3 AND t.is_active = true
+            RETURNING id, transaction_id, user_id, file_path, content_hash, file_size, created_at
+            "#,
+            Uuid::new_v4().to_string(),
+            attachment.transaction_id,
+            attachment.user_id,
+            attachment.file_path,
+            attachment.content_hash.to_ascii_lowercase(),
+            attachment.file_size,
+            now
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| FinancialError::DatabaseError(format!("Failed to add attachment: {}", e)))?;
+
+        row.ok_or_else(|| FinancialError::ValidationError("Transaction not found".to_string()))
+    }
+
+    /// Attachments of a transaction, oldest first
+    pub async fn find_by_transaction(&self, transaction_id: &str, user_id: &str) -> Result<Vec<AttachmentRecord>, FinancialError> {
+        Uuid::parse_str(transaction_id)
+            .map_err(|_| FinancialError::ValidationError("Invalid transaction ID format".to_string()))?;
+        Uuid::parse_str(user_id)
+            .map_err(|_| FinancialError::ValidationError("Invalid user ID format".to_string()))?;
+
+        let mut conn = self.connection().await?;
+        let rows = sqlx::query_as!(
+            AttachmentRecord,
+            r#"
+            SELECT id, transaction_id, user_id, file_path, content_hash, file_size, created_at
+            FROM transaction_attachments
+            WHERE transaction_id = $1 AND user_id = $2
+            ORDER BY created_at ASC, id ASC
+            "#,
+            transaction_id,
+            user_id
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| FinancialError::DatabaseError(format!("Failed to fetch attachments: {}", e)))?;
+
+        Ok(rows)
+    }
+
+    /// Remove one attachment reference; the file itself is left alone
+    pub async fn remove(&self, attachment_id: &str, user_id: &str) -> Result<bool, FinancialError> {
+        Uuid::parse_str(attachment_id)
+            .map_err(|_| FinancialError::ValidationError("Invalid attachment ID format".to_string()))?;
+        Uuid::parse_str(user_id)
+            .map_err(|_| FinancialError::ValidationError("Invalid user ID format".to_string()))?;
+
+        let mut conn = self.connection().await?;
+        let result = sqlx::query!(
+            "DELETE FROM transaction_attachments WHERE id = $1 AND user_id = $2",
+            attachment_id,
+            user_id
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| FinancialError::DatabaseError(format!("Failed to remove attachment: {}", e)))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+/// Import checkpoint repository, one row per user and imported file
+pub struct ImportCheckpointRepository<'a> {
+    db: &'a DatabaseManager,
+    unit: Option<&'a UnitOfWork<'a>>,
+}
+
+impl<'a> ImportCheckpointRepository<'a> {
+    pub fn new(db: &'a DatabaseManager) -> Self {
+        Self { db, unit: None }
+    }
+
+    /// Repository whose statements run inside `unit`
+    pub fn within(unit: &'a UnitOfWork<'a>) -> Self {
+        Self { db: unit.db, unit: Some(unit) }
+    }
+
+    async fn connection(&self) -> Result<DbConnection<'a>, FinancialError> {
+        connection(self.db, self.unit).await
+    }
+
+    /// Checkpoint of an earlier import of the file with `file_hash`, if any
+    pub async fn find(&self, user_id: &str, file_hash: &str) -> Result<Option<ImportCheckpointRecord>, FinancialError> {
+        Uuid::parse_str(user_id)
+            .map_err(|_| FinancialError::ValidationError("Invalid user ID format".to_string()))?;
+
+        let mut conn = self.connection().await?;
+        sqlx::query_as!(
+            ImportCheckpointRecord,
+            r#"
+            SELECT user_id, file_hash, last_committed_row, total_rows, updated_at
+            FROM import_checkpoints
+            WHERE user_id = $1 AND file_hash = $2
             "#,
-            id,
-            transaction.user_id,
-            transaction.account_id,
-            transaction.amount,
-            transaction.description,
-            transaction.category,
-            transaction.subcategory,
-            transaction.transaction_date.unwrap_or(now),
-            now,
-            now,
-            transaction.transaction_type as TransactionType,
-            transaction.merchant,
-            transaction.location,
-            transaction.is_recurring.unwrap_or(false),
-            &transaction.tags.as_ref().unwrap_or(&vec![]),
-            transaction.notes,
-            transaction.ml_confidence,
-            true // is_active
+            user_id,
+            file_hash
         )
-        .fetch_one(&self.db.pool)
+        .fetch_optional(&mut *conn)
         .await
-        .map_err(|e| FinancialError::DatabaseError(format!("Failed to create transaction: {}", e)))?;
+        .map_err(|e| FinancialError::DatabaseError(format!("Failed to load import checkpoint: {}", e)))
+    }
 
-        Ok(row)
+    /// Record that rows up to `last_committed_row` of the file are imported
+    ///
+    /// Run this inside the unit that inserts the batch so the checkpoint only
+    /// moves when the rows are committed.
+    pub async fn save(&self, user_id: &str, file_hash: &str, last_committed_row: i64, total_rows: i64) -> Result<(), FinancialError> {
+        Uuid::parse_str(user_id)
+            .map_err(|_| FinancialError::ValidationError("Invalid user ID format".to_string()))?;
+        if file_hash.len() != 64 || !file_hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(FinancialError::ValidationError("File hash must be a hex-encoded SHA-256 digest".to_string()));
+        }
+
+        let mut conn = self.connection().await?;
+        sqlx::query!(
+            r#"
+            INSERT INTO import_checkpoints (user_id, file_hash, last_committed_row, total_rows, updated_at)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (user_id, file_hash) DO UPDATE SET
+                last_committed_row = EXCLUDED.last_committed_row,
+                total_rows = EXCLUDED.total_rows,
+                updated_at = EXCLUDED.updated_at
+            "#,
+            user_id,
+            file_hash.to_ascii_lowercase(),
+            last_committed_row,
+            total_rows,
+            Utc::now()
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| FinancialError::DatabaseError(format!("Failed to save import checkpoint: {}", e)))?;
+
+        Ok(())
+    }
+}
+
+/// Insight dismissal repository, one row per user and insight rule
+pub struct InsightDismissalRepository<'a> {
+    db: &'a DatabaseManager,
+    unit: Option<&'a UnitOfWork<'a>>,
+}
+
+impl<'a> InsightDismissalRepository<'a> {
+    pub fn new(db: &'a DatabaseManager) -> Self {
+        Self { db, unit: None }
+    }
+
+    /// Repository whose statements run inside `unit`
+    pub fn within(unit: &'a UnitOfWork<'a>) -> Self {
+        Self { db: unit.db, unit: Some(unit) }
+    }
+
+    async fn connection(&self) -> Result<DbConnection<'a>, FinancialError> {
+        connection(self.db, self.unit).await
+    }
+
+    /// Hide a rule's insights until `snoozed_until`, or for good when `None`
+    ///
+    /// Dismissing a rule again replaces the earlier dismissal or snooze.
+    pub async fn dismiss(
+        &self,
+        user_id: &str,
+        rule_id: &str,
+        snoozed_until: Option<DateTime<Utc>>,
+    ) -> Result<InsightDismissal, FinancialError> {
+        Uuid::parse_str(user_id)
+            .map_err(|_| FinancialError::ValidationError("Invalid user ID format".to_string()))?;
+        if rule_id.trim().is_empty() {
+            return Err(FinancialError::ValidationError("Rule ID cannot be empty".to_string()));
+        }
+        InputValidator::validate_string_field(rule_id, 100, "rule_id")?;
+
+        let mut conn = self.connection().await?;
+        sqlx::query_as!(
+            InsightDismissal,
+            r#"
+            INSERT INTO insight_dismissals (user_id, rule_id, dismissed_at, snoozed_until)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (user_id, rule_id) DO UPDATE SET
+                dismissed_at = EXCLUDED.dismissed_at,
+                snoozed_until = EXCLUDED.snoozed_until
+            RETURNING rule_id, dismissed_at, snoozed_until
+            "#,
+            user_id,
+            rule_id.trim(),
+            Utc::now(),
+            snoozed_until
+        )
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| FinancialError::DatabaseError(format!("Failed to save insight dismissal: {}", e)))
+    }
+
+    /// Every dismissal the user has made, including expired snoozes
+    pub async fn find_by_user_id(&self, user_id: &str) -> Result<Vec<InsightDismissal>, FinancialError> {
+        Uuid::parse_str(user_id)
+            .map_err(|_| FinancialError::ValidationError("Invalid user ID format".to_string()))?;
+
+        let mut conn = self.connection().await?;
+        sqlx::query_as!(
+            InsightDismissal,
+            r#"
+            SELECT rule_id, dismissed_at, snoozed_until
+            FROM insight_dismissals
+            WHERE user_id = $1
+            ORDER BY rule_id ASC
+            "#,
+            user_id
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| FinancialError::DatabaseError(format!("Failed to fetch insight dismissals: {}", e)))
+    }
+}
+
+/// Security audit repository, one row per entry of the audit hash chain
+///
+/// Entries are only ever appended; nothing here updates or deletes them.
+pub struct SecurityAuditRepository<'a> {
+    db: &'a DatabaseManager,
+    unit: Option<&'a UnitOfWork<'a>>,
+}
+
+impl<'a> SecurityAuditRepository<'a> {
+    pub fn new(db: &'a DatabaseManager) -> Self {
+        Self { db, unit: None }
+    }
+
+    /// Repository whose statements run inside `unit`
+    pub fn within(unit: &'a UnitOfWork<'a>) -> Self {
+        Self { db: unit.db, unit: Some(unit) }
+    }
+
+    async fn connection(&self) -> Result<DbConnection<'a>, FinancialError> {
+        connection(self.db, self.unit).await
+    }
+
+    /// Store a chain entry; a sequence that is already stored is rejected
+    pub async fn append(&self, entry: &SecurityAuditRecord) -> Result<(), FinancialError> {
+        let mut conn = self.connection().await?;
+        sqlx::query!(
+            r#"
+            INSERT INTO security_audit_events
+                (sequence, event_type, severity, occurred_at, source, details, previous_hash, hash)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+            entry.sequence,
+            entry.event_type,
+            entry.severity,
+            entry.occurred_at,
+            entry.source,
+            entry.details,
+            entry.previous_hash,
+            entry.hash
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| FinancialError::DatabaseError(format!("Failed to store security audit event: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Every stored entry in chain order
+    pub async fn find_all(&self) -> Result<Vec<SecurityAuditRecord>, FinancialError> {
+        let mut conn = self.connection().await?;
+        sqlx::query_as!(
+            SecurityAuditRecord,
+            r#"
+            SELECT sequence, event_type, severity, occurred_at, source, details, previous_hash, hash
+            FROM security_audit_events
+            ORDER BY sequence ASC
+            "#
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| FinancialError::DatabaseError(format!("Failed to fetch security audit events: {}", e)))
+    }
+
+    /// The most recently appended entry, if any
+    pub async fn find_latest(&self) -> Result<Option<SecurityAuditRecord>, FinancialError> {
+        let mut conn = self.connection().await?;
+        sqlx::query_as!(
+            SecurityAuditRecord,
+            r#"
+            SELECT sequence, event_type, severity, occurred_at, source, details, previous_hash, hash
+            FROM security_audit_events
+            ORDER BY sequence DESC
+            LIMIT 1
+            "#
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| FinancialError::DatabaseError(format!("Failed to fetch security audit events: {}", e)))
     }
 }
 
@@ -627,6 +1831,50 @@ pub struct AccountRecord {
     pub account_number_masked: Option<String>,
     pub credit_limit: Option<Decimal>,
     pub interest_rate: Option<Decimal>,
+    /// When the account was closed; archived accounts keep their history
+    pub archived_at: Option<DateTime<Utc>>,
+    /// Date from which an archived account drops out of net worth and reports
+    pub balance_zeroed_at: Option<DateTime<Utc>>,
+}
+
+impl AccountRecord {
+    pub fn is_archived(&self) -> bool {
+        self.archived_at.is_some()
+    }
+
+    /// Reject new transactions against a closed account
+    pub fn ensure_accepts_transactions(&self) -> Result<(), FinancialError> {
+        if self.is_archived() {
+            return Err(FinancialError::ValidationError(format!(
+                "Account {} is archived and cannot accept new transactions", self.name
+            )));
+        }
+        Ok(())
+    }
+
+    /// Whether the account contributes to net worth and reports at `as_of`
+    ///
+    /// Archived accounts stay in until their balance-zeroed date so historical
+    /// figures don't change when an account is closed.
+    pub fn included_in_reports_at(&self, as_of: DateTime<Utc>) -> bool {
+        if !self.is_active {
+            return false;
+        }
+        match (self.archived_at, self.balance_zeroed_at) {
+            (None, _) => true,
+            (Some(_), Some(zeroed_at)) => as_of < zeroed_at,
+            (Some(archived_at), None) => as_of < archived_at,
+        }
+    }
+}
+
+/// IDs of the archived accounts among `accounts`, which listings hide
+pub fn archived_account_ids(accounts: &[AccountRecord]) -> std::collections::HashSet<String> {
+    accounts
+        .iter()
+        .filter(|account| account.is_archived())
+        .map(|account| account.id.clone())
+        .collect()
 }
 
 #[derive(Debug, sqlx::FromRow, Serialize, Deserialize)]
@@ -651,6 +1899,73 @@ pub struct TransactionRecord {
     pub ml_confidence: Option<f64>,
     #[serde(default = "default_true")]
     pub is_active: bool,
+    /// Vault key generation sensitive fields were encrypted with; `None` for plaintext rows
+    #[serde(default, skip_serializing)]
+    pub encryption_generation: Option<i32>,
+}
+
+#[derive(Debug, sqlx::FromRow, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetRecord {
+    pub id: String,
+    pub user_id: String,
+    pub category: String,
+    pub monthly_limit: Decimal,
+    pub rollover: bool,
+    /// First day of the first month the budget applies to
+    pub starts_on: NaiveDate,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, sqlx::FromRow, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttachmentRecord {
+    pub id: String,
+    pub transaction_id: String,
+    pub user_id: String,
+    /// Canonical path of the file inside the app data directory
+    pub file_path: String,
+    /// Hex-encoded SHA-256 of the file contents when it was attached
+    pub content_hash: String,
+    pub file_size: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// How far the import of one file has got
+#[derive(Debug, sqlx::FromRow, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportCheckpointRecord {
+    pub user_id: String,
+    /// Hex-encoded SHA-256 of the imported file
+    pub file_hash: String,
+    /// Data rows are numbered from 1; 0 means nothing is committed yet
+    pub last_committed_row: i64,
+    pub total_rows: i64,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// One grouped row of `TransactionRepository::spending_by_bucket`
+#[derive(Debug, sqlx::FromRow)]
+pub struct SpendingBucketRow {
+    pub bucket: NaiveDate,
+    pub category: Option<String>,
+    pub total: Decimal,
+}
+
+/// One outflow of `TransactionRepository::tagged_spending`
+#[derive(Debug, sqlx::FromRow)]
+pub struct TaggedSpendingRow {
+    pub tags: Vec<String>,
+    pub amount: Decimal,
+}
+
+/// One grouped row of `TransactionRepository::totals_by_type_and_category`
+#[derive(Debug, sqlx::FromRow)]
+pub struct TypeCategoryTotalRow {
+    pub transaction_type: TransactionType,
+    pub category: Option<String>,
+    pub total: Decimal,
 }
 
 fn default_true() -> bool {
@@ -661,7 +1976,7 @@ fn default_true() -> bool {
 // Database Types (matching PostgreSQL enums)
 // ============================================================================
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, sqlx::Type, Serialize, Deserialize)]
 #[sqlx(type_name = "account_type", rename_all = "snake_case")]
 #[serde(rename_all = "camelCase")]
 pub enum AccountType {
@@ -727,6 +2042,26 @@ pub struct CreateTransactionRequest {
     pub ml_confidence: Option<f64>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateBudgetRequest {
+    pub user_id: String,
+    pub category: String,
+    pub monthly_limit: Decimal,
+    pub rollover: bool,
+    pub starts_on: NaiveDate,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateAttachmentRequest {
+    pub user_id: String,
+    pub transaction_id: String,
+    pub file_path: String,
+    pub content_hash: String,
+    pub file_size: i64,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TransactionFilter {
@@ -741,6 +2076,58 @@ pub struct TransactionFilter {
     pub search_text: Option<String>,
 }
 
+/// Keyset position of the last transaction returned by a page
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionCursor {
+    pub transaction_date: DateTime<Utc>,
+    pub id: String,
+}
+
+impl TransactionCursor {
+    /// Cursor pointing just past `record`
+    pub fn after(record: &TransactionRecord) -> Self {
+        Self {
+            transaction_date: record.transaction_date,
+            id: record.id.clone(),
+        }
+    }
+}
+
+/// An account balance that was rebuilt from the transaction ledger
+#[derive(Debug, Clone, sqlx::FromRow, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BalanceCorrection {
+    pub account_id: String,
+    pub name: String,
+    pub previous_balance: Decimal,
+    pub recomputed_balance: Decimal,
+}
+
+/// Result of a bulk update over a set of transaction IDs
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkUpdateOutcome {
+    pub updated_ids: Vec<String>,
+    pub not_found_ids: Vec<String>,
+}
+
+/// Entry of the security audit hash chain as stored
+///
+/// Event type and severity are kept as their variant names, which is also how
+/// they are hashed, so the chain can be recomputed from these rows alone.
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct SecurityAuditRecord {
+    pub sequence: i64,
+    pub event_type: String,
+    pub severity: String,
+    pub occurred_at: DateTime<Utc>,
+    pub source: String,
+    pub details: String,
+    pub previous_hash: String,
+    pub hash: String,
+}
+
 // ============================================================================
 // Migration Support
 // ============================================================================
@@ -792,6 +2179,7 @@ pub struct MigrationInfo {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::retention::{purge_expired, DataRetentionSettings};
 
     #[test]
     fn test_database_config_default() {
@@ -810,4 +2198,451 @@ mod tests {
         let deserialized: AccountType = serde_json::from_str(&json).unwrap();
         assert_eq!(deserialized, AccountType::Checking);
     }
+
+    fn account(name: &str, archived_at: Option<DateTime<Utc>>, balance_zeroed_at: Option<DateTime<Utc>>) -> AccountRecord {
+        let created = Utc::now() - chrono::Duration::days(365);
+        AccountRecord {
+            id: Uuid::new_v4().to_string(),
+            user_id: "user".to_string(),
+            name: name.to_string(),
+            account_type: AccountType::Checking,
+            balance: Decimal::ZERO,
+            currency: "USD".to_string(),
+            is_active: true,
+            created_at: created,
+            updated_at: created,
+            institution: None,
+            account_number_masked: None,
+            credit_limit: None,
+            interest_rate: None,
+            archived_at,
+            balance_zeroed_at,
+        }
+    }
+
+    #[test]
+    fn test_archived_account_rejects_new_transactions() {
+        let open = account("Checking", None, None);
+        assert!(open.ensure_accepts_transactions().is_ok());
+
+        let closed = account("Old Savings", Some(Utc::now()), None);
+        match closed.ensure_accepts_transactions() {
+            Err(FinancialError::ValidationError(message)) => assert!(message.contains("archived")),
+            other => panic!("expected validation error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_archived_accounts_hidden_unless_requested() {
+        let accounts = || vec![
+            account("Checking", None, None),
+            account("Old Savings", Some(Utc::now()), None),
+        ];
+
+        let accounts = accounts();
+        let archived = archived_account_ids(&accounts);
+        let listed: Vec<&AccountRecord> = accounts.iter().filter(|a| !archived.contains(&a.id)).collect();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].name, "Checking");
+        assert!(archived.contains(&accounts[1].id));
+    }
+
+    #[test]
+    fn test_archived_account_reported_until_balance_zeroed() {
+        let archived_at = Utc::now() - chrono::Duration::days(30);
+        let zeroed_at = Utc::now() - chrono::Duration::days(10);
+        let closed = account("Old Savings", Some(archived_at), Some(zeroed_at));
+
+        assert!(closed.included_in_reports_at(archived_at));
+        assert!(closed.included_in_reports_at(zeroed_at - chrono::Duration::days(1)));
+        assert!(!closed.included_in_reports_at(zeroed_at));
+        assert!(!closed.included_in_reports_at(Utc::now()));
+
+        let deleted = AccountRecord { is_active: false, ..account("Gone", None, None) };
+        assert!(!deleted.included_in_reports_at(Utc::now()));
+        assert!(account("Checking", None, None).included_in_reports_at(Utc::now()));
+    }
+
+    #[test]
+    fn test_bulk_update_id_bookkeeping() {
+        let requested = dedup_ids(&[
+            "a".to_string(),
+            "b".to_string(),
+            "a".to_string(),
+            "c".to_string(),
+        ]);
+        assert_eq!(requested, vec!["a", "b", "c"]);
+
+        let updated = vec!["c".to_string(), "a".to_string()];
+        assert_eq!(missing_ids(&requested, &updated), vec!["b"]);
+        assert!(missing_ids(&requested, &requested).is_empty());
+    }
+
+    #[test]
+    fn test_search_skips_encrypted_columns() {
+        assert_eq!(
+            transaction_search_clause(None),
+            "(description ILIKE $1 OR merchant ILIKE $1 OR notes ILIKE $1)"
+        );
+
+        let notes_only = transaction_search_clause(Some(SensitiveFieldPolicy::default()));
+        assert!(notes_only.contains("OR merchant ILIKE $1"));
+        assert!(notes_only.contains("(encryption_generation IS NULL AND notes ILIKE $1)"));
+
+        let with_merchant = transaction_search_clause(Some(SensitiveFieldPolicy { merchant: true, location: false }));
+        assert!(with_merchant.contains("(encryption_generation IS NULL AND merchant ILIKE $1)"));
+    }
+
+    fn test_db(pool: PgPool) -> DatabaseManager {
+        DatabaseManager { pool, config: DatabaseConfig::default() }
+    }
+
+    fn account_request(user_id: &str) -> CreateAccountRequest {
+        CreateAccountRequest {
+            user_id: user_id.to_string(),
+            name: "Checking".to_string(),
+            account_type: AccountType::Checking,
+            balance: Decimal::new(150000, 2),
+            currency: "USD".to_string(),
+            institution: None,
+            account_number_masked: None,
+            credit_limit: None,
+            interest_rate: None,
+        }
+    }
+
+    fn transaction_request(user_id: &str, account_id: &str) -> CreateTransactionRequest {
+        CreateTransactionRequest {
+            user_id: user_id.to_string(),
+            account_id: account_id.to_string(),
+            amount: Decimal::new(-4250, 2),
+            description: "Groceries".to_string(),
+            category: None,
+            subcategory: None,
+            transaction_date: None,
+            transaction_type: TransactionType::Debit,
+            merchant: None,
+            location: None,
+            is_recurring: None,
+            tags: None,
+            notes: None,
+            ml_confidence: None,
+        }
+    }
+
+    async fn persisted_counts(db: &DatabaseManager, user_id: &str) -> (i64, i64) {
+        let accounts = sqlx::query_scalar!("SELECT COUNT(*) FROM accounts WHERE user_id = $1", user_id)
+            .fetch_one(&db.pool)
+            .await
+            .unwrap()
+            .unwrap_or(0);
+        let transactions = sqlx::query_scalar!("SELECT COUNT(*) FROM transactions WHERE user_id = $1", user_id)
+            .fetch_one(&db.pool)
+            .await
+            .unwrap()
+            .unwrap_or(0);
+        (accounts, transactions)
+    }
+
+    #[sqlx::test]
+    async fn test_failed_unit_of_work_persists_nothing(pool: PgPool) {
+        let db = test_db(pool);
+        let user_id = Uuid::new_v4().to_string();
+
+        let user = user_id.clone();
+        let result = db.execute_transaction(move |unit| Box::pin(async move {
+            let account = AccountRepository::within(unit).create(&account_request(&user)).await?;
+
+            // The uncommitted account is visible to other repositories in the unit
+            TransactionRepository::within(unit)
+                .create(&transaction_request(&user, &account.id))
+                .await?;
+
+            // Fails midway: the second transaction names an account that does not exist
+            TransactionRepository::within(unit)
+                .create(&transaction_request(&user, &Uuid::new_v4().to_string()))
+                .await?;
+            Ok(account.id)
+        })).await;
+
+        assert!(matches!(result, Err(FinancialError::ValidationError(ref message)) if message == "Account not found"));
+        assert_eq!(persisted_counts(&db, &user_id).await, (0, 0));
+    }
+
+    #[sqlx::test]
+    async fn test_unit_of_work_commits_all_repositories_together(pool: PgPool) {
+        let db = test_db(pool);
+        let user_id = Uuid::new_v4().to_string();
+
+        // Dropping a unit without committing discards its changes
+        {
+            let unit = UnitOfWork::begin(&db).await.unwrap();
+            AccountRepository::within(&unit).create(&account_request(&user_id)).await.unwrap();
+        }
+        assert_eq!(persisted_counts(&db, &user_id).await, (0, 0));
+
+        let unit = UnitOfWork::begin(&db).await.unwrap();
+        let account = AccountRepository::within(&unit).create(&account_request(&user_id)).await.unwrap();
+        TransactionRepository::within(&unit)
+            .create(&transaction_request(&user_id, &account.id))
+            .await
+            .unwrap();
+
+        // Nothing is visible outside the unit until it commits
+        assert!(AccountRepository::new(&db).find_by_id(&account.id).await.unwrap().is_none());
+        unit.commit().await.unwrap();
+
+        assert!(AccountRepository::new(&db).find_by_id(&account.id).await.unwrap().is_some());
+        assert_eq!(persisted_counts(&db, &user_id).await, (1, 1));
+    }
+
+    fn attachment_request(user_id: &str, transaction_id: &str, file_name: &str) -> CreateAttachmentRequest {
+        CreateAttachmentRequest {
+            user_id: user_id.to_string(),
+            transaction_id: transaction_id.to_string(),
+            file_path: format!("/data/atlas/receipts/{}", file_name),
+            content_hash: "ab".repeat(32),
+            file_size: 2048,
+        }
+    }
+
+    #[sqlx::test]
+    async fn test_attachments_listed_and_removed_with_transaction(pool: PgPool) {
+        let db = test_db(pool);
+        let user_id = Uuid::new_v4().to_string();
+        let account = AccountRepository::new(&db).create(&account_request(&user_id)).await.unwrap();
+        let transaction = TransactionRepository::new(&db)
+            .create(&transaction_request(&user_id, &account.id))
+            .await
+            .unwrap();
+
+        let attachments = AttachmentRepository::new(&db);
+        let receipt = attachments
+            .add(&attachment_request(&user_id, &transaction.id, "receipt.pdf"))
+            .await
+            .unwrap();
+        let invoice = attachments
+            .add(&attachment_request(&user_id, &transaction.id, "invoice.png"))
+            .await
+            .unwrap();
+
+        let listed = attachments.find_by_transaction(&transaction.id, &user_id).await.unwrap();
+        let listed_ids: Vec<&str> = listed.iter().map(|a| a.id.as_str()).collect();
+        assert_eq!(listed_ids, vec![receipt.id.as_str(), invoice.id.as_str()]);
+
+        // Other users cannot see or attach to the transaction
+        let stranger = Uuid::new_v4().to_string();
+        assert!(attachments.find_by_transaction(&transaction.id, &stranger).await.unwrap().is_empty());
+        assert!(attachments.add(&attachment_request(&stranger, &transaction.id, "x.pdf")).await.is_err());
+
+        assert!(TransactionRepository::new(&db).soft_delete(&transaction.id, &user_id).await.unwrap());
+        assert!(attachments.find_by_transaction(&transaction.id, &user_id).await.unwrap().is_empty());
+        assert!(!attachments.remove(&receipt.id, &user_id).await.unwrap());
+
+        // A deleted transaction accepts no new attachments
+        assert!(matches!(
+            attachments.add(&attachment_request(&user_id, &transaction.id, "late.pdf")).await,
+            Err(FinancialError::ValidationError(ref message)) if message == "Transaction not found"
+        ));
+    }
+
+    #[sqlx::test]
+    async fn test_purge_removes_only_expired_soft_deletes(pool: PgPool) {
+        let db = test_db(pool);
+        let user_id = Uuid::new_v4().to_string();
+        let account = AccountRepository::new(&db).create(&account_request(&user_id)).await.unwrap();
+        let transactions = TransactionRepository::new(&db);
+        let mut ids = Vec::new();
+        for _ in 0..3 {
+            ids.push(transactions.create(&transaction_request(&user_id, &account.id)).await.unwrap().id);
+        }
+        let (expired, recent, active) = (&ids[0], &ids[1], &ids[2]);
+
+        assert!(transactions.soft_delete(expired, &user_id).await.unwrap());
+        assert!(transactions.soft_delete(recent, &user_id).await.unwrap());
+        // The first delete happened 60 days before the second
+        sqlx::query!(
+            "UPDATE transactions SET deleted_at = deleted_at - INTERVAL '60 days' WHERE id = $1",
+            expired
+        )
+        .execute(&db.pool)
+        .await
+        .unwrap();
+
+        // 31 days on, only the first delete is past the 90-day window
+        let settings = DataRetentionSettings { soft_delete_retention_days: 90, ..Default::default() };
+        let later = Utc::now() + chrono::Duration::days(31);
+        let report = purge_expired(&db, &settings, later).await.unwrap();
+        assert_eq!(report.purged, 1);
+        assert_eq!(report.cutoff, later - chrono::Duration::days(90));
+
+        let remaining: Vec<String> = sqlx::query_scalar!("SELECT id FROM transactions WHERE user_id = $1", user_id)
+            .fetch_all(&db.pool)
+            .await
+            .unwrap();
+        assert!(!remaining.contains(expired));
+        assert!(remaining.contains(recent) && remaining.contains(active));
+
+        // Purging again at the same time finds nothing new
+        assert_eq!(purge_expired(&db, &settings, later).await.unwrap().purged, 0);
+    }
+
+    #[sqlx::test]
+    async fn test_recompute_balances_corrects_corrupted_balance(pool: PgPool) {
+        let db = test_db(pool);
+        let user_id = Uuid::new_v4().to_string();
+        let accounts = AccountRepository::new(&db);
+        let account = accounts.create(&account_request(&user_id)).await.unwrap();
+        let transactions = TransactionRepository::new(&db);
+        transactions.create(&transaction_request(&user_id, &account.id)).await.unwrap();
+        let deleted = transactions.create(&transaction_request(&user_id, &account.id)).await.unwrap();
+        assert!(transactions.soft_delete(&deleted.id, &user_id).await.unwrap());
+
+        sqlx::query!("UPDATE accounts SET balance = 99999.99 WHERE id = $1", account.id)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+
+        // Opening 1500.00 plus the one active -42.50 debit
+        let corrections = accounts.recompute_balances(&user_id).await.unwrap();
+        assert_eq!(corrections.len(), 1);
+        assert_eq!(corrections[0].account_id, account.id);
+        assert_eq!(corrections[0].previous_balance, Decimal::new(9999999, 2));
+        assert_eq!(corrections[0].recomputed_balance, Decimal::new(145750, 2));
+        let stored = accounts.find_by_id(&account.id).await.unwrap().unwrap();
+        assert_eq!(stored.balance, Decimal::new(145750, 2));
+
+        // A consistent ledger reports nothing to change
+        assert!(accounts.recompute_balances(&user_id).await.unwrap().is_empty());
+    }
+
+    async fn active_counts(db: &DatabaseManager, account_id: &str) -> (i64, i64) {
+        let accounts = sqlx::query_scalar!("SELECT COUNT(*) FROM accounts WHERE id = $1 AND is_active = true", account_id)
+            .fetch_one(&db.pool)
+            .await
+            .unwrap()
+            .unwrap_or(0);
+        let transactions = sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM transactions WHERE account_id = $1 AND is_active = true",
+            account_id
+        )
+        .fetch_one(&db.pool)
+        .await
+        .unwrap()
+        .unwrap_or(0);
+        (accounts, transactions)
+    }
+
+    #[sqlx::test]
+    async fn test_account_with_transactions_is_not_deleted_without_cascade(pool: PgPool) {
+        let db = test_db(pool);
+        let user_id = Uuid::new_v4().to_string();
+        let accounts = AccountRepository::new(&db);
+        let account = accounts.create(&account_request(&user_id)).await.unwrap();
+        let transactions = TransactionRepository::new(&db);
+        transactions.create(&transaction_request(&user_id, &account.id)).await.unwrap();
+        transactions.create(&transaction_request(&user_id, &account.id)).await.unwrap();
+
+        let result = accounts.soft_delete(&account.id, &user_id, false).await;
+        assert!(
+            matches!(result, Err(FinancialError::ValidationError(ref message)) if message.contains("2 existing transaction(s)")),
+            "{:?}",
+            result
+        );
+        assert_eq!(active_counts(&db, &account.id).await, (1, 2));
+
+        // An empty account needs no cascade
+        let empty = accounts.create(&account_request(&user_id)).await.unwrap();
+        assert!(accounts.soft_delete(&empty.id, &user_id, false).await.unwrap());
+        assert!(!accounts.soft_delete(&empty.id, &user_id, false).await.unwrap());
+    }
+
+    #[sqlx::test]
+    async fn test_cascade_deletes_account_with_its_transactions(pool: PgPool) {
+        let db = test_db(pool);
+        let user_id = Uuid::new_v4().to_string();
+        let accounts = AccountRepository::new(&db);
+        let account = accounts.create(&account_request(&user_id)).await.unwrap();
+        let other = accounts.create(&account_request(&user_id)).await.unwrap();
+        let transactions = TransactionRepository::new(&db);
+        let transaction = transactions.create(&transaction_request(&user_id, &account.id)).await.unwrap();
+        transactions.create(&transaction_request(&user_id, &other.id)).await.unwrap();
+        AttachmentRepository::new(&db)
+            .add(&attachment_request(&user_id, &transaction.id, "receipt.pdf"))
+            .await
+            .unwrap();
+
+        // Another user's cascade touches nothing
+        let stranger = Uuid::new_v4().to_string();
+        assert!(!accounts.soft_delete(&account.id, &stranger, true).await.unwrap());
+        assert_eq!(active_counts(&db, &account.id).await, (1, 1));
+
+        assert!(accounts.soft_delete(&account.id, &user_id, true).await.unwrap());
+        assert_eq!(active_counts(&db, &account.id).await, (0, 0));
+        assert_eq!(active_counts(&db, &other.id).await, (1, 1));
+        assert!(AttachmentRepository::new(&db)
+            .find_by_transaction(&transaction.id, &user_id)
+            .await
+            .unwrap()
+            .is_empty());
+        let deleted_at = sqlx::query_scalar!("SELECT deleted_at FROM transactions WHERE id = $1", transaction.id)
+            .fetch_one(&db.pool)
+            .await
+            .unwrap();
+        assert!(deleted_at.is_some());
+    }
+
+    #[sqlx::test]
+    async fn test_dismissing_a_rule_again_replaces_its_snooze(pool: PgPool) {
+        let db = test_db(pool);
+        let user_id = Uuid::new_v4().to_string();
+        let dismissals = InsightDismissalRepository::new(&db);
+        let until = Utc::now() + chrono::Duration::days(7);
+
+        dismissals.dismiss(&user_id, "low-savings-rate", Some(until)).await.unwrap();
+        dismissals.dismiss(&user_id, "dining-share-of-income", None).await.unwrap();
+        let forever = dismissals.dismiss(&user_id, "low-savings-rate", None).await.unwrap();
+        assert_eq!(forever.snoozed_until, None);
+
+        let saved = dismissals.find_by_user_id(&user_id).await.unwrap();
+        let rule_ids: Vec<&str> = saved.iter().map(|d| d.rule_id.as_str()).collect();
+        assert_eq!(rule_ids, vec!["dining-share-of-income", "low-savings-rate"]);
+        assert!(saved.iter().all(|d| d.snoozed_until.is_none()));
+
+        assert!(dismissals.find_by_user_id(&Uuid::new_v4().to_string()).await.unwrap().is_empty());
+        assert!(dismissals.dismiss(&user_id, " ", None).await.is_err());
+    }
+
+    fn audit_record(sequence: i64, previous_hash: &str, hash: &str) -> SecurityAuditRecord {
+        SecurityAuditRecord {
+            sequence,
+            event_type: "IntegrityCheck".to_string(),
+            severity: "High".to_string(),
+            occurred_at: Utc::now(),
+            source: "App: Atlas".to_string(),
+            details: format!("event {}", sequence),
+            previous_hash: previous_hash.to_string(),
+            hash: hash.to_string(),
+        }
+    }
+
+    #[sqlx::test]
+    async fn test_security_audit_entries_come_back_in_chain_order(pool: PgPool) {
+        let db = test_db(pool);
+        let audit = SecurityAuditRepository::new(&db);
+        assert_eq!(audit.find_latest().await.unwrap(), None);
+
+        let first = audit_record(0, &"0".repeat(64), &"a".repeat(64));
+        let second = audit_record(1, &"a".repeat(64), &"b".repeat(64));
+        audit.append(&second).await.unwrap();
+        audit.append(&first).await.unwrap();
+
+        let stored = audit.find_all().await.unwrap();
+        assert_eq!(stored.iter().map(|e| e.sequence).collect::<Vec<_>>(), vec![0, 1]);
+        assert_eq!(stored[1].previous_hash, first.hash);
+        assert_eq!(audit.find_latest().await.unwrap().map(|e| e.hash), Some(second.hash.clone()));
+
+        // A sequence can only be written once
+        assert!(audit.append(&audit_record(1, &"a".repeat(64), &"c".repeat(64))).await.is_err());
+    }
 }
@@ -0,0 +1,164 @@
+// Transaction Tags for Atlas Desktop
+// Hierarchical tag rules and spending rollups by tag prefix
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// Separates the levels of a hierarchical tag, e.g. `travel/flights`
+pub const TAG_SEPARATOR: char = '/';
+
+/// Rules for the shape of transaction tags
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TagPolicy {
+    /// Most levels a tag may have; `1` keeps tags flat
+    pub max_depth: usize,
+}
+
+impl Default for TagPolicy {
+    fn default() -> Self {
+        Self { max_depth: 3 }
+    }
+}
+
+/// Why a tag was rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TagError {
+    InvalidFormat(String),
+    TooDeep { depth: usize, max_depth: usize },
+}
+
+impl TagError {
+    /// Machine-readable suffix for `tags.*` validation codes
+    pub fn code(&self) -> &'static str {
+        match self {
+            TagError::InvalidFormat(_) => "invalid_format",
+            TagError::TooDeep { .. } => "too_deep",
+        }
+    }
+}
+
+impl fmt::Display for TagError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TagError::InvalidFormat(message) => f.write_str(message),
+            TagError::TooDeep { depth, max_depth } => write!(f, "Tag has {} levels; at most {} are allowed", depth, max_depth),
+        }
+    }
+}
+
+impl TagPolicy {
+    /// Check `tag` is well formed and no deeper than the policy allows
+    ///
+    /// Each level must be non-empty, without leading or trailing spaces, and
+    /// use only letters, digits, spaces, `-`, `_`, `.` or `&`.
+    pub fn check(&self, tag: &str) -> Result<(), TagError> {
+        let mut depth = 0;
+        for segment in tag.split(TAG_SEPARATOR) {
+            depth += 1;
+            if segment.is_empty() {
+                return Err(TagError::InvalidFormat("Tag levels cannot be empty".to_string()));
+            }
+            if segment.trim() != segment {
+                return Err(TagError::InvalidFormat("Tag levels cannot start or end with whitespace".to_string()));
+            }
+            if let Some(c) = segment.chars().find(|&c| !is_tag_char(c)) {
+                return Err(TagError::InvalidFormat(format!("Tag contains unsupported character {:?}", c)));
+            }
+        }
+        if depth > self.max_depth {
+            return Err(TagError::TooDeep { depth, max_depth: self.max_depth });
+        }
+        Ok(())
+    }
+}
+
+fn is_tag_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, ' ' | '-' | '_' | '.' | '&')
+}
+
+/// `tag` and each of its parents, outermost first
+///
+/// `travel/flights/intl` yields `travel`, `travel/flights` and
+/// `travel/flights/intl`.
+pub fn tag_prefixes(tag: &str) -> impl Iterator<Item = &str> {
+    tag.match_indices(TAG_SEPARATOR)
+        .map(move |(index, _)| &tag[..index])
+        .chain(std::iter::once(tag))
+}
+
+/// Total spending under every tag and tag prefix
+///
+/// A transaction counts once towards each prefix, however many of its tags
+/// share it, so `travel` never double counts a `travel/flights` +
+/// `travel/hotels` booking.
+pub fn roll_up_by_prefix<'a, I>(transactions: I) -> BTreeMap<String, Decimal>
+where
+    I: IntoIterator<Item = (&'a [String], Decimal)>,
+{
+    let mut totals = BTreeMap::new();
+    for (tags, amount) in transactions {
+        let prefixes: HashSet<&str> = tags.iter().flat_map(|tag| tag_prefixes(tag)).collect();
+        for prefix in prefixes {
+            *totals.entry(prefix.to_string()).or_insert(Decimal::ZERO) += amount;
+        }
+    }
+    totals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_tag_format_is_validated() {
+        let policy = TagPolicy::default();
+        for tag in ["groceries", "travel/flights", "Food & Drink/coffee", "work_trip/2024-q1"] {
+            assert_eq!(policy.check(tag), Ok(()), "{}", tag);
+        }
+        for tag in ["", "travel/", "/travel", "travel//flights", " travel", "travel/ flights", "travel\\flights", "a;b"] {
+            let error = policy.check(tag).unwrap_err();
+            assert_eq!(error.code(), "invalid_format", "{}", tag);
+        }
+    }
+
+    #[test]
+    fn test_tags_deeper_than_the_policy_are_rejected() {
+        let policy = TagPolicy { max_depth: 2 };
+        assert_eq!(policy.check("travel/flights"), Ok(()));
+        assert_eq!(
+            policy.check("travel/flights/intl"),
+            Err(TagError::TooDeep { depth: 3, max_depth: 2 })
+        );
+
+        let flat = TagPolicy { max_depth: 1 };
+        assert_eq!(flat.check("travel"), Ok(()));
+        assert_eq!(flat.check("travel/flights").unwrap_err().code(), "too_deep");
+    }
+
+    #[test]
+    fn test_spending_rolls_up_by_parent_tag() {
+        let flight = vec!["travel/flights".to_string()];
+        let trip = vec!["travel/hotels".to_string(), "travel/flights/intl".to_string()];
+        let lunch = vec!["food".to_string()];
+        let untagged: Vec<String> = vec![];
+        let totals = roll_up_by_prefix([
+            (flight.as_slice(), dec!(300)),
+            (trip.as_slice(), dec!(1200)),
+            (lunch.as_slice(), dec!(15)),
+            (untagged.as_slice(), dec!(40)),
+        ]);
+
+        assert_eq!(totals["travel"], dec!(1500));
+        assert_eq!(totals["travel/flights"], dec!(1500));
+        assert_eq!(totals["travel/flights/intl"], dec!(1200));
+        assert_eq!(totals["travel/hotels"], dec!(1200));
+        assert_eq!(totals["food"], dec!(15));
+        assert_eq!(totals.len(), 5);
+        assert_eq!(tag_prefixes("a/b/c").collect::<Vec<_>>(), vec!["a", "a/b", "a/b/c"]);
+    }
+}
@@ -0,0 +1,286 @@
+// Tax Estimates for Atlas Desktop
+// Rough income tax liability from caller-supplied brackets and deductions
+
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Serialize};
+
+use crate::storage::TransactionType;
+
+/// Transaction types counted as income when money arrives
+pub const INCOME_TRANSACTION_TYPES: [TransactionType; 4] = [
+    TransactionType::Credit,
+    TransactionType::Deposit,
+    TransactionType::Interest,
+    TransactionType::Dividend,
+];
+
+/// One band of a progressive schedule
+///
+/// Taxable income above `threshold`, up to the next bracket's threshold, is
+/// taxed at `rate` percent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaxBracket {
+    pub threshold: Decimal,
+    pub rate: Decimal,
+}
+
+/// Deductions taken from gross income before brackets apply
+///
+/// The larger of the standard deduction and the itemized total is used.
+/// Itemized deductions are `itemized` plus spending in `deductible_categories`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaxDeductions {
+    pub standard: Decimal,
+    #[serde(default)]
+    pub itemized: Decimal,
+    #[serde(default)]
+    pub deductible_categories: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DeductionKind {
+    Standard,
+    Itemized,
+}
+
+/// Tax owed within one bracket
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BracketTax {
+    pub threshold: Decimal,
+    pub rate: Decimal,
+    pub taxed_income: Decimal,
+    pub tax: Decimal,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaxEstimate {
+    pub gross_income: Decimal,
+    pub deduction: Decimal,
+    pub deduction_kind: DeductionKind,
+    pub taxable_income: Decimal,
+    /// Estimated tax owed, rounded to cents
+    pub liability: Decimal,
+    /// Liability as a percentage of gross income
+    pub effective_rate: Decimal,
+    /// Rate applied to the last dollar of taxable income
+    pub marginal_rate: Decimal,
+    pub brackets: Vec<BracketTax>,
+}
+
+/// Income and deductible spending for a tax year
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TaxYearActivity {
+    pub gross_income: Decimal,
+    pub deductible_spending: Decimal,
+}
+
+impl TaxYearActivity {
+    /// Sum signed transaction totals grouped by type and category
+    ///
+    /// Inflows of an [income type](INCOME_TRANSACTION_TYPES) are income;
+    /// outflows in one of `deductible_categories` are deductible spending.
+    pub fn from_totals<'a, I>(totals: I, deductible_categories: &[String]) -> Self
+    where
+        I: IntoIterator<Item = (TransactionType, Option<&'a str>, Decimal)>,
+    {
+        let mut activity = Self::default();
+        for (transaction_type, category, total) in totals {
+            if total.is_sign_positive() && INCOME_TRANSACTION_TYPES.contains(&transaction_type) {
+                activity.gross_income += total;
+            } else if total.is_sign_negative()
+                && category.is_some_and(|category| deductible_categories.iter().any(|c| c == category))
+            {
+                activity.deductible_spending -= total;
+            }
+        }
+        activity
+    }
+}
+
+/// Check brackets start at zero, rise strictly, and have rates within 0-100%
+pub fn validate_brackets(brackets: &[TaxBracket]) -> Result<(), String> {
+    let first = brackets.first().ok_or("At least one tax bracket is required")?;
+    if !first.threshold.is_zero() {
+        return Err("The first tax bracket must start at 0".to_string());
+    }
+    for pair in brackets.windows(2) {
+        if pair[1].threshold <= pair[0].threshold {
+            return Err(format!(
+                "Tax bracket thresholds must increase; {} follows {}",
+                pair[1].threshold, pair[0].threshold
+            ));
+        }
+    }
+    if let Some(bracket) = brackets.iter().find(|b| b.rate < Decimal::ZERO || b.rate > Decimal::ONE_HUNDRED) {
+        return Err(format!("Tax rate {} must be between 0 and 100 percent", bracket.rate));
+    }
+    Ok(())
+}
+
+/// Estimate tax on a year's activity under `brackets` and `deductions`
+///
+/// Bracket amounts are exact; only the total liability is rounded to cents.
+pub fn estimate_tax(
+    activity: TaxYearActivity,
+    brackets: &[TaxBracket],
+    deductions: &TaxDeductions,
+) -> Result<TaxEstimate, String> {
+    validate_brackets(brackets)?;
+    if deductions.standard.is_sign_negative() || deductions.itemized.is_sign_negative() {
+        return Err("Deductions cannot be negative".to_string());
+    }
+
+    let itemized = deductions.itemized + activity.deductible_spending;
+    let (deduction, deduction_kind) = if itemized > deductions.standard {
+        (itemized, DeductionKind::Itemized)
+    } else {
+        (deductions.standard, DeductionKind::Standard)
+    };
+    let taxable_income = (activity.gross_income - deduction).max(Decimal::ZERO);
+
+    let mut marginal_rate = brackets[0].rate;
+    let mut bracket_taxes = Vec::new();
+    for (index, bracket) in brackets.iter().enumerate() {
+        if taxable_income <= bracket.threshold {
+            break;
+        }
+        let ceiling = brackets
+            .get(index + 1)
+            .map_or(taxable_income, |next| next.threshold.min(taxable_income));
+        let taxed_income = ceiling - bracket.threshold;
+        marginal_rate = bracket.rate;
+        bracket_taxes.push(BracketTax {
+            threshold: bracket.threshold,
+            rate: bracket.rate,
+            taxed_income,
+            tax: taxed_income * bracket.rate / Decimal::ONE_HUNDRED,
+        });
+    }
+
+    let liability = bracket_taxes
+        .iter()
+        .map(|b| b.tax)
+        .sum::<Decimal>()
+        .round_dp_with_strategy(2, RoundingStrategy::MidpointAwayFromZero);
+    let effective_rate = if activity.gross_income.is_zero() {
+        Decimal::ZERO
+    } else {
+        (liability / activity.gross_income * Decimal::ONE_HUNDRED).round_dp(2)
+    };
+
+    Ok(TaxEstimate {
+        gross_income: activity.gross_income,
+        deduction,
+        deduction_kind,
+        taxable_income,
+        liability,
+        effective_rate,
+        marginal_rate,
+        brackets: bracket_taxes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn brackets() -> Vec<TaxBracket> {
+        vec![
+            TaxBracket { threshold: dec!(0), rate: dec!(10) },
+            TaxBracket { threshold: dec!(10000), rate: dec!(20) },
+            TaxBracket { threshold: dec!(40000), rate: dec!(30) },
+        ]
+    }
+
+    fn standard(amount: Decimal) -> TaxDeductions {
+        TaxDeductions {
+            standard: amount,
+            itemized: Decimal::ZERO,
+            deductible_categories: vec![],
+        }
+    }
+
+    fn income(gross_income: Decimal) -> TaxYearActivity {
+        TaxYearActivity { gross_income, deductible_spending: Decimal::ZERO }
+    }
+
+    #[test]
+    fn test_marginal_and_effective_rates_across_brackets() {
+        let estimate = estimate_tax(income(dec!(60000)), &brackets(), &standard(dec!(5000))).unwrap();
+
+        // 10% of 10,000 + 20% of 30,000 + 30% of 15,000
+        assert_eq!(estimate.taxable_income, dec!(55000));
+        assert_eq!(estimate.liability, dec!(11500.00));
+        assert_eq!(estimate.marginal_rate, dec!(30));
+        assert_eq!(estimate.effective_rate, dec!(19.17));
+        let taxed: Vec<Decimal> = estimate.brackets.iter().map(|b| b.taxed_income).collect();
+        assert_eq!(taxed, vec![dec!(10000), dec!(30000), dec!(15000)]);
+
+        // Income ending exactly on a threshold stays in the lower bracket
+        let estimate = estimate_tax(income(dec!(45000)), &brackets(), &standard(dec!(5000))).unwrap();
+        assert_eq!(estimate.liability, dec!(7000.00));
+        assert_eq!(estimate.marginal_rate, dec!(20));
+        assert_eq!(estimate.effective_rate, dec!(15.56));
+
+        // Deductions larger than income leave nothing to tax
+        let estimate = estimate_tax(income(dec!(3000)), &brackets(), &standard(dec!(5000))).unwrap();
+        assert_eq!(estimate.taxable_income, Decimal::ZERO);
+        assert_eq!(estimate.liability, Decimal::ZERO);
+        assert_eq!(estimate.marginal_rate, dec!(10));
+        assert!(estimate.brackets.is_empty());
+    }
+
+    #[test]
+    fn test_larger_of_standard_and_itemized_deductions_applies() {
+        let totals = [
+            (TransactionType::Deposit, Some("Salary"), dec!(52000)),
+            (TransactionType::Dividend, None, dec!(1000.50)),
+            (TransactionType::Transfer, None, dec!(9000)),
+            (TransactionType::Debit, Some("Charity"), dec!(-4000)),
+            (TransactionType::Debit, Some("Groceries"), dec!(-6000)),
+        ];
+        let deductions = TaxDeductions {
+            standard: dec!(5000),
+            itemized: dec!(2000),
+            deductible_categories: vec!["Charity".to_string()],
+        };
+        let activity = TaxYearActivity::from_totals(totals, &deductions.deductible_categories);
+        assert_eq!(activity.gross_income, dec!(53000.50));
+        assert_eq!(activity.deductible_spending, dec!(4000));
+
+        let estimate = estimate_tax(activity, &brackets(), &deductions).unwrap();
+        assert_eq!(estimate.deduction_kind, DeductionKind::Itemized);
+        assert_eq!(estimate.deduction, dec!(6000));
+        // 1,000 + 6,000 + 30% of 7,000.50 = 9,100.15
+        assert_eq!(estimate.liability, dec!(9100.15));
+
+        let estimate = estimate_tax(activity, &brackets(), &TaxDeductions { standard: dec!(8000), ..deductions }).unwrap();
+        assert_eq!(estimate.deduction_kind, DeductionKind::Standard);
+        assert_eq!(estimate.taxable_income, dec!(45000.50));
+    }
+
+    #[test]
+    fn test_invalid_bracket_schedules_are_rejected() {
+        assert!(validate_brackets(&[]).is_err());
+        assert_eq!(
+            validate_brackets(&brackets()[1..]),
+            Err("The first tax bracket must start at 0".to_string())
+        );
+        let mut unordered = brackets();
+        unordered.swap(1, 2);
+        assert!(validate_brackets(&unordered).is_err());
+        let mut too_high = brackets();
+        too_high[2].rate = dec!(130);
+        assert_eq!(
+            validate_brackets(&too_high),
+            Err("Tax rate 130 must be between 0 and 100 percent".to_string())
+        );
+        assert!(estimate_tax(income(dec!(1000)), &brackets(), &standard(dec!(-1))).is_err());
+    }
+}
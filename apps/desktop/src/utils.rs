@@ -5,7 +5,17 @@ use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
-use crate::financial::FinancialError;
+use crate::anomaly::{AnomalyRule, AnomalySettings};
+use crate::financial::{display_amount, FinancialError, NumberFormat};
+use crate::import::ImportSettings;
+use crate::notifications::{NotificationChannel, NotificationThresholds};
+use crate::privacy::AmountMasking;
+use crate::retention::DataRetentionSettings;
+use crate::security::log_redaction::LogRedactionPolicy;
+use crate::security::path_policy::PathAccessPolicy;
+use crate::security::secure_query::TransactionAmountLimits;
+use crate::security::startup_gate::StartupSelfTestPolicy;
+use crate::tags::TagPolicy;
 
 // ============================================================================
 // Configuration Management
@@ -24,6 +34,16 @@ pub struct Config {
     pub notification_settings: NotificationSettings,
     pub performance_settings: PerformanceSettings,
     pub export_settings: ExportSettings,
+    #[serde(default)]
+    pub import_settings: ImportSettings,
+    #[serde(default)]
+    pub data_retention: DataRetentionSettings,
+    /// Largest transaction amounts accepted per account type
+    #[serde(default)]
+    pub transaction_limits: TransactionAmountLimits,
+    /// Format and depth rules for hierarchical transaction tags
+    #[serde(default)]
+    pub tag_policy: TagPolicy,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -44,6 +64,15 @@ pub struct SecuritySettings {
     pub require_authentication_on_startup: bool,
     pub encryption_enabled: bool,
     pub audit_logging_enabled: bool,
+    /// Masking of descriptions, names, and amounts in log output
+    #[serde(default)]
+    pub log_redaction: LogRedactionPolicy,
+    /// Locations file-system commands may use
+    #[serde(default)]
+    pub path_access: PathAccessPolicy,
+    /// Security self-tests run at launch and what happens when one fails
+    #[serde(default)]
+    pub startup_self_test: StartupSelfTestPolicy,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -52,18 +81,29 @@ pub struct UiSettings {
     pub theme: Theme,
     pub language: String,
     pub currency_display_format: CurrencyDisplayFormat,
+    /// Separators amounts are typed with
+    #[serde(default)]
+    pub number_format: NumberFormat,
     pub date_format: String,
     pub time_format: String,
     pub decimal_places: u8,
     pub compact_mode: bool,
     pub animations_enabled: bool,
+    /// How amounts appear in lists and overviews while privacy mode is on
+    #[serde(default)]
+    pub privacy_masking: AmountMasking,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", from = "StoredNotificationSettings")]
 pub struct NotificationSettings {
     pub enabled: bool,
-    pub large_transaction_threshold: Decimal,
+    /// Rules deciding which new transactions are flagged and notified
+    pub anomaly_detection: AnomalySettings,
+    /// Where notifications are delivered
+    pub channel: NotificationChannel,
+    /// When categorization and insight results are worth a notification
+    pub thresholds: NotificationThresholds,
     pub budget_alert_threshold: Decimal,
     pub security_alerts: bool,
     pub system_alerts: bool,
@@ -71,6 +111,54 @@ pub struct NotificationSettings {
     pub sound_enabled: bool,
 }
 
+/// Notification settings as saved, including fields earlier versions wrote
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StoredNotificationSettings {
+    enabled: bool,
+    anomaly_detection: Option<AnomalySettings>,
+    /// Fixed large-transaction alert, replaced by `anomaly_detection`
+    large_transaction_threshold: Option<Decimal>,
+    #[serde(default)]
+    channel: NotificationChannel,
+    #[serde(default)]
+    thresholds: NotificationThresholds,
+    budget_alert_threshold: Decimal,
+    security_alerts: bool,
+    system_alerts: bool,
+    marketing_notifications: bool,
+    sound_enabled: bool,
+}
+
+impl From<StoredNotificationSettings> for NotificationSettings {
+    fn from(stored: StoredNotificationSettings) -> Self {
+        // Settings saved before anomaly rules existed keep the user's alert threshold
+        let anomaly_detection = stored.anomaly_detection.unwrap_or_else(|| {
+            let mut anomaly_detection = AnomalySettings::default();
+            if let Some(legacy) = stored.large_transaction_threshold {
+                for rule in &mut anomaly_detection.rules {
+                    if let AnomalyRule::LargeAmount { threshold } = rule {
+                        *threshold = legacy;
+                    }
+                }
+            }
+            anomaly_detection
+        });
+
+        Self {
+            enabled: stored.enabled,
+            anomaly_detection,
+            channel: stored.channel,
+            thresholds: stored.thresholds,
+            budget_alert_threshold: stored.budget_alert_threshold,
+            security_alerts: stored.security_alerts,
+            system_alerts: stored.system_alerts,
+            marketing_notifications: stored.marketing_notifications,
+            sound_enabled: stored.sound_enabled,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PerformanceSettings {
@@ -145,6 +233,10 @@ impl Default for Config {
             notification_settings: NotificationSettings::default(),
             performance_settings: PerformanceSettings::default(),
             export_settings: ExportSettings::default(),
+            import_settings: ImportSettings::default(),
+            data_retention: DataRetentionSettings::default(),
+            transaction_limits: TransactionAmountLimits::default(),
+            tag_policy: TagPolicy::default(),
         }
     }
 }
@@ -169,6 +261,9 @@ impl Default for SecuritySettings {
             require_authentication_on_startup: true,
             encryption_enabled: true,
             audit_logging_enabled: true,
+            log_redaction: LogRedactionPolicy::default(),
+            path_access: PathAccessPolicy::default(),
+            startup_self_test: StartupSelfTestPolicy::default(),
         }
     }
 }
@@ -179,11 +274,13 @@ impl Default for UiSettings {
             theme: Theme::Auto,
             language: "en".to_string(),
             currency_display_format: CurrencyDisplayFormat::Symbol,
+            number_format: NumberFormat::default(),
             date_format: "MM/dd/yyyy".to_string(),
             time_format: "h:mm a".to_string(),
             decimal_places: 2,
             compact_mode: false,
             animations_enabled: true,
+            privacy_masking: AmountMasking::default(),
         }
     }
 }
@@ -194,7 +291,9 @@ impl Default for NotificationSettings {
 
         Self {
             enabled: true,
-            large_transaction_threshold: dec!(1000.00),
+            anomaly_detection: AnomalySettings::default(),
+            channel: NotificationChannel::default(),
+            thresholds: NotificationThresholds::default(),
             budget_alert_threshold: dec!(0.80), // 80%
             security_alerts: true,
             system_alerts: true,
@@ -312,7 +411,7 @@ pub fn format_currency_with_settings(
     currency: &str,
     settings: &UiSettings,
 ) -> String {
-    let formatted_amount = format!("{:.1$}", amount, settings.decimal_places as usize);
+    let formatted_amount = display_amount(amount, settings.decimal_places.into());
 
     match settings.currency_display_format {
         CurrencyDisplayFormat::Symbol => {
@@ -320,7 +419,7 @@ pub fn format_currency_with_settings(
                 "USD" => format!("${}", formatted_amount),
                 "EUR" => format!("€{}", formatted_amount),
                 "GBP" => format!("£{}", formatted_amount),
-                "JPY" => format!("¥{}", formatted_amount.split('.').next().unwrap_or(&formatted_amount)),
+                "JPY" => format!("¥{}", display_amount(amount, 0)),
                 _ => format!("{} {}", currency, formatted_amount),
             }
         }
@@ -613,6 +712,32 @@ mod tests {
         assert_eq!(format_currency_with_settings(dec!(1234.56), "EUR", &settings), "€1234.56");
     }
 
+    #[test]
+    fn test_legacy_large_transaction_threshold_becomes_an_anomaly_rule() {
+        let mut saved = serde_json::to_value(NotificationSettings::default()).unwrap();
+        let fields = saved.as_object_mut().unwrap();
+        fields.remove("anomalyDetection");
+        fields.insert("largeTransactionThreshold".to_string(), serde_json::json!("250.00"));
+
+        let settings: NotificationSettings = serde_json::from_value(saved.clone()).unwrap();
+        assert_eq!(
+            settings.anomaly_detection.rules[0],
+            AnomalyRule::LargeAmount { threshold: dec!(250.00) }
+        );
+        assert_eq!(settings.anomaly_detection.rules[1..], AnomalySettings::default().rules[1..]);
+
+        // Rules saved since then take precedence over the legacy field
+        let current = serde_json::to_value(&settings.anomaly_detection).unwrap();
+        let mut migrated = saved;
+        migrated["anomalyDetection"] = current;
+        migrated["largeTransactionThreshold"] = serde_json::json!("5000");
+        let settings: NotificationSettings = serde_json::from_value(migrated).unwrap();
+        assert_eq!(
+            settings.anomaly_detection.rules[0],
+            AnomalyRule::LargeAmount { threshold: dec!(250.00) }
+        );
+    }
+
     #[test]
     fn test_sanitize_filename() {
         assert_eq!(sanitize_filename("test<file>name"), "test_file_name");
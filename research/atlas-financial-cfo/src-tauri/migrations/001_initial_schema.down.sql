@@ -0,0 +1,8 @@
+-- Revert the initial schema, dependents first
+
+DROP TABLE IF EXISTS user_sessions;
+DROP TABLE IF EXISTS event_snapshots;
+DROP TABLE IF EXISTS domain_events;
+DROP TABLE IF EXISTS transactions;
+DROP TABLE IF EXISTS accounts;
+DROP TABLE IF EXISTS users;
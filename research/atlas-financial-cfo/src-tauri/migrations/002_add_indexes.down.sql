@@ -0,0 +1,27 @@
+-- Revert the performance indexes
+
+DROP INDEX IF EXISTS idx_users_username;
+DROP INDEX IF EXISTS idx_users_email;
+DROP INDEX IF EXISTS idx_users_is_active;
+DROP INDEX IF EXISTS idx_accounts_user_id;
+DROP INDEX IF EXISTS idx_accounts_type;
+DROP INDEX IF EXISTS idx_accounts_is_active;
+DROP INDEX IF EXISTS idx_accounts_user_active;
+DROP INDEX IF EXISTS idx_transactions_user_id;
+DROP INDEX IF EXISTS idx_transactions_account_id;
+DROP INDEX IF EXISTS idx_transactions_date;
+DROP INDEX IF EXISTS idx_transactions_type;
+DROP INDEX IF EXISTS idx_transactions_category;
+DROP INDEX IF EXISTS idx_transactions_reconciled;
+DROP INDEX IF EXISTS idx_transactions_user_date;
+DROP INDEX IF EXISTS idx_transactions_account_date;
+DROP INDEX IF EXISTS idx_domain_events_aggregate;
+DROP INDEX IF EXISTS idx_domain_events_type;
+DROP INDEX IF EXISTS idx_domain_events_created;
+DROP INDEX IF EXISTS idx_domain_events_user;
+DROP INDEX IF EXISTS idx_snapshots_aggregate;
+DROP INDEX IF EXISTS idx_snapshots_version;
+DROP INDEX IF EXISTS idx_sessions_user_id;
+DROP INDEX IF EXISTS idx_sessions_token;
+DROP INDEX IF EXISTS idx_sessions_expires;
+DROP INDEX IF EXISTS idx_sessions_active;
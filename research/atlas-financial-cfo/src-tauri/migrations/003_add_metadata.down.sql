@@ -0,0 +1,7 @@
+-- Revert metadata support
+
+DROP TABLE IF EXISTS audit_log;
+DROP TABLE IF EXISTS user_preferences;
+
+ALTER TABLE transactions DROP COLUMN metadata;
+ALTER TABLE accounts DROP COLUMN metadata;
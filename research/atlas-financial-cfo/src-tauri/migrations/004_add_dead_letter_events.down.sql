@@ -0,0 +1,3 @@
+-- Revert dead-letter storage
+
+DROP TABLE IF EXISTS dead_letter_events;
@@ -0,0 +1,16 @@
+-- Dead-letter storage for events whose handlers kept failing
+
+CREATE TABLE dead_letter_events (
+    id TEXT PRIMARY KEY,
+    aggregate_id TEXT NOT NULL,
+    aggregate_type TEXT NOT NULL,
+    event_type TEXT NOT NULL,
+    event_data TEXT NOT NULL, -- JSON serialized event data
+    handler_name TEXT NOT NULL,
+    error_message TEXT NOT NULL,
+    attempts INTEGER NOT NULL,
+    failed_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_dead_letter_events_event_type ON dead_letter_events(event_type);
+CREATE INDEX idx_dead_letter_events_failed_at ON dead_letter_events(failed_at);
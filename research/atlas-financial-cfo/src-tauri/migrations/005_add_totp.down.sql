@@ -0,0 +1,3 @@
+-- Revert TOTP two-factor enrollment
+
+DROP TABLE IF EXISTS user_totp;
@@ -0,0 +1,10 @@
+-- TOTP two-factor enrollment per user
+
+CREATE TABLE user_totp (
+    user_id TEXT PRIMARY KEY,
+    secret TEXT NOT NULL, -- Base32 encoded shared secret
+    enabled BOOLEAN NOT NULL DEFAULT 0, -- Set once the first code is verified
+    last_used_step INTEGER, -- Time step of the last accepted code, for replay protection
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
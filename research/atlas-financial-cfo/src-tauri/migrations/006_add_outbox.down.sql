@@ -0,0 +1,3 @@
+-- Revert the transactional outbox
+
+DROP TABLE IF EXISTS outbox;
@@ -0,0 +1,16 @@
+-- Transactional outbox: events written with the state change they describe,
+-- published by the relay once the change has committed
+
+CREATE TABLE outbox (
+    id TEXT PRIMARY KEY,
+    aggregate_id TEXT NOT NULL,
+    aggregate_type TEXT NOT NULL,
+    event_type TEXT NOT NULL,
+    event_data TEXT NOT NULL, -- JSON serialized event data
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    sent_at DATETIME -- NULL until the relay has published the event
+);
+
+CREATE INDEX idx_outbox_pending ON outbox(sent_at, created_at);
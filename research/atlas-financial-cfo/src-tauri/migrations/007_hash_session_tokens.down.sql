@@ -0,0 +1,4 @@
+-- Revert to raw session tokens; hashed sessions cannot be looked up by
+-- older builds, so they are dropped
+
+DELETE FROM user_sessions;
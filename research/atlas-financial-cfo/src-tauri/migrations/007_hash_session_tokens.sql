@@ -0,0 +1,5 @@
+-- Session tokens are now stored as SHA-256 hashes. Sessions saved with
+-- their raw token can no longer be looked up, so they are dropped and their
+-- users log in again.
+
+DELETE FROM user_sessions;
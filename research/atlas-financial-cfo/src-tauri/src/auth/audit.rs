@@ -0,0 +1,77 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use serde_json::Value;
+use sqlx::{Pool, Sqlite};
+use tracing::info;
+
+use crate::domain::EntityId;
+use crate::error::AppResult;
+
+/// A security-relevant action, as recorded in the `audit_log` table
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditEntry {
+    pub user_id: Option<EntityId>,
+    pub action: String,
+    pub resource_type: String,
+    pub resource_id: Option<String>,
+    pub details: Value,
+}
+
+/// Destination for audit entries
+#[async_trait]
+pub trait AuditLog: Send + Sync {
+    async fn record(&self, entry: &AuditEntry) -> AppResult<()>;
+}
+
+/// Default that writes entries to the application log only
+#[derive(Debug, Clone, Default)]
+pub struct TracingAuditLog;
+
+#[async_trait]
+impl AuditLog for TracingAuditLog {
+    async fn record(&self, entry: &AuditEntry) -> AppResult<()> {
+        info!(
+            action = %entry.action,
+            resource_type = %entry.resource_type,
+            user_id = ?entry.user_id.map(|id| id.to_string()),
+            details = %entry.details,
+            "Audit"
+        );
+        Ok(())
+    }
+}
+
+/// Keeps entries in the `audit_log` table
+#[derive(Clone)]
+pub struct SqliteAuditLog {
+    pool: Pool<Sqlite>,
+}
+
+impl SqliteAuditLog {
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl AuditLog for SqliteAuditLog {
+    async fn record(&self, entry: &AuditEntry) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO audit_log (id, user_id, action, resource_type, resource_id, details, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(EntityId::new().to_string())
+        .bind(entry.user_id.map(|id| id.to_string()))
+        .bind(&entry.action)
+        .bind(&entry.resource_type)
+        .bind(&entry.resource_id)
+        .bind(entry.details.to_string())
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -1,33 +1,91 @@
+pub mod audit;
+pub mod password_policy;
 pub mod service;
 pub mod session;
+pub mod session_limit;
+pub mod session_store;
+pub mod totp;
 
 use bcrypt::{hash, DEFAULT_COST};
 use secrecy::{ExposeSecret, Secret};
-use std::collections::HashMap;
+use chrono::Utc;
 use std::sync::Arc;
-use tokio::sync::RwLock;
 use uuid::Uuid;
 
 use crate::domain::{User, UserSession, LoginCredentials, EntityId, Timestamp};
+use crate::database::{TotpRepository, UserRepository};
 use crate::error::{AppError, AppResult};
 
+pub use audit::*;
+pub use password_policy::*;
 pub use service::*;
 pub use session::*;
+pub use session_limit::*;
+pub use session_store::*;
+pub use totp::*;
 
 #[derive(Clone)]
 pub struct AuthService {
-    sessions: Arc<RwLock<HashMap<String, UserSession>>>,
-    user_repository: Arc<crate::database::UserRepository>,
+    sessions: Arc<dyn SessionStore>,
+    user_repository: Arc<UserRepository>,
+    totp_repository: Arc<TotpRepository>,
+    totp_config: TotpConfig,
+    password_policy: PasswordPolicy,
+    breach_provider: Arc<dyn BreachProvider>,
+    session_limit: SessionLimit,
+    audit_log: Arc<dyn AuditLog>,
 }
 
 impl AuthService {
-    pub fn new(user_repository: Arc<crate::database::UserRepository>) -> Self {
+    pub fn new(user_repository: Arc<UserRepository>, totp_repository: Arc<TotpRepository>) -> Self {
         Self {
-            sessions: Arc::new(RwLock::new(HashMap::new())),
+            sessions: Arc::new(InMemorySessionStore::new()),
             user_repository,
+            totp_repository,
+            totp_config: TotpConfig::default(),
+            password_policy: PasswordPolicy::default(),
+            breach_provider: Arc::new(NoopBreachProvider),
+            session_limit: SessionLimit::default(),
+            audit_log: Arc::new(TracingAuditLog),
         }
     }
 
+    /// Replace the TOTP issuer, code length, step or drift window
+    pub fn with_totp_config(mut self, config: TotpConfig) -> Self {
+        self.totp_config = config;
+        self
+    }
+
+    /// Replace the password requirements enforced at registration
+    pub fn with_password_policy(mut self, policy: PasswordPolicy) -> Self {
+        self.password_policy = policy;
+        self
+    }
+
+    /// Check new passwords against a breach corpus
+    pub fn with_breach_provider(mut self, provider: Arc<dyn BreachProvider>) -> Self {
+        self.breach_provider = provider;
+        self
+    }
+
+    /// Keep sessions in the given store instead of process memory
+    pub fn with_session_store(mut self, store: Arc<dyn SessionStore>) -> Self {
+        self.sessions = store;
+        self
+    }
+
+    /// Cap how many sessions each user may hold at once
+    pub fn with_session_limit(mut self, limit: SessionLimit) -> Self {
+        self.session_limit = limit;
+        self
+    }
+
+    /// Record security events such as session evictions in the given log
+    pub fn with_audit_log(mut self, audit_log: Arc<dyn AuditLog>) -> Self {
+        self.audit_log = audit_log;
+        self
+    }
+
     pub async fn register_user(
         &self,
         username: String,
@@ -55,7 +113,7 @@ impl AuthService {
     pub async fn authenticate(
         &self,
         credentials: LoginCredentials,
-    ) -> AppResult<UserSession> {
+    ) -> AppResult<(String, UserSession)> {
         // Find user by username
         let user = self
             .user_repository
@@ -72,6 +130,19 @@ impl AuthService {
             });
         }
 
+        // Second factor, once the user has enabled TOTP
+        let totp_enabled = self
+            .totp_repository
+            .find_by_user(user.id)
+            .await?
+            .is_some_and(|enrollment| enrollment.enabled);
+        if totp_enabled {
+            let code = credentials.totp_code.as_deref().ok_or_else(|| AppError::Authentication {
+                message: "Two-factor code required".to_string(),
+            })?;
+            self.verify_totp(user.id, code).await?;
+        }
+
         // Update last login
         let mut updated_user = user.clone();
         updated_user.update_last_login();
@@ -81,28 +152,26 @@ impl AuthService {
         let session = UserSession::new(&user, 24); // 24 hour session
         let session_token = Uuid::new_v4().to_string();
 
-        // Store session
-        {
-            let mut sessions = self.sessions.write().await;
-            sessions.insert(session_token.clone(), session.clone());
-        }
+        // Store session, once the user is within their session limit
+        let evicted = self
+            .sessions
+            .insert_within_limit(&session_token_hash(&session_token), &session, self.session_limit)
+            .await?;
+        self.audit_evictions(&evicted).await?;
 
-        Ok(session)
+        Ok((session_token, session))
     }
 
     pub async fn verify_session(&self, session_token: &str) -> AppResult<UserSession> {
-        let sessions = self.sessions.read().await;
-
-        match sessions.get(session_token) {
+        match self.sessions.get(&session_token_hash(session_token)).await? {
             Some(session) => {
                 if session.is_expired() {
-                    drop(sessions);
                     self.logout(session_token).await?;
                     Err(AppError::Authentication {
                         message: "Session expired".to_string(),
                     })
                 } else {
-                    Ok(session.clone())
+                    Ok(session)
                 }
             }
             None => Err(AppError::Authentication {
@@ -112,8 +181,94 @@ impl AuthService {
     }
 
     pub async fn logout(&self, session_token: &str) -> AppResult<()> {
-        let mut sessions = self.sessions.write().await;
-        sessions.remove(session_token);
+        self.sessions.remove(&session_token_hash(session_token)).await
+    }
+
+    /// Audit sessions logged out to keep their user under the session limit
+    ///
+    /// The store counts, evicts and inserts in one step so concurrent logins
+    /// cannot exceed the cap, which means evictions are recorded just after
+    /// they happen rather than before.
+    async fn audit_evictions(&self, evicted: &[UserSession]) -> AppResult<()> {
+        let Some(max_sessions) = self.session_limit.max_sessions_per_user else {
+            return Ok(());
+        };
+        for session in evicted {
+            self.audit_log
+                .record(&AuditEntry {
+                    user_id: Some(session.user_id),
+                    action: "session_evicted".to_string(),
+                    resource_type: "user_session".to_string(),
+                    // Tokens are credentials, so the creation time identifies the session
+                    resource_id: None,
+                    details: serde_json::json!({
+                        "reason": "session_limit",
+                        "max_sessions_per_user": max_sessions.get(),
+                        "session_created_at": session.created_at.as_datetime(),
+                    }),
+                })
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Start TOTP enrollment with a fresh secret
+    ///
+    /// TOTP is only enforced at login once a code generated from the secret
+    /// has been confirmed with `verify_totp`.
+    pub async fn enroll_totp(&self, user_id: EntityId) -> AppResult<TotpSecret> {
+        let user = self
+            .user_repository
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound {
+                resource: "User".to_string(),
+            })?;
+
+        if let Some(enrollment) = self.totp_repository.find_by_user(user_id).await? {
+            if enrollment.enabled {
+                return Err(AppError::Validation {
+                    message: "Two-factor authentication is already enabled".to_string(),
+                });
+            }
+        }
+
+        let secret = TotpConfig::generate_secret();
+        self.totp_repository.save_pending(user_id, &secret).await?;
+
+        Ok(TotpSecret {
+            provisioning_uri: self.totp_config.provisioning_uri(&secret, &user.username),
+            secret,
+        })
+    }
+
+    /// Check a TOTP code, confirming a pending enrollment on success
+    ///
+    /// Each accepted code's time step is recorded, so a code cannot be
+    /// used twice even while it is still inside the drift window.
+    pub async fn verify_totp(&self, user_id: EntityId, code: &str) -> AppResult<()> {
+        let invalid = || AppError::Authentication {
+            message: "Invalid two-factor code".to_string(),
+        };
+
+        let enrollment = self
+            .totp_repository
+            .find_by_user(user_id)
+            .await?
+            .ok_or_else(|| AppError::Validation {
+                message: "Two-factor authentication is not enrolled".to_string(),
+            })?;
+
+        let now = Utc::now().timestamp().max(0) as u64;
+        let step = self
+            .totp_config
+            .matching_step(&enrollment.secret, code, now, enrollment.last_used_step)
+            .ok_or_else(invalid)?;
+
+        if !self.totp_repository.mark_step_used(user_id, step).await? {
+            return Err(invalid());
+        }
+
         Ok(())
     }
 
@@ -154,11 +309,8 @@ impl AuthService {
         }
 
         // Password validation
-        if password.len() < 8 {
-            return Err(AppError::Validation {
-                message: "Password must be at least 8 characters long".to_string(),
-            });
-        }
+        self.password_policy.validate(password)?;
+        ensure_not_breached(self.breach_provider.as_ref(), password).await?;
 
         // Check for existing username/email
         if self.user_repository.exists_by_username(username).await? {
@@ -177,8 +329,7 @@ impl AuthService {
     }
 
     /// Clean up expired sessions
-    pub async fn cleanup_expired_sessions(&self) {
-        let mut sessions = self.sessions.write().await;
-        sessions.retain(|_, session| !session.is_expired());
+    pub async fn cleanup_expired_sessions(&self) -> AppResult<usize> {
+        self.sessions.remove_expired().await
     }
 }
@@ -0,0 +1,311 @@
+use async_trait::async_trait;
+use sha1::{Digest, Sha1};
+use std::fmt;
+use tracing::warn;
+
+use crate::error::{AppError, AppResult};
+
+/// Length of the SHA-1 hex prefix sent to a breach provider
+pub const BREACH_PREFIX_LENGTH: usize = 5;
+
+/// Configurable password strength requirements for registration
+#[derive(Debug, Clone)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub max_length: usize,
+    pub require_uppercase: bool,
+    pub require_lowercase: bool,
+    pub require_digit: bool,
+    pub require_symbol: bool,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            min_length: 8,
+            max_length: 128,
+            require_uppercase: true,
+            require_lowercase: true,
+            require_digit: true,
+            require_symbol: false,
+        }
+    }
+}
+
+/// A single password requirement that was not met
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PasswordRule {
+    MinLength(usize),
+    MaxLength(usize),
+    Uppercase,
+    Lowercase,
+    Digit,
+    Symbol,
+    Breached(u64),
+}
+
+impl fmt::Display for PasswordRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PasswordRule::MinLength(min) => write!(f, "Password must be at least {} characters long", min),
+            PasswordRule::MaxLength(max) => write!(f, "Password must be at most {} characters long", max),
+            PasswordRule::Uppercase => write!(f, "Password must contain an uppercase letter"),
+            PasswordRule::Lowercase => write!(f, "Password must contain a lowercase letter"),
+            PasswordRule::Digit => write!(f, "Password must contain a digit"),
+            PasswordRule::Symbol => write!(f, "Password must contain a symbol"),
+            PasswordRule::Breached(count) => write!(
+                f,
+                "Password has appeared in {} known data breaches and cannot be used",
+                count
+            ),
+        }
+    }
+}
+
+impl PasswordPolicy {
+    /// Every rule the password violates, in policy order
+    pub fn violations(&self, password: &str) -> Vec<PasswordRule> {
+        let length = password.chars().count();
+        let mut violations = Vec::new();
+
+        if length < self.min_length {
+            violations.push(PasswordRule::MinLength(self.min_length));
+        }
+        if length > self.max_length {
+            violations.push(PasswordRule::MaxLength(self.max_length));
+        }
+        if self.require_uppercase && !password.chars().any(char::is_uppercase) {
+            violations.push(PasswordRule::Uppercase);
+        }
+        if self.require_lowercase && !password.chars().any(char::is_lowercase) {
+            violations.push(PasswordRule::Lowercase);
+        }
+        if self.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+            violations.push(PasswordRule::Digit);
+        }
+        if self.require_symbol && !password.chars().any(|c| !c.is_alphanumeric() && !c.is_whitespace()) {
+            violations.push(PasswordRule::Symbol);
+        }
+
+        violations
+    }
+
+    /// Reject the password with a message naming every violated rule
+    pub fn validate(&self, password: &str) -> AppResult<()> {
+        let violations = self.violations(password);
+        if violations.is_empty() {
+            return Ok(());
+        }
+
+        Err(AppError::Validation {
+            message: violations
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("; "),
+        })
+    }
+}
+
+/// Source of breached password hashes queried by SHA-1 prefix
+///
+/// Only the first five hex characters of the hash leave the process; the
+/// provider returns every known suffix for that prefix with its breach count,
+/// so the full password hash is never disclosed (k-anonymity).
+#[async_trait]
+pub trait BreachProvider: Send + Sync {
+    /// Uppercase hex suffixes and breach counts for a five character prefix
+    async fn range(&self, prefix: &str) -> AppResult<Vec<(String, u64)>>;
+}
+
+/// Offline default that never reports a breach
+#[derive(Debug, Clone, Default)]
+pub struct NoopBreachProvider;
+
+#[async_trait]
+impl BreachProvider for NoopBreachProvider {
+    async fn range(&self, _prefix: &str) -> AppResult<Vec<(String, u64)>> {
+        Ok(Vec::new())
+    }
+}
+
+/// Uppercase hex SHA-1 of the password split into prefix and suffix
+fn sha1_prefix_suffix(password: &str) -> (String, String) {
+    let digest = Sha1::digest(password.as_bytes());
+    let hex: String = digest.iter().map(|b| format!("{:02X}", b)).collect();
+    let (prefix, suffix) = hex.split_at(BREACH_PREFIX_LENGTH);
+    (prefix.to_string(), suffix.to_string())
+}
+
+/// Number of breaches the password appears in, if any
+pub async fn breach_count(provider: &dyn BreachProvider, password: &str) -> AppResult<Option<u64>> {
+    let (prefix, suffix) = sha1_prefix_suffix(password);
+    let count = provider
+        .range(&prefix)
+        .await?
+        .into_iter()
+        .find(|(candidate, _)| candidate.eq_ignore_ascii_case(&suffix))
+        .map(|(_, count)| count)
+        .filter(|count| *count > 0);
+
+    Ok(count)
+}
+
+/// Reject passwords found in a breach corpus
+///
+/// Provider failures are logged and allowed through so registration keeps
+/// working offline.
+pub async fn ensure_not_breached(provider: &dyn BreachProvider, password: &str) -> AppResult<()> {
+    match breach_count(provider, password).await {
+        Ok(Some(count)) => Err(AppError::Validation {
+            message: PasswordRule::Breached(count).to_string(),
+        }),
+        Ok(None) => Ok(()),
+        Err(e) => {
+            warn!("Password breach check unavailable: {}", e);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Serves a fixed corpus and records the prefixes it was asked for
+    struct MockBreachProvider {
+        breached: Vec<(&'static str, u64)>,
+        queried: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl BreachProvider for MockBreachProvider {
+        async fn range(&self, prefix: &str) -> AppResult<Vec<(String, u64)>> {
+            self.queried.lock().unwrap().push(prefix.to_string());
+            Ok(self
+                .breached
+                .iter()
+                .map(|(password, count)| (sha1_prefix_suffix(password), *count))
+                .filter(|((candidate_prefix, _), _)| candidate_prefix == prefix)
+                .map(|((_, suffix), count)| (suffix, count))
+                .collect())
+        }
+    }
+
+    struct FailingBreachProvider;
+
+    #[async_trait]
+    impl BreachProvider for FailingBreachProvider {
+        async fn range(&self, _prefix: &str) -> AppResult<Vec<(String, u64)>> {
+            Err(AppError::Internal {
+                message: "offline".to_string(),
+            })
+        }
+    }
+
+    fn strict_policy() -> PasswordPolicy {
+        PasswordPolicy {
+            min_length: 10,
+            max_length: 20,
+            require_uppercase: true,
+            require_lowercase: true,
+            require_digit: true,
+            require_symbol: true,
+        }
+    }
+
+    fn validation_message(result: AppResult<()>) -> String {
+        match result {
+            Err(AppError::Validation { message }) => message,
+            other => panic!("expected validation error, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn test_compliant_password_passes() {
+        assert!(strict_policy().validate("Str0ng!Pass").is_ok());
+        assert!(PasswordPolicy::default().validate("Passw0rdOk").is_ok());
+    }
+
+    #[test]
+    fn test_min_length_rule() {
+        let violations = strict_policy().violations("Sh0rt!");
+        assert_eq!(violations, vec![PasswordRule::MinLength(10)]);
+        assert_eq!(
+            validation_message(strict_policy().validate("Sh0rt!")),
+            "Password must be at least 10 characters long"
+        );
+    }
+
+    #[test]
+    fn test_max_length_rule() {
+        let violations = strict_policy().violations("Way!T00LongForThisPolicy");
+        assert_eq!(violations, vec![PasswordRule::MaxLength(20)]);
+    }
+
+    #[test]
+    fn test_character_class_rules() {
+        let policy = strict_policy();
+        assert_eq!(policy.violations("l0wercase!only"), vec![PasswordRule::Uppercase]);
+        assert_eq!(policy.violations("UPPERCASE!0NLY"), vec![PasswordRule::Lowercase]);
+        assert_eq!(policy.violations("NoDigits!Here"), vec![PasswordRule::Digit]);
+        assert_eq!(policy.violations("NoSymb0lsHere"), vec![PasswordRule::Symbol]);
+    }
+
+    #[test]
+    fn test_rules_can_be_disabled() {
+        let policy = PasswordPolicy {
+            min_length: 4,
+            max_length: 64,
+            require_uppercase: false,
+            require_lowercase: false,
+            require_digit: false,
+            require_symbol: false,
+        };
+        assert!(policy.validate("plain").is_ok());
+    }
+
+    #[test]
+    fn test_every_violation_is_named() {
+        let message = validation_message(strict_policy().validate("abc"));
+        assert!(message.contains("at least 10 characters"));
+        assert!(message.contains("uppercase letter"));
+        assert!(message.contains("digit"));
+        assert!(message.contains("symbol"));
+        assert!(!message.contains("lowercase letter"));
+    }
+
+    #[tokio::test]
+    async fn test_breached_password_rejected() {
+        let provider = MockBreachProvider {
+            breached: vec![("Password123!", 42)],
+            queried: Mutex::new(Vec::new()),
+        };
+
+        let message = validation_message(ensure_not_breached(&provider, "Password123!").await);
+        assert!(message.contains("42 known data breaches"));
+
+        // Only the five character prefix is disclosed to the provider
+        let queried = provider.queried.lock().unwrap().clone();
+        assert_eq!(queried.len(), 1);
+        assert_eq!(queried[0].len(), BREACH_PREFIX_LENGTH);
+        assert_eq!(queried[0], sha1_prefix_suffix("Password123!").0);
+
+        assert!(ensure_not_breached(&provider, "Unl1sted!Secret").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_offline_providers_allow_registration() {
+        assert!(ensure_not_breached(&NoopBreachProvider, "Password123!").await.is_ok());
+        assert!(ensure_not_breached(&FailingBreachProvider, "Password123!").await.is_ok());
+    }
+
+    #[test]
+    fn test_sha1_split() {
+        // SHA-1("password") = 5BAA61E4C9B93F3F0682250B6CF8331B7EE68FD8
+        let (prefix, suffix) = sha1_prefix_suffix("password");
+        assert_eq!(prefix, "5BAA6");
+        assert_eq!(suffix, "1E4C9B93F3F0682250B6CF8331B7EE68FD8");
+    }
+}
@@ -0,0 +1,96 @@
+use serde::{Deserialize, Serialize};
+use std::num::NonZeroUsize;
+use std::str::FromStr;
+
+use crate::error::{AppError, AppResult};
+
+/// What happens to a login that would take a user past their session cap
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionLimitAction {
+    /// Log the user's oldest sessions out to make room
+    #[default]
+    EvictOldest,
+    /// Refuse the login until another session ends
+    Reject,
+}
+
+impl FromStr for SessionLimitAction {
+    type Err = AppError;
+
+    fn from_str(value: &str) -> AppResult<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "evict_oldest" => Ok(SessionLimitAction::EvictOldest),
+            "reject" => Ok(SessionLimitAction::Reject),
+            other => Err(AppError::Validation {
+                message: format!("Unknown session limit action '{}', expected 'evict_oldest' or 'reject'", other),
+            }),
+        }
+    }
+}
+
+/// Cap on the concurrent sessions one user may hold
+///
+/// Unlimited by default. Sessions that have expired do not count.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionLimit {
+    pub max_sessions_per_user: Option<NonZeroUsize>,
+    pub on_limit: SessionLimitAction,
+}
+
+impl SessionLimit {
+    /// Environment variable holding the per-user cap
+    pub const MAX_SESSIONS_ENV_VAR: &'static str = "ATLAS_MAX_SESSIONS_PER_USER";
+    /// Environment variable selecting evict_oldest or reject
+    pub const ACTION_ENV_VAR: &'static str = "ATLAS_SESSION_LIMIT_ACTION";
+
+    pub fn new(max_sessions_per_user: NonZeroUsize, on_limit: SessionLimitAction) -> Self {
+        Self {
+            max_sessions_per_user: Some(max_sessions_per_user),
+            on_limit,
+        }
+    }
+
+    /// How many of a user's `open_sessions` must be evicted before they may
+    /// start another session
+    ///
+    /// Fails when the limit refuses logins past the cap.
+    pub fn evictions_needed(&self, open_sessions: usize) -> AppResult<usize> {
+        let Some(max_sessions) = self.max_sessions_per_user else {
+            return Ok(0);
+        };
+        if open_sessions < max_sessions.get() {
+            return Ok(0);
+        }
+
+        match self.on_limit {
+            SessionLimitAction::Reject => Err(AppError::Authentication {
+                message: format!(
+                    "Session limit of {} reached; log out of another device first",
+                    max_sessions
+                ),
+            }),
+            SessionLimitAction::EvictOldest => Ok(open_sessions + 1 - max_sessions.get()),
+        }
+    }
+
+    /// Limit named by `ATLAS_MAX_SESSIONS_PER_USER` and `ATLAS_SESSION_LIMIT_ACTION`,
+    /// unlimited when the cap is unset
+    pub fn from_env() -> AppResult<Self> {
+        let max_sessions_per_user = match std::env::var(Self::MAX_SESSIONS_ENV_VAR) {
+            Ok(value) => Some(value.trim().parse::<NonZeroUsize>().map_err(|_| AppError::Validation {
+                message: format!("{} must be a positive whole number, got '{}'", Self::MAX_SESSIONS_ENV_VAR, value),
+            })?),
+            Err(_) => None,
+        };
+        let on_limit = match std::env::var(Self::ACTION_ENV_VAR) {
+            Ok(value) => value.parse()?,
+            Err(_) => SessionLimitAction::default(),
+        };
+
+        Ok(Self {
+            max_sessions_per_user,
+            on_limit,
+        })
+    }
+}
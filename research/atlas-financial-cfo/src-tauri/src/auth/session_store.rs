@@ -0,0 +1,512 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Pool, Row, Sqlite};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::database::retry_on_busy;
+use crate::auth::SessionLimit;
+use crate::domain::{EntityId, Timestamp, UserSession};
+use crate::error::{AppError, AppResult};
+
+/// Key a session is stored under: the hex SHA-256 digest of its token
+///
+/// Tokens are credentials, so stores only ever see their hashes and a copy
+/// of the session table cannot be used to log in.
+pub fn session_token_hash(session_token: &str) -> String {
+    Sha256::digest(session_token.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Storage for authenticated sessions, keyed by [`session_token_hash`]
+#[async_trait]
+pub trait SessionStore: Send + Sync {
+    async fn insert(&self, token_hash: &str, session: &UserSession) -> AppResult<()>;
+    async fn get(&self, token_hash: &str) -> AppResult<Option<UserSession>>;
+    async fn remove(&self, token_hash: &str) -> AppResult<()>;
+
+    /// Store `session` once its user is within `limit`, returning the
+    /// sessions evicted to make room, oldest first
+    ///
+    /// Counting the user's unexpired sessions, evicting and inserting happen
+    /// atomically, so logins racing each other cannot exceed the cap.
+    async fn insert_within_limit(
+        &self,
+        token_hash: &str,
+        session: &UserSession,
+        limit: SessionLimit,
+    ) -> AppResult<Vec<UserSession>>;
+
+    /// Drop every expired session, returning how many were removed
+    async fn remove_expired(&self) -> AppResult<usize>;
+}
+
+/// Where the auth service keeps sessions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionStorage {
+    /// Sessions live only as long as the process
+    #[default]
+    Memory,
+    /// Sessions are kept in the `user_sessions` table and survive restarts
+    Sqlite,
+}
+
+impl SessionStorage {
+    /// Environment variable selecting the backend
+    pub const ENV_VAR: &'static str = "ATLAS_SESSION_STORAGE";
+
+    /// Backend named by `ATLAS_SESSION_STORAGE`, in memory when unset
+    pub fn from_env() -> AppResult<Self> {
+        match std::env::var(Self::ENV_VAR) {
+            Ok(value) => value.parse(),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    pub fn build(self, pool: &Pool<Sqlite>) -> Arc<dyn SessionStore> {
+        match self {
+            SessionStorage::Memory => Arc::new(InMemorySessionStore::new()),
+            SessionStorage::Sqlite => Arc::new(SqliteSessionStore::new(pool.clone())),
+        }
+    }
+}
+
+impl FromStr for SessionStorage {
+    type Err = AppError;
+
+    fn from_str(value: &str) -> AppResult<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "memory" => Ok(SessionStorage::Memory),
+            "sqlite" => Ok(SessionStorage::Sqlite),
+            other => Err(AppError::Validation {
+                message: format!("Unknown session storage '{}', expected 'memory' or 'sqlite'", other),
+            }),
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct InMemorySessionStore {
+    sessions: Arc<RwLock<HashMap<String, UserSession>>>,
+}
+
+impl InMemorySessionStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl SessionStore for InMemorySessionStore {
+    async fn insert(&self, token_hash: &str, session: &UserSession) -> AppResult<()> {
+        let mut sessions = self.sessions.write().await;
+        sessions.insert(token_hash.to_string(), session.clone());
+        Ok(())
+    }
+
+    async fn get(&self, token_hash: &str) -> AppResult<Option<UserSession>> {
+        let sessions = self.sessions.read().await;
+        Ok(sessions.get(token_hash).cloned())
+    }
+
+    async fn remove(&self, token_hash: &str) -> AppResult<()> {
+        let mut sessions = self.sessions.write().await;
+        sessions.remove(token_hash);
+        Ok(())
+    }
+
+    async fn insert_within_limit(
+        &self,
+        token_hash: &str,
+        session: &UserSession,
+        limit: SessionLimit,
+    ) -> AppResult<Vec<UserSession>> {
+        // One write lock covers the count, the evictions and the insert
+        let mut sessions = self.sessions.write().await;
+        let mut open_sessions: Vec<(String, UserSession)> = sessions
+            .iter()
+            .filter(|(_, open)| open.user_id == session.user_id && !open.is_expired())
+            .map(|(token_hash, open)| (token_hash.clone(), open.clone()))
+            .collect();
+        open_sessions.sort_by_key(|(_, open)| open.created_at);
+
+        let excess = limit.evictions_needed(open_sessions.len())?;
+        let evicted = open_sessions
+            .into_iter()
+            .take(excess)
+            .map(|(evicted_hash, evicted)| {
+                sessions.remove(&evicted_hash);
+                evicted
+            })
+            .collect();
+        sessions.insert(token_hash.to_string(), session.clone());
+        Ok(evicted)
+    }
+
+    async fn remove_expired(&self) -> AppResult<usize> {
+        let mut sessions = self.sessions.write().await;
+        let initial_count = sessions.len();
+        sessions.retain(|_, session| !session.is_expired());
+        Ok(initial_count - sessions.len())
+    }
+}
+
+/// Sessions in the `user_sessions` table, whose `session_token` column holds
+/// token hashes
+#[derive(Clone)]
+pub struct SqliteSessionStore {
+    pool: Pool<Sqlite>,
+}
+
+impl SqliteSessionStore {
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl SessionStore for SqliteSessionStore {
+    async fn insert(&self, token_hash: &str, session: &UserSession) -> AppResult<()> {
+        let id = EntityId::new().to_string();
+        retry_on_busy(|| {
+            sqlx::query(
+                r#"
+                INSERT INTO user_sessions (id, user_id, session_token, created_at, expires_at)
+                VALUES (?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(&id)
+            .bind(session.user_id.to_string())
+            .bind(token_hash)
+            .bind(session.created_at.as_datetime())
+            .bind(session.expires_at.as_datetime())
+            .execute(&self.pool)
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get(&self, token_hash: &str) -> AppResult<Option<UserSession>> {
+        let row = sqlx::query(
+            r#"
+            SELECT s.user_id, u.username, u.email, s.created_at, s.expires_at
+            FROM user_sessions s
+            JOIN users u ON u.id = s.user_id
+            WHERE s.session_token = ? AND s.is_active = 1
+            "#,
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.as_ref().map(session_from_row).transpose()
+    }
+
+    async fn remove(&self, token_hash: &str) -> AppResult<()> {
+        retry_on_busy(|| {
+            sqlx::query("DELETE FROM user_sessions WHERE session_token = ?")
+                .bind(token_hash)
+                .execute(&self.pool)
+        })
+        .await?;
+        Ok(())
+    }
+
+    async fn insert_within_limit(
+        &self,
+        token_hash: &str,
+        session: &UserSession,
+        limit: SessionLimit,
+    ) -> AppResult<Vec<UserSession>> {
+        let id = EntityId::new().to_string();
+        let user_id = session.user_id.to_string();
+        let (id, user_id) = (&id, &user_id);
+
+        // A concurrent login that commits first makes this transaction busy,
+        // and the retry counts again with that session included
+        let evicted = retry_on_busy(|| async move {
+            let mut tx = self.pool.begin().await?;
+            let rows = sqlx::query(
+                r#"
+                SELECT s.id, s.user_id, u.username, u.email, s.created_at, s.expires_at
+                FROM user_sessions s
+                JOIN users u ON u.id = s.user_id
+                WHERE s.user_id = ? AND s.is_active = 1 AND s.expires_at >= ?
+                ORDER BY s.created_at, s.rowid
+                "#,
+            )
+            .bind(user_id)
+            .bind(Utc::now())
+            .fetch_all(&mut *tx)
+            .await?;
+
+            let excess = match limit.evictions_needed(rows.len()) {
+                Ok(excess) => excess,
+                Err(refused) => return Ok(Err(refused)),
+            };
+            let mut evicted = Vec::with_capacity(excess);
+            for row in rows.iter().take(excess) {
+                sqlx::query("DELETE FROM user_sessions WHERE id = ?")
+                    .bind(row.try_get::<String, _>("id")?)
+                    .execute(&mut *tx)
+                    .await?;
+                evicted.push(session_from_row(row));
+            }
+
+            sqlx::query(
+                r#"
+                INSERT INTO user_sessions (id, user_id, session_token, created_at, expires_at)
+                VALUES (?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(id)
+            .bind(user_id)
+            .bind(token_hash)
+            .bind(session.created_at.as_datetime())
+            .bind(session.expires_at.as_datetime())
+            .execute(&mut *tx)
+            .await?;
+
+            tx.commit().await?;
+            Ok(evicted.into_iter().collect::<AppResult<Vec<UserSession>>>())
+        })
+        .await??;
+
+        Ok(evicted)
+    }
+
+    async fn remove_expired(&self) -> AppResult<usize> {
+        let now = Utc::now();
+        let result = retry_on_busy(|| {
+            sqlx::query("DELETE FROM user_sessions WHERE expires_at < ?")
+                .bind(now)
+                .execute(&self.pool)
+        })
+        .await?;
+        Ok(result.rows_affected() as usize)
+    }
+}
+
+fn session_from_row(row: &SqliteRow) -> AppResult<UserSession> {
+    let user_id = uuid::Uuid::parse_str(row.try_get("user_id")?).map_err(|e| AppError::Database {
+        message: format!("Invalid user ID UUID: {}", e),
+    })?;
+    Ok(UserSession {
+        user_id: EntityId::from_uuid(user_id),
+        username: row.try_get("username")?,
+        email: row.try_get("email")?,
+        created_at: Timestamp::from_datetime(row.try_get::<DateTime<Utc>, _>("created_at")?),
+        expires_at: Timestamp::from_datetime(row.try_get::<DateTime<Utc>, _>("expires_at")?),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{AuthService, SessionLimit, SessionLimitAction, SqliteAuditLog};
+    use crate::database::{TotpRepository, UserRepository};
+    use crate::domain::LoginCredentials;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use std::num::NonZeroUsize;
+
+    async fn test_pool() -> Pool<Sqlite> {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::query(include_str!("../../migrations/001_initial_schema.sql"))
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(include_str!("../../migrations/003_add_metadata.sql"))
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(include_str!("../../migrations/005_add_totp.sql"))
+            .execute(&pool)
+            .await
+            .unwrap();
+        pool
+    }
+
+    fn auth_service(pool: &Pool<Sqlite>, storage: SessionStorage) -> AuthService {
+        AuthService::new(
+            Arc::new(UserRepository::new(pool.clone())),
+            Arc::new(TotpRepository::new(pool.clone())),
+        )
+        .with_session_store(storage.build(pool))
+    }
+
+    async fn log_in(service: &AuthService) -> (String, UserSession) {
+        service
+            .register_user("casey".to_string(), "casey@example.com".to_string(), "Passw0rdOk".to_string())
+            .await
+            .unwrap();
+        log_in_again(service).await.unwrap()
+    }
+
+    async fn log_in_again(service: &AuthService) -> AppResult<(String, UserSession)> {
+        service
+            .authenticate(LoginCredentials::new("casey".to_string(), "Passw0rdOk".to_string()))
+            .await
+    }
+
+    #[test]
+    fn test_storage_is_parsed_from_config_values() {
+        assert_eq!("sqlite".parse::<SessionStorage>().unwrap(), SessionStorage::Sqlite);
+        assert_eq!(" Memory ".parse::<SessionStorage>().unwrap(), SessionStorage::Memory);
+        assert!(matches!("redis".parse::<SessionStorage>(), Err(AppError::Validation { .. })));
+        assert_eq!(SessionStorage::default(), SessionStorage::Memory);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_sessions_survive_rebuilding_the_service() {
+        let pool = test_pool().await;
+        let (session_token, session) = log_in(&auth_service(&pool, SessionStorage::Sqlite)).await;
+
+        let rebuilt = auth_service(&pool, SessionStorage::Sqlite);
+        let restored = rebuilt.verify_session(&session_token).await.unwrap();
+        assert_eq!(restored.user_id, session.user_id);
+        assert_eq!(restored.username, "casey");
+        assert_eq!(restored.expires_at.as_datetime(), session.expires_at.as_datetime());
+
+        rebuilt.logout(&session_token).await.unwrap();
+        assert!(auth_service(&pool, SessionStorage::Sqlite)
+            .verify_session(&session_token)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_only_token_hashes_are_stored() {
+        let pool = test_pool().await;
+        let (session_token, _) = log_in(&auth_service(&pool, SessionStorage::Sqlite)).await;
+
+        let stored: Vec<String> = sqlx::query_scalar("SELECT session_token FROM user_sessions")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(stored, [session_token_hash(&session_token)]);
+        assert_ne!(stored[0], session_token);
+        assert_eq!(stored[0].len(), 64);
+    }
+
+    #[tokio::test]
+    async fn test_memory_sessions_do_not_survive_rebuilding_the_service() {
+        let pool = test_pool().await;
+        let service = auth_service(&pool, SessionStorage::Memory);
+        let (session_token, _) = log_in(&service).await;
+
+        assert!(service.verify_session(&session_token).await.is_ok());
+        assert!(auth_service(&pool, SessionStorage::Memory)
+            .verify_session(&session_token)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_expired_sessions_are_rejected_and_removed() {
+        let pool = test_pool().await;
+        let service = auth_service(&pool, SessionStorage::Sqlite);
+        let (_, mut session) = log_in(&service).await;
+
+        let store = SqliteSessionStore::new(pool.clone());
+        session.expires_at = Timestamp::from_datetime(Utc::now() - chrono::Duration::minutes(1));
+        let expired = session_token_hash("expired-token");
+        store.insert(&expired, &session).await.unwrap();
+
+        assert!(matches!(
+            service.verify_session("expired-token").await,
+            Err(AppError::Authentication { message }) if message == "Session expired"
+        ));
+        assert!(store.get(&expired).await.unwrap().is_none());
+
+        store.insert(&session_token_hash("expired-again"), &session).await.unwrap();
+        assert_eq!(store.remove_expired().await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_oldest_session_is_evicted_past_the_limit() {
+        for storage in [SessionStorage::Memory, SessionStorage::Sqlite] {
+            let pool = test_pool().await;
+            let limit = SessionLimit::new(NonZeroUsize::new(2).unwrap(), SessionLimitAction::EvictOldest);
+            let service = auth_service(&pool, storage)
+                .with_session_limit(limit)
+                .with_audit_log(Arc::new(SqliteAuditLog::new(pool.clone())));
+
+            let (oldest, session) = log_in(&service).await;
+            let (second, _) = log_in_again(&service).await.unwrap();
+            assert!(service.verify_session(&oldest).await.is_ok());
+
+            let (newest, _) = log_in_again(&service).await.unwrap();
+            assert!(
+                matches!(
+                    service.verify_session(&oldest).await,
+                    Err(AppError::Authentication { message }) if message == "Invalid session"
+                ),
+                "{:?}",
+                storage
+            );
+            assert!(service.verify_session(&second).await.is_ok());
+            assert!(service.verify_session(&newest).await.is_ok());
+
+            let audited: Vec<(String, String, String)> =
+                sqlx::query_as("SELECT user_id, action, details FROM audit_log")
+                    .fetch_all(&pool)
+                    .await
+                    .unwrap();
+            assert_eq!(audited.len(), 1);
+            assert_eq!(audited[0].0, session.user_id.to_string());
+            assert_eq!(audited[0].1, "session_evicted");
+            let details: serde_json::Value = serde_json::from_str(&audited[0].2).unwrap();
+            assert_eq!(details["max_sessions_per_user"], 2);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_logins_past_a_rejecting_limit_are_refused() {
+        let pool = test_pool().await;
+        let limit = SessionLimit::new(NonZeroUsize::new(1).unwrap(), "reject".parse().unwrap());
+        let service = auth_service(&pool, SessionStorage::Sqlite).with_session_limit(limit);
+
+        let (first, _) = log_in(&service).await;
+        assert!(matches!(log_in_again(&service).await, Err(AppError::Authentication { .. })));
+        assert!(service.verify_session(&first).await.is_ok());
+
+        // Ending the session frees its slot
+        service.logout(&first).await.unwrap();
+        assert!(log_in_again(&service).await.is_ok());
+        assert_eq!(SessionLimit::default().max_sessions_per_user, None);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_logins_cannot_exceed_the_limit() {
+        for storage in [SessionStorage::Memory, SessionStorage::Sqlite] {
+            let pool = test_pool().await;
+            let limit = SessionLimit::new(NonZeroUsize::new(1).unwrap(), SessionLimitAction::Reject);
+            let service = auth_service(&pool, storage).with_session_limit(limit);
+            service
+                .register_user("casey".to_string(), "casey@example.com".to_string(), "Passw0rdOk".to_string())
+                .await
+                .unwrap();
+
+            let (first, second, third) = tokio::join!(
+                log_in_again(&service),
+                log_in_again(&service),
+                log_in_again(&service)
+            );
+            let accepted = [first, second, third].iter().filter(|login| login.is_ok()).count();
+            assert_eq!(accepted, 1, "{:?}", storage);
+        }
+    }
+}
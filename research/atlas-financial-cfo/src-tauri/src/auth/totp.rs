@@ -0,0 +1,266 @@
+use data_encoding::BASE32_NOPAD;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+
+/// Bytes of randomness in a generated shared secret (160 bits, per RFC 4226)
+pub const TOTP_SECRET_LENGTH: usize = 20;
+
+/// Time-based one-time password parameters (RFC 6238, HMAC-SHA1)
+#[derive(Debug, Clone)]
+pub struct TotpConfig {
+    pub issuer: String,
+    pub digits: u32,
+    pub step_seconds: u64,
+    /// Steps either side of the current one that are still accepted, to
+    /// tolerate clock drift between the server and the authenticator app
+    pub allowed_skew_steps: u64,
+}
+
+impl Default for TotpConfig {
+    fn default() -> Self {
+        Self {
+            issuer: "Atlas Financial CFO".to_string(),
+            digits: 6,
+            step_seconds: 30,
+            allowed_skew_steps: 1,
+        }
+    }
+}
+
+/// Shared secret handed to the user when enrolling an authenticator app
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TotpSecret {
+    /// Base32 secret for manual entry
+    pub secret: String,
+    /// `otpauth://` URI to render as a QR code
+    pub provisioning_uri: String,
+}
+
+impl TotpConfig {
+    /// Generate a random Base32 encoded shared secret
+    pub fn generate_secret() -> String {
+        let mut bytes = [0u8; TOTP_SECRET_LENGTH];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        BASE32_NOPAD.encode(&bytes)
+    }
+
+    pub fn provisioning_uri(&self, secret: &str, account_name: &str) -> String {
+        let issuer = uri_encode(&self.issuer);
+        format!(
+            "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+            issuer,
+            uri_encode(account_name),
+            secret,
+            issuer,
+            self.digits,
+            self.step_seconds
+        )
+    }
+
+    /// Time step containing the given Unix time
+    pub fn step_at(&self, unix_seconds: u64) -> u64 {
+        unix_seconds / self.step_seconds
+    }
+
+    /// Code for a time step, or `None` when the secret is not valid Base32
+    pub fn code_at_step(&self, secret: &str, step: u64) -> Option<String> {
+        let key = BASE32_NOPAD.decode(secret.as_bytes()).ok()?;
+        let mut mac = Hmac::<Sha1>::new_from_slice(&key).ok()?;
+        mac.update(&step.to_be_bytes());
+        let digest = mac.finalize().into_bytes();
+
+        // Dynamic truncation (RFC 4226 section 5.3)
+        let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+        let binary = u32::from_be_bytes([
+            digest[offset] & 0x7f,
+            digest[offset + 1],
+            digest[offset + 2],
+            digest[offset + 3],
+        ]);
+        let code = binary % 10u32.pow(self.digits);
+        Some(format!("{:0width$}", code, width = self.digits as usize))
+    }
+
+    /// Time step `code` is valid for at `unix_seconds`, if any
+    ///
+    /// Steps at or before `last_used_step` are never matched, so a code
+    /// cannot be replayed while it is still inside the drift window.
+    pub fn matching_step(
+        &self,
+        secret: &str,
+        code: &str,
+        unix_seconds: u64,
+        last_used_step: Option<u64>,
+    ) -> Option<u64> {
+        let code = code.trim();
+        if code.len() != self.digits as usize || !code.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+
+        let current = self.step_at(unix_seconds);
+        let first = current.saturating_sub(self.allowed_skew_steps);
+        (first..=current + self.allowed_skew_steps)
+            .filter(|step| last_used_step.is_none_or(|used| *step > used))
+            .find(|step| {
+                self.code_at_step(secret, *step)
+                    .is_some_and(|expected| constant_time_eq(expected.as_bytes(), code.as_bytes()))
+            })
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Percent-encode a label or parameter for an `otpauth://` URI
+fn uri_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::AuthService;
+    use crate::database::{TotpRepository, UserRepository};
+    use crate::domain::LoginCredentials;
+    use crate::error::AppError;
+    use chrono::Utc;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use std::sync::Arc;
+
+    /// RFC 6238 appendix B shared secret ("12345678901234567890")
+    const RFC_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+    fn eight_digits() -> TotpConfig {
+        TotpConfig {
+            digits: 8,
+            ..TotpConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_codes_match_rfc_vectors() {
+        let config = eight_digits();
+        assert_eq!(config.code_at_step(RFC_SECRET, config.step_at(59)).unwrap(), "94287082");
+        assert_eq!(config.code_at_step(RFC_SECRET, config.step_at(1111111109)).unwrap(), "07081804");
+        assert_eq!(config.code_at_step(RFC_SECRET, config.step_at(2000000000)).unwrap(), "69279037");
+    }
+
+    #[test]
+    fn test_valid_code_is_accepted_within_drift() {
+        let config = TotpConfig::default();
+        let now = 1_700_000_000;
+        let step = config.step_at(now);
+        let code = config.code_at_step(RFC_SECRET, step).unwrap();
+
+        assert_eq!(config.matching_step(RFC_SECRET, &code, now, None), Some(step));
+        // One step of drift either way is tolerated
+        assert_eq!(config.matching_step(RFC_SECRET, &code, now + 30, None), Some(step));
+        assert_eq!(config.matching_step(RFC_SECRET, &code, now - 30, None), Some(step));
+        assert_eq!(config.matching_step(RFC_SECRET, "12345", now, None), None);
+        assert_eq!(config.matching_step(RFC_SECRET, "abcdef", now, None), None);
+    }
+
+    #[test]
+    fn test_expired_code_is_rejected() {
+        let config = TotpConfig::default();
+        let now = 1_700_000_000;
+        let stale = config.code_at_step(RFC_SECRET, config.step_at(now) - 2).unwrap();
+
+        assert_eq!(config.matching_step(RFC_SECRET, &stale, now, None), None);
+    }
+
+    #[test]
+    fn test_replayed_code_is_rejected() {
+        let config = TotpConfig::default();
+        let now = 1_700_000_000;
+        let step = config.step_at(now);
+        let code = config.code_at_step(RFC_SECRET, step).unwrap();
+
+        let used = config.matching_step(RFC_SECRET, &code, now, None);
+        assert_eq!(used, Some(step));
+        assert_eq!(config.matching_step(RFC_SECRET, &code, now + 10, used), None);
+
+        // The next step's code is still accepted
+        let next = config.code_at_step(RFC_SECRET, step + 1).unwrap();
+        assert_eq!(config.matching_step(RFC_SECRET, &next, now + 30, used), Some(step + 1));
+    }
+
+    #[test]
+    fn test_provisioning_uri_and_generated_secret() {
+        let config = TotpConfig::default();
+        let secret = TotpConfig::generate_secret();
+        assert_eq!(BASE32_NOPAD.decode(secret.as_bytes()).unwrap().len(), TOTP_SECRET_LENGTH);
+
+        assert_eq!(
+            config.provisioning_uri("JBSWY3DPEHPK3PXP", "casey@example.com"),
+            "otpauth://totp/Atlas%20Financial%20CFO:casey%40example.com?secret=JBSWY3DPEHPK3PXP\
+             &issuer=Atlas%20Financial%20CFO&algorithm=SHA1&digits=6&period=30"
+        );
+    }
+
+    async fn auth_service() -> AuthService {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        for migration in [
+            include_str!("../../migrations/001_initial_schema.sql"),
+            include_str!("../../migrations/005_add_totp.sql"),
+        ] {
+            sqlx::query(migration).execute(&pool).await.unwrap();
+        }
+        AuthService::new(
+            Arc::new(UserRepository::new(pool.clone())),
+            Arc::new(TotpRepository::new(pool)),
+        )
+    }
+
+    fn credentials() -> LoginCredentials {
+        LoginCredentials::new("casey".to_string(), "Passw0rdOk".to_string())
+    }
+
+    #[tokio::test]
+    async fn test_login_requires_totp_once_enrolled() {
+        let service = auth_service().await;
+        service
+            .register_user("casey".to_string(), "casey@example.com".to_string(), "Passw0rdOk".to_string())
+            .await
+            .unwrap();
+        let (_, session) = service.authenticate(credentials()).await.unwrap();
+
+        let enrolled = service.enroll_totp(session.user_id).await.unwrap();
+        assert!(enrolled.provisioning_uri.contains(&enrolled.secret));
+
+        // A pending enrollment does not affect login until confirmed
+        assert!(service.authenticate(credentials()).await.is_ok());
+
+        let config = TotpConfig::default();
+        let step = config.step_at(Utc::now().timestamp() as u64);
+        let code = config.code_at_step(&enrolled.secret, step).unwrap();
+        service.verify_totp(session.user_id, &code).await.unwrap();
+
+        assert!(matches!(
+            service.authenticate(credentials()).await,
+            Err(AppError::Authentication { message }) if message == "Two-factor code required"
+        ));
+        // The confirming code has been spent
+        assert!(service.authenticate(credentials().with_totp_code(code)).await.is_err());
+
+        let next = config.code_at_step(&enrolled.secret, step + 1).unwrap();
+        assert!(service.authenticate(credentials().with_totp_code(next)).await.is_ok());
+        assert!(matches!(
+            service.enroll_totp(session.user_id).await,
+            Err(AppError::Validation { .. })
+        ));
+    }
+}
@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     AppState,
-    domain::{Account, CreateAccountRequest, UpdateAccountRequest, EntityId, Money, AccountType, Currency, Timestamp},
+    domain::{Account, CreateAccountRequest, UpdateAccountRequest, EntityId, Money, AccountType, Currency, Page, PageRequest, Timestamp},
     error::AppResult,
 };
 
@@ -94,11 +94,14 @@ pub async fn create_account(
     Ok(account)
 }
 
+/// One page of the user's accounts; `limit` defaults to 50 and is capped at 200
 #[tauri::command]
 pub async fn get_accounts(
     user_id: String,
+    limit: Option<u32>,
+    offset: Option<u64>,
     state: State<'_, AppState>,
-) -> Result<Vec<Account>, String> {
+) -> Result<Page<Account>, String> {
     let user_id = EntityId::from_uuid(
         uuid::Uuid::parse_str(&user_id).map_err(|e| format!("Invalid user ID: {}", e))?
     );
@@ -106,7 +109,7 @@ pub async fn get_accounts(
     state
         .services
         .account_repository
-        .find_by_user_id(user_id)
+        .find_page_by_user_id(user_id, PageRequest::new(limit, offset))
         .await
         .map_err(|e| e.to_string())
 }
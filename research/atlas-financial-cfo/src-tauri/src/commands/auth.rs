@@ -1,12 +1,13 @@
 use tauri::State;
 use serde::{Deserialize, Serialize};
 
-use crate::{AppState, domain::{LoginCredentials, UserSession}, error::AppResult};
+use crate::{AppState, auth::TotpSecret, domain::{LoginCredentials, UserSession}, error::AppResult};
 
 #[derive(Debug, Deserialize)]
 pub struct LoginRequest {
     pub username: String,
     pub password: String,
+    pub totp_code: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -20,18 +21,18 @@ pub async fn login(
     request: LoginRequest,
     state: State<'_, AppState>,
 ) -> Result<LoginResponse, String> {
-    let credentials = LoginCredentials::new(request.username, request.password);
+    let mut credentials = LoginCredentials::new(request.username, request.password);
+    if let Some(code) = request.totp_code {
+        credentials = credentials.with_totp_code(code);
+    }
 
-    let session = state
+    let (session_token, session) = state
         .services
         .auth_service
         .authenticate(credentials)
         .await
         .map_err(|e| e.to_string())?;
 
-    // Generate session token
-    let session_token = uuid::Uuid::new_v4().to_string();
-
     Ok(LoginResponse {
         session,
         session_token,
@@ -65,3 +66,38 @@ pub async fn verify_session(
         .await
         .map_err(|e| e.to_string())
 }
+
+#[tauri::command]
+pub async fn enroll_totp(
+    session_token: String,
+    state: State<'_, AppState>,
+) -> Result<TotpSecret, String> {
+    let auth_service = &state.services.auth_service;
+    let session = auth_service
+        .verify_session(&session_token)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    auth_service
+        .enroll_totp(session.user_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn verify_totp(
+    session_token: String,
+    code: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let auth_service = &state.services.auth_service;
+    let session = auth_service
+        .verify_session(&session_token)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    auth_service
+        .verify_totp(session.user_id, &code)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -1,39 +1,261 @@
+use std::collections::HashSet;
+
 use sqlx::{Pool, Sqlite};
 use tracing::info;
 
-use crate::error::AppResult;
+use crate::error::{AppError, AppResult};
 
-pub async fn run_migrations(pool: &Pool<Sqlite>) -> AppResult<()> {
-    let migrations = [
-        ("001_initial_schema", include_str!("../../migrations/001_initial_schema.sql")),
-        ("002_add_indexes", include_str!("../../migrations/002_add_indexes.sql")),
-        ("003_add_metadata", include_str!("../../migrations/003_add_metadata.sql")),
-    ];
-
-    for (version, sql) in migrations.iter() {
-        // Check if migration already applied
-        let applied = sqlx::query_scalar::<_, i32>(
-            "SELECT COUNT(*) FROM _migrations WHERE version = ?",
+/// A schema change and the SQL that reverts it
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    /// Name recorded in `_migrations`, prefixed with its zero-padded number
+    pub version: &'static str,
+    pub up: &'static str,
+    pub down: &'static str,
+}
+
+impl Migration {
+    /// Schema version the database is at once this migration is applied
+    pub fn number(&self) -> u32 {
+        self.version
+            .split('_')
+            .next()
+            .and_then(|prefix| prefix.parse().ok())
+            .unwrap_or(0)
+    }
+}
+
+/// Every migration this build knows, oldest first
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: "001_initial_schema",
+        up: include_str!("../../migrations/001_initial_schema.sql"),
+        down: include_str!("../../migrations/001_initial_schema.down.sql"),
+    },
+    Migration {
+        version: "002_add_indexes",
+        up: include_str!("../../migrations/002_add_indexes.sql"),
+        down: include_str!("../../migrations/002_add_indexes.down.sql"),
+    },
+    Migration {
+        version: "003_add_metadata",
+        up: include_str!("../../migrations/003_add_metadata.sql"),
+        down: include_str!("../../migrations/003_add_metadata.down.sql"),
+    },
+    Migration {
+        version: "004_add_dead_letter_events",
+        up: include_str!("../../migrations/004_add_dead_letter_events.sql"),
+        down: include_str!("../../migrations/004_add_dead_letter_events.down.sql"),
+    },
+    Migration {
+        version: "005_add_totp",
+        up: include_str!("../../migrations/005_add_totp.sql"),
+        down: include_str!("../../migrations/005_add_totp.down.sql"),
+    },
+    Migration {
+        version: "006_add_outbox",
+        up: include_str!("../../migrations/006_add_outbox.sql"),
+        down: include_str!("../../migrations/006_add_outbox.down.sql"),
+    },
+    Migration {
+        version: "007_hash_session_tokens",
+        up: include_str!("../../migrations/007_hash_session_tokens.sql"),
+        down: include_str!("../../migrations/007_hash_session_tokens.down.sql"),
+    },
+];
+
+/// Newest schema version this build can migrate to
+pub fn latest_version() -> u32 {
+    MIGRATIONS.last().map(Migration::number).unwrap_or(0)
+}
+
+pub async fn ensure_migrations_table(pool: &Pool<Sqlite>) -> AppResult<()> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS _migrations (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            version TEXT NOT NULL UNIQUE,
+            applied_at DATETIME DEFAULT CURRENT_TIMESTAMP
         )
-        .bind(version)
-        .fetch_one(pool)
-        .await?;
+        "#,
+    )
+    .execute(pool)
+    .await?;
+    Ok(())
+}
 
-        if applied == 0 {
-            info!("Applying migration: {}", version);
+/// Versions recorded as applied, refusing databases written by a newer build
+///
+/// A migration this build does not know means the schema has moved past what
+/// the code understands, and running against it could corrupt data.
+async fn applied_versions(pool: &Pool<Sqlite>) -> AppResult<HashSet<String>> {
+    let applied: HashSet<String> =
+        sqlx::query_scalar::<_, String>("SELECT version FROM _migrations")
+            .fetch_all(pool)
+            .await?
+            .into_iter()
+            .collect();
 
-            // Execute migration
-            sqlx::query(sql).execute(pool).await?;
+    let mut unknown: Vec<&String> = applied
+        .iter()
+        .filter(|version| !MIGRATIONS.iter().any(|m| m.version == version.as_str()))
+        .collect();
+    if !unknown.is_empty() {
+        unknown.sort();
+        return Err(AppError::Database {
+            message: format!(
+                "Database has migrations this build does not know ({}); latest supported version is {}",
+                unknown.into_iter().cloned().collect::<Vec<_>>().join(", "),
+                latest_version()
+            ),
+        });
+    }
+
+    Ok(applied)
+}
+
+/// Schema version of the database: the newest migration applied to it
+pub async fn current_version(pool: &Pool<Sqlite>) -> AppResult<u32> {
+    ensure_migrations_table(pool).await?;
+    let applied = applied_versions(pool).await?;
+    Ok(MIGRATIONS
+        .iter()
+        .filter(|m| applied.contains(m.version))
+        .map(Migration::number)
+        .max()
+        .unwrap_or(0))
+}
+
+pub async fn run_migrations(pool: &Pool<Sqlite>) -> AppResult<()> {
+    migrate_to(pool, latest_version()).await
+}
+
+/// Bring the schema to `target`, applying newer migrations or rolling back
+/// ones past it
+///
+/// Each migration runs in its own transaction together with its
+/// `_migrations` record, so a failure leaves the schema at the last
+/// completed version. Version 0 rolls back everything.
+pub async fn migrate_to(pool: &Pool<Sqlite>, target: u32) -> AppResult<()> {
+    if target > latest_version() {
+        return Err(AppError::Validation {
+            message: format!(
+                "Cannot migrate to version {}; latest supported version is {}",
+                target,
+                latest_version()
+            ),
+        });
+    }
 
-            // Record migration
-            sqlx::query("INSERT INTO _migrations (version) VALUES (?)")
-                .bind(version)
-                .execute(pool)
-                .await?;
+    ensure_migrations_table(pool).await?;
+    let applied = applied_versions(pool).await?;
 
-            info!("Migration {} applied successfully", version);
+    for migration in MIGRATIONS.iter().filter(|m| m.number() <= target) {
+        if applied.contains(migration.version) {
+            continue;
         }
+        info!("Applying migration: {}", migration.version);
+
+        let mut tx = pool.begin().await?;
+        sqlx::query(migration.up).execute(&mut *tx).await?;
+        sqlx::query("INSERT INTO _migrations (version) VALUES (?)")
+            .bind(migration.version)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        info!("Migration {} applied successfully", migration.version);
+    }
+
+    for migration in MIGRATIONS.iter().rev().filter(|m| m.number() > target) {
+        if !applied.contains(migration.version) {
+            continue;
+        }
+        info!("Rolling back migration: {}", migration.version);
+
+        let mut tx = pool.begin().await?;
+        sqlx::query(migration.down).execute(&mut *tx).await?;
+        sqlx::query("DELETE FROM _migrations WHERE version = ?")
+            .bind(migration.version)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        info!("Migration {} rolled back successfully", migration.version);
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn test_pool() -> Pool<Sqlite> {
+        SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap()
+    }
+
+    async fn table_exists(pool: &Pool<Sqlite>, name: &str) -> bool {
+        sqlx::query_scalar::<_, i32>(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?",
+        )
+        .bind(name)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+            == 1
+    }
+
+    #[tokio::test]
+    async fn test_migrations_apply_and_roll_back() {
+        let pool = test_pool().await;
+        run_migrations(&pool).await.unwrap();
+        assert_eq!(current_version(&pool).await.unwrap(), latest_version());
+        assert!(table_exists(&pool, "user_totp").await);
+
+        // Running again is a no-op
+        run_migrations(&pool).await.unwrap();
+
+        migrate_to(&pool, 3).await.unwrap();
+        assert_eq!(current_version(&pool).await.unwrap(), 3);
+        assert!(!table_exists(&pool, "user_totp").await);
+        assert!(!table_exists(&pool, "dead_letter_events").await);
+        assert!(table_exists(&pool, "audit_log").await);
+
+        // Rolled-back migrations can be applied again
+        run_migrations(&pool).await.unwrap();
+        assert!(table_exists(&pool, "user_totp").await);
+
+        migrate_to(&pool, 0).await.unwrap();
+        assert_eq!(current_version(&pool).await.unwrap(), 0);
+        assert!(!table_exists(&pool, "users").await);
+
+        assert!(matches!(
+            migrate_to(&pool, latest_version() + 1).await,
+            Err(AppError::Validation { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_newer_database_is_refused() {
+        let pool = test_pool().await;
+        run_migrations(&pool).await.unwrap();
+        sqlx::query("INSERT INTO _migrations (version) VALUES ('099_from_a_newer_build')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let error = run_migrations(&pool).await.unwrap_err();
+        assert!(error.to_string().contains("099_from_a_newer_build"));
+        assert!(migrate_to(&pool, 3).await.is_err());
+        assert!(current_version(&pool).await.is_err());
+
+        // Nothing was rolled back
+        assert!(table_exists(&pool, "user_totp").await);
+    }
+}
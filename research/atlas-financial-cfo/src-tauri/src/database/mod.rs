@@ -1,6 +1,7 @@
 pub mod connection;
 pub mod migrations;
 pub mod repositories;
+pub mod retry;
 
 use anyhow::Result;
 use sqlx::{Pool, Sqlite, SqlitePool};
@@ -12,6 +13,7 @@ use crate::error::{AppError, AppResult};
 
 pub use connection::*;
 pub use repositories::*;
+pub use retry::*;
 
 #[derive(Clone)]
 pub struct Database {
@@ -79,19 +81,6 @@ impl Database {
     pub async fn migrate(&self) -> AppResult<()> {
         info!("Running database migrations");
 
-        // Create migrations table if it doesn't exist
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS _migrations (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                version TEXT NOT NULL UNIQUE,
-                applied_at DATETIME DEFAULT CURRENT_TIMESTAMP
-            )
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
-
         // Run migrations in order
         migrations::run_migrations(&self.pool).await?;
 
@@ -99,6 +88,12 @@ impl Database {
         Ok(())
     }
 
+    /// Migrate to a specific schema version, rolling back newer migrations
+    pub async fn migrate_to(&self, version: u32) -> AppResult<()> {
+        info!("Migrating database to schema version {}", version);
+        migrations::migrate_to(&self.pool, version).await
+    }
+
     pub fn pool(&self) -> &Pool<Sqlite> {
         &self.pool
     }
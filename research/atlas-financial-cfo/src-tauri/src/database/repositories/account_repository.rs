@@ -1,8 +1,11 @@
-use sqlx::{Pool, Sqlite};
+use chrono::{DateTime, Utc};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Pool, Row, Sqlite};
 use std::collections::HashMap;
 
-use crate::domain::{Account, EntityId, Money, AccountType, Currency, Timestamp};
+use crate::domain::{Account, EntityId, Money, AccountType, Currency, Page, PageRequest, Timestamp};
 use crate::error::{AppError, AppResult};
+use crate::events::{AccountEvent, OutboxMessage, SqliteOutbox};
 
 #[derive(Clone)]
 pub struct AccountRepository {
@@ -21,30 +24,42 @@ impl AccountRepository {
             }
         })?;
 
-        sqlx::query(
-            r#"
-            INSERT INTO accounts (
-                id, user_id, name, account_type, balance_amount, balance_currency,
-                institution_name, account_number, routing_number, created_at,
-                updated_at, is_active, metadata
+        let message = OutboxMessage::new(&AccountEvent::AccountCreated {
+            account_id: account.id,
+            user_id: account.user_id,
+            name: account.name.clone(),
+            account_type: account.account_type,
+            initial_balance: account.balance,
+            institution_name: account.institution_name.clone(),
+            occurred_at: account.created_at,
+            version: 1,
+        })?;
+
+        SqliteOutbox::execute_with_event(&self.pool, &message, || {
+            sqlx::query(
+                r#"
+                INSERT INTO accounts (
+                    id, user_id, name, account_type, balance_amount, balance_currency,
+                    institution_name, account_number, routing_number, created_at,
+                    updated_at, is_active, metadata
+                )
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
             )
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-            "#,
-        )
-        .bind(account.id.to_string())
-        .bind(account.user_id.to_string())
-        .bind(&account.name)
-        .bind(format!("{:?}", account.account_type))
-        .bind(account.balance.amount().to_string())
-        .bind(format!("{:?}", account.balance.currency()))
-        .bind(&account.institution_name)
-        .bind(&account.account_number)
-        .bind(&account.routing_number)
-        .bind(account.created_at.as_datetime())
-        .bind(account.updated_at.as_datetime())
-        .bind(account.is_active)
-        .bind(metadata_json)
-        .execute(&self.pool)
+            .bind(account.id.to_string())
+            .bind(account.user_id.to_string())
+            .bind(&account.name)
+            .bind(format!("{:?}", account.account_type))
+            .bind(account.balance.amount().to_string())
+            .bind(format!("{:?}", account.balance.currency()))
+            .bind(&account.institution_name)
+            .bind(&account.account_number)
+            .bind(&account.routing_number)
+            .bind(account.created_at.as_datetime())
+            .bind(account.updated_at.as_datetime())
+            .bind(account.is_active)
+            .bind(&metadata_json)
+        })
         .await?;
 
         Ok(())
@@ -66,26 +81,8 @@ impl AccountRepository {
 
         match row {
             Some(row) => {
-                let account_type = match row.account_type.as_str() {
-                    "Checking" => AccountType::Checking,
-                    "Savings" => AccountType::Savings,
-                    "Credit" => AccountType::Credit,
-                    "Investment" => AccountType::Investment,
-                    "Retirement" => AccountType::Retirement,
-                    "Loan" => AccountType::Loan,
-                    "Mortgage" => AccountType::Mortgage,
-                    "Cash" => AccountType::Cash,
-                    _ => AccountType::Other,
-                };
-
-                let currency = match row.balance_currency.as_str() {
-                    "USD" => Currency::USD,
-                    "EUR" => Currency::EUR,
-                    "GBP" => Currency::GBP,
-                    "CAD" => Currency::CAD,
-                    "AUD" => Currency::AUD,
-                    _ => Currency::USD,
-                };
+                let account_type = account_type_from_db(&row.account_type);
+                let currency = currency_from_db(&row.balance_currency);
 
                 let balance_amount = row.balance_amount.parse().map_err(|e| {
                     AppError::Database {
@@ -142,26 +139,8 @@ impl AccountRepository {
 
         let mut accounts = Vec::new();
         for row in rows {
-            let account_type = match row.account_type.as_str() {
-                "Checking" => AccountType::Checking,
-                "Savings" => AccountType::Savings,
-                "Credit" => AccountType::Credit,
-                "Investment" => AccountType::Investment,
-                "Retirement" => AccountType::Retirement,
-                "Loan" => AccountType::Loan,
-                "Mortgage" => AccountType::Mortgage,
-                "Cash" => AccountType::Cash,
-                _ => AccountType::Other,
-            };
-
-            let currency = match row.balance_currency.as_str() {
-                "USD" => Currency::USD,
-                "EUR" => Currency::EUR,
-                "GBP" => Currency::GBP,
-                "CAD" => Currency::CAD,
-                "AUD" => Currency::AUD,
-                _ => Currency::USD,
-            };
+            let account_type = account_type_from_db(&row.account_type);
+            let currency = currency_from_db(&row.balance_currency);
 
             let balance_amount = row.balance_amount.parse().map_err(|e| {
                 AppError::Database {
@@ -201,6 +180,38 @@ impl AccountRepository {
         Ok(accounts)
     }
 
+    /// One page of a user's active accounts, ordered by name
+    ///
+    /// `total` counts every active account, not just those on the page.
+    pub async fn find_page_by_user_id(&self, user_id: EntityId, page: PageRequest) -> AppResult<Page<Account>> {
+        let total: i64 = sqlx::query("SELECT COUNT(*) FROM accounts WHERE user_id = ? AND is_active = 1")
+            .bind(user_id.to_string())
+            .fetch_one(&self.pool)
+            .await?
+            .try_get(0)?;
+
+        let (limit, offset) = page.sql_bounds();
+        let rows = sqlx::query(
+            r#"
+            SELECT id, user_id, name, account_type, balance_amount, balance_currency,
+                   institution_name, account_number, routing_number, created_at,
+                   updated_at, is_active, metadata
+            FROM accounts
+            WHERE user_id = ? AND is_active = 1
+            ORDER BY name ASC, id ASC
+            LIMIT ? OFFSET ?
+            "#,
+        )
+        .bind(user_id.to_string())
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let accounts = rows.iter().map(account_from_row).collect::<AppResult<Vec<_>>>()?;
+        Ok(Page::new(accounts, total.max(0) as u64, page))
+    }
+
     pub async fn update(&self, account: &Account) -> AppResult<()> {
         let metadata_json = serde_json::to_string(&account.metadata).map_err(|e| {
             AppError::Database {
@@ -208,26 +219,36 @@ impl AccountRepository {
             }
         })?;
 
-        let affected = sqlx::query(
-            r#"
-            UPDATE accounts
-            SET name = ?, balance_amount = ?, balance_currency = ?,
-                institution_name = ?, account_number = ?, routing_number = ?,
-                updated_at = ?, is_active = ?, metadata = ?
-            WHERE id = ?
-            "#,
-        )
-        .bind(&account.name)
-        .bind(account.balance.amount().to_string())
-        .bind(format!("{:?}", account.balance.currency()))
-        .bind(&account.institution_name)
-        .bind(&account.account_number)
-        .bind(&account.routing_number)
-        .bind(account.updated_at.as_datetime())
-        .bind(account.is_active)
-        .bind(metadata_json)
-        .bind(account.id.to_string())
-        .execute(&self.pool)
+        let message = OutboxMessage::new(&AccountEvent::AccountUpdated {
+            account_id: account.id,
+            user_id: account.user_id,
+            name: Some(account.name.clone()),
+            institution_name: account.institution_name.clone(),
+            occurred_at: account.updated_at,
+            version: 1,
+        })?;
+
+        let affected = SqliteOutbox::execute_with_event(&self.pool, &message, || {
+            sqlx::query(
+                r#"
+                UPDATE accounts
+                SET name = ?, balance_amount = ?, balance_currency = ?,
+                    institution_name = ?, account_number = ?, routing_number = ?,
+                    updated_at = ?, is_active = ?, metadata = ?
+                WHERE id = ?
+                "#,
+            )
+            .bind(&account.name)
+            .bind(account.balance.amount().to_string())
+            .bind(format!("{:?}", account.balance.currency()))
+            .bind(&account.institution_name)
+            .bind(&account.account_number)
+            .bind(&account.routing_number)
+            .bind(account.updated_at.as_datetime())
+            .bind(account.is_active)
+            .bind(&metadata_json)
+            .bind(account.id.to_string())
+        })
         .await?
         .rows_affected();
 
@@ -241,11 +262,22 @@ impl AccountRepository {
     }
 
     pub async fn delete(&self, id: EntityId) -> AppResult<()> {
-        let affected = sqlx::query("DELETE FROM accounts WHERE id = ?")
-            .bind(id.to_string())
-            .execute(&self.pool)
-            .await?
-            .rows_affected();
+        let account = self.find_by_id(id).await?.ok_or_else(|| AppError::NotFound {
+            resource: "Account".to_string(),
+        })?;
+
+        let message = OutboxMessage::new(&AccountEvent::AccountDeleted {
+            account_id: account.id,
+            user_id: account.user_id,
+            occurred_at: Timestamp::now(),
+            version: 1,
+        })?;
+
+        let affected = SqliteOutbox::execute_with_event(&self.pool, &message, || {
+            sqlx::query("DELETE FROM accounts WHERE id = ?").bind(id.to_string())
+        })
+        .await?
+        .rows_affected();
 
         if affected == 0 {
             return Err(AppError::NotFound {
@@ -256,3 +288,147 @@ impl AccountRepository {
         Ok(())
     }
 }
+
+fn account_type_from_db(value: &str) -> AccountType {
+    match value {
+        "Checking" => AccountType::Checking,
+        "Savings" => AccountType::Savings,
+        "Credit" => AccountType::Credit,
+        "Investment" => AccountType::Investment,
+        "Retirement" => AccountType::Retirement,
+        "Loan" => AccountType::Loan,
+        "Mortgage" => AccountType::Mortgage,
+        "Cash" => AccountType::Cash,
+        _ => AccountType::Other,
+    }
+}
+
+fn currency_from_db(value: &str) -> Currency {
+    match value {
+        "USD" => Currency::USD,
+        "EUR" => Currency::EUR,
+        "GBP" => Currency::GBP,
+        "CAD" => Currency::CAD,
+        "AUD" => Currency::AUD,
+        _ => Currency::USD,
+    }
+}
+
+fn parse_entity_id(value: &str) -> AppResult<EntityId> {
+    let uuid = uuid::Uuid::parse_str(value).map_err(|e| AppError::Database {
+        message: format!("Invalid UUID: {}", e),
+    })?;
+    Ok(EntityId::from_uuid(uuid))
+}
+
+fn account_from_row(row: &SqliteRow) -> AppResult<Account> {
+    let balance_amount = row.try_get::<String, _>("balance_amount")?.parse().map_err(|e| {
+        AppError::Database {
+            message: format!("Invalid balance amount: {}", e),
+        }
+    })?;
+    let currency = currency_from_db(row.try_get("balance_currency")?);
+    let metadata: HashMap<String, String> = row
+        .try_get::<Option<String>, _>("metadata")?
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+
+    Ok(Account {
+        id: parse_entity_id(row.try_get("id")?)?,
+        user_id: parse_entity_id(row.try_get("user_id")?)?,
+        name: row.try_get("name")?,
+        account_type: account_type_from_db(row.try_get("account_type")?),
+        balance: Money::new(balance_amount, currency),
+        institution_name: row.try_get("institution_name")?,
+        account_number: row.try_get("account_number")?,
+        routing_number: row.try_get("routing_number")?,
+        created_at: Timestamp::from_datetime(row.try_get::<DateTime<Utc>, _>("created_at")?),
+        updated_at: Timestamp::from_datetime(row.try_get::<DateTime<Utc>, _>("updated_at")?),
+        is_active: row.try_get("is_active")?,
+        metadata,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::UserRepository;
+    use crate::domain::User;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn test_pool() -> Pool<Sqlite> {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        for migration in [
+            include_str!("../../../migrations/001_initial_schema.sql"),
+            include_str!("../../../migrations/003_add_metadata.sql"),
+            include_str!("../../../migrations/006_add_outbox.sql"),
+        ] {
+            sqlx::query(migration).execute(&pool).await.unwrap();
+        }
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_page_total_counts_all_accounts_while_items_respect_limit() {
+        let pool = test_pool().await;
+        let user = User::new("casey".to_string(), "casey@example.com".to_string(), "hash".to_string());
+        UserRepository::new(pool.clone()).create(&user).await.unwrap();
+
+        let repository = AccountRepository::new(pool);
+        for name in ["Brokerage", "Checking", "Emergency Fund", "Mortgage", "Savings"] {
+            let account = Account::new(user.id, name.to_string(), AccountType::Checking, Currency::USD, None);
+            repository.create(&account).await.unwrap();
+        }
+
+        let first = repository
+            .find_page_by_user_id(user.id, PageRequest::new(Some(2), None))
+            .await
+            .unwrap();
+        assert_eq!(first.total, 5);
+        assert_eq!((first.limit, first.offset), (2, 0));
+        let names: Vec<&str> = first.items.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["Brokerage", "Checking"]);
+        assert!(first.has_more());
+
+        let last = repository
+            .find_page_by_user_id(user.id, PageRequest::new(Some(2), Some(4)))
+            .await
+            .unwrap();
+        assert_eq!(last.total, 5);
+        assert_eq!(last.items.len(), 1);
+        assert_eq!(last.items[0].name, "Savings");
+        assert!(!last.has_more());
+
+        // Limits are clamped so a caller cannot ask for an unbounded page
+        assert_eq!(PageRequest::new(Some(0), None).limit, 1);
+        assert_eq!(PageRequest::new(Some(10_000), None).limit, crate::domain::MAX_PAGE_LIMIT);
+    }
+
+    #[tokio::test]
+    async fn test_each_write_enqueues_its_event() {
+        let pool = test_pool().await;
+        let user = User::new("robin".to_string(), "robin@example.com".to_string(), "hash".to_string());
+        UserRepository::new(pool.clone()).create(&user).await.unwrap();
+
+        let repository = AccountRepository::new(pool.clone());
+        let mut account = Account::new(user.id, "Checking".to_string(), AccountType::Checking, Currency::USD, None);
+        repository.create(&account).await.unwrap();
+        account.name = "Joint Checking".to_string();
+        repository.update(&account).await.unwrap();
+        repository.delete(account.id).await.unwrap();
+
+        // A write that matches no row records no event
+        assert!(repository.update(&account).await.is_err());
+
+        let pending = SqliteOutbox::new(pool).pending(10).await.unwrap();
+        let event_types: Vec<&str> = pending.iter().map(|entry| entry.event_type.as_str()).collect();
+        assert_eq!(event_types, vec!["AccountCreated", "AccountUpdated", "AccountDeleted"]);
+        assert!(pending
+            .iter()
+            .all(|entry| entry.aggregate_id == account.id && entry.aggregate_type == "Account"));
+    }
+}
@@ -2,8 +2,10 @@ pub mod account_repository;
 pub mod transaction_repository;
 pub mod user_repository;
 pub mod event_repository;
+pub mod totp_repository;
 
 pub use account_repository::*;
 pub use transaction_repository::*;
 pub use user_repository::*;
 pub use event_repository::*;
+pub use totp_repository::*;
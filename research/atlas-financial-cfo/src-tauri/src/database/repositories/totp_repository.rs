@@ -0,0 +1,84 @@
+use sqlx::{Pool, Row, Sqlite};
+
+use crate::domain::EntityId;
+use crate::database::retry_on_busy;
+use crate::error::AppResult;
+
+/// A user's TOTP shared secret and verification state
+#[derive(Debug, Clone)]
+pub struct TotpEnrollment {
+    pub user_id: EntityId,
+    pub secret: String,
+    pub enabled: bool,
+    pub last_used_step: Option<u64>,
+}
+
+#[derive(Clone)]
+pub struct TotpRepository {
+    pool: Pool<Sqlite>,
+}
+
+impl TotpRepository {
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+
+    /// Store a new, not yet verified secret, replacing any pending enrollment
+    pub async fn save_pending(&self, user_id: EntityId, secret: &str) -> AppResult<()> {
+        retry_on_busy(|| {
+            sqlx::query(
+                r#"
+                INSERT OR REPLACE INTO user_totp (user_id, secret, enabled, last_used_step)
+                VALUES (?, ?, 0, NULL)
+                "#,
+            )
+            .bind(user_id.to_string())
+            .bind(secret)
+            .execute(&self.pool)
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn find_by_user(&self, user_id: EntityId) -> AppResult<Option<TotpEnrollment>> {
+        let row = sqlx::query("SELECT secret, enabled, last_used_step FROM user_totp WHERE user_id = ?")
+            .bind(user_id.to_string())
+            .fetch_optional(&self.pool)
+            .await?;
+
+        row.map(|row| {
+            Ok(TotpEnrollment {
+                user_id,
+                secret: row.try_get("secret")?,
+                enabled: row.try_get("enabled")?,
+                last_used_step: row.try_get::<Option<i64>, _>("last_used_step")?.map(|step| step as u64),
+            })
+        })
+        .transpose()
+    }
+
+    /// Record `step` as used and enable the enrollment
+    ///
+    /// Returns `false` when a code for this or a later step was already
+    /// accepted, so concurrent logins cannot both spend the same code.
+    pub async fn mark_step_used(&self, user_id: EntityId, step: u64) -> AppResult<bool> {
+        let affected = retry_on_busy(|| {
+            sqlx::query(
+                r#"
+                UPDATE user_totp
+                SET enabled = 1, last_used_step = ?
+                WHERE user_id = ? AND (last_used_step IS NULL OR last_used_step < ?)
+                "#,
+            )
+            .bind(step as i64)
+            .bind(user_id.to_string())
+            .bind(step as i64)
+            .execute(&self.pool)
+        })
+        .await?
+        .rows_affected();
+
+        Ok(affected > 0)
+    }
+}
@@ -3,6 +3,7 @@ use std::collections::HashMap;
 
 use crate::domain::{Transaction, EntityId, Money, TransactionType, Currency, Timestamp, TransactionFilter};
 use crate::error::{AppError, AppResult};
+use crate::events::{OutboxMessage, SqliteOutbox, TransactionEvent};
 
 #[derive(Clone)]
 pub struct TransactionRepository {
@@ -27,34 +28,47 @@ impl TransactionRepository {
             }
         })?;
 
-        sqlx::query(
-            r#"
-            INSERT INTO transactions (
-                id, user_id, account_id, transaction_type, amount_value, amount_currency,
-                description, category, subcategory, tags, transaction_date,
-                created_at, updated_at, reconciled, reference_number, counterparty, metadata
+        let message = OutboxMessage::new(&TransactionEvent::TransactionCreated {
+            transaction_id: transaction.id,
+            user_id: transaction.user_id,
+            account_id: transaction.account_id,
+            transaction_type: transaction.transaction_type,
+            amount: transaction.amount,
+            description: transaction.description.clone(),
+            category: transaction.category.clone(),
+            occurred_at: transaction.created_at,
+            version: 1,
+        })?;
+
+        SqliteOutbox::execute_with_event(&self.pool, &message, || {
+            sqlx::query(
+                r#"
+                INSERT INTO transactions (
+                    id, user_id, account_id, transaction_type, amount_value, amount_currency,
+                    description, category, subcategory, tags, transaction_date,
+                    created_at, updated_at, reconciled, reference_number, counterparty, metadata
+                )
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
             )
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-            "#,
-        )
-        .bind(transaction.id.to_string())
-        .bind(transaction.user_id.to_string())
-        .bind(transaction.account_id.to_string())
-        .bind(format!("{:?}", transaction.transaction_type))
-        .bind(transaction.amount.amount().to_string())
-        .bind(format!("{:?}", transaction.amount.currency()))
-        .bind(&transaction.description)
-        .bind(&transaction.category)
-        .bind(&transaction.subcategory)
-        .bind(tags_json)
-        .bind(transaction.transaction_date.as_datetime())
-        .bind(transaction.created_at.as_datetime())
-        .bind(transaction.updated_at.as_datetime())
-        .bind(transaction.reconciled)
-        .bind(&transaction.reference_number)
-        .bind(&transaction.counterparty)
-        .bind(metadata_json)
-        .execute(&self.pool)
+            .bind(transaction.id.to_string())
+            .bind(transaction.user_id.to_string())
+            .bind(transaction.account_id.to_string())
+            .bind(format!("{:?}", transaction.transaction_type))
+            .bind(transaction.amount.amount().to_string())
+            .bind(format!("{:?}", transaction.amount.currency()))
+            .bind(&transaction.description)
+            .bind(&transaction.category)
+            .bind(&transaction.subcategory)
+            .bind(&tags_json)
+            .bind(transaction.transaction_date.as_datetime())
+            .bind(transaction.created_at.as_datetime())
+            .bind(transaction.updated_at.as_datetime())
+            .bind(transaction.reconciled)
+            .bind(&transaction.reference_number)
+            .bind(&transaction.counterparty)
+            .bind(&metadata_json)
+        })
         .await?;
 
         Ok(())
@@ -191,27 +205,38 @@ impl TransactionRepository {
             }
         })?;
 
-        let affected = sqlx::query(
-            r#"
-            UPDATE transactions
-            SET description = ?, category = ?, subcategory = ?, tags = ?,
-                transaction_date = ?, updated_at = ?, reconciled = ?,
-                reference_number = ?, counterparty = ?, metadata = ?
-            WHERE id = ?
-            "#,
-        )
-        .bind(&transaction.description)
-        .bind(&transaction.category)
-        .bind(&transaction.subcategory)
-        .bind(tags_json)
-        .bind(transaction.transaction_date.as_datetime())
-        .bind(transaction.updated_at.as_datetime())
-        .bind(transaction.reconciled)
-        .bind(&transaction.reference_number)
-        .bind(&transaction.counterparty)
-        .bind(metadata_json)
-        .bind(transaction.id.to_string())
-        .execute(&self.pool)
+        let message = OutboxMessage::new(&TransactionEvent::TransactionUpdated {
+            transaction_id: transaction.id,
+            user_id: transaction.user_id,
+            description: Some(transaction.description.clone()),
+            category: transaction.category.clone(),
+            tags: Some(transaction.tags.clone()),
+            occurred_at: transaction.updated_at,
+            version: 1,
+        })?;
+
+        let affected = SqliteOutbox::execute_with_event(&self.pool, &message, || {
+            sqlx::query(
+                r#"
+                UPDATE transactions
+                SET description = ?, category = ?, subcategory = ?, tags = ?,
+                    transaction_date = ?, updated_at = ?, reconciled = ?,
+                    reference_number = ?, counterparty = ?, metadata = ?
+                WHERE id = ?
+                "#,
+            )
+            .bind(&transaction.description)
+            .bind(&transaction.category)
+            .bind(&transaction.subcategory)
+            .bind(&tags_json)
+            .bind(transaction.transaction_date.as_datetime())
+            .bind(transaction.updated_at.as_datetime())
+            .bind(transaction.reconciled)
+            .bind(&transaction.reference_number)
+            .bind(&transaction.counterparty)
+            .bind(&metadata_json)
+            .bind(transaction.id.to_string())
+        })
         .await?
         .rows_affected();
 
@@ -225,11 +250,23 @@ impl TransactionRepository {
     }
 
     pub async fn delete(&self, id: EntityId) -> AppResult<()> {
-        let affected = sqlx::query("DELETE FROM transactions WHERE id = ?")
-            .bind(id.to_string())
-            .execute(&self.pool)
-            .await?
-            .rows_affected();
+        let transaction = self.find_by_id(id).await?.ok_or_else(|| AppError::NotFound {
+            resource: "Transaction".to_string(),
+        })?;
+
+        let message = OutboxMessage::new(&TransactionEvent::TransactionDeleted {
+            transaction_id: transaction.id,
+            user_id: transaction.user_id,
+            account_id: transaction.account_id,
+            occurred_at: Timestamp::now(),
+            version: 1,
+        })?;
+
+        let affected = SqliteOutbox::execute_with_event(&self.pool, &message, || {
+            sqlx::query("DELETE FROM transactions WHERE id = ?").bind(id.to_string())
+        })
+        .await?
+        .rows_affected();
 
         if affected == 0 {
             return Err(AppError::NotFound {
@@ -3,6 +3,7 @@ use secrecy::Secret;
 use sqlx::{Pool, Sqlite};
 
 use crate::domain::{User, EntityId, Timestamp};
+use crate::database::retry_on_busy;
 use crate::error::{AppError, AppResult};
 
 #[derive(Clone)]
@@ -16,20 +17,22 @@ impl UserRepository {
     }
 
     pub async fn create(&self, user: &User) -> AppResult<()> {
-        sqlx::query(
-            r#"
-            INSERT INTO users (id, username, email, password_hash, created_at, updated_at, is_active)
-            VALUES (?, ?, ?, ?, ?, ?, ?)
-            "#,
-        )
-        .bind(user.id.to_string())
-        .bind(&user.username)
-        .bind(&user.email)
-        .bind(user.password_hash.expose_secret())
-        .bind(user.created_at.as_datetime())
-        .bind(user.updated_at.as_datetime())
-        .bind(user.is_active)
-        .execute(&self.pool)
+        retry_on_busy(|| {
+            sqlx::query(
+                r#"
+                INSERT INTO users (id, username, email, password_hash, created_at, updated_at, is_active)
+                VALUES (?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(user.id.to_string())
+            .bind(&user.username)
+            .bind(&user.email)
+            .bind(user.password_hash.expose_secret())
+            .bind(user.created_at.as_datetime())
+            .bind(user.updated_at.as_datetime())
+            .bind(user.is_active)
+            .execute(&self.pool)
+        })
         .await?;
 
         Ok(())
@@ -138,22 +141,24 @@ impl UserRepository {
     }
 
     pub async fn update(&self, user: &User) -> AppResult<()> {
-        let affected = sqlx::query(
-            r#"
-            UPDATE users
-            SET username = ?, email = ?, password_hash = ?, updated_at = ?,
-                last_login = ?, is_active = ?
-            WHERE id = ?
-            "#,
-        )
-        .bind(&user.username)
-        .bind(&user.email)
-        .bind(user.password_hash.expose_secret())
-        .bind(user.updated_at.as_datetime())
-        .bind(user.last_login.map(|t| t.as_datetime()))
-        .bind(user.is_active)
-        .bind(user.id.to_string())
-        .execute(&self.pool)
+        let affected = retry_on_busy(|| {
+            sqlx::query(
+                r#"
+                UPDATE users
+                SET username = ?, email = ?, password_hash = ?, updated_at = ?,
+                    last_login = ?, is_active = ?
+                WHERE id = ?
+                "#,
+            )
+            .bind(&user.username)
+            .bind(&user.email)
+            .bind(user.password_hash.expose_secret())
+            .bind(user.updated_at.as_datetime())
+            .bind(user.last_login.map(|t| t.as_datetime()))
+            .bind(user.is_active)
+            .bind(user.id.to_string())
+            .execute(&self.pool)
+        })
         .await?
         .rows_affected();
 
@@ -167,11 +172,13 @@ impl UserRepository {
     }
 
     pub async fn delete(&self, id: EntityId) -> AppResult<()> {
-        let affected = sqlx::query("DELETE FROM users WHERE id = ?")
-            .bind(id.to_string())
-            .execute(&self.pool)
-            .await?
-            .rows_affected();
+        let affected = retry_on_busy(|| {
+            sqlx::query("DELETE FROM users WHERE id = ?")
+                .bind(id.to_string())
+                .execute(&self.pool)
+        })
+        .await?
+        .rows_affected();
 
         if affected == 0 {
             return Err(AppError::NotFound {
@@ -0,0 +1,185 @@
+use std::future::Future;
+use std::time::Duration;
+use tracing::warn;
+
+/// Primary result code for "the database file is locked"
+const SQLITE_BUSY: i32 = 5;
+
+/// Primary result code for "a table in the database is locked"
+const SQLITE_LOCKED: i32 = 6;
+
+/// Backoff applied when a write finds the database busy
+///
+/// WAL mode allows a single writer at a time, so concurrent Tauri commands
+/// can briefly lock each other out. These errors clear on their own and are
+/// retried; every other error is returned straight away.
+#[derive(Debug, Clone)]
+pub struct BusyRetryPolicy {
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for BusyRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(20),
+            max_backoff: Duration::from_millis(500),
+        }
+    }
+}
+
+impl BusyRetryPolicy {
+    /// Delay to wait after the given (1-based) busy attempt
+    pub fn backoff_for(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff
+            .checked_mul(factor)
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff))
+    }
+}
+
+/// Whether `err` is SQLite reporting a busy or locked database
+///
+/// SQLite reports extended result codes, whose low byte is the primary code,
+/// so `SQLITE_BUSY_SNAPSHOT` and friends are recognised as well.
+pub fn is_busy_error(err: &sqlx::Error) -> bool {
+    match err {
+        sqlx::Error::Database(db_error) => db_error
+            .code()
+            .and_then(|code| code.parse::<i32>().ok())
+            .is_some_and(|code| matches!(code & 0xff, SQLITE_BUSY | SQLITE_LOCKED)),
+        _ => false,
+    }
+}
+
+/// Run a write with the default [`BusyRetryPolicy`]
+pub async fn retry_on_busy<T, F, Fut>(operation: F) -> Result<T, sqlx::Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, sqlx::Error>>,
+{
+    retry_on_busy_with(&BusyRetryPolicy::default(), operation).await
+}
+
+/// Run a write, retrying it while the database is busy
+///
+/// `operation` is called again for each attempt, so it must rebuild the query.
+pub async fn retry_on_busy_with<T, F, Fut>(policy: &BusyRetryPolicy, mut operation: F) -> Result<T, sqlx::Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, sqlx::Error>>,
+{
+    let mut attempt = 1;
+    loop {
+        match operation().await {
+            Err(err) if attempt < policy.max_attempts && is_busy_error(&err) => {
+                let backoff = policy.backoff_for(attempt);
+                warn!("Database busy on attempt {}, retrying in {:?}: {}", attempt, backoff, err);
+                tokio::time::sleep(backoff).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions};
+    use sqlx::{Pool, Sqlite};
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Pool on a scratch database file that fails immediately when locked
+    async fn file_pool(path: &PathBuf) -> Pool<Sqlite> {
+        let options = SqliteConnectOptions::new()
+            .filename(path)
+            .create_if_missing(true)
+            .journal_mode(SqliteJournalMode::Wal)
+            .busy_timeout(Duration::ZERO);
+        SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .unwrap()
+    }
+
+    fn fast_policy() -> BusyRetryPolicy {
+        BusyRetryPolicy {
+            max_attempts: 20,
+            initial_backoff: Duration::from_millis(5),
+            max_backoff: Duration::from_millis(20),
+        }
+    }
+
+    #[test]
+    fn test_backoff_grows_and_caps() {
+        let policy = BusyRetryPolicy::default();
+        assert_eq!(policy.backoff_for(1), Duration::from_millis(20));
+        assert_eq!(policy.backoff_for(3), Duration::from_millis(80));
+        assert_eq!(policy.backoff_for(40), Duration::from_millis(500));
+    }
+
+    #[tokio::test]
+    async fn test_busy_write_is_retried_until_the_lock_clears() {
+        let path = std::env::temp_dir().join(format!("atlas-busy-{}.db", uuid::Uuid::new_v4()));
+        let writer = file_pool(&path).await;
+        let contender = file_pool(&path).await;
+        sqlx::query("CREATE TABLE notes (body TEXT NOT NULL)")
+            .execute(&writer)
+            .await
+            .unwrap();
+
+        // Hold the write lock on one connection while the other tries to write
+        let mut lock = writer.acquire().await.unwrap();
+        sqlx::query("BEGIN IMMEDIATE").execute(&mut *lock).await.unwrap();
+
+        let busy = sqlx::query("INSERT INTO notes (body) VALUES ('first')")
+            .execute(&contender)
+            .await
+            .unwrap_err();
+        assert!(is_busy_error(&busy));
+
+        let release = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            sqlx::query("COMMIT").execute(&mut *lock).await.unwrap();
+        });
+
+        let attempts = AtomicU32::new(0);
+        let result = retry_on_busy_with(&fast_policy(), || {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            sqlx::query("INSERT INTO notes (body) VALUES ('second')").execute(&contender)
+        })
+        .await;
+        release.await.unwrap();
+
+        assert_eq!(result.unwrap().rows_affected(), 1);
+        assert!(attempts.load(Ordering::SeqCst) > 1);
+
+        writer.close().await;
+        contender.close().await;
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_other_errors_are_not_retried() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+
+        let attempts = AtomicU32::new(0);
+        let result = retry_on_busy_with(&fast_policy(), || {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            sqlx::query("INSERT INTO missing_table (body) VALUES ('x')").execute(&pool)
+        })
+        .await;
+
+        assert!(!is_busy_error(&result.unwrap_err()));
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+}
@@ -1,9 +1,13 @@
 pub mod account;
+pub mod pagination;
+pub mod roundup;
 pub mod transaction;
 pub mod user;
 pub mod value_objects;
 
 pub use account::*;
+pub use pagination::*;
+pub use roundup::*;
 pub use transaction::*;
 pub use user::*;
 pub use value_objects::*;
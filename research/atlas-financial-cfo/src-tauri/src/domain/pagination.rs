@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+
+/// Page size used when the caller does not ask for one
+pub const DEFAULT_PAGE_LIMIT: u32 = 50;
+
+/// Largest page a caller may ask for
+pub const MAX_PAGE_LIMIT: u32 = 200;
+
+/// Window into a result set requested by the caller
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PageRequest {
+    pub limit: u32,
+    pub offset: u64,
+}
+
+impl PageRequest {
+    /// Limit clamped to `1..=MAX_PAGE_LIMIT`, so responses stay bounded
+    pub fn new(limit: Option<u32>, offset: Option<u64>) -> Self {
+        Self {
+            limit: limit.unwrap_or(DEFAULT_PAGE_LIMIT).clamp(1, MAX_PAGE_LIMIT),
+            offset: offset.unwrap_or(0),
+        }
+    }
+
+    /// Limit and offset as SQLite `LIMIT ? OFFSET ?` parameters
+    pub fn sql_bounds(&self) -> (i64, i64) {
+        (
+            i64::from(self.limit),
+            i64::try_from(self.offset).unwrap_or(i64::MAX),
+        )
+    }
+}
+
+impl Default for PageRequest {
+    fn default() -> Self {
+        Self::new(None, None)
+    }
+}
+
+/// One page of a larger result set
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Number of items across every page
+    pub total: u64,
+    pub limit: u32,
+    pub offset: u64,
+}
+
+impl<T> Page<T> {
+    pub fn new(items: Vec<T>, total: u64, request: PageRequest) -> Self {
+        Self {
+            items,
+            total,
+            limit: request.limit,
+            offset: request.offset,
+        }
+    }
+
+    /// Whether items remain after this page
+    pub fn has_more(&self) -> bool {
+        self.offset.saturating_add(self.items.len() as u64) < self.total
+    }
+}
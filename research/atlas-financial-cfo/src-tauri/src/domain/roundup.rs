@@ -0,0 +1,171 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use super::account::Account;
+use super::transaction::{Transaction, TransactionType};
+use super::value_objects::Money;
+
+/// Tag carried by every generated round-up transfer
+pub const ROUNDUP_TAG: &str = "roundup";
+
+/// Metadata key holding the ID of the purchase a round-up was taken from
+pub const ROUNDUP_SOURCE_KEY: &str = "roundup_source_transaction_id";
+
+/// Amount each purchase is rounded up to a multiple of
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RoundUpBase {
+    #[default]
+    NearestDollar,
+    NearestFiveDollars,
+}
+
+impl RoundUpBase {
+    pub fn increment(&self) -> Decimal {
+        match self {
+            RoundUpBase::NearestDollar => Decimal::ONE,
+            RoundUpBase::NearestFiveDollars => Decimal::from(5),
+        }
+    }
+
+    /// Spare change needed to bring `amount` up to the next multiple of the
+    /// base; zero when it already is one
+    pub fn roundup(&self, amount: Decimal) -> Decimal {
+        let remainder = amount.abs() % self.increment();
+        if remainder.is_zero() {
+            Decimal::ZERO
+        } else {
+            self.increment() - remainder
+        }
+    }
+}
+
+/// Transfers setting aside the spare change of each purchase in `target_account`
+///
+/// Only outflows belonging to the target account's owner, in its currency and
+/// from other accounts count as purchases. Purchases that are already round
+/// produce no transfer.
+pub fn compute_roundups(
+    transactions: &[Transaction],
+    target_account: &Account,
+    base: RoundUpBase,
+) -> Vec<Transaction> {
+    let currency = target_account.balance.currency();
+
+    transactions
+        .iter()
+        .filter(|purchase| {
+            purchase.transaction_type.is_outflow()
+                && purchase.user_id == target_account.user_id
+                && purchase.account_id != target_account.id
+                && purchase.amount.currency() == currency
+        })
+        .filter_map(|purchase| {
+            let spare_change = base.roundup(purchase.amount.amount());
+            if spare_change.is_zero() {
+                return None;
+            }
+
+            let mut transfer = Transaction::new(
+                target_account.user_id,
+                target_account.id,
+                TransactionType::Transfer,
+                Money::new(spare_change, currency),
+                format!("Round-up: {}", purchase.description),
+                Some(purchase.transaction_date),
+            );
+            transfer.tags.push(ROUNDUP_TAG.to_string());
+            transfer
+                .metadata
+                .insert(ROUNDUP_SOURCE_KEY.to_string(), purchase.id.to_string());
+            Some(transfer)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{AccountType, Currency, EntityId};
+    use std::str::FromStr;
+
+    fn usd(amount: &str) -> Money {
+        Money::new(Decimal::from_str(amount).unwrap(), Currency::USD)
+    }
+
+    fn purchase(user_id: EntityId, account_id: EntityId, amount: &str) -> Transaction {
+        Transaction::new(
+            user_id,
+            account_id,
+            TransactionType::Debit,
+            usd(amount),
+            "Coffee".to_string(),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_purchases_round_up_to_the_next_dollar() {
+        let user_id = EntityId::new();
+        let checking = EntityId::new();
+        let savings = Account::new(
+            user_id,
+            "Savings".to_string(),
+            AccountType::Savings,
+            Currency::USD,
+            None,
+        );
+
+        let spent = purchase(user_id, checking, "4.30");
+        let round = purchase(user_id, checking, "12.00");
+        let transfers = compute_roundups(
+            &[spent.clone(), round],
+            &savings,
+            RoundUpBase::NearestDollar,
+        );
+
+        // Already-round purchases set nothing aside
+        assert_eq!(transfers.len(), 1);
+        let transfer = &transfers[0];
+        assert_eq!(transfer.amount, usd("0.70"));
+        assert_eq!(transfer.account_id, savings.id);
+        assert_eq!(transfer.transaction_type, TransactionType::Transfer);
+        assert_eq!(transfer.transaction_date, spent.transaction_date);
+        assert_eq!(transfer.metadata[ROUNDUP_SOURCE_KEY], spent.id.to_string());
+        assert_eq!(transfer.tags, vec![ROUNDUP_TAG.to_string()]);
+    }
+
+    #[test]
+    fn test_roundup_base_and_eligibility() {
+        let base = RoundUpBase::NearestFiveDollars;
+        assert_eq!(
+            base.roundup(Decimal::from_str("4.30").unwrap()),
+            Decimal::from_str("0.70").unwrap()
+        );
+        assert_eq!(
+            base.roundup(Decimal::from_str("12.01").unwrap()),
+            Decimal::from_str("2.99").unwrap()
+        );
+        assert!(base.roundup(Decimal::from(15)).is_zero());
+        assert!(RoundUpBase::NearestDollar.roundup(Decimal::ZERO).is_zero());
+
+        let user_id = EntityId::new();
+        let checking = EntityId::new();
+        let savings = Account::new(
+            user_id,
+            "Savings".to_string(),
+            AccountType::Savings,
+            Currency::USD,
+            None,
+        );
+
+        let mut refund = purchase(user_id, checking, "3.25");
+        refund.transaction_type = TransactionType::Credit;
+        let other_user = purchase(EntityId::new(), checking, "3.25");
+        let from_savings = purchase(user_id, savings.id, "3.25");
+        let mut in_euros = purchase(user_id, checking, "3.25");
+        in_euros.amount = Money::new(Decimal::from_str("3.25").unwrap(), Currency::EUR);
+
+        let ignored = [refund, other_user, from_savings, in_euros];
+        assert!(compute_roundups(&ignored, &savings, base).is_empty());
+    }
+}
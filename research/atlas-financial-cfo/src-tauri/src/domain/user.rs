@@ -56,6 +56,9 @@ pub struct LoginCredentials {
     pub username: String,
     #[zeroize(skip)]
     pub password: Secret<String>,
+    /// One-time code, required once the user has enabled TOTP
+    #[serde(default)]
+    pub totp_code: Option<String>,
 }
 
 impl LoginCredentials {
@@ -63,8 +66,14 @@ impl LoginCredentials {
         Self {
             username,
             password: Secret::new(password),
+            totp_code: None,
         }
     }
+
+    pub fn with_totp_code(mut self, code: String) -> Self {
+        self.totp_code = Some(code);
+        self
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
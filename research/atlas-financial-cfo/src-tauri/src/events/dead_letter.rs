@@ -0,0 +1,89 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Row, Sqlite};
+use tracing::error;
+
+use crate::domain::{EntityId, Timestamp};
+use crate::error::{AppError, AppResult};
+use super::{DeadLetter, DeadLetterStore};
+
+#[derive(Clone)]
+pub struct SqliteDeadLetterStore {
+    pool: Pool<Sqlite>,
+}
+
+impl SqliteDeadLetterStore {
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+}
+
+fn parse_entity_id(value: &str, field: &str) -> AppResult<EntityId> {
+    uuid::Uuid::parse_str(value)
+        .map(EntityId::from_uuid)
+        .map_err(|e| AppError::Database {
+            message: format!("Invalid {} UUID: {}", field, e),
+        })
+}
+
+#[async_trait]
+impl DeadLetterStore for SqliteDeadLetterStore {
+    async fn record_dead_letter(&self, letter: DeadLetter) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO dead_letter_events (
+                id, aggregate_id, aggregate_type, event_type, event_data,
+                handler_name, error_message, attempts, failed_at
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(letter.id.to_string())
+        .bind(letter.aggregate_id.to_string())
+        .bind(&letter.aggregate_type)
+        .bind(&letter.event_type)
+        .bind(&letter.event_data)
+        .bind(&letter.handler_name)
+        .bind(&letter.error_message)
+        .bind(letter.attempts as i64)
+        .bind(letter.failed_at.as_datetime())
+        .execute(&self.pool)
+        .await?;
+
+        error!(
+            "Event {} for aggregate {} dead-lettered by {} after {} attempts: {}",
+            letter.event_type, letter.aggregate_id, letter.handler_name, letter.attempts, letter.error_message
+        );
+
+        Ok(())
+    }
+
+    async fn get_dead_letters(&self) -> AppResult<Vec<DeadLetter>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, aggregate_id, aggregate_type, event_type, event_data,
+                   handler_name, error_message, attempts, failed_at
+            FROM dead_letter_events
+            ORDER BY failed_at ASC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(DeadLetter {
+                    id: parse_entity_id(row.try_get("id")?, "dead letter ID")?,
+                    aggregate_id: parse_entity_id(row.try_get("aggregate_id")?, "aggregate ID")?,
+                    aggregate_type: row.try_get("aggregate_type")?,
+                    event_type: row.try_get("event_type")?,
+                    event_data: row.try_get("event_data")?,
+                    handler_name: row.try_get("handler_name")?,
+                    error_message: row.try_get("error_message")?,
+                    attempts: row.try_get::<i64, _>("attempts")? as u32,
+                    failed_at: Timestamp::from_datetime(row.try_get::<DateTime<Utc>, _>("failed_at")?),
+                })
+            })
+            .collect()
+    }
+}
@@ -40,6 +40,12 @@ pub enum AccountEvent {
         occurred_at: Timestamp,
         version: i32,
     },
+    AccountDeleted {
+        account_id: EntityId,
+        user_id: EntityId,
+        occurred_at: Timestamp,
+        version: i32,
+    },
 }
 
 impl DomainEvent for AccountEvent {
@@ -49,6 +55,7 @@ impl DomainEvent for AccountEvent {
             AccountEvent::AccountUpdated { .. } => "AccountUpdated".to_string(),
             AccountEvent::AccountBalanceChanged { .. } => "AccountBalanceChanged".to_string(),
             AccountEvent::AccountDeactivated { .. } => "AccountDeactivated".to_string(),
+            AccountEvent::AccountDeleted { .. } => "AccountDeleted".to_string(),
         }
     }
 
@@ -57,7 +64,8 @@ impl DomainEvent for AccountEvent {
             AccountEvent::AccountCreated { account_id, .. }
             | AccountEvent::AccountUpdated { account_id, .. }
             | AccountEvent::AccountBalanceChanged { account_id, .. }
-            | AccountEvent::AccountDeactivated { account_id, .. } => *account_id,
+            | AccountEvent::AccountDeactivated { account_id, .. }
+            | AccountEvent::AccountDeleted { account_id, .. } => *account_id,
         }
     }
 
@@ -70,7 +78,8 @@ impl DomainEvent for AccountEvent {
             AccountEvent::AccountCreated { version, .. }
             | AccountEvent::AccountUpdated { version, .. }
             | AccountEvent::AccountBalanceChanged { version, .. }
-            | AccountEvent::AccountDeactivated { version, .. } => *version,
+            | AccountEvent::AccountDeactivated { version, .. }
+            | AccountEvent::AccountDeleted { version, .. } => *version,
         }
     }
 
@@ -79,7 +88,8 @@ impl DomainEvent for AccountEvent {
             AccountEvent::AccountCreated { occurred_at, .. }
             | AccountEvent::AccountUpdated { occurred_at, .. }
             | AccountEvent::AccountBalanceChanged { occurred_at, .. }
-            | AccountEvent::AccountDeactivated { occurred_at, .. } => *occurred_at,
+            | AccountEvent::AccountDeactivated { occurred_at, .. }
+            | AccountEvent::AccountDeleted { occurred_at, .. } => *occurred_at,
         }
     }
 }
@@ -122,6 +132,13 @@ pub enum TransactionEvent {
         occurred_at: Timestamp,
         version: i32,
     },
+    TransactionDeleted {
+        transaction_id: EntityId,
+        user_id: EntityId,
+        account_id: EntityId,
+        occurred_at: Timestamp,
+        version: i32,
+    },
 }
 
 impl DomainEvent for TransactionEvent {
@@ -131,6 +148,7 @@ impl DomainEvent for TransactionEvent {
             TransactionEvent::TransactionUpdated { .. } => "TransactionUpdated".to_string(),
             TransactionEvent::TransactionReconciled { .. } => "TransactionReconciled".to_string(),
             TransactionEvent::TransactionCategorized { .. } => "TransactionCategorized".to_string(),
+            TransactionEvent::TransactionDeleted { .. } => "TransactionDeleted".to_string(),
         }
     }
 
@@ -139,7 +157,8 @@ impl DomainEvent for TransactionEvent {
             TransactionEvent::TransactionCreated { transaction_id, .. }
             | TransactionEvent::TransactionUpdated { transaction_id, .. }
             | TransactionEvent::TransactionReconciled { transaction_id, .. }
-            | TransactionEvent::TransactionCategorized { transaction_id, .. } => *transaction_id,
+            | TransactionEvent::TransactionCategorized { transaction_id, .. }
+            | TransactionEvent::TransactionDeleted { transaction_id, .. } => *transaction_id,
         }
     }
 
@@ -152,7 +171,8 @@ impl DomainEvent for TransactionEvent {
             TransactionEvent::TransactionCreated { version, .. }
             | TransactionEvent::TransactionUpdated { version, .. }
             | TransactionEvent::TransactionReconciled { version, .. }
-            | TransactionEvent::TransactionCategorized { version, .. } => *version,
+            | TransactionEvent::TransactionCategorized { version, .. }
+            | TransactionEvent::TransactionDeleted { version, .. } => *version,
         }
     }
 
@@ -161,7 +181,8 @@ impl DomainEvent for TransactionEvent {
             TransactionEvent::TransactionCreated { occurred_at, .. }
             | TransactionEvent::TransactionUpdated { occurred_at, .. }
             | TransactionEvent::TransactionReconciled { occurred_at, .. }
-            | TransactionEvent::TransactionCategorized { occurred_at, .. } => *occurred_at,
+            | TransactionEvent::TransactionCategorized { occurred_at, .. }
+            | TransactionEvent::TransactionDeleted { occurred_at, .. } => *occurred_at,
         }
     }
 }
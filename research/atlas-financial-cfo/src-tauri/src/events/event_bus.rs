@@ -0,0 +1,331 @@
+use async_trait::async_trait;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tracing::{debug, warn};
+
+use crate::domain::{EntityId, Timestamp};
+use crate::error::{AppError, AppResult};
+use super::{DeadLetter, DeadLetterStore, DomainEvent, EventBus, EventHandler};
+
+type SharedHandler<T> = Arc<dyn EventHandler<T> + Send + Sync>;
+type HandlerList<T> = Vec<SharedHandler<T>>;
+
+/// Exponential backoff applied between handler attempts
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub multiplier: u32,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+            multiplier: 2,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay to wait after the given (1-based) failed attempt
+    pub fn backoff_for(&self, attempt: u32) -> Duration {
+        let factor = self.multiplier.saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// In-process event bus that retries failing handlers and dead-letters
+/// events that still fail once the retry budget is spent.
+///
+/// Every handler runs in its own task, so a slow, failing or panicking
+/// handler never prevents the others from receiving the event.
+pub struct RetryingEventBus {
+    handlers: RwLock<HashMap<TypeId, Box<dyn Any + Send + Sync>>>,
+    dead_letters: Arc<dyn DeadLetterStore + Send + Sync>,
+    policy: RetryPolicy,
+}
+
+impl RetryingEventBus {
+    pub fn new(dead_letters: Arc<dyn DeadLetterStore + Send + Sync>) -> Self {
+        Self::with_policy(dead_letters, RetryPolicy::default())
+    }
+
+    pub fn with_policy(dead_letters: Arc<dyn DeadLetterStore + Send + Sync>, policy: RetryPolicy) -> Self {
+        Self {
+            handlers: RwLock::new(HashMap::new()),
+            dead_letters,
+            policy,
+        }
+    }
+
+    async fn handlers_for<T: DomainEvent + 'static>(&self) -> HandlerList<T> {
+        self.handlers
+            .read()
+            .await
+            .get(&TypeId::of::<T>())
+            .and_then(|list| list.downcast_ref::<HandlerList<T>>())
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+/// Run a handler until it succeeds or the policy gives up
+async fn dispatch_with_retry<T: DomainEvent>(
+    handler: &SharedHandler<T>,
+    event: &T,
+    policy: &RetryPolicy,
+) -> Result<u32, (u32, AppError)> {
+    let mut attempt = 1;
+    loop {
+        match handler.handle(event).await {
+            Ok(()) => return Ok(attempt),
+            Err(e) if attempt >= policy.max_attempts => return Err((attempt, e)),
+            Err(e) => {
+                let delay = policy.backoff_for(attempt);
+                warn!(
+                    "Handler {} failed {} on attempt {}, retrying in {:?}: {}",
+                    handler.name(),
+                    event.event_type(),
+                    attempt,
+                    delay,
+                    e
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl EventBus for RetryingEventBus {
+    async fn publish<T: DomainEvent + 'static>(&self, event: T) -> AppResult<()> {
+        let handlers = self.handlers_for::<T>().await;
+        if handlers.is_empty() {
+            debug!("No handlers subscribed to {}", event.event_type());
+            return Ok(());
+        }
+
+        let event = Arc::new(event);
+        let tasks: Vec<_> = handlers
+            .into_iter()
+            .map(|handler| {
+                let name = handler.name();
+                let event = event.clone();
+                let policy = self.policy.clone();
+                let task = tokio::spawn(async move {
+                    dispatch_with_retry(&handler, event.as_ref(), &policy).await
+                });
+                (name, task)
+            })
+            .collect();
+
+        let event_data = serde_json::to_string(event.as_ref()).map_err(|e| AppError::Internal {
+            message: format!("Failed to serialize event: {}", e),
+        })?;
+
+        // Deliver every failure to the dead-letter store before reporting errors
+        let mut first_error = None;
+        for (handler_name, task) in tasks {
+            let (attempts, error_message) = match task.await {
+                Ok(Ok(_)) => continue,
+                Ok(Err((attempts, e))) => (attempts, e.to_string()),
+                Err(join_error) => (1, format!("Handler panicked: {}", join_error)),
+            };
+
+            let letter = DeadLetter {
+                id: EntityId::new(),
+                aggregate_id: event.aggregate_id(),
+                aggregate_type: event.aggregate_type(),
+                event_type: event.event_type(),
+                event_data: event_data.clone(),
+                handler_name,
+                error_message,
+                attempts,
+                failed_at: Timestamp::now(),
+            };
+
+            if let Err(e) = self.dead_letters.record_dead_letter(letter).await {
+                first_error.get_or_insert(e);
+            }
+        }
+
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    async fn subscribe<T: DomainEvent + 'static>(
+        &self,
+        handler: Box<dyn EventHandler<T> + Send + Sync>,
+    ) -> AppResult<()> {
+        let mut handlers = self.handlers.write().await;
+        let list = handlers
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(HandlerList::<T>::new()))
+            .downcast_mut::<HandlerList<T>>()
+            .ok_or_else(|| AppError::Internal {
+                message: "Handler registry type mismatch".to_string(),
+            })?;
+
+        list.push(Arc::from(handler));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::SqliteDeadLetterStore;
+    use serde::{Deserialize, Serialize};
+    use sqlx::sqlite::SqlitePoolOptions;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct TestEvent {
+        aggregate_id: EntityId,
+        occurred_at: Timestamp,
+    }
+
+    impl DomainEvent for TestEvent {
+        fn event_type(&self) -> String {
+            "TestEvent".to_string()
+        }
+
+        fn aggregate_id(&self) -> EntityId {
+            self.aggregate_id
+        }
+
+        fn aggregate_type(&self) -> String {
+            "Test".to_string()
+        }
+
+        fn event_version(&self) -> i32 {
+            1
+        }
+
+        fn occurred_at(&self) -> Timestamp {
+            self.occurred_at
+        }
+    }
+
+    /// Fails until it has been called `failures + 1` times
+    struct FlakyHandler {
+        calls: Arc<AtomicU32>,
+        failures: u32,
+    }
+
+    #[async_trait]
+    impl EventHandler<TestEvent> for FlakyHandler {
+        async fn handle(&self, _event: &TestEvent) -> AppResult<()> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            if call <= self.failures {
+                return Err(AppError::Internal {
+                    message: format!("transient failure {}", call),
+                });
+            }
+            Ok(())
+        }
+
+        fn name(&self) -> String {
+            "FlakyHandler".to_string()
+        }
+    }
+
+    fn test_event() -> TestEvent {
+        TestEvent {
+            aggregate_id: EntityId::new(),
+            occurred_at: Timestamp::now(),
+        }
+    }
+
+    fn fast_policy() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(5),
+            multiplier: 2,
+        }
+    }
+
+    async fn dead_letter_store() -> Arc<SqliteDeadLetterStore> {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::query(include_str!("../../migrations/004_add_dead_letter_events.sql"))
+            .execute(&pool)
+            .await
+            .unwrap();
+        Arc::new(SqliteDeadLetterStore::new(pool))
+    }
+
+    #[test]
+    fn test_backoff_grows_exponentially_and_caps() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.backoff_for(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff_for(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff_for(4), Duration::from_millis(800));
+        assert_eq!(policy.backoff_for(20), Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_flaky_handler_succeeds_on_retry() {
+        let store = dead_letter_store().await;
+        let bus = RetryingEventBus::with_policy(store.clone(), fast_policy());
+        let calls = Arc::new(AtomicU32::new(0));
+
+        bus.subscribe(Box::new(FlakyHandler { calls: calls.clone(), failures: 2 }))
+            .await
+            .unwrap();
+        bus.publish(test_event()).await.unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert!(store.get_dead_letters().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_failing_handler_is_dead_lettered_without_blocking_others() {
+        let store = dead_letter_store().await;
+        let bus = RetryingEventBus::with_policy(store.clone(), fast_policy());
+        let failing_calls = Arc::new(AtomicU32::new(0));
+        let healthy_calls = Arc::new(AtomicU32::new(0));
+
+        bus.subscribe(Box::new(FlakyHandler { calls: failing_calls.clone(), failures: u32::MAX }))
+            .await
+            .unwrap();
+        bus.subscribe(Box::new(FlakyHandler { calls: healthy_calls.clone(), failures: 0 }))
+            .await
+            .unwrap();
+
+        let event = test_event();
+        bus.publish(event.clone()).await.unwrap();
+
+        assert_eq!(failing_calls.load(Ordering::SeqCst), 3);
+        assert_eq!(healthy_calls.load(Ordering::SeqCst), 1);
+
+        let letters = store.get_dead_letters().await.unwrap();
+        assert_eq!(letters.len(), 1);
+
+        let letter = &letters[0];
+        assert_eq!(letter.handler_name, "FlakyHandler");
+        assert_eq!(letter.event_type, "TestEvent");
+        assert_eq!(letter.aggregate_id, event.aggregate_id);
+        assert_eq!(letter.attempts, 3);
+        assert!(letter.error_message.contains("transient failure 3"));
+
+        let stored: TestEvent = serde_json::from_str(&letter.event_data).unwrap();
+        assert_eq!(stored.aggregate_id, event.aggregate_id);
+    }
+}
@@ -57,6 +57,10 @@ impl EventHandler<AccountEvent> for AccountEventHandler {
                 warn!("Account {} deactivated: {}", account_id, reason);
                 Ok(())
             }
+            AccountEvent::AccountDeleted { account_id, .. } => {
+                warn!("Account {} deleted", account_id);
+                Ok(())
+            }
         }
     }
 }
@@ -120,6 +124,15 @@ impl EventHandler<TransactionEvent> for TransactionEventHandler {
                 info!("Transaction {} updated", transaction_id);
                 Ok(())
             }
+            TransactionEvent::TransactionDeleted {
+                transaction_id, account_id, ..
+            } => {
+                info!("Transaction {} deleted from account {}", transaction_id, account_id);
+
+                // Reverse its effect on balances, analytics, budgets
+
+                Ok(())
+            }
         }
     }
 }
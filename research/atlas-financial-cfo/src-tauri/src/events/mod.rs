@@ -1,6 +1,9 @@
+pub mod dead_letter;
 pub mod domain_event;
+pub mod event_bus;
 pub mod event_store;
 pub mod handlers;
+pub mod outbox;
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
@@ -9,9 +12,12 @@ use std::fmt::Debug;
 use crate::domain::{EntityId, Timestamp};
 use crate::error::AppResult;
 
+pub use dead_letter::*;
 pub use domain_event::*;
+pub use event_bus::*;
 pub use event_store::*;
 pub use handlers::*;
+pub use outbox::*;
 
 /// Base trait for all domain events
 #[async_trait]
@@ -27,12 +33,17 @@ pub trait DomainEvent: Debug + Clone + Send + Sync + Serialize + for<'de> Deseri
 #[async_trait]
 pub trait EventHandler<T: DomainEvent> {
     async fn handle(&self, event: &T) -> AppResult<()>;
+
+    /// Name recorded against dead-lettered events for this handler
+    fn name(&self) -> String {
+        std::any::type_name::<Self>().to_string()
+    }
 }
 
 /// Event bus for publishing and subscribing to events
 #[async_trait]
 pub trait EventBus {
-    async fn publish<T: DomainEvent>(&self, event: T) -> AppResult<()>;
+    async fn publish<T: DomainEvent + 'static>(&self, event: T) -> AppResult<()>;
     async fn subscribe<T: DomainEvent + 'static>(
         &self,
         handler: Box<dyn EventHandler<T> + Send + Sync>,
@@ -52,6 +63,13 @@ pub trait EventStore {
     async fn get_events_since(&self, timestamp: Timestamp) -> AppResult<Vec<StoredEvent>>;
 }
 
+/// Store for events that could not be delivered to a handler
+#[async_trait]
+pub trait DeadLetterStore {
+    async fn record_dead_letter(&self, letter: DeadLetter) -> AppResult<()>;
+    async fn get_dead_letters(&self) -> AppResult<Vec<DeadLetter>>;
+}
+
 /// Stored event with metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StoredEvent {
@@ -64,3 +82,17 @@ pub struct StoredEvent {
     pub created_at: Timestamp,
     pub user_id: Option<EntityId>,
 }
+
+/// Event that exhausted its retries for a handler
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetter {
+    pub id: EntityId,
+    pub aggregate_id: EntityId,
+    pub aggregate_type: String,
+    pub event_type: String,
+    pub event_data: String, // JSON serialized event data
+    pub handler_name: String,
+    pub error_message: String,
+    pub attempts: u32,
+    pub failed_at: Timestamp,
+}
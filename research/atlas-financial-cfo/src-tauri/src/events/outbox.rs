@@ -0,0 +1,436 @@
+use chrono::{DateTime, Utc};
+use sqlx::query::Query;
+use sqlx::sqlite::{SqliteArguments, SqliteQueryResult};
+use sqlx::{Pool, Row, Sqlite, SqliteConnection};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+use crate::database::retry_on_busy;
+use crate::domain::{EntityId, Timestamp};
+use crate::error::{AppError, AppResult};
+use super::{DomainEvent, EventBus};
+
+/// Event waiting in the outbox to be published
+#[derive(Debug, Clone)]
+pub struct OutboxEntry {
+    pub id: EntityId,
+    pub aggregate_id: EntityId,
+    pub aggregate_type: String,
+    pub event_type: String,
+    pub event_data: String, // JSON serialized event data
+    pub attempts: u32,
+    pub last_error: Option<String>,
+    pub created_at: Timestamp,
+}
+
+/// Event serialized and ready to be written to the outbox
+#[derive(Debug, Clone)]
+pub struct OutboxMessage {
+    aggregate_id: EntityId,
+    aggregate_type: String,
+    event_type: String,
+    event_data: String,
+}
+
+impl OutboxMessage {
+    pub fn new<T: DomainEvent>(event: &T) -> AppResult<Self> {
+        let event_data = serde_json::to_string(event).map_err(|e| AppError::Internal {
+            message: format!("Failed to serialize event: {}", e),
+        })?;
+
+        Ok(Self {
+            aggregate_id: event.aggregate_id(),
+            aggregate_type: event.aggregate_type(),
+            event_type: event.event_type(),
+            event_data,
+        })
+    }
+
+    /// Insert the message on `conn`, usually an open transaction
+    pub async fn write(&self, conn: &mut SqliteConnection) -> Result<EntityId, sqlx::Error> {
+        let id = EntityId::new();
+        sqlx::query(
+            r#"
+            INSERT INTO outbox (id, aggregate_id, aggregate_type, event_type, event_data, created_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(self.aggregate_id.to_string())
+        .bind(&self.aggregate_type)
+        .bind(&self.event_type)
+        .bind(&self.event_data)
+        .bind(Timestamp::now().as_datetime())
+        .execute(conn)
+        .await?;
+
+        Ok(id)
+    }
+}
+
+/// Transactional outbox backed by the `outbox` table
+///
+/// Events are written on the same connection as the state change they
+/// describe, so they commit or roll back with it. An [`OutboxRelay`] then
+/// publishes committed events, which survive a crash between commit and
+/// publish.
+#[derive(Clone)]
+pub struct SqliteOutbox {
+    pool: Pool<Sqlite>,
+}
+
+fn parse_entity_id(value: &str, field: &str) -> AppResult<EntityId> {
+    uuid::Uuid::parse_str(value)
+        .map(EntityId::from_uuid)
+        .map_err(|e| AppError::Database {
+            message: format!("Invalid {} UUID: {}", field, e),
+        })
+}
+
+impl SqliteOutbox {
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+
+    /// Write `event` to the outbox on `conn`, usually an open transaction
+    pub async fn enqueue<T: DomainEvent>(conn: &mut SqliteConnection, event: &T) -> AppResult<EntityId> {
+        Ok(OutboxMessage::new(event)?.write(conn).await?)
+    }
+
+    /// Run the write built by `query` and enqueue `message` in one transaction
+    ///
+    /// The message is only written when the query changes a row, so a write
+    /// that matches nothing records no event. The whole transaction is
+    /// retried while the database is busy.
+    pub async fn execute_with_event<'q, F>(
+        pool: &Pool<Sqlite>,
+        message: &OutboxMessage,
+        query: F,
+    ) -> Result<SqliteQueryResult, sqlx::Error>
+    where
+        F: Fn() -> Query<'q, Sqlite, SqliteArguments<'q>>,
+    {
+        let query = &query;
+        retry_on_busy(|| async move {
+            let mut tx = pool.begin().await?;
+            let result = query().execute(&mut *tx).await?;
+            if result.rows_affected() > 0 {
+                message.write(&mut tx).await?;
+            }
+            tx.commit().await?;
+            Ok(result)
+        })
+        .await
+    }
+
+    /// Oldest events not yet published, in the order they were written
+    pub async fn pending(&self, limit: u32) -> AppResult<Vec<OutboxEntry>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, aggregate_id, aggregate_type, event_type, event_data,
+                   attempts, last_error, created_at
+            FROM outbox
+            WHERE sent_at IS NULL
+            ORDER BY created_at ASC, rowid ASC
+            LIMIT ?
+            "#,
+        )
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(OutboxEntry {
+                    id: parse_entity_id(row.try_get("id")?, "outbox ID")?,
+                    aggregate_id: parse_entity_id(row.try_get("aggregate_id")?, "aggregate ID")?,
+                    aggregate_type: row.try_get("aggregate_type")?,
+                    event_type: row.try_get("event_type")?,
+                    event_data: row.try_get("event_data")?,
+                    attempts: row.try_get::<i64, _>("attempts")? as u32,
+                    last_error: row.try_get("last_error")?,
+                    created_at: Timestamp::from_datetime(row.try_get::<DateTime<Utc>, _>("created_at")?),
+                })
+            })
+            .collect()
+    }
+
+    /// Record that an entry was published
+    pub async fn mark_sent(&self, id: EntityId) -> AppResult<()> {
+        sqlx::query("UPDATE outbox SET sent_at = ?, attempts = attempts + 1, last_error = NULL WHERE id = ?")
+            .bind(Timestamp::now().as_datetime())
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Record a failed publish; the entry stays pending and is retried
+    pub async fn record_failure(&self, id: EntityId, error_message: &str) -> AppResult<()> {
+        sqlx::query("UPDATE outbox SET attempts = attempts + 1, last_error = ? WHERE id = ?")
+            .bind(error_message)
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
+
+type PublishFuture = Pin<Box<dyn Future<Output = AppResult<()>> + Send>>;
+type Publisher = Box<dyn Fn(String) -> PublishFuture + Send + Sync>;
+
+/// Background relay publishing committed outbox events to an event bus
+///
+/// Entries are marked sent only after the bus accepts them, so an event is
+/// delivered at least once: a crash after publishing but before marking it
+/// sent publishes it again on the next run. Handlers must tolerate repeats.
+pub struct OutboxRelay<B> {
+    outbox: SqliteOutbox,
+    bus: Arc<B>,
+    publishers: HashMap<String, Publisher>,
+    batch_size: u32,
+    poll_interval: Duration,
+}
+
+impl<B: EventBus + Send + Sync + 'static> OutboxRelay<B> {
+    pub fn new(outbox: SqliteOutbox, bus: Arc<B>) -> Self {
+        Self {
+            outbox,
+            bus,
+            publishers: HashMap::new(),
+            batch_size: 100,
+            poll_interval: Duration::from_secs(1),
+        }
+    }
+
+    /// Publish entries for `aggregate_type` as events of type `T`
+    pub fn route<T: DomainEvent + 'static>(mut self, aggregate_type: &str) -> Self {
+        let bus = self.bus.clone();
+        let publisher: Publisher = Box::new(move |event_data| {
+            let bus = bus.clone();
+            Box::pin(async move {
+                let event: T = serde_json::from_str(&event_data).map_err(|e| AppError::Internal {
+                    message: format!("Failed to deserialize event: {}", e),
+                })?;
+                bus.publish(event).await
+            })
+        });
+        self.publishers.insert(aggregate_type.to_string(), publisher);
+        self
+    }
+
+    /// Wait this long between passes over the outbox
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Publish one batch of pending entries, returning how many were sent
+    ///
+    /// An entry that fails is left pending for the next pass; the rest of the
+    /// batch is still published.
+    pub async fn relay_pending(&self) -> AppResult<usize> {
+        let mut sent = 0;
+        for entry in self.outbox.pending(self.batch_size).await? {
+            let result = match self.publishers.get(&entry.aggregate_type) {
+                Some(publish) => publish(entry.event_data.clone()).await,
+                None => Err(AppError::Internal {
+                    message: format!("No outbox route for aggregate type {}", entry.aggregate_type),
+                }),
+            };
+
+            match result {
+                Ok(()) => {
+                    self.outbox.mark_sent(entry.id).await?;
+                    sent += 1;
+                }
+                Err(e) => {
+                    warn!(
+                        "Publishing outbox event {} ({}) failed on attempt {}: {}",
+                        entry.id,
+                        entry.event_type,
+                        entry.attempts + 1,
+                        e
+                    );
+                    self.outbox.record_failure(entry.id, &e.to_string()).await?;
+                }
+            }
+        }
+        Ok(sent)
+    }
+
+    /// Relay pending entries until the task is aborted
+    ///
+    /// The first pass runs straight away, so events left over from a
+    /// previous run are published on startup.
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                match self.relay_pending().await {
+                    Ok(0) => {}
+                    Ok(sent) => info!("Relayed {} outbox events", sent),
+                    Err(e) => error!("Outbox relay pass failed: {}", e),
+                }
+                tokio::time::sleep(self.poll_interval).await;
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::migrations::run_migrations;
+    use crate::events::EventHandler;
+    use async_trait::async_trait;
+    use serde::{Deserialize, Serialize};
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use tokio::sync::Mutex;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct TestEvent {
+        aggregate_id: EntityId,
+        occurred_at: Timestamp,
+    }
+
+    impl DomainEvent for TestEvent {
+        fn event_type(&self) -> String {
+            "TestEvent".to_string()
+        }
+
+        fn aggregate_id(&self) -> EntityId {
+            self.aggregate_id
+        }
+
+        fn aggregate_type(&self) -> String {
+            "Test".to_string()
+        }
+
+        fn event_version(&self) -> i32 {
+            1
+        }
+
+        fn occurred_at(&self) -> Timestamp {
+            self.occurred_at
+        }
+    }
+
+    /// Bus recording what it publishes, failing the first `failures` calls
+    #[derive(Default)]
+    struct RecordingBus {
+        published: Mutex<Vec<EntityId>>,
+        calls: AtomicU32,
+        failures: u32,
+    }
+
+    #[async_trait]
+    impl EventBus for RecordingBus {
+        async fn publish<T: DomainEvent + 'static>(&self, event: T) -> AppResult<()> {
+            if self.calls.fetch_add(1, Ordering::SeqCst) < self.failures {
+                return Err(AppError::Internal {
+                    message: "bus unavailable".to_string(),
+                });
+            }
+            self.published.lock().await.push(event.aggregate_id());
+            Ok(())
+        }
+
+        async fn subscribe<T: DomainEvent + 'static>(
+            &self,
+            _handler: Box<dyn EventHandler<T> + Send + Sync>,
+        ) -> AppResult<()> {
+            Ok(())
+        }
+    }
+
+    async fn file_pool(path: &PathBuf) -> Pool<Sqlite> {
+        let options = SqliteConnectOptions::new().filename(path).create_if_missing(true);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .unwrap();
+        run_migrations(&pool).await.unwrap();
+        pool
+    }
+
+    fn test_event() -> TestEvent {
+        TestEvent {
+            aggregate_id: EntityId::new(),
+            occurred_at: Timestamp::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_committed_event_is_delivered_after_restart() {
+        let path = std::env::temp_dir().join(format!("atlas-outbox-{}.db", uuid::Uuid::new_v4()));
+        let committed = test_event();
+
+        // First run: the state change commits with its event, then the
+        // process stops before anything is relayed
+        {
+            let pool = file_pool(&path).await;
+            let mut tx = pool.begin().await.unwrap();
+            SqliteOutbox::enqueue(&mut tx, &committed).await.unwrap();
+            tx.commit().await.unwrap();
+
+            // An event from a rolled-back change never reaches the outbox
+            let mut tx = pool.begin().await.unwrap();
+            SqliteOutbox::enqueue(&mut tx, &test_event()).await.unwrap();
+            tx.rollback().await.unwrap();
+            pool.close().await;
+        }
+
+        // Second run: a fresh relay publishes what the first run left behind
+        let pool = file_pool(&path).await;
+        let bus = Arc::new(RecordingBus::default());
+        let relay = OutboxRelay::new(SqliteOutbox::new(pool.clone()), bus.clone()).route::<TestEvent>("Test");
+
+        assert_eq!(relay.relay_pending().await.unwrap(), 1);
+        assert_eq!(*bus.published.lock().await, vec![committed.aggregate_id]);
+
+        // Sent entries are not published again
+        assert_eq!(relay.relay_pending().await.unwrap(), 0);
+        assert!(relay.outbox.pending(10).await.unwrap().is_empty());
+
+        pool.close().await;
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_failed_publish_is_retried_on_the_next_pass() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        run_migrations(&pool).await.unwrap();
+
+        let event = test_event();
+        let mut conn = pool.acquire().await.unwrap();
+        SqliteOutbox::enqueue(&mut conn, &event).await.unwrap();
+        drop(conn);
+
+        let bus = Arc::new(RecordingBus {
+            failures: 1,
+            ..RecordingBus::default()
+        });
+        let relay = OutboxRelay::new(SqliteOutbox::new(pool), bus.clone()).route::<TestEvent>("Test");
+
+        assert_eq!(relay.relay_pending().await.unwrap(), 0);
+        let pending = relay.outbox.pending(10).await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].attempts, 1);
+        assert!(pending[0].last_error.as_deref().unwrap().contains("bus unavailable"));
+
+        assert_eq!(relay.relay_pending().await.unwrap(), 1);
+        assert_eq!(*bus.published.lock().await, vec![event.aggregate_id]);
+    }
+}
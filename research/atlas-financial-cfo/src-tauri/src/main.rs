@@ -57,6 +57,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             commands::auth::login,
             commands::auth::logout,
             commands::auth::verify_session,
+            commands::auth::enroll_totp,
+            commands::auth::verify_totp,
             commands::accounts::create_account,
             commands::accounts::get_accounts,
             commands::accounts::update_account,
@@ -1,8 +1,12 @@
 use std::sync::Arc;
+use tokio::task::JoinHandle;
 
-use crate::auth::AuthService;
-use crate::database::{Database, UserRepository, AccountRepository, TransactionRepository};
-use crate::events::{SqliteEventStore, EventStore};
+use crate::auth::{AuthService, SessionLimit, SessionStorage, SqliteAuditLog};
+use crate::database::{Database, UserRepository, AccountRepository, TransactionRepository, TotpRepository};
+use crate::events::{
+    AccountEvent, AccountEventHandler, EventBus, EventStore, OutboxRelay, RetryingEventBus, SqliteDeadLetterStore,
+    SqliteEventStore, SqliteOutbox, TransactionEvent, TransactionEventHandler, UserEventHandler,
+};
 use crate::error::AppResult;
 
 pub struct AppServices {
@@ -11,6 +15,9 @@ pub struct AppServices {
     pub account_repository: Arc<AccountRepository>,
     pub transaction_repository: Arc<TransactionRepository>,
     pub event_store: Arc<dyn EventStore + Send + Sync>,
+    pub event_bus: Arc<RetryingEventBus>,
+    /// Background task publishing committed outbox events to `event_bus`
+    pub outbox_relay: JoinHandle<()>,
 }
 
 impl AppServices {
@@ -26,8 +33,27 @@ impl AppServices {
         let event_store: Arc<dyn EventStore + Send + Sync> =
             Arc::new(SqliteEventStore::new(pool.clone()));
 
-        // Initialize auth service
-        let auth_service = Arc::new(AuthService::new(user_repository.clone()));
+        // Initialize event bus with dead-letter storage for failed deliveries
+        let event_bus = Arc::new(RetryingEventBus::new(Arc::new(SqliteDeadLetterStore::new(pool.clone()))));
+        event_bus.subscribe(Box::new(AccountEventHandler)).await?;
+        event_bus.subscribe(Box::new(TransactionEventHandler)).await?;
+        event_bus.subscribe(Box::new(UserEventHandler)).await?;
+
+        // Repositories enqueue events with each write; the relay delivers them
+        let outbox_relay = OutboxRelay::new(SqliteOutbox::new(pool.clone()), event_bus.clone())
+            .route::<AccountEvent>("Account")
+            .route::<TransactionEvent>("Transaction")
+            .spawn();
+
+        // Initialize auth service with the configured session backend and limit
+        let session_store = SessionStorage::from_env()?.build(&pool);
+        let totp_repository = Arc::new(TotpRepository::new(pool.clone()));
+        let auth_service = Arc::new(
+            AuthService::new(user_repository.clone(), totp_repository)
+                .with_session_store(session_store)
+                .with_session_limit(SessionLimit::from_env()?)
+                .with_audit_log(Arc::new(SqliteAuditLog::new(pool.clone()))),
+        );
 
         Ok(Self {
             auth_service,
@@ -35,6 +61,8 @@ impl AppServices {
             account_repository,
             transaction_repository,
             event_store,
+            event_bus,
+            outbox_relay,
         })
     }
 }
@@ -1,9 +1,15 @@
 /// Atlas Financial API integration for user validation and session management
 use crate::auth::claims::{UserClaims, UserRole};
 use crate::error::{ApiError, ApiResult};
+use crate::monitoring::request_id::{current_request_id, REQUEST_ID_HEADER};
 use chrono::{DateTime, Utc};
+use financial_core::{system_clock, Clock};
 use serde::{Deserialize, Serialize};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Arc;
 use std::time::Duration;
+use tokio::time::Instant;
 use tracing::{debug, error, warn};
 use uuid::Uuid;
 
@@ -13,6 +19,78 @@ pub struct AtlasApiClient {
     client: reqwest::Client,
     base_url: String,
     api_key: String,
+    retry_policy: RetryPolicy,
+    /// Time sessions are checked for expiry against
+    clock: Arc<dyn Clock>,
+}
+
+/// Retry settings for idempotent Atlas API requests
+///
+/// Failed attempts are retried with jittered exponential backoff until
+/// `max_attempts` is reached or `total_timeout` has elapsed, whichever comes
+/// first. Only transport errors, 429 and 5xx responses are retried; a circuit
+/// breaker should wrap the whole retried call rather than each attempt.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Attempts including the first request
+    pub max_attempts: u32,
+    /// Backoff before the first retry; doubled for each further retry
+    pub base_delay: Duration,
+    /// Upper bound for a single backoff
+    pub max_delay: Duration,
+    /// Budget for all attempts and backoffs of one call
+    pub total_timeout: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(2),
+            total_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that sends each request exactly once
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Backoff before retry number `retry` (starting at 1), with equal jitter
+    fn backoff(&self, retry: u32) -> Duration {
+        let exponential = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+            .min(self.max_delay);
+        let half = exponential / 2;
+        let jitter_nanos = half.as_nanos() as u64;
+        let jitter = if jitter_nanos == 0 {
+            Duration::ZERO
+        } else {
+            Duration::from_nanos(RandomState::new().build_hasher().finish() % jitter_nanos)
+        };
+        half + jitter
+    }
+
+    /// Whether a response status is worth retrying
+    fn is_retryable_status(status: reqwest::StatusCode) -> bool {
+        status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+    }
+
+    /// Whether a failed request is worth retrying
+    ///
+    /// Only failures to connect and timeouts are; other errors, such as a
+    /// request that could not be built or a redirect loop, fail the same way
+    /// on every attempt.
+    fn is_retryable_error(error: &reqwest::Error) -> bool {
+        error.is_connect() || error.is_timeout()
+    }
 }
 
 /// Atlas user information response
@@ -86,28 +164,102 @@ impl AtlasApiClient {
             client,
             base_url,
             api_key,
+            retry_policy: RetryPolicy::default(),
+            clock: system_clock(),
         })
     }
 
+    /// Use a custom retry policy for idempotent requests
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Judge session expiry by `clock` instead of the system time
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Authorized request to Atlas, carrying the current request's correlation ID
+    fn request(&self, method: reqwest::Method, url: &str) -> reqwest::RequestBuilder {
+        let request = self
+            .client
+            .request(method, url)
+            .header("Authorization", format!("Bearer {}", self.api_key));
+        match current_request_id() {
+            Some(request_id) => request.header(REQUEST_ID_HEADER, request_id),
+            None => request,
+        }
+    }
+
+    /// Send an idempotent GET, retrying transient failures per the retry policy
+    ///
+    /// The final response is returned as-is, so non-retryable statuses such as
+    /// 401/403 reach the caller after a single attempt.
+    async fn get_with_retry(&self, url: &str, context: &str) -> ApiResult<reqwest::Response> {
+        let policy = &self.retry_policy;
+        let deadline = Instant::now() + policy.total_timeout;
+        let mut attempt = 1;
+
+        loop {
+            let request = self
+                .request(reqwest::Method::GET, url)
+                .header("Content-Type", "application/json")
+                .send();
+
+            let result = match tokio::time::timeout_at(deadline, request).await {
+                Ok(result) => result,
+                Err(_) => {
+                    error!("{} timed out after {} attempt(s)", context, attempt);
+                    return Err(ApiError::AtlasApiError {
+                        message: format!("{}: timed out after {:?}", context, policy.total_timeout),
+                    });
+                }
+            };
+
+            let retryable = match &result {
+                Ok(response) => RetryPolicy::is_retryable_status(response.status()),
+                Err(e) => RetryPolicy::is_retryable_error(e),
+            };
+
+            let backoff = policy.backoff(attempt);
+            if !retryable || attempt >= policy.max_attempts || Instant::now() + backoff >= deadline {
+                return result.map_err(|e| {
+                    error!("{}: {}", context, e);
+                    ApiError::AtlasApiError {
+                        message: format!("{}: {}", context, e),
+                    }
+                });
+            }
+
+            match &result {
+                Ok(response) => warn!(
+                    "{} returned {} (attempt {}/{}), retrying in {:?}",
+                    context,
+                    response.status(),
+                    attempt,
+                    policy.max_attempts,
+                    backoff
+                ),
+                Err(e) => warn!(
+                    "{} failed: {} (attempt {}/{}), retrying in {:?}",
+                    context, e, attempt, policy.max_attempts, backoff
+                ),
+            }
+
+            tokio::time::sleep(backoff).await;
+            attempt += 1;
+        }
+    }
+
     /// Validate user credentials and get user information
     pub async fn validate_user(&self, user_id: &str) -> ApiResult<AtlasUser> {
         debug!("Validating user with Atlas API: {}", user_id);
 
         let url = format!("{}/api/v1/users/{}", self.base_url, user_id);
 
-        let response = self
-            .client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .header("Content-Type", "application/json")
-            .send()
-            .await
-            .map_err(|e| {
-                error!("Failed to call Atlas API: {}", e);
-                ApiError::AtlasApiError {
-                    message: format!("Request failed: {}", e),
-                }
-            })?;
+        let response = self.get_with_retry(&url, "Request failed").await?;
 
         if response.status().is_success() {
             let user: AtlasUser = response.json().await.map_err(|e| {
@@ -152,18 +304,8 @@ impl AtlasApiClient {
         let url = format!("{}/api/v1/sessions/{}", self.base_url, session_id);
 
         let response = self
-            .client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .header("Content-Type", "application/json")
-            .send()
-            .await
-            .map_err(|e| {
-                error!("Failed to call Atlas API for session validation: {}", e);
-                ApiError::AtlasApiError {
-                    message: format!("Session validation failed: {}", e),
-                }
-            })?;
+            .get_with_retry(&url, "Session validation failed")
+            .await?;
 
         if response.status().is_success() {
             let session: AtlasSession = response.json().await.map_err(|e| {
@@ -179,7 +321,7 @@ impl AtlasApiClient {
                 });
             }
 
-            if session.expires_at < Utc::now() {
+            if session.expires_at < self.clock.now() {
                 return Err(ApiError::TokenExpired);
             }
 
@@ -223,9 +365,7 @@ impl AtlasApiClient {
         };
 
         let response = self
-            .client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", self.api_key))
+            .request(reqwest::Method::POST, &url)
             .header("Content-Type", "application/json")
             .json(&request)
             .send()
@@ -268,9 +408,7 @@ impl AtlasApiClient {
         let url = format!("{}/api/v1/sessions/{}", self.base_url, session_id);
 
         let response = self
-            .client
-            .delete(&url)
-            .header("Authorization", format!("Bearer {}", self.api_key))
+            .request(reqwest::Method::DELETE, &url)
             .send()
             .await
             .map_err(|e| {
@@ -305,17 +443,8 @@ impl AtlasApiClient {
         let url = format!("{}/api/v1/users/{}/permissions", self.base_url, user_id);
 
         let response = self
-            .client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .send()
-            .await
-            .map_err(|e| {
-                error!("Failed to fetch user permissions: {}", e);
-                ApiError::AtlasApiError {
-                    message: format!("Permission fetch failed: {}", e),
-                }
-            })?;
+            .get_with_retry(&url, "Permission fetch failed")
+            .await?;
 
         if response.status().is_success() {
             #[derive(Deserialize)]
@@ -351,6 +480,30 @@ impl AtlasApiClient {
             }
         }
     }
+
+    /// Check that the Atlas API is up
+    ///
+    /// Sent once without retries, so a readiness probe reports an outage
+    /// promptly instead of waiting out the retry policy.
+    pub async fn check_health(&self) -> ApiResult<()> {
+        let url = format!("{}/health", self.base_url);
+
+        let response = self
+            .request(reqwest::Method::GET, &url)
+            .send()
+            .await
+            .map_err(|e| ApiError::AtlasApiError {
+                message: format!("Health check failed: {}", e),
+            })?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(ApiError::AtlasApiError {
+                message: format!("Health check error: {}", response.status()),
+            })
+        }
+    }
 }
 
 /// Convert Atlas user to JWT user claims
@@ -460,6 +613,151 @@ mod tests {
         mock.assert_async().await;
     }
 
+    fn fast_retry_policy() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(5),
+            max_delay: Duration::from_millis(20),
+            total_timeout: Duration::from_secs(5),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_transient_failures_are_retried() {
+        let mut server = Server::new_async().await;
+        let client = AtlasApiClient::new(server.url(), "test-key".to_string())
+            .unwrap()
+            .with_retry_policy(fast_retry_policy());
+
+        // Once the failing mock has served its two responses, mockito moves
+        // on to the next matching mock
+        let failures = server
+            .mock("GET", "/api/v1/users/123/permissions")
+            .with_status(503)
+            .expect(2)
+            .create_async()
+            .await;
+        let success = server
+            .mock("GET", "/api/v1/users/123/permissions")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"permissions": ["debt:read"]}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let permissions = client.get_user_permissions("123").await.unwrap();
+        assert_eq!(permissions, vec!["debt:read".to_string()]);
+
+        failures.assert_async().await;
+        success.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_unauthorized_is_not_retried() {
+        let mut server = Server::new_async().await;
+        let client = AtlasApiClient::new(server.url(), "bad-key".to_string())
+            .unwrap()
+            .with_retry_policy(fast_retry_policy());
+
+        let mock = server
+            .mock("GET", "/api/v1/users/123")
+            .with_status(401)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let result = client.validate_user("123").await;
+        assert!(matches!(result, Err(ApiError::AuthenticationFailed { .. })));
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_retries_stop_at_max_attempts() {
+        let mut server = Server::new_async().await;
+        let client = AtlasApiClient::new(server.url(), "test-key".to_string())
+            .unwrap()
+            .with_retry_policy(fast_retry_policy());
+
+        let mock = server
+            .mock("GET", "/api/v1/sessions/abc")
+            .with_status(500)
+            .expect(3)
+            .create_async()
+            .await;
+
+        let result = client.validate_session("abc").await;
+        assert!(matches!(result, Err(ApiError::AtlasApiError { .. })));
+
+        mock.assert_async().await;
+    }
+
+    #[test]
+    fn test_backoff_grows_and_is_capped() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(400),
+            total_timeout: Duration::from_secs(10),
+        };
+
+        for _ in 0..20 {
+            let first = policy.backoff(1);
+            assert!(first >= Duration::from_millis(50) && first < Duration::from_millis(100));
+
+            let second = policy.backoff(2);
+            assert!(second >= Duration::from_millis(100) && second < Duration::from_millis(200));
+
+            let capped = policy.backoff(8);
+            assert!(capped >= Duration::from_millis(200) && capped < Duration::from_millis(400));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_session_expiry_follows_clock() {
+        use chrono::TimeZone;
+        use financial_core::MockClock;
+
+        let mut server = Server::new_async().await;
+        let expires_at = Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap();
+        let clock = MockClock::new(expires_at - chrono::Duration::seconds(1));
+        let client = AtlasApiClient::new(server.url(), "test-key".to_string())
+            .unwrap()
+            .with_clock(Arc::new(clock.clone()));
+
+        let _mock = server
+            .mock("GET", "/api/v1/sessions/session-1")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                    "session_id": "session-1",
+                    "user_id": "123",
+                    "created_at": "2025-06-01T04:00:00Z",
+                    "expires_at": "2025-06-01T12:00:00Z",
+                    "is_active": true,
+                    "device_info": null,
+                    "ip_address": null
+                }"#,
+            )
+            .expect(3)
+            .create_async()
+            .await;
+
+        let session = client.validate_session("session-1").await.unwrap();
+        assert_eq!(session.expires_at, expires_at);
+
+        // Still valid at the exact expiry instant, expired one second after
+        clock.set(expires_at);
+        assert!(client.validate_session("session-1").await.is_ok());
+        clock.advance(chrono::Duration::seconds(1));
+        assert!(matches!(
+            client.validate_session("session-1").await,
+            Err(ApiError::TokenExpired)
+        ));
+    }
+
     #[test]
     fn test_atlas_user_to_claims() {
         let atlas_user = AtlasUser {
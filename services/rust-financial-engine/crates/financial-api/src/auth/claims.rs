@@ -3,6 +3,9 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+/// Clock skew tolerated on `exp`, `nbf` and `iat` when none is configured, in seconds
+pub const DEFAULT_LEEWAY_SECONDS: u64 = 60;
+
 /// JWT claims structure compatible with Atlas Financial
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JwtClaims {
@@ -21,12 +24,20 @@ pub struct JwtClaims {
     /// Expiration time
     pub exp: i64,
 
+    /// Not before
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nbf: Option<i64>,
+
     /// User details
     pub user: UserClaims,
 
     /// Permissions and roles
     pub permissions: Vec<String>,
 
+    /// OAuth2-style space-delimited scopes, merged with `permissions`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+
     /// Organization/tenant context
     pub org_id: Option<String>,
 
@@ -109,23 +120,49 @@ impl Permissions {
 impl JwtClaims {
     /// Check if token is expired
     pub fn is_expired(&self) -> bool {
+        self.is_expired_with_leeway(0)
+    }
+
+    /// Check if token expired more than `leeway` seconds ago
+    pub fn is_expired_with_leeway(&self, leeway: u64) -> bool {
         let now = Utc::now().timestamp();
-        self.exp < now
+        self.exp.saturating_add(leeway as i64) < now
     }
 
     /// Check if token was issued in the future (clock skew protection)
     pub fn is_issued_in_future(&self) -> bool {
+        self.is_issued_in_future_with_leeway(DEFAULT_LEEWAY_SECONDS)
+    }
+
+    /// Check if token was issued more than `leeway` seconds in the future
+    pub fn is_issued_in_future_with_leeway(&self, leeway: u64) -> bool {
         let now = Utc::now().timestamp();
-        self.iat > now + 60 // Allow 60 second clock skew
+        self.iat > now.saturating_add(leeway as i64)
+    }
+
+    /// Check if the token's `nbf` is more than `leeway` seconds in the future
+    pub fn is_not_yet_valid_with_leeway(&self, leeway: u64) -> bool {
+        let now = Utc::now().timestamp();
+        self.nbf.is_some_and(|nbf| nbf > now.saturating_add(leeway as i64))
     }
 
     /// Validate basic JWT structure and timing
     pub fn validate_basic(&self) -> Result<(), String> {
-        if self.is_expired() {
+        self.validate_with_leeway(DEFAULT_LEEWAY_SECONDS)
+    }
+
+    /// Validate JWT structure, tolerating `leeway` seconds of clock skew on
+    /// `exp`, `nbf` and `iat`
+    pub fn validate_with_leeway(&self, leeway: u64) -> Result<(), String> {
+        if self.is_expired_with_leeway(leeway) {
             return Err("Token expired".to_string());
         }
 
-        if self.is_issued_in_future() {
+        if self.is_not_yet_valid_with_leeway(leeway) {
+            return Err("Token not yet valid".to_string());
+        }
+
+        if self.is_issued_in_future_with_leeway(leeway) {
             return Err("Token issued in the future".to_string());
         }
 
@@ -145,9 +182,25 @@ impl JwtClaims {
         Ok(())
     }
 
+    /// All scopes granted by the token: `permissions` plus any `scope` entries
+    pub fn granted_scopes(&self) -> Vec<String> {
+        let mut scopes = self.permissions.clone();
+        for scope in self.scope.iter().flat_map(|s| s.split_whitespace()) {
+            if !scopes.iter().any(|existing| existing == scope) {
+                scopes.push(scope.to_string());
+            }
+        }
+        scopes
+    }
+
     /// Convert to AuthContext
     pub fn to_auth_context(&self) -> Result<AuthContext, String> {
-        self.validate_basic()?;
+        self.to_auth_context_with_leeway(DEFAULT_LEEWAY_SECONDS)
+    }
+
+    /// Convert to AuthContext, tolerating `leeway` seconds of clock skew
+    pub fn to_auth_context_with_leeway(&self, leeway: u64) -> Result<AuthContext, String> {
+        self.validate_with_leeway(leeway)?;
 
         let user_id =
             Uuid::parse_str(&self.user.id).map_err(|_| "Invalid user ID format".to_string())?;
@@ -164,7 +217,7 @@ impl JwtClaims {
             user_email: self.user.email.clone(),
             user_name: self.user.name.clone(),
             user_role: self.user.role.clone(),
-            permissions: self.permissions.clone(),
+            permissions: self.granted_scopes(),
             org_id,
             session_id: self.session_id.clone(),
             token_issued_at: DateTime::from_timestamp(self.iat, 0)
@@ -181,6 +234,11 @@ impl AuthContext {
         self.permissions.contains(&permission.to_string())
     }
 
+    /// Check if the token grants a scope such as `debt:read`
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.has_permission(scope)
+    }
+
     /// Check if user has any of the specified permissions
     pub fn has_any_permission(&self, permissions: &[&str]) -> bool {
         permissions.iter().any(|p| self.has_permission(p))
@@ -316,6 +374,7 @@ mod tests {
             aud: "financial-api".to_string(),
             iat: now.timestamp() - 3600,
             exp: now.timestamp() + 3600,
+            nbf: None,
             user: UserClaims {
                 id: "123e4567-e89b-12d3-a456-426614174000".to_string(),
                 email: "test@example.com".to_string(),
@@ -326,6 +385,7 @@ mod tests {
                 last_login: Some(now),
             },
             permissions: vec![Permissions::PORTFOLIO_READ.to_string()],
+            scope: None,
             org_id: None,
             session_id: "session123".to_string(),
         };
@@ -0,0 +1,255 @@
+/// Per-user concurrency limits for authenticated requests
+///
+/// Rate limits cap how many requests a user sends over time; this caps how
+/// many of them run at once, so one user firing expensive queries in
+/// parallel cannot starve everyone else.
+use crate::auth::AuthContextExtension;
+use crate::config::PerformanceConfig;
+use crate::error::ApiError;
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::warn;
+use uuid::Uuid;
+
+/// In-flight request slots, one semaphore per authenticated user
+#[derive(Clone)]
+pub struct UserConcurrencyLimiter {
+    max_in_flight: u32,
+    queue_timeout: Duration,
+    users: Arc<Mutex<HashMap<Uuid, Arc<Semaphore>>>>,
+}
+
+impl UserConcurrencyLimiter {
+    /// Allow each user `max_in_flight` concurrent requests, rejecting the rest
+    pub fn new(max_in_flight: u32) -> Self {
+        Self {
+            max_in_flight: max_in_flight.max(1),
+            queue_timeout: Duration::ZERO,
+            users: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Limiter configured from `max_concurrent_requests_per_user` and
+    /// `user_queue_timeout_ms`
+    pub fn from_config(performance: &PerformanceConfig) -> Self {
+        Self::new(performance.max_concurrent_requests_per_user)
+            .with_queue_timeout(Duration::from_millis(performance.user_queue_timeout_ms))
+    }
+
+    /// Let requests over the cap wait up to `timeout` for a slot
+    pub fn with_queue_timeout(mut self, timeout: Duration) -> Self {
+        self.queue_timeout = timeout;
+        self
+    }
+
+    /// Take one of `user_id`'s slots, waiting up to the queue timeout
+    pub async fn acquire(&self, user_id: Uuid) -> Result<UserPermit, ApiError> {
+        let semaphore = self.semaphore(user_id);
+        let acquired = if self.queue_timeout.is_zero() {
+            semaphore.clone().try_acquire_owned().ok()
+        } else {
+            tokio::time::timeout(self.queue_timeout, semaphore.clone().acquire_owned())
+                .await
+                .ok()
+                .and_then(|permit| permit.ok())
+        };
+
+        match acquired {
+            Some(permit) => Ok(UserPermit {
+                permit: Some(permit),
+                user_id,
+                limiter: self.clone(),
+            }),
+            None => {
+                self.release_idle(user_id);
+                Err(ApiError::ConcurrencyLimitExceeded {
+                    limit: self.max_in_flight,
+                })
+            }
+        }
+    }
+
+    /// Requests `user_id` currently has in flight
+    pub fn in_flight(&self, user_id: Uuid) -> u32 {
+        self.users
+            .lock()
+            .unwrap()
+            .get(&user_id)
+            .map_or(0, |s| self.max_in_flight - s.available_permits() as u32)
+    }
+
+    fn semaphore(&self, user_id: Uuid) -> Arc<Semaphore> {
+        self.users
+            .lock()
+            .unwrap()
+            .entry(user_id)
+            .or_insert_with(|| Arc::new(Semaphore::new(self.max_in_flight as usize)))
+            .clone()
+    }
+
+    /// Forget a user's semaphore once nothing holds or waits on it
+    fn release_idle(&self, user_id: Uuid) {
+        let mut users = self.users.lock().unwrap();
+        if users
+            .get(&user_id)
+            .is_some_and(|s| Arc::strong_count(s) == 1)
+        {
+            users.remove(&user_id);
+        }
+    }
+}
+
+/// A held slot, returned to the user's pool when dropped
+pub struct UserPermit {
+    permit: Option<OwnedSemaphorePermit>,
+    user_id: Uuid,
+    limiter: UserConcurrencyLimiter,
+}
+
+impl Drop for UserPermit {
+    fn drop(&mut self) {
+        // The permit keeps the semaphore alive; drop it first so an idle
+        // user's entry can be removed
+        self.permit.take();
+        self.limiter.release_idle(self.user_id);
+    }
+}
+
+/// Middleware holding a per-user slot for the duration of each request
+///
+/// Runs after authentication; requests without an authenticated user are
+/// left to the global limits. Requests over the cap get 429 Too Many Requests.
+pub async fn user_concurrency_middleware(
+    State(limiter): State<UserConcurrencyLimiter>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(user_id) = request
+        .extensions()
+        .get::<AuthContextExtension>()
+        .map(|AuthContextExtension(context)| context.user_id)
+    else {
+        return next.run(request).await;
+    };
+
+    match limiter.acquire(user_id).await {
+        Ok(_permit) => next.run(request).await,
+        Err(e) => {
+            warn!("Rejecting request from user {}: {}", user_id, e);
+            e.into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{AuthContext, UserRole};
+    use axum::{body::Body, http::StatusCode, middleware, routing::get, Router};
+    use chrono::Utc;
+    use tokio::sync::Notify;
+    use tower::ServiceExt;
+
+    const ALICE: &str = "123e4567-e89b-12d3-a456-426614174000";
+    const BOB: &str = "00000000-0000-0000-0000-000000000001";
+
+    fn request_as(user_id: &str) -> Request {
+        let mut request = Request::builder()
+            .uri("/query")
+            .body(Body::empty())
+            .unwrap();
+        request
+            .extensions_mut()
+            .insert(AuthContextExtension(AuthContext {
+                user_id: user_id.parse().unwrap(),
+                user_email: "user@example.com".to_string(),
+                user_name: "User".to_string(),
+                user_role: UserRole::User,
+                permissions: Vec::new(),
+                org_id: None,
+                session_id: "session".to_string(),
+                token_issued_at: Utc::now(),
+                token_expires_at: Utc::now(),
+            }));
+        request
+    }
+
+    #[tokio::test]
+    async fn test_user_over_the_cap_is_rejected_while_others_are_served() {
+        let limiter = UserConcurrencyLimiter::new(1);
+        let release = Arc::new(Notify::new());
+        let started = Arc::new(Notify::new());
+
+        // Alice's requests block until released; Bob's return at once
+        let (hold, entered) = (release.clone(), started.clone());
+        let app = Router::new()
+            .route(
+                "/query",
+                get(move |request: Request| {
+                    let (hold, entered) = (hold.clone(), entered.clone());
+                    async move {
+                        let AuthContextExtension(context) =
+                            request.extensions().get::<AuthContextExtension>().unwrap();
+                        if context.user_id.to_string() == ALICE {
+                            entered.notify_one();
+                            hold.notified().await;
+                        }
+                        "done"
+                    }
+                }),
+            )
+            .layer(middleware::from_fn_with_state(
+                limiter.clone(),
+                user_concurrency_middleware,
+            ));
+
+        let slow = tokio::spawn(app.clone().oneshot(request_as(ALICE)));
+        started.notified().await;
+        assert_eq!(limiter.in_flight(ALICE.parse().unwrap()), 1);
+
+        let rejected = app.clone().oneshot(request_as(ALICE)).await.unwrap();
+        assert_eq!(rejected.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        let other_user = app.clone().oneshot(request_as(BOB)).await.unwrap();
+        assert_eq!(other_user.status(), StatusCode::OK);
+
+        release.notify_one();
+        assert_eq!(slow.await.unwrap().unwrap().status(), StatusCode::OK);
+        assert_eq!(limiter.in_flight(ALICE.parse().unwrap()), 0);
+        assert!(limiter.users.lock().unwrap().is_empty());
+
+        // Alice's slot is free again once her first request finished
+        release.notify_one();
+        let retried = app.oneshot(request_as(ALICE)).await.unwrap();
+        assert_eq!(retried.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_queued_request_takes_a_slot_freed_before_timeout() {
+        let limiter = UserConcurrencyLimiter::new(1).with_queue_timeout(Duration::from_millis(500));
+        let user_id: Uuid = ALICE.parse().unwrap();
+
+        let first = limiter.acquire(user_id).await.unwrap();
+        let waiting = tokio::spawn({
+            let limiter = limiter.clone();
+            async move { limiter.acquire(user_id).await.map(|_| ()) }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        drop(first);
+        assert!(waiting.await.unwrap().is_ok());
+
+        let _held = limiter.acquire(user_id).await.unwrap();
+        let limiter = limiter.with_queue_timeout(Duration::from_millis(20));
+        assert!(matches!(
+            limiter.acquire(user_id).await,
+            Err(ApiError::ConcurrencyLimitExceeded { limit: 1 })
+        ));
+    }
+}
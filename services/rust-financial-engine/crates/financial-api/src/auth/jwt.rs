@@ -1,5 +1,6 @@
 /// JWT token validation and management
-use crate::auth::claims::{AuthContext, JwtClaims};
+use crate::auth::claims::{AuthContext, JwtClaims, DEFAULT_LEEWAY_SECONDS};
+use crate::config::JwtConfig;
 use crate::error::{ApiError, ApiResult};
 use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
@@ -39,7 +40,7 @@ impl JwtManager {
         let mut validation = Validation::new(algorithm);
         validation.set_issuer(&allowed_issuers);
         validation.set_audience(&["financial-api"]);
-        validation.leeway = 60; // Allow 60 second clock skew
+        validation.leeway = DEFAULT_LEEWAY_SECONDS;
 
         Ok(Self {
             encoding_key,
@@ -49,6 +50,29 @@ impl JwtManager {
         })
     }
 
+    /// Create a JWT manager that enforces the issuer, audience and timing
+    /// settings from configuration
+    ///
+    /// `validation.leeway` is the clock skew tolerated on `exp`, `nbf` and
+    /// `iat`, so tokens from services with slightly different clocks are
+    /// not rejected at the edges of their validity window.
+    pub fn from_config(secret: &str, config: &JwtConfig) -> ApiResult<Self> {
+        if config.validation.validate_aud && config.audience.is_empty() {
+            return Err(ApiError::ConfigurationError {
+                message: "JWT audience must be set when audience validation is enabled".to_string(),
+            });
+        }
+
+        let mut manager = Self::new(secret, vec![config.issuer.clone()])?;
+        manager.validation.set_audience(&[config.audience.as_str()]);
+        manager.validation.validate_aud = config.validation.validate_aud;
+        manager.validation.validate_exp = config.validation.validate_exp;
+        manager.validation.validate_nbf = config.validation.validate_nbf;
+        manager.validation.leeway = config.validation.leeway;
+
+        Ok(manager)
+    }
+
     /// Encode JWT claims into a token
     pub fn encode_token(&self, claims: &JwtClaims) -> ApiResult<String> {
         let header = Header::new(self.algorithm);
@@ -87,9 +111,9 @@ impl JwtManager {
 
         let claims = token_data.claims;
 
-        // Additional custom validation
+        // Additional custom validation, with the same clock skew tolerance
         claims
-            .validate_basic()
+            .validate_with_leeway(self.validation.leeway)
             .map_err(|msg| ApiError::InvalidToken { reason: msg })?;
 
         Ok(claims)
@@ -116,7 +140,7 @@ impl JwtManager {
     pub fn validate_and_extract_context(&self, token: &str) -> ApiResult<AuthContext> {
         let claims = self.decode_token(token)?;
         claims
-            .to_auth_context()
+            .to_auth_context_with_leeway(self.validation.leeway)
             .map_err(|msg| ApiError::InvalidToken { reason: msg })
     }
 
@@ -203,6 +227,7 @@ mod tests {
             aud: "financial-api".to_string(),
             iat: now.timestamp(),
             exp: (now + Duration::hours(1)).timestamp(),
+            nbf: None,
             user: UserClaims {
                 id: "123e4567-e89b-12d3-a456-426614174000".to_string(),
                 email: "test@example.com".to_string(),
@@ -213,6 +238,7 @@ mod tests {
                 last_login: Some(now),
             },
             permissions: vec!["portfolio:read".to_string()],
+            scope: None,
             org_id: None,
             session_id: "session123".to_string(),
         }
@@ -236,6 +262,59 @@ mod tests {
         assert_eq!(decoded_claims.user.email, claims.user.email);
     }
 
+    fn test_jwt_config(audience: &str) -> JwtConfig {
+        JwtConfig {
+            issuer: "atlas-financial".to_string(),
+            audience: audience.to_string(),
+            jwks_url: "http://localhost/jwks".to_string(),
+            validation: crate::config::TokenValidation {
+                validate_exp: true,
+                validate_nbf: false,
+                validate_aud: true,
+                leeway: 60,
+            },
+        }
+    }
+
+    #[test]
+    fn test_configured_audience_enforced() {
+        let secret = "super-secret-key-that-is-at-least-32-chars";
+        let jwt_manager = JwtManager::from_config(secret, &test_jwt_config("atlas-financial-api")).unwrap();
+
+        let mut claims = create_test_claims();
+        claims.aud = "atlas-financial-api".to_string();
+        let token = jwt_manager.encode_token(&claims).unwrap();
+        assert!(jwt_manager.decode_token(&token).is_ok());
+
+        claims.aud = "some-other-service".to_string();
+        let token = jwt_manager.encode_token(&claims).unwrap();
+        match jwt_manager.decode_token(&token) {
+            Err(ApiError::InvalidToken { reason }) => assert_eq!(reason, "Invalid token audience"),
+            other => panic!("expected audience rejection, got {:?}", other.map(|c| c.aud)),
+        }
+
+        assert!(JwtManager::from_config(secret, &test_jwt_config("")).is_err());
+    }
+
+    #[test]
+    fn test_scope_claim_merged_into_context() {
+        let jwt_manager = JwtManager::new(
+            "super-secret-key-that-is-at-least-32-chars",
+            vec!["atlas-financial".to_string()],
+        )
+        .unwrap();
+
+        let mut claims = create_test_claims();
+        claims.scope = Some("debt:read portfolio:read".to_string());
+        let token = jwt_manager.encode_token(&claims).unwrap();
+
+        let context = jwt_manager.validate_and_extract_context(&token).unwrap();
+        assert!(context.has_scope("debt:read"));
+        assert!(context.has_scope("portfolio:read"));
+        assert!(!context.has_scope("portfolio:write"));
+        assert_eq!(context.permissions.len(), 2);
+    }
+
     #[test]
     fn test_token_extraction() {
         let valid_header = "Bearer eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9.test";
@@ -274,6 +353,42 @@ mod tests {
         }
     }
 
+    fn manager_with_leeway(leeway: u64) -> JwtManager {
+        let mut config = test_jwt_config("financial-api");
+        config.validation.validate_nbf = true;
+        config.validation.leeway = leeway;
+        JwtManager::from_config("super-secret-key-that-is-at-least-32-chars", &config).unwrap()
+    }
+
+    #[test]
+    fn test_clock_skew_leeway() {
+        let lenient = manager_with_leeway(30);
+        let strict = manager_with_leeway(0);
+
+        let mut claims = create_test_claims();
+        claims.exp = (Utc::now() - Duration::seconds(10)).timestamp();
+        let expired = lenient.encode_token(&claims).unwrap();
+        assert!(lenient.decode_token(&expired).is_ok());
+        assert!(lenient.validate_and_extract_context(&expired).is_ok());
+        assert!(matches!(strict.decode_token(&expired), Err(ApiError::TokenExpired)));
+
+        // nbf and iat slightly ahead of this server's clock
+        let mut claims = create_test_claims();
+        claims.nbf = Some((Utc::now() + Duration::seconds(10)).timestamp());
+        let not_yet_valid = lenient.encode_token(&claims).unwrap();
+        assert!(lenient.decode_token(&not_yet_valid).is_ok());
+        assert!(strict.decode_token(&not_yet_valid).is_err());
+
+        let mut claims = create_test_claims();
+        claims.iat = (Utc::now() + Duration::seconds(10)).timestamp();
+        let issued_ahead = lenient.encode_token(&claims).unwrap();
+        assert!(lenient.decode_token(&issued_ahead).is_ok());
+        match strict.decode_token(&issued_ahead) {
+            Err(ApiError::InvalidToken { reason }) => assert_eq!(reason, "Token issued in the future"),
+            other => panic!("expected iat rejection, got {:?}", other.map(|c| c.iat)),
+        }
+    }
+
     #[test]
     fn test_token_blacklist() {
         let mut blacklist = TokenBlacklist::new();
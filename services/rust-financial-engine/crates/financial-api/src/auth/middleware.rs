@@ -326,6 +326,7 @@ mod tests {
             aud: "financial-api".to_string(),
             iat: now.timestamp(),
             exp: (now + Duration::hours(1)).timestamp(),
+            nbf: None,
             user: UserClaims {
                 id: "123e4567-e89b-12d3-a456-426614174000".to_string(),
                 email: "test@example.com".to_string(),
@@ -336,6 +337,7 @@ mod tests {
                 last_login: Some(now),
             },
             permissions: vec![Permissions::PORTFOLIO_READ.to_string()],
+            scope: None,
             org_id: None,
             session_id: "session123".to_string(),
         }
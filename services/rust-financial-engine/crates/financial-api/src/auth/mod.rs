@@ -1,5 +1,6 @@
 pub mod atlas;
 pub mod claims;
+pub mod concurrency;
 /// Authentication and authorization module
 ///
 /// Provides JWT token validation, Atlas API integration,
@@ -9,5 +10,6 @@ pub mod middleware;
 
 pub use atlas::*;
 pub use claims::*;
+pub use concurrency::*;
 pub use jwt::*;
 pub use middleware::*;
@@ -0,0 +1,425 @@
+/// Key-value cache shared by API features
+///
+/// Features talk to an [`AsyncCache`] rather than to Redis, so tests and
+/// offline development run against [`InMemoryCache`] while deployments point
+/// the same code at Redis. [`connect_cache`] picks the backend from
+/// configuration.
+///
+/// Cache failures are never request failures: backends log them and report
+/// a miss, and callers fall back to doing the work themselves.
+use async_graphql::async_trait::async_trait;
+use redis::aio::ConnectionManager;
+use redis::AsyncCommands;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tokio::time::Instant;
+use tracing::warn;
+
+use crate::config::RedisConfig;
+use crate::error::{ApiError, Result};
+
+/// Reconnect backoff: attempts wait up to `factor * base^attempt` milliseconds
+const CONNECT_BACKOFF_BASE_MS: u64 = 2;
+const CONNECT_BACKOFF_FACTOR: u64 = 100;
+const CONNECT_RETRIES: usize = 3;
+
+/// Cache state reported by the health check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheHealth {
+    Ok,
+    /// The cache answers too slowly; requests are bypassing it
+    Degraded,
+    Unavailable,
+}
+
+/// String cache with per-entry expiry
+#[async_trait]
+pub trait AsyncCache: Send + Sync + 'static {
+    /// Value stored under `key`, or `None` if it is missing, expired or unreachable
+    async fn get(&self, key: &str) -> Option<String>;
+    /// Store `value` under `key` until `ttl` has elapsed
+    async fn set(&self, key: &str, value: &str, ttl: Duration);
+    async fn delete(&self, key: &str);
+    /// Report whether the cache is usable
+    async fn check_cache_health(&self) -> CacheHealth;
+}
+
+/// Cache selected by `config`: Redis when enabled, otherwise in-process
+pub async fn connect_cache(config: &RedisConfig) -> Result<Arc<dyn AsyncCache>> {
+    if config.enabled {
+        Ok(Arc::new(RedisCache::connect(config).await?))
+    } else {
+        Ok(Arc::new(InMemoryCache::new()))
+    }
+}
+
+struct CacheEntry {
+    value: String,
+    expires_at: Instant,
+}
+
+/// Process-local cache, used when Redis is disabled
+///
+/// Expired entries are dropped when they are next read or overwritten.
+#[derive(Clone, Default)]
+pub struct InMemoryCache {
+    entries: Arc<RwLock<HashMap<String, CacheEntry>>>,
+}
+
+impl InMemoryCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl AsyncCache for InMemoryCache {
+    async fn get(&self, key: &str) -> Option<String> {
+        {
+            let entries = self.entries.read().await;
+            match entries.get(key) {
+                Some(entry) if entry.expires_at > Instant::now() => {
+                    return Some(entry.value.clone())
+                }
+                Some(_) => {}
+                None => return None,
+            }
+        }
+        let mut entries = self.entries.write().await;
+        if entries
+            .get(key)
+            .is_some_and(|entry| entry.expires_at <= Instant::now())
+        {
+            entries.remove(key);
+        }
+        None
+    }
+
+    async fn set(&self, key: &str, value: &str, ttl: Duration) {
+        self.entries.write().await.insert(
+            key.to_string(),
+            CacheEntry {
+                value: value.to_string(),
+                expires_at: Instant::now() + ttl,
+            },
+        );
+    }
+
+    async fn delete(&self, key: &str) {
+        self.entries.write().await.remove(key);
+    }
+
+    async fn check_cache_health(&self) -> CacheHealth {
+        CacheHealth::Ok
+    }
+}
+
+/// Cache shared by every API instance through Redis
+///
+/// Redis failures and timeouts are logged and treated as a cache miss, so
+/// callers fall back to the uncached path instead of the request failing or
+/// hanging on a slow Redis.
+#[derive(Clone)]
+pub struct RedisCache {
+    connection: ConnectionManager,
+    read_timeout: Duration,
+    write_timeout: Duration,
+    /// Timed-out operations since the last one that completed
+    consecutive_timeouts: Arc<AtomicU32>,
+}
+
+impl RedisCache {
+    /// Connect to the configured Redis instance
+    ///
+    /// Connecting gives up after `timeout` seconds. Reads and writes are
+    /// bounded by `read_timeout_ms` and `write_timeout_ms`.
+    pub async fn connect(config: &RedisConfig) -> Result<Self> {
+        let client =
+            redis::Client::open(config.url.as_str()).map_err(|e| ApiError::CacheError {
+                message: format!("Invalid Redis URL: {}", e),
+            })?;
+        let connect_timeout = Duration::from_secs(config.timeout);
+        let read_timeout = Duration::from_millis(config.read_timeout_ms);
+        let write_timeout = Duration::from_millis(config.write_timeout_ms);
+        let connecting = ConnectionManager::new_with_backoff_and_timeouts(
+            client,
+            CONNECT_BACKOFF_BASE_MS,
+            CONNECT_BACKOFF_FACTOR,
+            CONNECT_RETRIES,
+            read_timeout.max(write_timeout),
+            connect_timeout,
+        );
+        let connection = tokio::time::timeout(connect_timeout, connecting)
+            .await
+            .map_err(|_| ApiError::CacheError {
+                message: format!("Timed out connecting to Redis after {}s", config.timeout),
+            })?
+            .map_err(|e| ApiError::CacheError {
+                message: format!("Failed to connect to Redis: {}", e),
+            })?;
+        Ok(Self {
+            connection,
+            read_timeout,
+            write_timeout,
+            consecutive_timeouts: Arc::new(AtomicU32::new(0)),
+        })
+    }
+
+    /// Run a Redis command, giving up after `limit`
+    ///
+    /// `None` means the command failed or timed out and has been logged.
+    async fn bounded<T>(
+        &self,
+        operation: &str,
+        limit: Duration,
+        command: impl Future<Output = redis::RedisResult<T>>,
+    ) -> Option<T> {
+        match tokio::time::timeout(limit, command).await {
+            Ok(Ok(value)) => {
+                self.consecutive_timeouts.store(0, Ordering::Relaxed);
+                Some(value)
+            }
+            Ok(Err(e)) if e.is_timeout() => {
+                self.record_timeout(operation, limit);
+                None
+            }
+            Ok(Err(e)) => {
+                warn!("Redis {} failed: {}", operation, e);
+                None
+            }
+            Err(_) => {
+                self.record_timeout(operation, limit);
+                None
+            }
+        }
+    }
+
+    fn record_timeout(&self, operation: &str, limit: Duration) {
+        let timeouts = self.consecutive_timeouts.fetch_add(1, Ordering::Relaxed) + 1;
+        warn!(
+            "Redis {} timed out after {}ms ({} in a row); bypassing cache",
+            operation,
+            limit.as_millis(),
+            timeouts
+        );
+    }
+}
+
+#[async_trait]
+impl AsyncCache for RedisCache {
+    async fn get(&self, key: &str) -> Option<String> {
+        let mut connection = self.connection.clone();
+        self.bounded(
+            &format!("read of {}", key),
+            self.read_timeout,
+            connection.get(key),
+        )
+        .await
+        .flatten()
+    }
+
+    async fn set(&self, key: &str, value: &str, ttl: Duration) {
+        let mut connection = self.connection.clone();
+        // Redis rejects a zero expiry, so round short TTLs up to a millisecond
+        let ttl_ms = ttl.as_millis().clamp(1, u64::MAX as u128) as u64;
+        self.bounded::<()>(
+            &format!("write of {}", key),
+            self.write_timeout,
+            connection.pset_ex(key, value, ttl_ms),
+        )
+        .await;
+    }
+
+    async fn delete(&self, key: &str) {
+        let mut connection = self.connection.clone();
+        self.bounded::<()>(
+            &format!("delete of {}", key),
+            self.write_timeout,
+            connection.del(key),
+        )
+        .await;
+    }
+
+    /// Ping Redis
+    ///
+    /// The cache is degraded while recent commands are timing out, even if
+    /// the ping itself gets through.
+    async fn check_cache_health(&self) -> CacheHealth {
+        let mut connection = self.connection.clone();
+        let ping = tokio::time::timeout(
+            self.read_timeout,
+            redis::cmd("PING").query_async::<_, String>(&mut connection),
+        )
+        .await;
+        match ping {
+            Ok(Err(e)) if !e.is_timeout() => {
+                warn!("Redis health check failed: {}", e);
+                CacheHealth::Unavailable
+            }
+            Ok(Ok(_)) if self.consecutive_timeouts.load(Ordering::Relaxed) == 0 => CacheHealth::Ok,
+            _ => CacheHealth::Degraded,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use std::collections::HashMap;
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::{TcpListener, TcpStream};
+
+    /// Minimal Redis stand-in speaking enough RESP for [`RedisCache`]
+    async fn fake_redis() -> RedisConfig {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let store: Arc<RwLock<HashMap<String, (String, Instant)>>> = Arc::default();
+        tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                tokio::spawn(serve_redis(socket, store.clone()));
+            }
+        });
+        RedisConfig {
+            url: format!("redis://{}", address),
+            enabled: true,
+            ..Config::test_config().redis
+        }
+    }
+
+    async fn serve_redis(
+        socket: TcpStream,
+        store: Arc<RwLock<HashMap<String, (String, Instant)>>>,
+    ) {
+        let (reader, mut writer) = socket.into_split();
+        let mut reader = BufReader::new(reader);
+        while let Some(command) = read_command(&mut reader).await {
+            let name = command[0].to_ascii_uppercase();
+            let reply = match (name.as_str(), &command[1..]) {
+                ("PING", _) => "+PONG\r\n".to_string(),
+                ("GET", [key]) => match store.read().await.get(key) {
+                    Some((value, expires_at)) if *expires_at > Instant::now() => {
+                        format!("${}\r\n{}\r\n", value.len(), value)
+                    }
+                    _ => "$-1\r\n".to_string(),
+                },
+                ("PSETEX", [key, ttl_ms, value]) => {
+                    let ttl = Duration::from_millis(ttl_ms.parse().unwrap());
+                    store
+                        .write()
+                        .await
+                        .insert(key.clone(), (value.clone(), Instant::now() + ttl));
+                    "+OK\r\n".to_string()
+                }
+                ("DEL", [key]) => {
+                    let removed = store.write().await.remove(key).is_some();
+                    format!(":{}\r\n", removed as u8)
+                }
+                _ => "+OK\r\n".to_string(),
+            };
+            if writer.write_all(reply.as_bytes()).await.is_err() {
+                return;
+            }
+        }
+    }
+
+    async fn read_command(
+        reader: &mut BufReader<tokio::net::tcp::OwnedReadHalf>,
+    ) -> Option<Vec<String>> {
+        let mut line = String::new();
+        reader.read_line(&mut line).await.ok().filter(|&n| n > 0)?;
+        let count: usize = line.trim().strip_prefix('*')?.parse().ok()?;
+        let mut parts = Vec::with_capacity(count);
+        for _ in 0..count {
+            line.clear();
+            reader.read_line(&mut line).await.ok()?;
+            let len: usize = line.trim().strip_prefix('$')?.parse().ok()?;
+            let mut bytes = vec![0; len + 2];
+            reader.read_exact(&mut bytes).await.ok()?;
+            bytes.truncate(len);
+            parts.push(String::from_utf8(bytes).ok()?);
+        }
+        Some(parts)
+    }
+
+    /// Caching behaviour every backend must share
+    async fn exercise(cache: &dyn AsyncCache) {
+        assert_eq!(cache.check_cache_health().await, CacheHealth::Ok);
+        assert_eq!(cache.get("portfolio:1").await, None);
+
+        cache
+            .set("portfolio:1", "cached", Duration::from_secs(60))
+            .await;
+        assert_eq!(cache.get("portfolio:1").await.as_deref(), Some("cached"));
+
+        // Writing again replaces the value
+        cache
+            .set("portfolio:1", "updated", Duration::from_secs(60))
+            .await;
+        assert_eq!(cache.get("portfolio:1").await.as_deref(), Some("updated"));
+
+        cache.delete("portfolio:1").await;
+        assert_eq!(cache.get("portfolio:1").await, None);
+        // Deleting a missing key is not an error
+        cache.delete("portfolio:1").await;
+
+        cache
+            .set("portfolio:2", "short-lived", Duration::from_millis(50))
+            .await;
+        assert_eq!(
+            cache.get("portfolio:2").await.as_deref(),
+            Some("short-lived")
+        );
+        tokio::time::sleep(Duration::from_millis(120)).await;
+        assert_eq!(cache.get("portfolio:2").await, None);
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_cache() {
+        exercise(&InMemoryCache::new()).await;
+    }
+
+    #[tokio::test]
+    async fn test_redis_cache() {
+        let cache = RedisCache::connect(&fake_redis().await).await.unwrap();
+        exercise(&cache).await;
+    }
+
+    #[tokio::test]
+    async fn test_backend_follows_config() {
+        // Unit test configuration disables Redis, so nothing needs to be running
+        let offline = connect_cache(&Config::test_config().redis).await.unwrap();
+        exercise(offline.as_ref()).await;
+
+        // Redis entries are visible to every instance connected to it
+        let redis = fake_redis().await;
+        let first = connect_cache(&redis).await.unwrap();
+        let second = connect_cache(&redis).await.unwrap();
+        first.set("quote", "42", Duration::from_secs(60)).await;
+        assert_eq!(second.get("quote").await.as_deref(), Some("42"));
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_redis_fails_to_connect() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        drop(listener);
+        let config = RedisConfig {
+            url: format!("redis://{}", address),
+            enabled: true,
+            ..Config::test_config().redis
+        };
+
+        let connecting = connect_cache(&config);
+        let result = tokio::time::timeout(Duration::from_secs(5), connecting)
+            .await
+            .unwrap();
+        assert!(matches!(result, Err(ApiError::CacheError { .. })));
+    }
+}
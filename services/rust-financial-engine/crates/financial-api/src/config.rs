@@ -29,6 +29,9 @@ pub struct Config {
 
     /// Performance settings
     pub performance: PerformanceConfig,
+
+    /// Cross-origin resource sharing policy
+    pub cors: CorsConfig,
 }
 
 /// Environment type
@@ -68,8 +71,11 @@ pub struct TokenValidation {
 /// GraphQL configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GraphqlConfig {
-    /// Enable GraphQL introspection
+    /// Enable GraphQL introspection and the `/schema` SDL endpoint
     pub introspection: bool,
+    /// Keep introspection enabled in production; otherwise production
+    /// ignores `introspection`
+    pub allow_introspection_in_production: bool,
     /// Enable GraphQL playground
     pub playground: bool,
     /// Maximum query depth
@@ -78,6 +84,10 @@ pub struct GraphqlConfig {
     pub max_complexity: u32,
     /// Request timeout in seconds
     pub timeout: u64,
+    /// Seconds an idle subscription waits before sending a heartbeat
+    pub subscription_heartbeat_interval: u64,
+    /// Render the `/schema` SDL and its ETag once at startup rather than per request
+    pub cache_schema_sdl: bool,
 }
 
 /// Redis configuration
@@ -89,6 +99,10 @@ pub struct RedisConfig {
     pub pool_size: u32,
     /// Connection timeout in seconds
     pub timeout: u64,
+    /// Milliseconds a cache read may take before it counts as a miss
+    pub read_timeout_ms: u64,
+    /// Milliseconds a cache write may take before it is abandoned
+    pub write_timeout_ms: u64,
     /// Default TTL for cached items (seconds)
     pub default_ttl: u64,
     /// Enable Redis caching
@@ -113,14 +127,41 @@ pub struct MonitoringConfig {
 pub struct PerformanceConfig {
     /// Maximum concurrent requests
     pub max_concurrent_requests: u32,
+    /// Maximum requests one authenticated user may have in flight at once
+    pub max_concurrent_requests_per_user: u32,
+    /// How long a user's request over the cap waits for a free slot before
+    /// it is rejected; zero rejects immediately
+    pub user_queue_timeout_ms: u64,
     /// Request rate limit per minute
     pub rate_limit_per_minute: u32,
-    /// Enable request compression
+    /// Compress responses with gzip or brotli when the client accepts it
     pub enable_compression: bool,
-    /// Maximum request size in bytes
+    /// Responses smaller than this many bytes are sent uncompressed
+    pub compression_min_size: u16,
+    /// Maximum request body size in bytes; larger bodies get 413 Payload Too Large
     pub max_request_size: u64,
 }
 
+/// CORS configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CorsConfig {
+    /// Allowed origins; `*` allows any origin and is only meant for development
+    pub allowed_origins: Vec<String>,
+    /// Allowed HTTP methods
+    pub allowed_methods: Vec<String>,
+    /// Allowed request headers
+    pub allowed_headers: Vec<String>,
+    /// Allow cookies and authorization headers on cross-origin requests
+    pub allow_credentials: bool,
+}
+
+impl CorsConfig {
+    /// Check whether the policy allows every origin
+    pub fn allows_any_origin(&self) -> bool {
+        self.allowed_origins.iter().any(|origin| origin == "*")
+    }
+}
+
 /// Configuration errors
 #[derive(Error, Debug)]
 pub enum ConfigError {
@@ -184,13 +225,22 @@ impl Config {
                 validate_exp: true,
                 validate_nbf: true,
                 validate_aud: true,
-                leeway: 30, // 30 seconds clock skew tolerance
+                leeway: Self::get_env_var("JWT_LEEWAY_SECONDS")
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(30), // 30 seconds clock skew tolerance
             },
         };
 
         // GraphQL configuration
         let graphql = GraphqlConfig {
-            introspection: environment == Environment::Development,
+            introspection: Self::get_env_var("GRAPHQL_INTROSPECTION")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(environment == Environment::Development),
+            allow_introspection_in_production: Self::get_env_var(
+                "GRAPHQL_ALLOW_PRODUCTION_INTROSPECTION",
+            )
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(false),
             playground: environment != Environment::Production,
             max_depth: Self::get_env_var("GRAPHQL_MAX_DEPTH")
                 .and_then(|v| v.parse().ok())
@@ -201,6 +251,15 @@ impl Config {
             timeout: Self::get_env_var("GRAPHQL_TIMEOUT")
                 .and_then(|v| v.parse().ok())
                 .unwrap_or(30),
+            subscription_heartbeat_interval: Self::get_env_var(
+                "GRAPHQL_SUBSCRIPTION_HEARTBEAT_INTERVAL",
+            )
+            .and_then(|v| v.parse().ok())
+            .filter(|&seconds| seconds > 0)
+            .unwrap_or(15),
+            cache_schema_sdl: Self::get_env_var("GRAPHQL_CACHE_SCHEMA_SDL")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(true),
         };
 
         // Redis configuration
@@ -213,6 +272,14 @@ impl Config {
             timeout: Self::get_env_var("REDIS_TIMEOUT")
                 .and_then(|v| v.parse().ok())
                 .unwrap_or(5),
+            read_timeout_ms: Self::get_env_var("REDIS_READ_TIMEOUT_MS")
+                .and_then(|v| v.parse().ok())
+                .filter(|&ms| ms > 0)
+                .unwrap_or(500),
+            write_timeout_ms: Self::get_env_var("REDIS_WRITE_TIMEOUT_MS")
+                .and_then(|v| v.parse().ok())
+                .filter(|&ms| ms > 0)
+                .unwrap_or(500),
             default_ttl: Self::get_env_var("REDIS_DEFAULT_TTL")
                 .and_then(|v| v.parse().ok())
                 .unwrap_or(3600), // 1 hour
@@ -239,15 +306,44 @@ impl Config {
             max_concurrent_requests: Self::get_env_var("MAX_CONCURRENT_REQUESTS")
                 .and_then(|v| v.parse().ok())
                 .unwrap_or(1000),
+            max_concurrent_requests_per_user: Self::get_env_var("MAX_CONCURRENT_REQUESTS_PER_USER")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(20),
+            user_queue_timeout_ms: Self::get_env_var("USER_QUEUE_TIMEOUT_MS")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0),
             rate_limit_per_minute: Self::get_env_var("RATE_LIMIT_PER_MINUTE")
                 .and_then(|v| v.parse().ok())
                 .unwrap_or(1000),
             enable_compression: Self::get_env_var("ENABLE_COMPRESSION")
                 .and_then(|v| v.parse().ok())
                 .unwrap_or(true),
+            compression_min_size: Self::get_env_var("COMPRESSION_MIN_SIZE")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(1024),
             max_request_size: Self::get_env_var("MAX_REQUEST_SIZE")
                 .and_then(|v| v.parse().ok())
-                .unwrap_or(10 * 1024 * 1024), // 10MB
+                .unwrap_or(1024 * 1024), // 1MB
+        };
+
+        // CORS configuration - deny cross-origin requests in production unless allowlisted
+        let default_origins = match environment {
+            Environment::Production => Vec::new(),
+            Environment::Development | Environment::Test => vec!["*".to_string()],
+        };
+        let cors = CorsConfig {
+            allowed_origins: Self::get_env_var("CORS_ALLOWED_ORIGINS")
+                .map(|v| Self::split_list(&v))
+                .unwrap_or(default_origins),
+            allowed_methods: Self::get_env_var("CORS_ALLOWED_METHODS")
+                .map(|v| Self::split_list(&v))
+                .unwrap_or_else(|| vec!["GET".to_string(), "POST".to_string(), "OPTIONS".to_string()]),
+            allowed_headers: Self::get_env_var("CORS_ALLOWED_HEADERS")
+                .map(|v| Self::split_list(&v))
+                .unwrap_or_else(|| vec!["content-type".to_string(), "authorization".to_string()]),
+            allow_credentials: Self::get_env_var("CORS_ALLOW_CREDENTIALS")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(environment == Environment::Production),
         };
 
         Ok(Config {
@@ -259,6 +355,7 @@ impl Config {
             redis,
             monitoring,
             performance,
+            cors,
         })
     }
 
@@ -281,15 +378,20 @@ impl Config {
             },
             graphql: GraphqlConfig {
                 introspection: true,
+                allow_introspection_in_production: false,
                 playground: true,
                 max_depth: 10,
                 max_complexity: 100,
                 timeout: 10,
+                subscription_heartbeat_interval: 15,
+                cache_schema_sdl: true,
             },
             redis: RedisConfig {
                 url: "redis://localhost:6379/1".to_string(), // Use DB 1 for tests
                 pool_size: 5,
                 timeout: 2,
+                read_timeout_ms: 100,
+                write_timeout_ms: 100,
                 default_ttl: 300, // 5 minutes for tests
                 enabled: false,   // Disable Redis for unit tests
             },
@@ -301,10 +403,19 @@ impl Config {
             },
             performance: PerformanceConfig {
                 max_concurrent_requests: 100,
+                max_concurrent_requests_per_user: 10,
+                user_queue_timeout_ms: 0,
                 rate_limit_per_minute: 100,
                 enable_compression: false,
+                compression_min_size: 1024,
                 max_request_size: 1024 * 1024, // 1MB for tests
             },
+            cors: CorsConfig {
+                allowed_origins: vec!["*".to_string()],
+                allowed_methods: vec!["GET".to_string(), "POST".to_string(), "OPTIONS".to_string()],
+                allowed_headers: vec!["content-type".to_string(), "authorization".to_string()],
+                allow_credentials: false,
+            },
         }
     }
 
@@ -313,6 +424,16 @@ impl Config {
         env::var(key).ok().filter(|v| !v.is_empty())
     }
 
+    /// Split a comma-separated environment value into trimmed, non-empty items
+    fn split_list(value: &str) -> Vec<String> {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
     /// Validate configuration
     pub fn validate(&self) -> Result<(), ConfigError> {
         // Validate JWT issuer URL
@@ -332,6 +453,30 @@ impl Config {
             })?;
         }
 
+        // Wildcard origins must not be combined with credentials or used in production
+        if self.cors.allows_any_origin() && (self.cors.allow_credentials || self.is_production()) {
+            return Err(ConfigError::InvalidEnvVar {
+                var: "CORS_ALLOWED_ORIGINS".to_string(),
+                value: self.cors.allowed_origins.join(","),
+            });
+        }
+
+        // A zero body limit would reject every GraphQL request
+        if self.performance.max_request_size == 0 {
+            return Err(ConfigError::InvalidEnvVar {
+                var: "MAX_REQUEST_SIZE".to_string(),
+                value: "0".to_string(),
+            });
+        }
+
+        // A zero per-user cap would reject every authenticated request
+        if self.performance.max_concurrent_requests_per_user == 0 {
+            return Err(ConfigError::InvalidEnvVar {
+                var: "MAX_CONCURRENT_REQUESTS_PER_USER".to_string(),
+                value: "0".to_string(),
+            });
+        }
+
         // Validate port range
         if self.port == 0 && self.environment != Environment::Test {
             return Err(ConfigError::InvalidEnvVar {
@@ -368,6 +513,15 @@ impl Config {
     pub fn is_test(&self) -> bool {
         self.environment == Environment::Test
     }
+
+    /// Whether the schema may be introspected or downloaded
+    ///
+    /// Production never exposes the schema unless
+    /// `allow_introspection_in_production` is set as well.
+    pub fn introspection_enabled(&self) -> bool {
+        self.graphql.introspection
+            && (!self.is_production() || self.graphql.allow_introspection_in_production)
+    }
 }
 
 impl std::str::FromStr for Environment {
@@ -463,6 +617,50 @@ mod tests {
         assert!(!prod_config.is_test());
     }
 
+    #[test]
+    fn test_introspection_disabled_in_production_without_override() {
+        let dev_config = Config {
+            environment: Environment::Development,
+            ..Config::test_config()
+        };
+        assert!(dev_config.introspection_enabled());
+
+        let mut prod_config = Config {
+            environment: Environment::Production,
+            ..Config::test_config()
+        };
+        assert!(prod_config.graphql.introspection);
+        assert!(!prod_config.introspection_enabled());
+
+        prod_config.graphql.allow_introspection_in_production = true;
+        assert!(prod_config.introspection_enabled());
+
+        prod_config.graphql.introspection = false;
+        assert!(!prod_config.introspection_enabled());
+    }
+
+    #[test]
+    fn test_split_list() {
+        assert_eq!(
+            Config::split_list(" https://app.atlas.com, ,https://admin.atlas.com "),
+            vec!["https://app.atlas.com", "https://admin.atlas.com"]
+        );
+        assert!(Config::split_list("").is_empty());
+    }
+
+    #[test]
+    fn test_cors_wildcard_rejected_in_production() {
+        let mut config = Config {
+            port: 8080,
+            environment: Environment::Production,
+            ..Config::test_config()
+        };
+        assert!(config.validate().is_err());
+
+        config.cors.allowed_origins = vec!["https://app.atlas-financial.com".to_string()];
+        assert!(config.validate().is_ok());
+    }
+
     #[test]
     fn test_from_env_with_defaults() {
         // Clear environment
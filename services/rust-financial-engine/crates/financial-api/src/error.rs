@@ -8,6 +8,7 @@ use axum::{
     response::{IntoResponse, Response},
     Json,
 };
+use crate::monitoring::request_id::current_request_id;
 use financial_core::error::{ErrorCategory, FinancialError};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
@@ -51,6 +52,9 @@ pub enum ApiError {
     #[error("Missing required field: {field}")]
     MissingField { field: String },
 
+    #[error("Invalid debts: {}", describe_diagnostics(.diagnostics))]
+    InvalidDebts { diagnostics: Vec<DebtDiagnostic> },
+
     /// Business logic errors
     #[error("Portfolio not found: {id}")]
     PortfolioNotFound { id: String },
@@ -81,6 +85,9 @@ pub enum ApiError {
     #[error("Rate limit exceeded: {limit} requests per {window}")]
     RateLimitExceeded { limit: u32, window: String },
 
+    #[error("Too many concurrent requests: at most {limit} may be in flight per user")]
+    ConcurrencyLimitExceeded { limit: u32 },
+
     #[error("Request timeout")]
     RequestTimeout,
 
@@ -106,6 +113,37 @@ pub enum ApiError {
     NotImplemented { operation: String },
 }
 
+/// One problem with one debt in a multi-debt input
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DebtDiagnostic {
+    /// Position of the debt in the input list
+    pub index: usize,
+    /// Debt name as submitted, trimmed
+    pub name: String,
+    /// Offending field of the debt input, e.g. `balance`
+    pub field: String,
+    pub message: String,
+}
+
+impl fmt::Display for DebtDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "debts[{}] ({}) {}: {}",
+            self.index, self.name, self.field, self.message
+        )
+    }
+}
+
+fn describe_diagnostics(diagnostics: &[DebtDiagnostic]) -> String {
+    diagnostics
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
 /// Error response for REST endpoints
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ErrorResponse {
@@ -146,7 +184,7 @@ impl ApiError {
             ApiError::AuthorizationFailed { .. } => "AUTHZ_FAILED",
             ApiError::InvalidToken { .. } => "INVALID_TOKEN",
             ApiError::TokenExpired => "TOKEN_EXPIRED",
-            ApiError::ValidationError { .. } => "VALIDATION_ERROR",
+            ApiError::ValidationError { .. } | ApiError::InvalidDebts { .. } => "VALIDATION_ERROR",
             ApiError::InvalidInput { .. } => "INVALID_INPUT",
             ApiError::MissingField { .. } => "MISSING_FIELD",
             ApiError::PortfolioNotFound { .. } => "PORTFOLIO_NOT_FOUND",
@@ -158,6 +196,7 @@ impl ApiError {
             ApiError::CacheError { .. } => "CACHE_ERROR",
             ApiError::DatabaseError { .. } => "DATABASE_ERROR",
             ApiError::RateLimitExceeded { .. } => "RATE_LIMIT_EXCEEDED",
+            ApiError::ConcurrencyLimitExceeded { .. } => "CONCURRENCY_LIMIT_EXCEEDED",
             ApiError::RequestTimeout => "REQUEST_TIMEOUT",
             ApiError::ConfigurationError { .. } => "CONFIG_ERROR",
             ApiError::ServiceUnavailable { .. } => "SERVICE_UNAVAILABLE",
@@ -168,6 +207,23 @@ impl ApiError {
         }
     }
 
+    /// Code of the underlying financial error, which is finer-grained than
+    /// the category-level [`code`](Self::code)
+    pub fn financial_code(&self) -> Option<&'static str> {
+        match self {
+            ApiError::Financial(e) => Some(e.code()),
+            _ => None,
+        }
+    }
+
+    /// Per-debt problems behind an [`InvalidDebts`](Self::InvalidDebts) error
+    pub fn debt_diagnostics(&self) -> Option<&[DebtDiagnostic]> {
+        match self {
+            ApiError::InvalidDebts { diagnostics } => Some(diagnostics),
+            _ => None,
+        }
+    }
+
     /// Get the error category
     pub fn category(&self) -> &'static str {
         match self {
@@ -191,7 +247,8 @@ impl ApiError {
             | ApiError::TokenExpired => "authentication",
             ApiError::ValidationError { .. }
             | ApiError::InvalidInput { .. }
-            | ApiError::MissingField { .. } => "validation",
+            | ApiError::MissingField { .. }
+            | ApiError::InvalidDebts { .. } => "validation",
             ApiError::PortfolioNotFound { .. }
             | ApiError::AssetNotFound { .. }
             | ApiError::DebtAccountNotFound { .. }
@@ -200,7 +257,9 @@ impl ApiError {
             ApiError::AtlasApiError { .. }
             | ApiError::CacheError { .. }
             | ApiError::DatabaseError { .. } => "external",
-            ApiError::RateLimitExceeded { .. } | ApiError::RequestTimeout => "throttling",
+            ApiError::RateLimitExceeded { .. }
+            | ApiError::ConcurrencyLimitExceeded { .. }
+            | ApiError::RequestTimeout => "throttling",
             ApiError::ConfigurationError { .. }
             | ApiError::ServiceUnavailable { .. }
             | ApiError::InternalError { .. } => "system",
@@ -223,7 +282,8 @@ impl ApiError {
             }
             ApiError::ValidationError { .. }
             | ApiError::InvalidInput { .. }
-            | ApiError::MissingField { .. } => StatusCode::BAD_REQUEST,
+            | ApiError::MissingField { .. }
+            | ApiError::InvalidDebts { .. } => StatusCode::BAD_REQUEST,
             ApiError::PortfolioNotFound { .. }
             | ApiError::AssetNotFound { .. }
             | ApiError::DebtAccountNotFound { .. }
@@ -232,7 +292,9 @@ impl ApiError {
             | ApiError::DatabaseError { .. }
             | ApiError::ServiceUnavailable { .. } => StatusCode::BAD_GATEWAY,
             ApiError::CacheError { .. } => StatusCode::SERVICE_UNAVAILABLE,
-            ApiError::RateLimitExceeded { .. } => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::RateLimitExceeded { .. } | ApiError::ConcurrencyLimitExceeded { .. } => {
+                StatusCode::TOO_MANY_REQUESTS
+            }
             ApiError::RequestTimeout => StatusCode::REQUEST_TIMEOUT,
             ApiError::ConfigurationError { .. }
             | ApiError::InternalError { .. }
@@ -251,7 +313,7 @@ impl ApiError {
             | ApiError::DatabaseError { .. }
             | ApiError::ServiceUnavailable { .. }
             | ApiError::RequestTimeout => true,
-            ApiError::RateLimitExceeded { .. } => true,
+            ApiError::RateLimitExceeded { .. } | ApiError::ConcurrencyLimitExceeded { .. } => true,
             _ => false,
         }
     }
@@ -271,11 +333,19 @@ impl ApiError {
                 format!("Check the format of the '{}' field", field),
                 "Refer to the API documentation for valid input formats".to_string(),
             ]),
+            ApiError::InvalidDebts { .. } => Some(vec![
+                "Correct each debt field listed in the diagnostics".to_string(),
+                "No debts were processed; resubmit the full list once fixed".to_string(),
+            ]),
             ApiError::RateLimitExceeded { .. } => Some(vec![
                 "Reduce the frequency of your requests".to_string(),
                 "Implement exponential backoff in your client".to_string(),
                 "Contact support if you need higher rate limits".to_string(),
             ]),
+            ApiError::ConcurrencyLimitExceeded { .. } => Some(vec![
+                "Wait for your in-flight requests to finish before sending more".to_string(),
+                "Batch related queries into a single request".to_string(),
+            ]),
             ApiError::Financial(FinancialError::CurrencyMismatch { .. }) => Some(vec![
                 "Ensure all monetary amounts use the same currency".to_string(),
                 "Convert currencies before performing calculations".to_string(),
@@ -321,9 +391,20 @@ impl ErrorExtensions for ApiError {
         error = error.extend_with(|_, e| {
             e.set("code", self.code());
             e.set("category", self.category());
+            if let Some(financial_code) = self.financial_code() {
+                e.set("financialCode", financial_code);
+            }
             if let Some(suggestions) = self.suggestions() {
                 e.set("suggestions", suggestions);
             }
+            if let Some(diagnostics) = self.debt_diagnostics() {
+                if let Ok(diagnostics) = async_graphql::to_value(diagnostics) {
+                    e.set("diagnostics", diagnostics);
+                }
+            }
+            if let Some(request_id) = current_request_id() {
+                e.set("requestId", request_id);
+            }
         });
 
         // Log error based on severity
@@ -346,10 +427,14 @@ impl IntoResponse for ApiError {
                 code: self.code().to_string(),
                 message: self.to_string(),
                 category: self.category().to_string(),
-                details: None,
+                details: match (self.financial_code(), self.debt_diagnostics()) {
+                    (Some(code), _) => Some(json!({ "financialCode": code })),
+                    (None, Some(diagnostics)) => Some(json!({ "diagnostics": diagnostics })),
+                    (None, None) => None,
+                },
                 suggestions: self.suggestions(),
             },
-            request_id: None, // Would be set by middleware
+            request_id: current_request_id(),
             timestamp: chrono::Utc::now(),
         };
 
@@ -436,6 +521,14 @@ mod tests {
         assert_eq!(api_error.code(), "MATH_ERROR");
         assert_eq!(api_error.category(), "mathematical");
         assert_eq!(api_error.status_code(), StatusCode::BAD_REQUEST);
+        assert_eq!(api_error.financial_code(), Some("DIVISION_BY_ZERO"));
+
+        let extensions = api_error.extend().extensions.unwrap();
+        assert_eq!(
+            extensions.get("financialCode"),
+            Some(&async_graphql::Value::from("DIVISION_BY_ZERO"))
+        );
+        assert_eq!(ApiError::RequestTimeout.financial_code(), None);
     }
 
     #[test]
@@ -0,0 +1,357 @@
+/// Authorization audit trail for GraphQL mutations
+///
+/// Every top-level mutation field that executes produces an [`AuditRecord`]
+/// naming the authenticated subject, the operation and field, the entity IDs
+/// it touched, and whether it succeeded. Records go to an [`AuditSink`]
+/// rather than the request trace, so they can be retained and queried apart
+/// from operational logs. Denied mutations are recorded as failures.
+use async_graphql::async_trait::async_trait;
+use async_graphql::extensions::{
+    Extension, ExtensionContext, ExtensionFactory, NextExecute, NextParseQuery,
+};
+use async_graphql::parser::types::{ExecutableDocument, OperationType, Selection, SelectionSet};
+use async_graphql::{Name, PathSegment, Response, ServerResult, Value, Variables};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use tokio::sync::RwLock;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::auth::AuthContext;
+
+/// Tracing target audit records are written under by [`TracingAuditSink`]
+pub const AUDIT_TARGET: &str = "audit";
+
+/// One mutation field executed on behalf of a subject
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AuditRecord {
+    /// Authenticated user, `None` for anonymous requests
+    pub subject: Option<Uuid>,
+    /// Client-supplied operation name, if any
+    pub operation_name: Option<String>,
+    /// Mutation field that was executed
+    pub field: String,
+    /// IDs passed to the mutation or returned as the `id` of its result
+    pub entity_ids: Vec<String>,
+    /// Whether the field resolved without errors
+    pub succeeded: bool,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// Destination for audit records
+#[async_trait]
+pub trait AuditSink: Send + Sync + 'static {
+    async fn record(&self, record: AuditRecord);
+}
+
+/// Writes audit records as structured log events under [`AUDIT_TARGET`]
+#[derive(Clone, Copy, Default)]
+pub struct TracingAuditSink;
+
+#[async_trait]
+impl AuditSink for TracingAuditSink {
+    async fn record(&self, record: AuditRecord) {
+        info!(
+            target: AUDIT_TARGET,
+            subject = ?record.subject,
+            operation_name = ?record.operation_name,
+            field = %record.field,
+            entity_ids = ?record.entity_ids,
+            succeeded = record.succeeded,
+            "GraphQL mutation"
+        );
+    }
+}
+
+/// Keeps audit records in process memory
+#[derive(Clone, Default)]
+pub struct InMemoryAuditSink {
+    records: Arc<RwLock<Vec<AuditRecord>>>,
+}
+
+impl InMemoryAuditSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn records(&self) -> Vec<AuditRecord> {
+        self.records.read().await.clone()
+    }
+}
+
+#[async_trait]
+impl AuditSink for InMemoryAuditSink {
+    async fn record(&self, record: AuditRecord) {
+        self.records.write().await.push(record);
+    }
+}
+
+/// async-graphql extension recording mutations to an [`AuditSink`]
+#[derive(Clone)]
+pub struct AuditTrail {
+    sink: Arc<dyn AuditSink>,
+}
+
+impl AuditTrail {
+    pub fn new<A: AuditSink>(sink: A) -> Self {
+        Self {
+            sink: Arc::new(sink),
+        }
+    }
+}
+
+impl Default for AuditTrail {
+    fn default() -> Self {
+        Self::new(TracingAuditSink)
+    }
+}
+
+impl ExtensionFactory for AuditTrail {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(AuditTrailExtension {
+            sink: self.sink.clone(),
+            document: Mutex::new(None),
+        })
+    }
+}
+
+struct AuditTrailExtension {
+    sink: Arc<dyn AuditSink>,
+    document: Mutex<Option<(ExecutableDocument, Variables)>>,
+}
+
+/// Top-level field of a mutation and the IDs found in its arguments
+struct MutationField {
+    response_key: String,
+    name: String,
+    entity_ids: Vec<String>,
+}
+
+#[async_trait]
+impl Extension for AuditTrailExtension {
+    async fn parse_query(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        query: &str,
+        variables: &Variables,
+        next: NextParseQuery<'_>,
+    ) -> ServerResult<ExecutableDocument> {
+        let document = next.run(ctx, query, variables).await?;
+        *self.document.lock().unwrap() = Some((document.clone(), variables.clone()));
+        Ok(document)
+    }
+
+    async fn execute(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        operation_name: Option<&str>,
+        next: NextExecute<'_>,
+    ) -> Response {
+        let mutation = self
+            .document
+            .lock()
+            .unwrap()
+            .take()
+            .and_then(|(document, variables)| mutation(&document, &variables, operation_name));
+
+        let response = next.run(ctx, operation_name).await;
+        let Some((mutation_name, fields)) = mutation else {
+            return response;
+        };
+
+        let subject = ctx.data_opt::<AuthContext>().map(|auth| auth.user_id);
+        let data = match &response.data {
+            Value::Object(data) => Some(data),
+            _ => None,
+        };
+
+        for field in fields {
+            // Errors without a path fail the whole operation
+            let failed = response.errors.iter().any(|error| {
+                error.path.first().is_none_or(|segment| {
+                    matches!(segment, PathSegment::Field(key) if *key == field.response_key)
+                })
+            });
+
+            let mut entity_ids = field.entity_ids;
+            if let Some(Value::Object(result)) =
+                data.and_then(|data| data.get(field.response_key.as_str()))
+            {
+                collect_entity_ids(
+                    "id",
+                    result.get("id").unwrap_or(&Value::Null),
+                    &mut entity_ids,
+                );
+            }
+
+            self.sink
+                .record(AuditRecord {
+                    subject,
+                    operation_name: mutation_name.clone(),
+                    field: field.name,
+                    entity_ids,
+                    succeeded: !failed,
+                    recorded_at: Utc::now(),
+                })
+                .await;
+        }
+
+        response
+    }
+}
+
+/// Name and top-level fields of the operation that will run, if it is a
+/// mutation
+fn mutation(
+    document: &ExecutableDocument,
+    variables: &Variables,
+    operation_name: Option<&str>,
+) -> Option<(Option<String>, Vec<MutationField>)> {
+    let (name, operation) = document
+        .operations
+        .iter()
+        .find(|(name, _)| operation_name.is_none() || name.map(Name::as_str) == operation_name)?;
+    if operation.node.ty != OperationType::Mutation {
+        return None;
+    }
+
+    let mut fields = Vec::new();
+    collect_fields(
+        document,
+        variables,
+        &operation.node.selection_set.node,
+        &mut fields,
+    );
+    Some((name.map(Name::to_string), fields))
+}
+
+fn collect_fields(
+    document: &ExecutableDocument,
+    variables: &Variables,
+    selection_set: &SelectionSet,
+    fields: &mut Vec<MutationField>,
+) {
+    for selection in &selection_set.items {
+        match &selection.node {
+            Selection::Field(field) => {
+                let field = &field.node;
+                let mut entity_ids = Vec::new();
+                for (name, value) in &field.arguments {
+                    let value = value
+                        .node
+                        .clone()
+                        .into_const_with(|variable| {
+                            Ok::<_, ()>(variables.get(&variable).cloned().unwrap_or(Value::Null))
+                        })
+                        .unwrap_or(Value::Null);
+                    collect_entity_ids(name.node.as_str(), &value, &mut entity_ids);
+                }
+
+                fields.push(MutationField {
+                    response_key: field.response_key().node.to_string(),
+                    name: field.name.node.to_string(),
+                    entity_ids,
+                });
+            }
+            Selection::FragmentSpread(spread) => {
+                if let Some(fragment) = document.fragments.get(&spread.node.fragment_name.node) {
+                    collect_fields(
+                        document,
+                        variables,
+                        &fragment.node.selection_set.node,
+                        fields,
+                    );
+                }
+            }
+            Selection::InlineFragment(fragment) => {
+                collect_fields(
+                    document,
+                    variables,
+                    &fragment.node.selection_set.node,
+                    fields,
+                );
+            }
+        }
+    }
+}
+
+/// Gather string values held under `id`, `*Id` or `*Ids` keys, including
+/// those nested in input objects
+fn collect_entity_ids(key: &str, value: &Value, ids: &mut Vec<String>) {
+    let is_id_key = key == "id" || key.ends_with("Id") || key.ends_with("Ids");
+    match value {
+        Value::String(id) if is_id_key && !ids.contains(id) => ids.push(id.clone()),
+        Value::List(items) if is_id_key => {
+            for item in items {
+                collect_entity_ids(key, item, ids);
+            }
+        }
+        Value::Object(fields) => {
+            for (name, value) in fields {
+                collect_entity_ids(name.as_str(), value, ids);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_graphql::{EmptySubscription, Object, Request, Schema};
+
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn version(&self) -> &str {
+            "1"
+        }
+    }
+
+    struct Mutation;
+
+    #[Object]
+    impl Mutation {
+        async fn rename_account(&self, _account_id: String, name: String) -> String {
+            name
+        }
+
+        async fn close_account(&self, account_id: String) -> async_graphql::Result<bool> {
+            Err(format!("Account {} cannot be closed", account_id).into())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mutations_are_audited_and_queries_are_not() {
+        let sink = InMemoryAuditSink::new();
+        let schema = Schema::build(Query, Mutation, EmptySubscription)
+            .extension(AuditTrail::new(sink.clone()))
+            .finish();
+
+        schema.execute("{ version }").await;
+        assert!(sink.records().await.is_empty());
+
+        let request = Request::new(
+            r#"mutation CloseOut($id: String!) {
+                renamed: renameAccount(accountId: $id, name: "Old checking")
+                closeAccount(accountId: "acct-2")
+            }"#,
+        )
+        .variables(Variables::from_json(serde_json::json!({ "id": "acct-1" })));
+        let response = schema.execute(request).await;
+        assert_eq!(response.errors.len(), 1);
+
+        let records = sink.records().await;
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].subject, None);
+        assert_eq!(records[0].operation_name.as_deref(), Some("CloseOut"));
+        assert_eq!(records[0].field, "renameAccount");
+        assert_eq!(records[0].entity_ids, vec!["acct-1".to_string()]);
+        assert!(records[0].succeeded);
+        assert_eq!(records[1].field, "closeAccount");
+        assert_eq!(records[1].entity_ids, vec!["acct-2".to_string()]);
+        assert!(!records[1].succeeded);
+    }
+}
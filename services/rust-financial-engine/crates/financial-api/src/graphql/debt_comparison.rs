@@ -0,0 +1,378 @@
+/// Side-by-side debt strategy comparison for GraphQL resolvers
+///
+/// Converts inline debt inputs to core debt accounts and runs the core
+/// snowball / avalanche comparison over them.
+use financial_core::debt::{
+    payment_plans_ics, DebtAccount as CoreDebtAccount, DebtComparison as CoreDebtComparison,
+    DebtOptimizer,
+};
+use financial_core::types::{Currency as CoreCurrency, Percentage as CorePercentage, Rate as CoreRate};
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use crate::error::{ApiError, DebtDiagnostic, Result};
+use crate::graphql::schema::debt::{CompareDebtStrategiesInput, CreateDebtAccountInput};
+use crate::graphql::types::DebtStrategy;
+
+/// Validate debt inputs and convert them to core debt accounts owned by `user_id`
+///
+/// At least one debt is required, every balance must be positive, and all
+/// balances and payments must share one currency. Every debt is checked
+/// before any is converted, and all problems are returned together as
+/// [`ApiError::InvalidDebts`] diagnostics naming the debt and field.
+pub fn debt_accounts_from_inputs(
+    user_id: Uuid,
+    inputs: &[CreateDebtAccountInput],
+) -> Result<Vec<CoreDebtAccount>> {
+    if inputs.is_empty() {
+        return Err(ApiError::validation_error("debts", "At least one debt is required"));
+    }
+
+    let mut currency: Option<CoreCurrency> = None;
+    let mut debts = Vec::with_capacity(inputs.len());
+    let mut diagnostics = Vec::new();
+
+    for (index, input) in inputs.iter().enumerate() {
+        match debt_account_from_input(user_id, index, input, &mut currency) {
+            Ok(debt) => debts.push(debt),
+            Err(issues) => diagnostics.extend(issues),
+        }
+    }
+
+    if diagnostics.is_empty() {
+        Ok(debts)
+    } else {
+        Err(ApiError::InvalidDebts { diagnostics })
+    }
+}
+
+/// Problems found with one debt input
+struct DebtIssues<'a> {
+    index: usize,
+    name: &'a str,
+    diagnostics: Vec<DebtDiagnostic>,
+}
+
+impl DebtIssues<'_> {
+    fn push(&mut self, field: &str, message: impl Into<String>) {
+        self.diagnostics.push(DebtDiagnostic {
+            index: self.index,
+            name: self.name.to_string(),
+            field: field.to_string(),
+            message: message.into(),
+        });
+    }
+
+    /// Value of `result`, or `None` once its error is recorded
+    fn check<T>(&mut self, result: Result<T>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(ApiError::ValidationError { field, message }) => {
+                self.push(&field, message);
+                None
+            }
+            Err(other) => {
+                self.push("debt", other.to_string());
+                None
+            }
+        }
+    }
+}
+
+/// Convert one debt input, recording every problem with it
+///
+/// The first currency seen across the inputs becomes the one the rest must use.
+fn debt_account_from_input(
+    user_id: Uuid,
+    index: usize,
+    input: &CreateDebtAccountInput,
+    currency: &mut Option<CoreCurrency>,
+) -> std::result::Result<CoreDebtAccount, Vec<DebtDiagnostic>> {
+    let name = input.name.trim();
+    let mut issues = DebtIssues {
+        index,
+        name,
+        diagnostics: Vec::new(),
+    };
+
+    if name.is_empty() {
+        issues.push("name", "Debt name cannot be empty");
+    }
+
+    let balance = issues.check(input.balance.to_core("balance"));
+    if balance.is_some_and(|balance| balance.amount().is_zero()) {
+        issues.push("balance", "Balance must be positive");
+    }
+    let minimum_payment = issues.check(input.minimum_payment.to_core("minimumPayment"));
+
+    for money in [balance, minimum_payment].into_iter().flatten() {
+        match *currency {
+            None => *currency = Some(money.currency()),
+            Some(expected) if expected != money.currency() => {
+                issues.push(
+                    "currency",
+                    format!(
+                        "All debts must use {}, but this debt uses {}",
+                        expected,
+                        money.currency()
+                    ),
+                );
+                break;
+            }
+            Some(_) => {}
+        }
+    }
+
+    let rate_value = input.interest_rate.percentage.value.0;
+    let interest_rate = if rate_value < Decimal::ZERO {
+        issues.push("interestRate", "Interest rate cannot be negative");
+        None
+    } else {
+        issues.check(
+            CorePercentage::from_percentage(rate_value)
+                .map_err(|e| ApiError::validation_error("interestRate", &e.to_string())),
+        )
+    };
+
+    let credit_limit = input
+        .credit_limit
+        .as_ref()
+        .map(|limit| issues.check(limit.to_core("creditLimit")));
+
+    match (balance, minimum_payment, interest_rate) {
+        (Some(balance), Some(minimum_payment), Some(rate)) if issues.diagnostics.is_empty() => {
+            let mut debt = CoreDebtAccount::new(
+                user_id,
+                name.to_string(),
+                input.debt_type.into(),
+                balance,
+                CoreRate::new(rate, input.interest_rate.period.into()),
+                minimum_payment,
+            );
+            debt.due_date = input.due_date;
+            debt.credit_limit = credit_limit.flatten();
+            Ok(debt)
+        }
+        _ => Err(issues.diagnostics),
+    }
+}
+
+/// Compare snowball and avalanche payoff for the debts described by `input`
+pub fn compare_debt_strategies(
+    user_id: Uuid,
+    input: &CompareDebtStrategiesInput,
+) -> Result<CoreDebtComparison> {
+    let debts = debt_accounts_from_inputs(user_id, &input.debts)?;
+
+    let extra_payment = input.extra_payment.to_core("extraPayment")?;
+    if extra_payment.currency() != debts[0].balance.currency() {
+        return Err(ApiError::validation_error(
+            "extraPayment",
+            &format!(
+                "Extra payment must use {}, the currency of the debts",
+                debts[0].balance.currency()
+            ),
+        ));
+    }
+
+    let mut optimizer = DebtOptimizer::new(extra_payment);
+    if let Some(preference) = input.psychological_preference {
+        optimizer = optimizer.with_psychological_preference(preference.into());
+    }
+
+    Ok(optimizer.create_debt_comparison(&debts)?)
+}
+
+/// iCalendar feed of every payment in the chosen strategy's payoff plans
+///
+/// Only the snowball and avalanche plans of the comparison can be exported.
+pub fn debt_payment_calendar(
+    user_id: Uuid,
+    input: &CompareDebtStrategiesInput,
+    strategy: DebtStrategy,
+) -> Result<String> {
+    let comparison = compare_debt_strategies(user_id, input)?;
+    let result = match strategy {
+        DebtStrategy::Snowball => &comparison.snowball_result,
+        DebtStrategy::Avalanche => &comparison.avalanche_result,
+        DebtStrategy::Custom | DebtStrategy::Consolidation | DebtStrategy::HybridKickstart => {
+            return Err(ApiError::validation_error(
+                "strategy",
+                "Payment calendars are available for snowball and avalanche plans",
+            ));
+        }
+    };
+    Ok(payment_plans_ics(&result.payment_plans))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphql::types::{
+        Currency, DebtType, DecimalType, MoneyInput, PercentageInput, Period, RateInput,
+    };
+    use rust_decimal_macros::dec;
+
+    fn money(amount: Decimal, currency: Currency) -> MoneyInput {
+        MoneyInput {
+            amount: DecimalType(amount),
+            currency,
+        }
+    }
+
+    fn debt(name: &str, balance: Decimal, rate: Decimal, currency: Currency) -> CreateDebtAccountInput {
+        CreateDebtAccountInput {
+            name: name.to_string(),
+            debt_type: DebtType::CreditCard,
+            balance: money(balance, currency),
+            interest_rate: RateInput {
+                percentage: PercentageInput {
+                    value: DecimalType(rate),
+                },
+                period: Period::Annual,
+            },
+            minimum_payment: money(dec!(50), currency),
+            due_date: None,
+            credit_limit: None,
+        }
+    }
+
+    fn validation_field<T>(result: Result<T>) -> String {
+        match result {
+            Err(ApiError::ValidationError { field, .. }) => field,
+            Err(ApiError::InvalidDebts { diagnostics }) => diagnostics[0].field.clone(),
+            Err(other) => panic!("expected validation error, got {}", other),
+            Ok(_) => panic!("expected validation error"),
+        }
+    }
+
+    #[test]
+    fn test_debt_inputs_convert() {
+        let user_id = Uuid::new_v4();
+        let debts = debt_accounts_from_inputs(
+            user_id,
+            &[debt(" Visa ", dec!(2500), dec!(19.99), Currency::USD)],
+        )
+        .unwrap();
+
+        assert_eq!(debts[0].user_id, user_id);
+        assert_eq!(debts[0].name, "Visa");
+        assert_eq!(debts[0].balance.amount(), dec!(2500));
+        assert_eq!(debts[0].interest_rate.as_decimal(), dec!(0.1999));
+    }
+
+    #[test]
+    fn test_invalid_debt_inputs_rejected() {
+        let user_id = Uuid::new_v4();
+        assert_eq!(validation_field(debt_accounts_from_inputs(user_id, &[])), "debts");
+
+        let mixed = [
+            debt("Visa", dec!(2500), dec!(19.99), Currency::USD),
+            debt("Barclaycard", dec!(900), dec!(22.9), Currency::GBP),
+        ];
+        assert_eq!(validation_field(debt_accounts_from_inputs(user_id, &mixed)), "currency");
+
+        let paid_off = [debt("Visa", Decimal::ZERO, dec!(19.99), Currency::USD)];
+        assert_eq!(validation_field(debt_accounts_from_inputs(user_id, &paid_off)), "balance");
+
+        let negative_rate = [debt("Visa", dec!(2500), dec!(-1), Currency::USD)];
+        assert_eq!(
+            validation_field(debt_accounts_from_inputs(user_id, &negative_rate)),
+            "interestRate"
+        );
+
+        let foreign_extra = CompareDebtStrategiesInput {
+            debts: vec![debt("Visa", dec!(2500), dec!(19.99), Currency::USD)],
+            extra_payment: money(dec!(100), Currency::EUR),
+            psychological_preference: None,
+        };
+        assert_eq!(
+            validation_field(compare_debt_strategies(user_id, &foreign_extra)),
+            "extraPayment"
+        );
+    }
+
+    #[test]
+    fn test_every_invalid_debt_is_diagnosed() {
+        use async_graphql::ErrorExtensions;
+
+        let mut unnamed = debt("  ", dec!(300), dec!(12), Currency::USD);
+        unnamed.minimum_payment = money(dec!(-25), Currency::USD);
+        let debts = [
+            debt("Visa", dec!(2500), dec!(19.99), Currency::USD),
+            debt("Store Card", dec!(-400), dec!(24.9), Currency::USD),
+            debt("Car Loan", dec!(9000), dec!(-3), Currency::USD),
+            unnamed,
+            debt("Payday", dec!(500), dec!(20000), Currency::USD),
+        ];
+
+        let error = debt_accounts_from_inputs(Uuid::new_v4(), &debts).unwrap_err();
+        let diagnostics = error.debt_diagnostics().unwrap();
+        let pinpointed: Vec<(usize, &str, &str)> = diagnostics
+            .iter()
+            .map(|d| (d.index, d.name.as_str(), d.field.as_str()))
+            .collect();
+        assert_eq!(
+            pinpointed,
+            vec![
+                (1, "Store Card", "balance"),
+                (2, "Car Loan", "interestRate"),
+                (3, "", "name"),
+                (3, "", "minimumPayment"),
+                (4, "Payday", "interestRate"),
+            ]
+        );
+        assert_eq!(diagnostics[0].message, "Amount cannot be negative");
+        assert_eq!(diagnostics[1].message, "Interest rate cannot be negative");
+        assert!(error.to_string().contains("debts[2] (Car Loan) interestRate"));
+
+        // Clients receive the diagnostics as structured error extensions
+        let extensions = error.extend().extensions.unwrap();
+        let value = extensions.get("diagnostics").unwrap().clone().into_json().unwrap();
+        assert_eq!(value.as_array().unwrap().len(), 5);
+        assert_eq!(value[4]["name"], "Payday");
+        assert_eq!(value[4]["field"], "interestRate");
+
+        // Once fixed, every debt converts
+        let fixed: Vec<_> = debts
+            .iter()
+            .cloned()
+            .map(|mut debt| {
+                debt.name = "Fixed".to_string();
+                debt.balance = money(dec!(800), Currency::USD);
+                debt.minimum_payment = money(dec!(40), Currency::USD);
+                debt.interest_rate.percentage.value = DecimalType(dec!(18));
+                debt
+            })
+            .collect();
+        assert_eq!(debt_accounts_from_inputs(Uuid::new_v4(), &fixed).unwrap().len(), 5);
+    }
+
+    #[test]
+    fn test_payment_calendar_covers_every_debt() {
+        let input = CompareDebtStrategiesInput {
+            debts: vec![
+                debt("Visa", dec!(1200), dec!(19.99), Currency::USD),
+                debt("Store card", dec!(400), dec!(24.9), Currency::USD),
+            ],
+            extra_payment: money(dec!(150), Currency::USD),
+            psychological_preference: None,
+        };
+        let ics =
+            debt_payment_calendar(Uuid::new_v4(), &input, DebtStrategy::Snowball).unwrap();
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.contains("SUMMARY:Visa payment: "));
+        assert!(ics.contains("SUMMARY:Store card payment: "));
+        assert!(ics.contains("RRULE:FREQ=DAILY;INTERVAL=30;COUNT="));
+
+        assert_eq!(
+            validation_field(debt_payment_calendar(
+                Uuid::new_v4(),
+                &input,
+                DebtStrategy::Consolidation
+            )),
+            "strategy"
+        );
+    }
+}
@@ -0,0 +1,281 @@
+/// CSV import of debt accounts for GraphQL resolvers
+///
+/// Columns are found by header name: `name`, `balance`, `rate` (annual, in
+/// percent), `minimum` and `type` are required, and `currency` defaults to
+/// USD. Each row is validated like an inline debt input; rows that fail are
+/// reported individually so the rest of the file still imports.
+use financial_core::debt::DebtAccount as CoreDebtAccount;
+use financial_core::types::Currency as CoreCurrency;
+use rust_decimal::Decimal;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::error::{ApiError, Result};
+use crate::graphql::debt_comparison::debt_accounts_from_inputs;
+use crate::graphql::schema::debt::{CreateDebtAccountInput, DebtImportError};
+use crate::graphql::types::{
+    Currency, DebtType, DecimalType, MoneyInput, PercentageInput, Period, RateInput,
+};
+
+/// Split CSV text into records, honouring quoted fields and skipping blank lines
+pub fn parse_csv(contents: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = contents.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => record.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes => {}
+            '\n' if !in_quotes => {
+                record.push(std::mem::take(&mut field));
+                if record.iter().any(|f| !f.is_empty()) {
+                    records.push(std::mem::take(&mut record));
+                } else {
+                    record.clear();
+                }
+            }
+            _ => field.push(c),
+        }
+    }
+
+    record.push(field);
+    if record.iter().any(|f| !f.is_empty()) {
+        records.push(record);
+    }
+    records
+}
+
+/// Debt type from a label such as "credit_card" or "Credit Card"
+fn parse_debt_type(label: &str) -> Option<DebtType> {
+    let key: String = label
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .collect::<String>()
+        .to_ascii_lowercase();
+    Some(match key.as_str() {
+        "creditcard" => DebtType::CreditCard,
+        "studentloan" => DebtType::StudentLoan,
+        "mortgage" => DebtType::Mortgage,
+        "personalloan" => DebtType::PersonalLoan,
+        "autoloan" => DebtType::AutoLoan,
+        "homeequityloan" => DebtType::HomeEquityLoan,
+        "medicaldebt" => DebtType::MedicalDebt,
+        "other" => DebtType::Other,
+        _ => return None,
+    })
+}
+
+/// Positions of the debt columns in the header
+struct DebtColumns {
+    name: usize,
+    balance: usize,
+    rate: usize,
+    minimum: usize,
+    debt_type: usize,
+    currency: Option<usize>,
+}
+
+impl DebtColumns {
+    fn from_header(header: &[String]) -> Result<Self> {
+        let column = |name: &str| {
+            header
+                .iter()
+                .position(|h| h.trim().eq_ignore_ascii_case(name))
+        };
+        let required = |name: &str| {
+            column(name).ok_or_else(|| {
+                ApiError::validation_error("csv", &format!("CSV has no {} column", name))
+            })
+        };
+
+        Ok(Self {
+            name: required("name")?,
+            balance: required("balance")?,
+            rate: required("rate")?,
+            minimum: required("minimum")?,
+            debt_type: required("type")?,
+            currency: column("currency"),
+        })
+    }
+
+    /// CSV column holding the input field a validation error names
+    fn for_field(&self, field: &str) -> (&'static str, Option<usize>) {
+        match field {
+            "name" => ("name", Some(self.name)),
+            "balance" => ("balance", Some(self.balance)),
+            "interestRate" => ("rate", Some(self.rate)),
+            "minimumPayment" => ("minimum", Some(self.minimum)),
+            _ => ("row", None),
+        }
+    }
+
+    fn debt(
+        &self,
+        user_id: Uuid,
+        row: i32,
+        record: &[String],
+    ) -> std::result::Result<CoreDebtAccount, DebtImportError> {
+        let value = |col: Option<usize>| {
+            col.and_then(|c| record.get(c))
+                .map(|v| v.trim())
+                .unwrap_or_default()
+        };
+        let reject = |field: &str, message: &str, value: &str| DebtImportError {
+            row,
+            field: field.to_string(),
+            message: message.to_string(),
+            value: value.to_string(),
+        };
+        let decimal = |col: usize, field: &str, message: &str| {
+            let raw = value(Some(col));
+            Decimal::from_str(raw.trim_end_matches('%').trim_end())
+                .map_err(|_| reject(field, message, raw))
+        };
+
+        let balance = decimal(self.balance, "balance", "Invalid balance")?;
+        let rate = decimal(self.rate, "rate", "Invalid interest rate")?;
+        let minimum = decimal(self.minimum, "minimum", "Invalid minimum payment")?;
+        let type_value = value(Some(self.debt_type));
+        let debt_type = parse_debt_type(type_value)
+            .ok_or_else(|| reject("type", "Unknown debt type", type_value))?;
+        let currency = match value(self.currency) {
+            "" => Currency::USD,
+            code => CoreCurrency::from_str(code)
+                .map(Currency::from)
+                .map_err(|_| reject("currency", "Unknown currency", code))?,
+        };
+
+        let input = CreateDebtAccountInput {
+            name: value(Some(self.name)).to_string(),
+            debt_type,
+            balance: MoneyInput {
+                amount: DecimalType(balance),
+                currency,
+            },
+            interest_rate: RateInput {
+                percentage: PercentageInput {
+                    value: DecimalType(rate),
+                },
+                period: Period::Annual,
+            },
+            minimum_payment: MoneyInput {
+                amount: DecimalType(minimum),
+                currency,
+            },
+            due_date: None,
+            credit_limit: None,
+        };
+
+        debt_accounts_from_inputs(user_id, std::slice::from_ref(&input))
+            .map(|mut debts| debts.remove(0))
+            .map_err(|e| match e {
+                ApiError::ValidationError { field, message } => {
+                    let (column, col) = self.for_field(&field);
+                    reject(column, &message, value(col))
+                }
+                ApiError::InvalidDebts { diagnostics } => {
+                    let (column, col) = self.for_field(&diagnostics[0].field);
+                    reject(column, &diagnostics[0].message, value(col))
+                }
+                other => reject("row", &other.to_string(), ""),
+            })
+    }
+}
+
+/// Turn CSV text with a header row into debts owned by `user_id`
+///
+/// Returns the debts that passed validation and an error for every row that
+/// did not; row numbers count data rows from 1. Only a missing header or
+/// required column fails the whole import.
+pub fn debts_from_csv(
+    user_id: Uuid,
+    csv: &str,
+) -> Result<(Vec<CoreDebtAccount>, Vec<DebtImportError>)> {
+    let records = parse_csv(csv);
+    let Some((header, data)) = records.split_first() else {
+        return Err(ApiError::validation_error("csv", "CSV has no header row"));
+    };
+    let columns = DebtColumns::from_header(header)?;
+
+    let mut debts = Vec::with_capacity(data.len());
+    let mut errors = Vec::new();
+    for (index, record) in data.iter().enumerate() {
+        match columns.debt(user_id, index as i32 + 1, record) {
+            Ok(debt) => debts.push(debt),
+            Err(error) => errors.push(error),
+        }
+    }
+    Ok((debts, errors))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_malformed_rows_are_reported_and_the_rest_import() {
+        let csv = "name,balance,rate,minimum,type\n\
+                   Visa,9000,24.99%,225,credit_card\n\
+                   \"Car, financed\",15000.50,abc,350,Auto Loan\n\
+                   \n\
+                   Student,20000,5.5,200,STUDENT_LOAN\n\
+                   ,100,5,10,other\n\
+                   Timeshare,5000,10,100,timeshare\n";
+        let user_id = Uuid::new_v4();
+
+        let (debts, errors) = debts_from_csv(user_id, csv).unwrap();
+
+        let names: Vec<&str> = debts.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, ["Visa", "Student"]);
+        assert!(debts.iter().all(|d| d.user_id == user_id));
+        assert_eq!(debts[0].balance.amount(), dec!(9000));
+        assert_eq!(debts[0].interest_rate.as_decimal(), dec!(0.2499));
+        assert_eq!(debts[1].minimum_payment.amount(), dec!(200));
+
+        assert_eq!(
+            errors,
+            vec![
+                DebtImportError {
+                    row: 2,
+                    field: "rate".to_string(),
+                    message: "Invalid interest rate".to_string(),
+                    value: "abc".to_string(),
+                },
+                DebtImportError {
+                    row: 4,
+                    field: "name".to_string(),
+                    message: "Debt name cannot be empty".to_string(),
+                    value: String::new(),
+                },
+                DebtImportError {
+                    row: 5,
+                    field: "type".to_string(),
+                    message: "Unknown debt type".to_string(),
+                    value: "timeshare".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_missing_required_column_fails_the_import() {
+        let result = debts_from_csv(
+            Uuid::new_v4(),
+            "name,balance,minimum,type\nVisa,100,10,other\n",
+        );
+        assert!(matches!(
+            result,
+            Err(ApiError::ValidationError { ref message, .. }) if message == "CSV has no rate column"
+        ));
+        assert!(debts_from_csv(Uuid::new_v4(), "").is_err());
+    }
+}
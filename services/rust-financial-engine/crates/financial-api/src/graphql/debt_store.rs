@@ -0,0 +1,39 @@
+/// Saved debt account storage for GraphQL resolvers
+///
+/// Holds debts created through the API, such as a CSV import, so later
+/// operations can refer to them by id.
+use financial_core::debt::DebtAccount as CoreDebtAccount;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// In-memory debt store shared by all resolvers of a schema
+#[derive(Clone, Default)]
+pub struct DebtStore {
+    debts: Arc<RwLock<HashMap<Uuid, CoreDebtAccount>>>,
+}
+
+impl DebtStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Save new debts under a single lock and return their ids in order
+    pub async fn create_many(&self, debts: Vec<CoreDebtAccount>) -> Vec<Uuid> {
+        let mut stored = self.debts.write().await;
+        debts
+            .into_iter()
+            .map(|debt| {
+                let id = debt.id;
+                stored.insert(id, debt);
+                id
+            })
+            .collect()
+    }
+
+    /// Load a saved debt
+    pub async fn get(&self, id: Uuid) -> Option<CoreDebtAccount> {
+        self.debts.read().await.get(&id).cloned()
+    }
+}
@@ -0,0 +1,38 @@
+/// GraphQL field guards
+///
+/// Scope checks applied to resolvers with `#[graphql(guard = "...")]`
+use async_graphql::{Context, Error, ErrorExtensions};
+use uuid::Uuid;
+
+use crate::auth::AuthContext;
+use crate::error::ApiError;
+
+/// Error extension code returned when a guard rejects an operation
+pub const UNAUTHORIZED_CODE: &str = "UNAUTHORIZED";
+
+fn unauthorized(message: String) -> Error {
+    Error::new(message).extend_with(|_, e| e.set("code", UNAUTHORIZED_CODE))
+}
+
+/// Guard that only resolves the field when the request's token grants `scope`
+///
+/// Combine scopes with `GuardExt::and` / `GuardExt::or`.
+pub fn require_scope(
+    scope: &'static str,
+) -> impl Fn(&Context<'_>) -> async_graphql::Result<()> + Send + Sync + 'static {
+    move |ctx: &Context<'_>| match ctx.data_opt::<AuthContext>() {
+        Some(auth) if auth.has_scope(scope) => Ok(()),
+        Some(_) => Err(unauthorized(format!("Missing required scope: {}", scope))),
+        None => Err(unauthorized("Authentication required".to_string())),
+    }
+}
+
+/// Reject access to a resource owned by `owner_id` unless the caller may act for that user
+pub fn ensure_user_access(ctx: &Context<'_>, owner_id: Uuid, resource: &str) -> crate::error::Result<()> {
+    match ctx.data_opt::<AuthContext>() {
+        Some(auth) if auth.can_access_user_resource(owner_id) => Ok(()),
+        _ => Err(ApiError::InsufficientPermissions {
+            resource: resource.to_string(),
+        }),
+    }
+}
@@ -0,0 +1,145 @@
+/// Keepalive heartbeats for GraphQL subscriptions
+///
+/// Proxies drop connections that stay idle, which long-running simulations
+/// easily do. Subscriptions wrap their event stream with
+/// [`SubscriptionHeartbeat::wrap`] so a [`Heartbeat`] is sent whenever no data
+/// event has gone out for one interval. Heartbeats are their own GraphQL type,
+/// so clients tell them apart from data by `__typename`.
+use async_graphql::SimpleObject;
+use chrono::{DateTime, Utc};
+use futures::{Stream, StreamExt};
+use std::time::Duration;
+use tokio::time::{interval_at, Instant, MissedTickBehavior};
+
+use crate::config::GraphqlConfig;
+
+/// Idle time before a heartbeat is sent when none is configured
+pub const DEFAULT_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Keepalive message sent on an otherwise idle subscription
+#[derive(SimpleObject, Clone, Debug, PartialEq)]
+pub struct Heartbeat {
+    /// Position among the subscription's heartbeats, starting at 1
+    pub sequence: u32,
+    pub sent_at: DateTime<Utc>,
+}
+
+/// How often idle subscriptions send heartbeats
+///
+/// Read from request or schema data; subscriptions fall back to
+/// [`DEFAULT_HEARTBEAT_INTERVAL`] when neither carries one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SubscriptionHeartbeat {
+    pub interval: Duration,
+}
+
+impl SubscriptionHeartbeat {
+    pub fn new(interval: Duration) -> Self {
+        Self { interval }
+    }
+
+    /// Interleave heartbeats, built with `heartbeat`, into `events`
+    ///
+    /// A heartbeat follows every full interval without an event. The stream
+    /// ends when `events` does.
+    pub fn wrap<S, T>(
+        self,
+        events: S,
+        heartbeat: impl Fn(Heartbeat) -> T + Send + 'static,
+    ) -> impl Stream<Item = T>
+    where
+        S: Stream<Item = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let mut ticker = interval_at(Instant::now() + self.interval, self.interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        futures::stream::unfold(
+            (events.boxed(), ticker, heartbeat, 0),
+            |(mut events, mut ticker, heartbeat, sequence)| async move {
+                tokio::select! {
+                    biased;
+                    event = events.next() => {
+                        ticker.reset();
+                        let event = event?;
+                        Some((event, (events, ticker, heartbeat, sequence)))
+                    }
+                    _ = ticker.tick() => {
+                        let sequence = sequence + 1;
+                        let beat = heartbeat(Heartbeat { sequence, sent_at: Utc::now() });
+                        Some((beat, (events, ticker, heartbeat, sequence)))
+                    }
+                }
+            },
+        )
+    }
+}
+
+impl Default for SubscriptionHeartbeat {
+    fn default() -> Self {
+        Self::new(DEFAULT_HEARTBEAT_INTERVAL)
+    }
+}
+
+impl From<&GraphqlConfig> for SubscriptionHeartbeat {
+    fn from(config: &GraphqlConfig) -> Self {
+        Self::new(Duration::from_secs(config.subscription_heartbeat_interval))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    enum Event {
+        Data(&'static str),
+        Heartbeat(u32),
+    }
+
+    #[tokio::test]
+    async fn test_heartbeats_fill_idle_gaps_at_interval() {
+        let interval = Duration::from_millis(50);
+        let data = futures::stream::once(async {
+            tokio::time::sleep(Duration::from_millis(175)).await;
+            Event::Data("done")
+        });
+        let started = Instant::now();
+
+        let events: Vec<_> = SubscriptionHeartbeat::new(interval)
+            .wrap(data, |beat| Event::Heartbeat(beat.sequence))
+            .map(|event| (event, started.elapsed()))
+            .collect()
+            .await;
+
+        // Three heartbeats at 50, 100 and 150ms, then the data at 175ms
+        let kinds: Vec<_> = events.iter().map(|(event, _)| event).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                &Event::Heartbeat(1),
+                &Event::Heartbeat(2),
+                &Event::Heartbeat(3),
+                &Event::Data("done"),
+            ]
+        );
+        for (index, (_, elapsed)) in events.iter().take(3).enumerate() {
+            assert!(*elapsed >= interval * (index as u32 + 1));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_busy_streams_send_no_heartbeats() {
+        let data = futures::stream::iter(["a", "b", "c"]).map(Event::Data);
+
+        let events: Vec<_> = SubscriptionHeartbeat::new(Duration::from_millis(20))
+            .wrap(data, |beat| Event::Heartbeat(beat.sequence))
+            .collect()
+            .await;
+
+        assert_eq!(
+            events,
+            vec![Event::Data("a"), Event::Data("b"), Event::Data("c")]
+        );
+    }
+}
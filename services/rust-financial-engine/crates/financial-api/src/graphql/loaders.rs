@@ -0,0 +1,52 @@
+/// DataLoaders that batch entity lookups made by resolvers
+///
+/// Resolvers running concurrently within one request (aliased root fields,
+/// list items) each ask for a single id; the loader collects those ids and
+/// fetches them with one store call instead of one call per item.
+use async_graphql::async_trait::async_trait;
+use async_graphql::dataloader::Loader;
+use financial_core::portfolio::Portfolio as CorePortfolio;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::error::ApiError;
+use crate::graphql::portfolio_store::PortfolioStore;
+
+/// Loads saved portfolios, with their holdings, by id
+#[derive(Clone)]
+pub struct PortfolioLoader {
+    store: PortfolioStore,
+    batches: Arc<AtomicUsize>,
+}
+
+impl PortfolioLoader {
+    pub fn new(store: PortfolioStore) -> Self {
+        Self {
+            store,
+            batches: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Store the loader reads from
+    pub fn store(&self) -> &PortfolioStore {
+        &self.store
+    }
+
+    /// Number of batched loads issued so far
+    pub fn batch_count(&self) -> usize {
+        self.batches.load(Ordering::Relaxed)
+    }
+}
+
+#[async_trait]
+impl Loader<Uuid> for PortfolioLoader {
+    type Value = CorePortfolio;
+    type Error = ApiError;
+
+    async fn load(&self, keys: &[Uuid]) -> Result<HashMap<Uuid, CorePortfolio>, ApiError> {
+        self.batches.fetch_add(1, Ordering::Relaxed);
+        Ok(self.store.get_many(keys).await)
+    }
+}
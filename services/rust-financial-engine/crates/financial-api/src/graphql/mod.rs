@@ -1,11 +1,34 @@
+pub mod audit;
+pub mod debt_comparison;
+pub mod debt_import;
+pub mod debt_store;
+pub mod guards;
+pub mod heartbeat;
+pub mod loaders;
+pub mod persisted_queries;
+pub mod portfolio_store;
+pub mod query_cost;
 pub mod resolvers;
 /// GraphQL module for the financial API
 ///
 /// Provides comprehensive GraphQL schema, resolvers, and types
 /// for portfolio and debt management operations.
 pub mod schema;
+pub mod single_flight;
 pub mod types;
 
+pub use audit::{AuditRecord, AuditSink, AuditTrail, InMemoryAuditSink, TracingAuditSink};
+pub use debt_store::DebtStore;
+pub use guards::*;
+pub use heartbeat::{Heartbeat, SubscriptionHeartbeat, DEFAULT_HEARTBEAT_INTERVAL};
+pub use loaders::PortfolioLoader;
+pub use persisted_queries::{
+    CacheHealth, CachedPersistedQueryStore, InMemoryPersistedQueryStore, PersistedQueries,
+    PersistedQueryStore,
+};
+pub use portfolio_store::PortfolioStore;
+pub use query_cost::{QueryCost, QUERY_COST_EXTENSION};
 pub use resolvers::*;
 pub use schema::*;
+pub use single_flight::{RiskAnalysisFlights, SingleFlight};
 pub use types::*;
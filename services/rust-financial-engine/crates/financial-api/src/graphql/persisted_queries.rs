@@ -0,0 +1,381 @@
+/// Automatic persisted queries (APQ) for the GraphQL endpoint
+///
+/// Clients send the SHA-256 hash of a query in the `persistedQuery` request
+/// extension instead of the query text. Unknown hashes are answered with a
+/// `PersistedQueryNotFound` error, prompting the client to resend the query
+/// together with its hash so the server can register it.
+use async_graphql::async_trait::async_trait;
+use async_graphql::extensions::{
+    Extension, ExtensionContext, ExtensionFactory, NextPrepareRequest,
+};
+use async_graphql::{ErrorExtensionValues, Request, ServerError, ServerResult, Value};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+pub use crate::cache::CacheHealth;
+use crate::cache::{connect_cache, AsyncCache};
+use crate::config::RedisConfig;
+use crate::error::Result;
+
+/// Request extension carrying the persisted query hash
+const PERSISTED_QUERY_EXTENSION: &str = "persistedQuery";
+
+/// Only version of the APQ protocol clients speak
+const PERSISTED_QUERY_VERSION: i64 = 1;
+
+/// Cache key prefix for registered queries
+const CACHE_KEY_PREFIX: &str = "apq:";
+
+/// Error message APQ clients react to by registering the query
+pub const PERSISTED_QUERY_NOT_FOUND: &str = "PersistedQueryNotFound";
+
+/// Hex-encoded SHA-256 hash of a query, as sent by APQ clients
+pub fn query_hash(query: &str) -> String {
+    format!("{:x}", Sha256::digest(query.as_bytes()))
+}
+
+/// Storage for registered query text, keyed by hash
+#[async_trait]
+pub trait PersistedQueryStore: Send + Sync + 'static {
+    async fn get(&self, hash: &str) -> Option<String>;
+    async fn set(&self, hash: &str, query: &str);
+}
+
+/// Process-local query store without expiry
+#[derive(Clone, Default)]
+pub struct InMemoryPersistedQueryStore {
+    queries: Arc<RwLock<HashMap<String, String>>>,
+}
+
+impl InMemoryPersistedQueryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl PersistedQueryStore for InMemoryPersistedQueryStore {
+    async fn get(&self, hash: &str) -> Option<String> {
+        self.queries.read().await.get(hash).cloned()
+    }
+
+    async fn set(&self, hash: &str, query: &str) {
+        self.queries
+            .write()
+            .await
+            .insert(hash.to_string(), query.to_string());
+    }
+}
+
+/// Query store kept in an [`AsyncCache`]
+///
+/// Backed by Redis, every API instance shares the registered queries. Cache
+/// failures read as a miss, so clients fall back to sending the full query
+/// instead of the request failing or hanging on a slow Redis.
+#[derive(Clone)]
+pub struct CachedPersistedQueryStore {
+    cache: Arc<dyn AsyncCache>,
+    ttl: Duration,
+}
+
+impl CachedPersistedQueryStore {
+    /// Store entries in `cache`, expiring after `ttl`
+    pub fn new(cache: Arc<dyn AsyncCache>, ttl: Duration) -> Self {
+        Self { cache, ttl }
+    }
+
+    /// Store entries in the cache `config` selects; they expire after `default_ttl`
+    pub async fn connect(config: &RedisConfig) -> Result<Self> {
+        Ok(Self::new(
+            connect_cache(config).await?,
+            Duration::from_secs(config.default_ttl),
+        ))
+    }
+
+    /// Cache the queries are kept in
+    pub fn cache(&self) -> Arc<dyn AsyncCache> {
+        self.cache.clone()
+    }
+
+    pub async fn check_cache_health(&self) -> CacheHealth {
+        self.cache.check_cache_health().await
+    }
+}
+
+#[async_trait]
+impl PersistedQueryStore for CachedPersistedQueryStore {
+    async fn get(&self, hash: &str) -> Option<String> {
+        self.cache
+            .get(&format!("{}{}", CACHE_KEY_PREFIX, hash))
+            .await
+    }
+
+    async fn set(&self, hash: &str, query: &str) {
+        self.cache
+            .set(&format!("{}{}", CACHE_KEY_PREFIX, hash), query, self.ttl)
+            .await;
+    }
+}
+
+/// async-graphql extension resolving persisted query hashes before parsing
+pub struct PersistedQueries<S> {
+    store: Arc<S>,
+}
+
+impl<S: PersistedQueryStore> PersistedQueries<S> {
+    pub fn new(store: S) -> Self {
+        Self {
+            store: Arc::new(store),
+        }
+    }
+}
+
+impl<S: PersistedQueryStore> ExtensionFactory for PersistedQueries<S> {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(PersistedQueriesExtension {
+            store: self.store.clone(),
+        })
+    }
+}
+
+struct PersistedQueriesExtension<S> {
+    store: Arc<S>,
+}
+
+/// `persistedQuery` error carrying a machine-readable `code`
+fn persisted_query_error(message: &str, code: &str) -> ServerError {
+    let mut extensions = ErrorExtensionValues::default();
+    extensions.set("code", code);
+    let mut error = ServerError::new(message, None);
+    error.extensions = Some(extensions);
+    error
+}
+
+/// Hash sent in a version 1 `persistedQuery` extension
+fn requested_hash(extension: &Value) -> ServerResult<String> {
+    let Value::Object(fields) = extension else {
+        return Err(persisted_query_error(
+            "Invalid persistedQuery extension",
+            "PERSISTED_QUERY_INVALID",
+        ));
+    };
+
+    let version = match fields.get("version") {
+        Some(Value::Number(version)) => version.as_i64(),
+        _ => None,
+    };
+    if version != Some(PERSISTED_QUERY_VERSION) {
+        return Err(persisted_query_error(
+            "Only persistedQuery version 1 is supported",
+            "PERSISTED_QUERY_INVALID",
+        ));
+    }
+
+    match fields.get("sha256Hash") {
+        Some(Value::String(hash)) => Ok(hash.to_ascii_lowercase()),
+        _ => Err(persisted_query_error(
+            "persistedQuery is missing sha256Hash",
+            "PERSISTED_QUERY_INVALID",
+        )),
+    }
+}
+
+#[async_trait]
+impl<S: PersistedQueryStore> Extension for PersistedQueriesExtension<S> {
+    async fn prepare_request(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        mut request: Request,
+        next: NextPrepareRequest<'_>,
+    ) -> ServerResult<Request> {
+        let Some(extension) = request.extensions.remove(PERSISTED_QUERY_EXTENSION) else {
+            return next.run(ctx, request).await;
+        };
+        let hash = requested_hash(&extension)?;
+
+        if request.query.is_empty() {
+            // Hash only: run the registered query or ask the client to register it
+            request.query = self.store.get(&hash).await.ok_or_else(|| {
+                persisted_query_error(PERSISTED_QUERY_NOT_FOUND, "PERSISTED_QUERY_NOT_FOUND")
+            })?;
+        } else {
+            // Registration: the hash must match so one query cannot shadow another
+            if query_hash(&request.query) != hash {
+                return Err(persisted_query_error(
+                    "Persisted query hash does not match the query",
+                    "PERSISTED_QUERY_HASH_MISMATCH",
+                ));
+            }
+            self.store.set(&hash, &request.query).await;
+        }
+
+        next.run(ctx, request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::InMemoryCache;
+    use async_graphql::{value, EmptyMutation, EmptySubscription, Object, Schema};
+
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn answer(&self) -> i32 {
+            42
+        }
+    }
+
+    fn schema(
+        store: InMemoryPersistedQueryStore,
+    ) -> Schema<Query, EmptyMutation, EmptySubscription> {
+        Schema::build(Query, EmptyMutation, EmptySubscription)
+            .extension(PersistedQueries::new(store))
+            .finish()
+    }
+
+    fn persisted(query: &str, hash: &str) -> Request {
+        let mut request = Request::new(query);
+        request.extensions.insert(
+            PERSISTED_QUERY_EXTENSION.to_string(),
+            value!({ "version": 1, "sha256Hash": hash }),
+        );
+        request
+    }
+
+    fn error_code(error: &ServerError) -> Option<&Value> {
+        error
+            .extensions
+            .as_ref()
+            .and_then(|extensions| extensions.get("code"))
+    }
+
+    #[tokio::test]
+    async fn test_register_then_send_hash_only() {
+        let store = InMemoryPersistedQueryStore::new();
+        let schema = schema(store.clone());
+        let query = "{ answer }";
+        let hash = query_hash(query);
+
+        let registered = schema.execute(persisted(query, &hash)).await;
+        assert!(registered.errors.is_empty());
+        assert_eq!(registered.data, value!({ "answer": 42 }));
+        assert_eq!(store.get(&hash).await.as_deref(), Some(query));
+
+        let hash_only = schema.execute(persisted("", &hash)).await;
+        assert!(hash_only.errors.is_empty());
+        assert_eq!(hash_only.data, value!({ "answer": 42 }));
+
+        // Other schemas sharing the store see the registration too
+        let shared = self::schema(store)
+            .execute(persisted("", &hash.to_uppercase()))
+            .await;
+        assert_eq!(shared.data, value!({ "answer": 42 }));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_hash_is_not_found() {
+        let schema = schema(InMemoryPersistedQueryStore::new());
+
+        let response = schema
+            .execute(persisted("", &query_hash("{ answer }")))
+            .await;
+        assert_eq!(response.errors.len(), 1);
+        assert_eq!(response.errors[0].message, PERSISTED_QUERY_NOT_FOUND);
+        assert_eq!(
+            error_code(&response.errors[0]),
+            Some(&Value::from("PERSISTED_QUERY_NOT_FOUND"))
+        );
+
+        // A hash that does not match the query is rejected without registering it
+        let mismatch = schema
+            .execute(persisted("{ answer }", &query_hash("{ other }")))
+            .await;
+        assert_eq!(
+            error_code(&mismatch.errors[0]),
+            Some(&Value::from("PERSISTED_QUERY_HASH_MISMATCH"))
+        );
+        let still_missing = schema
+            .execute(persisted("", &query_hash("{ other }")))
+            .await;
+        assert_eq!(still_missing.errors[0].message, PERSISTED_QUERY_NOT_FOUND);
+
+        // Requests without the extension are unaffected
+        let plain = schema.execute(Request::new("{ answer }")).await;
+        assert_eq!(plain.data, value!({ "answer": 42 }));
+    }
+
+    /// Redis stand-in that accepts connections and never answers
+    async fn silent_redis() -> RedisConfig {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut held = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                held.push(socket);
+            }
+        });
+        RedisConfig {
+            url: format!("redis://{}", address),
+            enabled: true,
+            ..crate::config::Config::test_config().redis
+        }
+    }
+
+    #[tokio::test]
+    async fn test_slow_redis_is_bypassed() {
+        let store = CachedPersistedQueryStore::connect(&silent_redis().await)
+            .await
+            .unwrap();
+        let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+            .extension(PersistedQueries::new(store.clone()))
+            .finish();
+        let query = "{ answer }";
+        let hash = query_hash(query);
+
+        // Registration still runs the query when the write times out
+        let started = std::time::Instant::now();
+        let registered = schema.execute(persisted(query, &hash)).await;
+        assert!(registered.errors.is_empty());
+        assert_eq!(registered.data, value!({ "answer": 42 }));
+
+        // The timed-out read is a miss, so the client resends the full query
+        let hash_only = schema.execute(persisted("", &hash)).await;
+        assert_eq!(hash_only.errors[0].message, PERSISTED_QUERY_NOT_FOUND);
+        assert!(started.elapsed() < std::time::Duration::from_secs(2));
+
+        assert_eq!(store.check_cache_health().await, CacheHealth::Degraded);
+    }
+
+    #[tokio::test]
+    async fn test_cached_store_shares_queries_and_expires() {
+        let cache: Arc<dyn AsyncCache> = Arc::new(InMemoryCache::new());
+        let store = CachedPersistedQueryStore::new(cache.clone(), Duration::from_millis(50));
+        let query = "{ answer }";
+        let hash = query_hash(query);
+
+        let registered = Schema::build(Query, EmptyMutation, EmptySubscription)
+            .extension(PersistedQueries::new(store.clone()))
+            .finish()
+            .execute(persisted(query, &hash))
+            .await;
+        assert!(registered.errors.is_empty());
+        assert_eq!(
+            cache.get(&format!("apq:{}", hash)).await.as_deref(),
+            Some(query)
+        );
+
+        // A second instance over the same cache sees the registration
+        let other = CachedPersistedQueryStore::new(cache, Duration::from_secs(60));
+        assert_eq!(other.get(&hash).await.as_deref(), Some(query));
+
+        tokio::time::sleep(Duration::from_millis(120)).await;
+        assert_eq!(store.get(&hash).await, None);
+        assert_eq!(store.check_cache_health().await, CacheHealth::Ok);
+    }
+}
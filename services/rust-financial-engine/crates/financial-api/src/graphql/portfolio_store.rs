@@ -0,0 +1,429 @@
+/// Saved portfolio storage for GraphQL resolvers
+///
+/// Holds portfolios and their holdings server-side so analysis operations
+/// can load a portfolio by id instead of receiving it inline. Portfolios are
+/// kept in the schema's cache, so with Redis enabled they survive restarts
+/// and are shared by every API instance.
+use chrono::{DateTime, Duration as ChronoDuration, Months, Utc};
+use financial_core::portfolio::{
+    Asset as CoreAsset, HistoricalReturns, PeriodReturn, Portfolio as CorePortfolio,
+    ReturnFrequency as CoreReturnFrequency,
+};
+use financial_core::types::{Currency as CoreCurrency, Percentage as CorePercentage};
+use futures::future::join_all;
+use rust_decimal::Decimal;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::cache::{AsyncCache, InMemoryCache};
+use crate::error::{ApiError, Result};
+use crate::graphql::schema::portfolio::{AssetReturnsInput, HoldingInput};
+
+/// Tolerance when checking that fully specified targets add up to 100%
+const TARGET_WEIGHT_TOLERANCE: Decimal = Decimal::from_parts(1, 0, 0, false, 2);
+
+/// Prefix of the cache keys portfolios are stored under
+const CACHE_KEY_PREFIX: &str = "portfolio:";
+
+/// How long a portfolio is kept after it was last saved
+pub const PORTFOLIO_RETENTION: Duration = Duration::from_secs(365 * 24 * 60 * 60);
+
+/// Portfolio store shared by all resolvers of a schema
+#[derive(Clone)]
+pub struct PortfolioStore {
+    cache: Arc<dyn AsyncCache>,
+}
+
+impl PortfolioStore {
+    /// Store portfolios in process memory
+    pub fn new() -> Self {
+        Self::cached(Arc::new(InMemoryCache::new()))
+    }
+
+    /// Store portfolios in `cache`, each kept for [`PORTFOLIO_RETENTION`]
+    pub fn cached(cache: Arc<dyn AsyncCache>) -> Self {
+        Self { cache }
+    }
+
+    /// Save a new portfolio and return it
+    pub async fn create(&self, portfolio: CorePortfolio) -> Result<CorePortfolio> {
+        self.save(&portfolio).await?;
+        Ok(portfolio)
+    }
+
+    /// Load a saved portfolio
+    pub async fn get(&self, id: Uuid) -> Option<CorePortfolio> {
+        let stored = self.cache.get(&cache_key(id)).await?;
+        match serde_json::from_str(&stored) {
+            Ok(portfolio) => Some(portfolio),
+            Err(e) => {
+                tracing::warn!("Ignoring unreadable saved portfolio {}: {}", id, e);
+                None
+            }
+        }
+    }
+
+    /// Load several saved portfolios concurrently; unknown ids are omitted
+    pub async fn get_many(&self, ids: &[Uuid]) -> HashMap<Uuid, CorePortfolio> {
+        join_all(ids.iter().map(|id| async move { (*id, self.get(*id).await) }))
+            .await
+            .into_iter()
+            .filter_map(|(id, portfolio)| portfolio.map(|portfolio| (id, portfolio)))
+            .collect()
+    }
+
+    /// Replace every holding of a saved portfolio
+    pub async fn replace_holdings(&self, id: Uuid, assets: Vec<CoreAsset>) -> Result<CorePortfolio> {
+        let mut portfolio = self
+            .get(id)
+            .await
+            .ok_or_else(|| ApiError::PortfolioNotFound { id: id.to_string() })?;
+
+        portfolio.assets = assets;
+        portfolio.updated_at = Utc::now();
+        self.save(&portfolio).await?;
+        Ok(portfolio)
+    }
+
+    async fn save(&self, portfolio: &CorePortfolio) -> Result<()> {
+        let stored = serde_json::to_string(portfolio).map_err(|e| ApiError::InternalError {
+            message: format!("Failed to serialize portfolio {}: {}", portfolio.id, e),
+        })?;
+        self.cache
+            .set(&cache_key(portfolio.id), &stored, PORTFOLIO_RETENTION)
+            .await;
+        Ok(())
+    }
+}
+
+impl Default for PortfolioStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn cache_key(id: Uuid) -> String {
+    format!("{}{}", CACHE_KEY_PREFIX, id)
+}
+
+/// Validate holdings and convert them to core assets
+///
+/// Every holding must use the same currency, symbols must be unique,
+/// quantities positive, and allocation targets between 0% and 100%. Targets
+/// may not add up to more than 100%, and when every holding has one they must
+/// add up to exactly 100%.
+pub fn holdings_to_assets(holdings: &[HoldingInput]) -> Result<Vec<CoreAsset>> {
+    let mut currency: Option<CoreCurrency> = None;
+    let mut symbols = HashSet::new();
+    let mut target_total = Decimal::ZERO;
+    let mut targeted = 0;
+    let mut assets = Vec::with_capacity(holdings.len());
+
+    for holding in holdings {
+        let symbol = holding.symbol.trim().to_uppercase();
+        if symbol.is_empty() {
+            return Err(ApiError::validation_error("symbol", "Symbol cannot be empty"));
+        }
+        if !symbols.insert(symbol.clone()) {
+            return Err(ApiError::validation_error("symbol", &format!("Duplicate holding: {}", symbol)));
+        }
+        if holding.quantity.0 <= Decimal::ZERO {
+            return Err(ApiError::validation_error(
+                "quantity",
+                &format!("Quantity for {} must be positive", symbol),
+            ));
+        }
+
+        let cost_basis = holding.cost_basis.to_core("costBasis")?;
+        let current_value = match &holding.current_value {
+            Some(value) => value.to_core("currentValue")?,
+            None => cost_basis,
+        };
+
+        for money in [cost_basis, current_value] {
+            match currency {
+                None => currency = Some(money.currency()),
+                Some(expected) if expected != money.currency() => {
+                    return Err(ApiError::validation_error(
+                        "currency",
+                        &format!(
+                            "All holdings must use {}, but {} uses {}",
+                            expected,
+                            symbol,
+                            money.currency()
+                        ),
+                    ));
+                }
+                Some(_) => {}
+            }
+        }
+
+        let mut asset = CoreAsset::new(
+            symbol.clone(),
+            holding.name.clone().unwrap_or_else(|| symbol.clone()),
+            holding.asset_class.into(),
+            holding.quantity.0,
+            cost_basis,
+            current_value,
+        );
+
+        if let Some(target) = &holding.allocation_target {
+            if target.value.0 < Decimal::ZERO || target.value.0 > Decimal::ONE_HUNDRED {
+                return Err(ApiError::validation_error(
+                    "allocationTarget",
+                    &format!("Target weight for {} must be between 0% and 100%", symbol),
+                ));
+            }
+            target_total += target.value.0;
+            targeted += 1;
+            asset.allocation_target = Some(
+                CorePercentage::from_percentage(target.value.0)
+                    .map_err(|e| ApiError::validation_error("allocationTarget", &e.to_string()))?,
+            );
+        }
+
+        assets.push(asset);
+    }
+
+    if target_total > Decimal::ONE_HUNDRED + TARGET_WEIGHT_TOLERANCE {
+        return Err(ApiError::validation_error(
+            "allocationTarget",
+            &format!("Target weights add up to {}%, more than 100%", target_total),
+        ));
+    }
+    if targeted > 0
+        && targeted == assets.len()
+        && (target_total - Decimal::ONE_HUNDRED).abs() > TARGET_WEIGHT_TOLERANCE
+    {
+        return Err(ApiError::validation_error(
+            "allocationTarget",
+            &format!("Target weights add up to {}%, expected 100%", target_total),
+        ));
+    }
+
+    Ok(assets)
+}
+
+/// Line up supplied returns with the portfolio's holdings
+///
+/// The risk analyzer weights returns by position, so the result follows the
+/// order of `portfolio.assets`. Every holding needs a return series; series
+/// for symbols the portfolio does not hold are rejected. Returns carry no
+/// dates of their own, so the latest is dated at the holding's last
+/// valuation and each earlier one a period before the next.
+pub fn holding_returns(
+    portfolio: &CorePortfolio,
+    inputs: &[AssetReturnsInput],
+) -> Result<Vec<HistoricalReturns>> {
+    if let Some(unknown) = inputs
+        .iter()
+        .find(|input| !portfolio.assets.iter().any(|a| a.symbol.eq_ignore_ascii_case(input.symbol.trim())))
+    {
+        return Err(ApiError::validation_error(
+            "assetReturns",
+            &format!("Portfolio has no holding {}", unknown.symbol),
+        ));
+    }
+
+    portfolio
+        .assets
+        .iter()
+        .map(|asset| {
+            let input = inputs
+                .iter()
+                .find(|input| asset.symbol.eq_ignore_ascii_case(input.symbol.trim()))
+                .ok_or_else(|| {
+                    ApiError::validation_error(
+                        "assetReturns",
+                        &format!("Missing returns for holding {}", asset.symbol),
+                    )
+                })?;
+
+            let frequency = input.frequency.into();
+            let latest = input.returns.len().saturating_sub(1);
+            Ok(HistoricalReturns {
+                asset_id: asset.id,
+                symbol: asset.symbol.clone(),
+                returns: input
+                    .returns
+                    .iter()
+                    .enumerate()
+                    .map(|(index, value)| PeriodReturn {
+                        date: periods_before(asset.last_updated, frequency, latest - index),
+                        return_value: value.0,
+                        adjusted_close: None,
+                    })
+                    .collect(),
+                frequency,
+            })
+        })
+        .collect()
+}
+
+/// The date `periods` return periods of `frequency` before `date`
+fn periods_before(date: DateTime<Utc>, frequency: CoreReturnFrequency, periods: usize) -> DateTime<Utc> {
+    let periods = u32::try_from(periods).unwrap_or(u32::MAX);
+    let months = match frequency {
+        CoreReturnFrequency::Daily => return date - ChronoDuration::days(periods.into()),
+        CoreReturnFrequency::Weekly => return date - ChronoDuration::weeks(periods.into()),
+        CoreReturnFrequency::Monthly => periods,
+        CoreReturnFrequency::Quarterly => periods.saturating_mul(3),
+        CoreReturnFrequency::Annual => periods.saturating_mul(12),
+    };
+    date.checked_sub_months(Months::new(months))
+        .unwrap_or(DateTime::<Utc>::MIN_UTC)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphql::schema::portfolio::ReturnFrequency;
+    use crate::graphql::types::{AssetClass, Currency, DecimalType, MoneyInput, PercentageInput};
+    use rust_decimal_macros::dec;
+
+    fn holding(symbol: &str, cost: Decimal, currency: Currency, target: Option<Decimal>) -> HoldingInput {
+        HoldingInput {
+            symbol: symbol.to_string(),
+            name: None,
+            asset_class: AssetClass::Stocks,
+            quantity: DecimalType(dec!(10)),
+            cost_basis: MoneyInput {
+                amount: DecimalType(cost),
+                currency,
+            },
+            current_value: None,
+            allocation_target: target.map(|value| PercentageInput {
+                value: DecimalType(value),
+            }),
+        }
+    }
+
+    fn validation_field(result: Result<Vec<CoreAsset>>) -> String {
+        match result {
+            Err(ApiError::ValidationError { field, .. }) => field,
+            Err(other) => panic!("expected validation error, got {}", other),
+            Ok(_) => panic!("expected validation error"),
+        }
+    }
+
+    #[test]
+    fn test_valid_holdings_convert() {
+        let assets = holdings_to_assets(&[
+            holding("vti", dec!(6000), Currency::USD, Some(dec!(60))),
+            holding("BND", dec!(4000), Currency::USD, Some(dec!(40))),
+        ])
+        .unwrap();
+
+        assert_eq!(assets.len(), 2);
+        assert_eq!(assets[0].symbol, "VTI");
+        assert_eq!(assets[0].current_value.amount(), dec!(6000));
+        assert_eq!(assets[0].last_price.amount(), dec!(600));
+    }
+
+    #[test]
+    fn test_mixed_currencies_rejected() {
+        let field = validation_field(holdings_to_assets(&[
+            holding("VTI", dec!(6000), Currency::USD, None),
+            holding("VWRL", dec!(4000), Currency::GBP, None),
+        ]));
+        assert_eq!(field, "currency");
+    }
+
+    #[test]
+    fn test_weights_validated() {
+        let over = holdings_to_assets(&[
+            holding("VTI", dec!(6000), Currency::USD, Some(dec!(70))),
+            holding("BND", dec!(4000), Currency::USD, Some(dec!(40))),
+        ]);
+        assert_eq!(validation_field(over), "allocationTarget");
+
+        let under = holdings_to_assets(&[
+            holding("VTI", dec!(6000), Currency::USD, Some(dec!(50))),
+            holding("BND", dec!(4000), Currency::USD, Some(dec!(40))),
+        ]);
+        assert_eq!(validation_field(under), "allocationTarget");
+
+        // Partial targets only need to stay within 100%
+        assert!(holdings_to_assets(&[
+            holding("VTI", dec!(6000), Currency::USD, Some(dec!(50))),
+            holding("BND", dec!(4000), Currency::USD, None),
+        ])
+        .is_ok());
+    }
+
+    #[test]
+    fn test_duplicate_and_invalid_holdings_rejected() {
+        let duplicate = holdings_to_assets(&[
+            holding("VTI", dec!(6000), Currency::USD, None),
+            holding("vti", dec!(4000), Currency::USD, None),
+        ]);
+        assert_eq!(validation_field(duplicate), "symbol");
+
+        let mut zero_quantity = holding("VTI", dec!(6000), Currency::USD, None);
+        zero_quantity.quantity = DecimalType(Decimal::ZERO);
+        assert_eq!(validation_field(holdings_to_assets(&[zero_quantity])), "quantity");
+
+        let negative_cost = holding("VTI", dec!(-1), Currency::USD, None);
+        assert_eq!(validation_field(holdings_to_assets(&[negative_cost])), "costBasis");
+    }
+
+    #[test]
+    fn test_returns_are_dated_back_from_each_holdings_valuation() {
+        let mut portfolio = CorePortfolio::new(Uuid::new_v4(), "Retirement".to_string());
+        portfolio.assets = holdings_to_assets(&[
+            holding("VTI", dec!(6000), Currency::USD, None),
+            holding("BND", dec!(4000), Currency::USD, None),
+        ])
+        .unwrap();
+        let valued = |day| "2026-06-30T00:00:00Z".parse::<DateTime<Utc>>().unwrap() + ChronoDuration::days(day);
+        portfolio.assets[0].last_updated = valued(0);
+        portfolio.assets[1].last_updated = valued(1);
+
+        let returns = |symbol: &str, frequency| AssetReturnsInput {
+            symbol: symbol.to_string(),
+            returns: vec![DecimalType(dec!(0.01)), DecimalType(dec!(-0.02)), DecimalType(dec!(0.03))],
+            frequency,
+        };
+        let series = holding_returns(
+            &portfolio,
+            &[returns("BND", ReturnFrequency::Daily), returns("vti", ReturnFrequency::Monthly)],
+        )
+        .unwrap();
+
+        let dates = |index: usize| -> Vec<String> {
+            series[index]
+                .returns
+                .iter()
+                .map(|r| r.date.format("%Y-%m-%d").to_string())
+                .collect()
+        };
+        assert_eq!(dates(0), ["2026-04-30", "2026-05-30", "2026-06-30"]);
+        assert_eq!(dates(1), ["2026-06-29", "2026-06-30", "2026-07-01"]);
+        assert_eq!(series[1].returns[2].return_value, dec!(0.03));
+    }
+
+    #[tokio::test]
+    async fn test_portfolios_are_shared_through_the_cache() {
+        let cache: Arc<dyn AsyncCache> = Arc::new(InMemoryCache::new());
+        let writer = PortfolioStore::cached(cache.clone());
+        let reader = PortfolioStore::cached(cache);
+
+        let portfolio = CorePortfolio::new(Uuid::new_v4(), "Retirement".to_string());
+        let id = writer.create(portfolio).await.unwrap().id;
+        let assets = holdings_to_assets(&[holding("VTI", dec!(6000), Currency::USD, None)]).unwrap();
+        writer.replace_holdings(id, assets).await.unwrap();
+
+        let loaded = reader.get(id).await.unwrap();
+        assert_eq!(loaded.name, "Retirement");
+        assert_eq!(loaded.assets[0].symbol, "VTI");
+
+        let missing = Uuid::new_v4();
+        assert_eq!(reader.get_many(&[id, missing]).await.keys().collect::<Vec<_>>(), [&id]);
+        assert!(matches!(
+            reader.replace_holdings(missing, Vec::new()).await,
+            Err(ApiError::PortfolioNotFound { .. })
+        ));
+    }
+}
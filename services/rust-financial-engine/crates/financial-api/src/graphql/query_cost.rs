@@ -0,0 +1,126 @@
+/// Per-response query cost reporting
+///
+/// Adds a `queryCost` entry to the extensions of every GraphQL response with
+/// the server-side execution time in milliseconds and, for queries that
+/// passed validation, their computed complexity and depth. Frontend
+/// developers can use it to spot expensive queries without server access.
+use async_graphql::async_trait::async_trait;
+use async_graphql::extensions::{
+    Extension, ExtensionContext, ExtensionFactory, NextExecute, NextRequest, NextValidation,
+};
+use async_graphql::{value, Response, ServerError, ValidationResult};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Response extension carrying the cost report
+pub const QUERY_COST_EXTENSION: &str = "queryCost";
+
+/// async-graphql extension reporting execution time and query cost
+pub struct QueryCost;
+
+impl ExtensionFactory for QueryCost {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(QueryCostExtension::default())
+    }
+}
+
+#[derive(Default)]
+struct QueryCostExtension {
+    validation: Mutex<Option<ValidationResult>>,
+    execution_time: Mutex<Option<Duration>>,
+}
+
+#[async_trait]
+impl Extension for QueryCostExtension {
+    async fn request(&self, ctx: &ExtensionContext<'_>, next: NextRequest<'_>) -> Response {
+        let response = next.run(ctx).await;
+
+        // Requests rejected before execution (parse or validation errors) are not timed
+        let Some(execution_time) = self.execution_time.lock().unwrap().take() else {
+            return response;
+        };
+        let validation = self.validation.lock().unwrap().take();
+        let (complexity, depth) = validation.map_or((0, 0), |v| (v.complexity, v.depth));
+
+        response.extension(
+            QUERY_COST_EXTENSION,
+            value!({
+                "executionTimeMs": execution_time.as_secs_f64() * 1000.0,
+                "complexity": complexity,
+                "depth": depth,
+            }),
+        )
+    }
+
+    async fn validation(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        next: NextValidation<'_>,
+    ) -> Result<ValidationResult, Vec<ServerError>> {
+        let result = next.run(ctx).await?;
+        *self.validation.lock().unwrap() = Some(result);
+        Ok(result)
+    }
+
+    async fn execute(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        operation_name: Option<&str>,
+        next: NextExecute<'_>,
+    ) -> Response {
+        let started = Instant::now();
+        let response = next.run(ctx, operation_name).await;
+        *self.execution_time.lock().unwrap() = Some(started.elapsed());
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_graphql::{EmptyMutation, EmptySubscription, Object, Schema, SimpleObject, Value};
+
+    #[derive(SimpleObject)]
+    struct Account {
+        name: String,
+    }
+
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn accounts(&self) -> Vec<Account> {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            vec![Account {
+                name: "Checking".to_string(),
+            }]
+        }
+    }
+
+    fn schema() -> Schema<Query, EmptyMutation, EmptySubscription> {
+        Schema::build(Query, EmptyMutation, EmptySubscription)
+            .extension(QueryCost)
+            .finish()
+    }
+
+    #[tokio::test]
+    async fn test_response_reports_execution_time_and_cost() {
+        let response = schema().execute("{ accounts { name } }").await;
+        assert!(response.errors.is_empty());
+
+        let Some(Value::Object(cost)) = response.extensions.get(QUERY_COST_EXTENSION) else {
+            panic!("missing {} extension", QUERY_COST_EXTENSION);
+        };
+        let Some(Value::Number(execution_time)) = cost.get("executionTimeMs") else {
+            panic!("missing execution time");
+        };
+        assert!(execution_time.as_f64().unwrap() > 0.0);
+        assert_eq!(cost.get("complexity"), Some(&Value::from(2)));
+        assert_eq!(cost.get("depth"), Some(&Value::from(2)));
+
+        // Queries that never execute carry no report
+        let invalid = schema().execute("{ missingField }").await;
+        assert!(!invalid.errors.is_empty());
+        assert!(invalid.extensions.get(QUERY_COST_EXTENSION).is_none());
+    }
+}
@@ -1,18 +1,133 @@
 /// GraphQL resolvers implementation
 ///
 /// Contains resolver functions for GraphQL queries, mutations, and subscriptions
+use async_graphql::dataloader::DataLoader;
 use async_graphql::{Context, Schema};
 use std::sync::Arc;
+use std::time::Duration;
 
+use crate::cache::AsyncCache;
+use crate::config::Config;
 use crate::error::ApiError;
+use crate::graphql::audit::AuditTrail;
+use crate::graphql::debt_store::DebtStore;
+use crate::graphql::heartbeat::SubscriptionHeartbeat;
+use crate::graphql::loaders::PortfolioLoader;
+use crate::graphql::persisted_queries::{
+    CachedPersistedQueryStore, InMemoryPersistedQueryStore, PersistedQueries, PersistedQueryStore,
+};
+use crate::graphql::portfolio_store::PortfolioStore;
+use crate::graphql::query_cost::QueryCost;
 use crate::graphql::schema::{Mutation, Query, Subscription};
+use crate::graphql::single_flight::RiskAnalysisFlights;
+use crate::monitoring::metrics::CalculationMetrics;
+use crate::monitoring::request_id::spawn_in_request;
 
 /// GraphQL schema type
 pub type ApiSchema = Schema<Query, Mutation, Subscription>;
 
 /// Create the GraphQL schema
+///
+/// Persisted queries and saved portfolios are kept in process memory.
 pub fn create_schema() -> ApiSchema {
-    Schema::build(Query, Mutation, Subscription).finish()
+    build_schema(
+        PortfolioLoader::new(PortfolioStore::new()),
+        None,
+        InMemoryPersistedQueryStore::new(),
+        AuditTrail::default(),
+        SubscriptionHeartbeat::default(),
+        true,
+    )
+}
+
+/// Create the GraphQL schema for a deployment
+///
+/// Introspection follows [`Config::introspection_enabled`], so production
+/// schemas cannot be introspected unless explicitly allowed. Persisted
+/// queries live in `cache` for `redis.default_ttl` seconds and saved
+/// portfolios are kept there too, so instances sharing a Redis cache share
+/// registered queries and portfolios. Idle subscriptions send
+/// heartbeats every `graphql.subscription_heartbeat_interval` seconds.
+pub fn create_schema_for_config(
+    config: &Config,
+    calculations: CalculationMetrics,
+    cache: Arc<dyn AsyncCache>,
+) -> ApiSchema {
+    build_schema(
+        PortfolioLoader::new(PortfolioStore::cached(cache.clone())),
+        Some(calculations),
+        CachedPersistedQueryStore::new(cache, Duration::from_secs(config.redis.default_ttl)),
+        AuditTrail::default(),
+        SubscriptionHeartbeat::from(&config.graphql),
+        config.introspection_enabled(),
+    )
+}
+
+/// Create the GraphQL schema with calculation metrics recorded by resolvers
+pub fn create_schema_with_metrics(calculations: CalculationMetrics) -> ApiSchema {
+    create_schema_with_persisted_queries(calculations, InMemoryPersistedQueryStore::new())
+}
+
+/// Create the GraphQL schema recording mutations through `audit`
+pub fn create_schema_with_audit_trail(audit: AuditTrail) -> ApiSchema {
+    build_schema(
+        PortfolioLoader::new(PortfolioStore::new()),
+        None,
+        InMemoryPersistedQueryStore::new(),
+        audit,
+        SubscriptionHeartbeat::default(),
+        true,
+    )
+}
+
+/// Create the GraphQL schema with persisted queries kept in `store`
+///
+/// Pass a [`CachedPersistedQueryStore`](crate::graphql::CachedPersistedQueryStore)
+/// backed by Redis so every API instance shares the registered queries.
+pub fn create_schema_with_persisted_queries<S: PersistedQueryStore>(
+    calculations: CalculationMetrics,
+    store: S,
+) -> ApiSchema {
+    build_schema(
+        PortfolioLoader::new(PortfolioStore::new()),
+        Some(calculations),
+        store,
+        AuditTrail::default(),
+        SubscriptionHeartbeat::default(),
+        true,
+    )
+}
+
+/// Schema whose resolvers read saved portfolios through `portfolios`
+///
+/// The DataLoader does not cache, so it only batches lookups made while a
+/// request is being resolved and never serves data from earlier requests.
+/// Mutations are recorded through `audit`, and idle subscriptions send
+/// heartbeats every `heartbeat` interval unless a request overrides it.
+fn build_schema<S: PersistedQueryStore>(
+    portfolios: PortfolioLoader,
+    calculations: Option<CalculationMetrics>,
+    store: S,
+    audit: AuditTrail,
+    heartbeat: SubscriptionHeartbeat,
+    introspection: bool,
+) -> ApiSchema {
+    let mut builder = Schema::build(Query, Mutation, Subscription)
+        .data(portfolios.store().clone())
+        .data(DataLoader::new(portfolios, spawn_in_request))
+        .data(RiskAnalysisFlights::new())
+        .data(DebtStore::new())
+        .data(heartbeat)
+        .extension(PersistedQueries::new(store))
+        .extension(QueryCost)
+        .extension(audit);
+    if let Some(calculations) = calculations {
+        builder = builder.data(calculations);
+    }
+    if !introspection {
+        builder = builder.disable_introspection();
+    }
+    builder.finish()
 }
 
 /// GraphQL context for resolver functions
@@ -25,3 +140,621 @@ impl ApiContext {
         Self {}
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{AuthContext, JwtClaims, JwtManager, UserClaims, UserRole};
+    use crate::graphql::audit::InMemoryAuditSink;
+    use async_graphql::{Request, Value};
+    use chrono::{Duration, Utc};
+    use prometheus::Registry;
+
+    /// Issue a real token carrying only `scope` and decode it back into a context
+    fn auth_context_with_scope(scope: &str) -> AuthContext {
+        let jwt_manager = JwtManager::new(
+            "super-secret-key-that-is-at-least-32-chars",
+            vec!["atlas-financial".to_string()],
+        )
+        .unwrap();
+
+        let now = Utc::now();
+        let claims = JwtClaims {
+            sub: "123e4567-e89b-12d3-a456-426614174000".to_string(),
+            iss: "atlas-financial".to_string(),
+            aud: "financial-api".to_string(),
+            iat: now.timestamp(),
+            exp: (now + Duration::hours(1)).timestamp(),
+            nbf: None,
+            user: UserClaims {
+                id: "123e4567-e89b-12d3-a456-426614174000".to_string(),
+                email: "test@example.com".to_string(),
+                name: "Test User".to_string(),
+                role: UserRole::User,
+                verified: true,
+                created_at: now,
+                last_login: None,
+            },
+            permissions: vec![],
+            scope: Some(scope.to_string()),
+            org_id: None,
+            session_id: "session123".to_string(),
+        };
+
+        let token = jwt_manager.encode_token(&claims).unwrap();
+        jwt_manager.validate_and_extract_context(&token).unwrap()
+    }
+
+    fn error_code(response: &async_graphql::Response) -> Option<Value> {
+        response.errors[0]
+            .extensions
+            .as_ref()
+            .and_then(|extensions| extensions.get("code").cloned())
+    }
+
+    #[tokio::test]
+    async fn test_debt_read_scope_denied_portfolio_mutation() {
+        let schema = create_schema();
+        let mutation = r#"mutation {
+            createPortfolio(
+                userId: "123e4567-e89b-12d3-a456-426614174000",
+                input: { name: "Retirement" }
+            ) { name }
+        }"#;
+
+        let response = schema
+            .execute(Request::new(mutation).data(auth_context_with_scope("debt:read")))
+            .await;
+
+        assert_eq!(response.errors.len(), 1);
+        assert_eq!(error_code(&response), Some(Value::from("UNAUTHORIZED")));
+        assert!(response.errors[0].message.contains("portfolio:write"));
+    }
+
+    #[tokio::test]
+    async fn test_scope_guard_allows_granted_and_blocks_anonymous() {
+        let schema = create_schema();
+        let query = r#"{ debtAccounts(userId: "123e4567-e89b-12d3-a456-426614174000") { name } }"#;
+
+        // The guard passes and the resolver itself runs
+        let response = schema
+            .execute(Request::new(query).data(auth_context_with_scope("debt:read")))
+            .await;
+        assert_eq!(response.errors.len(), 1);
+        assert_ne!(error_code(&response), Some(Value::from("UNAUTHORIZED")));
+        assert!(response.errors[0].message.contains("debt_accounts"));
+
+        let response = schema.execute(query).await;
+        assert_eq!(error_code(&response), Some(Value::from("UNAUTHORIZED")));
+
+        // Combined guards need every scope
+        let query = r#"{ netWorth(userId: "123e4567-e89b-12d3-a456-426614174000") }"#;
+        let response = schema
+            .execute(Request::new(query).data(auth_context_with_scope("debt:read")))
+            .await;
+        assert_eq!(error_code(&response), Some(Value::from("UNAUTHORIZED")));
+    }
+
+    #[tokio::test]
+    async fn test_resolvers_record_calculation_metrics() {
+        let calculations = CalculationMetrics::new(&Registry::new()).unwrap();
+        let schema = create_schema_with_metrics(calculations.clone());
+
+        let query = r#"{ debtPayoffPlan(debtIds: [], strategy: AVALANCHE) { strategy } }"#;
+        let response = schema
+            .execute(Request::new(query).data(auth_context_with_scope("debt:read")))
+            .await;
+        assert!(!response.errors.is_empty());
+
+        let failures = calculations
+            .operations_total
+            .with_label_values(&[CalculationMetrics::DEBT_OPTIMIZATION, "Avalanche", "failure"])
+            .get();
+        assert_eq!(failures, 1);
+        assert_eq!(
+            calculations
+                .debt_optimization_latency
+                .with_label_values(&["Avalanche"])
+                .get_sample_count(),
+            1
+        );
+    }
+
+    const TEST_USER_ID: &str = "123e4567-e89b-12d3-a456-426614174000";
+
+    async fn execute_as(schema: &ApiSchema, scope: &str, operation: String) -> serde_json::Value {
+        let response = schema
+            .execute(Request::new(operation).data(auth_context_with_scope(scope)))
+            .await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        response.data.into_json().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_saved_portfolio_round_trip_and_risk_analysis() {
+        let schema = create_schema();
+        let scope = "portfolio:read portfolio:write";
+
+        let created = execute_as(
+            &schema,
+            scope,
+            format!(
+                r#"mutation {{
+                    createPortfolio(
+                        userId: "{}",
+                        input: {{
+                            name: "Retirement",
+                            holdings: [{{
+                                symbol: "VTI",
+                                assetClass: STOCKS,
+                                quantity: "10",
+                                costBasis: {{ amount: "2000", currency: USD }}
+                            }}]
+                        }}
+                    ) {{ id assets {{ symbol }} }}
+                }}"#,
+                TEST_USER_ID
+            ),
+        )
+        .await;
+        let id = created["createPortfolio"]["id"].as_str().unwrap().to_string();
+        assert_eq!(created["createPortfolio"]["assets"][0]["symbol"], "VTI");
+
+        let updated = execute_as(
+            &schema,
+            scope,
+            format!(
+                r#"mutation {{
+                    updatePortfolioHoldings(portfolioId: "{}", holdings: [
+                        {{
+                            symbol: "VTI", assetClass: STOCKS, quantity: "30",
+                            costBasis: {{ amount: "6000", currency: USD }},
+                            allocationTarget: {{ value: "60" }}
+                        }},
+                        {{
+                            symbol: "BND", assetClass: BONDS, quantity: "50",
+                            costBasis: {{ amount: "4000", currency: USD }},
+                            allocationTarget: {{ value: "40" }}
+                        }}
+                    ]) {{ assets {{ symbol }} }}
+                }}"#,
+                id
+            ),
+        )
+        .await;
+        assert_eq!(updated["updatePortfolioHoldings"]["assets"].as_array().unwrap().len(), 2);
+
+        let loaded = execute_as(
+            &schema,
+            "portfolio:read",
+            format!(
+                r#"{{ getPortfolio(id: "{}") {{ name assets {{ symbol quantity costBasis {{ amount }} }} }} }}"#,
+                id
+            ),
+        )
+        .await;
+        assert_eq!(loaded["getPortfolio"]["name"], "Retirement");
+        assert_eq!(loaded["getPortfolio"]["assets"][1]["symbol"], "BND");
+        assert_eq!(loaded["getPortfolio"]["assets"][1]["costBasis"]["amount"], "4000");
+
+        let analysis = execute_as(
+            &schema,
+            "portfolio:read",
+            format!(
+                r#"{{ analyzePortfolioRisk(portfolioId: "{}", assetReturns: [
+                    {{ symbol: "BND", frequency: MONTHLY, returns: ["0.01", "0.00", "0.01", "-0.01"] }},
+                    {{ symbol: "VTI", frequency: MONTHLY, returns: ["0.05", "-0.10", "0.04", "0.02"] }}
+                ]) {{ portfolioId volatility maximumDrawdown valueAtRisk95 {{ amount currency }} }} }}"#,
+                id
+            ),
+        )
+        .await;
+        let metrics = &analysis["analyzePortfolioRisk"];
+        assert_eq!(metrics["portfolioId"], id.as_str());
+        assert_eq!(metrics["valueAtRisk95"]["currency"], "USD");
+        let volatility: rust_decimal::Decimal = metrics["volatility"].as_str().unwrap().parse().unwrap();
+        assert!(volatility > rust_decimal::Decimal::ZERO);
+
+        let correlations = execute_as(
+            &schema,
+            "portfolio:read",
+            format!(
+                r#"{{ portfolioCorrelationMatrix(portfolioId: "{}", assetReturns: [
+                    {{ symbol: "BND", frequency: MONTHLY, returns: ["0.02", "-0.04", "0.016", "0.008"] }},
+                    {{ symbol: "VTI", frequency: MONTHLY, returns: ["0.05", "-0.10", "0.04", "0.02"] }}
+                ]) {{ symbols values }} }}"#,
+                id
+            ),
+        )
+        .await;
+        let matrix = &correlations["portfolioCorrelationMatrix"];
+        assert_eq!(matrix["symbols"], serde_json::json!(["VTI", "BND"]));
+        assert_eq!(matrix["values"], serde_json::json!([["1", "1"], ["1", "1"]]));
+    }
+
+    #[tokio::test]
+    async fn test_saved_portfolio_validation_and_lookup_errors() {
+        let schema = create_schema();
+        let auth = || auth_context_with_scope("portfolio:read portfolio:write");
+
+        let mixed_currencies = format!(
+            r#"mutation {{
+                createPortfolio(userId: "{}", input: {{ name: "Mixed", holdings: [
+                    {{ symbol: "VTI", assetClass: STOCKS, quantity: "1", costBasis: {{ amount: "100", currency: USD }} }},
+                    {{ symbol: "VWRL", assetClass: STOCKS, quantity: "1", costBasis: {{ amount: "100", currency: GBP }} }}
+                ] }}) {{ id }}
+            }}"#,
+            TEST_USER_ID
+        );
+        let response = schema.execute(Request::new(mixed_currencies).data(auth())).await;
+        assert_eq!(response.errors.len(), 1);
+        assert!(response.errors[0].message.contains("All holdings must use USD"));
+
+        let overweight = format!(
+            r#"mutation {{
+                createPortfolio(userId: "{}", input: {{ name: "Heavy", holdings: [
+                    {{ symbol: "VTI", assetClass: STOCKS, quantity: "1", costBasis: {{ amount: "100", currency: USD }},
+                       allocationTarget: {{ value: "80" }} }},
+                    {{ symbol: "BND", assetClass: BONDS, quantity: "1", costBasis: {{ amount: "100", currency: USD }},
+                       allocationTarget: {{ value: "30" }} }}
+                ] }}) {{ id }}
+            }}"#,
+            TEST_USER_ID
+        );
+        let response = schema.execute(Request::new(overweight).data(auth())).await;
+        assert!(response.errors[0].message.contains("more than 100%"));
+
+        // Portfolios belong to their owner
+        let other_user = r#"mutation {
+            createPortfolio(userId: "00000000-0000-0000-0000-000000000001", input: { name: "Theirs" }) { id }
+        }"#;
+        let response = schema.execute(Request::new(other_user).data(auth())).await;
+        assert!(response.errors[0].message.contains("Insufficient permissions"));
+
+        let missing = r#"{ getPortfolio(id: "00000000-0000-0000-0000-000000000002") { id } }"#;
+        let response = schema.execute(Request::new(missing).data(auth())).await;
+        assert!(response.errors[0].message.contains("Portfolio not found"));
+
+        let optional = r#"{ portfolio(id: "00000000-0000-0000-0000-000000000002") { id } }"#;
+        let response = schema.execute(Request::new(optional).data(auth())).await;
+        assert!(response.errors.is_empty());
+        assert_eq!(response.data.into_json().unwrap()["portfolio"], serde_json::Value::Null);
+    }
+
+    #[tokio::test]
+    async fn test_portfolio_lookups_are_batched() {
+        let loader = PortfolioLoader::new(PortfolioStore::new());
+        let schema = build_schema(
+            loader.clone(),
+            None,
+            InMemoryPersistedQueryStore::new(),
+            AuditTrail::default(),
+            SubscriptionHeartbeat::default(),
+            true,
+        );
+        let scope = "portfolio:read portfolio:write";
+
+        let mut ids = Vec::new();
+        for name in ["Retirement", "College", "Emergency"] {
+            let created = execute_as(
+                &schema,
+                scope,
+                format!(
+                    r#"mutation {{ createPortfolio(userId: "{}", input: {{ name: "{}" }}) {{ id }} }}"#,
+                    TEST_USER_ID, name
+                ),
+            )
+            .await;
+            ids.push(created["createPortfolio"]["id"].as_str().unwrap().to_string());
+        }
+
+        let before = loader.batch_count();
+        let loaded = execute_as(
+            &schema,
+            "portfolio:read",
+            format!(
+                r#"{{
+                    a: getPortfolio(id: "{}") {{ name }}
+                    b: getPortfolio(id: "{}") {{ name }}
+                    c: portfolio(id: "{}") {{ name }}
+                }}"#,
+                ids[0], ids[1], ids[2]
+            ),
+        )
+        .await;
+        assert_eq!(loaded["a"]["name"], "Retirement");
+        assert_eq!(loaded["b"]["name"], "College");
+        assert_eq!(loaded["c"]["name"], "Emergency");
+        assert_eq!(loader.batch_count() - before, 1);
+    }
+
+    #[tokio::test]
+    async fn test_mutations_produce_audit_records() {
+        let sink = InMemoryAuditSink::new();
+        let schema = build_schema(
+            PortfolioLoader::new(PortfolioStore::new()),
+            None,
+            InMemoryPersistedQueryStore::new(),
+            AuditTrail::new(sink.clone()),
+            SubscriptionHeartbeat::default(),
+            true,
+        );
+
+        let created = execute_as(
+            &schema,
+            "portfolio:read portfolio:write",
+            format!(
+                r#"mutation OpenPortfolio {{
+                    createPortfolio(userId: "{}", input: {{ name: "Retirement" }}) {{ id }}
+                }}"#,
+                TEST_USER_ID
+            ),
+        )
+        .await;
+        let id = created["createPortfolio"]["id"].as_str().unwrap();
+
+        // Reads are not audited
+        execute_as(
+            &schema,
+            "portfolio:read",
+            format!(r#"{{ getPortfolio(id: "{}") {{ name }} }}"#, id),
+        )
+        .await;
+
+        let records = sink.records().await;
+        assert_eq!(records.len(), 1);
+        let record = &records[0];
+        assert_eq!(record.subject, Some(TEST_USER_ID.parse().unwrap()));
+        assert_eq!(record.operation_name.as_deref(), Some("OpenPortfolio"));
+        assert_eq!(record.field, "createPortfolio");
+        assert_eq!(record.entity_ids, vec![TEST_USER_ID.to_string(), id.to_string()]);
+        assert!(record.succeeded);
+
+        // Denied mutations are recorded as failures
+        let denied = r#"mutation {
+            createPortfolio(userId: "123e4567-e89b-12d3-a456-426614174000", input: { name: "Denied" }) { id }
+        }"#;
+        schema
+            .execute(Request::new(denied).data(auth_context_with_scope("debt:read")))
+            .await;
+        let records = sink.records().await;
+        assert_eq!(records.len(), 2);
+        assert!(!records[1].succeeded);
+        assert_eq!(records[1].operation_name, None);
+    }
+
+    fn debt_comparison_query(preference: &str) -> String {
+        format!(
+            r#"{{ compareDebtStrategies(userId: "{}", input: {{
+                extraPayment: {{ amount: "300", currency: USD }},
+                psychologicalPreference: {},
+                debts: [
+                    {{
+                        name: "Store Card", debtType: CREDIT_CARD,
+                        balance: {{ amount: "600", currency: USD }},
+                        interestRate: {{ percentage: {{ value: "6.5" }}, period: ANNUAL }},
+                        minimumPayment: {{ amount: "25", currency: USD }}
+                    }},
+                    {{
+                        name: "Visa", debtType: CREDIT_CARD,
+                        balance: {{ amount: "9000", currency: USD }},
+                        interestRate: {{ percentage: {{ value: "24.99" }}, period: ANNUAL }},
+                        minimumPayment: {{ amount: "225", currency: USD }}
+                    }}
+                ]
+            }}) {{
+                recommendedStrategy
+                recommendationReason
+                snowballResult {{ strategy totalInterestPaid {{ amount currency }} }}
+                avalancheResult {{ strategy totalInterestPaid {{ amount currency }} }}
+                psychologicalFactors {{ quickWinsImportance estimatedSuccessProbability {{ value }} }}
+            }} }}"#,
+            TEST_USER_ID, preference
+        )
+    }
+
+    fn interest_paid(result: &serde_json::Value) -> rust_decimal::Decimal {
+        result["totalInterestPaid"]["amount"].as_str().unwrap().parse().unwrap()
+    }
+
+    /// Check the reason credits whichever strategy the results say pays less interest
+    fn assert_reason_matches_results(comparison: &serde_json::Value) {
+        let snowball = interest_paid(&comparison["snowballResult"]);
+        let avalanche = interest_paid(&comparison["avalancheResult"]);
+        let reason = comparison["recommendationReason"].as_str().unwrap();
+        let savings = format!("${:.2}", (snowball - avalanche).abs());
+
+        if avalanche < snowball {
+            assert!(
+                reason.contains(&format!("Avalanche method saves {}", savings))
+                    || reason.contains(&format!("Avalanche would save {} more", savings)),
+                "{}",
+                reason
+            );
+        } else if snowball < avalanche {
+            assert_eq!(comparison["recommendedStrategy"], "SNOWBALL");
+            assert!(
+                reason.contains(&format!("saves {} in interest compared to Avalanche", savings)),
+                "{}",
+                reason
+            );
+        } else {
+            assert!(reason.contains("same interest cost"), "{}", reason);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_debt_comparison_reason_names_cheaper_strategy() {
+        let schema = create_schema();
+
+        for preference in ["MATHEMATICAL", "QUICK_WINS", "BALANCED"] {
+            let data = execute_as(&schema, "debt:read", debt_comparison_query(preference)).await;
+            let comparison = &data["compareDebtStrategies"];
+            assert_reason_matches_results(comparison);
+
+            // Money is reported in cents and percentages without trailing zeros
+            assert_eq!(comparison["snowballResult"]["strategy"], "SNOWBALL");
+            assert_eq!(comparison["avalancheResult"]["totalInterestPaid"]["currency"], "USD");
+            assert!(interest_paid(&comparison["snowballResult"]).scale() <= 2);
+            assert!(interest_paid(&comparison["avalancheResult"]).scale() <= 2);
+            assert_eq!(
+                comparison["psychologicalFactors"]["estimatedSuccessProbability"]["value"],
+                "75"
+            );
+        }
+
+        // When the smallest debt also has the highest rate both strategies pay in the same order
+        let same_order = debt_comparison_query("MATHEMATICAL").replace(r#"value: "6.5""#, r#"value: "29.99""#);
+        let data = execute_as(&schema, "debt:read", same_order).await;
+        let comparison = &data["compareDebtStrategies"];
+        assert_eq!(
+            interest_paid(&comparison["snowballResult"]),
+            interest_paid(&comparison["avalancheResult"])
+        );
+        assert_reason_matches_results(comparison);
+    }
+
+    #[tokio::test]
+    async fn test_debt_comparison_requires_scope_and_ownership() {
+        let schema = create_schema();
+
+        let response = schema
+            .execute(Request::new(debt_comparison_query("BALANCED")).data(auth_context_with_scope("portfolio:read")))
+            .await;
+        assert_eq!(error_code(&response), Some(Value::from("UNAUTHORIZED")));
+
+        let other_user = debt_comparison_query("BALANCED")
+            .replace(TEST_USER_ID, "00000000-0000-0000-0000-000000000001");
+        let response = schema
+            .execute(Request::new(other_user).data(auth_context_with_scope("debt:read")))
+            .await;
+        assert!(response.errors[0].message.contains("Insufficient permissions"));
+    }
+
+    #[tokio::test]
+    async fn test_debt_comparison_subscription_heartbeats_until_result() {
+        use futures::StreamExt;
+
+        let query = debt_comparison_query("BALANCED")
+            .replacen("{ compareDebtStrategies(", "subscription { debtComparisonProgress(", 1)
+            .replacen(
+                "recommendedStrategy",
+                "__typename ... on Heartbeat { sequence } ... on DebtComparison { recommendedStrategy",
+                1,
+            )
+            + " }";
+        let request = Request::new(query)
+            .data(auth_context_with_scope("debt:read"))
+            .data(SubscriptionHeartbeat::new(
+                std::time::Duration::from_millis(1),
+            ));
+
+        let events: Vec<_> = create_schema().execute_stream(request).collect().await;
+        let (result, heartbeats) = events.split_last().unwrap();
+
+        // Heartbeats are numbered in order and carry no comparison data
+        for (index, heartbeat) in heartbeats.iter().enumerate() {
+            assert!(heartbeat.errors.is_empty(), "{:?}", heartbeat.errors);
+            let event = heartbeat.data.clone().into_json().unwrap();
+            assert_eq!(event["debtComparisonProgress"]["__typename"], "Heartbeat");
+            assert_eq!(event["debtComparisonProgress"]["sequence"], index + 1);
+        }
+
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        let data = result.data.clone().into_json().unwrap();
+        let comparison = &data["debtComparisonProgress"];
+        assert_eq!(comparison["__typename"], "DebtComparison");
+        assert_reason_matches_results(comparison);
+    }
+
+    #[tokio::test]
+    async fn test_import_debts_keeps_valid_rows_when_one_rate_is_malformed() {
+        let schema = create_schema();
+        let csv = "name,balance,rate,minimum,type\\nVisa,9000,24.99,225,credit_card\\nCar,15000,abc,350,auto_loan\\nStudent,20000,5.5,200,student_loan";
+
+        let data = execute_as(
+            &schema,
+            "debt:write",
+            format!(
+                r#"mutation {{
+                    importDebts(userId: "{}", csv: "{}") {{
+                        debtIds
+                        errors {{ row field message value }}
+                    }}
+                }}"#,
+                TEST_USER_ID, csv
+            ),
+        )
+        .await;
+
+        let result = &data["importDebts"];
+        assert_eq!(result["debtIds"].as_array().unwrap().len(), 2);
+        assert_eq!(
+            result["errors"],
+            serde_json::json!([{
+                "row": 2,
+                "field": "rate",
+                "message": "Invalid interest rate",
+                "value": "abc"
+            }])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_configured_schemas_share_persisted_queries_through_the_cache() {
+        use crate::graphql::persisted_queries::query_hash;
+
+        let cache: Arc<dyn AsyncCache> = Arc::new(crate::cache::InMemoryCache::new());
+        let instance = || {
+            let calculations = CalculationMetrics::new(&Registry::new()).unwrap();
+            create_schema_for_config(&Config::test_config(), calculations, cache.clone())
+        };
+        let query = "{ __typename }";
+        let hash = query_hash(query);
+        let persisted = |query: &str| {
+            let mut request = Request::new(query);
+            request.extensions.insert(
+                "persistedQuery".to_string(),
+                async_graphql::value!({ "version": 1, "sha256Hash": hash.clone() }),
+            );
+            request
+        };
+
+        let registered = instance().execute(persisted(query)).await;
+        assert!(registered.errors.is_empty(), "{:?}", registered.errors);
+
+        // Another instance only receives the hash
+        let hash_only = instance().execute(persisted("")).await;
+        assert!(hash_only.errors.is_empty(), "{:?}", hash_only.errors);
+        assert_eq!(hash_only.data.into_json().unwrap()["__typename"], "Query");
+    }
+
+    #[tokio::test]
+    async fn test_introspection_follows_the_environment() {
+        let introspect = |environment| async move {
+            let config = Config {
+                environment,
+                ..Config::test_config()
+            };
+            let calculations = CalculationMetrics::new(&Registry::new()).unwrap();
+            let cache = Arc::new(crate::cache::InMemoryCache::new());
+            create_schema_for_config(&config, calculations, cache)
+                .execute("{ __schema { queryType { name } } }")
+                .await
+        };
+
+        let dev = introspect(crate::config::Environment::Development).await;
+        assert!(dev.errors.is_empty(), "{:?}", dev.errors);
+        assert_eq!(
+            dev.data.into_json().unwrap()["__schema"]["queryType"]["name"],
+            "Query"
+        );
+
+        // Disabled introspection resolves `__schema` to null
+        let prod = introspect(crate::config::Environment::Production).await;
+        assert_eq!(
+            prod.data.into_json().unwrap()["__schema"],
+            serde_json::Value::Null
+        );
+    }
+}
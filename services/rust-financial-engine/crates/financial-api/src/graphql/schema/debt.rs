@@ -205,6 +205,17 @@ pub struct PsychologicalFactors {
     pub estimated_success_probability: Percentage,
 }
 
+/// How much weight the user puts on quick wins versus interest savings
+#[derive(async_graphql::Enum, Copy, Clone, Eq, PartialEq, Debug)]
+pub enum PsychologicalPreference {
+    /// Prefers early victories (snowball-friendly)
+    QuickWins,
+    /// Prefers optimal savings (avalanche-friendly)
+    Mathematical,
+    /// No strong preference
+    Balanced,
+}
+
 /// Debt negotiation opportunity
 #[derive(SimpleObject, Clone, Debug)]
 pub struct NegotiationOpportunity {
@@ -238,6 +249,28 @@ pub enum NegotiationType {
     DebtForgiveness,
 }
 
+/// Outcome of a CSV debt import
+#[derive(SimpleObject, Clone, Debug)]
+pub struct DebtImportResult {
+    /// IDs of the imported debts, in file order
+    pub debt_ids: Vec<UuidType>,
+    /// Rows that were rejected
+    pub errors: Vec<DebtImportError>,
+}
+
+/// A CSV row that could not be imported
+#[derive(SimpleObject, Clone, Debug, PartialEq, Eq)]
+pub struct DebtImportError {
+    /// Data row number, counting from 1 after the header
+    pub row: i32,
+    /// CSV column at fault
+    pub field: String,
+    /// Why the row was rejected
+    pub message: String,
+    /// Offending value as written in the file
+    pub value: String,
+}
+
 /// Input types for mutations
 
 /// Create debt account input
@@ -291,6 +324,17 @@ pub struct OptimizeDebtInput {
     pub target_payoff_date: Option<DateTime<Utc>>,
 }
 
+/// Debt strategy comparison input
+#[derive(InputObject, Clone, Debug)]
+pub struct CompareDebtStrategiesInput {
+    /// Debts to pay off
+    pub debts: Vec<CreateDebtAccountInput>,
+    /// Monthly amount available on top of minimum payments
+    pub extra_payment: MoneyInput,
+    /// Payoff preference (optional, defaults to balanced)
+    pub psychological_preference: Option<PsychologicalPreference>,
+}
+
 /// Payment plan input
 #[derive(InputObject, Clone, Debug)]
 pub struct CreatePaymentPlanInput {
@@ -364,6 +408,102 @@ pub struct DebtUtilization {
     pub accounts_at_max_utilization: i32,
 }
 
+impl From<PsychologicalPreference> for financial_core::debt::PsychologicalPreference {
+    fn from(preference: PsychologicalPreference) -> Self {
+        match preference {
+            PsychologicalPreference::QuickWins => Self::QuickWins,
+            PsychologicalPreference::Mathematical => Self::Mathematical,
+            PsychologicalPreference::Balanced => Self::Balanced,
+        }
+    }
+}
+
+/// Money rounded to cents for display
+fn display_money(money: financial_core::types::Money) -> Money {
+    Money {
+        amount: DecimalType(money.amount().round_dp(2).normalize()),
+        currency: money.currency().into(),
+    }
+}
+
+impl From<financial_core::debt::PaymentScheduleItem> for PaymentScheduleItem {
+    fn from(item: financial_core::debt::PaymentScheduleItem) -> Self {
+        Self {
+            payment_number: item.payment_number as i32,
+            payment_date: item.payment_date,
+            payment_amount: display_money(item.payment_amount),
+            principal: display_money(item.principal),
+            interest: display_money(item.interest),
+            remaining_balance: display_money(item.remaining_balance),
+        }
+    }
+}
+
+impl From<financial_core::debt::PaymentPlan> for PaymentPlan {
+    fn from(plan: financial_core::debt::PaymentPlan) -> Self {
+        Self {
+            debt_id: UuidType(plan.debt_id),
+            debt_name: plan.debt_name,
+            strategy: plan.strategy.into(),
+            monthly_payment: display_money(plan.monthly_payment),
+            total_payments: display_money(plan.total_payments),
+            total_interest: display_money(plan.total_interest),
+            payoff_date: plan.payoff_date,
+            payment_schedule: plan.payment_schedule.into_iter().map(Into::into).collect(),
+            created_at: plan.created_at,
+        }
+    }
+}
+
+impl From<financial_core::debt::DebtOptimizationResult> for DebtOptimizationResult {
+    fn from(result: financial_core::debt::DebtOptimizationResult) -> Self {
+        Self {
+            strategy: result.strategy.into(),
+            payment_plans: result.payment_plans.into_iter().map(Into::into).collect(),
+            total_monthly_payment: display_money(result.total_monthly_payment),
+            total_interest_paid: display_money(result.total_interest_paid),
+            total_time_to_payoff_months: result.total_time_to_payoff_months as i32,
+            final_payoff_date: result.final_payoff_date,
+            interest_savings_vs_minimum: display_money(result.interest_savings_vs_minimum),
+            time_savings_vs_minimum_months: result.time_savings_vs_minimum_months as i32,
+            generated_at: result.generated_at,
+        }
+    }
+}
+
+impl From<financial_core::debt::PsychologicalFactors> for PsychologicalFactors {
+    fn from(factors: financial_core::debt::PsychologicalFactors) -> Self {
+        Self {
+            motivation_score_snowball: factors.motivation_score_snowball as i32,
+            motivation_score_avalanche: factors.motivation_score_avalanche as i32,
+            quick_wins_importance: DecimalType(factors.quick_wins_importance.normalize()),
+            mathematical_optimality: DecimalType(factors.mathematical_optimality.normalize()),
+            estimated_success_probability: Percentage {
+                value: DecimalType(
+                    factors
+                        .estimated_success_probability
+                        .as_percentage()
+                        .round_dp(2)
+                        .normalize(),
+                ),
+            },
+        }
+    }
+}
+
+impl From<financial_core::debt::DebtComparison> for DebtComparison {
+    fn from(comparison: financial_core::debt::DebtComparison) -> Self {
+        Self {
+            snowball_result: comparison.snowball_result.into(),
+            avalanche_result: comparison.avalanche_result.into(),
+            minimum_only_result: comparison.minimum_only_result.into(),
+            recommended_strategy: comparison.recommended_strategy.into(),
+            recommendation_reason: comparison.recommendation_reason,
+            psychological_factors: comparison.psychological_factors.into(),
+        }
+    }
+}
+
 /// Debt connections for pagination
 pub type DebtAccountConnection = Connection<DebtAccount>;
 pub type PaymentPlanConnection = Connection<PaymentPlan>;
@@ -5,12 +5,18 @@ use async_graphql::*;
 use rust_decimal::Decimal;
 use uuid::Uuid;
 
+use crate::auth::Permissions;
 use crate::error::{ApiError, Result};
+use crate::graphql::debt_import::debts_from_csv;
+use crate::graphql::debt_store::DebtStore;
+use crate::graphql::guards::{ensure_user_access, require_scope};
+use crate::graphql::portfolio_store::{holdings_to_assets, PortfolioStore};
 use crate::graphql::schema::{
-    debt::{CreateDebtAccountInput, DebtAccount, UpdateDebtAccountInput},
-    portfolio::{CreatePortfolioInput, Portfolio, UpdatePortfolioInput},
+    debt::{CreateDebtAccountInput, DebtAccount, DebtImportResult, UpdateDebtAccountInput},
+    portfolio::{CreatePortfolioInput, HoldingInput, Portfolio, UpdatePortfolioInput},
     user::{UpdateUserInput, User},
 };
+use crate::graphql::types::UuidType;
 
 /// Root mutation object
 #[derive(Default)]
@@ -19,19 +25,47 @@ pub struct Mutation;
 #[Object]
 impl Mutation {
     /// Create a new investment portfolio
+    #[graphql(guard = "require_scope(Permissions::PORTFOLIO_WRITE)")]
     async fn create_portfolio(
         &self,
+        ctx: &Context<'_>,
         user_id: Uuid,
         input: CreatePortfolioInput,
     ) -> Result<Portfolio> {
-        // TODO: Implement portfolio creation logic
-        Err(ApiError::NotImplemented {
-            operation: "create_portfolio".to_string(),
+        ensure_user_access(ctx, user_id, "portfolio")?;
+        if input.name.trim().is_empty() {
+            return Err(ApiError::validation_error("name", "Portfolio name cannot be empty"));
         }
-        .into())
+
+        let mut portfolio = financial_core::portfolio::Portfolio::new(user_id, input.name);
+        portfolio.description = input.description;
+        portfolio.benchmark = input.benchmark;
+        portfolio.assets = holdings_to_assets(input.holdings.as_deref().unwrap_or_default())?;
+
+        let store = ctx.data_unchecked::<PortfolioStore>();
+        Ok(store.create(portfolio).await?.into())
+    }
+
+    /// Replace the holdings of a saved portfolio
+    #[graphql(guard = "require_scope(Permissions::PORTFOLIO_WRITE)")]
+    async fn update_portfolio_holdings(
+        &self,
+        ctx: &Context<'_>,
+        portfolio_id: Uuid,
+        holdings: Vec<HoldingInput>,
+    ) -> Result<Portfolio> {
+        let store = ctx.data_unchecked::<PortfolioStore>();
+        let existing = store.get(portfolio_id).await.ok_or_else(|| ApiError::PortfolioNotFound {
+            id: portfolio_id.to_string(),
+        })?;
+        ensure_user_access(ctx, existing.user_id, "portfolio")?;
+
+        let assets = holdings_to_assets(&holdings)?;
+        Ok(store.replace_holdings(portfolio_id, assets).await?.into())
     }
 
     /// Update an existing portfolio
+    #[graphql(guard = "require_scope(Permissions::PORTFOLIO_WRITE)")]
     async fn update_portfolio(&self, id: Uuid, input: UpdatePortfolioInput) -> Result<Portfolio> {
         // TODO: Implement portfolio update logic
         Err(ApiError::NotImplemented {
@@ -41,6 +75,7 @@ impl Mutation {
     }
 
     /// Delete a portfolio
+    #[graphql(guard = "require_scope(Permissions::PORTFOLIO_DELETE)")]
     async fn delete_portfolio(&self, id: Uuid) -> Result<bool> {
         // TODO: Implement portfolio deletion logic
         Err(ApiError::NotImplemented {
@@ -50,6 +85,7 @@ impl Mutation {
     }
 
     /// Create a new debt account
+    #[graphql(guard = "require_scope(Permissions::DEBT_WRITE)")]
     async fn create_debt_account(
         &self,
         user_id: Uuid,
@@ -62,7 +98,30 @@ impl Mutation {
         .into())
     }
 
+    /// Import debts from CSV with `name`, `balance`, `rate`, `minimum` and `type` columns
+    ///
+    /// Valid rows are saved even when others fail; each failed row is
+    /// reported with the column that was rejected.
+    #[graphql(guard = "require_scope(Permissions::DEBT_WRITE)")]
+    async fn import_debts(
+        &self,
+        ctx: &Context<'_>,
+        user_id: Uuid,
+        csv: String,
+    ) -> Result<DebtImportResult> {
+        ensure_user_access(ctx, user_id, "debt")?;
+        let (debts, errors) = debts_from_csv(user_id, &csv)?;
+
+        let store = ctx.data_unchecked::<DebtStore>();
+        let debt_ids = store.create_many(debts).await;
+        Ok(DebtImportResult {
+            debt_ids: debt_ids.into_iter().map(UuidType).collect(),
+            errors,
+        })
+    }
+
     /// Update an existing debt account
+    #[graphql(guard = "require_scope(Permissions::DEBT_WRITE)")]
     async fn update_debt_account(
         &self,
         id: Uuid,
@@ -76,6 +135,7 @@ impl Mutation {
     }
 
     /// Delete a debt account
+    #[graphql(guard = "require_scope(Permissions::DEBT_DELETE)")]
     async fn delete_debt_account(&self, id: Uuid) -> Result<bool> {
         // TODO: Implement debt account deletion logic
         Err(ApiError::NotImplemented {
@@ -85,6 +145,7 @@ impl Mutation {
     }
 
     /// Make a payment towards a debt account
+    #[graphql(guard = "require_scope(Permissions::DEBT_WRITE)")]
     async fn make_debt_payment(
         &self,
         debt_id: Uuid,
@@ -99,6 +160,7 @@ impl Mutation {
     }
 
     /// Update user profile
+    #[graphql(guard = "require_scope(Permissions::USER_WRITE)")]
     async fn update_user_profile(&self, user_id: Uuid, input: UpdateUserInput) -> Result<User> {
         // TODO: Implement user profile update logic
         Err(ApiError::NotImplemented {
@@ -108,6 +170,7 @@ impl Mutation {
     }
 
     /// Deactivate user account
+    #[graphql(guard = "require_scope(Permissions::USER_DELETE)")]
     async fn deactivate_user_account(&self, user_id: Uuid) -> Result<bool> {
         // TODO: Implement user deactivation logic
         Err(ApiError::NotImplemented {
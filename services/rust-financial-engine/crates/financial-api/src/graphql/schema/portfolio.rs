@@ -203,6 +203,78 @@ pub struct CreatePortfolioInput {
     pub description: Option<String>,
     /// Optional benchmark
     pub benchmark: Option<String>,
+    /// Initial holdings (optional)
+    pub holdings: Option<Vec<HoldingInput>>,
+}
+
+/// A holding to store on a portfolio
+#[derive(InputObject, Clone, Debug)]
+pub struct HoldingInput {
+    /// Asset symbol/ticker
+    pub symbol: String,
+    /// Asset name (defaults to the symbol)
+    pub name: Option<String>,
+    /// Asset class
+    pub asset_class: AssetClass,
+    /// Quantity held
+    pub quantity: DecimalType,
+    /// Original cost basis
+    pub cost_basis: MoneyInput,
+    /// Current market value (defaults to the cost basis)
+    pub current_value: Option<MoneyInput>,
+    /// Target allocation (optional)
+    pub allocation_target: Option<PercentageInput>,
+}
+
+/// Periodic returns for one holding, used for risk analysis
+#[derive(InputObject, Clone, Debug)]
+pub struct AssetReturnsInput {
+    /// Symbol of the holding the returns belong to
+    pub symbol: String,
+    /// Period returns as decimals (e.g. 0.01 for 1%), oldest first
+    pub returns: Vec<DecimalType>,
+    /// Frequency of the returns
+    pub frequency: ReturnFrequency,
+}
+
+/// Portfolio risk metrics
+#[derive(SimpleObject, Clone, Debug)]
+pub struct PortfolioRiskMetrics {
+    /// Portfolio ID
+    pub portfolio_id: UuidType,
+    /// Portfolio volatility (standard deviation of returns)
+    pub volatility: DecimalType,
+    /// Downside deviation
+    pub downside_deviation: DecimalType,
+    /// Value at Risk (95% confidence)
+    pub value_at_risk_95: Money,
+    /// Value at Risk (99% confidence)
+    pub value_at_risk_99: Money,
+    /// Conditional Value at Risk (95% confidence)
+    pub conditional_value_at_risk_95: Money,
+    /// Conditional Value at Risk (99% confidence)
+    pub conditional_value_at_risk_99: Money,
+    /// Maximum drawdown
+    pub maximum_drawdown: DecimalType,
+    /// Calmar ratio
+    pub calmar_ratio: DecimalType,
+    /// Sortino ratio
+    pub sortino_ratio: DecimalType,
+    /// Analysis timestamp
+    pub analyzed_at: DateTime<Utc>,
+}
+
+/// Pairwise correlations between a portfolio's holdings
+#[derive(SimpleObject, Clone, Debug)]
+pub struct PortfolioCorrelationMatrix {
+    /// Portfolio ID
+    pub portfolio_id: UuidType,
+    /// Holding symbols, in row and column order
+    pub symbols: Vec<String>,
+    /// `values[i][j]` is the correlation between holdings `i` and `j`, from -1 to 1
+    pub values: Vec<Vec<DecimalType>>,
+    /// Analysis timestamp
+    pub analyzed_at: DateTime<Utc>,
 }
 
 /// Update portfolio input
@@ -348,6 +420,93 @@ pub struct ValueRangeInput {
     pub max: Option<MoneyInput>,
 }
 
+impl From<financial_core::portfolio::Asset> for Asset {
+    fn from(asset: financial_core::portfolio::Asset) -> Self {
+        Self {
+            id: UuidType(asset.id),
+            symbol: asset.symbol,
+            name: asset.name,
+            asset_class: asset.asset_class.into(),
+            quantity: DecimalType(asset.quantity),
+            cost_basis: asset.cost_basis.into(),
+            current_value: asset.current_value.into(),
+            allocation_target: asset.allocation_target.map(Into::into),
+            last_price: asset.last_price.into(),
+            last_updated: asset.last_updated,
+        }
+    }
+}
+
+impl From<financial_core::portfolio::Portfolio> for Portfolio {
+    fn from(portfolio: financial_core::portfolio::Portfolio) -> Self {
+        Self {
+            id: UuidType(portfolio.id),
+            user_id: UuidType(portfolio.user_id),
+            name: portfolio.name,
+            description: portfolio.description,
+            assets: portfolio.assets.into_iter().map(Into::into).collect(),
+            benchmark: portfolio.benchmark,
+            created_at: portfolio.created_at,
+            updated_at: portfolio.updated_at,
+        }
+    }
+}
+
+impl PortfolioRiskMetrics {
+    /// Wrap core risk metrics computed for a saved portfolio
+    pub fn new(
+        portfolio_id: uuid::Uuid,
+        metrics: financial_core::portfolio::RiskMetrics,
+        analyzed_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            portfolio_id: UuidType(portfolio_id),
+            volatility: DecimalType(metrics.volatility),
+            downside_deviation: DecimalType(metrics.downside_deviation),
+            value_at_risk_95: metrics.value_at_risk_95.into(),
+            value_at_risk_99: metrics.value_at_risk_99.into(),
+            conditional_value_at_risk_95: metrics.conditional_value_at_risk_95.into(),
+            conditional_value_at_risk_99: metrics.conditional_value_at_risk_99.into(),
+            maximum_drawdown: DecimalType(metrics.maximum_drawdown),
+            calmar_ratio: DecimalType(metrics.calmar_ratio),
+            sortino_ratio: DecimalType(metrics.sortino_ratio),
+            analyzed_at,
+        }
+    }
+}
+
+impl PortfolioCorrelationMatrix {
+    /// Wrap a core correlation matrix computed for a saved portfolio
+    pub fn new(
+        portfolio_id: uuid::Uuid,
+        matrix: financial_core::portfolio::CorrelationMatrix,
+        analyzed_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            portfolio_id: UuidType(portfolio_id),
+            symbols: matrix.symbols,
+            values: matrix
+                .values
+                .into_iter()
+                .map(|row| row.into_iter().map(DecimalType).collect())
+                .collect(),
+            analyzed_at,
+        }
+    }
+}
+
+impl From<ReturnFrequency> for financial_core::portfolio::ReturnFrequency {
+    fn from(frequency: ReturnFrequency) -> Self {
+        match frequency {
+            ReturnFrequency::Daily => Self::Daily,
+            ReturnFrequency::Weekly => Self::Weekly,
+            ReturnFrequency::Monthly => Self::Monthly,
+            ReturnFrequency::Quarterly => Self::Quarterly,
+            ReturnFrequency::Annual => Self::Annual,
+        }
+    }
+}
+
 /// Portfolio connection for pagination
 pub type PortfolioConnection = Connection<Portfolio>;
 
@@ -1,17 +1,28 @@
 /// GraphQL query definitions
 ///
 /// Contains all query operations for financial data
+use async_graphql::dataloader::DataLoader;
 use async_graphql::*;
 use rust_decimal::Decimal;
 use uuid::Uuid;
 
+use crate::auth::Permissions;
 use crate::error::{ApiError, Result};
+use crate::graphql::debt_comparison::{compare_debt_strategies, debt_payment_calendar};
+use crate::graphql::guards::{ensure_user_access, require_scope};
+use crate::graphql::loaders::PortfolioLoader;
+use crate::graphql::portfolio_store::holding_returns;
 use crate::graphql::schema::{
-    debt::{DebtAccount, PayoffPlan},
-    portfolio::{OptimizationStrategy, Portfolio, PortfolioAnalysis},
+    debt::{CompareDebtStrategiesInput, DebtAccount, DebtComparison, PayoffPlan},
+    portfolio::{
+        AssetReturnsInput, OptimizationStrategy, Portfolio, PortfolioAnalysis,
+        PortfolioCorrelationMatrix, PortfolioRiskMetrics,
+    },
     user::{User, UserSession},
 };
+use crate::graphql::single_flight::{risk_analysis_key, RiskAnalysisFlights};
 use crate::graphql::types::DebtStrategy;
+use crate::monitoring::metrics::CalculationMetrics;
 
 /// Root query object
 #[derive(Default)]
@@ -20,6 +31,7 @@ pub struct Query;
 #[Object]
 impl Query {
     /// Get current user information
+    #[graphql(guard = "require_scope(Permissions::USER_READ)")]
     async fn current_user(&self, user_id: Uuid) -> Result<User> {
         // TODO: Implement user lookup logic
         Err(ApiError::NotImplemented {
@@ -29,6 +41,7 @@ impl Query {
     }
 
     /// Get user by ID
+    #[graphql(guard = "require_scope(Permissions::USER_READ)")]
     async fn user(&self, id: Uuid) -> Result<Option<User>> {
         // TODO: Implement user lookup logic
         Err(ApiError::NotImplemented {
@@ -38,6 +51,7 @@ impl Query {
     }
 
     /// Get user sessions
+    #[graphql(guard = "require_scope(Permissions::USER_READ)")]
     async fn user_sessions(&self, user_id: Uuid) -> Result<Vec<UserSession>> {
         // TODO: Implement user sessions lookup logic
         Err(ApiError::NotImplemented {
@@ -47,6 +61,7 @@ impl Query {
     }
 
     /// Get all portfolios for a user
+    #[graphql(guard = "require_scope(Permissions::PORTFOLIO_READ)")]
     async fn portfolios(&self, user_id: Uuid) -> Result<Vec<Portfolio>> {
         // TODO: Implement portfolio lookup logic
         Err(ApiError::NotImplemented {
@@ -56,28 +71,118 @@ impl Query {
     }
 
     /// Get a specific portfolio by ID
-    async fn portfolio(&self, id: Uuid) -> Result<Option<Portfolio>> {
-        // TODO: Implement portfolio lookup logic
-        Err(ApiError::NotImplemented {
-            operation: "portfolio".to_string(),
+    #[graphql(guard = "require_scope(Permissions::PORTFOLIO_READ)")]
+    async fn portfolio(&self, ctx: &Context<'_>, id: Uuid) -> Result<Option<Portfolio>> {
+        match ctx
+            .data_unchecked::<DataLoader<PortfolioLoader>>()
+            .load_one(id)
+            .await?
+        {
+            Some(portfolio) => {
+                ensure_user_access(ctx, portfolio.user_id, "portfolio")?;
+                Ok(Some(portfolio.into()))
+            }
+            None => Ok(None),
         }
-        .into())
+    }
+
+    /// Get a saved portfolio by ID, failing when it does not exist
+    #[graphql(guard = "require_scope(Permissions::PORTFOLIO_READ)")]
+    async fn get_portfolio(&self, ctx: &Context<'_>, id: Uuid) -> Result<Portfolio> {
+        let portfolio = ctx
+            .data_unchecked::<DataLoader<PortfolioLoader>>()
+            .load_one(id)
+            .await?
+            .ok_or_else(|| ApiError::PortfolioNotFound { id: id.to_string() })?;
+        ensure_user_access(ctx, portfolio.user_id, "portfolio")?;
+        Ok(portfolio.into())
+    }
+
+    /// Calculate risk metrics for a saved portfolio from per-holding returns
+    #[graphql(guard = "require_scope(Permissions::PORTFOLIO_READ)")]
+    async fn analyze_portfolio_risk(
+        &self,
+        ctx: &Context<'_>,
+        portfolio_id: Uuid,
+        asset_returns: Vec<AssetReturnsInput>,
+    ) -> Result<PortfolioRiskMetrics> {
+        let portfolio = ctx
+            .data_unchecked::<DataLoader<PortfolioLoader>>()
+            .load_one(portfolio_id)
+            .await?
+            .ok_or_else(|| ApiError::PortfolioNotFound {
+                id: portfolio_id.to_string(),
+            })?;
+        ensure_user_access(ctx, portfolio.user_id, "portfolio")?;
+
+        let analyze = || {
+            timed_portfolio_risk(ctx, "risk_metrics", async {
+                let analyzed_at = chrono::Utc::now();
+                let returns = holding_returns(&portfolio, &asset_returns)?;
+                let metrics = financial_core::portfolio::RiskAnalyzer::new()
+                    .calculate_risk_metrics(&portfolio, &returns, None)?;
+                Ok(PortfolioRiskMetrics::new(portfolio.id, metrics, analyzed_at))
+            })
+        };
+        // Identical analyses requested at the same time share one calculation
+        match ctx.data_opt::<RiskAnalysisFlights>() {
+            Some(flights) => {
+                flights
+                    .run(risk_analysis_key(portfolio.id, &asset_returns), analyze)
+                    .await
+            }
+            None => analyze().await,
+        }
+    }
+
+    /// Correlate a saved portfolio's holdings from per-holding returns
+    ///
+    /// Every holding needs a return series covering the same periods.
+    #[graphql(guard = "require_scope(Permissions::PORTFOLIO_READ)")]
+    async fn portfolio_correlation_matrix(
+        &self,
+        ctx: &Context<'_>,
+        portfolio_id: Uuid,
+        asset_returns: Vec<AssetReturnsInput>,
+    ) -> Result<PortfolioCorrelationMatrix> {
+        let portfolio = ctx
+            .data_unchecked::<DataLoader<PortfolioLoader>>()
+            .load_one(portfolio_id)
+            .await?
+            .ok_or_else(|| ApiError::PortfolioNotFound {
+                id: portfolio_id.to_string(),
+            })?;
+        ensure_user_access(ctx, portfolio.user_id, "portfolio")?;
+
+        timed_portfolio_risk(ctx, "correlation_matrix", async {
+            let analyzed_at = chrono::Utc::now();
+            let returns = holding_returns(&portfolio, &asset_returns)?;
+            let matrix = financial_core::portfolio::RiskAnalyzer::new().correlation_matrix(&returns)?;
+            Ok(PortfolioCorrelationMatrix::new(portfolio.id, matrix, analyzed_at))
+        })
+        .await
     }
 
     /// Get portfolio analysis and recommendations
+    #[graphql(guard = "require_scope(Permissions::PORTFOLIO_READ)")]
     async fn portfolio_analysis(
         &self,
+        ctx: &Context<'_>,
         portfolio_id: Uuid,
         strategy: Option<OptimizationStrategy>,
     ) -> Result<PortfolioAnalysis> {
-        // TODO: Implement portfolio analysis logic
-        Err(ApiError::NotImplemented {
-            operation: "portfolio_analysis".to_string(),
-        }
-        .into())
+        let analysis = strategy.map_or_else(|| "default".to_string(), |s| format!("{:?}", s));
+        timed_portfolio_risk(ctx, &analysis, async {
+            // TODO: Implement portfolio analysis logic
+            Err(ApiError::NotImplemented {
+                operation: "portfolio_analysis".to_string(),
+            })
+        })
+        .await
     }
 
     /// Get all debt accounts for a user
+    #[graphql(guard = "require_scope(Permissions::DEBT_READ)")]
     async fn debt_accounts(&self, user_id: Uuid) -> Result<Vec<DebtAccount>> {
         // TODO: Implement debt accounts lookup logic
         Err(ApiError::NotImplemented {
@@ -87,6 +192,7 @@ impl Query {
     }
 
     /// Get a specific debt account by ID
+    #[graphql(guard = "require_scope(Permissions::DEBT_READ)")]
     async fn debt_account(&self, id: Uuid) -> Result<Option<DebtAccount>> {
         // TODO: Implement debt account lookup logic
         Err(ApiError::NotImplemented {
@@ -96,33 +202,73 @@ impl Query {
     }
 
     /// Get debt optimization strategies
+    #[graphql(guard = "require_scope(Permissions::DEBT_READ)")]
     async fn debt_strategies(
         &self,
+        ctx: &Context<'_>,
         user_id: Uuid,
         extra_payment: Option<Decimal>,
     ) -> Result<Vec<DebtStrategy>> {
-        // TODO: Implement debt strategy calculation logic
-        Err(ApiError::NotImplemented {
-            operation: "debt_strategies".to_string(),
-        }
-        .into())
+        timed_debt_optimization(ctx, "all", async {
+            // TODO: Implement debt strategy calculation logic
+            Err(ApiError::NotImplemented {
+                operation: "debt_strategies".to_string(),
+            })
+        })
+        .await
     }
 
     /// Get debt payoff plan
+    #[graphql(guard = "require_scope(Permissions::DEBT_READ)")]
     async fn debt_payoff_plan(
         &self,
+        ctx: &Context<'_>,
         debt_ids: Vec<Uuid>,
         strategy: DebtStrategy,
         extra_payment: Option<Decimal>,
     ) -> Result<PayoffPlan> {
-        // TODO: Implement debt payoff plan logic
-        Err(ApiError::NotImplemented {
-            operation: "debt_payoff_plan".to_string(),
-        }
-        .into())
+        timed_debt_optimization(ctx, &format!("{:?}", strategy), async {
+            // TODO: Implement debt payoff plan logic
+            Err(ApiError::NotImplemented {
+                operation: "debt_payoff_plan".to_string(),
+            })
+        })
+        .await
+    }
+
+    /// Compare snowball and avalanche payoff side by side with a recommendation
+    #[graphql(guard = "require_scope(Permissions::DEBT_READ)")]
+    async fn compare_debt_strategies(
+        &self,
+        ctx: &Context<'_>,
+        user_id: Uuid,
+        input: CompareDebtStrategiesInput,
+    ) -> Result<DebtComparison> {
+        ensure_user_access(ctx, user_id, "debt")?;
+        timed_debt_optimization(ctx, "comparison", async {
+            Ok(compare_debt_strategies(user_id, &input)?.into())
+        })
+        .await
+    }
+
+    /// iCalendar (.ics) feed of the payment dates in a snowball or avalanche plan
+    #[graphql(guard = "require_scope(Permissions::DEBT_READ)")]
+    async fn debt_payment_calendar(
+        &self,
+        ctx: &Context<'_>,
+        user_id: Uuid,
+        input: CompareDebtStrategiesInput,
+        strategy: DebtStrategy,
+    ) -> Result<String> {
+        ensure_user_access(ctx, user_id, "debt")?;
+        timed_debt_optimization(ctx, "calendar", async {
+            debt_payment_calendar(user_id, &input, strategy)
+        })
+        .await
     }
 
     /// Calculate net worth for a user
+    #[graphql(guard = "require_scope(Permissions::DEBT_READ).and(require_scope(Permissions::PORTFOLIO_READ))")]
     async fn net_worth(&self, user_id: Uuid) -> Result<Decimal> {
         // TODO: Implement net worth calculation logic
         Err(ApiError::NotImplemented {
@@ -132,6 +278,7 @@ impl Query {
     }
 
     /// Get financial summary for a user
+    #[graphql(guard = "require_scope(Permissions::DEBT_READ).and(require_scope(Permissions::PORTFOLIO_READ))")]
     async fn financial_summary(&self, user_id: Uuid) -> Result<FinancialSummary> {
         // TODO: Implement financial summary logic
         Err(ApiError::NotImplemented {
@@ -141,6 +288,30 @@ impl Query {
     }
 }
 
+/// Run a debt optimization, recording metrics when the schema carries them
+async fn timed_debt_optimization<T>(
+    ctx: &Context<'_>,
+    strategy: &str,
+    calculation: impl std::future::Future<Output = Result<T>>,
+) -> Result<T> {
+    match ctx.data_opt::<CalculationMetrics>() {
+        Some(metrics) => metrics.time_debt_optimization(strategy, calculation).await,
+        None => calculation.await,
+    }
+}
+
+/// Run a portfolio risk computation, recording metrics when the schema carries them
+async fn timed_portfolio_risk<T>(
+    ctx: &Context<'_>,
+    analysis: &str,
+    calculation: impl std::future::Future<Output = Result<T>>,
+) -> Result<T> {
+    match ctx.data_opt::<CalculationMetrics>() {
+        Some(metrics) => metrics.time_portfolio_risk(analysis, calculation).await,
+        None => calculation.await,
+    }
+}
+
 /// Financial summary information
 #[derive(SimpleObject)]
 pub struct FinancialSummary {
@@ -6,8 +6,17 @@ use futures::Stream;
 use std::pin::Pin;
 use uuid::Uuid;
 
+use crate::auth::Permissions;
 use crate::error::{ApiError, Result};
-use crate::graphql::schema::{debt::DebtAccount, portfolio::Portfolio, user::User};
+use crate::graphql::debt_comparison::compare_debt_strategies;
+use crate::graphql::guards::{ensure_user_access, require_scope};
+use crate::graphql::heartbeat::{Heartbeat, SubscriptionHeartbeat};
+use crate::graphql::schema::{
+    debt::{CompareDebtStrategiesInput, DebtAccount, DebtComparison},
+    portfolio::Portfolio,
+    user::User,
+};
+use crate::monitoring::request_id::spawn_blocking_in_request;
 
 /// Root subscription object
 #[derive(Default)]
@@ -37,6 +46,33 @@ impl Subscription {
         Box::pin(stream)
     }
 
+    /// Compare snowball and avalanche payoff, sending heartbeats while the
+    /// simulation runs and ending once the comparison has been sent
+    #[graphql(guard = "require_scope(Permissions::DEBT_READ)")]
+    async fn debt_comparison_progress(
+        &self,
+        ctx: &Context<'_>,
+        user_id: Uuid,
+        input: CompareDebtStrategiesInput,
+    ) -> Result<impl Stream<Item = Result<DebtComparisonEvent>>> {
+        ensure_user_access(ctx, user_id, "debt")?;
+        let heartbeat = ctx
+            .data_opt::<SubscriptionHeartbeat>()
+            .copied()
+            .unwrap_or_default();
+
+        let comparison = futures::stream::once(async move {
+            let comparison =
+                spawn_blocking_in_request(move || compare_debt_strategies(user_id, &input))
+                    .await
+                    .map_err(|e| ApiError::InternalError {
+                        message: format!("Debt comparison did not complete: {}", e),
+                    })??;
+            Ok(DebtComparisonEvent::Comparison(comparison.into()))
+        });
+        Ok(heartbeat.wrap(comparison, |beat| Ok(DebtComparisonEvent::Heartbeat(beat))))
+    }
+
     /// Subscribe to user profile changes
     async fn user_profile_updates(&self, user_id: Uuid) -> impl Stream<Item = Result<User>> {
         // TODO: Implement user profile update subscription logic
@@ -68,6 +104,15 @@ impl Subscription {
     }
 }
 
+/// Event on the debt comparison subscription
+#[derive(Union)]
+pub enum DebtComparisonEvent {
+    /// Keepalive sent while the comparison is still running
+    Heartbeat(Heartbeat),
+    /// The finished comparison
+    Comparison(DebtComparison),
+}
+
 /// Market update information
 #[derive(SimpleObject)]
 pub struct MarketUpdate {
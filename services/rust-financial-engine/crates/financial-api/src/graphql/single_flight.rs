@@ -0,0 +1,151 @@
+/// Single-flight execution of expensive calculations
+///
+/// When several requests ask for the same calculation at once, only the first
+/// one runs it; the rest wait and receive a copy of its result. This sits in
+/// front of any cache lookup made inside the calculation, so a cold key in
+/// Redis is filled by one computation instead of one per concurrent request.
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use sha2::{Digest, Sha256};
+use tokio::sync::OnceCell;
+
+use crate::error::Result;
+use crate::graphql::schema::portfolio::{AssetReturnsInput, PortfolioRiskMetrics};
+
+/// Calculations currently running, keyed by what they calculate
+///
+/// Results are only shared while a calculation is in flight; once it
+/// finishes, the next request for the same key computes afresh. If the
+/// request running a calculation is dropped, one of the waiting requests
+/// takes it over.
+pub struct SingleFlight<V> {
+    in_flight: Mutex<HashMap<String, Arc<OnceCell<V>>>>,
+}
+
+impl<V> Default for SingleFlight<V> {
+    fn default() -> Self {
+        Self {
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<V: Clone> SingleFlight<V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Result of `compute` for `key`, shared with every concurrent caller
+    /// asking for the same key
+    pub async fn run<F, Fut>(&self, key: impl Into<String>, compute: F) -> V
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = V>,
+    {
+        let key = key.into();
+        let flight = self
+            .in_flight
+            .lock()
+            .unwrap()
+            .entry(key.clone())
+            .or_default()
+            .clone();
+
+        let value = flight.get_or_init(compute).await.clone();
+
+        // The first caller to finish lands the flight; callers arriving later
+        // start a new one
+        let mut in_flight = self.in_flight.lock().unwrap();
+        if in_flight
+            .get(&key)
+            .is_some_and(|current| Arc::ptr_eq(current, &flight))
+        {
+            in_flight.remove(&key);
+        }
+        value
+    }
+}
+
+/// In-flight `analyzePortfolioRisk` calculations, shared through the schema data
+pub type RiskAnalysisFlights = SingleFlight<Result<PortfolioRiskMetrics>>;
+
+/// Key identifying a risk analysis by its portfolio and input returns
+pub fn risk_analysis_key(portfolio_id: uuid::Uuid, asset_returns: &[AssetReturnsInput]) -> String {
+    let mut hasher = Sha256::new();
+    for asset in asset_returns {
+        hasher.update(asset.symbol.as_bytes());
+        hasher.update(format!("|{:?}|", asset.frequency).as_bytes());
+        for value in &asset.returns {
+            hasher.update(value.0.normalize().to_string().as_bytes());
+            hasher.update(b",");
+        }
+        hasher.update(b";");
+    }
+    format!("risk_metrics:{}:{:x}", portfolio_id, hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_concurrent_requests_for_a_cold_key_compute_once() {
+        let flights = Arc::new(SingleFlight::<u64>::new());
+        let computations = Arc::new(AtomicUsize::new(0));
+
+        let requests = (0..16).map(|_| {
+            let flights = flights.clone();
+            let computations = computations.clone();
+            tokio::spawn(async move {
+                flights
+                    .run("portfolio-1", || async {
+                        computations.fetch_add(1, Ordering::SeqCst);
+                        tokio::time::sleep(Duration::from_millis(50)).await;
+                        42
+                    })
+                    .await
+            })
+        });
+        let results = futures::future::join_all(requests).await;
+
+        assert!(results.into_iter().all(|result| result.unwrap() == 42));
+        assert_eq!(computations.load(Ordering::SeqCst), 1);
+        assert!(flights.in_flight.lock().unwrap().is_empty());
+
+        // Finished flights are not reused, and other keys never share one
+        flights.run("portfolio-1", || async { 7 }).await;
+        assert_eq!(flights.run("portfolio-2", || async { 9 }).await, 9);
+        assert_eq!(flights.run("portfolio-1", || async { 8 }).await, 8);
+    }
+
+    #[tokio::test]
+    async fn test_dropped_leader_is_taken_over_by_a_waiter() {
+        let flights = Arc::new(SingleFlight::<&'static str>::new());
+
+        let leader = {
+            let flights = flights.clone();
+            tokio::spawn(async move {
+                flights
+                    .run("key", || async {
+                        tokio::time::sleep(Duration::from_secs(60)).await;
+                        "leader"
+                    })
+                    .await
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        let waiter = {
+            let flights = flights.clone();
+            tokio::spawn(async move { flights.run("key", || async { "waiter" }).await })
+        };
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        leader.abort();
+
+        assert_eq!(waiter.await.unwrap(), "waiter");
+    }
+}
@@ -15,6 +15,8 @@ use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::error::{ApiError, Result};
+
 /// Custom scalar for Decimal amounts
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DecimalType(pub Decimal);
@@ -256,6 +258,8 @@ pub enum DebtStrategy {
     Avalanche,
     Custom,
     Consolidation,
+    /// Clear the smallest balance first, then avalanche the rest
+    HybridKickstart,
 }
 
 impl From<CoreDebtStrategy> for DebtStrategy {
@@ -265,6 +269,7 @@ impl From<CoreDebtStrategy> for DebtStrategy {
             CoreDebtStrategy::Avalanche => DebtStrategy::Avalanche,
             CoreDebtStrategy::Custom => DebtStrategy::Custom,
             CoreDebtStrategy::Consolidation => DebtStrategy::Consolidation,
+            CoreDebtStrategy::HybridKickstart => DebtStrategy::HybridKickstart,
         }
     }
 }
@@ -276,6 +281,7 @@ impl From<DebtStrategy> for CoreDebtStrategy {
             DebtStrategy::Avalanche => CoreDebtStrategy::Avalanche,
             DebtStrategy::Custom => CoreDebtStrategy::Custom,
             DebtStrategy::Consolidation => CoreDebtStrategy::Consolidation,
+            DebtStrategy::HybridKickstart => CoreDebtStrategy::HybridKickstart,
         }
     }
 }
@@ -342,6 +348,17 @@ pub struct MoneyInput {
     pub currency: Currency,
 }
 
+impl MoneyInput {
+    /// Core money for input `field`, which may not be negative
+    pub fn to_core(&self, field: &str) -> Result<financial_core::types::Money> {
+        if self.amount.0 < Decimal::ZERO {
+            return Err(ApiError::validation_error(field, "Amount cannot be negative"));
+        }
+        financial_core::types::Money::new(self.amount.0, self.currency.into())
+            .map_err(|e| ApiError::validation_error(field, &e.to_string()))
+    }
+}
+
 /// Input type for creating percentages
 #[derive(InputObject, Clone, Debug)]
 pub struct PercentageInput {
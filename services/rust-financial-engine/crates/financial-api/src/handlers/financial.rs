@@ -118,7 +118,7 @@ pub async fn validate_precision(Json(data): Json<MoneyData>) -> Result<Json<Vali
     }
 
     // Validate currency
-    if !is_valid_currency(&data.currency) {
+    if data.currency.parse::<Currency>().is_err() {
         response.is_valid = false;
         response
             .errors
@@ -309,7 +309,8 @@ async fn handle_compound_interest(request: CalculationRequest) -> Result<Calcula
     let years = request.years.unwrap_or(1) as u32;
 
     // Calculate compound interest: A = P(1 + r/n)^(nt)
-    let rate_per_compound = annual_rate / Decimal::from(100) / Decimal::from(compounds_per_year);
+    let rate_per_compound =
+        Percentage::from_percentage(annual_rate)?.as_decimal() / Decimal::from(compounds_per_year);
     let total_compounds = compounds_per_year * years;
 
     let growth_factor = (Decimal::from(1) + rate_per_compound)
@@ -361,7 +362,7 @@ async fn handle_loan_payment(request: CalculationRequest) -> Result<CalculationR
         });
     }
 
-    let monthly_rate = annual_rate / Decimal::from(100) / Decimal::from(12);
+    let monthly_rate = Percentage::from_percentage(annual_rate)?.as_decimal() / Decimal::from(12);
     let factor = (Decimal::from(1) + monthly_rate)
         .powu(term_months as u32)
         .unwrap();
@@ -383,33 +384,16 @@ fn parse_money_data(data: &MoneyData) -> Result<Money> {
         crate::error::FinancialError::ValidationError("Invalid amount format".to_string())
     })?;
 
-    let currency = match data.currency.as_str() {
-        "USD" => Currency::USD,
-        "EUR" => Currency::EUR,
-        "GBP" => Currency::GBP,
-        "CAD" => Currency::CAD,
-        "AUD" => Currency::AUD,
-        "JPY" => Currency::JPY,
-        "CHF" => Currency::CHF,
-        "CNY" => Currency::CNY,
-        _ => {
-            return Err(crate::error::FinancialError::ValidationError(format!(
-                "Unsupported currency: {}",
-                data.currency
-            )))
-        }
-    };
+    let currency = data.currency.parse::<Currency>().map_err(|_| {
+        crate::error::FinancialError::ValidationError(format!(
+            "Unsupported currency: {}",
+            data.currency
+        ))
+    })?;
 
     Money::new(amount, currency).map_err(|e| e.into())
 }
 
-fn is_valid_currency(currency: &str) -> bool {
-    matches!(
-        currency,
-        "USD" | "EUR" | "GBP" | "CAD" | "AUD" | "JPY" | "CHF" | "CNY"
-    )
-}
-
 async fn test_calculation() -> Result<()> {
     // Test basic addition
     let money1 = Money::new(Decimal::from(100), Currency::USD)?;
@@ -3,23 +3,210 @@ use async_graphql::{Request, Response};
 /// GraphQL HTTP handlers
 ///
 /// Contains handlers for GraphQL endpoints
-use axum::{extract::State, http::StatusCode, response::Json};
+use axum::{
+    extract::{Extension, State},
+    http::{
+        header::{CACHE_CONTROL, ETAG, IF_NONE_MATCH},
+        HeaderMap, StatusCode,
+    },
+    response::{IntoResponse, Json},
+};
 use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
 
+use crate::auth::AuthContextExtension;
+use crate::config::Config;
 use crate::error::Result;
 use crate::graphql::resolvers::{ApiContext, ApiSchema};
 
 /// GraphQL request handler
 pub async fn graphql_handler(
     State(schema): State<ApiSchema>,
+    auth: Option<Extension<AuthContextExtension>>,
     Json(request): Json<Request>,
 ) -> Result<Json<Response>> {
-    let response = schema.execute(request).await;
+    let response = schema.execute(with_auth_context(request, auth)).await;
     Ok(Json(response))
 }
 
+/// Hand the caller `auth_middleware` authenticated to the GraphQL resolvers
+///
+/// Scope guards and ownership checks read the [`AuthContext`](crate::auth::AuthContext)
+/// from the request data, so a request without it is treated as anonymous.
+pub fn with_auth_context(
+    request: Request,
+    auth: Option<Extension<AuthContextExtension>>,
+) -> Request {
+    match auth {
+        Some(Extension(AuthContextExtension(context))) => request.data(context),
+        None => request,
+    }
+}
+
 /// GraphQL playground handler (development only)
 pub async fn graphql_playground() -> std::result::Result<String, StatusCode> {
     // TODO: Only enable in development mode
     Ok(playground_source(GraphQLPlaygroundConfig::new("/graphql")))
 }
+
+/// Rendered schema SDL and the strong ETag derived from its hash
+#[derive(Debug)]
+pub struct SchemaDocument {
+    pub sdl: String,
+    pub etag: String,
+}
+
+impl SchemaDocument {
+    pub fn render(schema: &ApiSchema) -> Self {
+        let sdl = schema.sdl();
+        let etag = format!("\"{:x}\"", Sha256::digest(sdl.as_bytes()));
+        Self { sdl, etag }
+    }
+
+    /// Whether an `If-None-Match` header value names this document
+    fn matches(&self, if_none_match: &str) -> bool {
+        if_none_match
+            .split(',')
+            .map(str::trim)
+            .any(|tag| tag == "*" || tag.strip_prefix("W/").unwrap_or(tag) == self.etag)
+    }
+}
+
+/// State for the schema SDL endpoint
+#[derive(Clone)]
+pub struct SchemaEndpoint {
+    schema: ApiSchema,
+    enabled: bool,
+    cached: Option<Arc<SchemaDocument>>,
+}
+
+impl SchemaEndpoint {
+    /// Serve `schema` only where `config` allows introspection
+    ///
+    /// With `cache_schema_sdl` set the SDL is rendered here, once.
+    pub fn new(schema: ApiSchema, config: &Config) -> Self {
+        let enabled = config.introspection_enabled();
+        let cached = (enabled && config.graphql.cache_schema_sdl)
+            .then(|| Arc::new(SchemaDocument::render(&schema)));
+        Self {
+            schema,
+            enabled,
+            cached,
+        }
+    }
+
+    /// The SDL rendered at startup, if caching is enabled
+    pub fn cached(&self) -> Option<&SchemaDocument> {
+        self.cached.as_deref()
+    }
+
+    fn document(&self) -> Arc<SchemaDocument> {
+        self.cached
+            .clone()
+            .unwrap_or_else(|| Arc::new(SchemaDocument::render(&self.schema)))
+    }
+}
+
+/// Schema SDL handler; answers 404 where introspection is disabled
+///
+/// Responses carry the schema hash as an ETag, and a request whose
+/// `If-None-Match` names it gets `304 Not Modified` without a body.
+pub async fn schema_sdl(
+    State(endpoint): State<SchemaEndpoint>,
+    headers: HeaderMap,
+) -> axum::response::Response {
+    if !endpoint.enabled {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+
+    let document = endpoint.document();
+    let not_modified = headers
+        .get(IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| document.matches(value));
+    if not_modified {
+        return (StatusCode::NOT_MODIFIED, [(ETAG, document.etag.clone())]).into_response();
+    }
+
+    (
+        [
+            (ETAG, document.etag.clone()),
+            (CACHE_CONTROL, "no-cache".to_string()),
+        ],
+        document.sdl.clone(),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Environment;
+    use crate::graphql::create_schema;
+    use axum::{body::Body, http::Request as HttpRequest, routing::get, Router};
+    use tower::ServiceExt;
+
+    fn schema_app(config: &Config) -> Router {
+        Router::new().route(
+            "/schema",
+            get(schema_sdl).with_state(SchemaEndpoint::new(create_schema(), config)),
+        )
+    }
+
+    async fn get_schema(app: &Router, if_none_match: Option<&str>) -> axum::response::Response {
+        let mut request = HttpRequest::builder().uri("/schema");
+        if let Some(etag) = if_none_match {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        app.clone()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    async fn schema_status(environment: Environment) -> StatusCode {
+        let config = Config {
+            environment,
+            ..Config::test_config()
+        };
+        get_schema(&schema_app(&config), None).await.status()
+    }
+
+    #[tokio::test]
+    async fn test_schema_endpoint_hidden_in_production() {
+        assert_eq!(
+            schema_status(Environment::Development).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            schema_status(Environment::Production).await,
+            StatusCode::NOT_FOUND
+        );
+    }
+
+    #[tokio::test]
+    async fn test_repeat_schema_request_with_etag_is_not_modified() {
+        let mut config = Config::test_config();
+        for cache_schema_sdl in [true, false] {
+            config.graphql.cache_schema_sdl = cache_schema_sdl;
+            let app = schema_app(&config);
+
+            let first = get_schema(&app, None).await;
+            assert_eq!(first.status(), StatusCode::OK);
+            let etag = first.headers()[ETAG].to_str().unwrap().to_string();
+            assert_eq!(etag, SchemaDocument::render(&create_schema()).etag);
+
+            let repeat = get_schema(&app, Some(&etag)).await;
+            assert_eq!(repeat.status(), StatusCode::NOT_MODIFIED);
+            assert_eq!(repeat.headers()[ETAG], etag.as_str());
+            let body = axum::body::to_bytes(repeat.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            assert!(body.is_empty());
+
+            let stale = get_schema(&app, Some("\"0000\"")).await;
+            assert_eq!(stale.status(), StatusCode::OK);
+        }
+    }
+}
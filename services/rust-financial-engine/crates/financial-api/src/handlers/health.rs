@@ -1,9 +1,11 @@
 /// Health check handlers
 ///
 /// Contains handlers for service health monitoring
-use axum::{http::StatusCode, response::Json};
+use axum::{extract::State, http::StatusCode, response::Json};
 use serde::Serialize;
 
+use crate::monitoring::readiness::{ReadinessProbes, ReadinessReport};
+
 /// Health check response
 #[derive(Serialize)]
 pub struct HealthResponse {
@@ -12,7 +14,10 @@ pub struct HealthResponse {
     pub timestamp: String,
 }
 
-/// Basic health check endpoint
+/// Liveness endpoint: the process is up and serving requests
+///
+/// Never touches a dependency, so an outage downstream does not get the
+/// process restarted.
 pub async fn health_check() -> Json<HealthResponse> {
     Json(HealthResponse {
         status: "healthy".to_string(),
@@ -21,12 +26,16 @@ pub async fn health_check() -> Json<HealthResponse> {
     })
 }
 
-/// Readiness check endpoint
-pub async fn readiness_check() -> Result<Json<HealthResponse>, StatusCode> {
-    // TODO: Add database connectivity checks, etc.
-    Ok(Json(HealthResponse {
-        status: "ready".to_string(),
-        version: env!("CARGO_PKG_VERSION").to_string(),
-        timestamp: chrono::Utc::now().to_rfc3339(),
-    }))
+/// Readiness endpoint: probes every dependency and answers 503 while a
+/// critical one is unhealthy
+pub async fn readiness_check(
+    State(probes): State<ReadinessProbes>,
+) -> (StatusCode, Json<ReadinessReport>) {
+    let report = probes.check().await;
+    let status = if report.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(report))
 }
@@ -3,10 +3,11 @@
 /// High-performance GraphQL API for financial calculations with:
 /// - Exact decimal precision using rust_decimal
 /// - JWT authentication with Atlas integration
-/// - Redis caching for performance
+/// - Redis or in-memory caching for performance
 /// - Comprehensive debt and portfolio analysis
 /// - Prometheus metrics and monitoring
 pub mod auth;
+pub mod cache;
 pub mod config;
 pub mod error;
 pub mod graphql;
@@ -3,24 +3,33 @@
 /// High-performance GraphQL API server for financial calculations
 /// Built with Axum, async-graphql, and Tokio for maximum concurrency
 use axum::{
-    extract::State,
-    http::{header, StatusCode},
-    response::Html,
+    extract::{Extension, State},
+    response::{Html, Json},
     routing::{get, post},
     Router,
 };
 use financial_api::{
-    auth::middleware::auth_middleware,
+    auth::concurrency::{user_concurrency_middleware, UserConcurrencyLimiter},
+    auth::middleware::{auth_middleware, AuthContextExtension, AuthState},
+    auth::{AtlasApiClient, JwtManager, TokenBlacklist},
+    cache::{connect_cache, AsyncCache},
     config::Config,
     error::ApiError,
-    graphql::{create_schema, GraphQLRequest, GraphQLResponse},
-    monitoring::metrics::setup_metrics,
-    service::ApiService,
+    graphql::{create_schema_for_config, ApiSchema},
+    handlers::{
+        health_check as liveness_check, readiness_check, schema_sdl, with_auth_context,
+        SchemaEndpoint,
+    },
+    monitoring::{
+        metrics::{setup_metrics, CalculationMetrics},
+        request_id_middleware, ReadinessProbes, DEFAULT_PROBE_TIMEOUT,
+    },
+    service::{compress_responses, cors_layer, request_body_limit_layer},
 };
 use std::net::SocketAddr;
+use std::sync::Arc;
 use tower::ServiceBuilder;
 use tower_http::{
-    cors::{Any, CorsLayer},
     trace::{DefaultMakeSpan, DefaultOnRequest, DefaultOnResponse, TraceLayer},
     LatencyUnit,
 };
@@ -49,53 +58,79 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     info!("📊 Configuration loaded successfully");
     info!("🌐 Server will bind to: {}:{}", config.host, config.port);
     info!("🔐 JWT issuer: {}", config.jwt.issuer);
-    info!("📊 GraphQL introspection: {}", config.graphql.introspection);
-
-    // Setup metrics
-    let metrics_handle = setup_metrics()?;
+    info!("🔐 JWT audience: {}", config.jwt.audience);
+    info!(
+        "📊 GraphQL introspection: {}",
+        config.introspection_enabled()
+    );
 
-    // Initialize API service
-    let api_service = ApiService::new(config.clone()).await?;
+    // Tokens must match the configured issuer and audience
+    let jwt_secret = std::env::var("JWT_SECRET").unwrap_or_default();
+    let auth = auth_state(&config, &jwt_secret)?;
 
-    // Create GraphQL schema
-    let schema = create_schema(api_service.clone()).await?;
+    // Setup metrics
+    let _metrics_handle = setup_metrics()?;
 
+    // Connect the cache backend selected by configuration
+    let cache = connect_cache(&config.redis).await?;
     info!(
-        "🎯 GraphQL schema created with {} types",
-        schema.sdl().lines().count()
+        "🗄️ Cache backend: {}",
+        if config.redis.enabled {
+            "redis"
+        } else {
+            "in-memory"
+        }
     );
 
-    // Setup CORS
-    let cors = CorsLayer::new()
-        .allow_origin(Any)
-        .allow_methods(Any)
-        .allow_headers([header::CONTENT_TYPE, header::AUTHORIZATION]);
+    // Probe the cache and, when configured, the Atlas auth service on /ready
+    let atlas = match std::env::var("ATLAS_API_URL") {
+        Ok(url) => Some(AtlasApiClient::new(
+            url,
+            std::env::var("ATLAS_API_KEY").unwrap_or_default(),
+        )?),
+        Err(_) => None,
+    };
+    let readiness = readiness_probes(cache.clone(), atlas);
 
-    // Setup tracing
-    let trace_layer = TraceLayer::new_for_http()
-        .make_span_with(DefaultMakeSpan::new().level(Level::INFO))
-        .on_request(DefaultOnRequest::new().level(Level::INFO))
-        .on_response(
-            DefaultOnResponse::new()
-                .level(Level::INFO)
-                .latency_unit(LatencyUnit::Millis),
-        );
+    // Create GraphQL schema
+    let calculations = CalculationMetrics::new(prometheus::default_registry())?;
+    let schema = create_schema_for_config(&config, calculations, cache.clone());
+
+    let schema_endpoint = SchemaEndpoint::new(schema.clone(), &config);
+    match schema_endpoint.cached() {
+        Some(document) => info!(
+            "🎯 GraphQL schema created ({} SDL lines, ETag {})",
+            document.sdl.lines().count(),
+            document.etag
+        ),
+        None => info!("🎯 GraphQL schema created"),
+    }
 
+    // CORS follows the configured allowlist
+    info!("🌍 CORS allowed origins: {:?}", config.cors.allowed_origins);
+    info!(
+        "📦 Maximum request body size: {} bytes",
+        config.performance.max_request_size
+    );
+    info!(
+        "🚦 Maximum in-flight requests per user: {}",
+        config.performance.max_concurrent_requests_per_user
+    );
+    info!(
+        "🗜️ Response compression: {} (minimum {} bytes)",
+        config.performance.enable_compression, config.performance.compression_min_size
+    );
     // Build application routes
-    let app = Router::new()
-        .route("/", get(playground).post(graphql_handler))
-        .route("/graphql", post(graphql_handler))
-        .route("/health", get(health_check))
-        .route("/metrics", get(metrics_handler))
-        .route("/schema", get(schema_handler))
-        .with_state(AppState {
+    let app = app_router(
+        AppState {
             schema: schema.clone(),
             config: config.clone(),
-            api_service: api_service.clone(),
-        })
-        .layer(ServiceBuilder::new().layer(trace_layer).layer(cors).layer(
-            axum::middleware::from_fn_with_state(config.clone(), auth_middleware),
-        ));
+            cache: cache.clone(),
+        },
+        auth,
+        schema_endpoint,
+        readiness,
+    );
 
     // Start server
     let addr = SocketAddr::from(([0, 0, 0, 0], config.port));
@@ -103,6 +138,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     info!("🎉 Atlas Financial API Server starting on {}", addr);
     info!("📊 GraphQL Playground available at http://{}/", addr);
     info!("🔍 Health check available at http://{}/health", addr);
+    info!(
+        "🩺 Liveness and readiness at http://{0}/live and http://{0}/ready",
+        addr
+    );
     info!("📈 Metrics available at http://{}/metrics", addr);
 
     let listener = tokio::net::TcpListener::bind(addr).await?;
@@ -121,22 +160,97 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 /// Application state shared across handlers
 #[derive(Clone)]
 struct AppState {
-    schema: async_graphql::Schema<
-        financial_api::graphql::Query,
-        financial_api::graphql::Mutation,
-        async_graphql::EmptySubscription,
-    >,
+    schema: ApiSchema,
     config: Config,
-    api_service: ApiService,
+    /// Cache backend built from `config.redis`, shared with the schema
+    cache: Arc<dyn AsyncCache>,
+}
+
+/// Authentication state validating tokens against `config.jwt`
+///
+/// Authentication is only optional in development.
+fn auth_state(config: &Config, secret: &str) -> Result<AuthState, ApiError> {
+    let jwt_manager = JwtManager::from_config(secret, &config.jwt)?;
+    Ok(AuthState::new(
+        jwt_manager,
+        TokenBlacklist::new(),
+        !config.is_development(),
+    ))
 }
 
-/// GraphQL handler
+/// Application routes behind the request ID, tracing, CORS, auth, per-user
+/// concurrency and body size layers
+fn app_router(
+    state: AppState,
+    auth: AuthState,
+    schema_endpoint: SchemaEndpoint,
+    readiness: ReadinessProbes,
+) -> Router {
+    let config = state.config.clone();
+
+    // Setup tracing
+    let trace_layer = TraceLayer::new_for_http()
+        .make_span_with(DefaultMakeSpan::new().level(Level::INFO))
+        .on_request(DefaultOnRequest::new().level(Level::INFO))
+        .on_response(
+            DefaultOnResponse::new()
+                .level(Level::INFO)
+                .latency_unit(LatencyUnit::Millis),
+        );
+
+    // Metrics are added after compression so Prometheus scrapes are never compressed
+    let routes = Router::new()
+        .route("/", get(playground).post(graphql_handler))
+        .route("/graphql", post(graphql_handler))
+        .route("/health", get(health_check))
+        .route("/schema", get(schema_sdl).with_state(schema_endpoint));
+    compress_responses(routes, &config.performance)
+        .route("/metrics", get(metrics_handler))
+        .with_state(state)
+        .layer(
+            ServiceBuilder::new()
+                .layer(axum::middleware::from_fn(request_id_middleware))
+                .layer(trace_layer)
+                .layer(cors_layer(&config.cors))
+                .layer(axum::middleware::from_fn_with_state(auth, auth_middleware))
+                .layer(axum::middleware::from_fn_with_state(
+                    UserConcurrencyLimiter::from_config(&config.performance),
+                    user_concurrency_middleware,
+                ))
+                // Innermost, as it changes the body type the middleware above expects
+                .layer(request_body_limit_layer(&config.performance)),
+        )
+        // Orchestrator probes carry no token, so they sit outside auth
+        .merge(probe_routes(readiness))
+}
+
+/// Dependencies `/ready` checks
+///
+/// The service is not ready while Atlas auth is down, as no token can be
+/// issued. A failing cache only degrades the report, since requests bypass it.
+fn readiness_probes(cache: Arc<dyn AsyncCache>, atlas: Option<AtlasApiClient>) -> ReadinessProbes {
+    let probes = ReadinessProbes::new().with_probe("cache", cache, DEFAULT_PROBE_TIMEOUT, false);
+    match atlas {
+        Some(atlas) => probes.with_probe("atlas_auth", atlas, DEFAULT_PROBE_TIMEOUT, true),
+        None => probes,
+    }
+}
+
+/// Liveness and readiness routes, served without authentication
+fn probe_routes(readiness: ReadinessProbes) -> Router {
+    Router::new()
+        .route("/live", get(liveness_check))
+        .route("/ready", get(readiness_check).with_state(readiness))
+}
+
+/// GraphQL handler, running each request as the caller the auth layer authenticated
 async fn graphql_handler(
     State(state): State<AppState>,
-    req: GraphQLRequest,
-) -> Result<GraphQLResponse, ApiError> {
-    let response = state.schema.execute(req.into_inner()).await;
-    Ok(GraphQLResponse::from(response))
+    auth: Option<Extension<AuthContextExtension>>,
+    Json(request): Json<async_graphql::Request>,
+) -> Result<Json<async_graphql::Response>, ApiError> {
+    let response = state.schema.execute(with_auth_context(request, auth)).await;
+    Ok(Json(response))
 }
 
 /// GraphQL Playground handler
@@ -245,7 +359,7 @@ async fn health_check(
         "checks": {
             "graphql_schema": "ok",
             "authentication": "ok",
-            "cache": state.api_service.check_cache_health().await,
+            "cache": state.cache.check_cache_health().await,
             "memory": "ok"
         }
     });
@@ -262,54 +376,325 @@ async fn metrics_handler() -> Result<String, ApiError> {
 
     encoder
         .encode_to_string(&metric_families)
-        .map_err(|e| ApiError::Internal(format!("Failed to encode metrics: {}", e)))
-}
-
-/// Schema SDL handler
-async fn schema_handler(State(state): State<AppState>) -> String {
-    state.schema.sdl()
+        .map_err(|e| ApiError::InternalError {
+            message: format!("Failed to encode metrics: {}", e),
+        })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use axum::http::StatusCode;
-    use axum_test::TestServer;
+    use axum::{body::Body, http::Request, http::StatusCode};
+    use financial_api::auth::{JwtClaims, UserClaims, UserRole};
+    use financial_api::graphql::{
+        create_schema, create_schema_with_audit_trail, AuditTrail, InMemoryAuditSink,
+    };
+    use tower::ServiceExt;
 
     async fn create_test_app() -> Router {
         let config = Config::test_config();
-        let api_service = ApiService::new(config.clone()).await.unwrap();
-        let schema = create_schema(api_service.clone()).await.unwrap();
+        let cache = connect_cache(&config.redis).await.unwrap();
+        let calculations = CalculationMetrics::new(&prometheus::Registry::new()).unwrap();
+        let schema = create_schema_for_config(&config, calculations, cache.clone());
 
         Router::new()
             .route("/health", get(health_check))
             .with_state(AppState {
                 schema,
                 config,
-                api_service,
+                cache,
             })
     }
 
+    async fn fetch(app: Router, uri: &str) -> (StatusCode, String) {
+        let response = app
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
     #[tokio::test]
     async fn test_health_check() {
-        let app = create_test_app().await;
-        let server = TestServer::new(app).unwrap();
+        let (status, body) = fetch(create_test_app().await, "/health").await;
+        assert_eq!(status, StatusCode::OK);
 
-        let response = server.get("/health").await;
-        assert_eq!(response.status_code(), StatusCode::OK);
-
-        let health: serde_json::Value = response.json();
+        let health: serde_json::Value = serde_json::from_str(&body).unwrap();
         assert_eq!(health["status"], "healthy");
         assert_eq!(health["service"], "atlas-financial-api");
+        // Test config disables Redis, so the in-memory cache is reported
+        assert_eq!(health["checks"]["cache"], "ok");
+    }
+
+    const TEST_SECRET: &str = "test-secret-key-that-is-long-enough-for-testing";
+
+    fn claims_for(config: &Config) -> JwtClaims {
+        let now = chrono::Utc::now();
+        JwtClaims {
+            sub: "123e4567-e89b-12d3-a456-426614174000".to_string(),
+            iss: config.jwt.issuer.clone(),
+            aud: config.jwt.audience.clone(),
+            iat: now.timestamp(),
+            exp: (now + chrono::Duration::hours(1)).timestamp(),
+            nbf: None,
+            user: UserClaims {
+                id: "123e4567-e89b-12d3-a456-426614174000".to_string(),
+                email: "test@example.com".to_string(),
+                name: "Test User".to_string(),
+                role: UserRole::User,
+                verified: true,
+                created_at: now,
+                last_login: Some(now),
+            },
+            permissions: Vec::new(),
+            scope: None,
+            org_id: None,
+            session_id: "session123".to_string(),
+        }
+    }
+
+    /// Status of a request carrying `claims` through the server's auth layer
+    async fn authenticated_status(config: &Config, claims: &JwtClaims) -> StatusCode {
+        let token = JwtManager::new(TEST_SECRET, vec![claims.iss.clone()])
+            .unwrap()
+            .encode_token(claims)
+            .unwrap();
+        let app = Router::new()
+            .route("/protected", get(|| async { "ok" }))
+            .layer(axum::middleware::from_fn_with_state(
+                auth_state(config, TEST_SECRET).unwrap(),
+                auth_middleware,
+            ));
+
+        app.oneshot(
+            Request::builder()
+                .uri("/protected")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+        .status()
+    }
+
+    #[tokio::test]
+    async fn test_server_enforces_the_configured_audience() {
+        let config = Config::test_config();
+        let mut claims = claims_for(&config);
+        assert_eq!(authenticated_status(&config, &claims).await, StatusCode::OK);
+
+        claims.aud = "financial-api".to_string();
+        assert_eq!(
+            authenticated_status(&config, &claims).await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+
+    #[tokio::test]
+    async fn test_server_applies_the_configured_leeway() {
+        let mut config = Config::test_config();
+        config.jwt.validation.validate_exp = true;
+        let mut claims = claims_for(&config);
+        claims.exp = (chrono::Utc::now() - chrono::Duration::seconds(10)).timestamp();
+
+        config.jwt.validation.leeway = 30;
+        assert_eq!(authenticated_status(&config, &claims).await, StatusCode::OK);
+
+        config.jwt.validation.leeway = 0;
+        assert_eq!(
+            authenticated_status(&config, &claims).await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+
+    #[tokio::test]
+    async fn test_probes_answer_without_a_token() {
+        // The test environment requires a token on every other route
+        let config = Config::test_config();
+        let cache = connect_cache(&config.redis).await.unwrap();
+        let mut atlas = mockito::Server::new_async().await;
+        let client = AtlasApiClient::new(atlas.url(), "test-key".to_string()).unwrap();
+
+        let app = Router::new()
+            .route("/protected", get(|| async { "ok" }))
+            .layer(axum::middleware::from_fn_with_state(
+                auth_state(&config, TEST_SECRET).unwrap(),
+                auth_middleware,
+            ))
+            .merge(probe_routes(readiness_probes(cache, Some(client))));
+        assert_eq!(
+            fetch(app.clone(), "/protected").await.0,
+            StatusCode::UNAUTHORIZED
+        );
+
+        let (status, _) = fetch(app.clone(), "/live").await;
+        assert_eq!(status, StatusCode::OK);
+
+        let up = atlas
+            .mock("GET", "/health")
+            .with_status(200)
+            .create_async()
+            .await;
+        let (status, body) = fetch(app.clone(), "/ready").await;
+        assert_eq!(status, StatusCode::OK);
+        let report: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(report["dependencies"][0]["name"], "cache");
+        assert_eq!(report["dependencies"][1]["name"], "atlas_auth");
+
+        up.remove_async().await;
+        atlas
+            .mock("GET", "/health")
+            .with_status(503)
+            .create_async()
+            .await;
+        let (status, _) = fetch(app, "/ready").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    /// The server's routes and layers around `schema`
+    async fn server_with(config: Config, schema: ApiSchema) -> Router {
+        let cache = connect_cache(&config.redis).await.unwrap();
+        app_router(
+            AppState {
+                schema: schema.clone(),
+                config: config.clone(),
+                cache: cache.clone(),
+            },
+            auth_state(&config, TEST_SECRET).unwrap(),
+            SchemaEndpoint::new(schema, &config),
+            readiness_probes(cache, None),
+        )
+    }
+
+    /// Data and errors of `query` posted with a bearer token granting `scope`
+    async fn post_graphql(
+        app: Router,
+        config: &Config,
+        scope: &str,
+        query: &str,
+    ) -> serde_json::Value {
+        let mut claims = claims_for(config);
+        claims.scope = Some(scope.to_string());
+        let token = JwtManager::new(TEST_SECRET, vec![claims.iss.clone()])
+            .unwrap()
+            .encode_token(&claims)
+            .unwrap();
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/graphql")
+                    .header("authorization", format!("Bearer {}", token))
+                    .header("content-type", "application/json")
+                    .body(Body::from(
+                        serde_json::json!({ "query": query }).to_string(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_graphql_runs_as_the_authenticated_caller() {
+        let config = Config::test_config();
+        let app = server_with(config.clone(), create_schema()).await;
+
+        let comparison = r#"{ compareDebtStrategies(userId: "123e4567-e89b-12d3-a456-426614174000", input: {
+            extraPayment: { amount: "100", currency: USD },
+            debts: [{
+                name: "Visa", debtType: CREDIT_CARD,
+                balance: { amount: "1000", currency: USD },
+                interestRate: { percentage: { value: "19.99" }, period: ANNUAL },
+                minimumPayment: { amount: "50", currency: USD }
+            }]
+        }) { recommendedStrategy } }"#;
+        let response = post_graphql(app.clone(), &config, "debt:read", comparison).await;
+        assert!(response.get("errors").is_none(), "{}", response);
+        assert!(response["data"]["compareDebtStrategies"]["recommendedStrategy"].is_string());
+
+        let mutation = r#"mutation {
+            createPortfolio(userId: "123e4567-e89b-12d3-a456-426614174000", input: { name: "Retirement" }) { id }
+        }"#;
+        let response = post_graphql(app, &config, "debt:read", mutation).await;
+        assert_eq!(response["errors"][0]["extensions"]["code"], "UNAUTHORIZED");
+        assert!(response["errors"][0]["message"]
+            .as_str()
+            .unwrap()
+            .contains("portfolio:write"));
+    }
+
+    #[tokio::test]
+    async fn test_saved_portfolios_are_reachable_over_http() {
+        let config = Config::test_config();
+        let app = server_with(config.clone(), create_schema()).await;
+        let scope = "portfolio:read portfolio:write";
+
+        let created = post_graphql(
+            app.clone(),
+            &config,
+            scope,
+            r#"mutation {
+                createPortfolio(userId: "123e4567-e89b-12d3-a456-426614174000", input: { name: "Retirement" }) { id }
+            }"#,
+        )
+        .await;
+        assert!(created.get("errors").is_none(), "{}", created);
+        let id = created["data"]["createPortfolio"]["id"].as_str().unwrap();
+
+        let fetched = post_graphql(
+            app,
+            &config,
+            scope,
+            &format!(r#"{{ getPortfolio(id: "{}") {{ name }} }}"#, id),
+        )
+        .await;
+        assert_eq!(fetched["data"]["getPortfolio"]["name"], "Retirement");
+    }
+
+    #[tokio::test]
+    async fn test_audit_records_name_the_token_subject() {
+        let config = Config::test_config();
+        let sink = InMemoryAuditSink::new();
+        let schema = create_schema_with_audit_trail(AuditTrail::new(sink.clone()));
+        let app = server_with(config.clone(), schema).await;
+
+        post_graphql(
+            app,
+            &config,
+            "portfolio:read portfolio:write",
+            r#"mutation {
+                createPortfolio(userId: "123e4567-e89b-12d3-a456-426614174000", input: { name: "Retirement" }) { id }
+            }"#,
+        )
+        .await;
+
+        let records = sink.records().await;
+        assert_eq!(records.len(), 1);
+        assert_eq!(
+            records[0].subject,
+            Some(claims_for(&config).sub.parse().unwrap())
+        );
+        assert!(records[0].succeeded);
     }
 
     #[tokio::test]
     async fn test_playground_loads() {
         let app = Router::new().route("/", get(playground));
-        let server = TestServer::new(app).unwrap();
 
-        let response = server.get("/").await;
-        assert_eq!(response.status_code(), StatusCode::OK);
-        assert!(response.text().contains("GraphQL Playground"));
+        let (status, body) = fetch(app, "/").await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.contains("GraphQL Playground"));
     }
 }
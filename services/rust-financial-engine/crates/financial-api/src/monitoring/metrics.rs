@@ -4,8 +4,9 @@
 /// system performance, and business logic health.
 use prometheus::{
     register_counter, register_histogram, register_int_counter, register_int_gauge, Counter,
-    Histogram, IntCounter, IntGauge, Opts, Registry,
+    Histogram, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, Opts, Registry,
 };
+use std::future::Future;
 use std::time::Instant;
 
 /// Metrics handle for the financial API
@@ -44,6 +45,116 @@ pub struct MetricsHandle {
     pub active_connections: IntGauge,
     pub memory_usage_bytes: IntGauge,
     pub cpu_usage_percent: IntGauge,
+
+    // Labeled per-strategy calculation metrics
+    pub calculations: CalculationMetrics,
+}
+
+/// Calculation metrics labeled by strategy or analysis kind
+///
+/// Registered against an explicit registry so that independent instances can
+/// be created, e.g. one per test.
+#[derive(Clone)]
+pub struct CalculationMetrics {
+    /// Calculations run, labeled by operation, kind and outcome
+    pub operations_total: IntCounterVec,
+    /// Debt optimization latency, labeled by strategy
+    pub debt_optimization_latency: HistogramVec,
+    /// Portfolio risk computation latency, labeled by analysis kind
+    pub portfolio_risk_latency: HistogramVec,
+}
+
+impl CalculationMetrics {
+    pub const DEBT_OPTIMIZATION: &'static str = "debt_optimization";
+    pub const PORTFOLIO_RISK: &'static str = "portfolio_risk";
+
+    /// Create calculation metrics registered with `registry`
+    pub fn new(registry: &Registry) -> Result<Self, prometheus::Error> {
+        let operations_total = IntCounterVec::new(
+            Opts::new(
+                "calculation_operations_total",
+                "Total number of financial calculations by operation, kind and outcome",
+            )
+            .namespace("atlas_financial"),
+            &["operation", "kind", "outcome"],
+        )?;
+
+        let debt_optimization_latency = HistogramVec::new(
+            HistogramOpts::new(
+                "debt_optimization_latency_seconds",
+                "Debt optimization latency in seconds by strategy",
+            )
+            .namespace("atlas_financial"),
+            &["strategy"],
+        )?;
+
+        let portfolio_risk_latency = HistogramVec::new(
+            HistogramOpts::new(
+                "portfolio_risk_latency_seconds",
+                "Portfolio risk computation latency in seconds by analysis kind",
+            )
+            .namespace("atlas_financial"),
+            &["analysis"],
+        )?;
+
+        registry.register(Box::new(operations_total.clone()))?;
+        registry.register(Box::new(debt_optimization_latency.clone()))?;
+        registry.register(Box::new(portfolio_risk_latency.clone()))?;
+
+        Ok(Self {
+            operations_total,
+            debt_optimization_latency,
+            portfolio_risk_latency,
+        })
+    }
+
+    /// Record a debt optimization for the given strategy
+    pub fn record_debt_optimization(&self, strategy: &str, duration: std::time::Duration, success: bool) {
+        self.debt_optimization_latency
+            .with_label_values(&[strategy])
+            .observe(duration.as_secs_f64());
+        self.record_operation(Self::DEBT_OPTIMIZATION, strategy, success);
+    }
+
+    /// Record a portfolio risk computation for the given analysis kind
+    pub fn record_portfolio_risk(&self, analysis: &str, duration: std::time::Duration, success: bool) {
+        self.portfolio_risk_latency
+            .with_label_values(&[analysis])
+            .observe(duration.as_secs_f64());
+        self.record_operation(Self::PORTFOLIO_RISK, analysis, success);
+    }
+
+    /// Count a calculation without recording its latency
+    pub fn record_operation(&self, operation: &str, kind: &str, success: bool) {
+        let outcome = if success { "success" } else { "failure" };
+        self.operations_total
+            .with_label_values(&[operation, kind, outcome])
+            .inc();
+    }
+
+    /// Run a debt optimization and record its latency and outcome
+    pub async fn time_debt_optimization<T, E>(
+        &self,
+        strategy: &str,
+        calculation: impl Future<Output = Result<T, E>>,
+    ) -> Result<T, E> {
+        let timer = Timer::new();
+        let result = calculation.await;
+        self.record_debt_optimization(strategy, timer.elapsed(), result.is_ok());
+        result
+    }
+
+    /// Run a portfolio risk computation and record its latency and outcome
+    pub async fn time_portfolio_risk<T, E>(
+        &self,
+        analysis: &str,
+        calculation: impl Future<Output = Result<T, E>>,
+    ) -> Result<T, E> {
+        let timer = Timer::new();
+        let result = calculation.await;
+        self.record_portfolio_risk(analysis, timer.elapsed(), result.is_ok());
+        result
+    }
 }
 
 impl MetricsHandle {
@@ -177,6 +288,8 @@ impl MetricsHandle {
                 Opts::new("cpu_usage_percent", "CPU usage percentage").namespace("atlas_financial")
             )?;
 
+        let calculations = CalculationMetrics::new(prometheus::default_registry())?;
+
         Ok(Self {
             http_requests_total,
             http_request_duration,
@@ -198,6 +311,7 @@ impl MetricsHandle {
             active_connections,
             memory_usage_bytes,
             cpu_usage_percent,
+            calculations,
         })
     }
 
@@ -314,6 +428,67 @@ mod tests {
         assert!(metrics.authentication_attempts.get() > 0);
     }
 
+    #[test]
+    fn test_calculation_metric_families_registered() {
+        let registry = Registry::new();
+        let metrics = CalculationMetrics::new(&registry).unwrap();
+
+        metrics.record_debt_optimization("Avalanche", Duration::from_millis(20), true);
+        metrics.record_portfolio_risk("MinimizeRisk", Duration::from_millis(40), false);
+
+        let families: Vec<String> = registry
+            .gather()
+            .iter()
+            .map(|family| family.get_name().to_string())
+            .collect();
+
+        assert!(families.contains(&"atlas_financial_calculation_operations_total".to_string()));
+        assert!(families.contains(&"atlas_financial_debt_optimization_latency_seconds".to_string()));
+        assert!(families.contains(&"atlas_financial_portfolio_risk_latency_seconds".to_string()));
+
+        // Registering the same families twice in one registry is rejected
+        assert!(CalculationMetrics::new(&registry).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_timed_calculations_increment_labeled_metrics() {
+        let metrics = CalculationMetrics::new(&Registry::new()).unwrap();
+
+        let result: Result<u32, String> = metrics
+            .time_debt_optimization("Snowball", async { Ok(42) })
+            .await;
+        assert_eq!(result.unwrap(), 42);
+
+        let failed: Result<u32, String> = metrics
+            .time_portfolio_risk("MaximizeSharpe", async { Err("no data".to_string()) })
+            .await;
+        assert!(failed.is_err());
+
+        let debt_count = metrics
+            .operations_total
+            .with_label_values(&[CalculationMetrics::DEBT_OPTIMIZATION, "Snowball", "success"])
+            .get();
+        let risk_failures = metrics
+            .operations_total
+            .with_label_values(&[CalculationMetrics::PORTFOLIO_RISK, "MaximizeSharpe", "failure"])
+            .get();
+        assert_eq!(debt_count, 1);
+        assert_eq!(risk_failures, 1);
+
+        let debt_samples = metrics
+            .debt_optimization_latency
+            .with_label_values(&["Snowball"])
+            .get_sample_count();
+        assert_eq!(debt_samples, 1);
+
+        // Other strategies are tracked separately
+        let avalanche_samples = metrics
+            .debt_optimization_latency
+            .with_label_values(&["Avalanche"])
+            .get_sample_count();
+        assert_eq!(avalanche_samples, 0);
+    }
+
     #[test]
     fn test_timer() {
         let timer = Timer::new();
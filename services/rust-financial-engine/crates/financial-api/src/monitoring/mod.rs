@@ -3,8 +3,18 @@
 /// Comprehensive monitoring and observability for the financial API
 /// including Prometheus metrics, health checks, and performance tracking.
 pub mod metrics;
+pub mod readiness;
+pub mod request_id;
 
 pub use metrics::{setup_metrics, MetricsHandle, Timer};
+pub use readiness::{
+    DependencyProbe, DependencyStatus, ProbeStatus, ReadinessProbes, ReadinessReport,
+    DEFAULT_PROBE_TIMEOUT,
+};
+pub use request_id::{
+    current_request_id, request_id_middleware, spawn_blocking_in_request, spawn_in_request,
+    RequestId, REQUEST_ID_HEADER,
+};
 
 // Health check response structure
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -0,0 +1,262 @@
+/// Readiness probes for downstream dependencies
+///
+/// Liveness only says the process is serving; readiness says whether it can
+/// do useful work. Every registered dependency is probed concurrently, each
+/// under its own timeout, so one hung dependency cannot stall the report.
+/// The service is ready unless a critical dependency is unhealthy; optional
+/// ones (such as the cache, which requests bypass when it fails) only mark the
+/// report as degraded.
+use crate::auth::AtlasApiClient;
+use crate::cache::{AsyncCache, CacheHealth};
+use async_graphql::async_trait::async_trait;
+use futures::future::join_all;
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Default time a dependency has to answer its probe
+pub const DEFAULT_PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// State of one dependency, or of the service as a whole
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProbeStatus {
+    Healthy,
+    /// Usable, but slow or partially failing
+    Degraded,
+    Unhealthy,
+}
+
+/// A dependency that can report whether it is reachable
+#[async_trait]
+pub trait DependencyProbe: Send + Sync + 'static {
+    /// Check the dependency; unhealthy results carry the reason
+    async fn probe(&self) -> Result<ProbeStatus, String>;
+}
+
+/// Outcome of probing one dependency
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DependencyStatus {
+    pub name: String,
+    pub status: ProbeStatus,
+    pub critical: bool,
+    pub latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Per-dependency results and the overall verdict
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReadinessReport {
+    pub ready: bool,
+    pub status: ProbeStatus,
+    pub dependencies: Vec<DependencyStatus>,
+}
+
+#[derive(Clone)]
+struct RegisteredProbe {
+    name: String,
+    probe: Arc<dyn DependencyProbe>,
+    timeout: Duration,
+    critical: bool,
+}
+
+/// Dependencies checked by the readiness endpoint
+#[derive(Clone, Default)]
+pub struct ReadinessProbes {
+    probes: Arc<Vec<RegisteredProbe>>,
+}
+
+impl ReadinessProbes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Probe `name` on every readiness check; the service is not ready while
+    /// a `critical` dependency is unhealthy
+    pub fn with_probe(
+        mut self,
+        name: &str,
+        probe: impl DependencyProbe,
+        timeout: Duration,
+        critical: bool,
+    ) -> Self {
+        Arc::make_mut(&mut self.probes).push(RegisteredProbe {
+            name: name.to_string(),
+            probe: Arc::new(probe),
+            timeout,
+            critical,
+        });
+        self
+    }
+
+    /// Probe every dependency concurrently
+    pub async fn check(&self) -> ReadinessReport {
+        let dependencies = join_all(self.probes.iter().map(Self::run)).await;
+
+        let ready = !dependencies
+            .iter()
+            .any(|d| d.critical && d.status == ProbeStatus::Unhealthy);
+        let status = if !ready {
+            ProbeStatus::Unhealthy
+        } else if dependencies
+            .iter()
+            .all(|d| d.status == ProbeStatus::Healthy)
+        {
+            ProbeStatus::Healthy
+        } else {
+            ProbeStatus::Degraded
+        };
+
+        ReadinessReport {
+            ready,
+            status,
+            dependencies,
+        }
+    }
+
+    async fn run(registered: &RegisteredProbe) -> DependencyStatus {
+        let started = Instant::now();
+        let (status, error) =
+            match tokio::time::timeout(registered.timeout, registered.probe.probe()).await {
+                Ok(Ok(status)) => (status, None),
+                Ok(Err(reason)) => (ProbeStatus::Unhealthy, Some(reason)),
+                Err(_) => (
+                    ProbeStatus::Unhealthy,
+                    Some(format!(
+                        "No response within {}ms",
+                        registered.timeout.as_millis()
+                    )),
+                ),
+            };
+        DependencyStatus {
+            name: registered.name.clone(),
+            status,
+            critical: registered.critical,
+            latency_ms: started.elapsed().as_millis() as u64,
+            error,
+        }
+    }
+}
+
+#[async_trait]
+impl DependencyProbe for Arc<dyn AsyncCache> {
+    async fn probe(&self) -> Result<ProbeStatus, String> {
+        match self.check_cache_health().await {
+            CacheHealth::Ok => Ok(ProbeStatus::Healthy),
+            CacheHealth::Degraded => Ok(ProbeStatus::Degraded),
+            CacheHealth::Unavailable => Err("Cache is unavailable".to_string()),
+        }
+    }
+}
+
+#[async_trait]
+impl DependencyProbe for AtlasApiClient {
+    async fn probe(&self) -> Result<ProbeStatus, String> {
+        self.check_health()
+            .await
+            .map(|()| ProbeStatus::Healthy)
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FixedProbe(Result<ProbeStatus, String>);
+
+    #[async_trait]
+    impl DependencyProbe for FixedProbe {
+        async fn probe(&self) -> Result<ProbeStatus, String> {
+            self.0.clone()
+        }
+    }
+
+    struct HungProbe;
+
+    #[async_trait]
+    impl DependencyProbe for HungProbe {
+        async fn probe(&self) -> Result<ProbeStatus, String> {
+            futures::future::pending().await
+        }
+    }
+
+    fn healthy() -> FixedProbe {
+        FixedProbe(Ok(ProbeStatus::Healthy))
+    }
+
+    #[tokio::test]
+    async fn test_all_healthy_dependencies_are_ready() {
+        let report = ReadinessProbes::new()
+            .with_probe("database", healthy(), DEFAULT_PROBE_TIMEOUT, true)
+            .with_probe("atlas_auth", healthy(), DEFAULT_PROBE_TIMEOUT, true)
+            .with_probe("redis", healthy(), DEFAULT_PROBE_TIMEOUT, false)
+            .check()
+            .await;
+
+        assert!(report.ready);
+        assert_eq!(report.status, ProbeStatus::Healthy);
+        let names: Vec<_> = report
+            .dependencies
+            .iter()
+            .map(|d| d.name.as_str())
+            .collect();
+        assert_eq!(names, ["database", "atlas_auth", "redis"]);
+        assert!(report.dependencies.iter().all(|d| d.error.is_none()));
+    }
+
+    #[tokio::test]
+    async fn test_one_degraded_dependency_sets_overall_status() {
+        // A slow, optional cache leaves the service ready but degraded
+        let report = ReadinessProbes::new()
+            .with_probe("atlas_auth", healthy(), DEFAULT_PROBE_TIMEOUT, true)
+            .with_probe(
+                "redis",
+                FixedProbe(Ok(ProbeStatus::Degraded)),
+                DEFAULT_PROBE_TIMEOUT,
+                false,
+            )
+            .check()
+            .await;
+        assert!(report.ready);
+        assert_eq!(report.status, ProbeStatus::Degraded);
+
+        // An optional dependency that is down does not block readiness
+        let report = ReadinessProbes::new()
+            .with_probe("atlas_auth", healthy(), DEFAULT_PROBE_TIMEOUT, true)
+            .with_probe(
+                "redis",
+                FixedProbe(Err("connection refused".to_string())),
+                DEFAULT_PROBE_TIMEOUT,
+                false,
+            )
+            .check()
+            .await;
+        assert!(report.ready);
+        assert_eq!(report.status, ProbeStatus::Degraded);
+        assert_eq!(
+            report.dependencies[1].error.as_deref(),
+            Some("connection refused")
+        );
+
+        // A critical dependency that hangs is cut off by its own timeout
+        let started = Instant::now();
+        let report = ReadinessProbes::new()
+            .with_probe("atlas_auth", HungProbe, Duration::from_millis(50), true)
+            .with_probe("redis", healthy(), DEFAULT_PROBE_TIMEOUT, false)
+            .check()
+            .await;
+        assert!(started.elapsed() < DEFAULT_PROBE_TIMEOUT);
+        assert!(!report.ready);
+        assert_eq!(report.status, ProbeStatus::Unhealthy);
+        assert_eq!(report.dependencies[0].status, ProbeStatus::Unhealthy);
+        assert!(report.dependencies[0]
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("50ms"));
+        assert_eq!(report.dependencies[1].status, ProbeStatus::Healthy);
+    }
+}
@@ -0,0 +1,226 @@
+/// Request correlation IDs
+///
+/// Every request carries an `X-Request-Id`: the caller's own when it sends a
+/// usable one, otherwise a fresh UUID. The ID is recorded on the request's
+/// tracing span, stored in the request extensions, echoed on the response, and
+/// available to error responses and downstream calls made while the request is
+/// handled through [`current_request_id`]. Work moved onto other tasks keeps
+/// the ID only when spawned with [`spawn_in_request`] or
+/// [`spawn_blocking_in_request`].
+use axum::{extract::Request, http::HeaderValue, middleware::Next, response::Response};
+use std::future::Future;
+use tokio::task::JoinHandle;
+use tracing::Instrument;
+use uuid::Uuid;
+
+/// Header carrying the correlation ID on requests, responses and outbound calls
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Longest caller-supplied ID that is accepted as-is
+pub const MAX_REQUEST_ID_LEN: usize = 128;
+
+tokio::task_local! {
+    static CURRENT_REQUEST_ID: RequestId;
+}
+
+/// Correlation ID of a request, available from the request extensions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+impl RequestId {
+    /// The caller's ID if it is short and plain enough to log and forward,
+    /// otherwise a new one
+    pub fn from_header(value: Option<&HeaderValue>) -> Self {
+        value
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|id| Self::is_acceptable(id))
+            .map(|id| Self(id.to_string()))
+            .unwrap_or_else(Self::generate)
+    }
+
+    pub fn generate() -> Self {
+        Self(Uuid::new_v4().to_string())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    fn is_acceptable(id: &str) -> bool {
+        !id.is_empty()
+            && id.len() <= MAX_REQUEST_ID_LEN
+            && id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))
+    }
+}
+
+/// Correlation ID of the request being handled on this task, if any
+pub fn current_request_id() -> Option<String> {
+    CURRENT_REQUEST_ID.try_with(|id| id.0.clone()).ok()
+}
+
+/// Spawn `future` on its own task with the current request's ID and span
+///
+/// Task-locals stay on the task that set them, so `tokio::spawn` alone would
+/// leave the spawned work logging without a correlation ID.
+pub fn spawn_in_request<F>(future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    let request_id = CURRENT_REQUEST_ID.try_with(Clone::clone).ok();
+    let future = future.in_current_span();
+    tokio::spawn(async move {
+        match request_id {
+            Some(request_id) => CURRENT_REQUEST_ID.scope(request_id, future).await,
+            None => future.await,
+        }
+    })
+}
+
+/// Run `work` on the blocking pool with the current request's ID and span
+pub fn spawn_blocking_in_request<F, R>(work: F) -> JoinHandle<R>
+where
+    F: FnOnce() -> R + Send + 'static,
+    R: Send + 'static,
+{
+    let request_id = CURRENT_REQUEST_ID.try_with(Clone::clone).ok();
+    let span = tracing::Span::current();
+    tokio::task::spawn_blocking(move || {
+        let _entered = span.enter();
+        match request_id {
+            Some(request_id) => CURRENT_REQUEST_ID.sync_scope(request_id, work),
+            None => work(),
+        }
+    })
+}
+
+/// Assign a correlation ID to the request and echo it on the response
+///
+/// Should be the outermost layer so the trace layer's span, and every log line
+/// written while the request is handled, sits under the `request_id` span.
+pub async fn request_id_middleware(mut request: Request, next: Next) -> Response {
+    let request_id = RequestId::from_header(request.headers().get(REQUEST_ID_HEADER));
+    request.extensions_mut().insert(request_id.clone());
+
+    let span = tracing::info_span!("request", request_id = %request_id.as_str());
+    let mut response = CURRENT_REQUEST_ID
+        .scope(request_id.clone(), next.run(request).instrument(span))
+        .await;
+
+    if let Ok(value) = HeaderValue::from_str(request_id.as_str()) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{ApiError, ErrorResponse};
+    use axum::{
+        body::{to_bytes, Body},
+        http::StatusCode,
+        routing::get,
+        Extension, Router,
+    };
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        Router::new()
+            .route(
+                "/echo",
+                get(|Extension(id): Extension<RequestId>| async move {
+                    assert_eq!(current_request_id().as_deref(), Some(id.as_str()));
+                    id.0
+                }),
+            )
+            .route(
+                "/fail",
+                get(|| async { Err::<(), _>(ApiError::internal_error("boom")) }),
+            )
+            .layer(axum::middleware::from_fn(request_id_middleware))
+    }
+
+    async fn get_with_id(uri: &str, request_id: Option<&str>) -> Response {
+        let mut request = Request::builder().uri(uri);
+        if let Some(id) = request_id {
+            request = request.header(REQUEST_ID_HEADER, id);
+        }
+        app()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    fn echoed(response: &Response) -> String {
+        response.headers()[REQUEST_ID_HEADER]
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_provided_request_id_is_echoed() {
+        let response = get_with_id("/echo", Some("req-7f3a.checkout:42")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(echoed(&response), "req-7f3a.checkout:42");
+
+        // Handlers see the same ID in their extensions
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"req-7f3a.checkout:42");
+
+        // Errors carry the ID in their body as well as the header
+        let response = get_with_id("/fail", Some("req-failing")).await;
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(echoed(&response), "req-failing");
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let error: ErrorResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(error.request_id.as_deref(), Some("req-failing"));
+    }
+
+    #[tokio::test]
+    async fn test_missing_or_unusable_request_id_is_replaced() {
+        let generated = echoed(&get_with_id("/echo", None).await);
+        assert!(Uuid::parse_str(&generated).is_ok());
+
+        let too_long = "a".repeat(MAX_REQUEST_ID_LEN + 1);
+        for unusable in ["", "has spaces", "line\tbreak", too_long.as_str()] {
+            let replaced = echoed(&get_with_id("/echo", Some(unusable)).await);
+            assert_ne!(replaced, unusable);
+            assert!(Uuid::parse_str(&replaced).is_ok());
+        }
+
+        // Nothing is set outside a request
+        assert!(current_request_id().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_spawned_work_keeps_the_request_id() {
+        let request_id = RequestId("req-background".to_string());
+        let (spawned, blocking, plain) = CURRENT_REQUEST_ID
+            .scope(request_id, async {
+                (
+                    spawn_in_request(async { current_request_id() })
+                        .await
+                        .unwrap(),
+                    spawn_blocking_in_request(current_request_id).await.unwrap(),
+                    tokio::spawn(async { current_request_id() }).await.unwrap(),
+                )
+            })
+            .await;
+        assert_eq!(spawned.as_deref(), Some("req-background"));
+        assert_eq!(blocking.as_deref(), Some("req-background"));
+        assert_eq!(plain, None);
+
+        // Outside a request the work simply runs without an ID
+        assert_eq!(
+            spawn_in_request(async { current_request_id() })
+                .await
+                .unwrap(),
+            None
+        );
+    }
+}
@@ -2,26 +2,57 @@
 ///
 /// Contains business logic and service implementations
 use axum::{
+    http::{HeaderName, HeaderValue, Method},
     routing::{get, post},
     Router,
 };
 use std::sync::Arc;
+use tower_http::compression::predicate::{And, NotForContentType, Predicate, SizeAbove};
+use tower_http::compression::CompressionLayer;
+use tower_http::cors::{AllowOrigin, CorsLayer};
+use tower_http::limit::RequestBodyLimitLayer;
+use tracing::warn;
 
-use crate::graphql::resolvers::{create_schema, ApiSchema};
+use crate::config::{CorsConfig, PerformanceConfig};
+use crate::graphql::resolvers::{create_schema, create_schema_with_metrics, ApiSchema};
 use crate::handlers::{
     calculate, financial_health, health_check, readiness_check, validate_precision,
 };
+use crate::monitoring::metrics::CalculationMetrics;
+use crate::monitoring::readiness::ReadinessProbes;
 
 /// Financial API service
 pub struct FinancialService {
     schema: Arc<ApiSchema>,
+    readiness: ReadinessProbes,
 }
 
 impl FinancialService {
     /// Create a new financial service instance
     pub fn new() -> Self {
         let schema = Arc::new(create_schema());
-        Self { schema }
+        Self {
+            schema,
+            readiness: ReadinessProbes::new(),
+        }
+    }
+
+    /// Create a service whose GraphQL resolvers record calculation metrics
+    pub fn with_metrics(calculations: CalculationMetrics) -> Self {
+        let schema = Arc::new(create_schema_with_metrics(calculations));
+        Self {
+            schema,
+            readiness: ReadinessProbes::new(),
+        }
+    }
+
+    /// Dependencies the readiness endpoint probes
+    ///
+    /// Until probes are registered `/ready` has nothing to check and always
+    /// reports ready; the server binary registers the cache and Atlas auth.
+    pub fn with_readiness_probes(mut self, readiness: ReadinessProbes) -> Self {
+        self.readiness = readiness;
+        self
     }
 
     /// Create the Axum router with all routes
@@ -29,7 +60,11 @@ impl FinancialService {
         Router::new()
             // Health checks
             .route("/health", get(health_check))
-            .route("/ready", get(readiness_check))
+            .route("/live", get(health_check))
+            .route(
+                "/ready",
+                get(readiness_check).with_state(self.readiness.clone()),
+            )
             // Financial calculation API endpoints
             .route("/api/v1/calculate", post(calculate))
             .route("/api/v1/validate", post(validate_precision))
@@ -45,3 +80,310 @@ impl Default for FinancialService {
         Self::new()
     }
 }
+
+/// Build the CORS layer from configuration
+///
+/// Origins outside the allowlist receive no `Access-Control-Allow-*` headers,
+/// so an empty allowlist denies every cross-origin request.
+pub fn cors_layer(cors: &CorsConfig) -> CorsLayer {
+    let allow_origin = if cors.allows_any_origin() {
+        AllowOrigin::any()
+    } else {
+        AllowOrigin::list(cors.allowed_origins.iter().filter_map(|origin| {
+            HeaderValue::from_str(origin)
+                .map_err(|_| warn!("Ignoring invalid CORS origin: {}", origin))
+                .ok()
+        }))
+    };
+
+    let methods: Vec<Method> = cors
+        .allowed_methods
+        .iter()
+        .filter_map(|method| Method::from_bytes(method.to_uppercase().as_bytes()).ok())
+        .collect();
+
+    let headers: Vec<HeaderName> = cors
+        .allowed_headers
+        .iter()
+        .filter_map(|header| HeaderName::from_bytes(header.as_bytes()).ok())
+        .collect();
+
+    CorsLayer::new()
+        .allow_origin(allow_origin)
+        .allow_methods(methods)
+        .allow_headers(headers)
+        // Credentials are never combined with a wildcard origin
+        .allow_credentials(cors.allow_credentials && !cors.allows_any_origin())
+}
+
+/// Build the request body size limit from configuration
+///
+/// Bodies over `max_request_size` bytes are refused with 413 Payload Too
+/// Large before they are buffered, whether or not they declare a length.
+pub fn request_body_limit_layer(performance: &PerformanceConfig) -> RequestBodyLimitLayer {
+    let limit = usize::try_from(performance.max_request_size).unwrap_or(usize::MAX);
+    RequestBodyLimitLayer::new(limit)
+}
+
+/// Responses worth compressing: large enough, and not gRPC, images or
+/// event streams
+pub type CompressionPredicate =
+    And<And<And<SizeAbove, NotForContentType>, NotForContentType>, NotForContentType>;
+
+/// Build the response compression layer from configuration
+///
+/// gzip or brotli is chosen from the request's `Accept-Encoding`; responses
+/// under `compression_min_size` bytes are sent as-is.
+pub fn compression_layer(
+    performance: &PerformanceConfig,
+) -> CompressionLayer<CompressionPredicate> {
+    let predicate = SizeAbove::new(performance.compression_min_size)
+        .and(NotForContentType::GRPC)
+        .and(NotForContentType::IMAGES)
+        .and(NotForContentType::SSE);
+    CompressionLayer::new()
+        .gzip(true)
+        .br(true)
+        .compress_when(predicate)
+}
+
+/// Compress responses from the routes registered so far, if enabled
+///
+/// Routes added afterwards are left uncompressed; the metrics endpoint is
+/// registered after this so Prometheus scrapes always get plain text.
+pub fn compress_responses<S>(router: Router<S>, performance: &PerformanceConfig) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    if performance.enable_compression {
+        router.layer(compression_layer(performance))
+    } else {
+        router
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::Body,
+        http::{header, Request, StatusCode},
+    };
+    use tower::ServiceExt;
+
+    fn cors_config(origins: &[&str]) -> CorsConfig {
+        CorsConfig {
+            allowed_origins: origins.iter().map(|o| o.to_string()).collect(),
+            allowed_methods: vec!["GET".to_string(), "POST".to_string()],
+            allowed_headers: vec!["content-type".to_string(), "authorization".to_string()],
+            allow_credentials: true,
+        }
+    }
+
+    async fn allow_origin_header(cors: &CorsConfig, origin: &str) -> Option<HeaderValue> {
+        let app = Router::new()
+            .route("/health", get(|| async { "ok" }))
+            .layer(cors_layer(cors));
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/health")
+                    .header(header::ORIGIN, origin)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        response
+            .headers()
+            .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+            .cloned()
+    }
+
+    async fn post_body_status(limit: u64, body: Vec<u8>, declare_length: bool) -> StatusCode {
+        let mut performance = crate::config::Config::test_config().performance;
+        performance.max_request_size = limit;
+        let app = Router::new()
+            .route("/graphql", post(|body: String| async move { body.len().to_string() }))
+            .layer(request_body_limit_layer(&performance));
+
+        let mut request = Request::builder().method("POST").uri("/graphql");
+        if declare_length {
+            request = request.header(header::CONTENT_LENGTH, body.len());
+        }
+        let response = app
+            .oneshot(request.body(Body::from(body)).unwrap())
+            .await
+            .unwrap();
+        response.status()
+    }
+
+    async fn get_with_encoding(
+        app: &Router,
+        uri: &str,
+        encoding: &str,
+    ) -> axum::response::Response {
+        app.clone()
+            .oneshot(
+                Request::builder()
+                    .uri(uri)
+                    .header(header::ACCEPT_ENCODING, encoding)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_large_responses_are_compressed_except_metrics() {
+        let large = "type Query { portfolio: Portfolio }\n".repeat(200);
+        let mut performance = crate::config::Config::test_config().performance;
+        performance.enable_compression = true;
+
+        let routes = {
+            let large = large.clone();
+            Router::new()
+                .route("/schema", get(move || async move { large }))
+                .route("/health", get(|| async { "ok" }))
+        };
+        let app = compress_responses(routes, &performance).route(
+            "/metrics",
+            get(|| async { "# TYPE requests_total counter\n".repeat(200) }),
+        );
+
+        let response = get_with_encoding(&app, "/schema", "gzip").await;
+        assert_eq!(response.headers()[header::CONTENT_ENCODING], "gzip");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..2], &[0x1f, 0x8b]);
+        assert!(body.len() < large.len());
+
+        let response = get_with_encoding(&app, "/schema", "br").await;
+        assert_eq!(response.headers()[header::CONTENT_ENCODING], "br");
+
+        // Small responses and the metrics endpoint are sent as-is
+        for uri in ["/health", "/metrics"] {
+            let response = get_with_encoding(&app, uri, "gzip, br").await;
+            assert!(response.headers().get(header::CONTENT_ENCODING).is_none());
+        }
+
+        // Clients that do not ask for compression get plain text
+        let response = get_with_encoding(&app, "/schema", "identity").await;
+        assert!(response.headers().get(header::CONTENT_ENCODING).is_none());
+
+        performance.enable_compression = false;
+        let app = compress_responses(
+            Router::new().route("/schema", get(move || async move { large })),
+            &performance,
+        );
+        let response = get_with_encoding(&app, "/schema", "gzip").await;
+        assert!(response.headers().get(header::CONTENT_ENCODING).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_over_limit_body_is_rejected_with_413() {
+        let query = br#"{"query":"{ health }"}"#.to_vec();
+        assert_eq!(post_body_status(1024, query, true).await, StatusCode::OK);
+
+        let huge = vec![b'a'; 1025];
+        assert_eq!(
+            post_body_status(1024, huge.clone(), true).await,
+            StatusCode::PAYLOAD_TOO_LARGE
+        );
+        // Bodies without a Content-Length are cut off while being read
+        assert_eq!(
+            post_body_status(1024, huge, false).await,
+            StatusCode::PAYLOAD_TOO_LARGE
+        );
+    }
+
+    #[tokio::test]
+    async fn test_allowed_origin_is_reflected() {
+        let cors = cors_config(&["https://app.atlas-financial.com"]);
+        let header = allow_origin_header(&cors, "https://app.atlas-financial.com").await;
+        assert_eq!(
+            header,
+            Some(HeaderValue::from_static("https://app.atlas-financial.com"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_disallowed_origin_is_not_reflected() {
+        let cors = cors_config(&["https://app.atlas-financial.com"]);
+        assert!(allow_origin_header(&cors, "https://evil.example.com")
+            .await
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_empty_allowlist_denies_all_origins() {
+        let cors = cors_config(&[]);
+        assert!(allow_origin_header(&cors, "https://app.atlas-financial.com")
+            .await
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_wildcard_allows_any_origin_without_credentials() {
+        let cors = cors_config(&["*"]);
+        let header = allow_origin_header(&cors, "http://localhost:3000").await;
+        assert_eq!(header, Some(HeaderValue::from_static("*")));
+    }
+
+    async fn get_status(app: &Router, uri: &str) -> (StatusCode, serde_json::Value) {
+        let response = app
+            .clone()
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_readiness_reflects_atlas_auth_while_liveness_does_not() {
+        use crate::auth::AtlasApiClient;
+        use crate::monitoring::readiness::DEFAULT_PROBE_TIMEOUT;
+
+        let mut server = mockito::Server::new_async().await;
+        let atlas = AtlasApiClient::new(server.url(), "test-key".to_string()).unwrap();
+        let service = FinancialService::new().with_readiness_probes(
+            ReadinessProbes::new().with_probe("atlas_auth", atlas, DEFAULT_PROBE_TIMEOUT, true),
+        );
+        let app = service.router();
+
+        let up = server
+            .mock("GET", "/health")
+            .with_status(200)
+            .create_async()
+            .await;
+        let (status, report) = get_status(&app, "/ready").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(report["ready"], true);
+        assert_eq!(report["status"], "healthy");
+        assert_eq!(report["dependencies"][0]["name"], "atlas_auth");
+        up.remove_async().await;
+
+        server
+            .mock("GET", "/health")
+            .with_status(503)
+            .create_async()
+            .await;
+        let (status, report) = get_status(&app, "/ready").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(report["ready"], false);
+        assert_eq!(report["dependencies"][0]["status"], "unhealthy");
+
+        let (status, liveness) = get_status(&app, "/live").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(liveness["status"], "healthy");
+    }
+}
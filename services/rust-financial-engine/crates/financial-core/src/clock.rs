@@ -0,0 +1,82 @@
+/// Source of the current time for time-dependent calculations
+///
+/// Simulations start from "now" and sessions expire against it. Taking the
+/// time from a [`Clock`] instead of calling `Utc::now()` directly lets tests
+/// pin it with a [`MockClock`] and assert exact dates.
+use chrono::{DateTime, Duration, Utc};
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+
+/// Current time provider
+pub trait Clock: Debug + Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// Clock reading the system time
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Clock that only moves when told to
+///
+/// Clones share the same time, so a test can keep one handle and advance the
+/// clock it gave to the code under test.
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    now: Arc<Mutex<DateTime<Utc>>>,
+}
+
+impl MockClock {
+    /// Clock fixed at `now`
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            now: Arc::new(Mutex::new(now)),
+        }
+    }
+
+    /// Move the clock to `now`
+    pub fn set(&self, now: DateTime<Utc>) {
+        *self.now.lock().unwrap() = now;
+    }
+
+    /// Move the clock forward by `duration`
+    pub fn advance(&self, duration: Duration) {
+        *self.now.lock().unwrap() += duration;
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().unwrap()
+    }
+}
+
+/// The system clock, shared
+pub fn system_clock() -> Arc<dyn Clock> {
+    Arc::new(SystemClock)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_mock_clock_moves_only_when_told() {
+        let start = Utc.with_ymd_and_hms(2025, 1, 15, 9, 0, 0).unwrap();
+        let clock = MockClock::new(start);
+        let shared: Arc<dyn Clock> = Arc::new(clock.clone());
+        assert_eq!(shared.now(), start);
+
+        clock.advance(Duration::days(1));
+        assert_eq!(shared.now(), start + Duration::days(1));
+
+        clock.set(start);
+        assert_eq!(shared.now(), start);
+    }
+}
@@ -1,5 +1,9 @@
-use crate::debt::types::{DebtAccount, DebtStrategy, PaymentPlan, PaymentScheduleItem};
-use crate::{FinancialError, Money, Result};
+use crate::debt::cascade::CascadeSimulation;
+use crate::debt::types::{
+    avalanche_order, ensure_amortizes, rate_changes_for, sort_custom_order, sort_hybrid_kickstart,
+    DebtAccount, DebtStrategy, MinimumPaymentFloor, PaymentPlan, PaymentScheduleItem, RateChangeEvent,
+};
+use crate::{system_clock, CalcContext, Clock, FinancialError, Money, Result};
 use chrono::{DateTime, Duration, Utc};
 /// Debt Avalanche Strategy Implementation
 ///
@@ -7,11 +11,16 @@ use chrono::{DateTime, Duration, Utc};
 /// minimizing total interest paid over time. This is mathematically optimal.
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
+use std::sync::Arc;
 
 /// Debt Avalanche calculator for payment optimization
 pub struct AvalancheCalculator {
     extra_payment_budget: Money,
     payment_frequency: PaymentFrequency,
+    rate_changes: Vec<RateChangeEvent>,
+    minimum_payment_floor: MinimumPaymentFloor,
+    context: CalcContext,
+    clock: Arc<dyn Clock>,
 }
 
 /// Payment frequency options
@@ -28,6 +37,10 @@ impl AvalancheCalculator {
         Self {
             extra_payment_budget,
             payment_frequency: PaymentFrequency::Monthly,
+            rate_changes: Vec::new(),
+            minimum_payment_floor: MinimumPaymentFloor::default(),
+            context: CalcContext::default(),
+            clock: system_clock(),
         }
     }
 
@@ -37,8 +50,74 @@ impl AvalancheCalculator {
         self
     }
 
+    /// Apply interest rate changes when their effective date is reached
+    pub fn with_rate_changes(mut self, rate_changes: Vec<RateChangeEvent>) -> Self {
+        self.rate_changes = rate_changes;
+        self
+    }
+
+    /// Raise each period's payment to a floor instead of rejecting debts whose
+    /// minimum payment does not cover interest
+    pub fn with_minimum_payment_floor(mut self, floor: MinimumPaymentFloor) -> Self {
+        self.minimum_payment_floor = floor;
+        self
+    }
+
+    /// Round interest charges and payments to the user's precision
+    pub fn with_context(mut self, context: CalcContext) -> Self {
+        self.context = context;
+        self
+    }
+
+    /// Start payment schedules from `clock`'s current time
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
     /// Calculate optimal avalanche payment plan for multiple debts
     pub fn calculate_payment_plan(&self, debts: &[DebtAccount]) -> Result<Vec<PaymentPlan>> {
+        self.run_cascade(debts, DebtStrategy::Avalanche, |sorted| {
+            sorted.sort_by(avalanche_order)
+        })
+    }
+
+    /// Calculate an avalanche plan that clears the smallest debt first
+    ///
+    /// The extra budget goes to the debt with the smallest balance until it
+    /// is paid off, for an early win, and then switches to the highest
+    /// interest rate like a plain avalanche.
+    pub fn calculate_kickstart_payment_plan(
+        &self,
+        debts: &[DebtAccount],
+    ) -> Result<Vec<PaymentPlan>> {
+        self.run_cascade(debts, DebtStrategy::HybridKickstart, sort_hybrid_kickstart)
+    }
+
+    /// Calculate a plan that pays debts off in a user-chosen order
+    ///
+    /// The extra budget, and the minimums of debts already cleared, cascade
+    /// into the debts in `payment_order` exactly as listed, whatever their
+    /// balances or rates. Debts left out of `payment_order` come after, in
+    /// avalanche order.
+    pub fn calculate_custom_payment_plan(
+        &self,
+        debts: &[DebtAccount],
+        payment_order: &[uuid::Uuid],
+    ) -> Result<Vec<PaymentPlan>> {
+        let mut ordered = debts.to_vec();
+        sort_custom_order(&mut ordered, payment_order)?;
+        // Already in priority order, so the cascade keeps it as-is
+        self.run_cascade(&ordered, DebtStrategy::Custom, |_| {})
+    }
+
+    /// Run the shared payoff cascade over `debts` in the order `prioritize` sorts them
+    fn run_cascade(
+        &self,
+        debts: &[DebtAccount],
+        strategy: DebtStrategy,
+        prioritize: impl FnOnce(&mut [DebtAccount]),
+    ) -> Result<Vec<PaymentPlan>> {
         if debts.is_empty() {
             return Ok(Vec::new());
         }
@@ -56,42 +135,20 @@ impl AvalancheCalculator {
             });
         }
 
-        // Sort debts by interest rate (avalanche method - highest first)
         let mut sorted_debts = debts.to_vec();
-        sorted_debts.sort_by(|a, b| {
-            b.interest_rate
-                .as_decimal()
-                .partial_cmp(&a.interest_rate.as_decimal())
-                .unwrap_or(std::cmp::Ordering::Equal)
-        });
-
-        let mut payment_plans: Vec<PaymentPlan> = Vec::new();
-        let remaining_extra_budget = self.extra_payment_budget.clone();
-
-        for (index, debt) in sorted_debts.iter().enumerate() {
-            let extra_payment = if index == 0 {
-                // First debt (highest interest) gets all extra payment
-                remaining_extra_budget.clone()
-            } else {
-                // Later debts get rolled-over payments from previously paid-off debts
-                let mut total_extra = remaining_extra_budget.clone();
-                for prev_index in 0..index {
-                    if let Some(prev_plan) = payment_plans.get(prev_index) {
-                        total_extra = total_extra.add(
-                            &prev_plan
-                                .monthly_payment
-                                .subtract(&sorted_debts[prev_index].minimum_payment)?,
-                        )?;
-                    }
-                }
-                total_extra
-            };
-
-            let payment_plan = self.calculate_single_debt_plan(debt, &extra_payment)?;
-            payment_plans.push(payment_plan);
+        prioritize(&mut sorted_debts);
+
+        CascadeSimulation {
+            strategy,
+            extra_payment_budget: &self.extra_payment_budget,
+            rate_changes: &self.rate_changes,
+            minimum_payment_floor: self.minimum_payment_floor,
+            context: self.context,
+            clock: self.clock.as_ref(),
+            period_rate: |rate: &crate::types::Rate| self.calculate_monthly_rate(rate),
+            next_payment_date: |date| self.next_payment_date(date),
         }
-
-        Ok(payment_plans)
+        .run(&sorted_debts)
     }
 
     /// Calculate payment plan for a single debt
@@ -105,15 +162,28 @@ impl AvalancheCalculator {
         let mut payment_schedule = Vec::new();
         let mut total_interest = Money::new_unchecked(Decimal::ZERO, debt.balance.currency());
         let mut payment_number = 1;
-        let mut current_date = Utc::now();
+        let mut current_date = self.clock.now();
 
         // Calculate monthly interest rate
-        let monthly_rate = self.calculate_monthly_rate(&debt.interest_rate)?;
+        let mut monthly_rate = self.calculate_monthly_rate(&debt.interest_rate)?;
+        let mut pending_rate_changes = rate_changes_for(&self.rate_changes, debt.id).into_iter().peekable();
 
         while remaining_balance.amount() > dec!(0.01) && payment_number <= 600 {
             // Max 50 years
-            let interest_charge = remaining_balance.multiply(monthly_rate)?;
-            let principal_payment = total_monthly_payment.subtract(&interest_charge)?;
+            while let Some(change) =
+                pending_rate_changes.next_if(|c| c.effective_date <= current_date)
+            {
+                monthly_rate = self.calculate_monthly_rate(&change.new_rate)?;
+            }
+
+            let interest_charge = self.context.round_money(remaining_balance.multiply(monthly_rate)?);
+            let period_payment = self.context.round_money(self.minimum_payment_floor.apply(
+                total_monthly_payment,
+                interest_charge,
+                remaining_balance,
+            )?);
+            ensure_amortizes(debt, &period_payment, &interest_charge)?;
+            let principal_payment = period_payment.subtract(&interest_charge)?;
 
             // Ensure we don't overpay
             let actual_principal = if principal_payment.amount() > remaining_balance.amount() {
@@ -124,6 +194,11 @@ impl AvalancheCalculator {
 
             let actual_payment = interest_charge.add(&actual_principal)?;
             remaining_balance = remaining_balance.subtract(&actual_principal)?;
+            debug_assert!(
+                remaining_balance.amount() >= Decimal::ZERO,
+                "rounding drove the remaining balance negative: {}",
+                remaining_balance
+            );
             total_interest = total_interest.add(&interest_charge)?;
 
             payment_schedule.push(PaymentScheduleItem {
@@ -162,7 +237,7 @@ impl AvalancheCalculator {
             total_interest,
             payoff_date,
             payment_schedule,
-            created_at: Utc::now(),
+            created_at: self.clock.now(),
         })
     }
 
@@ -187,13 +262,13 @@ impl AvalancheCalculator {
             .iter()
             .map(|p| p.payoff_date)
             .max()
-            .unwrap_or(Utc::now());
+            .unwrap_or_else(|| self.clock.now());
 
         let minimum_final_date = minimum_plans
             .iter()
             .map(|p| p.payoff_date)
             .max()
-            .unwrap_or(Utc::now());
+            .unwrap_or_else(|| self.clock.now());
 
         let time_savings_days = (minimum_final_date - avalanche_final_date).num_days();
         let time_savings_months = time_savings_days / 30;
@@ -211,12 +286,7 @@ impl AvalancheCalculator {
     /// Get debt prioritization order for avalanche method
     pub fn get_priority_order(&self, debts: &[DebtAccount]) -> Vec<(usize, String, Decimal)> {
         let mut indexed_debts: Vec<(usize, &DebtAccount)> = debts.iter().enumerate().collect();
-        indexed_debts.sort_by(|a, b| {
-            b.1.interest_rate
-                .as_decimal()
-                .partial_cmp(&a.1.interest_rate.as_decimal())
-                .unwrap_or(std::cmp::Ordering::Equal)
-        });
+        indexed_debts.sort_by(|a, b| avalanche_order(a.1, b.1));
 
         indexed_debts
             .into_iter()
@@ -260,11 +330,11 @@ impl AvalancheCalculator {
             });
         }
 
-        // Sort by avalanche priority (highest interest first)
+        // Sort by avalanche priority (highest interest first, ties by id)
         metrics.sort_by(|a, b| {
             b.avalanche_priority
-                .partial_cmp(&a.avalanche_priority)
-                .unwrap_or(std::cmp::Ordering::Equal)
+                .cmp(&a.avalanche_priority)
+                .then_with(|| a.debt_id.cmp(&b.debt_id))
         });
 
         Ok(metrics)
@@ -276,7 +346,9 @@ impl AvalancheCalculator {
 
         // Calculate snowball for comparison
         let snowball_calculator =
-            crate::debt::snowball::SnowballCalculator::new(self.extra_payment_budget.clone());
+            crate::debt::snowball::SnowballCalculator::new(self.extra_payment_budget)
+                .with_context(self.context)
+                .with_clock(self.clock.clone());
         let snowball_plans = snowball_calculator.calculate_payment_plan(debts)?;
 
         let avalanche_total_interest: Money = avalanche_plans.iter().try_fold(
@@ -295,13 +367,13 @@ impl AvalancheCalculator {
             .iter()
             .map(|p| p.payoff_date)
             .max()
-            .unwrap_or(Utc::now());
+            .unwrap_or_else(|| self.clock.now());
 
         let snowball_final_date = snowball_plans
             .iter()
             .map(|p| p.payoff_date)
             .max()
-            .unwrap_or(Utc::now());
+            .unwrap_or_else(|| self.clock.now());
 
         let time_difference_days = (snowball_final_date - avalanche_final_date).num_days();
 
@@ -551,6 +623,56 @@ mod tests {
         assert!(metrics[0].interest_rate > metrics[1].interest_rate);
     }
 
+    #[test]
+    fn test_rate_increase_midway_raises_interest() {
+        let extra_payment = Money::new(dec!(50), Currency::USD).unwrap();
+        let mut debt = DebtAccount::new(
+            Uuid::new_v4(),
+            "Credit Card".to_string(),
+            DebtType::CreditCard,
+            Money::new(dec!(3000), Currency::USD).unwrap(),
+            Rate::new(
+                Percentage::from_percentage(dec!(15.0)).unwrap(),
+                Period::Annual,
+            ),
+            Money::new(dec!(100), Currency::USD).unwrap(),
+        );
+
+        let baseline = AvalancheCalculator::new(extra_payment)
+            .calculate_single_debt_plan(&debt, &extra_payment)
+            .unwrap();
+
+        // APR jumps to 24.99% halfway through the payoff
+        let halfway = baseline.payment_schedule.len() / 2;
+        let effective_date = baseline.payment_schedule[halfway].payment_date;
+        let new_rate = Rate::new(
+            Percentage::from_percentage(dec!(24.99)).unwrap(),
+            Period::Annual,
+        );
+        let change = debt.update_rate(new_rate, effective_date).unwrap();
+
+        let updated = AvalancheCalculator::new(extra_payment)
+            .with_rate_changes(vec![change])
+            .calculate_single_debt_plan(&debt, &extra_payment)
+            .unwrap();
+
+        // Payments before the change are unaffected
+        assert_eq!(
+            updated.payment_schedule[halfway - 1].interest,
+            baseline.payment_schedule[halfway - 1].interest
+        );
+
+        // From the effective date interest accrues at the new monthly rate
+        let expected_interest = updated.payment_schedule[halfway - 1]
+            .remaining_balance
+            .multiply(dec!(0.2499) / dec!(12))
+            .unwrap();
+        assert_eq!(updated.payment_schedule[halfway].interest, expected_interest);
+
+        assert!(updated.total_interest.amount() > baseline.total_interest.amount());
+        assert!(updated.payoff_date > baseline.payoff_date);
+    }
+
     #[test]
     fn test_payment_frequencies() {
         let extra_payment = Money::new(dec!(100), Currency::USD).unwrap();
@@ -593,4 +715,48 @@ mod tests {
             .unwrap();
         assert_eq!(score, dec!(50)); // 50% savings
     }
+
+    fn payday_loan(minimum_payment: Decimal) -> DebtAccount {
+        // 120% APR accrues $100 a month on $1,000
+        DebtAccount::new(
+            Uuid::new_v4(),
+            "Payday Loan".to_string(),
+            DebtType::PersonalLoan,
+            Money::new(dec!(1000), Currency::USD).unwrap(),
+            Rate::new(
+                Percentage::from_percentage(dec!(120)).unwrap(),
+                Period::Annual,
+            ),
+            Money::new(minimum_payment, Currency::USD).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_minimum_below_interest_is_negative_amortization() {
+        let calculator = AvalancheCalculator::default();
+
+        let result = calculator.calculate_payment_plan(&[payday_loan(dec!(25))]);
+        assert!(matches!(
+            result,
+            Err(FinancialError::NegativeAmortization { ref debt, .. }) if debt == "Payday Loan"
+        ));
+
+        // Interest-only payments never reduce the balance either
+        let result = calculator.calculate_payment_plan(&[payday_loan(dec!(100))]);
+        assert!(matches!(result, Err(FinancialError::NegativeAmortization { .. })));
+    }
+
+    #[test]
+    fn test_minimum_payment_floor_pays_off_debt() {
+        let calculator = AvalancheCalculator::default()
+            .with_minimum_payment_floor(MinimumPaymentFloor::InterestPlusPrincipal(dec!(0.05)));
+
+        let plans = calculator.calculate_payment_plan(&[payday_loan(dec!(25))]).unwrap();
+        let schedule = &plans[0].payment_schedule;
+
+        // First payment is raised to $100 interest + 5% of $1,000
+        assert_eq!(schedule[0].payment_amount.amount(), dec!(150));
+        assert!(schedule.last().unwrap().remaining_balance.amount() <= dec!(0.01));
+        assert!(schedule.len() < 600);
+    }
 }
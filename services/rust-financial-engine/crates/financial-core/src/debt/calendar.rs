@@ -0,0 +1,408 @@
+/// iCalendar (RFC 5545) export of payment plans
+///
+/// Each payment becomes an all-day event on its due date, with the amount in
+/// the summary. Runs of equal payments at a fixed interval are written as a
+/// single event with an `RRULE`, so a five-year monthly plan is a couple of
+/// events rather than sixty.
+use crate::debt::types::{PaymentPlan, PaymentScheduleItem};
+use chrono::{DateTime, Datelike, Months, NaiveDate, Utc};
+
+/// Product identifier written in every exported calendar
+pub const ICS_PRODUCT_ID: &str = "-//Atlas Financial//Debt Payoff Calendar//EN";
+
+/// Longest content line, in octets, before it is folded
+const MAX_LINE_OCTETS: usize = 75;
+
+/// Spacing between two consecutive payments
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Recurrence {
+    /// Same day of the following calendar month
+    Monthly,
+    Days(i64),
+}
+
+impl Recurrence {
+    /// Every recurrence that steps from `from` to `to`
+    fn candidates(from: NaiveDate, to: NaiveDate) -> Vec<Self> {
+        let days = (to - from).num_days();
+        [Recurrence::Monthly, Recurrence::Days(days)]
+            .into_iter()
+            .filter(|recurrence| recurrence.next(from) == Some(to))
+            .collect()
+    }
+
+    /// Occurrence after `date`; a monthly rule has none in months too short
+    /// for the day, matching how calendars expand it
+    fn next(&self, date: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            Recurrence::Monthly => date
+                .checked_add_months(Months::new(1))
+                .filter(|next| next.day() == date.day()),
+            Recurrence::Days(days) if *days > 0 => Some(date + chrono::Duration::days(*days)),
+            Recurrence::Days(_) => None,
+        }
+    }
+
+    fn rrule(&self, count: usize) -> String {
+        match self {
+            Recurrence::Monthly => format!("FREQ=MONTHLY;COUNT={}", count),
+            Recurrence::Days(7) => format!("FREQ=WEEKLY;COUNT={}", count),
+            Recurrence::Days(days) if days % 7 == 0 => {
+                format!("FREQ=WEEKLY;INTERVAL={};COUNT={}", days / 7, count)
+            }
+            Recurrence::Days(1) => format!("FREQ=DAILY;COUNT={}", count),
+            Recurrence::Days(days) => format!("FREQ=DAILY;INTERVAL={};COUNT={}", days, count),
+        }
+    }
+}
+
+/// Payments that can be written as one event
+struct PaymentRun<'a> {
+    payments: &'a [PaymentScheduleItem],
+    recurrence: Option<Recurrence>,
+}
+
+/// Split a schedule into runs of equal payments at a fixed interval
+fn payment_runs(schedule: &[PaymentScheduleItem]) -> Vec<PaymentRun<'_>> {
+    let mut runs = Vec::new();
+    let mut start = 0;
+
+    while start < schedule.len() {
+        let first = &schedule[start];
+        let run_end = |recurrence: Recurrence| {
+            let mut end = start + 1;
+            while end < schedule.len()
+                && schedule[end].payment_amount == first.payment_amount
+                && recurrence.next(schedule[end - 1].payment_date.date_naive())
+                    == Some(schedule[end].payment_date.date_naive())
+            {
+                end += 1;
+            }
+            end
+        };
+
+        // Dates such as Apr 30 and May 30 fit both a monthly and a 30-day
+        // rule, so keep whichever covers more payments, monthly on a tie
+        let candidates = match schedule.get(start + 1) {
+            Some(next) if next.payment_amount == first.payment_amount => Recurrence::candidates(
+                first.payment_date.date_naive(),
+                next.payment_date.date_naive(),
+            ),
+            _ => Vec::new(),
+        };
+        let (end, recurrence) = candidates
+            .into_iter()
+            .rev()
+            .map(|recurrence| (run_end(recurrence), Some(recurrence)))
+            .max_by_key(|(end, _)| *end)
+            .unwrap_or((start + 1, None));
+
+        runs.push(PaymentRun {
+            payments: &schedule[start..end],
+            recurrence,
+        });
+        start = end;
+    }
+
+    runs
+}
+
+/// Calendar with the payment dates of one plan
+pub fn payment_plan_ics(plan: &PaymentPlan) -> String {
+    payment_plans_ics(std::slice::from_ref(plan))
+}
+
+/// Calendar with the payment dates of every plan, e.g. all debts in a payoff strategy
+pub fn payment_plans_ics(plans: &[PaymentPlan]) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:{}", ICS_PRODUCT_ID),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        "X-WR-CALNAME:Debt payoff plan".to_string(),
+    ];
+
+    for plan in plans {
+        let total = plan.payment_schedule.len();
+        for run in payment_runs(&plan.payment_schedule) {
+            let first = &run.payments[0];
+            let last = &run.payments[run.payments.len() - 1];
+
+            lines.push("BEGIN:VEVENT".to_string());
+            lines.push(format!(
+                "UID:{}-{}@atlas-financial",
+                plan.debt_id, first.payment_number
+            ));
+            lines.push(format!("DTSTAMP:{}", ics_timestamp(plan.created_at)));
+            lines.push(format!(
+                "DTSTART;VALUE=DATE:{}",
+                first.payment_date.format("%Y%m%d")
+            ));
+            if let Some(recurrence) = run.recurrence {
+                lines.push(format!("RRULE:{}", recurrence.rrule(run.payments.len())));
+            }
+            lines.push(format!(
+                "SUMMARY:{}",
+                escape_text(&format!(
+                    "{} payment: {:.2} {}",
+                    plan.debt_name,
+                    first.payment_amount.amount(),
+                    first.payment_amount.currency()
+                ))
+            ));
+            let description = if run.payments.len() > 1 {
+                format!(
+                    "Payments {} to {} of {}",
+                    first.payment_number, last.payment_number, total
+                )
+            } else {
+                format!(
+                    "Payment {} of {}, leaving {:.2} {}",
+                    first.payment_number,
+                    total,
+                    first.remaining_balance.amount(),
+                    first.remaining_balance.currency()
+                )
+            };
+            lines.push(format!("DESCRIPTION:{}", escape_text(&description)));
+            lines.push("TRANSP:TRANSPARENT".to_string());
+            lines.push("END:VEVENT".to_string());
+        }
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+
+    lines
+        .iter()
+        .map(|line| fold_line(line))
+        .collect::<Vec<_>>()
+        .join("\r\n")
+        + "\r\n"
+}
+
+fn ics_timestamp(at: DateTime<Utc>) -> String {
+    at.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escape a TEXT property value
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Fold a content line so no physical line exceeds 75 octets, never
+/// splitting a UTF-8 character
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + line.len() / MAX_LINE_OCTETS * 3);
+    let mut line_octets = 0;
+    for c in line.chars() {
+        if line_octets + c.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            // The leading space counts towards the continuation line
+            line_octets = 1;
+        }
+        folded.push(c);
+        line_octets += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::debt::types::DebtStrategy;
+    use crate::types::{Currency, Money};
+    use chrono::{Duration, TimeZone};
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+    use uuid::Uuid;
+
+    fn usd(amount: Decimal) -> Money {
+        Money::new(amount, Currency::USD).unwrap()
+    }
+
+    /// `amounts` paid on the given dates
+    fn plan(name: &str, payments: &[(DateTime<Utc>, Decimal)]) -> PaymentPlan {
+        let mut balance: Decimal = payments.iter().map(|(_, amount)| *amount).sum();
+        let payment_schedule = payments
+            .iter()
+            .enumerate()
+            .map(|(i, (date, amount))| {
+                balance -= amount;
+                PaymentScheduleItem {
+                    payment_number: i as u32 + 1,
+                    payment_date: *date,
+                    payment_amount: usd(*amount),
+                    principal: usd(*amount),
+                    interest: usd(Decimal::ZERO),
+                    remaining_balance: usd(balance),
+                }
+            })
+            .collect();
+
+        PaymentPlan {
+            debt_id: Uuid::from_u128(7),
+            debt_name: name.to_string(),
+            strategy: DebtStrategy::Avalanche,
+            monthly_payment: usd(payments[0].1),
+            total_payments: usd(payments.iter().map(|(_, amount)| *amount).sum()),
+            total_interest: usd(Decimal::ZERO),
+            payoff_date: payments.last().unwrap().0,
+            payment_schedule,
+            created_at: Utc.with_ymd_and_hms(2024, 1, 1, 9, 30, 0).unwrap(),
+        }
+    }
+
+    /// Unfolded content lines of each VEVENT
+    fn parse_events(ics: &str) -> Vec<Vec<String>> {
+        assert!(ics.ends_with("\r\n"));
+        let physical: Vec<&str> = ics.trim_end_matches("\r\n").split("\r\n").collect();
+        assert!(physical.iter().all(|line| line.len() <= MAX_LINE_OCTETS));
+
+        let mut lines: Vec<String> = Vec::new();
+        for line in physical {
+            match line.strip_prefix(' ') {
+                Some(continuation) => lines.last_mut().unwrap().push_str(continuation),
+                None => lines.push(line.to_string()),
+            }
+        }
+        assert_eq!(lines.first().map(String::as_str), Some("BEGIN:VCALENDAR"));
+        assert_eq!(lines.last().map(String::as_str), Some("END:VCALENDAR"));
+
+        let mut events = Vec::new();
+        let mut current: Option<Vec<String>> = None;
+        for line in lines {
+            match line.as_str() {
+                "BEGIN:VEVENT" => current = Some(Vec::new()),
+                "END:VEVENT" => events.push(current.take().unwrap()),
+                _ => {
+                    if let Some(event) = current.as_mut() {
+                        event.push(line);
+                    }
+                }
+            }
+        }
+        events
+    }
+
+    fn property<'e>(event: &'e [String], name: &str) -> Option<&'e str> {
+        event.iter().find_map(|line| {
+            line.strip_prefix(name)
+                .and_then(|rest| rest.strip_prefix([':', ';']))
+        })
+    }
+
+    #[test]
+    fn test_monthly_payments_compress_into_rrule() {
+        let first = Utc.with_ymd_and_hms(2024, 1, 15, 12, 0, 0).unwrap();
+        let mut payments: Vec<_> = (0..12)
+            .map(|month| {
+                (
+                    first.checked_add_months(Months::new(month)).unwrap(),
+                    dec!(250),
+                )
+            })
+            .collect();
+        payments.push((
+            Utc.with_ymd_and_hms(2025, 1, 15, 12, 0, 0).unwrap(),
+            dec!(83.4),
+        ));
+
+        let events = parse_events(&payment_plan_ics(&plan("Visa", &payments)));
+
+        // Twelve equal payments in one recurring event, then the smaller final payment
+        assert_eq!(events.len(), 2);
+        assert_eq!(property(&events[0], "RRULE"), Some("FREQ=MONTHLY;COUNT=12"));
+        assert_eq!(property(&events[0], "DTSTART"), Some("VALUE=DATE:20240115"));
+        assert_eq!(
+            property(&events[0], "SUMMARY"),
+            Some("Visa payment: 250.00 USD")
+        );
+        assert_eq!(property(&events[1], "RRULE"), None);
+        assert_eq!(property(&events[1], "DTSTART"), Some("VALUE=DATE:20250115"));
+        assert_eq!(
+            property(&events[1], "SUMMARY"),
+            Some("Visa payment: 83.40 USD")
+        );
+        assert_eq!(
+            property(&events[1], "UID"),
+            Some("00000000-0000-0000-0000-000000000007-13@atlas-financial")
+        );
+
+        // Two payments a calendar month apart read as monthly, not every 31 days
+        let pair = [
+            (first, dec!(90)),
+            (first.checked_add_months(Months::new(1)).unwrap(), dec!(90)),
+        ];
+        let events = parse_events(&payment_plan_ics(&plan("Visa", &pair)));
+        assert_eq!(property(&events[0], "RRULE"), Some("FREQ=MONTHLY;COUNT=2"));
+    }
+
+    #[test]
+    fn test_fixed_interval_schedules_and_irregular_dates() {
+        // The payoff calculators space "monthly" payments 30 days apart
+        let first = Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap();
+        let every_30_days: Vec<_> = (0..6)
+            .map(|i| (first + Duration::days(30 * i), dec!(100)))
+            .collect();
+        let events = parse_events(&payment_plan_ics(&plan("Car loan", &every_30_days)));
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            property(&events[0], "RRULE"),
+            Some("FREQ=DAILY;INTERVAL=30;COUNT=6")
+        );
+
+        let biweekly: Vec<_> = (0..4)
+            .map(|i| (first + Duration::days(14 * i), dec!(40)))
+            .collect();
+        let events = parse_events(&payment_plan_ics(&plan("Card", &biweekly)));
+        assert_eq!(
+            property(&events[0], "RRULE"),
+            Some("FREQ=WEEKLY;INTERVAL=2;COUNT=4")
+        );
+
+        // Dates that no rule reproduces are written one event each
+        let irregular = [
+            (first, dec!(50)),
+            (first + Duration::days(10), dec!(50)),
+            (first + Duration::days(40), dec!(50)),
+        ];
+        let ics = payment_plans_ics(&[
+            plan("Medical, \"urgent\"; dental", &irregular),
+            plan("Car loan", &every_30_days),
+        ]);
+        let events = parse_events(&ics);
+        assert_eq!(events.len(), 3);
+        assert_eq!(
+            property(&events[0], "RRULE"),
+            Some("FREQ=DAILY;INTERVAL=10;COUNT=2")
+        );
+        assert_eq!(property(&events[1], "RRULE"), None);
+        assert_eq!(
+            property(&events[0], "SUMMARY"),
+            Some("Medical\\, \"urgent\"\\; dental payment: 50.00 USD")
+        );
+    }
+
+    #[test]
+    fn test_long_lines_are_folded() {
+        let name = "Ünïcödé ".repeat(12);
+        let ics = payment_plan_ics(&plan(&name, &[(Utc::now(), dec!(10))]));
+        let events = parse_events(&ics);
+        assert_eq!(
+            property(&events[0], "SUMMARY"),
+            Some(format!("{} payment: 10.00 USD", name).as_str())
+        );
+    }
+}
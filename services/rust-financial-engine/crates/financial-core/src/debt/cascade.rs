@@ -0,0 +1,185 @@
+/// Period-by-period payoff of several debts with a shared payment budget
+///
+/// Every period each open debt receives its minimum payment and the rest of
+/// the budget goes to debts in priority order. A payment never exceeds a
+/// debt's balance plus the interest accrued that period; whatever is left
+/// over flows to the next debt in the same period, and the minimums of
+/// paid-off debts stay in the budget for the debts that remain.
+use crate::debt::types::{
+    ensure_amortizes, rate_changes_for, DebtAccount, DebtStrategy, MinimumPaymentFloor, PaymentPlan,
+    PaymentScheduleItem, RateChangeEvent,
+};
+use crate::types::Rate;
+use crate::{CalcContext, Clock, Money, Result};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+/// Longest simulation, in payment periods (50 years of monthly payments)
+const MAX_PAYMENT_PERIODS: u32 = 600;
+
+/// Balances at or below this are treated as paid off
+pub(crate) const PAID_OFF_THRESHOLD: Decimal = dec!(0.01);
+
+/// Inputs shared by every debt in a cascade simulation
+pub(crate) struct CascadeSimulation<'a, R, D>
+where
+    R: Fn(&Rate) -> Result<Decimal>,
+    D: Fn(DateTime<Utc>) -> DateTime<Utc>,
+{
+    pub strategy: DebtStrategy,
+    pub extra_payment_budget: &'a Money,
+    pub rate_changes: &'a [RateChangeEvent],
+    pub minimum_payment_floor: MinimumPaymentFloor,
+    /// Precision interest charges and payments are rounded to
+    pub context: CalcContext,
+    /// First payment falls at this clock's current time
+    pub clock: &'a dyn Clock,
+    /// Interest rate for one payment period
+    pub period_rate: R,
+    pub next_payment_date: D,
+}
+
+/// Running state of one debt during the simulation
+struct OpenDebt<'a> {
+    debt: &'a DebtAccount,
+    balance: Money,
+    period_rate: Decimal,
+    pending_rate_changes: std::iter::Peekable<std::vec::IntoIter<RateChangeEvent>>,
+    schedule: Vec<PaymentScheduleItem>,
+    total_interest: Money,
+}
+
+impl OpenDebt<'_> {
+    fn is_paid_off(&self) -> bool {
+        self.balance.amount() <= PAID_OFF_THRESHOLD
+    }
+}
+
+impl<R, D> CascadeSimulation<'_, R, D>
+where
+    R: Fn(&Rate) -> Result<Decimal>,
+    D: Fn(DateTime<Utc>) -> DateTime<Utc>,
+{
+    /// Simulate paying off `debts`, given in priority order
+    ///
+    /// Returns one plan per debt in the same order. Debts with no balance, or
+    /// a credit balance, are already paid off and get an empty schedule.
+    pub fn run(&self, debts: &[DebtAccount]) -> Result<Vec<PaymentPlan>> {
+        let currency = debts[0].balance.currency();
+        let zero = Money::new_unchecked(Decimal::ZERO, currency);
+
+        // Minimums of debts that are already paid off are not part of the budget
+        let mut budget = *self.extra_payment_budget;
+        let mut open = Vec::with_capacity(debts.len());
+        for debt in debts {
+            if debt.balance.amount() > PAID_OFF_THRESHOLD {
+                budget = budget.add(&debt.minimum_payment)?;
+            }
+            open.push(OpenDebt {
+                debt,
+                balance: debt.balance,
+                period_rate: (self.period_rate)(&debt.interest_rate)?,
+                pending_rate_changes: rate_changes_for(self.rate_changes, debt.id).into_iter().peekable(),
+                schedule: Vec::new(),
+                total_interest: zero,
+            });
+        }
+
+        let now = self.clock.now();
+        let mut payment_number = 1;
+        let mut current_date = now;
+
+        while payment_number <= MAX_PAYMENT_PERIODS && open.iter().any(|d| !d.is_paid_off()) {
+            // Interest accrues and minimums are due on every open debt first
+            let mut periods = Vec::with_capacity(open.len());
+            let mut committed = zero;
+            for debt in open.iter_mut().filter(|d| !d.is_paid_off()) {
+                while let Some(change) =
+                    debt.pending_rate_changes.next_if(|c| c.effective_date <= current_date)
+                {
+                    debt.period_rate = (self.period_rate)(&change.new_rate)?;
+                }
+
+                let interest = self.context.round_money(debt.balance.multiply(debt.period_rate)?);
+                let payoff_amount = debt.balance.add(&interest)?;
+                let required = self.context.round_money(self.minimum_payment_floor.apply(
+                    debt.debt.minimum_payment,
+                    interest,
+                    debt.balance,
+                )?);
+                let payment = min_money(required, payoff_amount);
+                committed = committed.add(&payment)?;
+                periods.push((debt, interest, payoff_amount, payment));
+            }
+
+            // The rest of the budget cascades down the priority order, so a
+            // debt paid off this period passes its surplus straight on
+            let mut surplus = if budget.amount() > committed.amount() {
+                budget.subtract(&committed)?
+            } else {
+                zero
+            };
+            for (_, _, payoff_amount, payment) in periods.iter_mut() {
+                if surplus.amount() <= Decimal::ZERO {
+                    break;
+                }
+                let room = payoff_amount.subtract(payment)?;
+                let applied = min_money(room, surplus);
+                *payment = payment.add(&applied)?;
+                surplus = surplus.subtract(&applied)?;
+            }
+
+            for (debt, interest, payoff_amount, payment) in periods {
+                if payment.amount() < payoff_amount.amount() {
+                    ensure_amortizes(debt.debt, &payment, &interest)?;
+                }
+                let principal = payment.subtract(&interest)?;
+                debt.balance = debt.balance.subtract(&principal)?;
+                debt.total_interest = debt.total_interest.add(&interest)?;
+                debt.schedule.push(PaymentScheduleItem {
+                    payment_number,
+                    payment_date: current_date,
+                    payment_amount: payment,
+                    principal,
+                    interest,
+                    remaining_balance: debt.balance,
+                });
+            }
+
+            payment_number += 1;
+            current_date = (self.next_payment_date)(current_date);
+        }
+
+        open.into_iter()
+            .map(|debt| {
+                let total_payments = debt
+                    .schedule
+                    .iter()
+                    .try_fold(zero, |acc, item| acc.add(&item.payment_amount))?;
+                Ok(PaymentPlan {
+                    debt_id: debt.debt.id,
+                    debt_name: debt.debt.name.clone(),
+                    strategy: self.strategy,
+                    monthly_payment: debt
+                        .schedule
+                        .first()
+                        .map_or(zero, |item| item.payment_amount),
+                    total_payments,
+                    total_interest: debt.total_interest,
+                    payoff_date: debt.schedule.last().map_or(now, |item| item.payment_date),
+                    payment_schedule: debt.schedule,
+                    created_at: now,
+                })
+            })
+            .collect()
+    }
+}
+
+fn min_money(a: Money, b: Money) -> Money {
+    if a.amount() <= b.amount() {
+        a
+    } else {
+        b
+    }
+}
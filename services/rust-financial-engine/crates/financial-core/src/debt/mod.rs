@@ -1,6 +1,9 @@
 pub mod avalanche;
+pub mod calendar;
+mod cascade;
 pub mod consolidation;
 pub mod optimization;
+pub mod ratios;
 pub mod snowball;
 /// Debt management and optimization module
 ///
@@ -9,10 +12,13 @@ pub mod snowball;
 /// - Payment optimization algorithms
 /// - Debt consolidation analysis
 /// - Interest savings calculations
+/// - Debt-to-income and payment-to-income ratios
 pub mod types;
 
 pub use avalanche::*;
+pub use calendar::*;
 pub use consolidation::*;
 pub use optimization::*;
+pub use ratios::*;
 pub use snowball::*;
 pub use types::*;
@@ -1,12 +1,15 @@
 use crate::debt::avalanche::{AvalancheCalculator, AvalancheSavings, StrategyComparison};
+use crate::debt::cascade::PAID_OFF_THRESHOLD;
 use crate::debt::snowball::{SnowballCalculator, SnowballSavings};
 use crate::debt::types::{
-    ConsolidationOpportunity, DebtAccount, DebtComparison, DebtOptimizationResult, DebtStrategy,
-    NegotiationOpportunity, PaymentPlan, PsychologicalFactors, RiskLevel,
+    ConsolidationOpportunity, DebtAccount, DebtComparison, DebtFreeProjection, DebtOptimizationResult,
+    DebtPayoffMilestone, DebtStrategy, NegotiationModel, NegotiationOpportunity, PaymentPlan,
+    PsychologicalFactors, RiskLevel,
 };
 use crate::types::Percentage;
-use crate::{FinancialError, Money, Result};
-use chrono::{DateTime, Utc};
+use crate::{system_clock, CalcContext, Clock, FinancialError, Money, Result};
+use std::borrow::Cow;
+use std::sync::Arc;
 /// Debt optimization engine combining multiple strategies
 ///
 /// This module provides comprehensive debt optimization analysis including:
@@ -22,6 +25,11 @@ pub struct DebtOptimizer {
     extra_payment_budget: Money,
     risk_tolerance: RiskLevel,
     psychological_preference: PsychologicalPreference,
+    context: CalcContext,
+    seed: Option<u64>,
+    negotiation_model: NegotiationModel,
+    clock: Arc<dyn Clock>,
+    custom_payment_order: Option<Vec<uuid::Uuid>>,
 }
 
 /// User's psychological preference for debt payoff
@@ -78,6 +86,11 @@ impl DebtOptimizer {
             extra_payment_budget,
             risk_tolerance: RiskLevel::Moderate,
             psychological_preference: PsychologicalPreference::Balanced,
+            context: CalcContext::default(),
+            seed: None,
+            negotiation_model: NegotiationModel::default(),
+            clock: system_clock(),
+            custom_payment_order: None,
         }
     }
 
@@ -93,6 +106,69 @@ impl DebtOptimizer {
         self
     }
 
+    /// Round every payment plan the optimizer produces to the user's precision
+    pub fn with_context(mut self, context: CalcContext) -> Self {
+        self.context = context;
+        self
+    }
+
+    /// Make repeated runs over the same debts produce the same analysis
+    ///
+    /// Debts are considered in id order whatever order they are passed in,
+    /// and ids the optimizer makes up (such as the consolidated debt's) are
+    /// derived from `seed` instead of generated randomly.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Replace the default savings and success assumptions used for
+    /// negotiation opportunities
+    pub fn with_negotiation_model(mut self, model: NegotiationModel) -> Self {
+        self.negotiation_model = model;
+        self
+    }
+
+    /// Start every payment plan, and date the analysis, at `clock`'s current time
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Pay debts off in `payment_order` instead of by strategy
+    ///
+    /// Optimization results then use [`DebtStrategy::Custom`], cascading the
+    /// extra budget into the listed debts in exactly that order; debts not
+    /// listed follow in avalanche order.
+    pub fn with_custom_payment_order(mut self, payment_order: Vec<uuid::Uuid>) -> Self {
+        self.custom_payment_order = Some(payment_order);
+        self
+    }
+
+    /// `debts` in the order the analysis considers them
+    fn ordered_debts<'a>(&self, debts: &'a [DebtAccount]) -> Cow<'a, [DebtAccount]> {
+        match self.seed {
+            Some(_) => {
+                let mut sorted = debts.to_vec();
+                sorted.sort_by_key(|d| d.id);
+                Cow::Owned(sorted)
+            }
+            None => Cow::Borrowed(debts),
+        }
+    }
+
+    fn snowball_calculator(&self, extra_payment_budget: Money) -> SnowballCalculator {
+        SnowballCalculator::new(extra_payment_budget)
+            .with_context(self.context)
+            .with_clock(self.clock.clone())
+    }
+
+    fn avalanche_calculator(&self, extra_payment_budget: Money) -> AvalancheCalculator {
+        AvalancheCalculator::new(extra_payment_budget)
+            .with_context(self.context)
+            .with_clock(self.clock.clone())
+    }
+
     /// Perform comprehensive debt optimization analysis
     pub fn optimize(&self, debts: &[DebtAccount]) -> Result<OptimizationAnalysis> {
         if debts.is_empty() {
@@ -100,10 +176,12 @@ impl DebtOptimizer {
                 details: "At least one debt account".to_string(),
             });
         }
+        let debts = self.ordered_debts(debts);
+        let debts = debts.as_ref();
 
         // Calculate both strategies
-        let _snowball_calculator = SnowballCalculator::new(self.extra_payment_budget.clone());
-        let avalanche_calculator = AvalancheCalculator::new(self.extra_payment_budget.clone());
+        let _snowball_calculator = self.snowball_calculator(self.extra_payment_budget);
+        let avalanche_calculator = self.avalanche_calculator(self.extra_payment_budget);
 
         let strategy_comparison = avalanche_calculator.compare_with_snowball(debts)?;
 
@@ -140,24 +218,39 @@ impl DebtOptimizer {
         &self,
         debts: &[DebtAccount],
     ) -> Result<DebtOptimizationResult> {
+        let debts = self.ordered_debts(debts);
+        let debts = debts.as_ref();
         let analysis = self.optimize(debts)?;
 
-        let payment_plans = match analysis.recommended_strategy {
+        // A user-chosen order overrides whatever the analysis recommends
+        let strategy = match self.custom_payment_order {
+            Some(_) => DebtStrategy::Custom,
+            None => analysis.recommended_strategy,
+        };
+
+        let payment_plans = match strategy {
             DebtStrategy::Snowball => {
-                let calculator = SnowballCalculator::new(self.extra_payment_budget.clone());
+                let calculator = self.snowball_calculator(self.extra_payment_budget);
                 calculator.calculate_payment_plan(debts)?
             }
             DebtStrategy::Avalanche => {
-                let calculator = AvalancheCalculator::new(self.extra_payment_budget.clone());
+                let calculator = self.avalanche_calculator(self.extra_payment_budget);
                 calculator.calculate_payment_plan(debts)?
             }
+            DebtStrategy::HybridKickstart => {
+                let calculator = self.avalanche_calculator(self.extra_payment_budget);
+                calculator.calculate_kickstart_payment_plan(debts)?
+            }
             DebtStrategy::Custom => {
-                // Use the first custom strategy suggestion if available
-                if let Some(custom_strategy) = analysis.custom_strategy_suggestions.first() {
+                if let Some(payment_order) = &self.custom_payment_order {
+                    let calculator = self.avalanche_calculator(self.extra_payment_budget);
+                    calculator.calculate_custom_payment_plan(debts, payment_order)?
+                } else if let Some(custom_strategy) = analysis.custom_strategy_suggestions.first() {
+                    // Use the first custom strategy suggestion if available
                     self.calculate_custom_payment_plan(debts, &custom_strategy.payment_allocation)?
                 } else {
                     // Fallback to avalanche
-                    let calculator = AvalancheCalculator::new(self.extra_payment_budget.clone());
+                    let calculator = self.avalanche_calculator(self.extra_payment_budget);
                     calculator.calculate_payment_plan(debts)?
                 }
             }
@@ -181,7 +274,7 @@ impl DebtOptimizer {
             .iter()
             .map(|p| p.payoff_date)
             .max()
-            .unwrap_or(Utc::now());
+            .unwrap_or_else(|| self.clock.now());
 
         let total_time_to_payoff_months = payment_plans
             .iter()
@@ -190,7 +283,7 @@ impl DebtOptimizer {
             .unwrap_or(0);
 
         // Calculate savings vs minimum payments
-        let minimum_calculator = AvalancheCalculator::new(Money::new_unchecked(
+        let minimum_calculator = self.avalanche_calculator(Money::new_unchecked(
             Decimal::ZERO,
             debts[0].balance.currency(),
         ));
@@ -212,7 +305,7 @@ impl DebtOptimizer {
             minimum_total_months.saturating_sub(total_time_to_payoff_months);
 
         Ok(DebtOptimizationResult {
-            strategy: analysis.recommended_strategy,
+            strategy,
             payment_plans,
             total_monthly_payment,
             total_interest_paid,
@@ -220,15 +313,17 @@ impl DebtOptimizer {
             final_payoff_date,
             interest_savings_vs_minimum,
             time_savings_vs_minimum_months,
-            generated_at: Utc::now(),
+            generated_at: self.clock.now(),
         })
     }
 
     /// Create a debt comparison analysis
     pub fn create_debt_comparison(&self, debts: &[DebtAccount]) -> Result<DebtComparison> {
-        let snowball_calculator = SnowballCalculator::new(self.extra_payment_budget.clone());
-        let avalanche_calculator = AvalancheCalculator::new(self.extra_payment_budget.clone());
-        let minimum_calculator = AvalancheCalculator::new(Money::new_unchecked(
+        let debts = self.ordered_debts(debts);
+        let debts = debts.as_ref();
+        let snowball_calculator = self.snowball_calculator(self.extra_payment_budget);
+        let avalanche_calculator = self.avalanche_calculator(self.extra_payment_budget);
+        let minimum_calculator = self.avalanche_calculator(Money::new_unchecked(
             Decimal::ZERO,
             debts[0].balance.currency(),
         ));
@@ -250,9 +345,9 @@ impl DebtOptimizer {
         let recommended_strategy = if avalanche_result.total_interest_paid.amount()
             < snowball_result.total_interest_paid.amount()
         {
-            if avalanche_result
+            if snowball_result
                 .total_interest_paid
-                .subtract(&snowball_result.total_interest_paid)?
+                .subtract(&avalanche_result.total_interest_paid)?
                 .amount()
                 > dec!(500)
             {
@@ -291,6 +386,90 @@ impl DebtOptimizer {
         })
     }
 
+    /// Project when all `debts` are paid off with the extra budget shared between them
+    ///
+    /// Runs the same cascade as the strategy's payment plan, so the date is
+    /// when the last debt clears once earlier payoffs have rolled into it.
+    /// Only the snowball, avalanche and hybrid kickstart strategies can be
+    /// projected, plus the custom strategy when a
+    /// [custom payment order](Self::with_custom_payment_order) is set.
+    pub fn project_debt_free_date(
+        &self,
+        debts: &[DebtAccount],
+        strategy: DebtStrategy,
+    ) -> Result<DebtFreeProjection> {
+        if debts.is_empty() {
+            return Err(FinancialError::InsufficientData {
+                details: "At least one debt account".to_string(),
+            });
+        }
+        let debts = self.ordered_debts(debts);
+        let debts = debts.as_ref();
+
+        let plans = match strategy {
+            DebtStrategy::Snowball => self
+                .snowball_calculator(self.extra_payment_budget)
+                .calculate_payment_plan(debts)?,
+            DebtStrategy::Avalanche => self
+                .avalanche_calculator(self.extra_payment_budget)
+                .calculate_payment_plan(debts)?,
+            DebtStrategy::HybridKickstart => self
+                .avalanche_calculator(self.extra_payment_budget)
+                .calculate_kickstart_payment_plan(debts)?,
+            DebtStrategy::Custom if self.custom_payment_order.is_some() => {
+                let payment_order = self.custom_payment_order.as_deref().unwrap_or_default();
+                self.avalanche_calculator(self.extra_payment_budget)
+                    .calculate_custom_payment_plan(debts, payment_order)?
+            }
+            DebtStrategy::Custom | DebtStrategy::Consolidation => {
+                return Err(FinancialError::InvalidParameter {
+                    parameter: "strategy".to_string(),
+                    value: format!("{:?}", strategy),
+                });
+            }
+        };
+
+        let mut total_interest = Money::new_unchecked(Decimal::ZERO, debts[0].balance.currency());
+        let mut payoff_cascade = Vec::with_capacity(plans.len());
+        for plan in &plans {
+            total_interest = total_interest.add(&plan.total_interest)?;
+            // Debts with nothing owed take no payments and are not part of the cascade
+            let Some(last_payment) = plan.payment_schedule.last() else {
+                continue;
+            };
+            if last_payment.remaining_balance.amount() > PAID_OFF_THRESHOLD {
+                return Err(FinancialError::DebtCalculationFailed {
+                    reason: format!(
+                        "{} is not paid off within {} payments",
+                        plan.debt_name,
+                        plan.payment_count()
+                    ),
+                });
+            }
+            payoff_cascade.push(DebtPayoffMilestone {
+                debt_id: plan.debt_id,
+                debt_name: plan.debt_name.clone(),
+                payoff_date: last_payment.payment_date,
+                payoff_payment_number: last_payment.payment_number,
+                total_interest: plan.total_interest,
+            });
+        }
+        // Stable, so debts cleared in the same period keep their priority order
+        payoff_cascade.sort_by_key(|milestone| milestone.payoff_payment_number);
+
+        let (debt_free_date, months_to_debt_free) = payoff_cascade
+            .last()
+            .map_or((self.clock.now(), 0), |last| (last.payoff_date, last.payoff_payment_number));
+
+        Ok(DebtFreeProjection {
+            strategy,
+            debt_free_date,
+            months_to_debt_free,
+            total_interest,
+            payoff_cascade,
+        })
+    }
+
     // Private helper methods
 
     fn analyze_psychological_factors(
@@ -416,28 +595,28 @@ impl DebtOptimizer {
     }
 
     fn create_negotiation_opportunity(&self, debt: &DebtAccount) -> Result<NegotiationOpportunity> {
-        use crate::debt::types::NegotiationType;
-
-        let (negotiation_type, potential_savings_pct, success_probability_pct) =
-            match debt.debt_type {
-                crate::debt::types::DebtType::CreditCard => {
-                    if debt.balance.amount() > dec!(5000) {
-                        (NegotiationType::InterestRateReduction, dec!(20), dec!(70))
-                    } else {
-                        (NegotiationType::PaymentPlanModification, dec!(10), dec!(60))
-                    }
-                }
-                crate::debt::types::DebtType::MedicalDebt => {
-                    (NegotiationType::BalanceSettlement, dec!(40), dec!(80))
+        let assumption = self.negotiation_model.assumption_for(debt);
+        let negotiation_type = assumption.negotiation_type;
+
+        let gross_savings =
+            Percentage::from_percentage(assumption.savings_pct)?.apply_to(&debt.balance)?;
+        let settlement_tax = match self.negotiation_model.settlement_tax_rate_pct {
+            Some(rate_pct) if negotiation_type.forgives_balance() => {
+                if rate_pct < Decimal::ZERO || rate_pct > dec!(100) {
+                    return Err(FinancialError::ParameterOutOfRange {
+                        parameter: "settlement_tax_rate_pct".to_string(),
+                        min: "0".to_string(),
+                        max: "100".to_string(),
+                        actual: rate_pct.to_string(),
+                    });
                 }
-                crate::debt::types::DebtType::PersonalLoan => {
-                    (NegotiationType::InterestRateReduction, dec!(15), dec!(50))
-                }
-                _ => (NegotiationType::PaymentPlanModification, dec!(5), dec!(30)),
-            };
-
-        let potential_savings = debt.balance.multiply(potential_savings_pct / dec!(100))?;
-        let success_probability = Percentage::from_percentage(success_probability_pct)?;
+                Percentage::from_percentage(rate_pct)?.apply_to(&gross_savings)?
+            }
+            _ => Money::new_unchecked(Decimal::ZERO, debt.balance.currency()),
+        };
+        let potential_savings = gross_savings.subtract(&settlement_tax)?;
+        let success_probability =
+            Percentage::from_percentage(assumption.success_probability_pct)?;
 
         Ok(NegotiationOpportunity {
             debt_id: debt.id,
@@ -445,6 +624,7 @@ impl DebtOptimizer {
             current_balance: debt.balance.clone(),
             negotiation_type,
             potential_savings,
+            settlement_tax,
             success_probability,
             negotiation_strategy: self.generate_negotiation_strategy(&negotiation_type),
             talking_points: self.generate_talking_points(debt, &negotiation_type),
@@ -460,23 +640,21 @@ impl DebtOptimizer {
 
         // Hybrid strategy: Focus extra payment on highest-interest debt above certain threshold
         if debts.len() > 2 {
-            let high_interest_threshold = dec!(15.0); // 15% APR
+            let high_interest_threshold = Percentage::from_percentage(dec!(15.0))?; // 15% APR
             let mut allocations = Vec::new();
 
             for debt in debts {
-                let is_high_interest =
-                    debt.interest_rate.as_decimal() > high_interest_threshold / dec!(100);
-                let extra_percentage = if is_high_interest { dec!(70) } else { dec!(10) };
+                let is_high_interest = debt.interest_rate.percentage() > high_interest_threshold;
+                let extra_percentage =
+                    Percentage::from_percentage(if is_high_interest { dec!(70) } else { dec!(10) })?;
 
                 allocations.push(DebtAllocation {
                     debt_id: debt.id,
                     debt_name: debt.name.clone(),
-                    monthly_payment: debt.minimum_payment.add(
-                        &self
-                            .extra_payment_budget
-                            .multiply(extra_percentage / dec!(100))?,
-                    )?,
-                    percentage_of_extra: Percentage::from_percentage(extra_percentage)?,
+                    monthly_payment: debt
+                        .minimum_payment
+                        .add(&extra_percentage.apply_to(&self.extra_payment_budget)?)?,
+                    percentage_of_extra: extra_percentage,
                 });
             }
 
@@ -504,7 +682,7 @@ impl DebtOptimizer {
         for allocation in allocations {
             if let Some(debt) = debts.iter().find(|d| d.id == allocation.debt_id) {
                 let extra_payment = allocation.monthly_payment.subtract(&debt.minimum_payment)?;
-                let calculator = AvalancheCalculator::new(extra_payment);
+                let calculator = self.avalanche_calculator(extra_payment);
                 let plan = calculator.calculate_single_debt_plan(debt, &extra_payment)?;
                 plans.push(plan);
             }
@@ -519,7 +697,7 @@ impl DebtOptimizer {
         let weighted_avg_rate = self.calculate_weighted_average_rate(debts)?;
 
         // Create a virtual consolidated debt
-        let consolidated_debt = DebtAccount::new(
+        let mut consolidated_debt = DebtAccount::new(
             uuid::Uuid::new_v4(),
             "Consolidated Debt".to_string(),
             crate::debt::types::DebtType::PersonalLoan,
@@ -527,8 +705,11 @@ impl DebtOptimizer {
             weighted_avg_rate,
             self.extra_payment_budget.clone(), // Assume consolidation allows using full extra payment
         );
+        if let Some(seed) = self.seed {
+            consolidated_debt.id = uuid::Uuid::from_u64_pair(seed, 0);
+        }
 
-        let calculator = AvalancheCalculator::new(self.extra_payment_budget.clone());
+        let calculator = self.avalanche_calculator(self.extra_payment_budget);
         let plan = calculator
             .calculate_single_debt_plan(&consolidated_debt, &self.extra_payment_budget)?;
 
@@ -581,7 +762,7 @@ impl DebtOptimizer {
             .iter()
             .map(|p| p.payoff_date)
             .max()
-            .unwrap_or(Utc::now());
+            .unwrap_or_else(|| self.clock.now());
 
         let total_time_to_payoff_months =
             plans.iter().map(|p| p.payment_count()).max().unwrap_or(0);
@@ -595,7 +776,7 @@ impl DebtOptimizer {
             final_payoff_date,
             interest_savings_vs_minimum: Money::new_unchecked(Decimal::ZERO, currency),
             time_savings_vs_minimum_months: 0,
-            generated_at: Utc::now(),
+            generated_at: self.clock.now(),
         })
     }
 
@@ -618,10 +799,10 @@ impl DebtOptimizer {
             dec!(0.4)
         };
 
-        let interest_diff = avalanche_result
+        let avalanche_savings = snowball_result
             .total_interest_paid
-            .subtract(&snowball_result.total_interest_paid)?;
-        let mathematical_optimality = if interest_diff.amount() > dec!(1000) {
+            .subtract(&avalanche_result.total_interest_paid)?;
+        let mathematical_optimality = if avalanche_savings.amount() > dec!(1000) {
             dec!(0.9)
         } else {
             dec!(0.5)
@@ -636,6 +817,7 @@ impl DebtOptimizer {
         })
     }
 
+    /// Explain the recommendation, always naming the strategy that pays less interest
     fn generate_recommendation_reason(
         &self,
         avalanche_result: &DebtOptimizationResult,
@@ -643,30 +825,32 @@ impl DebtOptimizer {
         psychological: &PsychologicalFactors,
         recommended_strategy: DebtStrategy,
     ) -> String {
-        let interest_diff = avalanche_result
-            .total_interest_paid
-            .subtract(&snowball_result.total_interest_paid)
-            .unwrap_or_else(|_| {
-                Money::new_unchecked(
-                    Decimal::ZERO,
-                    avalanche_result.total_interest_paid.currency(),
-                )
-            });
+        // Positive when avalanche is cheaper, negative when snowball is
+        let avalanche_savings = snowball_result.total_interest_paid.amount()
+            - avalanche_result.total_interest_paid.amount();
+        let savings = avalanche_savings.abs().round_dp(2);
 
         match recommended_strategy {
             DebtStrategy::Avalanche => {
-                if interest_diff.amount() > dec!(1000) {
-                    format!("Avalanche method saves ${:.2} in interest payments - significant mathematical advantage", interest_diff.amount())
+                if avalanche_savings > dec!(1000) {
+                    format!("Avalanche method saves ${:.2} in interest compared to Snowball - significant mathematical advantage", savings)
                 } else {
-                    "Avalanche method provides modest interest savings with good mathematical foundation".to_string()
+                    format!("Avalanche method saves ${:.2} in interest compared to Snowball - modest savings with good mathematical foundation", savings)
                 }
             }
             DebtStrategy::Snowball => {
+                let tradeoff = if avalanche_savings > Decimal::ZERO {
+                    format!("though Avalanche would save ${:.2} more in interest", savings)
+                } else if avalanche_savings < Decimal::ZERO {
+                    format!("and it saves ${:.2} in interest compared to Avalanche", savings)
+                } else {
+                    "at the same interest cost as Avalanche".to_string()
+                };
+
                 if psychological.quick_wins_importance > dec!(0.7) {
-                    "Snowball method recommended for psychological benefits and early motivation wins".to_string()
+                    format!("Snowball method recommended for psychological benefits and early motivation wins, {}", tradeoff)
                 } else {
-                    "Snowball method provides good balance of savings and psychological benefits"
-                        .to_string()
+                    format!("Snowball method provides good balance of savings and psychological benefits, {}", tradeoff)
                 }
             }
             _ => "Custom strategy recommended based on your specific situation".to_string(),
@@ -763,10 +947,22 @@ impl Default for DebtOptimizer {
     }
 }
 
+/// When all `debts` are paid off if `total_extra` is put toward them each month
+///
+/// Shorthand for [`DebtOptimizer::project_debt_free_date`] with default settings.
+pub fn debt_free_date(
+    debts: &[DebtAccount],
+    total_extra: Money,
+    strategy: DebtStrategy,
+) -> Result<DebtFreeProjection> {
+    DebtOptimizer::new(total_extra).project_debt_free_date(debts, strategy)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::debt::types::DebtType;
+    use chrono::Utc;
     use crate::types::{Currency, Percentage, Period, Rate};
     use uuid::Uuid;
 
@@ -823,6 +1019,237 @@ mod tests {
         assert!(!comparison.recommendation_reason.is_empty());
     }
 
+    fn result_with_interest(strategy: DebtStrategy, interest: Decimal) -> DebtOptimizationResult {
+        let zero = Money::new(Decimal::ZERO, Currency::USD).unwrap();
+        DebtOptimizationResult {
+            strategy,
+            payment_plans: Vec::new(),
+            total_monthly_payment: zero,
+            total_interest_paid: Money::new(interest, Currency::USD).unwrap(),
+            total_time_to_payoff_months: 0,
+            final_payoff_date: Utc::now(),
+            interest_savings_vs_minimum: zero,
+            time_savings_vs_minimum_months: 0,
+            generated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_recommendation_reason_names_cheaper_strategy() {
+        let optimizer = DebtOptimizer::default();
+        let factors = PsychologicalFactors {
+            motivation_score_snowball: 7,
+            motivation_score_avalanche: 7,
+            quick_wins_importance: dec!(0.8),
+            mathematical_optimality: dec!(0.9),
+            estimated_success_probability: Percentage::from_percentage(dec!(75)).unwrap(),
+        };
+        let avalanche = result_with_interest(DebtStrategy::Avalanche, dec!(1200));
+        let snowball = result_with_interest(DebtStrategy::Snowball, dec!(2450.555));
+
+        let reason = optimizer.generate_recommendation_reason(
+            &avalanche,
+            &snowball,
+            &factors,
+            DebtStrategy::Avalanche,
+        );
+        assert!(reason.starts_with("Avalanche method saves $1250.56 in interest"), "{}", reason);
+
+        // Choosing snowball anyway states what it costs
+        let reason = optimizer.generate_recommendation_reason(
+            &avalanche,
+            &snowball,
+            &factors,
+            DebtStrategy::Snowball,
+        );
+        assert!(reason.contains("Avalanche would save $1250.56 more"), "{}", reason);
+
+        let reason = optimizer.generate_recommendation_reason(
+            &snowball,
+            &avalanche,
+            &factors,
+            DebtStrategy::Snowball,
+        );
+        assert!(reason.contains("saves $1250.56 in interest compared to Avalanche"), "{}", reason);
+    }
+
+    #[test]
+    fn test_debt_free_date_is_last_payoff_of_the_cascade() {
+        let debt = |name: &str, balance: Decimal, apr: Decimal, minimum: Decimal| {
+            DebtAccount::new(
+                Uuid::new_v4(),
+                name.to_string(),
+                DebtType::CreditCard,
+                Money::new(balance, Currency::USD).unwrap(),
+                Rate::new(Percentage::from_percentage(apr).unwrap(), Period::Annual),
+                Money::new(minimum, Currency::USD).unwrap(),
+            )
+        };
+        let debts = vec![
+            debt("Store Card", dec!(800), dec!(26.99), dec!(25)),
+            debt("Car Loan", dec!(9000), dec!(6.5), dec!(250)),
+            debt("Visa", dec!(4000), dec!(21.0), dec!(90)),
+            debt("Paid Off", dec!(0), dec!(19.99), dec!(0)),
+        ];
+        let extra = Money::new(dec!(300), Currency::USD).unwrap();
+
+        for strategy in [DebtStrategy::Snowball, DebtStrategy::Avalanche] {
+            let projection = debt_free_date(&debts, extra, strategy).unwrap();
+            let plans = match strategy {
+                DebtStrategy::Snowball => SnowballCalculator::new(extra).calculate_payment_plan(&debts),
+                _ => AvalancheCalculator::new(extra).calculate_payment_plan(&debts),
+            }
+            .unwrap();
+
+            // The combined date is the latest individual payoff under the cascade
+            let last = projection.payoff_cascade.last().unwrap();
+            assert_eq!(
+                projection.debt_free_date,
+                projection.payoff_cascade.iter().map(|m| m.payoff_date).max().unwrap()
+            );
+            assert_eq!(projection.debt_free_date, last.payoff_date);
+            assert_eq!(
+                projection.months_to_debt_free,
+                plans.iter().map(|p| p.payment_count()).max().unwrap()
+            );
+            assert_eq!(
+                projection.total_interest.amount(),
+                plans.iter().map(|p| p.total_interest.amount()).sum::<Decimal>()
+            );
+
+            assert_eq!(projection.payoff_cascade.len(), 3);
+            assert!(projection
+                .payoff_cascade
+                .windows(2)
+                .all(|pair| pair[0].payoff_payment_number <= pair[1].payoff_payment_number));
+        }
+
+        let snowball = debt_free_date(&debts, extra, DebtStrategy::Snowball).unwrap();
+        assert_eq!(snowball.payoff_cascade[0].debt_name, "Store Card");
+        assert_eq!(snowball.payoff_cascade[2].debt_name, "Car Loan");
+
+        assert!(matches!(
+            debt_free_date(&debts, extra, DebtStrategy::Consolidation),
+            Err(FinancialError::InvalidParameter { .. })
+        ));
+        assert!(debt_free_date(&[], extra, DebtStrategy::Avalanche).is_err());
+    }
+
+    #[test]
+    fn test_hybrid_kickstart_clears_smallest_debt_first_then_avalanches() {
+        let debt = |name: &str, balance: Decimal, apr: Decimal, minimum: Decimal| {
+            DebtAccount::new(
+                Uuid::new_v4(),
+                name.to_string(),
+                DebtType::CreditCard,
+                Money::new(balance, Currency::USD).unwrap(),
+                Rate::new(Percentage::from_percentage(apr).unwrap(), Period::Annual),
+                Money::new(minimum, Currency::USD).unwrap(),
+            )
+        };
+        let debts = vec![
+            debt("Visa", dec!(5000), dec!(24.0), dec!(150)),
+            debt("Medical Bill", dec!(500), dec!(8.0), dec!(25)),
+            debt("Store Card", dec!(3000), dec!(18.0), dec!(90)),
+            debt("Paid Off", dec!(0), dec!(29.99), dec!(0)),
+        ];
+        let extra = Money::new(dec!(200), Currency::USD).unwrap();
+
+        let hybrid = debt_free_date(&debts, extra, DebtStrategy::HybridKickstart).unwrap();
+        let avalanche = debt_free_date(&debts, extra, DebtStrategy::Avalanche).unwrap();
+        let snowball = debt_free_date(&debts, extra, DebtStrategy::Snowball).unwrap();
+
+        // The tiny low-rate debt goes first, then the rest by rate
+        let order: Vec<&str> = hybrid.payoff_cascade.iter().map(|m| m.debt_name.as_str()).collect();
+        assert_eq!(order, ["Medical Bill", "Visa", "Store Card"]);
+        assert_eq!(avalanche.payoff_cascade[0].debt_name, "Visa");
+        assert!(
+            hybrid.payoff_cascade[0].payoff_payment_number
+                < avalanche
+                    .payoff_cascade
+                    .iter()
+                    .find(|m| m.debt_name == "Medical Bill")
+                    .unwrap()
+                    .payoff_payment_number
+        );
+
+        // Paying the small debt first costs some interest, but less than snowball
+        assert!(avalanche.total_interest.amount() < hybrid.total_interest.amount());
+        assert!(hybrid.total_interest.amount() < snowball.total_interest.amount());
+
+        let plans = AvalancheCalculator::new(extra)
+            .calculate_kickstart_payment_plan(&debts)
+            .unwrap();
+        assert!(plans.iter().all(|p| p.strategy == DebtStrategy::HybridKickstart));
+        assert_eq!(
+            plans.iter().map(|p| p.total_interest.amount()).sum::<Decimal>(),
+            hybrid.total_interest.amount()
+        );
+    }
+
+    #[test]
+    fn test_custom_payment_order_overrides_snowball_and_avalanche() {
+        let debt = |name: &str, balance: Decimal, apr: Decimal, minimum: Decimal| {
+            DebtAccount::new(
+                Uuid::new_v4(),
+                name.to_string(),
+                DebtType::CreditCard,
+                Money::new(balance, Currency::USD).unwrap(),
+                Rate::new(Percentage::from_percentage(apr).unwrap(), Period::Annual),
+                Money::new(minimum, Currency::USD).unwrap(),
+            )
+        };
+        let debts = vec![
+            debt("Visa", dec!(5000), dec!(24.0), dec!(150)),
+            debt("Medical Bill", dec!(500), dec!(8.0), dec!(25)),
+            debt("Store Card", dec!(3000), dec!(18.0), dec!(90)),
+        ];
+        let extra = Money::new(dec!(200), Currency::USD).unwrap();
+        let payoff_order = |projection: &DebtFreeProjection| -> Vec<String> {
+            projection.payoff_cascade.iter().map(|m| m.debt_name.clone()).collect()
+        };
+
+        let snowball = debt_free_date(&debts, extra, DebtStrategy::Snowball).unwrap();
+        let avalanche = debt_free_date(&debts, extra, DebtStrategy::Avalanche).unwrap();
+        assert_eq!(payoff_order(&snowball), ["Medical Bill", "Store Card", "Visa"]);
+        assert_eq!(payoff_order(&avalanche), ["Visa", "Medical Bill", "Store Card"]);
+
+        // The store card goes first for personal reasons, then the medical bill
+        let optimizer = DebtOptimizer::new(extra)
+            .with_custom_payment_order(vec![debts[2].id, debts[1].id, debts[0].id]);
+        let custom = optimizer
+            .project_debt_free_date(&debts, DebtStrategy::Custom)
+            .unwrap();
+        assert_eq!(custom.strategy, DebtStrategy::Custom);
+        assert_eq!(payoff_order(&custom), ["Store Card", "Medical Bill", "Visa"]);
+        assert!(avalanche.total_interest.amount() < custom.total_interest.amount());
+
+        let result = optimizer.generate_optimization_result(&debts).unwrap();
+        assert_eq!(result.strategy, DebtStrategy::Custom);
+        let plan_order: Vec<&str> = result.payment_plans.iter().map(|p| p.debt_name.as_str()).collect();
+        assert_eq!(plan_order, ["Store Card", "Medical Bill", "Visa"]);
+        assert_eq!(result.total_interest_paid, custom.total_interest);
+
+        // Debts left out of the order follow by interest rate
+        let partial = AvalancheCalculator::new(extra)
+            .calculate_custom_payment_plan(&debts, &[debts[1].id])
+            .unwrap();
+        let plan_order: Vec<&str> = partial.iter().map(|p| p.debt_name.as_str()).collect();
+        assert_eq!(plan_order, ["Medical Bill", "Visa", "Store Card"]);
+        assert!(partial.iter().all(|p| p.strategy == DebtStrategy::Custom));
+
+        for bad_order in [vec![Uuid::new_v4()], vec![debts[0].id, debts[0].id]] {
+            assert!(matches!(
+                AvalancheCalculator::new(extra).calculate_custom_payment_plan(&debts, &bad_order),
+                Err(FinancialError::InvalidParameter { .. })
+            ));
+        }
+        assert!(matches!(
+            DebtOptimizer::new(extra).project_debt_free_date(&debts, DebtStrategy::Custom),
+            Err(FinancialError::InvalidParameter { .. })
+        ));
+    }
+
     #[test]
     fn test_negotiation_opportunities() {
         let optimizer = DebtOptimizer::default();
@@ -846,6 +1273,116 @@ mod tests {
         assert!(opportunities[0].potential_savings.amount() > Decimal::ZERO);
     }
 
+    #[test]
+    fn test_negotiation_model_drives_potential_savings() {
+        use crate::debt::types::{NegotiationAssumption, NegotiationModel, NegotiationType};
+
+        let medical = DebtAccount::new(
+            Uuid::new_v4(),
+            "Hospital Bill".to_string(),
+            DebtType::MedicalDebt,
+            Money::new(dec!(10000), Currency::USD).unwrap(),
+            Rate::new(Percentage::from_percentage(dec!(0)).unwrap(), Period::Annual),
+            Money::new(dec!(100), Currency::USD).unwrap(),
+        );
+        let debts = vec![medical];
+
+        let default = DebtOptimizer::default()
+            .find_negotiation_opportunities(&debts)
+            .unwrap();
+        assert_eq!(default[0].potential_savings.amount(), dec!(4000));
+        assert_eq!(default[0].settlement_tax.amount(), Decimal::ZERO);
+
+        let model = NegotiationModel {
+            medical_debt: NegotiationAssumption::new(
+                NegotiationType::BalanceSettlement,
+                dec!(50),
+                dec!(65),
+            ),
+            settlement_tax_rate_pct: Some(dec!(22)),
+            ..NegotiationModel::default()
+        };
+        let custom = DebtOptimizer::default()
+            .with_negotiation_model(model.clone())
+            .find_negotiation_opportunities(&debts)
+            .unwrap();
+        // 50% of the balance is forgiven and 22% of that is owed as tax
+        assert_eq!(custom[0].settlement_tax.amount(), dec!(1100));
+        assert_eq!(custom[0].potential_savings.amount(), dec!(3900));
+        assert_eq!(custom[0].success_probability.as_percentage(), dec!(65));
+
+        let invalid = NegotiationModel {
+            settlement_tax_rate_pct: Some(dec!(120)),
+            ..model
+        };
+        assert!(matches!(
+            DebtOptimizer::default()
+                .with_negotiation_model(invalid)
+                .find_negotiation_opportunities(&debts),
+            Err(FinancialError::ParameterOutOfRange { .. })
+        ));
+    }
+
+    /// Everything in a plan except the timestamps taken from the clock
+    fn plan_outline(plan: &PaymentPlan) -> (Uuid, Money, Money, Vec<Money>) {
+        (
+            plan.debt_id,
+            plan.total_interest,
+            plan.total_payments,
+            plan.payment_schedule.iter().map(|item| item.payment_amount).collect(),
+        )
+    }
+
+    #[test]
+    fn test_seeded_optimization_is_reproducible() {
+        // Identical cards tie under both strategies
+        let card = |name: &str| {
+            DebtAccount::new(
+                Uuid::new_v4(),
+                name.to_string(),
+                DebtType::CreditCard,
+                Money::new(dec!(3000), Currency::USD).unwrap(),
+                Rate::new(
+                    Percentage::from_percentage(dec!(19.99)).unwrap(),
+                    Period::Annual,
+                ),
+                Money::new(dec!(90), Currency::USD).unwrap(),
+            )
+        };
+        let debts = vec![card("Visa"), card("Mastercard"), card("Discover")];
+        let reversed: Vec<DebtAccount> = debts.iter().rev().cloned().collect();
+
+        let optimizer =
+            DebtOptimizer::new(Money::new(dec!(250), Currency::USD).unwrap()).with_seed(42);
+        let first = optimizer.generate_optimization_result(&debts).unwrap();
+        let second = optimizer.generate_optimization_result(&reversed).unwrap();
+
+        assert_eq!(first.strategy, second.strategy);
+        assert_eq!(first.total_interest_paid, second.total_interest_paid);
+        assert_eq!(
+            first.payment_plans.iter().map(plan_outline).collect::<Vec<_>>(),
+            second.payment_plans.iter().map(plan_outline).collect::<Vec<_>>()
+        );
+        let mut ids: Vec<Uuid> = debts.iter().map(|d| d.id).collect();
+        ids.sort();
+        assert_eq!(
+            first.payment_plans.iter().map(|p| p.debt_id).collect::<Vec<_>>(),
+            ids
+        );
+
+        let first = optimizer.optimize(&debts).unwrap();
+        let second = optimizer.optimize(&reversed).unwrap();
+        assert_eq!(first.custom_strategy_suggestions, second.custom_strategy_suggestions);
+        assert_eq!(first.negotiation_opportunities, second.negotiation_opportunities);
+        assert_eq!(first.confidence_score, second.confidence_score);
+
+        let consolidated = optimizer.calculate_consolidation_plan(&debts).unwrap();
+        assert_eq!(
+            consolidated[0].debt_id,
+            optimizer.calculate_consolidation_plan(&reversed).unwrap()[0].debt_id
+        );
+    }
+
     #[test]
     fn test_psychological_preferences() {
         let debts = vec![
@@ -0,0 +1,199 @@
+/// Income ratios lenders use to judge how much debt a borrower can carry
+///
+/// Debt-to-income (DTI) compares all monthly debt payments with gross
+/// monthly income; payment-to-income (PTI) does the same for a single loan
+/// payment. Both are classified against configurable thresholds.
+use crate::types::Percentage;
+use crate::{FinancialError, Money, Result};
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+/// How a ratio compares with its thresholds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IncomeRatioLevel {
+    Healthy,
+    Caution,
+    High,
+}
+
+/// Upper bounds of the healthy and caution bands; anything above
+/// `caution_max` is high
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IncomeRatioThresholds {
+    pub healthy_max: Percentage,
+    pub caution_max: Percentage,
+}
+
+impl IncomeRatioThresholds {
+    /// Create thresholds; the caution bound may not be below the healthy one
+    pub fn new(healthy_max: Percentage, caution_max: Percentage) -> Result<Self> {
+        if caution_max.as_percentage() < healthy_max.as_percentage() {
+            return Err(FinancialError::ValidationError(
+                "Caution threshold must not be below the healthy threshold".to_string(),
+            ));
+        }
+        Ok(Self {
+            healthy_max,
+            caution_max,
+        })
+    }
+
+    /// Common DTI guidance: up to 36% is healthy and 43% is the most a
+    /// qualified mortgage allows
+    pub fn debt_to_income() -> Self {
+        Self {
+            healthy_max: Percentage::from_percentage(dec!(36)).unwrap(),
+            caution_max: Percentage::from_percentage(dec!(43)).unwrap(),
+        }
+    }
+
+    /// Common guidance for a single loan payment, such as a car loan: up to
+    /// 10% of income is healthy and lenders rarely go past 15%
+    pub fn payment_to_income() -> Self {
+        Self {
+            healthy_max: Percentage::from_percentage(dec!(10)).unwrap(),
+            caution_max: Percentage::from_percentage(dec!(15)).unwrap(),
+        }
+    }
+
+    /// Band `ratio` falls in; a ratio equal to a bound is in the lower band
+    pub fn classify(&self, ratio: Percentage) -> IncomeRatioLevel {
+        let value = ratio.as_percentage();
+        if value <= self.healthy_max.as_percentage() {
+            IncomeRatioLevel::Healthy
+        } else if value <= self.caution_max.as_percentage() {
+            IncomeRatioLevel::Caution
+        } else {
+            IncomeRatioLevel::High
+        }
+    }
+}
+
+/// Share of gross monthly income that goes to debt payments
+///
+/// Returns `FinancialError::DivisionByZero` when there is no income, since
+/// the ratio is undefined rather than zero.
+pub fn debt_to_income(monthly_debt_payments: Money, monthly_income: Money) -> Result<Percentage> {
+    income_ratio(monthly_debt_payments, monthly_income)
+}
+
+/// Share of gross monthly income taken by a single loan payment
+///
+/// Errors like [`debt_to_income`] when there is no income.
+pub fn payment_to_income(monthly_payment: Money, monthly_income: Money) -> Result<Percentage> {
+    income_ratio(monthly_payment, monthly_income)
+}
+
+fn income_ratio(payments: Money, income: Money) -> Result<Percentage> {
+    if payments.currency() != income.currency() {
+        return Err(FinancialError::CurrencyMismatch {
+            expected: income.currency(),
+            actual: payments.currency(),
+        });
+    }
+    if payments.is_negative() {
+        return Err(FinancialError::ValidationError(
+            "Monthly payments cannot be negative".to_string(),
+        ));
+    }
+    if income.amount().is_zero() {
+        return Err(FinancialError::DivisionByZero);
+    }
+    if income.is_negative() {
+        return Err(FinancialError::ValidationError(
+            "Monthly income cannot be negative".to_string(),
+        ));
+    }
+
+    let ratio = payments
+        .amount()
+        .checked_div(income.amount())
+        .ok_or(FinancialError::Overflow)?;
+    Percentage::from_decimal(ratio)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Currency;
+
+    fn usd(amount: rust_decimal::Decimal) -> Money {
+        Money::new(amount, Currency::USD).unwrap()
+    }
+
+    #[test]
+    fn test_debt_to_income_classified_across_boundaries() {
+        let thresholds = IncomeRatioThresholds::debt_to_income();
+        let income = usd(dec!(5000));
+
+        let cases = [
+            (dec!(0), IncomeRatioLevel::Healthy),
+            (dec!(1800), IncomeRatioLevel::Healthy), // exactly 36%
+            (dec!(1800.50), IncomeRatioLevel::Caution),
+            (dec!(2150), IncomeRatioLevel::Caution), // exactly 43%
+            (dec!(2150.50), IncomeRatioLevel::High),
+            (dec!(6000), IncomeRatioLevel::High),
+        ];
+        for (payments, expected) in cases {
+            let ratio = debt_to_income(usd(payments), income).unwrap();
+            assert_eq!(
+                thresholds.classify(ratio),
+                expected,
+                "payments {}",
+                payments
+            );
+        }
+        assert_eq!(
+            debt_to_income(usd(dec!(1800)), income)
+                .unwrap()
+                .as_percentage(),
+            dec!(36)
+        );
+
+        // Payment-to-income uses its own, tighter bands
+        let pti = payment_to_income(usd(dec!(600)), income).unwrap();
+        assert_eq!(pti.as_percentage(), dec!(12));
+        assert_eq!(
+            IncomeRatioThresholds::payment_to_income().classify(pti),
+            IncomeRatioLevel::Caution
+        );
+
+        // Custom thresholds move the bands
+        let strict = IncomeRatioThresholds::new(
+            Percentage::from_percentage(dec!(20)).unwrap(),
+            Percentage::from_percentage(dec!(30)).unwrap(),
+        )
+        .unwrap();
+        let ratio = debt_to_income(usd(dec!(1800)), income).unwrap();
+        assert_eq!(strict.classify(ratio), IncomeRatioLevel::High);
+        assert!(IncomeRatioThresholds::new(
+            Percentage::from_percentage(dec!(30)).unwrap(),
+            Percentage::from_percentage(dec!(20)).unwrap(),
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_zero_income_is_an_error() {
+        assert_eq!(
+            debt_to_income(usd(dec!(500)), usd(dec!(0))),
+            Err(FinancialError::DivisionByZero)
+        );
+        assert_eq!(
+            payment_to_income(usd(dec!(0)), usd(dec!(0))),
+            Err(FinancialError::DivisionByZero)
+        );
+        assert!(matches!(
+            debt_to_income(usd(dec!(500)), usd(dec!(-100))),
+            Err(FinancialError::ValidationError(_))
+        ));
+        assert!(matches!(
+            debt_to_income(
+                usd(dec!(500)),
+                Money::new(dec!(5000), Currency::EUR).unwrap()
+            ),
+            Err(FinancialError::CurrencyMismatch { .. })
+        ));
+    }
+}
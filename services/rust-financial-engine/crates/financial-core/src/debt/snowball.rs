@@ -1,5 +1,9 @@
-use crate::debt::types::{DebtAccount, DebtStrategy, PaymentPlan, PaymentScheduleItem};
-use crate::{FinancialError, Money, Result};
+use crate::debt::cascade::CascadeSimulation;
+use crate::debt::types::{
+    ensure_amortizes, rate_changes_for, snowball_order, DebtAccount, DebtStrategy,
+    MinimumPaymentFloor, PaymentPlan, PaymentScheduleItem, RateChangeEvent,
+};
+use crate::{system_clock, CalcContext, Clock, FinancialError, Money, Result};
 use chrono::{DateTime, Duration, Utc};
 /// Debt Snowball Strategy Implementation
 ///
@@ -7,11 +11,16 @@ use chrono::{DateTime, Duration, Utc};
 /// regardless of interest rate. This provides psychological wins and momentum.
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
+use std::sync::Arc;
 
 /// Debt Snowball calculator for payment optimization
 pub struct SnowballCalculator {
     extra_payment_budget: Money,
     payment_frequency: PaymentFrequency,
+    rate_changes: Vec<RateChangeEvent>,
+    minimum_payment_floor: MinimumPaymentFloor,
+    context: CalcContext,
+    clock: Arc<dyn Clock>,
 }
 
 /// Payment frequency options
@@ -28,6 +37,10 @@ impl SnowballCalculator {
         Self {
             extra_payment_budget,
             payment_frequency: PaymentFrequency::Monthly,
+            rate_changes: Vec::new(),
+            minimum_payment_floor: MinimumPaymentFloor::default(),
+            context: CalcContext::default(),
+            clock: system_clock(),
         }
     }
 
@@ -37,6 +50,31 @@ impl SnowballCalculator {
         self
     }
 
+    /// Apply interest rate changes when their effective date is reached
+    pub fn with_rate_changes(mut self, rate_changes: Vec<RateChangeEvent>) -> Self {
+        self.rate_changes = rate_changes;
+        self
+    }
+
+    /// Raise each period's payment to a floor instead of rejecting debts whose
+    /// minimum payment does not cover interest
+    pub fn with_minimum_payment_floor(mut self, floor: MinimumPaymentFloor) -> Self {
+        self.minimum_payment_floor = floor;
+        self
+    }
+
+    /// Round interest charges and payments to the user's precision
+    pub fn with_context(mut self, context: CalcContext) -> Self {
+        self.context = context;
+        self
+    }
+
+    /// Start payment schedules from `clock`'s current time
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
     /// Calculate optimal snowball payment plan for multiple debts
     pub fn calculate_payment_plan(&self, debts: &[DebtAccount]) -> Result<Vec<PaymentPlan>> {
         if debts.is_empty() {
@@ -58,37 +96,19 @@ impl SnowballCalculator {
 
         // Sort debts by balance (snowball method)
         let mut sorted_debts = debts.to_vec();
-        sorted_debts.sort_by(|a, b| a.balance.amount().cmp(&b.balance.amount()));
-
-        let mut payment_plans: Vec<PaymentPlan> = Vec::new();
-        let remaining_extra_budget = self.extra_payment_budget.clone();
-        let mut debt_payoff_dates = Vec::new();
+        sorted_debts.sort_by(snowball_order);
 
-        for (index, debt) in sorted_debts.iter().enumerate() {
-            let extra_payment = if index == 0 {
-                // First debt gets all extra payment
-                remaining_extra_budget.clone()
-            } else {
-                // Later debts get rolled-over payments from previously paid-off debts
-                let mut total_extra = remaining_extra_budget.clone();
-                for prev_index in 0..index {
-                    if let Some(prev_plan) = payment_plans.get(prev_index) {
-                        total_extra = total_extra.add(
-                            &prev_plan
-                                .monthly_payment
-                                .subtract(&sorted_debts[prev_index].minimum_payment)?,
-                        )?;
-                    }
-                }
-                total_extra
-            };
-
-            let payment_plan = self.calculate_single_debt_plan(debt, &extra_payment)?;
-            debt_payoff_dates.push(payment_plan.payoff_date);
-            payment_plans.push(payment_plan);
+        CascadeSimulation {
+            strategy: DebtStrategy::Snowball,
+            extra_payment_budget: &self.extra_payment_budget,
+            rate_changes: &self.rate_changes,
+            minimum_payment_floor: self.minimum_payment_floor,
+            context: self.context,
+            clock: self.clock.as_ref(),
+            period_rate: |rate: &crate::types::Rate| self.calculate_monthly_rate(rate),
+            next_payment_date: |date| self.next_payment_date(date),
         }
-
-        Ok(payment_plans)
+        .run(&sorted_debts)
     }
 
     /// Calculate payment plan for a single debt
@@ -102,15 +122,28 @@ impl SnowballCalculator {
         let mut payment_schedule = Vec::new();
         let mut total_interest = Money::new_unchecked(Decimal::ZERO, debt.balance.currency());
         let mut payment_number = 1;
-        let mut current_date = Utc::now();
+        let mut current_date = self.clock.now();
 
         // Calculate monthly interest rate
-        let monthly_rate = self.calculate_monthly_rate(&debt.interest_rate)?;
+        let mut monthly_rate = self.calculate_monthly_rate(&debt.interest_rate)?;
+        let mut pending_rate_changes = rate_changes_for(&self.rate_changes, debt.id).into_iter().peekable();
 
         while remaining_balance.amount() > dec!(0.01) && payment_number <= 600 {
             // Max 50 years
-            let interest_charge = remaining_balance.multiply(monthly_rate)?;
-            let principal_payment = total_monthly_payment.subtract(&interest_charge)?;
+            while let Some(change) =
+                pending_rate_changes.next_if(|c| c.effective_date <= current_date)
+            {
+                monthly_rate = self.calculate_monthly_rate(&change.new_rate)?;
+            }
+
+            let interest_charge = self.context.round_money(remaining_balance.multiply(monthly_rate)?);
+            let period_payment = self.context.round_money(self.minimum_payment_floor.apply(
+                total_monthly_payment,
+                interest_charge,
+                remaining_balance,
+            )?);
+            ensure_amortizes(debt, &period_payment, &interest_charge)?;
+            let principal_payment = period_payment.subtract(&interest_charge)?;
 
             // Ensure we don't overpay
             let actual_principal = if principal_payment.amount() > remaining_balance.amount() {
@@ -121,6 +154,11 @@ impl SnowballCalculator {
 
             let actual_payment = interest_charge.add(&actual_principal)?;
             remaining_balance = remaining_balance.subtract(&actual_principal)?;
+            debug_assert!(
+                remaining_balance.amount() >= Decimal::ZERO,
+                "rounding drove the remaining balance negative: {}",
+                remaining_balance
+            );
             total_interest = total_interest.add(&interest_charge)?;
 
             payment_schedule.push(PaymentScheduleItem {
@@ -159,7 +197,7 @@ impl SnowballCalculator {
             total_interest,
             payoff_date,
             payment_schedule,
-            created_at: Utc::now(),
+            created_at: self.clock.now(),
         })
     }
 
@@ -184,13 +222,13 @@ impl SnowballCalculator {
             .iter()
             .map(|p| p.payoff_date)
             .max()
-            .unwrap_or(Utc::now());
+            .unwrap_or_else(|| self.clock.now());
 
         let minimum_final_date = minimum_plans
             .iter()
             .map(|p| p.payoff_date)
             .max()
-            .unwrap_or(Utc::now());
+            .unwrap_or_else(|| self.clock.now());
 
         let time_savings_days = (minimum_final_date - snowball_final_date).num_days();
         let time_savings_months = time_savings_days / 30;
@@ -207,7 +245,7 @@ impl SnowballCalculator {
     /// Get debt prioritization order for snowball method
     pub fn get_priority_order(&self, debts: &[DebtAccount]) -> Vec<(usize, String, Money)> {
         let mut indexed_debts: Vec<(usize, &DebtAccount)> = debts.iter().enumerate().collect();
-        indexed_debts.sort_by(|a, b| a.1.balance.amount().cmp(&b.1.balance.amount()));
+        indexed_debts.sort_by(|a, b| snowball_order(a.1, b.1));
 
         indexed_debts
             .into_iter()
@@ -376,6 +414,56 @@ mod tests {
         assert!(!plan.payment_schedule.is_empty());
     }
 
+    #[test]
+    fn test_rate_increase_midway_raises_interest() {
+        let extra_payment = Money::new(dec!(50), Currency::USD).unwrap();
+        let mut debt = DebtAccount::new(
+            Uuid::new_v4(),
+            "Credit Card".to_string(),
+            DebtType::CreditCard,
+            Money::new(dec!(3000), Currency::USD).unwrap(),
+            Rate::new(
+                Percentage::from_percentage(dec!(15.0)).unwrap(),
+                Period::Annual,
+            ),
+            Money::new(dec!(100), Currency::USD).unwrap(),
+        );
+
+        let baseline = SnowballCalculator::new(extra_payment)
+            .calculate_single_debt_plan(&debt, &extra_payment)
+            .unwrap();
+
+        // APR jumps to 24.99% halfway through the payoff
+        let halfway = baseline.payment_schedule.len() / 2;
+        let effective_date = baseline.payment_schedule[halfway].payment_date;
+        let new_rate = Rate::new(
+            Percentage::from_percentage(dec!(24.99)).unwrap(),
+            Period::Annual,
+        );
+        let change = debt.update_rate(new_rate, effective_date).unwrap();
+
+        let updated = SnowballCalculator::new(extra_payment)
+            .with_rate_changes(vec![change])
+            .calculate_single_debt_plan(&debt, &extra_payment)
+            .unwrap();
+
+        // Payments before the change are unaffected
+        assert_eq!(
+            updated.payment_schedule[halfway - 1].interest,
+            baseline.payment_schedule[halfway - 1].interest
+        );
+
+        // From the effective date interest accrues at the new monthly rate
+        let expected_interest = updated.payment_schedule[halfway - 1]
+            .remaining_balance
+            .multiply(dec!(0.2499) / dec!(12))
+            .unwrap();
+        assert_eq!(updated.payment_schedule[halfway].interest, expected_interest);
+
+        assert!(updated.total_interest.amount() > baseline.total_interest.amount());
+        assert!(updated.payoff_date > baseline.payoff_date);
+    }
+
     #[test]
     fn test_payment_frequencies() {
         let extra_payment = Money::new(dec!(100), Currency::USD).unwrap();
@@ -423,4 +511,168 @@ mod tests {
         assert!(!wins.is_empty());
         assert_eq!(wins[0].debt_name, "Small Debt");
     }
+
+    fn usd(amount: Decimal) -> Money {
+        Money::new(amount, Currency::USD).unwrap()
+    }
+
+    fn card(name: &str, balance: Decimal, apr: Decimal, minimum: Decimal) -> DebtAccount {
+        DebtAccount::new(
+            Uuid::new_v4(),
+            name.to_string(),
+            DebtType::CreditCard,
+            Money::new_unchecked(balance, Currency::USD),
+            Rate::new(Percentage::from_percentage(apr).unwrap(), Period::Annual),
+            usd(minimum),
+        )
+    }
+
+    #[test]
+    fn test_overpayment_surplus_rolls_to_next_debt_in_same_period() {
+        let calculator = SnowballCalculator::new(usd(dec!(400)));
+        let debts = vec![
+            card("Visa", dec!(5000), dec!(18), dec!(100)),
+            card("Store Card", dec!(300), dec!(12), dec!(25)),
+        ];
+
+        let plans = calculator.calculate_payment_plan(&debts).unwrap();
+        let (store, visa) = (&plans[0], &plans[1]);
+        assert_eq!(store.debt_name, "Store Card");
+
+        // The store card is paid off in the first period: balance plus one
+        // month of interest, and no more
+        assert_eq!(store.payment_count(), 1);
+        let payoff = &store.payment_schedule[0];
+        assert_eq!(payoff.interest.amount(), dec!(3));
+        assert_eq!(payoff.payment_amount.amount(), dec!(303));
+        assert_eq!(payoff.remaining_balance.amount(), Decimal::ZERO);
+        assert_eq!(store.total_interest.amount(), dec!(3));
+
+        // The $122 it did not need goes to the Visa in that same period
+        assert_eq!(visa.payment_schedule[0].payment_date, payoff.payment_date);
+        assert_eq!(visa.payment_schedule[0].payment_amount.amount(), dec!(222));
+
+        // Afterwards the whole $525 budget goes to the Visa until the final
+        // payment, which is capped at what is owed
+        assert_eq!(visa.payment_schedule[1].payment_amount.amount(), dec!(525));
+        let last = visa.payment_schedule.last().unwrap();
+        assert_eq!(last.remaining_balance.amount(), Decimal::ZERO);
+        assert!(last.payment_amount.amount() <= dec!(525));
+        assert!(visa
+            .payment_schedule
+            .iter()
+            .all(|item| item.remaining_balance.amount() >= Decimal::ZERO));
+
+        let interest: Decimal = visa.payment_schedule.iter().map(|item| item.interest.amount()).sum();
+        assert_eq!(visa.total_interest.amount(), interest);
+        let paid: Decimal = visa.payment_schedule.iter().map(|item| item.payment_amount.amount()).sum();
+        assert_eq!(paid, dec!(5000) + interest);
+    }
+
+    #[test]
+    fn test_credit_balance_is_already_paid_off() {
+        let calculator = SnowballCalculator::new(usd(dec!(100)));
+        let debts = vec![
+            card("Overpaid Card", dec!(-40), dec!(22), dec!(0)),
+            card("Visa", dec!(1000), dec!(18), dec!(50)),
+        ];
+
+        let plans = calculator.calculate_payment_plan(&debts).unwrap();
+        assert_eq!(plans[0].debt_name, "Overpaid Card");
+        assert!(plans[0].payment_schedule.is_empty());
+        assert_eq!(plans[0].total_interest.amount(), Decimal::ZERO);
+
+        assert_eq!(plans[1].monthly_payment.amount(), dec!(150));
+        assert_eq!(
+            plans[1].payment_schedule.last().unwrap().remaining_balance.amount(),
+            Decimal::ZERO
+        );
+    }
+
+    #[test]
+    fn test_context_rounds_schedule_to_configured_precision() {
+        use crate::types::{CalcContext, RoundingMode};
+
+        // 19.99% APR on $1,234 accrues $20.556... in the first month
+        let debts = vec![card("Visa", dec!(1234), dec!(19.99), dec!(60))];
+        let cents = CalcContext::new(2, RoundingMode::HalfEven).unwrap();
+        let whole_dollars = CalcContext::new(0, RoundingMode::TowardZero).unwrap();
+
+        let in_cents = SnowballCalculator::new(usd(dec!(40)))
+            .with_context(cents)
+            .calculate_payment_plan(&debts)
+            .unwrap();
+        let in_dollars = SnowballCalculator::new(usd(dec!(40)))
+            .with_context(whole_dollars)
+            .calculate_payment_plan(&debts)
+            .unwrap();
+
+        assert_eq!(in_cents[0].payment_schedule[0].interest.amount(), dec!(20.56));
+        assert_eq!(in_dollars[0].payment_schedule[0].interest.amount(), dec!(20));
+        assert_ne!(in_cents[0].total_interest, in_dollars[0].total_interest);
+
+        for (plan, scale) in [(&in_cents[0], 2), (&in_dollars[0], 0)] {
+            assert!(plan.total_interest.amount().scale() <= scale);
+            assert!(plan.payment_schedule.iter().all(|item| {
+                item.interest.amount().scale() <= scale && item.payment_amount.amount().scale() <= scale
+            }));
+        }
+
+        // Without a context, interest keeps the full money scale
+        let unrounded = SnowballCalculator::new(usd(dec!(40))).calculate_payment_plan(&debts).unwrap();
+        assert!(unrounded[0].payment_schedule[0].interest.amount().scale() > 2);
+    }
+
+    #[test]
+    fn test_minimum_below_interest_is_negative_amortization() {
+        let calculator = SnowballCalculator::default();
+
+        let debt = DebtAccount::new(
+            Uuid::new_v4(),
+            "Store Card".to_string(),
+            DebtType::CreditCard,
+            Money::new(dec!(3000), Currency::USD).unwrap(),
+            Rate::new(
+                Percentage::from_percentage(dec!(36)).unwrap(),
+                Period::Annual,
+            ),
+            Money::new(dec!(50), Currency::USD).unwrap(),
+        );
+
+        let result = calculator.calculate_payment_plan(&[debt]);
+        assert!(matches!(result, Err(FinancialError::NegativeAmortization { .. })));
+    }
+
+    #[test]
+    fn test_fixed_clock_gives_exact_payoff_dates() {
+        use crate::MockClock;
+        use chrono::TimeZone;
+
+        let start = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let clock = std::sync::Arc::new(MockClock::new(start));
+        let calculator = SnowballCalculator::new(usd(dec!(100))).with_clock(clock.clone());
+        let debts = vec![
+            card("Store Card", dec!(200), dec!(0), dec!(50)),
+            card("Visa", dec!(600), dec!(0), dec!(50)),
+        ];
+
+        // $200 a period clears the store card in the second payment and the
+        // Visa in the fourth, 30 days apart from the first on the clock
+        let plans = calculator.calculate_payment_plan(&debts).unwrap();
+        assert_eq!(plans[0].payment_schedule[0].payment_date, start);
+        assert_eq!(plans[0].payoff_date, start + Duration::days(30));
+        assert_eq!(plans[1].payoff_date, start + Duration::days(90));
+        assert_eq!(plans[1].created_at, start);
+
+        let single = calculator
+            .calculate_single_debt_plan(&debts[1], &usd(dec!(100)))
+            .unwrap();
+        assert_eq!(single.payment_schedule.len(), 4);
+        assert_eq!(single.payoff_date, start + Duration::days(90));
+
+        // Running again later shifts every date by the same amount
+        clock.advance(Duration::days(7));
+        let later = calculator.calculate_payment_plan(&debts).unwrap();
+        assert_eq!(later[1].payoff_date, start + Duration::days(97));
+    }
 }
@@ -1,7 +1,9 @@
-use crate::types::{Money, Percentage, Rate};
+use crate::debt::cascade::PAID_OFF_THRESHOLD;
+use crate::types::{Money, Percentage, Period, Rate};
 use chrono::{DateTime, Utc};
 /// Debt management types and structures
 use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -23,6 +25,15 @@ pub struct DebtAccount {
     pub updated_at: DateTime<Utc>,
 }
 
+/// Interest rate change on a debt, applied from its effective date onward
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateChangeEvent {
+    pub debt_id: Uuid,
+    pub previous_rate: Rate,
+    pub new_rate: Rate,
+    pub effective_date: DateTime<Utc>,
+}
+
 /// Types of debt for categorization
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DebtType {
@@ -39,10 +50,11 @@ pub enum DebtType {
 /// Debt payoff strategy options
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DebtStrategy {
-    Snowball,      // Pay minimum on all, extra on lowest balance
-    Avalanche,     // Pay minimum on all, extra on highest interest rate
-    Custom,        // User-defined payment allocation
-    Consolidation, // Combine debts into single payment
+    Snowball,        // Pay minimum on all, extra on lowest balance
+    Avalanche,       // Pay minimum on all, extra on highest interest rate
+    Custom,          // Extra payments follow a user-chosen debt order
+    Consolidation,   // Combine debts into single payment
+    HybridKickstart, // Clear the smallest balance first, then avalanche the rest
 }
 
 /// Payment plan for a single debt
@@ -153,7 +165,10 @@ pub struct NegotiationOpportunity {
     pub debt_name: String,
     pub current_balance: Money,
     pub negotiation_type: NegotiationType,
+    /// Expected savings, net of `settlement_tax`
     pub potential_savings: Money,
+    /// Estimated income tax owed on a forgiven balance
+    pub settlement_tax: Money,
     pub success_probability: Percentage,
     pub negotiation_strategy: String,
     pub talking_points: Vec<String>,
@@ -170,6 +185,109 @@ pub enum NegotiationType {
     DebtForgiveness,
 }
 
+impl NegotiationType {
+    /// Whether the negotiation forgives part of the balance, which is usually
+    /// taxed as income
+    pub fn forgives_balance(&self) -> bool {
+        matches!(
+            self,
+            NegotiationType::BalanceSettlement | NegotiationType::DebtForgiveness
+        )
+    }
+}
+
+/// Expected outcome of negotiating one kind of debt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NegotiationAssumption {
+    pub negotiation_type: NegotiationType,
+    /// Share of the balance saved, as a percentage (e.g. 20 for 20%)
+    pub savings_pct: Decimal,
+    /// Chance the creditor agrees, as a percentage
+    pub success_probability_pct: Decimal,
+}
+
+impl NegotiationAssumption {
+    pub fn new(
+        negotiation_type: NegotiationType,
+        savings_pct: Decimal,
+        success_probability_pct: Decimal,
+    ) -> Self {
+        Self {
+            negotiation_type,
+            savings_pct,
+            success_probability_pct,
+        }
+    }
+}
+
+/// Assumptions behind negotiation opportunities
+///
+/// The defaults are rough industry figures; advisors can replace any of them
+/// to match what they see from creditors.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NegotiationModel {
+    /// Credit card balances above this use `high_balance_credit_card`
+    pub high_balance_threshold: Decimal,
+    pub high_balance_credit_card: NegotiationAssumption,
+    pub credit_card: NegotiationAssumption,
+    pub medical_debt: NegotiationAssumption,
+    pub personal_loan: NegotiationAssumption,
+    /// Every other debt type
+    pub other: NegotiationAssumption,
+    /// Marginal income tax rate charged on forgiven balances, as a
+    /// percentage; `None` reports savings before tax
+    pub settlement_tax_rate_pct: Option<Decimal>,
+}
+
+impl NegotiationModel {
+    /// Assumption that applies to `debt`
+    pub fn assumption_for(&self, debt: &DebtAccount) -> &NegotiationAssumption {
+        match debt.debt_type {
+            DebtType::CreditCard if debt.balance.amount() > self.high_balance_threshold => {
+                &self.high_balance_credit_card
+            }
+            DebtType::CreditCard => &self.credit_card,
+            DebtType::MedicalDebt => &self.medical_debt,
+            DebtType::PersonalLoan => &self.personal_loan,
+            _ => &self.other,
+        }
+    }
+}
+
+impl Default for NegotiationModel {
+    fn default() -> Self {
+        Self {
+            high_balance_threshold: dec!(5000),
+            high_balance_credit_card: NegotiationAssumption::new(
+                NegotiationType::InterestRateReduction,
+                dec!(20),
+                dec!(70),
+            ),
+            credit_card: NegotiationAssumption::new(
+                NegotiationType::PaymentPlanModification,
+                dec!(10),
+                dec!(60),
+            ),
+            medical_debt: NegotiationAssumption::new(
+                NegotiationType::BalanceSettlement,
+                dec!(40),
+                dec!(80),
+            ),
+            personal_loan: NegotiationAssumption::new(
+                NegotiationType::InterestRateReduction,
+                dec!(15),
+                dec!(50),
+            ),
+            other: NegotiationAssumption::new(
+                NegotiationType::PaymentPlanModification,
+                dec!(5),
+                dec!(30),
+            ),
+            settlement_tax_rate_pct: None,
+        }
+    }
+}
+
 impl DebtAccount {
     /// Create a new debt account
     pub fn new(
@@ -198,6 +316,38 @@ impl DebtAccount {
         }
     }
 
+    /// Change the interest rate of this debt from `effective_date` onward
+    ///
+    /// Changes that are already in effect update `interest_rate` immediately.
+    /// The returned event should be passed to the payoff calculators so that
+    /// future-dated changes are applied mid-simulation.
+    pub fn update_rate(
+        &mut self,
+        new_rate: Rate,
+        effective_date: DateTime<Utc>,
+    ) -> crate::Result<RateChangeEvent> {
+        if new_rate.as_decimal() < Decimal::ZERO {
+            return Err(crate::FinancialError::InterestRateError {
+                reason: format!("Interest rate cannot be negative: {}", new_rate.as_decimal()),
+            });
+        }
+
+        let event = RateChangeEvent {
+            debt_id: self.id,
+            previous_rate: self.interest_rate,
+            new_rate,
+            effective_date,
+        };
+
+        let now = Utc::now();
+        if effective_date <= now {
+            self.interest_rate = new_rate;
+            self.updated_at = now;
+        }
+
+        Ok(event)
+    }
+
     /// Calculate debt-to-limit ratio for credit cards
     pub fn debt_to_limit_ratio(&self) -> Option<Percentage> {
         if let Some(limit) = &self.credit_limit {
@@ -263,6 +413,214 @@ impl DebtAccount {
     }
 }
 
+/// Update the interest rate of the debt with `debt_id`
+pub fn update_debt_rate(
+    debts: &mut [DebtAccount],
+    debt_id: Uuid,
+    new_rate: Rate,
+    effective_date: DateTime<Utc>,
+) -> crate::Result<RateChangeEvent> {
+    let debt = debts
+        .iter_mut()
+        .find(|d| d.id == debt_id)
+        .ok_or_else(|| crate::FinancialError::NotFound {
+            resource: format!("Debt account {}", debt_id),
+        })?;
+
+    debt.update_rate(new_rate, effective_date)
+}
+
+/// Dashboard totals across a set of debts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DebtSummary {
+    pub debt_count: usize,
+    pub total_balance: Money,
+    pub total_minimum_payment: Money,
+    /// Annual rate weighted by each debt's outstanding balance
+    pub weighted_average_rate: Rate,
+}
+
+/// Total balance, total minimum payment, and blended APR of `debts`
+///
+/// Rates are converted to annual before blending. Debts with no balance or
+/// a credit balance carry no weight, and a set with nothing owed blends to 0%.
+/// A user with no debts gets an all-zero summary, in USD as there is no
+/// balance to take the currency from.
+pub fn debt_summary(debts: &[DebtAccount]) -> crate::Result<DebtSummary> {
+    let currency = debts
+        .first()
+        .map_or(crate::types::Currency::USD, |first| first.balance.currency());
+    let zero = Money::new_unchecked(Decimal::ZERO, currency);
+
+    let mut total_balance = zero;
+    let mut total_minimum_payment = zero;
+    let mut outstanding = Decimal::ZERO;
+    let mut weighted_rate = Decimal::ZERO;
+    for debt in debts {
+        total_balance = total_balance.add(&debt.balance)?;
+        total_minimum_payment = total_minimum_payment.add(&debt.minimum_payment)?;
+        if debt.balance.amount() > Decimal::ZERO {
+            let annual_rate = debt.interest_rate.convert_to_period(Period::Annual)?.as_decimal();
+            outstanding += debt.balance.amount();
+            weighted_rate += debt.balance.amount() * annual_rate;
+        }
+    }
+
+    let weighted_average_rate = if outstanding.is_zero() {
+        Decimal::ZERO
+    } else {
+        weighted_rate / outstanding
+    };
+
+    Ok(DebtSummary {
+        debt_count: debts.len(),
+        total_balance,
+        total_minimum_payment,
+        weighted_average_rate: Rate::new(Percentage::from_decimal(weighted_average_rate)?, Period::Annual),
+    })
+}
+
+/// When every debt is paid off with one combined extra-payment budget
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DebtFreeProjection {
+    pub strategy: DebtStrategy,
+    pub debt_free_date: DateTime<Utc>,
+    pub months_to_debt_free: u32,
+    pub total_interest: Money,
+    /// Debts in the order they are paid off
+    pub payoff_cascade: Vec<DebtPayoffMilestone>,
+}
+
+/// The point in a combined payoff where one debt is cleared
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DebtPayoffMilestone {
+    pub debt_id: Uuid,
+    pub debt_name: String,
+    pub payoff_date: DateTime<Utc>,
+    /// Payment that clears the debt, counted from the start of the plan
+    pub payoff_payment_number: u32,
+    pub total_interest: Money,
+}
+
+/// Rate changes for one debt in the order they take effect
+pub(crate) fn rate_changes_for(changes: &[RateChangeEvent], debt_id: Uuid) -> Vec<RateChangeEvent> {
+    let mut changes: Vec<RateChangeEvent> = changes
+        .iter()
+        .filter(|c| c.debt_id == debt_id)
+        .cloned()
+        .collect();
+    changes.sort_by_key(|c| c.effective_date);
+    changes
+}
+
+/// Snowball priority: smallest balance first, equal balances by id
+///
+/// Breaking ties on the id keeps plans identical whatever order the debts
+/// were loaded in.
+pub(crate) fn snowball_order(a: &DebtAccount, b: &DebtAccount) -> std::cmp::Ordering {
+    a.balance.amount().cmp(&b.balance.amount()).then_with(|| a.id.cmp(&b.id))
+}
+
+/// Avalanche priority: highest interest rate first, equal rates by id
+pub(crate) fn avalanche_order(a: &DebtAccount, b: &DebtAccount) -> std::cmp::Ordering {
+    b.interest_rate
+        .as_decimal()
+        .cmp(&a.interest_rate.as_decimal())
+        .then_with(|| a.id.cmp(&b.id))
+}
+
+/// Sort into hybrid kickstart priority: the smallest open balance first,
+/// then every other debt in avalanche order
+///
+/// Debts that are already paid off cannot be the kickstart, since clearing
+/// them is no win.
+pub(crate) fn sort_hybrid_kickstart(debts: &mut [DebtAccount]) {
+    debts.sort_by(avalanche_order);
+    let smallest = debts
+        .iter()
+        .enumerate()
+        .filter(|(_, debt)| debt.balance.amount() > PAID_OFF_THRESHOLD)
+        .min_by(|(_, a), (_, b)| snowball_order(a, b))
+        .map(|(index, _)| index);
+    if let Some(smallest) = smallest {
+        debts[..=smallest].rotate_right(1);
+    }
+}
+
+/// Sort into a user-chosen priority: the debts named in `order`, in that
+/// order, then any others in avalanche order
+///
+/// Every id in `order` must name one of `debts`, and only once.
+pub(crate) fn sort_custom_order(debts: &mut [DebtAccount], order: &[Uuid]) -> crate::Result<()> {
+    for (index, id) in order.iter().enumerate() {
+        let problem = if order[..index].contains(id) {
+            "listed twice"
+        } else if !debts.iter().any(|d| d.id == *id) {
+            "not one of the debts"
+        } else {
+            continue;
+        };
+        return Err(crate::FinancialError::InvalidParameter {
+            parameter: "payment_order".to_string(),
+            value: format!("{} is {}", id, problem),
+        });
+    }
+    let position = |debt: &DebtAccount| order.iter().position(|id| *id == debt.id).unwrap_or(order.len());
+    debts.sort_by(|a, b| position(a).cmp(&position(b)).then_with(|| avalanche_order(a, b)));
+    Ok(())
+}
+
+/// Lowest payment a payoff simulation makes in each period
+///
+/// Some debts carry a minimum payment below the interest that accrues each
+/// period, so the balance never falls. Without a floor the simulators reject
+/// such debts with `FinancialError::NegativeAmortization`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MinimumPaymentFloor {
+    /// Pay the scheduled amount as-is
+    #[default]
+    None,
+    /// Pay at least the accrued interest plus this fraction of the balance
+    /// (e.g. `0.01` for the common credit card "interest + 1%" minimum)
+    InterestPlusPrincipal(Decimal),
+}
+
+impl MinimumPaymentFloor {
+    /// Payment for one period, raised to the floor when it falls below it
+    pub fn apply(&self, scheduled: Money, interest: Money, balance: Money) -> crate::Result<Money> {
+        match self {
+            MinimumPaymentFloor::None => Ok(scheduled),
+            MinimumPaymentFloor::InterestPlusPrincipal(fraction) => {
+                if *fraction <= Decimal::ZERO || *fraction > Decimal::ONE {
+                    return Err(crate::FinancialError::parameter_out_of_range(
+                        "minimum_payment_floor",
+                        "0",
+                        "1",
+                        &fraction.to_string(),
+                    ));
+                }
+                let floor = interest.add(&balance.multiply(*fraction)?)?;
+                Ok(if floor.amount() > scheduled.amount() { floor } else { scheduled })
+            }
+        }
+    }
+}
+
+/// Reject a period whose payment does not reduce the principal
+///
+/// Covers both negative amortization (payment below interest) and
+/// interest-only payments, either of which would never pay the debt off.
+pub(crate) fn ensure_amortizes(debt: &DebtAccount, payment: &Money, interest: &Money) -> crate::Result<()> {
+    if payment.amount() <= interest.amount() {
+        return Err(crate::FinancialError::NegativeAmortization {
+            debt: debt.name.clone(),
+            payment: payment.to_string(),
+            interest: interest.to_string(),
+        });
+    }
+    Ok(())
+}
+
 impl PaymentPlan {
     /// Calculate total cost of debt (principal + interest)
     pub fn total_cost(&self) -> Money {
@@ -329,6 +687,7 @@ impl std::fmt::Display for DebtStrategy {
             DebtStrategy::Avalanche => write!(f, "Debt Avalanche"),
             DebtStrategy::Custom => write!(f, "Custom Strategy"),
             DebtStrategy::Consolidation => write!(f, "Debt Consolidation"),
+            DebtStrategy::HybridKickstart => write!(f, "Avalanche with Snowball Kickstart"),
         }
     }
 }
@@ -468,4 +827,72 @@ mod tests {
                 > high_interest_debt.snowball_priority_score()
         );
     }
+
+    #[test]
+    fn test_debt_summary_weights_rates_by_balance() {
+        let debt = |balance: Decimal, apr: Decimal, minimum: Decimal| {
+            DebtAccount::new(
+                Uuid::new_v4(),
+                "Debt".to_string(),
+                DebtType::CreditCard,
+                Money::new(balance, Currency::USD).unwrap(),
+                Rate::new(Percentage::from_percentage(apr).unwrap(), Period::Annual),
+                Money::new(minimum, Currency::USD).unwrap(),
+            )
+        };
+        let mut student_loan = debt(dec!(9000), dec!(0), dec!(150));
+        student_loan.interest_rate = Rate::new(Percentage::from_percentage(dec!(0.5)).unwrap(), Period::Monthly);
+        let debts = vec![debt(dec!(1000), dec!(24), dec!(35)), student_loan, debt(dec!(0), dec!(29.99), dec!(0))];
+
+        let summary = debt_summary(&debts).unwrap();
+        assert_eq!(summary.debt_count, 3);
+        assert_eq!(summary.total_balance.amount(), dec!(10000));
+        assert_eq!(summary.total_minimum_payment.amount(), dec!(185));
+        // (1000 x 24% + 9000 x 6%) / 10000, not the 15% simple mean of the owed rates
+        assert_eq!(summary.weighted_average_rate.as_decimal(), dec!(0.078));
+        assert_eq!(summary.weighted_average_rate.period(), Period::Annual);
+
+        let mut mixed = debts.clone();
+        mixed[1].balance = Money::new(dec!(9000), Currency::EUR).unwrap();
+        assert!(matches!(
+            debt_summary(&mixed),
+            Err(crate::FinancialError::CurrencyMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_debt_summary_of_no_debts_is_zero() {
+        let summary = debt_summary(&[]).unwrap();
+        assert_eq!(summary.debt_count, 0);
+        assert!(summary.total_balance.amount().is_zero());
+        assert!(summary.total_minimum_payment.amount().is_zero());
+        assert!(summary.weighted_average_rate.as_decimal().is_zero());
+        assert_eq!(summary.weighted_average_rate.period(), Period::Annual);
+    }
+
+    #[test]
+    fn test_update_debt_rate() {
+        let mut debts = vec![DebtAccount::new(
+            Uuid::new_v4(),
+            "Credit Card".to_string(),
+            DebtType::CreditCard,
+            Money::new(dec!(3000), Currency::USD).unwrap(),
+            Rate::new(Percentage::from_percentage(dec!(19.99)).unwrap(), Period::Annual),
+            Money::new(dec!(90), Currency::USD).unwrap(),
+        )];
+        let debt_id = debts[0].id;
+        let new_rate = Rate::new(Percentage::from_percentage(dec!(24.99)).unwrap(), Period::Annual);
+
+        // Future-dated changes leave the current rate alone
+        let future = Utc::now() + chrono::Duration::days(60);
+        let event = update_debt_rate(&mut debts, debt_id, new_rate, future).unwrap();
+        assert_eq!(event.previous_rate.as_decimal(), dec!(0.1999));
+        assert_eq!(debts[0].interest_rate.as_decimal(), dec!(0.1999));
+
+        // Changes already in effect apply immediately
+        update_debt_rate(&mut debts, debt_id, new_rate, Utc::now()).unwrap();
+        assert_eq!(debts[0].interest_rate.as_decimal(), dec!(0.2499));
+
+        assert!(update_debt_rate(&mut debts, Uuid::new_v4(), new_rate, Utc::now()).is_err());
+    }
 }
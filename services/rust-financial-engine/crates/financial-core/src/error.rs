@@ -9,6 +9,9 @@ use thiserror::Error;
 pub type Result<T> = std::result::Result<T, FinancialError>;
 
 /// Comprehensive error types for financial calculations
+///
+/// Messages are part of the public contract and only change with a
+/// release note; match on [`FinancialError::code`] rather than parsing them.
 #[derive(Error, Debug, Clone, PartialEq)]
 pub enum FinancialError {
     /// Mathematical computation errors
@@ -31,6 +34,9 @@ pub enum FinancialError {
         actual: Currency,
     },
 
+    #[error("Unsupported currency: {code}")]
+    UnsupportedCurrency { code: String },
+
     #[error("Unsupported currency operation: {operation}")]
     UnsupportedCurrencyOperation { operation: String },
 
@@ -59,9 +65,15 @@ pub enum FinancialError {
     #[error("Insufficient portfolio data: {missing}")]
     InsufficientPortfolioData { missing: String },
 
+    #[error("Infeasible optimization constraints: {reason}")]
+    InfeasibleConstraints { reason: String },
+
     #[error("Invalid asset allocation: total must equal 100%, got {total}%")]
     InvalidAssetAllocation { total: String },
 
+    #[error("Cannot sell {requested} units: only {available} held")]
+    InsufficientLotQuantity { requested: String, available: String },
+
     /// Debt calculation errors
     #[error("Invalid debt configuration: {reason}")]
     InvalidDebtConfiguration { reason: String },
@@ -69,6 +81,13 @@ pub enum FinancialError {
     #[error("Debt calculation failed: {reason}")]
     DebtCalculationFailed { reason: String },
 
+    #[error("Payment of {payment} does not cover {interest} interest on {debt}; it never pays off under minimum payments")]
+    NegativeAmortization {
+        debt: String,
+        payment: String,
+        interest: String,
+    },
+
     /// Time value of money errors
     #[error("Time value calculation error: {reason}")]
     TimeValueError { reason: String },
@@ -135,6 +154,53 @@ pub enum FinancialError {
 }
 
 impl FinancialError {
+    /// Stable machine-readable identifier for the variant
+    ///
+    /// API layers expose this in error extensions; unlike the message it never
+    /// carries runtime values, so clients can match on it.
+    pub fn code(&self) -> &'static str {
+        match self {
+            FinancialError::DivisionByZero => "DIVISION_BY_ZERO",
+            FinancialError::MathError { .. } => "MATH_ERROR",
+            FinancialError::Overflow => "OVERFLOW",
+            FinancialError::Underflow => "UNDERFLOW",
+            FinancialError::CurrencyMismatch { .. } => "CURRENCY_MISMATCH",
+            FinancialError::UnsupportedCurrency { .. } => "UNSUPPORTED_CURRENCY",
+            FinancialError::UnsupportedCurrencyOperation { .. } => "UNSUPPORTED_CURRENCY_OPERATION",
+            FinancialError::InvalidExchangeRate { .. } => "INVALID_EXCHANGE_RATE",
+            FinancialError::ValidationError(_) => "VALIDATION_ERROR",
+            FinancialError::InvalidParameter { .. } => "INVALID_PARAMETER",
+            FinancialError::ParameterOutOfRange { .. } => "PARAMETER_OUT_OF_RANGE",
+            FinancialError::PortfolioOptimizationFailed { .. } => "PORTFOLIO_OPTIMIZATION_FAILED",
+            FinancialError::InsufficientPortfolioData { .. } => "INSUFFICIENT_PORTFOLIO_DATA",
+            FinancialError::InfeasibleConstraints { .. } => "INFEASIBLE_CONSTRAINTS",
+            FinancialError::InvalidAssetAllocation { .. } => "INVALID_ASSET_ALLOCATION",
+            FinancialError::InsufficientLotQuantity { .. } => "INSUFFICIENT_LOT_QUANTITY",
+            FinancialError::InvalidDebtConfiguration { .. } => "INVALID_DEBT_CONFIGURATION",
+            FinancialError::DebtCalculationFailed { .. } => "DEBT_CALCULATION_FAILED",
+            FinancialError::NegativeAmortization { .. } => "NEGATIVE_AMORTIZATION",
+            FinancialError::TimeValueError { .. } => "TIME_VALUE_ERROR",
+            FinancialError::InvalidTimePeriod { .. } => "INVALID_TIME_PERIOD",
+            FinancialError::InterestRateError { .. } => "INTEREST_RATE_ERROR",
+            FinancialError::RiskCalculationFailed { .. } => "RISK_CALCULATION_FAILED",
+            FinancialError::InsufficientRiskData { .. } => "INSUFFICIENT_RISK_DATA",
+            FinancialError::InvalidRiskModel { .. } => "INVALID_RISK_MODEL",
+            FinancialError::BudgetAnalysisFailed { .. } => "BUDGET_ANALYSIS_FAILED",
+            FinancialError::InvalidBudgetData { .. } => "INVALID_BUDGET_DATA",
+            FinancialError::DatabaseError { .. } => "DATABASE_ERROR",
+            FinancialError::CacheError { .. } => "CACHE_ERROR",
+            FinancialError::ExternalServiceError { .. } => "EXTERNAL_SERVICE_ERROR",
+            FinancialError::RateLimitExceeded { .. } => "RATE_LIMIT_EXCEEDED",
+            FinancialError::ConfigurationError { .. } => "CONFIGURATION_ERROR",
+            FinancialError::MissingConfiguration { .. } => "MISSING_CONFIGURATION",
+            FinancialError::InternalError { .. } => "INTERNAL_ERROR",
+            FinancialError::NotSupported { .. } => "NOT_SUPPORTED",
+            FinancialError::UnsupportedOperation { .. } => "UNSUPPORTED_OPERATION",
+            FinancialError::InsufficientData { .. } => "INSUFFICIENT_DATA",
+            FinancialError::NotFound { .. } => "NOT_FOUND",
+        }
+    }
+
     /// Check if error is recoverable (client can retry)
     pub fn is_recoverable(&self) -> bool {
         match self {
@@ -155,6 +221,7 @@ impl FinancialError {
             | FinancialError::Underflow => ErrorCategory::Mathematical,
 
             FinancialError::CurrencyMismatch { .. }
+            | FinancialError::UnsupportedCurrency { .. }
             | FinancialError::UnsupportedCurrencyOperation { .. }
             | FinancialError::InvalidExchangeRate { .. } => ErrorCategory::Currency,
 
@@ -164,10 +231,13 @@ impl FinancialError {
 
             FinancialError::PortfolioOptimizationFailed { .. }
             | FinancialError::InsufficientPortfolioData { .. }
-            | FinancialError::InvalidAssetAllocation { .. } => ErrorCategory::Portfolio,
+            | FinancialError::InfeasibleConstraints { .. }
+            | FinancialError::InvalidAssetAllocation { .. }
+            | FinancialError::InsufficientLotQuantity { .. } => ErrorCategory::Portfolio,
 
             FinancialError::InvalidDebtConfiguration { .. }
-            | FinancialError::DebtCalculationFailed { .. } => ErrorCategory::Debt,
+            | FinancialError::DebtCalculationFailed { .. }
+            | FinancialError::NegativeAmortization { .. } => ErrorCategory::Debt,
 
             FinancialError::TimeValueError { .. }
             | FinancialError::InvalidTimePeriod { .. }
@@ -312,4 +382,141 @@ mod tests {
             _ => panic!("Expected ParameterOutOfRange error"),
         }
     }
+
+    #[test]
+    fn test_every_variant_has_a_stable_code_and_message() {
+        let s = |v: &str| v.to_string();
+        let cases = vec![
+            (FinancialError::DivisionByZero, "DIVISION_BY_ZERO", "Division by zero in financial calculation"),
+            (FinancialError::MathError { message: s("sqrt of -1") }, "MATH_ERROR", "Invalid mathematical operation: sqrt of -1"),
+            (FinancialError::Overflow, "OVERFLOW", "Numerical overflow in calculation"),
+            (FinancialError::Underflow, "UNDERFLOW", "Numerical underflow in calculation"),
+            (
+                FinancialError::CurrencyMismatch { expected: Currency::USD, actual: Currency::EUR },
+                "CURRENCY_MISMATCH",
+                "Currency mismatch: expected USD, got EUR",
+            ),
+            (FinancialError::UnsupportedCurrency { code: s("XYZ") }, "UNSUPPORTED_CURRENCY", "Unsupported currency: XYZ"),
+            (
+                FinancialError::UnsupportedCurrencyOperation { operation: s("convert") },
+                "UNSUPPORTED_CURRENCY_OPERATION",
+                "Unsupported currency operation: convert",
+            ),
+            (FinancialError::InvalidExchangeRate { rate: s("-1") }, "INVALID_EXCHANGE_RATE", "Invalid exchange rate: -1"),
+            (FinancialError::ValidationError(s("bad input")), "VALIDATION_ERROR", "Validation error: bad input"),
+            (
+                FinancialError::invalid_parameter("rate", "abc"),
+                "INVALID_PARAMETER",
+                "Invalid input parameter: rate = abc",
+            ),
+            (
+                FinancialError::parameter_out_of_range("amount", "0", "1000", "1500"),
+                "PARAMETER_OUT_OF_RANGE",
+                "Parameter out of range: amount must be between 0 and 1000, got 1500",
+            ),
+            (
+                FinancialError::PortfolioOptimizationFailed { reason: s("singular matrix") },
+                "PORTFOLIO_OPTIMIZATION_FAILED",
+                "Portfolio optimization failed: singular matrix",
+            ),
+            (
+                FinancialError::InsufficientPortfolioData { missing: s("returns") },
+                "INSUFFICIENT_PORTFOLIO_DATA",
+                "Insufficient portfolio data: returns",
+            ),
+            (
+                FinancialError::InfeasibleConstraints { reason: s("weights exceed 100%") },
+                "INFEASIBLE_CONSTRAINTS",
+                "Infeasible optimization constraints: weights exceed 100%",
+            ),
+            (
+                FinancialError::InvalidAssetAllocation { total: s("90") },
+                "INVALID_ASSET_ALLOCATION",
+                "Invalid asset allocation: total must equal 100%, got 90%",
+            ),
+            (
+                FinancialError::InsufficientLotQuantity { requested: s("10"), available: s("4") },
+                "INSUFFICIENT_LOT_QUANTITY",
+                "Cannot sell 10 units: only 4 held",
+            ),
+            (
+                FinancialError::InvalidDebtConfiguration { reason: s("no debts") },
+                "INVALID_DEBT_CONFIGURATION",
+                "Invalid debt configuration: no debts",
+            ),
+            (
+                FinancialError::DebtCalculationFailed { reason: s("did not converge") },
+                "DEBT_CALCULATION_FAILED",
+                "Debt calculation failed: did not converge",
+            ),
+            (
+                FinancialError::NegativeAmortization { debt: s("Visa"), payment: s("10"), interest: s("25") },
+                "NEGATIVE_AMORTIZATION",
+                "Payment of 10 does not cover 25 interest on Visa; it never pays off under minimum payments",
+            ),
+            (FinancialError::TimeValueError { reason: s("no periods") }, "TIME_VALUE_ERROR", "Time value calculation error: no periods"),
+            (FinancialError::InvalidTimePeriod { period: s("-3") }, "INVALID_TIME_PERIOD", "Invalid time period: -3"),
+            (
+                FinancialError::InterestRateError { reason: s("negative rate") },
+                "INTEREST_RATE_ERROR",
+                "Interest rate calculation failed: negative rate",
+            ),
+            (
+                FinancialError::RiskCalculationFailed { reason: s("empty series") },
+                "RISK_CALCULATION_FAILED",
+                "Risk calculation failed: empty series",
+            ),
+            (
+                FinancialError::InsufficientRiskData { missing: s("prices") },
+                "INSUFFICIENT_RISK_DATA",
+                "Insufficient risk data: prices",
+            ),
+            (
+                FinancialError::InvalidRiskModel { reason: s("confidence above 1") },
+                "INVALID_RISK_MODEL",
+                "Invalid risk model parameters: confidence above 1",
+            ),
+            (
+                FinancialError::BudgetAnalysisFailed { reason: s("no income") },
+                "BUDGET_ANALYSIS_FAILED",
+                "Budget analysis failed: no income",
+            ),
+            (FinancialError::InvalidBudgetData { reason: s("negative limit") }, "INVALID_BUDGET_DATA", "Invalid budget data: negative limit"),
+            (FinancialError::DatabaseError { message: s("timeout") }, "DATABASE_ERROR", "Database error: timeout"),
+            (FinancialError::CacheError { message: s("miss") }, "CACHE_ERROR", "Cache error: miss"),
+            (
+                FinancialError::ExternalServiceError { service: s("rates"), message: s("502") },
+                "EXTERNAL_SERVICE_ERROR",
+                "External service error: rates - 502",
+            ),
+            (
+                FinancialError::RateLimitExceeded { service: s("rates") },
+                "RATE_LIMIT_EXCEEDED",
+                "API rate limit exceeded for service: rates",
+            ),
+            (FinancialError::ConfigurationError { message: s("bad url") }, "CONFIGURATION_ERROR", "Configuration error: bad url"),
+            (
+                FinancialError::MissingConfiguration { key: s("API_KEY") },
+                "MISSING_CONFIGURATION",
+                "Missing required configuration: API_KEY",
+            ),
+            (FinancialError::InternalError { message: s("bug") }, "INTERNAL_ERROR", "Internal error: bug"),
+            (FinancialError::NotSupported { operation: s("short selling") }, "NOT_SUPPORTED", "Operation not supported: short selling"),
+            (
+                FinancialError::UnsupportedOperation { operation: s("margin") },
+                "UNSUPPORTED_OPERATION",
+                "Unsupported operation: margin",
+            ),
+            (FinancialError::InsufficientData { details: s("one debt") }, "INSUFFICIENT_DATA", "Insufficient data: one debt"),
+            (FinancialError::NotFound { resource: s("portfolio") }, "NOT_FOUND", "Resource not found: portfolio"),
+        ];
+
+        let mut codes = std::collections::HashSet::new();
+        for (error, code, message) in &cases {
+            assert_eq!(error.code(), *code, "{:?}", error);
+            assert_eq!(error.to_string(), *message);
+            assert!(codes.insert(*code), "duplicate code {}", code);
+        }
+        assert_eq!(cases.len(), 38);
+    }
 }
@@ -1,3 +1,4 @@
+pub mod clock;
 pub mod debt;
 pub mod error;
 pub mod portfolio;
@@ -8,6 +9,7 @@ pub mod portfolio;
 pub mod types;
 
 // Re-export commonly used types
+pub use clock::{system_clock, Clock, MockClock, SystemClock};
 pub use error::{FinancialError, Result};
 pub use types::*;
 
@@ -1,6 +1,9 @@
 pub mod allocation;
 pub mod optimization;
+pub mod performance;
 pub mod risk;
+pub mod stress;
+pub mod tax_lots;
 /// Portfolio analysis and optimization module
 ///
 /// Provides comprehensive portfolio analysis including:
@@ -12,5 +15,8 @@ pub mod types;
 
 pub use allocation::*;
 pub use optimization::*;
+pub use performance::*;
 pub use risk::*;
+pub use stress::*;
+pub use tax_lots::*;
 pub use types::*;
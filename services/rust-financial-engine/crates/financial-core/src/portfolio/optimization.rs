@@ -1,9 +1,10 @@
 use crate::portfolio::types::{
-    HistoricalReturns, OptimizationConstraints, PortfolioMetrics, RebalancingRecommendation,
-    TradeAction, TradeRecommendation,
+    FrontierPoint, HistoricalReturns, OptimizationConstraints, PortfolioMetrics,
+    RebalancingRecommendation, RiskTolerance, TradeAction, TradeRecommendation,
 };
-use crate::{Asset, FinancialError, Money, Portfolio, Result};
+use crate::{CalcContext, FinancialError, Money, Portfolio, Result};
 /// Portfolio optimization using Modern Portfolio Theory
+use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use std::collections::HashMap;
@@ -11,6 +12,7 @@ use std::collections::HashMap;
 pub struct PortfolioOptimizer {
     risk_free_rate: Decimal,
     confidence_level: Decimal,
+    context: CalcContext,
 }
 
 impl PortfolioOptimizer {
@@ -19,9 +21,16 @@ impl PortfolioOptimizer {
         Self {
             risk_free_rate,
             confidence_level: dec!(0.95), // 95% confidence level for VaR
+            context: CalcContext::default(),
         }
     }
 
+    /// Round monetary results (returns, VaR, trade values) to the user's precision
+    pub fn with_context(mut self, context: CalcContext) -> Self {
+        self.context = context;
+        self
+    }
+
     /// Calculate portfolio expected return
     pub fn expected_return(
         &self,
@@ -120,7 +129,9 @@ impl PortfolioOptimizer {
         let z_score = self.get_z_score(self.confidence_level)?;
         let var_percentage = expected_return - z_score * volatility;
 
-        let var_amount = total_value.amount() * var_percentage.abs();
+        let var_amount = self
+            .context
+            .round(total_value.amount() * var_percentage.abs());
         Ok(Money::new_unchecked(var_amount, total_value.currency()))
     }
 
@@ -151,7 +162,7 @@ impl PortfolioOptimizer {
         Ok(PortfolioMetrics {
             total_value,
             total_cost_basis,
-            total_return: total_return.amount(),
+            total_return: self.context.round(total_return.amount()),
             total_return_percentage,
             expected_return,
             volatility,
@@ -173,6 +184,7 @@ impl PortfolioOptimizer {
         returns: &[HistoricalReturns],
         constraints: &OptimizationConstraints,
     ) -> Result<RebalancingRecommendation> {
+        let returns = &self.returns_by_asset(portfolio, returns)?;
         let current_weights = self.get_asset_weights(portfolio)?;
         let target_weights = self.calculate_optimal_weights(portfolio, returns, constraints)?;
 
@@ -204,7 +216,7 @@ impl PortfolioOptimizer {
                     action,
                     quantity: quantity_diff.abs(),
                     estimated_value: Money::new_unchecked(
-                        value_diff.abs(),
+                        self.context.round(value_diff.abs()),
                         asset.current_value.currency(),
                     ),
                     current_weight: crate::types::Percentage::from_decimal(current_weight)?,
@@ -224,7 +236,8 @@ impl PortfolioOptimizer {
                 .map(|r| r.estimated_value.amount())
                 .sum();
             Money::new_unchecked(
-                total_trade_value * cost_rate.as_decimal(),
+                self.context
+                    .round(total_trade_value * cost_rate.as_decimal()),
                 total_value.currency(),
             )
         } else {
@@ -248,8 +261,130 @@ impl PortfolioOptimizer {
         })
     }
 
+    /// Trace the long-only efficient frontier with `points` portfolios
+    ///
+    /// Points run from the minimum-variance portfolio to the maximum-return
+    /// one at evenly spaced target returns; each is the lowest-volatility
+    /// portfolio with non-negative weights summing to 1 that reaches its
+    /// target. Weights follow the order of `returns`.
+    pub fn efficient_frontier(
+        &self,
+        returns: &[HistoricalReturns],
+        points: u32,
+    ) -> Result<Vec<FrontierPoint>> {
+        if returns.is_empty() {
+            return Err(FinancialError::InsufficientPortfolioData {
+                missing: "No return data provided".to_string(),
+            });
+        }
+        if points < 2 {
+            return Err(FinancialError::InvalidParameter {
+                parameter: "points".to_string(),
+                value: points.to_string(),
+            });
+        }
+
+        let problem = self.frontier_problem(returns, FeasibleWeights::long_only(returns.len()))?;
+        let min_variance = problem.solve(0.0, problem.feasible.project(&vec![0.0; returns.len()]));
+        let min_return = problem.portfolio_return(&min_variance);
+        let max_return = problem.portfolio_return(&problem.max_return(min_variance.clone()));
+
+        let mut frontier = Vec::with_capacity(points as usize);
+        let mut weights = min_variance;
+        for k in 0..points {
+            let target =
+                min_return + (max_return - min_return) * f64::from(k) / f64::from(points - 1);
+            if k > 0 {
+                weights = problem.solve_for_return(target, weights);
+            }
+
+            let weights = Self::decimal_weights(&weights);
+            frontier.push(FrontierPoint {
+                expected_return: self.calculate_expected_return_with_weights(&weights, returns)?,
+                volatility: self.calculate_volatility_with_weights(&weights, returns)?,
+                weights,
+            });
+        }
+
+        Ok(frontier)
+    }
+
     // Private helper methods
 
+    /// Convert solver weights to decimals that sum to exactly 1
+    fn decimal_weights(weights: &[f64]) -> Vec<Decimal> {
+        let mut decimal: Vec<Decimal> = weights
+            .iter()
+            .map(|w| Decimal::from_f64_retain(w.max(0.0)).unwrap_or(Decimal::ZERO))
+            .collect();
+        let sum: Decimal = decimal.iter().sum();
+        if sum.is_zero() {
+            return vec![Decimal::ONE / Decimal::from(weights.len()); weights.len()];
+        }
+        for weight in &mut decimal {
+            *weight /= sum;
+        }
+
+        // Absorb division rounding in the largest weight
+        let largest = (0..decimal.len()).max_by_key(|&i| decimal[i]).unwrap_or(0);
+        let rest: Decimal = decimal
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != largest)
+            .map(|(_, w)| *w)
+            .sum();
+        decimal[largest] = Decimal::ONE - rest;
+        decimal
+    }
+
+    /// Floating-point mean-variance problem over `returns` within `feasible`
+    fn frontier_problem(
+        &self,
+        returns: &[HistoricalReturns],
+        feasible: FeasibleWeights,
+    ) -> Result<FrontierProblem> {
+        let n_assets = returns.len();
+        let means = returns
+            .iter()
+            .map(|r| {
+                Ok(self
+                    .calculate_mean_return(&r.returns)?
+                    .to_f64()
+                    .unwrap_or(0.0))
+            })
+            .collect::<Result<Vec<f64>>>()?;
+        let covariance_matrix = self.calculate_covariance_matrix(returns)?;
+        let covariance: Vec<Vec<f64>> = (0..n_assets)
+            .map(|i| {
+                (0..n_assets)
+                    .map(|j| covariance_matrix[&(i, j)].to_f64().unwrap_or(0.0))
+                    .collect()
+            })
+            .collect();
+        Ok(FrontierProblem::new(means, covariance, feasible))
+    }
+
+    /// Return series in the order of the portfolio's assets
+    fn returns_by_asset(
+        &self,
+        portfolio: &Portfolio,
+        returns: &[HistoricalReturns],
+    ) -> Result<Vec<HistoricalReturns>> {
+        portfolio
+            .assets
+            .iter()
+            .map(|asset| {
+                returns
+                    .iter()
+                    .find(|r| r.asset_id == asset.id)
+                    .cloned()
+                    .ok_or_else(|| FinancialError::InsufficientPortfolioData {
+                        missing: format!("Returns for asset {}", asset.symbol),
+                    })
+            })
+            .collect()
+    }
+
     fn get_asset_weights(&self, portfolio: &Portfolio) -> Result<Vec<Decimal>> {
         let total_value = portfolio.total_value();
 
@@ -319,48 +454,32 @@ impl PortfolioOptimizer {
         Ok(covariance / Decimal::from(returns1.len() - 1))
     }
 
+    /// Minimum-variance weights for the constraints' target return
+    ///
+    /// Without a target return the risk tolerance picks a point on the
+    /// constrained frontier, from its minimum-variance end (conservative) to
+    /// its maximum-return end (very aggressive). A cardinality limit keeps the
+    /// largest holdings of that solution and solves again over them only.
     fn calculate_optimal_weights(
         &self,
         portfolio: &Portfolio,
         returns: &[HistoricalReturns],
         constraints: &OptimizationConstraints,
     ) -> Result<Vec<Decimal>> {
-        // Simplified optimization - for production use proper quadratic programming
-        // This is a basic mean-variance optimization approximation
-
-        let n_assets = portfolio.assets.len();
-        let _expected_returns: Vec<Decimal> = returns
-            .iter()
-            .map(|r| self.calculate_mean_return(&r.returns))
-            .collect::<Result<Vec<_>>>()?;
-
-        // Start with equal weights
-        let mut weights = vec![Decimal::from(1) / Decimal::from(n_assets); n_assets];
-
-        // Apply constraints
-        if let Some(max_weight) = constraints.max_asset_weight {
-            for weight in &mut weights {
-                if *weight > max_weight.as_decimal() {
-                    *weight = max_weight.as_decimal();
-                }
-            }
-        }
-
-        if let Some(min_weight) = constraints.min_asset_weight {
-            for weight in &mut weights {
-                if *weight < min_weight.as_decimal() {
-                    *weight = min_weight.as_decimal();
-                }
+        let feasible = FeasibleWeights::from_constraints(portfolio, constraints)?;
+        let problem = self.frontier_problem(returns, feasible)?;
+        let mut weights = problem.target_weights(constraints)?;
+
+        if let Some(max_assets) = constraints.max_assets {
+            let held = weights.iter().filter(|w| **w > HELD_WEIGHT).count();
+            if held > max_assets {
+                let feasible = problem.feasible.limited_to(&weights, max_assets)?;
+                let problem = self.frontier_problem(returns, feasible)?;
+                weights = problem.target_weights(constraints)?;
             }
         }
 
-        // Normalize weights to sum to 1
-        let weight_sum: Decimal = weights.iter().sum();
-        for weight in &mut weights {
-            *weight /= weight_sum;
-        }
-
-        Ok(weights)
+        Ok(Self::decimal_weights(&weights))
     }
 
     fn calculate_expected_return_with_weights(
@@ -441,11 +560,479 @@ impl PortfolioOptimizer {
     }
 }
 
+/// Weights below this count as not held
+const HELD_WEIGHT: f64 = 1e-9;
+
+/// Slack allowed when checking a solution against its constraints
+const CONSTRAINT_TOLERANCE: f64 = 1e-6;
+
+/// Linear limits on weights: per-asset bounds, groups, and a full allocation
+#[derive(Debug, Clone)]
+struct FeasibleWeights {
+    lower: Vec<f64>,
+    upper: Vec<f64>,
+    groups: Vec<WeightGroup>,
+}
+
+#[derive(Debug, Clone)]
+struct WeightGroup {
+    name: String,
+    members: Vec<usize>,
+    lower: f64,
+    upper: f64,
+}
+
+fn infeasible(reason: impl Into<String>) -> FinancialError {
+    FinancialError::InfeasibleConstraints {
+        reason: reason.into(),
+    }
+}
+
+impl FeasibleWeights {
+    const PROJECTION_ROUNDS: usize = 1_000;
+    const BISECTION_STEPS: usize = 200;
+
+    /// Non-negative weights summing to 1
+    fn long_only(n_assets: usize) -> Self {
+        Self {
+            lower: vec![0.0; n_assets],
+            upper: vec![1.0; n_assets],
+            groups: Vec::new(),
+        }
+    }
+
+    /// Limits for the portfolio's assets, rejecting sets no allocation can meet
+    fn from_constraints(
+        portfolio: &Portfolio,
+        constraints: &OptimizationConstraints,
+    ) -> Result<Self> {
+        let percent = |p: Option<crate::types::Percentage>, default: f64| {
+            p.map_or(default, |p| p.as_decimal().to_f64().unwrap_or(default))
+        };
+        let assets = &portfolio.assets;
+        let mut feasible = Self::long_only(assets.len());
+
+        let global_lower = percent(constraints.min_asset_weight, 0.0);
+        let global_upper = percent(constraints.max_asset_weight, 1.0);
+        for (i, asset) in assets.iter().enumerate() {
+            feasible.lower[i] = global_lower;
+            feasible.upper[i] = if constraints.exclude_assets.contains(&asset.symbol) {
+                0.0
+            } else {
+                global_upper
+            };
+        }
+
+        for bounds in &constraints.asset_bounds {
+            let i = assets
+                .iter()
+                .position(|a| a.symbol == bounds.symbol)
+                .ok_or_else(|| FinancialError::InvalidParameter {
+                    parameter: "asset_bounds".to_string(),
+                    value: bounds.symbol.clone(),
+                })?;
+            feasible.lower[i] = feasible.lower[i].max(percent(bounds.min_weight, 0.0));
+            feasible.upper[i] = feasible.upper[i].min(percent(bounds.max_weight, 1.0));
+        }
+
+        for (i, asset) in assets.iter().enumerate() {
+            if feasible.lower[i] > feasible.upper[i] + CONSTRAINT_TOLERANCE {
+                return Err(infeasible(format!(
+                    "{} must hold at least {:.2}% but at most {:.2}%",
+                    asset.symbol,
+                    feasible.lower[i] * 100.0,
+                    feasible.upper[i] * 100.0
+                )));
+            }
+        }
+
+        for group in &constraints.group_constraints {
+            let members: Vec<usize> = assets
+                .iter()
+                .enumerate()
+                .filter(|(_, a)| {
+                    group.asset_classes.contains(&a.asset_class)
+                        || group.symbols.contains(&a.symbol)
+                })
+                .map(|(i, _)| i)
+                .collect();
+            if members.is_empty() {
+                return Err(FinancialError::InvalidParameter {
+                    parameter: "group_constraints".to_string(),
+                    value: format!("{} matches no assets", group.name),
+                });
+            }
+            feasible.groups.push(WeightGroup {
+                name: group.name.clone(),
+                members,
+                lower: percent(group.min_weight, 0.0),
+                upper: percent(group.max_weight, 1.0),
+            });
+        }
+
+        if let Some(max_assets) = constraints.max_assets {
+            if max_assets == 0 {
+                return Err(FinancialError::InvalidParameter {
+                    parameter: "max_assets".to_string(),
+                    value: "0".to_string(),
+                });
+            }
+            let required = feasible.lower.iter().filter(|l| **l > HELD_WEIGHT).count();
+            if required > max_assets {
+                return Err(infeasible(format!(
+                    "{} assets have minimum weights but at most {} may be held",
+                    required, max_assets
+                )));
+            }
+        }
+
+        feasible.check()?;
+        Ok(feasible)
+    }
+
+    /// Necessary conditions for a feasible allocation, with a reason for each
+    fn check(&self) -> Result<()> {
+        let lower_sum: f64 = self.lower.iter().sum();
+        let upper_sum: f64 = self.upper.iter().sum();
+        if lower_sum > 1.0 + CONSTRAINT_TOLERANCE {
+            return Err(infeasible(format!(
+                "minimum weights add up to {:.2}%, more than 100%",
+                lower_sum * 100.0
+            )));
+        }
+        if upper_sum < 1.0 - CONSTRAINT_TOLERANCE {
+            return Err(infeasible(format!(
+                "maximum weights add up to {:.2}%, less than 100%",
+                upper_sum * 100.0
+            )));
+        }
+
+        for group in &self.groups {
+            let member_lower: f64 = group.members.iter().map(|&i| self.lower[i]).sum();
+            let member_upper: f64 = group.members.iter().map(|&i| self.upper[i]).sum();
+            let low = group
+                .lower
+                .max(member_lower)
+                .max(1.0 - (upper_sum - member_upper));
+            let high = group
+                .upper
+                .min(member_upper)
+                .min(1.0 - (lower_sum - member_lower));
+            if low > high + CONSTRAINT_TOLERANCE {
+                return Err(infeasible(format!(
+                    "group {} can hold between {:.2}% and {:.2}%, but its limits and the other assets' require {:.2}% to {:.2}%",
+                    group.name,
+                    group.lower * 100.0,
+                    group.upper * 100.0,
+                    low * 100.0,
+                    high * 100.0
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// The same limits over the `max_assets` largest holdings of `weights`
+    ///
+    /// Assets with a minimum weight are always kept.
+    fn limited_to(&self, weights: &[f64], max_assets: usize) -> Result<Self> {
+        let mut ranked: Vec<usize> = (0..weights.len()).collect();
+        ranked.sort_by(|&a, &b| {
+            let required = |i: usize| self.lower[i] > HELD_WEIGHT;
+            required(b)
+                .cmp(&required(a))
+                .then(weights[b].total_cmp(&weights[a]))
+        });
+
+        let mut limited = self.clone();
+        for &i in &ranked[max_assets..] {
+            limited.upper[i] = 0.0;
+        }
+        limited.check().map_err(|e| {
+            infeasible(format!(
+                "no allocation of at most {} assets fits: {}",
+                max_assets, e
+            ))
+        })?;
+        Ok(limited)
+    }
+
+    fn is_satisfied_by(&self, weights: &[f64]) -> bool {
+        let within = |value: f64, low: f64, high: f64| {
+            value >= low - CONSTRAINT_TOLERANCE && value <= high + CONSTRAINT_TOLERANCE
+        };
+        (weights.iter().sum::<f64>() - 1.0).abs() <= CONSTRAINT_TOLERANCE
+            && (0..weights.len()).all(|i| within(weights[i], self.lower[i], self.upper[i]))
+            && self.groups.iter().all(|g| {
+                within(
+                    g.members.iter().map(|&i| weights[i]).sum(),
+                    g.lower,
+                    g.upper,
+                )
+            })
+    }
+
+    /// Closest point to `values` satisfying every limit
+    ///
+    /// Group limits are combined with the bounds by Dykstra's alternating
+    /// projections, ending each round on the bounds so weights sum to 1.
+    fn project(&self, values: &[f64]) -> Vec<f64> {
+        if self.groups.is_empty() {
+            return self.project_onto_bounds(values);
+        }
+
+        let n_sets = self.groups.len() + 1;
+        let mut increments = vec![vec![0.0; values.len()]; n_sets];
+        let mut weights = values.to_vec();
+        for _ in 0..Self::PROJECTION_ROUNDS {
+            let previous = weights.clone();
+            for (set, increment) in increments.iter_mut().enumerate() {
+                let shifted: Vec<f64> = weights
+                    .iter()
+                    .zip(increment.iter())
+                    .map(|(w, d)| w + d)
+                    .collect();
+                weights = match self.groups.get(set) {
+                    Some(group) => group.project(&shifted),
+                    None => self.project_onto_bounds(&shifted),
+                };
+                for (d, (s, w)) in increment.iter_mut().zip(shifted.iter().zip(&weights)) {
+                    *d = s - w;
+                }
+            }
+            let change = largest_change(&weights, &previous);
+            if change < FrontierProblem::TOLERANCE {
+                break;
+            }
+        }
+        weights
+    }
+
+    /// Projection onto per-asset bounds with weights summing to 1
+    ///
+    /// The result is `clamp(v − θ)` for the shift θ, found by bisection, that
+    /// makes the weights sum to 1.
+    fn project_onto_bounds(&self, values: &[f64]) -> Vec<f64> {
+        let clamped = |theta: f64| -> Vec<f64> {
+            (0..values.len())
+                .map(|i| (values[i] - theta).clamp(self.lower[i], self.upper[i]))
+                .collect()
+        };
+
+        let mut low = (0..values.len())
+            .map(|i| values[i] - self.upper[i])
+            .fold(f64::MAX, f64::min);
+        let mut high = (0..values.len())
+            .map(|i| values[i] - self.lower[i])
+            .fold(f64::MIN, f64::max);
+        for _ in 0..Self::BISECTION_STEPS {
+            let theta = (low + high) / 2.0;
+            if clamped(theta).iter().sum::<f64>() > 1.0 {
+                low = theta;
+            } else {
+                high = theta;
+            }
+            if high - low <= f64::EPSILON * high.abs().max(1.0) {
+                break;
+            }
+        }
+        clamped((low + high) / 2.0)
+    }
+}
+
+impl WeightGroup {
+    /// Move the members' combined weight into the group's limits, evenly
+    fn project(&self, values: &[f64]) -> Vec<f64> {
+        let total: f64 = self.members.iter().map(|&i| values[i]).sum();
+        let shift = if total > self.upper {
+            self.upper - total
+        } else if total < self.lower {
+            self.lower - total
+        } else {
+            return values.to_vec();
+        };
+
+        let mut projected = values.to_vec();
+        let per_member = shift / self.members.len() as f64;
+        for &i in &self.members {
+            projected[i] += per_member;
+        }
+        projected
+    }
+}
+
+fn largest_change(a: &[f64], b: &[f64]) -> f64 {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| (x - y).abs())
+        .fold(0.0, f64::max)
+}
+
+/// Mean-variance problem over feasible weights, solved in floating point
+///
+/// `solve(λ)` minimizes `wᵀΣw − λ·μᵀw` over the feasible weights by
+/// projected gradient descent. Its return grows with λ, so a target return
+/// is reached by bisecting on λ.
+struct FrontierProblem {
+    means: Vec<f64>,
+    covariance: Vec<Vec<f64>>,
+    feasible: FeasibleWeights,
+    step: f64,
+}
+
+impl FrontierProblem {
+    const TOLERANCE: f64 = 1e-12;
+    const MAX_ITERATIONS: usize = 100_000;
+    const BISECTION_STEPS: usize = 100;
+    /// Largest move in any weight the return term may cause per step
+    const MAX_RETURN_STEP: f64 = 0.1;
+
+    fn new(means: Vec<f64>, covariance: Vec<Vec<f64>>, feasible: FeasibleWeights) -> Self {
+        // The Frobenius norm bounds the largest eigenvalue, so 1/L is a safe step
+        let frobenius = covariance
+            .iter()
+            .flatten()
+            .map(|c| c * c)
+            .sum::<f64>()
+            .sqrt();
+        let lipschitz = (2.0 * frobenius).max(1e-12);
+        Self {
+            means,
+            covariance,
+            feasible,
+            step: 1.0 / lipschitz,
+        }
+    }
+
+    fn portfolio_return(&self, weights: &[f64]) -> f64 {
+        weights.iter().zip(&self.means).map(|(w, m)| w * m).sum()
+    }
+
+    fn solve(&self, lambda: f64, mut weights: Vec<f64>) -> Vec<f64> {
+        // Keep each step close to the feasible set: projecting a far-away
+        // point onto intersecting group limits converges very slowly
+        let largest_mean = self.means.iter().fold(0.0, |m: f64, r| m.max(r.abs()));
+        let step = if lambda * largest_mean > 0.0 {
+            self.step
+                .min(Self::MAX_RETURN_STEP / (lambda * largest_mean))
+        } else {
+            self.step
+        };
+
+        for _ in 0..Self::MAX_ITERATIONS {
+            let stepped: Vec<f64> = (0..weights.len())
+                .map(|i| {
+                    let gradient = 2.0
+                        * self.covariance[i]
+                            .iter()
+                            .zip(&weights)
+                            .map(|(c, w)| c * w)
+                            .sum::<f64>()
+                        - lambda * self.means[i];
+                    weights[i] - step * gradient
+                })
+                .collect();
+            let next = self.feasible.project(&stepped);
+            let change = largest_change(&next, &weights);
+            weights = next;
+            if change < Self::TOLERANCE {
+                break;
+            }
+        }
+        weights
+    }
+
+    /// Feasible weights with the highest return, variance breaking ties
+    fn max_return(&self, start: Vec<f64>) -> Vec<f64> {
+        self.solve(1e9 * self.step.recip(), start)
+    }
+
+    /// Minimum-variance weights whose return reaches `target`, starting from `start`
+    fn solve_for_return(&self, target: f64, start: Vec<f64>) -> Vec<f64> {
+        let mut low = 0.0;
+        let mut high = self.step.recip();
+        let mut best = self.solve(high, start.clone());
+        while self.portfolio_return(&best) < target - Self::TOLERANCE && high < 1e12 {
+            high *= 2.0;
+            best = self.solve(high, best);
+        }
+
+        let mut weights = start;
+        for _ in 0..Self::BISECTION_STEPS {
+            let lambda = (low + high) / 2.0;
+            weights = self.solve(lambda, weights);
+            if self.portfolio_return(&weights) < target {
+                low = lambda;
+            } else {
+                high = lambda;
+                best = weights.clone();
+            }
+            if high - low <= Self::TOLERANCE * high {
+                break;
+            }
+        }
+        best
+    }
+
+    /// Weights for the constraints' target return or risk tolerance
+    fn target_weights(&self, constraints: &OptimizationConstraints) -> Result<Vec<f64>> {
+        let n_assets = self.means.len();
+        let min_variance = self.solve(
+            0.0,
+            self.feasible
+                .project(&vec![1.0 / n_assets as f64; n_assets]),
+        );
+        if !self.feasible.is_satisfied_by(&min_variance) {
+            return Err(infeasible(
+                "no allocation satisfies every group limit together",
+            ));
+        }
+        let max_return = self.max_return(min_variance.clone());
+        let (low, high) = (
+            self.portfolio_return(&min_variance),
+            self.portfolio_return(&max_return),
+        );
+
+        let target = match constraints.target_return {
+            Some(target) => {
+                let target = target.to_f64().unwrap_or(0.0);
+                if target > high + CONSTRAINT_TOLERANCE {
+                    return Err(infeasible(format!(
+                        "target return {:.4} is above the {:.4} the constraints allow",
+                        target, high
+                    )));
+                }
+                target
+            }
+            None => {
+                let share = match constraints.risk_tolerance {
+                    RiskTolerance::Conservative => 0.0,
+                    RiskTolerance::ModeratelyConservative => 0.2,
+                    RiskTolerance::Moderate => 0.4,
+                    RiskTolerance::ModeratelyAggressive => 0.6,
+                    RiskTolerance::Aggressive => 0.8,
+                    RiskTolerance::VeryAggressive => 1.0,
+                };
+                low + (high - low) * share
+            }
+        };
+
+        Ok(if target <= low {
+            min_variance
+        } else if target >= high {
+            max_return
+        } else {
+            self.solve_for_return(target, min_variance)
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::portfolio::types::{PeriodReturn, ReturnFrequency, RiskTolerance};
-    use crate::types::Currency;
+    use crate::Asset;
+    use crate::types::{Currency, Percentage};
     use chrono::Utc;
     use uuid::Uuid;
 
@@ -536,6 +1123,260 @@ mod tests {
         assert!(optimizer.get_z_score(dec!(0.80)).is_err());
     }
 
+    #[test]
+    fn test_value_at_risk_honors_context() {
+        use crate::types::{AssetClass, RoundingMode};
+
+        let mut returns = create_test_returns();
+        let mut portfolio = Portfolio::new(Uuid::new_v4(), "Test Portfolio".to_string());
+        for (symbol, class, value) in [
+            ("STOCK1", AssetClass::Stocks, dec!(6543.21)),
+            ("BOND1", AssetClass::Bonds, dec!(3456.79)),
+        ] {
+            let value = Money::new(value, Currency::USD).unwrap();
+            let asset = Asset::new(
+                symbol.to_string(),
+                symbol.to_string(),
+                class,
+                dec!(10),
+                value,
+                value,
+            );
+            returns
+                .iter_mut()
+                .find(|r| r.symbol == symbol)
+                .unwrap()
+                .asset_id = asset.id;
+            portfolio.assets.push(asset);
+        }
+
+        let cents = PortfolioOptimizer::new(dec!(0.02))
+            .with_context(CalcContext::new(2, RoundingMode::HalfEven).unwrap())
+            .value_at_risk(&portfolio, &returns)
+            .unwrap();
+        let dollars = PortfolioOptimizer::new(dec!(0.02))
+            .with_context(CalcContext::new(0, RoundingMode::AwayFromZero).unwrap())
+            .value_at_risk(&portfolio, &returns)
+            .unwrap();
+        let unrounded = PortfolioOptimizer::new(dec!(0.02))
+            .value_at_risk(&portfolio, &returns)
+            .unwrap();
+
+        assert_eq!(cents.amount(), unrounded.amount().round_dp(2));
+        assert_eq!(dollars.amount(), unrounded.amount().ceil());
+        assert_ne!(cents, dollars);
+    }
+
+    fn create_frontier_returns() -> Vec<HistoricalReturns> {
+        let mut returns = create_test_returns();
+        returns.push(HistoricalReturns {
+            asset_id: Uuid::new_v4(),
+            symbol: "REIT1".to_string(),
+            returns: [dec!(0.03), dec!(-0.01), dec!(0.02), dec!(0.01)]
+                .into_iter()
+                .map(|return_value| PeriodReturn {
+                    date: Utc::now(),
+                    return_value,
+                    adjusted_close: None,
+                })
+                .collect(),
+            frequency: ReturnFrequency::Monthly,
+        });
+        returns
+    }
+
+    #[test]
+    fn test_efficient_frontier_is_monotonic() {
+        let optimizer = PortfolioOptimizer::new(dec!(0.02));
+        let returns = create_frontier_returns();
+
+        let frontier = optimizer.efficient_frontier(&returns, 12).unwrap();
+        assert_eq!(frontier.len(), 12);
+
+        for pair in frontier.windows(2) {
+            assert!(pair[1].expected_return > pair[0].expected_return);
+            assert!(pair[1].volatility >= pair[0].volatility - dec!(0.0000001));
+        }
+
+        // Ends at the highest-returning asset held alone
+        let last = frontier.last().unwrap();
+        assert!((last.expected_return - dec!(0.025)).abs() < dec!(0.000001));
+        assert!(last.weights[0] > dec!(0.9999));
+    }
+
+    #[test]
+    fn test_efficient_frontier_weights_are_long_only_and_sum_to_one() {
+        let optimizer = PortfolioOptimizer::new(dec!(0.02));
+        let returns = create_frontier_returns();
+
+        for point in optimizer.efficient_frontier(&returns, 8).unwrap() {
+            assert_eq!(point.weights.len(), returns.len());
+            assert_eq!(point.weights.iter().sum::<Decimal>(), Decimal::ONE);
+            assert!(point.weights.iter().all(|w| *w >= Decimal::ZERO));
+        }
+
+        assert!(optimizer.efficient_frontier(&returns, 1).is_err());
+        assert!(optimizer.efficient_frontier(&[], 5).is_err());
+    }
+
+    fn frontier_portfolio(returns: &mut [HistoricalReturns]) -> Portfolio {
+        use crate::types::AssetClass;
+
+        let mut portfolio = Portfolio::new(Uuid::new_v4(), "Test Portfolio".to_string());
+        for (symbol, class) in [
+            ("STOCK1", AssetClass::Stocks),
+            ("BOND1", AssetClass::Bonds),
+            ("REIT1", AssetClass::RealEstate),
+        ] {
+            let value = Money::new(dec!(1000), Currency::USD).unwrap();
+            let asset = Asset::new(
+                symbol.to_string(),
+                symbol.to_string(),
+                class,
+                dec!(10),
+                value,
+                value,
+            );
+            returns
+                .iter_mut()
+                .find(|r| r.symbol == symbol)
+                .unwrap()
+                .asset_id = asset.id;
+            portfolio.assets.push(asset);
+        }
+        portfolio
+    }
+
+    fn constraints(risk_tolerance: RiskTolerance) -> OptimizationConstraints {
+        OptimizationConstraints {
+            risk_tolerance,
+            target_return: None,
+            max_asset_weight: None,
+            min_asset_weight: None,
+            exclude_assets: Vec::new(),
+            include_cash: false,
+            allow_short_selling: false,
+            transaction_cost: None,
+            asset_bounds: Vec::new(),
+            group_constraints: Vec::new(),
+            max_assets: None,
+        }
+    }
+
+    fn percent(value: Decimal) -> Percentage {
+        Percentage::from_percentage(value).unwrap()
+    }
+
+    #[test]
+    fn test_weight_caps_change_optimal_allocation() {
+        use crate::portfolio::types::{AssetWeightBounds, GroupWeightConstraint};
+        use crate::types::AssetClass;
+
+        let optimizer = PortfolioOptimizer::new(dec!(0.02));
+        let mut returns = create_frontier_returns();
+        let portfolio = frontier_portfolio(&mut returns);
+
+        // Minimum variance leans almost entirely on the bond
+        let uncapped = optimizer
+            .calculate_optimal_weights(
+                &portfolio,
+                &returns,
+                &constraints(RiskTolerance::Conservative),
+            )
+            .unwrap();
+        assert!(uncapped[1] > dec!(0.6));
+
+        let mut capped = constraints(RiskTolerance::Conservative);
+        capped.asset_bounds.push(AssetWeightBounds {
+            symbol: "BOND1".to_string(),
+            min_weight: None,
+            max_weight: Some(percent(dec!(40))),
+        });
+        let weights = optimizer
+            .calculate_optimal_weights(&portfolio, &returns, &capped)
+            .unwrap();
+        assert!((weights[1] - dec!(0.4)).abs() < dec!(0.0001));
+        assert_eq!(weights.iter().sum::<Decimal>(), Decimal::ONE);
+
+        // Maximum return puts everything in stocks unless a group caps them
+        let mut capped_equities = constraints(RiskTolerance::VeryAggressive);
+        capped_equities
+            .group_constraints
+            .push(GroupWeightConstraint {
+                name: "equities".to_string(),
+                asset_classes: vec![AssetClass::Stocks],
+                symbols: Vec::new(),
+                min_weight: None,
+                max_weight: Some(percent(dec!(70))),
+            });
+        let weights = optimizer
+            .calculate_optimal_weights(&portfolio, &returns, &capped_equities)
+            .unwrap();
+        assert!((weights[0] - dec!(0.7)).abs() < dec!(0.0001));
+
+        // A cardinality limit drops the smallest holding
+        let mut two_assets = constraints(RiskTolerance::Moderate);
+        two_assets.max_assets = Some(2);
+        let weights = optimizer
+            .calculate_optimal_weights(&portfolio, &returns, &two_assets)
+            .unwrap();
+        assert_eq!(weights.iter().filter(|w| **w > dec!(0.000001)).count(), 2);
+        assert_eq!(weights.iter().sum::<Decimal>(), Decimal::ONE);
+    }
+
+    #[test]
+    fn test_infeasible_constraints_are_rejected() {
+        use crate::portfolio::types::{AssetWeightBounds, GroupWeightConstraint};
+        use crate::types::AssetClass;
+
+        let optimizer = PortfolioOptimizer::new(dec!(0.02));
+        let mut returns = create_frontier_returns();
+        let portfolio = frontier_portfolio(&mut returns);
+
+        let mut minimums = constraints(RiskTolerance::Moderate);
+        minimums.min_asset_weight = Some(percent(dec!(40)));
+        let error = optimizer
+            .calculate_optimal_weights(&portfolio, &returns, &minimums)
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            FinancialError::InfeasibleConstraints { .. }
+        ));
+        assert!(error
+            .to_string()
+            .contains("minimum weights add up to 120.00%"));
+
+        // Equities must be 60% of the portfolio, but their only asset is capped at 40%
+        let mut conflicting = constraints(RiskTolerance::Moderate);
+        conflicting.asset_bounds.push(AssetWeightBounds {
+            symbol: "STOCK1".to_string(),
+            min_weight: None,
+            max_weight: Some(percent(dec!(40))),
+        });
+        conflicting.group_constraints.push(GroupWeightConstraint {
+            name: "equities".to_string(),
+            asset_classes: vec![AssetClass::Stocks],
+            symbols: Vec::new(),
+            min_weight: Some(percent(dec!(60))),
+            max_weight: None,
+        });
+        let error = optimizer
+            .calculate_optimal_weights(&portfolio, &returns, &conflicting)
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            FinancialError::InfeasibleConstraints { .. }
+        ));
+        assert!(error.to_string().contains("group equities"));
+
+        let mut unreachable = constraints(RiskTolerance::Moderate);
+        unreachable.target_return = Some(dec!(0.5));
+        assert!(matches!(
+            optimizer.calculate_optimal_weights(&portfolio, &returns, &unreachable),
+            Err(FinancialError::InfeasibleConstraints { .. })
+        ));
+    }
+
     #[test]
     fn test_decimal_sqrt() {
         let optimizer = PortfolioOptimizer::new(dec!(0.02));
@@ -0,0 +1,452 @@
+/// Realized and unrealized performance of tax-lot tracked holdings
+///
+/// Holdings keep their own currency; every figure in a report is converted
+/// to the tracker's base currency at its current exchange rates.
+use crate::portfolio::tax_lots::{CostBasisMethod, LotSale, RealizedGain, TaxLotLedger};
+use crate::types::{Currency, Money};
+use crate::{FinancialError, Result};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Conversion rates into a single base currency
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExchangeRates {
+    pub base: Currency,
+    /// Units of the base currency per unit of each other currency
+    rates: HashMap<Currency, Decimal>,
+}
+
+impl ExchangeRates {
+    /// Rates with only the base currency known
+    pub fn new(base: Currency) -> Self {
+        Self {
+            base,
+            rates: HashMap::new(),
+        }
+    }
+
+    /// Set how many units of the base currency one unit of `currency` buys
+    pub fn set_rate(&mut self, currency: Currency, rate: Decimal) -> Result<()> {
+        if rate <= Decimal::ZERO {
+            return Err(FinancialError::InvalidExchangeRate {
+                rate: rate.to_string(),
+            });
+        }
+        if currency != self.base {
+            self.rates.insert(currency, rate);
+        }
+        Ok(())
+    }
+
+    /// Convert `money` into the base currency
+    pub fn to_base(&self, money: &Money) -> Result<Money> {
+        if money.currency() == self.base {
+            return Ok(*money);
+        }
+        let rate = self.rates.get(&money.currency()).ok_or_else(|| {
+            FinancialError::UnsupportedCurrencyOperation {
+                operation: format!("convert {} to {}", money.currency(), self.base),
+            }
+        })?;
+        Ok(Money::new_unchecked(
+            money.multiply(*rate)?.amount(),
+            self.base,
+        ))
+    }
+}
+
+/// Portfolio value at a point in time, for time-weighted return
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValuationPoint {
+    pub date: DateTime<Utc>,
+    /// Market value on `date`, after `net_contribution` was made
+    pub value: Money,
+    /// Deposits minus withdrawals made on `date`
+    pub net_contribution: Money,
+}
+
+/// A holding's lots and its latest market price
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrackedHolding {
+    pub ledger: TaxLotLedger,
+    /// Price of one unit, in the holding's own currency
+    pub current_price: Money,
+}
+
+/// Performance of one holding, in the base currency
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HoldingPerformance {
+    pub symbol: String,
+    pub quantity: Decimal,
+    pub cost_basis: Money,
+    pub market_value: Money,
+    /// Market value minus cost basis of the open lots; negative for a loss
+    pub unrealized_gain: Money,
+    /// Gains from every recorded sale of the holding
+    pub realized_gain: Money,
+}
+
+/// Return between the first and last valuation, excluding contributions
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeWeightedReturn {
+    pub start_date: DateTime<Utc>,
+    pub end_date: DateTime<Utc>,
+    /// Compounded return as a decimal, e.g. 0.05 for 5%
+    pub return_value: Decimal,
+}
+
+/// Realized and unrealized gains across every tracked holding
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PerformanceReport {
+    pub base_currency: Currency,
+    pub holdings: Vec<HoldingPerformance>,
+    pub total_cost_basis: Money,
+    pub total_market_value: Money,
+    pub total_unrealized_gain: Money,
+    pub total_realized_gain: Money,
+    /// Unrealized plus realized gain
+    pub total_gain: Money,
+    /// `None` until at least two valuations are recorded
+    pub time_weighted_return: Option<TimeWeightedReturn>,
+}
+
+/// Tax-lot ledgers for a whole portfolio, with their sales and valuations
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PortfolioTracker {
+    pub exchange_rates: ExchangeRates,
+    holdings: Vec<TrackedHolding>,
+    sales: Vec<(String, RealizedGain)>,
+    valuations: Vec<ValuationPoint>,
+}
+
+impl PortfolioTracker {
+    /// Create an empty tracker reporting in the base currency of `exchange_rates`
+    pub fn new(exchange_rates: ExchangeRates) -> Self {
+        Self {
+            exchange_rates,
+            holdings: Vec::new(),
+            sales: Vec::new(),
+            valuations: Vec::new(),
+        }
+    }
+
+    /// Holdings in the order they were added
+    pub fn holdings(&self) -> &[TrackedHolding] {
+        &self.holdings
+    }
+
+    /// Sales recorded so far, with the symbol each was made from
+    pub fn sales(&self) -> &[(String, RealizedGain)] {
+        &self.sales
+    }
+
+    /// Start tracking a holding priced at `current_price`
+    pub fn add_holding(&mut self, ledger: TaxLotLedger, current_price: Money) -> Result<()> {
+        if self.holding_index(&ledger.symbol).is_some() {
+            return Err(FinancialError::ValidationError(format!(
+                "Holding {} is already tracked",
+                ledger.symbol
+            )));
+        }
+        Self::ensure_price_currency(&ledger, &current_price)?;
+        self.holdings.push(TrackedHolding {
+            ledger,
+            current_price,
+        });
+        Ok(())
+    }
+
+    /// Record a new market price for `symbol`
+    pub fn update_price(&mut self, symbol: &str, price: Money) -> Result<()> {
+        let index = self.require_holding(symbol)?;
+        let holding = &mut self.holdings[index];
+        Self::ensure_price_currency(&holding.ledger, &price)?;
+        holding.current_price = price;
+        Ok(())
+    }
+
+    /// Sell from a holding's lots and record the realized gain
+    pub fn sell(
+        &mut self,
+        symbol: &str,
+        sale: &LotSale,
+        method: &CostBasisMethod,
+    ) -> Result<RealizedGain> {
+        let index = self.require_holding(symbol)?;
+        let gain = self.holdings[index].ledger.sell(sale, method)?;
+        self.sales.push((symbol.to_string(), gain.clone()));
+        Ok(gain)
+    }
+
+    /// Record the portfolio's value on a date
+    pub fn record_valuation(&mut self, valuation: ValuationPoint) {
+        let position = self
+            .valuations
+            .partition_point(|existing| existing.date <= valuation.date);
+        self.valuations.insert(position, valuation);
+    }
+
+    /// Unrealized and realized gains per holding and in aggregate
+    pub fn portfolio_performance(&self) -> Result<PerformanceReport> {
+        let base = self.exchange_rates.base;
+        let zero = Money::new_unchecked(Decimal::ZERO, base);
+
+        let mut holdings = Vec::with_capacity(self.holdings.len());
+        let (mut total_cost_basis, mut total_market_value, mut total_realized_gain) =
+            (zero, zero, zero);
+        for holding in &self.holdings {
+            let mut cost_basis = zero;
+            for lot in holding.ledger.lots() {
+                cost_basis = cost_basis.add(&self.exchange_rates.to_base(&lot.cost_basis)?)?;
+            }
+            let quantity = holding.ledger.total_quantity();
+            let market_value = self
+                .exchange_rates
+                .to_base(&holding.current_price.multiply(quantity)?)?;
+
+            let mut realized_gain = zero;
+            for (_, sale) in self
+                .sales
+                .iter()
+                .filter(|(symbol, _)| *symbol == holding.ledger.symbol)
+            {
+                realized_gain = realized_gain.add(&self.exchange_rates.to_base(&sale.gain)?)?;
+            }
+
+            total_cost_basis = total_cost_basis.add(&cost_basis)?;
+            total_market_value = total_market_value.add(&market_value)?;
+            total_realized_gain = total_realized_gain.add(&realized_gain)?;
+            holdings.push(HoldingPerformance {
+                symbol: holding.ledger.symbol.clone(),
+                quantity,
+                cost_basis,
+                market_value,
+                unrealized_gain: market_value.subtract(&cost_basis)?,
+                realized_gain,
+            });
+        }
+
+        let total_unrealized_gain = total_market_value.subtract(&total_cost_basis)?;
+        Ok(PerformanceReport {
+            base_currency: base,
+            holdings,
+            total_cost_basis,
+            total_market_value,
+            total_unrealized_gain,
+            total_realized_gain,
+            total_gain: total_unrealized_gain.add(&total_realized_gain)?,
+            time_weighted_return: self.time_weighted_return()?,
+        })
+    }
+
+    /// Chain the return of each period between valuations
+    ///
+    /// A period's return is its closing value, less the contribution made at
+    /// its close, over its opening value, so deposits are not counted as growth.
+    fn time_weighted_return(&self) -> Result<Option<TimeWeightedReturn>> {
+        let (first, last) = match self.valuations.as_slice() {
+            [first, .., last] => (first, last),
+            _ => return Ok(None),
+        };
+
+        let mut growth = Decimal::ONE;
+        for pair in self.valuations.windows(2) {
+            let opening = self.exchange_rates.to_base(&pair[0].value)?;
+            if opening.amount() <= Decimal::ZERO {
+                return Err(FinancialError::InsufficientPortfolioData {
+                    missing: format!("a positive valuation on {}", pair[0].date),
+                });
+            }
+            let closing = self
+                .exchange_rates
+                .to_base(&pair[1].value)?
+                .subtract(&self.exchange_rates.to_base(&pair[1].net_contribution)?)?;
+            growth = growth
+                .checked_mul(closing.amount() / opening.amount())
+                .ok_or(FinancialError::Overflow)?;
+        }
+
+        Ok(Some(TimeWeightedReturn {
+            start_date: first.date,
+            end_date: last.date,
+            return_value: growth - Decimal::ONE,
+        }))
+    }
+
+    fn holding_index(&self, symbol: &str) -> Option<usize> {
+        self.holdings
+            .iter()
+            .position(|holding| holding.ledger.symbol == symbol)
+    }
+
+    fn require_holding(&self, symbol: &str) -> Result<usize> {
+        self.holding_index(symbol)
+            .ok_or_else(|| FinancialError::NotFound {
+                resource: format!("holding {}", symbol),
+            })
+    }
+
+    fn ensure_price_currency(ledger: &TaxLotLedger, price: &Money) -> Result<()> {
+        match ledger.lots().first() {
+            Some(lot) if lot.cost_basis.currency() != price.currency() => {
+                Err(FinancialError::CurrencyMismatch {
+                    expected: lot.cost_basis.currency(),
+                    actual: price.currency(),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::portfolio::tax_lots::TaxLot;
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    fn money(amount: Decimal, currency: Currency) -> Money {
+        Money::new(amount, currency).unwrap()
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, 0, 0, 0).unwrap()
+    }
+
+    fn ledger(symbol: &str, lots: &[(DateTime<Utc>, Decimal, Money)]) -> TaxLotLedger {
+        let mut ledger = TaxLotLedger::new(symbol.to_string());
+        for &(acquired_date, quantity, cost_basis) in lots {
+            ledger
+                .add_lot(TaxLot::new(acquired_date, quantity, cost_basis))
+                .unwrap();
+        }
+        ledger
+    }
+
+    /// VTI bought in dollars has risen; SAP bought in euros has fallen
+    fn tracker() -> PortfolioTracker {
+        let mut rates = ExchangeRates::new(Currency::USD);
+        rates.set_rate(Currency::EUR, dec!(1.10)).unwrap();
+        let mut tracker = PortfolioTracker::new(rates);
+
+        let usd = |amount| money(amount, Currency::USD);
+        let eur = |amount| money(amount, Currency::EUR);
+        tracker
+            .add_holding(
+                ledger(
+                    "VTI",
+                    &[
+                        (date(2022, 1, 10), dec!(10), usd(dec!(1000))),
+                        (date(2023, 3, 1), dec!(10), usd(dec!(1500))),
+                    ],
+                ),
+                usd(dec!(180)),
+            )
+            .unwrap();
+        tracker
+            .add_holding(
+                ledger("SAP", &[(date(2023, 6, 1), dec!(20), eur(dec!(3000)))]),
+                eur(dec!(120)),
+            )
+            .unwrap();
+        tracker
+    }
+
+    #[test]
+    fn test_appreciated_and_depreciated_holdings_net_in_base_currency() {
+        let mut tracker = tracker();
+
+        // Five VTI at $200 against the oldest $100 lot
+        let sale = LotSale {
+            sale_date: date(2024, 9, 1),
+            quantity: dec!(5),
+            proceeds: money(dec!(1000), Currency::USD),
+        };
+        let gain = tracker.sell("VTI", &sale, &CostBasisMethod::Fifo).unwrap();
+        assert_eq!(gain.gain.amount(), dec!(500));
+
+        let report = tracker.portfolio_performance().unwrap();
+        assert_eq!(report.base_currency, Currency::USD);
+
+        // 15 VTI @ $180 = $2700 against 5 @ $100 + 10 @ $150 = $2000
+        let vti = &report.holdings[0];
+        assert_eq!(vti.quantity, dec!(15));
+        assert_eq!(vti.cost_basis.amount(), dec!(2000));
+        assert_eq!(vti.market_value.amount(), dec!(2700));
+        assert_eq!(vti.unrealized_gain.amount(), dec!(700));
+        assert_eq!(vti.realized_gain.amount(), dec!(500));
+
+        // 20 SAP @ €120 = €2400 against €3000, at $1.10 per euro
+        let sap = &report.holdings[1];
+        assert_eq!(sap.cost_basis, money(dec!(3300), Currency::USD));
+        assert_eq!(sap.market_value.amount(), dec!(2640));
+        assert_eq!(sap.unrealized_gain.amount(), dec!(-660));
+        assert!(sap.realized_gain.amount().is_zero());
+
+        assert_eq!(report.total_cost_basis.amount(), dec!(5300));
+        assert_eq!(report.total_market_value.amount(), dec!(5340));
+        assert_eq!(report.total_unrealized_gain.amount(), dec!(40));
+        assert_eq!(report.total_realized_gain.amount(), dec!(500));
+        assert_eq!(report.total_gain.amount(), dec!(540));
+        assert!(report.time_weighted_return.is_none());
+    }
+
+    #[test]
+    fn test_time_weighted_return_ignores_contributions() {
+        let mut tracker = tracker();
+        let usd = |amount| money(amount, Currency::USD);
+
+        // +10% to $11000, a $5000 deposit, then -5%; recorded out of order
+        tracker.record_valuation(ValuationPoint {
+            date: date(2024, 7, 1),
+            value: usd(dec!(16000)),
+            net_contribution: usd(dec!(5000)),
+        });
+        tracker.record_valuation(ValuationPoint {
+            date: date(2024, 1, 1),
+            value: usd(dec!(10000)),
+            net_contribution: usd(dec!(10000)),
+        });
+        tracker.record_valuation(ValuationPoint {
+            date: date(2024, 12, 31),
+            value: usd(dec!(15200)),
+            net_contribution: usd(Decimal::ZERO),
+        });
+
+        let twr = tracker
+            .portfolio_performance()
+            .unwrap()
+            .time_weighted_return
+            .unwrap();
+        assert_eq!(twr.start_date, date(2024, 1, 1));
+        assert_eq!(twr.end_date, date(2024, 12, 31));
+        // 1.10 * 0.95 - 1
+        assert_eq!(twr.return_value, dec!(0.045));
+    }
+
+    #[test]
+    fn test_unknown_currency_is_rejected() {
+        let mut tracker = tracker();
+        tracker
+            .add_holding(
+                ledger(
+                    "7203",
+                    &[(
+                        date(2024, 2, 1),
+                        dec!(100),
+                        money(dec!(250000), Currency::JPY),
+                    )],
+                ),
+                money(dec!(2600), Currency::JPY),
+            )
+            .unwrap();
+
+        assert!(matches!(
+            tracker.portfolio_performance(),
+            Err(FinancialError::UnsupportedCurrencyOperation { .. })
+        ));
+    }
+}
@@ -4,6 +4,10 @@ use rust_decimal::prelude::ToPrimitive;
 /// Risk analysis and calculation module for portfolios
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
+use uuid::Uuid;
+
+/// Decimal places correlations are reported to
+const CORRELATION_SCALE: u32 = 6;
 
 /// Risk analysis engine for portfolio calculations
 pub struct RiskAnalyzer {
@@ -75,6 +79,25 @@ pub struct MonteCarloResult {
     pub standard_deviation: Money,
 }
 
+/// Pairwise correlations of asset return series
+///
+/// Symmetric, with 1 on the diagonal. Rows and columns follow the order of
+/// the return series the matrix was computed from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorrelationMatrix {
+    pub asset_ids: Vec<Uuid>,
+    pub symbols: Vec<String>,
+    /// `values[i][j]` is the correlation between assets `i` and `j`
+    pub values: Vec<Vec<Decimal>>,
+}
+
+impl CorrelationMatrix {
+    /// Correlation between the assets at positions `i` and `j`
+    pub fn get(&self, i: usize, j: usize) -> Option<Decimal> {
+        self.values.get(i).and_then(|row| row.get(j)).copied()
+    }
+}
+
 impl RiskAnalyzer {
     /// Create a new risk analyzer
     pub fn new() -> Self {
@@ -84,6 +107,63 @@ impl RiskAnalyzer {
         }
     }
 
+    /// Pearson correlation between every pair of asset return series
+    ///
+    /// All series must cover the same periods. A series that never changes has
+    /// no defined correlation and is reported as uncorrelated (0) with the
+    /// others.
+    pub fn correlation_matrix(&self, returns: &[HistoricalReturns]) -> Result<CorrelationMatrix> {
+        let periods = returns.first().map_or(0, |r| r.returns.len());
+        if periods < 2 {
+            return Err(FinancialError::InsufficientRiskData {
+                missing: "At least two returns per asset are required".to_string(),
+            });
+        }
+        if returns.iter().any(|r| r.returns.len() != periods) {
+            return Err(FinancialError::InsufficientRiskData {
+                missing: "Mismatched return series lengths".to_string(),
+            });
+        }
+
+        // Deviations from each series' mean and their sum of squares
+        let mut deviations = Vec::with_capacity(returns.len());
+        for series in returns {
+            let values: Vec<Decimal> = series.returns.iter().map(|r| r.return_value).collect();
+            let mean = self.calculate_mean(&values)?;
+            let series_deviations: Vec<Decimal> = values.iter().map(|v| v - mean).collect();
+            let sum_of_squares: Decimal = series_deviations.iter().map(|d| d * d).sum();
+            deviations.push((series_deviations, sum_of_squares));
+        }
+
+        let n = returns.len();
+        let mut values = vec![vec![Decimal::ZERO; n]; n];
+        for i in 0..n {
+            values[i][i] = Decimal::ONE;
+            for j in (i + 1)..n {
+                let (di, ssi) = &deviations[i];
+                let (dj, ssj) = &deviations[j];
+                let denominator = calculate_sqrt(ssi * ssj);
+                let correlation = if denominator.is_zero() {
+                    Decimal::ZERO
+                } else {
+                    let covariance: Decimal = di.iter().zip(dj).map(|(a, b)| a * b).sum();
+                    (covariance / denominator)
+                        .round_dp(CORRELATION_SCALE)
+                        .clamp(-Decimal::ONE, Decimal::ONE)
+                        .normalize()
+                };
+                values[i][j] = correlation;
+                values[j][i] = correlation;
+            }
+        }
+
+        Ok(CorrelationMatrix {
+            asset_ids: returns.iter().map(|r| r.asset_id).collect(),
+            symbols: returns.iter().map(|r| r.symbol.clone()).collect(),
+            values,
+        })
+    }
+
     /// Calculate comprehensive risk metrics for a portfolio
     pub fn calculate_risk_metrics(
         &self,
@@ -681,6 +761,63 @@ mod tests {
         assert!(max_dd >= Decimal::ZERO);
     }
 
+    fn series(symbol: &str, values: &[Decimal]) -> HistoricalReturns {
+        HistoricalReturns {
+            asset_id: Uuid::new_v4(),
+            symbol: symbol.to_string(),
+            returns: values
+                .iter()
+                .map(|&return_value| PeriodReturn {
+                    date: chrono::Utc::now(),
+                    return_value,
+                    adjusted_close: None,
+                })
+                .collect(),
+            frequency: crate::portfolio::types::ReturnFrequency::Monthly,
+        }
+    }
+
+    #[test]
+    fn test_correlation_matrix_symmetric_with_unit_diagonal() {
+        let analyzer = RiskAnalyzer::new();
+        let stocks = [dec!(0.05), dec!(-0.02), dec!(0.03), dec!(-0.01), dec!(0.04)];
+        // Same moves scaled and shifted: perfectly correlated
+        let leveraged: Vec<Decimal> = stocks.iter().map(|r| r * dec!(2) + dec!(0.01)).collect();
+        let inverse: Vec<Decimal> = stocks.iter().map(|r| -r).collect();
+        let bonds = [dec!(0.01), dec!(0.02), dec!(-0.01), dec!(0.00), dec!(0.01)];
+
+        let matrix = analyzer
+            .correlation_matrix(&[
+                series("VTI", &stocks),
+                series("SSO", &leveraged),
+                series("SH", &inverse),
+                series("BND", &bonds),
+            ])
+            .unwrap();
+
+        assert_eq!(matrix.symbols, vec!["VTI", "SSO", "SH", "BND"]);
+        for i in 0..4 {
+            assert_eq!(matrix.get(i, i), Some(Decimal::ONE));
+            for j in 0..4 {
+                assert_eq!(matrix.get(i, j), matrix.get(j, i));
+                let value = matrix.get(i, j).unwrap();
+                assert!(value >= -Decimal::ONE && value <= Decimal::ONE);
+            }
+        }
+        assert_eq!(matrix.get(0, 1), Some(Decimal::ONE));
+        assert_eq!(matrix.get(0, 2), Some(-Decimal::ONE));
+        assert!(matrix.get(0, 3).unwrap().abs() < Decimal::ONE);
+
+        // Constant series are uncorrelated, mismatched lengths are rejected
+        let flat = analyzer
+            .correlation_matrix(&[series("CASH", &[dec!(0.001); 5]), series("VTI", &stocks)])
+            .unwrap();
+        assert_eq!(flat.get(0, 1), Some(Decimal::ZERO));
+        assert!(analyzer
+            .correlation_matrix(&[series("VTI", &stocks), series("BND", &bonds[..3])])
+            .is_err());
+    }
+
     #[test]
     fn test_simple_random_generator() {
         let mut rng = SimpleRandomGenerator::new(42);
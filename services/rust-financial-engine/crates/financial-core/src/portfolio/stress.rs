@@ -0,0 +1,293 @@
+use crate::portfolio::types::{Asset, Portfolio};
+use crate::types::AssetClass;
+use crate::Money;
+/// Portfolio stress testing under named market shocks
+///
+/// A scenario is plain data, a fractional price change per asset class, so
+/// scenarios can be loaded from configuration and combined with each other.
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Price change applied to every holding of one asset class
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssetClassShock {
+    pub asset_class: AssetClass,
+    /// Fractional change in value, e.g. `-0.30` for a 30% fall
+    pub change: Decimal,
+}
+
+/// A named market shock, such as "2008" or "rate +200bps"
+///
+/// Asset classes without a shock keep their value.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShockScenario {
+    pub name: String,
+    pub shocks: Vec<AssetClassShock>,
+}
+
+/// Effect of a scenario on one holding
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HoldingImpact {
+    pub asset_id: Uuid,
+    pub symbol: String,
+    pub asset_class: AssetClass,
+    pub current_value: Money,
+    pub stressed_value: Money,
+    pub value_change: Money,
+    /// Fractional change applied to the holding
+    pub shock: Decimal,
+}
+
+/// Projected portfolio value after a scenario
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StressResult {
+    pub scenario_name: String,
+    pub current_value: Money,
+    pub stressed_value: Money,
+    pub value_change: Money,
+    /// `value_change` as a fraction of the current value
+    pub percentage_change: Decimal,
+    /// Holdings that lose value, largest loss first
+    pub worst_hit_holdings: Vec<HoldingImpact>,
+}
+
+impl ShockScenario {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            shocks: Vec::new(),
+        }
+    }
+
+    /// Add a shock to `asset_class`, compounding with any it already has
+    pub fn with_shock(mut self, asset_class: AssetClass, change: Decimal) -> Self {
+        match self
+            .shocks
+            .iter_mut()
+            .find(|shock| shock.asset_class == asset_class)
+        {
+            Some(shock) => shock.change = compound(shock.change, change),
+            None => self.shocks.push(AssetClassShock {
+                asset_class,
+                change,
+            }),
+        }
+        self
+    }
+
+    /// One scenario followed by another
+    ///
+    /// Shocks to the same asset class compound, so a 30% fall followed by a
+    /// 10% fall is a 37% fall.
+    pub fn then(self, other: ShockScenario) -> Self {
+        let name = format!("{} + {}", self.name, other.name);
+        let mut combined = other.shocks.into_iter().fold(self, |scenario, shock| {
+            scenario.with_shock(shock.asset_class, shock.change)
+        });
+        combined.name = name;
+        combined
+    }
+
+    /// Fractional change for holdings of `asset_class`
+    ///
+    /// A holding can lose at most its whole value.
+    pub fn shock_for(&self, asset_class: &AssetClass) -> Decimal {
+        self.shocks
+            .iter()
+            .find(|shock| shock.asset_class == *asset_class)
+            .map_or(Decimal::ZERO, |shock| shock.change.max(-Decimal::ONE))
+    }
+
+    /// Stocks fall by `fall`, e.g. `dec!(0.30)` for "equity -30%"
+    pub fn equity_crash(fall: Decimal) -> Self {
+        Self::new(format!("equity -{}%", (fall * dec!(100)).normalize()))
+            .with_shock(AssetClass::Stocks, -fall)
+    }
+
+    /// Interest rates rise by `basis_points`
+    ///
+    /// Bonds lose their duration times the rise, assuming a 6 year duration;
+    /// real estate, also rate sensitive, loses half as much.
+    pub fn rate_rise(basis_points: Decimal) -> Self {
+        let bond_change = -dec!(6) * basis_points / dec!(10000);
+        Self::new(format!("rate +{}bps", basis_points.normalize()))
+            .with_shock(AssetClass::Bonds, bond_change)
+            .with_shock(AssetClass::RealEstate, bond_change / dec!(2))
+    }
+
+    /// Peak-to-trough moves of the 2008 financial crisis
+    pub fn financial_crisis_2008() -> Self {
+        Self::new("2008")
+            .with_shock(AssetClass::Stocks, dec!(-0.37))
+            .with_shock(AssetClass::RealEstate, dec!(-0.40))
+            .with_shock(AssetClass::Commodities, dec!(-0.35))
+            .with_shock(AssetClass::Alternative, dec!(-0.20))
+            .with_shock(AssetClass::Crypto, dec!(-0.50))
+            .with_shock(AssetClass::Bonds, dec!(0.05))
+    }
+
+    /// The built-in scenarios, for listing to users
+    pub fn presets() -> Vec<ShockScenario> {
+        vec![
+            Self::financial_crisis_2008(),
+            Self::rate_rise(dec!(200)),
+            Self::equity_crash(dec!(0.30)),
+        ]
+    }
+}
+
+fn compound(first: Decimal, second: Decimal) -> Decimal {
+    (Decimal::ONE + first) * (Decimal::ONE + second) - Decimal::ONE
+}
+
+impl Portfolio {
+    /// Project the portfolio's value under `scenario`
+    ///
+    /// Every holding moves by its asset class's shock; the result totals the
+    /// change and lists the holdings that lose value, worst first.
+    pub fn stress_test(&self, scenario: ShockScenario) -> StressResult {
+        let current_value = self.total_value();
+        let currency = current_value.currency();
+
+        let mut impacts: Vec<HoldingImpact> = self
+            .assets
+            .iter()
+            .map(|asset| holding_impact(asset, &scenario))
+            .collect();
+        let stressed_total: Decimal = impacts.iter().map(|i| i.stressed_value.amount()).sum();
+        let change = stressed_total - current_value.amount();
+
+        impacts.retain(|impact| impact.value_change.amount().is_sign_negative());
+        impacts.sort_by(|a, b| {
+            a.value_change
+                .amount()
+                .cmp(&b.value_change.amount())
+                .then_with(|| a.symbol.cmp(&b.symbol))
+        });
+
+        StressResult {
+            scenario_name: scenario.name,
+            current_value,
+            stressed_value: Money::new_unchecked(stressed_total, currency),
+            value_change: Money::new_unchecked(change, currency),
+            percentage_change: if current_value.amount().is_zero() {
+                Decimal::ZERO
+            } else {
+                change / current_value.amount()
+            },
+            worst_hit_holdings: impacts,
+        }
+    }
+}
+
+fn holding_impact(asset: &Asset, scenario: &ShockScenario) -> HoldingImpact {
+    let shock = scenario.shock_for(&asset.asset_class);
+    let currency = asset.current_value.currency();
+    let change = asset.current_value.amount() * shock;
+    HoldingImpact {
+        asset_id: asset.id,
+        symbol: asset.symbol.clone(),
+        asset_class: asset.asset_class.clone(),
+        current_value: asset.current_value,
+        stressed_value: Money::new_unchecked(asset.current_value.amount() + change, currency),
+        value_change: Money::new_unchecked(change, currency),
+        shock,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Currency;
+
+    fn portfolio(holdings: &[(&str, AssetClass, Decimal)]) -> Portfolio {
+        let mut portfolio = Portfolio::new(Uuid::new_v4(), "Test".to_string());
+        for (symbol, class, value) in holdings {
+            let value = Money::new(*value, Currency::USD).unwrap();
+            portfolio.assets.push(Asset::new(
+                symbol.to_string(),
+                symbol.to_string(),
+                class.clone(),
+                dec!(10),
+                value,
+                value,
+            ));
+        }
+        portfolio
+    }
+
+    #[test]
+    fn test_equity_crash_hits_equity_heavy_portfolios_hardest() {
+        let equity_heavy = portfolio(&[
+            ("VTI", AssetClass::Stocks, dec!(70000)),
+            ("VXUS", AssetClass::Stocks, dec!(10000)),
+            ("BND", AssetClass::Bonds, dec!(20000)),
+        ]);
+        let bond_heavy = portfolio(&[
+            ("VTI", AssetClass::Stocks, dec!(20000)),
+            ("BND", AssetClass::Bonds, dec!(70000)),
+            ("CASH", AssetClass::Cash, dec!(10000)),
+        ]);
+        let crash = ShockScenario::equity_crash(dec!(0.30));
+        assert_eq!(crash.name, "equity -30%");
+
+        let equity_result = equity_heavy.stress_test(crash.clone());
+        let bond_result = bond_heavy.stress_test(crash);
+        assert_eq!(equity_result.value_change.amount(), dec!(-24000));
+        assert_eq!(equity_result.percentage_change, dec!(-0.24));
+        assert_eq!(bond_result.value_change.amount(), dec!(-6000));
+        assert!(equity_result.value_change.amount() < bond_result.value_change.amount());
+        assert_eq!(equity_result.stressed_value.amount(), dec!(76000));
+
+        // Only the stocks lose value, the largest holding first
+        let worst: Vec<&str> = equity_result
+            .worst_hit_holdings
+            .iter()
+            .map(|h| h.symbol.as_str())
+            .collect();
+        assert_eq!(worst, ["VTI", "VXUS"]);
+        assert_eq!(
+            equity_result.worst_hit_holdings[0].value_change.amount(),
+            dec!(-21000)
+        );
+        assert_eq!(bond_result.worst_hit_holdings.len(), 1);
+    }
+
+    #[test]
+    fn test_scenarios_compose_and_floor_at_zero() {
+        let combined =
+            ShockScenario::equity_crash(dec!(0.30)).then(ShockScenario::rate_rise(dec!(200)));
+        assert_eq!(combined.name, "equity -30% + rate +200bps");
+        assert_eq!(combined.shock_for(&AssetClass::Stocks), dec!(-0.30));
+        assert_eq!(combined.shock_for(&AssetClass::Bonds), dec!(-0.12));
+        assert_eq!(combined.shock_for(&AssetClass::Cash), Decimal::ZERO);
+
+        // Repeated shocks to a class compound rather than add
+        let double_dip =
+            ShockScenario::equity_crash(dec!(0.30)).then(ShockScenario::equity_crash(dec!(0.10)));
+        assert_eq!(double_dip.shock_for(&AssetClass::Stocks), dec!(-0.37));
+
+        // Scenarios are plain data; a shock can never take a holding below zero
+        let scenario = ShockScenario {
+            name: "crypto winter".to_string(),
+            shocks: vec![AssetClassShock {
+                asset_class: AssetClass::Crypto,
+                change: dec!(-1.5),
+            }],
+        };
+        let result = portfolio(&[("BTC", AssetClass::Crypto, dec!(5000))]).stress_test(scenario);
+        assert_eq!(result.stressed_value.amount(), Decimal::ZERO);
+
+        let names: Vec<String> = ShockScenario::presets()
+            .into_iter()
+            .map(|s| s.name)
+            .collect();
+        assert_eq!(names, ["2008", "rate +200bps", "equity -30%"]);
+        let crisis = portfolio(&[("BND", AssetClass::Bonds, dec!(1000))])
+            .stress_test(ShockScenario::financial_crisis_2008());
+        assert!(crisis.worst_hit_holdings.is_empty());
+        assert_eq!(crisis.value_change.amount(), dec!(50));
+    }
+}
@@ -0,0 +1,496 @@
+/// Tax-lot cost basis tracking for investment holdings
+///
+/// Each purchase of a holding is kept as a separate lot so that a sale can be
+/// matched against specific purchases and its realized gain reported under
+/// FIFO, LIFO, or specific-lot identification.
+use crate::types::Money;
+use crate::{FinancialError, Result};
+use chrono::{DateTime, Months, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Shares bought in a single purchase
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaxLot {
+    pub id: Uuid,
+    pub acquired_date: DateTime<Utc>,
+    pub quantity: Decimal,
+    /// Total amount paid for the lot, including fees
+    pub cost_basis: Money,
+}
+
+impl TaxLot {
+    /// Create a new tax lot
+    pub fn new(acquired_date: DateTime<Utc>, quantity: Decimal, cost_basis: Money) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            acquired_date,
+            quantity,
+            cost_basis,
+        }
+    }
+
+    /// Cost basis of `quantity` units of this lot
+    ///
+    /// Selling the whole lot returns its full cost basis, so partial sales
+    /// never leave rounding residue behind.
+    fn cost_basis_of(&self, quantity: Decimal) -> Result<Money> {
+        if quantity == self.quantity {
+            Ok(self.cost_basis)
+        } else {
+            self.cost_basis.divide(self.quantity)?.multiply(quantity)
+        }
+    }
+}
+
+/// Which lots a sale is matched against
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CostBasisMethod {
+    /// Oldest lots first
+    Fifo,
+    /// Newest lots first
+    Lifo,
+    /// Exactly the lots and quantities chosen by the investor
+    SpecificLots(Vec<LotSelection>),
+}
+
+/// Quantity to sell from one chosen lot
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LotSelection {
+    pub lot_id: Uuid,
+    pub quantity: Decimal,
+}
+
+/// A sale of part or all of a holding
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LotSale {
+    pub sale_date: DateTime<Utc>,
+    pub quantity: Decimal,
+    /// Total amount received for the sale, net of fees
+    pub proceeds: Money,
+}
+
+/// Tax treatment of a gain by how long the lot was held
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HoldingPeriod {
+    /// Held one year or less
+    ShortTerm,
+    /// Held more than one year
+    LongTerm,
+}
+
+impl HoldingPeriod {
+    fn between(acquired_date: DateTime<Utc>, sale_date: DateTime<Utc>) -> Self {
+        match acquired_date.checked_add_months(Months::new(12)) {
+            Some(one_year) if sale_date > one_year => HoldingPeriod::LongTerm,
+            _ => HoldingPeriod::ShortTerm,
+        }
+    }
+}
+
+/// The part of a sale matched against one lot
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LotDisposal {
+    pub lot_id: Uuid,
+    pub acquired_date: DateTime<Utc>,
+    pub quantity: Decimal,
+    pub cost_basis: Money,
+    pub proceeds: Money,
+    pub gain: Money,
+    pub holding_period: HoldingPeriod,
+}
+
+/// Realized gain or loss of a sale, broken down by lot
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RealizedGain {
+    pub sale_date: DateTime<Utc>,
+    pub quantity: Decimal,
+    pub proceeds: Money,
+    pub cost_basis: Money,
+    /// Proceeds minus cost basis; negative for a loss
+    pub gain: Money,
+    pub disposals: Vec<LotDisposal>,
+}
+
+/// Open tax lots of a single holding
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaxLotLedger {
+    pub symbol: String,
+    lots: Vec<TaxLot>,
+}
+
+impl TaxLotLedger {
+    /// Create an empty ledger for a holding
+    pub fn new(symbol: String) -> Self {
+        Self {
+            symbol,
+            lots: Vec::new(),
+        }
+    }
+
+    /// Open lots in the order they were added
+    pub fn lots(&self) -> &[TaxLot] {
+        &self.lots
+    }
+
+    /// Record a purchase
+    pub fn add_lot(&mut self, lot: TaxLot) -> Result<()> {
+        if lot.quantity <= Decimal::ZERO {
+            return Err(FinancialError::invalid_parameter(
+                "quantity",
+                &lot.quantity.to_string(),
+            ));
+        }
+        if lot.cost_basis.is_negative() {
+            return Err(FinancialError::invalid_parameter(
+                "cost_basis",
+                &lot.cost_basis.to_string(),
+            ));
+        }
+        if let Some(existing) = self.lots.first() {
+            if existing.cost_basis.currency() != lot.cost_basis.currency() {
+                return Err(FinancialError::CurrencyMismatch {
+                    expected: existing.cost_basis.currency(),
+                    actual: lot.cost_basis.currency(),
+                });
+            }
+        }
+        self.lots.push(lot);
+        Ok(())
+    }
+
+    /// Units held across all open lots
+    pub fn total_quantity(&self) -> Decimal {
+        self.lots.iter().map(|lot| lot.quantity).sum()
+    }
+
+    /// Realized gain `sale` would produce under `method`, without selling
+    pub fn realized_gain(&self, sale: &LotSale, method: &CostBasisMethod) -> Result<RealizedGain> {
+        let matches = self.match_lots(sale, method)?;
+        self.compute_gain(sale, &matches)
+    }
+
+    /// Sell from the ledger, removing the matched quantity from its lots
+    ///
+    /// The ledger is left unchanged if the sale cannot be matched.
+    pub fn sell(&mut self, sale: &LotSale, method: &CostBasisMethod) -> Result<RealizedGain> {
+        let matches = self.match_lots(sale, method)?;
+        let gain = self.compute_gain(sale, &matches)?;
+
+        for (index, quantity) in matches {
+            let lot = &mut self.lots[index];
+            let sold_cost = lot.cost_basis_of(quantity)?;
+            lot.cost_basis = lot.cost_basis.subtract(&sold_cost)?;
+            lot.quantity -= quantity;
+        }
+        self.lots.retain(|lot| !lot.quantity.is_zero());
+
+        Ok(gain)
+    }
+
+    /// Lot indices and the quantity taken from each, in disposal order
+    fn match_lots(
+        &self,
+        sale: &LotSale,
+        method: &CostBasisMethod,
+    ) -> Result<Vec<(usize, Decimal)>> {
+        if sale.quantity <= Decimal::ZERO {
+            return Err(FinancialError::invalid_parameter(
+                "quantity",
+                &sale.quantity.to_string(),
+            ));
+        }
+
+        let available = self.total_quantity();
+        if sale.quantity > available {
+            return Err(FinancialError::InsufficientLotQuantity {
+                requested: sale.quantity.to_string(),
+                available: available.to_string(),
+            });
+        }
+
+        let order: Vec<usize> = match method {
+            CostBasisMethod::SpecificLots(selections) => {
+                return self.match_specific(sale, selections)
+            }
+            CostBasisMethod::Fifo => self.indices_by_acquired_date(),
+            CostBasisMethod::Lifo => self.indices_by_acquired_date().into_iter().rev().collect(),
+        };
+
+        let mut remaining = sale.quantity;
+        let mut matches = Vec::new();
+        for index in order {
+            if remaining.is_zero() {
+                break;
+            }
+            let quantity = remaining.min(self.lots[index].quantity);
+            matches.push((index, quantity));
+            remaining -= quantity;
+        }
+        Ok(matches)
+    }
+
+    /// Lot indices oldest first; lots acquired together keep the order they were added
+    fn indices_by_acquired_date(&self) -> Vec<usize> {
+        let mut indices: Vec<usize> = (0..self.lots.len()).collect();
+        indices.sort_by_key(|&index| self.lots[index].acquired_date);
+        indices
+    }
+
+    fn match_specific(
+        &self,
+        sale: &LotSale,
+        selections: &[LotSelection],
+    ) -> Result<Vec<(usize, Decimal)>> {
+        let mut matches: Vec<(usize, Decimal)> = Vec::with_capacity(selections.len());
+        for selection in selections {
+            let index = self
+                .lots
+                .iter()
+                .position(|lot| lot.id == selection.lot_id)
+                .ok_or_else(|| FinancialError::NotFound {
+                    resource: format!("tax lot {}", selection.lot_id),
+                })?;
+            if selection.quantity <= Decimal::ZERO {
+                return Err(FinancialError::invalid_parameter(
+                    "quantity",
+                    &selection.quantity.to_string(),
+                ));
+            }
+
+            // Selecting the same lot twice draws on the same shares
+            let already_selected: Decimal = matches
+                .iter()
+                .filter(|(matched, _)| *matched == index)
+                .map(|(_, quantity)| *quantity)
+                .sum();
+            let available = self.lots[index].quantity - already_selected;
+            if selection.quantity > available {
+                return Err(FinancialError::InsufficientLotQuantity {
+                    requested: selection.quantity.to_string(),
+                    available: available.to_string(),
+                });
+            }
+            matches.push((index, selection.quantity));
+        }
+
+        let selected: Decimal = matches.iter().map(|(_, quantity)| *quantity).sum();
+        if selected != sale.quantity {
+            return Err(FinancialError::ValidationError(format!(
+                "Selected lots total {} units but the sale is for {}",
+                selected, sale.quantity
+            )));
+        }
+        Ok(matches)
+    }
+
+    fn compute_gain(&self, sale: &LotSale, matches: &[(usize, Decimal)]) -> Result<RealizedGain> {
+        let currency = sale.proceeds.currency();
+        let zero = Money::new_unchecked(Decimal::ZERO, currency);
+        let proceeds_per_unit = sale.proceeds.divide(sale.quantity)?;
+
+        let mut disposals = Vec::with_capacity(matches.len());
+        let mut allocated_proceeds = zero;
+        let mut total_cost_basis = zero;
+        for (position, &(index, quantity)) in matches.iter().enumerate() {
+            let lot = &self.lots[index];
+            let cost_basis = lot.cost_basis_of(quantity)?;
+
+            // The last lot takes whatever proceeds are left so the parts sum exactly
+            let proceeds = if position + 1 == matches.len() {
+                sale.proceeds.subtract(&allocated_proceeds)?
+            } else {
+                proceeds_per_unit.multiply(quantity)?
+            };
+            allocated_proceeds = allocated_proceeds.add(&proceeds)?;
+            total_cost_basis = total_cost_basis.add(&cost_basis)?;
+
+            disposals.push(LotDisposal {
+                lot_id: lot.id,
+                acquired_date: lot.acquired_date,
+                quantity,
+                cost_basis,
+                proceeds,
+                gain: proceeds.subtract(&cost_basis)?,
+                holding_period: HoldingPeriod::between(lot.acquired_date, sale.sale_date),
+            });
+        }
+
+        Ok(RealizedGain {
+            sale_date: sale.sale_date,
+            quantity: sale.quantity,
+            proceeds: sale.proceeds,
+            cost_basis: total_cost_basis,
+            gain: sale.proceeds.subtract(&total_cost_basis)?,
+            disposals,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Currency;
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    fn usd(amount: Decimal) -> Money {
+        Money::new(amount, Currency::USD).unwrap()
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, 0, 0, 0).unwrap()
+    }
+
+    /// Ten shares each at $100, $150, and $200, bought a year or more apart
+    fn ledger() -> TaxLotLedger {
+        let mut ledger = TaxLotLedger::new("VTI".to_string());
+        // Added out of order: matching goes by acquired date, not insertion
+        ledger
+            .add_lot(TaxLot::new(date(2023, 3, 1), dec!(10), usd(dec!(1500))))
+            .unwrap();
+        ledger
+            .add_lot(TaxLot::new(date(2022, 1, 10), dec!(10), usd(dec!(1000))))
+            .unwrap();
+        ledger
+            .add_lot(TaxLot::new(date(2024, 6, 1), dec!(10), usd(dec!(2000))))
+            .unwrap();
+        ledger
+    }
+
+    /// Fifteen shares at $200 each
+    fn sale() -> LotSale {
+        LotSale {
+            sale_date: date(2024, 9, 1),
+            quantity: dec!(15),
+            proceeds: usd(dec!(3000)),
+        }
+    }
+
+    #[test]
+    fn test_fifo_and_lifo_sell_across_lots() {
+        let mut fifo_ledger = ledger();
+        let fifo = fifo_ledger.sell(&sale(), &CostBasisMethod::Fifo).unwrap();
+
+        // 10 @ $100 + 5 @ $150
+        assert_eq!(fifo.cost_basis.amount(), dec!(1750));
+        assert_eq!(fifo.gain.amount(), dec!(1250));
+        assert_eq!(fifo.disposals.len(), 2);
+        assert_eq!(fifo.disposals[0].acquired_date, date(2022, 1, 10));
+        assert_eq!(fifo.disposals[0].gain.amount(), dec!(1000));
+        assert_eq!(fifo.disposals[1].quantity, dec!(5));
+        assert_eq!(fifo.disposals[1].gain.amount(), dec!(250));
+        assert!(fifo
+            .disposals
+            .iter()
+            .all(|d| d.holding_period == HoldingPeriod::LongTerm));
+
+        let mut lifo_ledger = ledger();
+        let lifo = lifo_ledger.sell(&sale(), &CostBasisMethod::Lifo).unwrap();
+
+        // 10 @ $200 + 5 @ $150
+        assert_eq!(lifo.cost_basis.amount(), dec!(2750));
+        assert_eq!(lifo.gain.amount(), dec!(250));
+        assert_eq!(lifo.disposals[0].acquired_date, date(2024, 6, 1));
+        assert_eq!(lifo.disposals[0].gain.amount(), Decimal::ZERO);
+        assert_eq!(lifo.disposals[0].holding_period, HoldingPeriod::ShortTerm);
+
+        // Both sell five $150 shares; FIFO's other ten cost $100 less apiece
+        assert_eq!(
+            fifo.gain.amount() - lifo.gain.amount(),
+            dec!(10) * (dec!(200) - dec!(100))
+        );
+
+        // Both leave 15 shares, with the partly sold $150 lot keeping its unit cost
+        for ledger in [&fifo_ledger, &lifo_ledger] {
+            assert_eq!(ledger.total_quantity(), dec!(15));
+            let partial = ledger
+                .lots()
+                .iter()
+                .find(|lot| lot.quantity == dec!(5))
+                .unwrap();
+            assert_eq!(partial.cost_basis.amount(), dec!(750));
+        }
+    }
+
+    #[test]
+    fn test_specific_lot_sale() {
+        let mut ledger = ledger();
+        let newest = ledger.lots()[2].id;
+        let oldest = ledger.lots()[1].id;
+
+        let sale = LotSale {
+            sale_date: date(2024, 9, 1),
+            quantity: dec!(6),
+            proceeds: usd(dec!(1200)),
+        };
+        let method = CostBasisMethod::SpecificLots(vec![
+            LotSelection {
+                lot_id: newest,
+                quantity: dec!(4),
+            },
+            LotSelection {
+                lot_id: oldest,
+                quantity: dec!(2),
+            },
+        ]);
+
+        let preview = ledger.realized_gain(&sale, &method).unwrap();
+        let gain = ledger.sell(&sale, &method).unwrap();
+        assert_eq!(preview, gain);
+
+        // 4 @ $200 + 2 @ $100
+        assert_eq!(gain.cost_basis.amount(), dec!(1000));
+        assert_eq!(gain.gain.amount(), dec!(200));
+        assert_eq!(gain.disposals[0].proceeds.amount(), dec!(800));
+        assert_eq!(gain.disposals[1].proceeds.amount(), dec!(400));
+        assert_eq!(ledger.total_quantity(), dec!(24));
+
+        // The chosen lots must add up to the sale
+        let short = CostBasisMethod::SpecificLots(vec![LotSelection {
+            lot_id: newest,
+            quantity: dec!(1),
+        }]);
+        assert!(matches!(
+            ledger.realized_gain(&sale, &short),
+            Err(FinancialError::ValidationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_selling_more_than_held_is_rejected() {
+        let mut ledger = ledger();
+        let before = ledger.clone();
+        let oversell = LotSale {
+            sale_date: date(2024, 9, 1),
+            quantity: dec!(31),
+            proceeds: usd(dec!(6200)),
+        };
+
+        for method in [CostBasisMethod::Fifo, CostBasisMethod::Lifo] {
+            assert!(matches!(
+                ledger.sell(&oversell, &method),
+                Err(FinancialError::InsufficientLotQuantity { .. })
+            ));
+        }
+
+        // A single lot cannot give up more than it holds either
+        let lot_id = ledger.lots()[0].id;
+        let from_one_lot = LotSale {
+            quantity: dec!(11),
+            ..oversell
+        };
+        let method = CostBasisMethod::SpecificLots(vec![LotSelection {
+            lot_id,
+            quantity: dec!(11),
+        }]);
+        assert!(matches!(
+            ledger.sell(&from_one_lot, &method),
+            Err(FinancialError::InsufficientLotQuantity { .. })
+        ));
+
+        // Failed sales leave every lot untouched
+        assert_eq!(ledger, before);
+    }
+}
@@ -50,6 +50,19 @@ pub struct PeriodReturn {
     pub adjusted_close: Option<Money>,
 }
 
+/// One fall from a peak value and the climb back to it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DrawdownPeriod {
+    pub peak_date: DateTime<Utc>,
+    pub trough_date: DateTime<Utc>,
+    /// First date the value was back at its peak; `None` while still below it
+    pub recovery_date: Option<DateTime<Utc>>,
+    /// Fall from peak to trough as a fraction of the peak value
+    pub depth: Decimal,
+    /// Periods from the peak to the recovery, or to the end of the series
+    pub duration_periods: usize,
+}
+
 /// Return calculation frequency
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ReturnFrequency {
@@ -100,6 +113,37 @@ pub struct OptimizationConstraints {
     pub include_cash: bool,
     pub allow_short_selling: bool,
     pub transaction_cost: Option<Percentage>,
+    /// Per-asset limits, applied on top of `min_asset_weight`/`max_asset_weight`
+    #[serde(default)]
+    pub asset_bounds: Vec<AssetWeightBounds>,
+    #[serde(default)]
+    pub group_constraints: Vec<GroupWeightConstraint>,
+    /// Most assets the allocation may hold
+    #[serde(default)]
+    pub max_assets: Option<usize>,
+}
+
+/// Weight limits for one asset, matched by symbol
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssetWeightBounds {
+    pub symbol: String,
+    pub min_weight: Option<Percentage>,
+    pub max_weight: Option<Percentage>,
+}
+
+/// Limits on the combined weight of a group of assets, e.g. equities ≤ 70%
+///
+/// An asset belongs to the group when its class is in `asset_classes` or its
+/// symbol is in `symbols`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroupWeightConstraint {
+    pub name: String,
+    #[serde(default)]
+    pub asset_classes: Vec<AssetClass>,
+    #[serde(default)]
+    pub symbols: Vec<String>,
+    pub min_weight: Option<Percentage>,
+    pub max_weight: Option<Percentage>,
 }
 
 /// Risk tolerance levels
@@ -138,6 +182,15 @@ pub struct TradeRecommendation {
     pub reason: String,
 }
 
+/// Portfolio on the efficient frontier
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FrontierPoint {
+    pub expected_return: Decimal,
+    pub volatility: Decimal,
+    /// Long-only weights summing to 1, in the order of the return series
+    pub weights: Vec<Decimal>,
+}
+
 /// Trade action type
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TradeAction {
@@ -232,6 +285,55 @@ impl Portfolio {
     }
 }
 
+impl HistoricalReturns {
+    /// Every drawdown in the series, in date order
+    ///
+    /// Returns compound from a starting value of 1; each return's date is the
+    /// date of the value after it. A fall in the first period is measured
+    /// from the starting value and uses the first date as its peak date.
+    pub fn drawdown_periods(&self) -> Vec<DrawdownPeriod> {
+        let Some(first) = self.returns.first() else {
+            return Vec::new();
+        };
+
+        let mut periods = Vec::new();
+        let mut value = Decimal::ONE;
+        let (mut peak, mut peak_date, mut peak_index) = (Decimal::ONE, first.date, 0);
+        // Trough value and date of the drawdown in progress
+        let mut trough: Option<(Decimal, DateTime<Utc>)> = None;
+
+        for (index, period) in self.returns.iter().enumerate() {
+            value *= Decimal::ONE + period.return_value;
+
+            if value >= peak {
+                if let Some((trough_value, trough_date)) = trough.take() {
+                    periods.push(DrawdownPeriod {
+                        peak_date,
+                        trough_date,
+                        recovery_date: Some(period.date),
+                        depth: (peak - trough_value) / peak,
+                        duration_periods: index + 1 - peak_index,
+                    });
+                }
+                (peak, peak_date, peak_index) = (value, period.date, index + 1);
+            } else if trough.is_none_or(|(trough_value, _)| value < trough_value) {
+                trough = Some((value, period.date));
+            }
+        }
+
+        if let Some((trough_value, trough_date)) = trough {
+            periods.push(DrawdownPeriod {
+                peak_date,
+                trough_date,
+                recovery_date: None,
+                depth: (peak - trough_value) / peak,
+                duration_periods: self.returns.len() - peak_index,
+            });
+        }
+        periods
+    }
+}
+
 impl Asset {
     /// Create a new asset
     pub fn new(
@@ -314,6 +416,63 @@ mod tests {
         assert_eq!(allocations.len(), 2);
     }
 
+    #[test]
+    fn test_drawdown_periods_find_each_fall_and_recovery() {
+        use chrono::TimeZone;
+
+        let month = |m: u32| Utc.with_ymd_and_hms(2024, m, 1, 0, 0, 0).unwrap();
+        let values = [
+            dec!(0.25), // 1.25 peak
+            dec!(-0.20),
+            dec!(-0.20), // 0.80 trough
+            dec!(0.50),
+            dec!(0.25), // 1.50 recovered, new peak
+            dec!(-0.10),
+            dec!(-0.20), // 1.08 still below the peak
+        ];
+        let series = HistoricalReturns {
+            asset_id: Uuid::new_v4(),
+            symbol: "VTI".to_string(),
+            returns: values
+                .iter()
+                .enumerate()
+                .map(|(i, &return_value)| PeriodReturn {
+                    date: month(i as u32 + 1),
+                    return_value,
+                    adjusted_close: None,
+                })
+                .collect(),
+            frequency: ReturnFrequency::Monthly,
+        };
+
+        let periods = series.drawdown_periods();
+
+        assert_eq!(
+            periods,
+            vec![
+                DrawdownPeriod {
+                    peak_date: month(1),
+                    trough_date: month(3),
+                    recovery_date: Some(month(5)),
+                    depth: dec!(0.36),
+                    duration_periods: 4,
+                },
+                DrawdownPeriod {
+                    peak_date: month(5),
+                    trough_date: month(7),
+                    recovery_date: None,
+                    depth: dec!(0.28),
+                    duration_periods: 2,
+                },
+            ]
+        );
+        let empty = HistoricalReturns {
+            returns: Vec::new(),
+            ..series
+        };
+        assert!(empty.drawdown_periods().is_empty());
+    }
+
     #[test]
     fn test_asset_gain_loss() {
         let asset = Asset::new(
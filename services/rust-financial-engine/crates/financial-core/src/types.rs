@@ -2,9 +2,19 @@
 ///
 /// All monetary calculations use rust_decimal to ensure
 /// exact decimal arithmetic without floating-point errors.
-use rust_decimal::Decimal;
+use rust_decimal::{Decimal, RoundingStrategy};
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::str::FromStr;
+
+use crate::error::FinancialError;
+
+/// Maximum number of decimal places kept on a `Money` amount
+///
+/// Results of arithmetic are rounded half-to-even to this scale so repeated
+/// multiplication and division in long simulations don't accumulate
+/// `Decimal`'s full 28 digits of scale and start losing integer precision.
+pub const MAX_MONEY_SCALE: u32 = 8;
 
 /// Supported currencies for financial calculations
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -19,21 +29,55 @@ pub enum Currency {
     CNY,
 }
 
-impl fmt::Display for Currency {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl Currency {
+    /// Every supported currency
+    pub const ALL: [Currency; 8] = [
+        Currency::USD,
+        Currency::EUR,
+        Currency::GBP,
+        Currency::CAD,
+        Currency::AUD,
+        Currency::JPY,
+        Currency::CHF,
+        Currency::CNY,
+    ];
+
+    /// ISO 4217 code
+    pub fn code(self) -> &'static str {
         match self {
-            Currency::USD => write!(f, "USD"),
-            Currency::EUR => write!(f, "EUR"),
-            Currency::GBP => write!(f, "GBP"),
-            Currency::CAD => write!(f, "CAD"),
-            Currency::AUD => write!(f, "AUD"),
-            Currency::JPY => write!(f, "JPY"),
-            Currency::CHF => write!(f, "CHF"),
-            Currency::CNY => write!(f, "CNY"),
+            Currency::USD => "USD",
+            Currency::EUR => "EUR",
+            Currency::GBP => "GBP",
+            Currency::CAD => "CAD",
+            Currency::AUD => "AUD",
+            Currency::JPY => "JPY",
+            Currency::CHF => "CHF",
+            Currency::CNY => "CNY",
         }
     }
 }
 
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl FromStr for Currency {
+    type Err = FinancialError;
+
+    /// Parse an ISO 4217 code, ignoring case and surrounding whitespace
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let code = s.trim();
+        Currency::ALL
+            .into_iter()
+            .find(|currency| currency.code().eq_ignore_ascii_case(code))
+            .ok_or_else(|| FinancialError::UnsupportedCurrency {
+                code: s.to_string(),
+            })
+    }
+}
+
 /// Exact decimal monetary amount with currency
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Money {
@@ -53,9 +97,33 @@ impl Money {
         Ok(Self { amount, currency })
     }
 
-    /// Create without validation (for internal use)
+    /// Create without range validation (for internal use)
+    ///
+    /// The amount is still limited to [`MAX_MONEY_SCALE`] decimal places.
     pub fn new_unchecked(amount: Decimal, currency: Currency) -> Self {
-        Self { amount, currency }
+        Self {
+            amount: Self::guard_scale(amount),
+            currency,
+        }
+    }
+
+    /// Round an amount down to the maximum supported scale
+    fn guard_scale(amount: Decimal) -> Decimal {
+        if amount.scale() > MAX_MONEY_SCALE {
+            amount.round_dp_with_strategy(MAX_MONEY_SCALE, RoundingStrategy::MidpointNearestEven)
+        } else {
+            amount
+        }
+    }
+
+    fn ensure_same_currency(&self, other: &Money) -> crate::Result<()> {
+        if self.currency != other.currency {
+            return Err(FinancialError::CurrencyMismatch {
+                expected: self.currency,
+                actual: other.currency,
+            });
+        }
+        Ok(())
     }
 
     /// Get the decimal amount
@@ -69,48 +137,52 @@ impl Money {
     }
 
     /// Add two money amounts (must be same currency)
+    ///
+    /// Returns `FinancialError::Overflow` if the sum exceeds `Decimal`'s range.
     pub fn add(&self, other: &Money) -> crate::Result<Money> {
-        if self.currency != other.currency {
-            return Err(crate::error::FinancialError::CurrencyMismatch {
-                expected: self.currency,
-                actual: other.currency,
-            });
-        }
-        Ok(Money::new_unchecked(
-            self.amount + other.amount,
-            self.currency,
-        ))
+        self.ensure_same_currency(other)?;
+        let result = self
+            .amount
+            .checked_add(other.amount)
+            .ok_or(FinancialError::Overflow)?;
+        Ok(Money::new_unchecked(result, self.currency))
     }
 
     /// Subtract two money amounts (must be same currency)
+    ///
+    /// Returns `FinancialError::Overflow` if the difference exceeds `Decimal`'s range.
     pub fn subtract(&self, other: &Money) -> crate::Result<Money> {
-        if self.currency != other.currency {
-            return Err(crate::error::FinancialError::CurrencyMismatch {
-                expected: self.currency,
-                actual: other.currency,
-            });
-        }
-        Ok(Money::new_unchecked(
-            self.amount - other.amount,
-            self.currency,
-        ))
+        self.ensure_same_currency(other)?;
+        let result = self
+            .amount
+            .checked_sub(other.amount)
+            .ok_or(FinancialError::Overflow)?;
+        Ok(Money::new_unchecked(result, self.currency))
     }
 
     /// Multiply money by a decimal factor
+    ///
+    /// Returns `FinancialError::Overflow` if the product exceeds `Decimal`'s range.
     pub fn multiply(&self, factor: Decimal) -> crate::Result<Money> {
         let result = self
             .amount
             .checked_mul(factor)
-            .ok_or_else(|| crate::error::FinancialError::Overflow)?;
+            .ok_or(FinancialError::Overflow)?;
         Ok(Money::new_unchecked(result, self.currency))
     }
 
     /// Divide money by a decimal divisor
+    ///
+    /// Returns `FinancialError::Overflow` if the quotient exceeds `Decimal`'s range.
     pub fn divide(&self, divisor: Decimal) -> crate::Result<Money> {
         if divisor.is_zero() {
-            return Err(crate::error::FinancialError::DivisionByZero);
+            return Err(FinancialError::DivisionByZero);
         }
-        Ok(Money::new_unchecked(self.amount / divisor, self.currency))
+        let result = self
+            .amount
+            .checked_div(divisor)
+            .ok_or(FinancialError::Overflow)?;
+        Ok(Money::new_unchecked(result, self.currency))
     }
 
     /// Check if amount is positive
@@ -135,8 +207,92 @@ impl fmt::Display for Money {
     }
 }
 
-/// Percentage type for rates and ratios
+/// How calculated results are rounded to a [`CalcContext`]'s scale
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RoundingMode {
+    /// Ties go to the even digit (banker's rounding)
+    HalfEven,
+    /// Ties go away from zero
+    HalfUp,
+    /// Always toward zero (truncation)
+    TowardZero,
+    /// Always away from zero
+    AwayFromZero,
+}
+
+impl RoundingMode {
+    fn strategy(self) -> RoundingStrategy {
+        match self {
+            RoundingMode::HalfEven => RoundingStrategy::MidpointNearestEven,
+            RoundingMode::HalfUp => RoundingStrategy::MidpointAwayFromZero,
+            RoundingMode::TowardZero => RoundingStrategy::ToZero,
+            RoundingMode::AwayFromZero => RoundingStrategy::AwayFromZero,
+        }
+    }
+}
+
+/// Precision that monetary results of a calculation are rounded to
+///
+/// Calculators round every amount they produce (interest charges, payments,
+/// balances, risk amounts) with the same context, so the engine's results
+/// match what is displayed under the user's precision settings. The default
+/// keeps [`MAX_MONEY_SCALE`] places with half-even rounding, the same as
+/// plain `Money` arithmetic.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CalcContext {
+    scale: u32,
+    rounding: RoundingMode,
+}
+
+impl CalcContext {
+    /// Create a context rounding to `scale` decimal places
+    pub fn new(scale: u32, rounding: RoundingMode) -> crate::Result<Self> {
+        if scale > MAX_MONEY_SCALE {
+            return Err(FinancialError::parameter_out_of_range(
+                "scale",
+                "0",
+                &MAX_MONEY_SCALE.to_string(),
+                &scale.to_string(),
+            ));
+        }
+        Ok(Self { scale, rounding })
+    }
+
+    /// Decimal places results are rounded to
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    /// Rounding applied when a result has more places than `scale`
+    pub fn rounding(&self) -> RoundingMode {
+        self.rounding
+    }
+
+    /// Round a decimal amount to this context
+    pub fn round(&self, amount: Decimal) -> Decimal {
+        amount.round_dp_with_strategy(self.scale, self.rounding.strategy())
+    }
+
+    /// Round a money amount to this context, keeping its currency
+    pub fn round_money(&self, money: Money) -> Money {
+        Money::new_unchecked(self.round(money.amount()), money.currency())
+    }
+}
+
+impl Default for CalcContext {
+    fn default() -> Self {
+        Self {
+            scale: MAX_MONEY_SCALE,
+            rounding: RoundingMode::HalfEven,
+        }
+    }
+}
+
+/// Percentage type for rates and ratios
+///
+/// Rate math should go through these methods rather than dividing by 100
+/// inline, so the percent/decimal conversion happens in one place.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct Percentage {
     value: Decimal,
 }
@@ -176,6 +332,44 @@ impl Percentage {
     pub fn as_percentage(&self) -> Decimal {
         self.value
     }
+
+    /// This percentage of a money amount (e.g., 5% of $200 is $10)
+    pub fn apply_to(&self, money: &Money) -> crate::Result<Money> {
+        money.multiply(self.as_decimal())
+    }
+
+    /// Sum of two percentages in percentage points (5% + 2.5% = 7.5%)
+    pub fn add(&self, other: &Percentage) -> crate::Result<Percentage> {
+        let value = self
+            .value
+            .checked_add(other.value)
+            .ok_or(FinancialError::Overflow)?;
+        Self::from_percentage(value)
+    }
+
+    /// Difference of two percentages in percentage points (5% - 2.5% = 2.5%)
+    pub fn subtract(&self, other: &Percentage) -> crate::Result<Percentage> {
+        let value = self
+            .value
+            .checked_sub(other.value)
+            .ok_or(FinancialError::Overflow)?;
+        Self::from_percentage(value)
+    }
+
+    /// Total growth from applying this percentage `periods` times in a row
+    ///
+    /// Computes `(1 + r)^n - 1`, so 1% compounded over 12 periods is about
+    /// 12.68%, not 12%.
+    pub fn compound(&self, periods: u32) -> crate::Result<Percentage> {
+        let growth_per_period = Decimal::ONE + self.as_decimal();
+        let mut growth = Decimal::ONE;
+        for _ in 0..periods {
+            growth = growth
+                .checked_mul(growth_per_period)
+                .ok_or(FinancialError::Overflow)?;
+        }
+        Self::from_decimal(growth - Decimal::ONE)
+    }
 }
 
 impl fmt::Display for Percentage {
@@ -202,6 +396,11 @@ impl Rate {
         self.percentage.as_decimal()
     }
 
+    /// Get the rate as a percentage for its period
+    pub fn percentage(&self) -> Percentage {
+        self.percentage
+    }
+
     /// Get the period for this rate
     pub fn period(&self) -> Period {
         self.period
@@ -280,6 +479,58 @@ mod tests {
         assert_eq!(quotient.amount(), dec!(50.25));
     }
 
+    #[test]
+    fn test_arithmetic_overflow_is_reported() {
+        let near_max = Money::new_unchecked(Decimal::MAX - dec!(1), Currency::USD);
+
+        assert!(matches!(near_max.multiply(dec!(2)), Err(FinancialError::Overflow)));
+        assert!(matches!(near_max.multiply(dec!(1.5)), Err(FinancialError::Overflow)));
+        assert!(matches!(near_max.add(&near_max), Err(FinancialError::Overflow)));
+        assert!(matches!(
+            near_max.abs().subtract(&Money::new_unchecked(Decimal::MIN, Currency::USD)),
+            Err(FinancialError::Overflow)
+        ));
+        assert!(matches!(near_max.divide(dec!(0.5)), Err(FinancialError::Overflow)));
+
+        // In-range results near the limit are still exact
+        let half = near_max.divide(dec!(2)).unwrap();
+        assert_eq!(half.multiply(dec!(2)).unwrap().amount(), near_max.amount());
+    }
+
+    #[test]
+    fn test_scale_is_capped() {
+        let third = Money::new(dec!(100), Currency::USD).unwrap().divide(dec!(3)).unwrap();
+        assert_eq!(third.amount().scale(), MAX_MONEY_SCALE);
+        assert_eq!(third.amount(), dec!(33.33333333));
+
+        // Repeated operations stay at the capped scale
+        let mut balance = Money::new(dec!(1000), Currency::USD).unwrap();
+        for _ in 0..600 {
+            balance = balance.multiply(dec!(1.0041666666666666666666666667)).unwrap();
+        }
+        assert!(balance.amount().scale() <= MAX_MONEY_SCALE);
+
+        let exact = Money::new_unchecked(dec!(12.345), Currency::USD);
+        assert_eq!(exact.amount(), dec!(12.345));
+    }
+
+    #[test]
+    fn test_calc_context_rounding() {
+        let cents = CalcContext::new(2, RoundingMode::HalfEven).unwrap();
+        let whole_up = CalcContext::new(0, RoundingMode::HalfUp).unwrap();
+        let truncate = CalcContext::new(2, RoundingMode::TowardZero).unwrap();
+
+        let amount = Money::new(dec!(16.665), Currency::USD).unwrap();
+        assert_eq!(cents.round_money(amount).amount(), dec!(16.66));
+        assert_eq!(whole_up.round_money(amount).amount(), dec!(17));
+        assert_eq!(truncate.round(dec!(-16.669)), dec!(-16.66));
+        assert_eq!(cents.round_money(amount).currency(), Currency::USD);
+
+        // The default only applies the usual money scale cap
+        assert_eq!(CalcContext::default().round(dec!(12.345)), dec!(12.345));
+        assert!(CalcContext::new(MAX_MONEY_SCALE + 1, RoundingMode::HalfEven).is_err());
+    }
+
     #[test]
     fn test_currency_mismatch() {
         let m1 = Money::new(dec!(100.00), Currency::USD).unwrap();
@@ -289,6 +540,27 @@ mod tests {
         assert!(m1.subtract(&m2).is_err());
     }
 
+    #[test]
+    fn test_currency_parsing() {
+        assert_eq!("USD".parse::<Currency>().unwrap(), Currency::USD);
+        assert_eq!(" jpy ".parse::<Currency>().unwrap(), Currency::JPY);
+        assert_eq!("Eur".parse::<Currency>().unwrap(), Currency::EUR);
+
+        for code in ["", "US", "USDX", "XYZ", "U$D"] {
+            assert_eq!(
+                code.parse::<Currency>(),
+                Err(FinancialError::UnsupportedCurrency {
+                    code: code.to_string()
+                })
+            );
+        }
+
+        for currency in Currency::ALL {
+            assert_eq!(currency.to_string(), currency.code());
+            assert_eq!(currency.to_string().parse::<Currency>().unwrap(), currency);
+        }
+    }
+
     #[test]
     fn test_percentage() {
         let pct = Percentage::from_percentage(dec!(5.5)).unwrap();
@@ -297,6 +569,35 @@ mod tests {
 
         let pct2 = Percentage::from_decimal(dec!(0.075)).unwrap();
         assert_eq!(pct2.as_percentage(), dec!(7.5));
+
+        assert!(pct < pct2);
+        assert_eq!(pct.add(&pct2).unwrap().as_percentage(), dec!(13.0));
+        assert_eq!(pct2.subtract(&pct).unwrap().as_percentage(), dec!(2.0));
+        assert!(pct.subtract(&Percentage::from_percentage(dec!(200)).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_percentage_apply_to_money() {
+        let balance = Money::new(dec!(1250.00), Currency::EUR).unwrap();
+
+        let fee = Percentage::from_percentage(dec!(3)).unwrap().apply_to(&balance).unwrap();
+        assert_eq!(fee.amount(), dec!(37.50));
+        assert_eq!(fee.currency(), Currency::EUR);
+
+        let none = Percentage::from_percentage(dec!(0)).unwrap().apply_to(&balance).unwrap();
+        assert_eq!(none.amount(), dec!(0));
+    }
+
+    #[test]
+    fn test_monthly_percentage_compounds_to_annual() {
+        let monthly = Percentage::from_percentage(dec!(1)).unwrap();
+
+        let annual = monthly.compound(12).unwrap();
+        assert_eq!(annual.as_percentage(), dec!(12.6825030131969720661201));
+        assert!(annual > Percentage::from_percentage(dec!(12)).unwrap());
+
+        assert_eq!(monthly.compound(1).unwrap(), monthly);
+        assert_eq!(monthly.compound(0).unwrap().as_percentage(), dec!(0));
     }
 
     #[test]